@@ -8,7 +8,7 @@
 
 use std::{fmt, mem, ops, panic::RefUnwindSafe, str::FromStr, sync::Arc};
 
-use cfg::CfgOptions;
+use cfg::{CfgDiff, CfgOptions};
 use la_arena::{Arena, Idx, RawIdx};
 use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::SmolStr;
@@ -455,6 +455,11 @@ impl CrateGraph {
         self.arena.iter().map(|(idx, _)| idx)
     }
 
+    /// Enables and disables the cfg atoms of `diff` in `crate_id`.
+    pub fn apply_cfg_diff(&mut self, crate_id: CrateId, diff: CfgDiff) {
+        self.arena[crate_id].cfg_options.apply_diff(diff);
+    }
+
     /// Returns an iterator over all transitive dependencies of the given crate,
     /// including the crate itself.
     pub fn transitive_deps(&self, of: CrateId) -> impl Iterator<Item = CrateId> {
@@ -73,6 +73,37 @@ impl DnfExpr {
         Some(res)
     }
 
+    /// Computes a list of present or absent atoms in `opts` that cause this expression to evaluate
+    /// to `true`, which are the ones of the first alternative that holds.
+    ///
+    /// Returns `None` when `self` is false, or contains errors.
+    pub fn why_active(&self, opts: &CfgOptions) -> Option<InactiveReason> {
+        'conj: for conj in &self.conjunctions {
+            let mut res = InactiveReason { enabled: Vec::new(), disabled: Vec::new() };
+            for lit in &conj.literals {
+                let atom = lit.var.as_ref()?;
+                let enabled = opts.enabled.contains(atom);
+                if lit.negate == enabled {
+                    continue 'conj;
+                }
+
+                if enabled {
+                    res.enabled.push(atom.clone());
+                } else {
+                    res.disabled.push(atom.clone());
+                }
+            }
+
+            res.enabled.sort_unstable();
+            res.enabled.dedup();
+            res.disabled.sort_unstable();
+            res.disabled.dedup();
+            return Some(res);
+        }
+
+        None
+    }
+
     /// Returns `CfgDiff` objects that would enable this directive if applied to `opts`.
     pub fn compute_enable_hints<'a>(
         &'a self,
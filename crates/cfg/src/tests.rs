@@ -38,6 +38,19 @@ fn check_why_inactive(input: &str, opts: &CfgOptions, expect: Expect) {
     expect.assert_eq(&why_inactive);
 }
 
+#[track_caller]
+fn check_why_active(input: &str, opts: &CfgOptions, expect: Expect) {
+    let (tt, _) = {
+        let source_file = ast::SourceFile::parse(input).ok().unwrap();
+        let tt = source_file.syntax().descendants().find_map(ast::TokenTree::cast).unwrap();
+        syntax_node_to_token_tree(tt.syntax())
+    };
+    let cfg = CfgExpr::parse(&tt);
+    let dnf = DnfExpr::new(cfg);
+    let why_active = dnf.why_active(opts).unwrap().to_string();
+    expect.assert_eq(&why_active);
+}
+
 #[track_caller]
 fn check_enable_hints(input: &str, opts: &CfgOptions, expected_hints: &[&str]) {
     let (tt, _) = {
@@ -205,6 +218,22 @@ fn why_inactive() {
     );
 }
 
+#[test]
+fn why_active() {
+    let mut opts = CfgOptions::default();
+    opts.insert_atom("test".into());
+    opts.insert_atom("test2".into());
+
+    check_why_active("#![cfg(test)]", &opts, expect![["test is enabled"]]);
+    check_why_active("#![cfg(not(a))]", &opts, expect![["a is disabled"]]);
+    check_why_active(
+        "#![cfg(any(a, all(test, not(b))))]",
+        &opts,
+        expect![["test is enabled and b is disabled"]],
+    );
+    check_why_active("#![cfg(any(test, test2))]", &opts, expect![["test is enabled"]]);
+}
+
 #[test]
 fn proptest() {
     const REPEATS: usize = 512;
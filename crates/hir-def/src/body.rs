@@ -47,6 +47,9 @@ pub struct Body {
     pub body_expr: ExprId,
     /// Block expressions in this body that may contain inner items.
     block_scopes: Vec<BlockId>,
+    /// The predicates of the `cfg!` calls checking `debug_assertions`, by the literal they expand
+    /// to. The interpreter checks them again under the build profile it runs with.
+    pub debug_assertions_cfgs: FxHashMap<ExprId, CfgExpr>,
     _c: Count<Self>,
 }
 
@@ -198,9 +201,19 @@ impl Body {
     }
 
     fn shrink_to_fit(&mut self) {
-        let Self { _c: _, body_expr: _, block_scopes, exprs, labels, params, pats, bindings } =
-            self;
+        let Self {
+            _c: _,
+            body_expr: _,
+            block_scopes,
+            exprs,
+            labels,
+            params,
+            pats,
+            bindings,
+            debug_assertions_cfgs,
+        } = self;
         block_scopes.shrink_to_fit();
+        debug_assertions_cfgs.shrink_to_fit();
         exprs.shrink_to_fit();
         labels.shrink_to_fit();
         params.shrink_to_fit();
@@ -257,6 +270,7 @@ impl Default for Body {
             labels: Default::default(),
             params: Default::default(),
             block_scopes: Default::default(),
+            debug_assertions_cfgs: Default::default(),
             _c: Default::default(),
         }
     }
@@ -4,11 +4,13 @@
 use std::{mem, sync::Arc};
 
 use base_db::CrateId;
+use cfg::{CfgAtom, CfgExpr};
 use either::Either;
 use hir_expand::{
     ast_id_map::AstIdMap,
+    builtin_fn_macro::BuiltinFnLikeExpander,
     name::{name, AsName, Name},
-    AstId, ExpandError, InFile,
+    AstId, ExpandError, InFile, MacroDefKind,
 };
 use intern::Interned;
 use la_arena::Arena;
@@ -30,8 +32,8 @@ use crate::{
     expander::Expander,
     hir::{
         dummy_expr_id, Array, Binding, BindingAnnotation, BindingId, CaptureBy, ClosureKind, Expr,
-        ExprId, Label, LabelId, Literal, MatchArm, Movability, Pat, PatId, RecordFieldPat,
-        RecordLitField, Statement,
+        ExprId, Label, LabelId, Literal, MatchArm, Movability, OffsetOf, Pat, PatId,
+        RecordFieldPat, RecordLitField, Statement,
     },
     item_scope::BuiltinShadowMode,
     lang_item::LangItem,
@@ -64,6 +66,7 @@ pub(super) fn lower(
             params: Vec::new(),
             body_expr: dummy_expr_id(),
             block_scopes: Vec::new(),
+            debug_assertions_cfgs: FxHashMap::default(),
             _c: Count::new(),
         },
         expander,
@@ -185,12 +188,18 @@ impl ExprCollector<'_> {
             if is_async_fn {
                 match body {
                     Some(e) => {
+                        let result_expr_id = this.missing_expr();
+                        let prev_binding_owner = this.current_binding_owner.replace(result_expr_id);
                         let expr = this.collect_expr(e);
-                        this.alloc_expr_desugared(Expr::Async {
+                        this.current_binding_owner = prev_binding_owner;
+                        // The body of an async fn takes ownership of the parameters.
+                        this.body.exprs[result_expr_id] = Expr::Async {
                             id: None,
                             statements: Box::new([]),
                             tail: Some(expr),
-                        })
+                            capture_by: CaptureBy::Value,
+                        };
+                        result_expr_id
                     }
                     None => this.missing_expr(),
                 }
@@ -258,13 +267,22 @@ impl ExprCollector<'_> {
                     })
                 }
                 Some(ast::BlockModifier::Async(_)) => {
-                    self.with_label_rib(RibKind::Closure, |this| {
-                        this.collect_block_(e, |id, statements, tail| Expr::Async {
+                    let capture_by =
+                        if e.move_token().is_some() { CaptureBy::Value } else { CaptureBy::Ref };
+                    // Like closures, async blocks own the bindings inside of them.
+                    let (result_expr_id, prev_binding_owner) =
+                        self.initialize_binding_owner(syntax_ptr);
+                    let async_block = self.with_label_rib(RibKind::Closure, |this| {
+                        this.collect_block_expr(e, |id, statements, tail| Expr::Async {
                             id,
                             statements,
                             tail,
+                            capture_by,
                         })
-                    })
+                    });
+                    self.body.exprs[result_expr_id] = async_block;
+                    self.current_binding_owner = prev_binding_owner;
+                    result_expr_id
                 }
                 Some(ast::BlockModifier::Const(_)) => {
                     self.with_label_rib(RibKind::Constant, |this| {
@@ -599,7 +617,11 @@ impl ExprCollector<'_> {
                 let e = e.macro_call()?;
                 let macro_ptr = AstPtr::new(&e);
                 let id = self.collect_macro_call(e, macro_ptr, true, |this, expansion| {
-                    expansion.map(|it| this.collect_expr(it))
+                    expansion.map(|it| {
+                        let id = this.collect_expr(it);
+                        this.record_debug_assertions_cfg(id);
+                        id
+                    })
                 });
                 match id {
                     Some(id) => {
@@ -613,9 +635,30 @@ impl ExprCollector<'_> {
                 }
             }
             ast::Expr::UnderscoreExpr(_) => self.alloc_expr(Expr::Underscore, syntax_ptr),
+            ast::Expr::OffsetOfExpr(e) => {
+                let container = Interned::new(TypeRef::from_ast_opt(&self.ctx(), e.ty()));
+                let fields = e.fields().map(|it| it.as_name()).collect();
+                self.alloc_expr(Expr::OffsetOf(OffsetOf { container, fields }), syntax_ptr)
+            }
         })
     }
 
+    /// Records the predicate of the `cfg!` call being expanded to `expr` if it checks
+    /// `debug_assertions`, see [`Body::debug_assertions_cfgs`].
+    fn record_debug_assertions_cfg(&mut self, expr: ExprId) {
+        let Some(macro_file) = self.expander.current_file_id.macro_file() else { return };
+        let call_id = macro_file.macro_call_id;
+        let loc = self.db.lookup_intern_macro_call(call_id);
+        if !matches!(loc.def.kind, MacroDefKind::BuiltIn(BuiltinFnLikeExpander::Cfg, _)) {
+            return;
+        }
+        let Some(arg) = self.db.macro_arg(call_id) else { return };
+        let cfg = CfgExpr::parse(&arg.0);
+        if checks_debug_assertions(&cfg) {
+            self.body.debug_assertions_cfgs.insert(expr, cfg);
+        }
+    }
+
     fn initialize_binding_owner(
         &mut self,
         syntax_ptr: AstPtr<ast::Expr>,
@@ -636,8 +679,16 @@ impl ExprCollector<'_> {
         syntax_ptr: AstPtr<ast::Expr>,
     ) -> ExprId {
         let (id, prev_owner) = self.initialize_binding_owner(syntax_ptr);
+        let bindings_start = self.body.bindings.len();
         let tmp = job(self);
         self.body.exprs[id] = mem::replace(&mut self.body.exprs[tmp], Expr::Missing);
+        // `tmp` can be a binding owner itself, e.g. a const block used as a repeat length. Its
+        // bindings, which are created by `job`, move along with the expression.
+        for binding in self.body.bindings.values_mut().skip(bindings_start) {
+            if binding.owner == Some(tmp) {
+                binding.owner = Some(id);
+            }
+        }
         self.current_binding_owner = prev_owner;
         id
     }
@@ -941,6 +992,17 @@ impl ExprCollector<'_> {
         block: ast::BlockExpr,
         mk_block: impl FnOnce(Option<BlockId>, Box<[Statement]>, Option<ExprId>) -> Expr,
     ) -> ExprId {
+        let syntax_node_ptr = AstPtr::new(&block.clone().into());
+        let expr = self.collect_block_expr(block, mk_block);
+        self.alloc_expr(expr, syntax_node_ptr)
+    }
+
+    /// Like `collect_block_`, but leaves allocating the block expression to the caller.
+    fn collect_block_expr(
+        &mut self,
+        block: ast::BlockExpr,
+        mk_block: impl FnOnce(Option<BlockId>, Box<[Statement]>, Option<ExprId>) -> Expr,
+    ) -> Expr {
         let block_has_items = {
             let statement_has_item = block.statements().any(|stmt| match stmt {
                 ast::Stmt::Item(_) => true,
@@ -986,13 +1048,11 @@ impl ExprCollector<'_> {
             None
         });
 
-        let syntax_node_ptr = AstPtr::new(&block.into());
-        let expr_id = self
-            .alloc_expr(mk_block(block_id, statements.into_boxed_slice(), tail), syntax_node_ptr);
+        let expr = mk_block(block_id, statements.into_boxed_slice(), tail);
 
         self.def_map = prev_def_map;
         self.expander.module = prev_local_module;
-        expr_id
+        expr
     }
 
     fn collect_block_opt(&mut self, expr: Option<ast::BlockExpr>) -> ExprId {
@@ -1135,23 +1195,9 @@ impl ExprCollector<'_> {
                     suffix: suffix.into_iter().map(|p| self.collect_pat(p, binding_list)).collect(),
                 }
             }
-            #[rustfmt::skip] // https://github.com/rust-lang/rustfmt/issues/5676
-            ast::Pat::LiteralPat(lit) => 'b: {
-                if let Some(ast_lit) = lit.literal() {
-                    let mut hir_lit: Literal = ast_lit.kind().into();
-                    if lit.minus_token().is_some() {
-                        let Some(h) = hir_lit.negate() else {
-                            break 'b Pat::Missing;
-                        };
-                        hir_lit = h;
-                    }
-                    let expr = Expr::Literal(hir_lit);
-                    let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
-                    let expr_id = self.alloc_expr(expr, expr_ptr);
-                    Pat::Lit(expr_id)
-                } else {
-                    Pat::Missing
-                }
+            ast::Pat::LiteralPat(lit) => match self.collect_lit_pat_expr(&lit) {
+                Some(expr_id) => Pat::Lit(expr_id),
+                None => Pat::Missing,
             },
             ast::Pat::RestPat(_) => {
                 // `RestPat` requires special handling and should not be mapped
@@ -1193,13 +1239,53 @@ impl ExprCollector<'_> {
                 }
                 None => Pat::Missing,
             },
-            // FIXME: implement
-            ast::Pat::RangePat(_) => Pat::Missing,
+            ast::Pat::RangePat(p) => match p.op_kind() {
+                Some(range_type) => {
+                    let start = p.start().map(|it| self.collect_range_pat_bound(it));
+                    let end = p.end().map(|it| self.collect_range_pat_bound(it));
+                    Pat::Range { start, end, range_type }
+                }
+                None => Pat::Missing,
+            },
         };
         let ptr = AstPtr::new(&pat);
         self.alloc_pat(pattern, Either::Left(ptr))
     }
 
+    /// Lowers the literal of a literal pattern, along with its sign, to an expression.
+    fn collect_lit_pat_expr(&mut self, lit: &ast::LiteralPat) -> Option<ExprId> {
+        let ast_lit = lit.literal()?;
+        let mut hir_lit: Literal = ast_lit.kind().into();
+        if lit.minus_token().is_some() {
+            hir_lit = hir_lit.negate()?;
+        }
+        let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
+        Some(self.alloc_expr(Expr::Literal(hir_lit), expr_ptr))
+    }
+
+    /// Lowers a bound of a range pattern, which is either a literal or a path to a constant.
+    fn collect_range_pat_bound(&mut self, pat: ast::Pat) -> ExprId {
+        let path = match pat {
+            ast::Pat::LiteralPat(lit) => {
+                return self.collect_lit_pat_expr(&lit).unwrap_or_else(|| self.missing_expr())
+            }
+            ast::Pat::PathPat(p) => {
+                p.path().and_then(|path| self.expander.parse_path(self.db, path))
+            }
+            // A single segment path is parsed as a binding.
+            ast::Pat::IdentPat(p)
+                if p.pat().is_none() && p.ref_token().is_none() && p.mut_token().is_none() =>
+            {
+                p.name().map(|name| Path::from(name.as_name()))
+            }
+            _ => None,
+        };
+        match path {
+            Some(path) => self.alloc_expr_desugared(Expr::Path(path)),
+            None => self.missing_expr(),
+        }
+    }
+
     fn collect_pat_opt(&mut self, pat: Option<ast::Pat>, binding_list: &mut BindingList) -> PatId {
         match pat {
             Some(pat) => self.collect_pat(pat, binding_list),
@@ -1368,3 +1454,12 @@ impl ExprCollector<'_> {
         self.body.labels.alloc(label)
     }
 }
+
+fn checks_debug_assertions(cfg: &CfgExpr) -> bool {
+    match cfg {
+        CfgExpr::Atom(CfgAtom::Flag(flag)) => flag == "debug_assertions",
+        CfgExpr::All(it) | CfgExpr::Any(it) => it.iter().any(checks_debug_assertions),
+        CfgExpr::Not(it) => checks_debug_assertions(it),
+        CfgExpr::Invalid | CfgExpr::Atom(CfgAtom::KeyValue { .. }) => false,
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::fmt::{self, Write};
 
+use itertools::Itertools;
 use syntax::ast::HasName;
 
 use crate::{
@@ -132,6 +133,11 @@ impl<'a> Printer<'a> {
         match expr {
             Expr::Missing => w!(self, "�"),
             Expr::Underscore => w!(self, "_"),
+            Expr::OffsetOf(offset_of) => {
+                w!(self, "builtin#offset_of(");
+                self.print_type_ref(&offset_of.container);
+                w!(self, ", {})", offset_of.fields.iter().format("."));
+            }
             Expr::Path(path) => self.print_path(path),
             Expr::If { condition, then_branch, else_branch } => {
                 w!(self, "if ");
@@ -433,8 +439,12 @@ impl<'a> Printer<'a> {
             Expr::Unsafe { id: _, statements, tail } => {
                 self.print_block(Some("unsafe "), statements, tail);
             }
-            Expr::Async { id: _, statements, tail } => {
-                self.print_block(Some("async "), statements, tail);
+            Expr::Async { id: _, statements, tail, capture_by } => {
+                let modifier = match capture_by {
+                    CaptureBy::Value => "async move ",
+                    CaptureBy::Ref => "async ",
+                };
+                self.print_block(Some(modifier), statements, tail);
             }
             Expr::Const { id: _, statements, tail } => {
                 self.print_block(Some("const "), statements, tail);
@@ -513,10 +523,18 @@ impl<'a> Printer<'a> {
                 });
                 w!(self, "}}");
             }
-            Pat::Range { start, end } => {
-                self.print_expr(*start);
-                w!(self, "...");
-                self.print_expr(*end);
+            Pat::Range { start, end, range_type } => {
+                if let Some(start) = start {
+                    self.print_expr(*start);
+                }
+                let range = match range_type {
+                    ast::RangeOp::Exclusive => "..",
+                    ast::RangeOp::Inclusive => "..=",
+                };
+                w!(self, "{}", range);
+                if let Some(end) = end {
+                    self.print_expr(*end);
+                }
             }
             Pat::Slice { prefix, slice, suffix } => {
                 w!(self, "[");
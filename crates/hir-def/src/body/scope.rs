@@ -220,7 +220,7 @@ fn compute_expr_scopes(expr: ExprId, body: &Body, scopes: &mut ExprScopes, scope
             compute_block_scopes(statements, *tail, body, scopes, &mut scope);
         }
         Expr::Unsafe { id, statements, tail }
-        | Expr::Async { id, statements, tail }
+        | Expr::Async { id, statements, tail, .. }
         | Expr::Const { id, statements, tail } => {
             let mut scope = scopes.new_block_scope(*scope, *id, None);
             // Overwrite the old scope for the block expr, so that every block scope can be found
@@ -168,6 +168,7 @@ pub enum Expr {
         id: Option<BlockId>,
         statements: Box<[Statement]>,
         tail: Option<ExprId>,
+        capture_by: CaptureBy,
     },
     Const {
         id: Option<BlockId>,
@@ -284,6 +285,7 @@ pub enum Expr {
     Array(Array),
     Literal(Literal),
     Underscore,
+    OffsetOf(OffsetOf),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -313,6 +315,13 @@ pub enum Array {
     Repeat { initializer: ExprId, repeat: ExprId },
 }
 
+/// `builtin # offset_of(Container, fields)`, which `core::mem::offset_of!` expands to.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OffsetOf {
+    pub container: Interned<TypeRef>,
+    pub fields: Box<[Name]>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MatchArm {
     pub pat: PatId,
@@ -451,6 +460,7 @@ impl Expr {
             },
             Expr::Literal(_) => {}
             Expr::Underscore => {}
+            Expr::OffsetOf(_) => {}
         }
     }
 }
@@ -525,7 +535,7 @@ pub enum Pat {
     Tuple { args: Box<[PatId]>, ellipsis: Option<usize> },
     Or(Box<[PatId]>),
     Record { path: Option<Box<Path>>, args: Box<[RecordFieldPat]>, ellipsis: bool },
-    Range { start: ExprId, end: ExprId },
+    Range { start: Option<ExprId>, end: Option<ExprId>, range_type: RangeOp },
     Slice { prefix: Box<[PatId]>, slice: Option<PatId>, suffix: Box<[PatId]> },
     Path(Box<Path>),
     Lit(ExprId),
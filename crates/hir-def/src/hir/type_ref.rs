@@ -423,6 +423,13 @@ impl ConstRefOrPath {
                 }
                 _ => Self::from_expr_opt(prefix_expr.expr()),
             },
+            // `{ 5 }` and `const { 5 }` are as good as the literal itself.
+            ast::Expr::BlockExpr(block)
+                if matches!(block.modifier(), None | Some(ast::BlockModifier::Const(_)))
+                    && block.statements().next().is_none() =>
+            {
+                Self::from_expr_opt(block.tail_expr())
+            }
             ast::Expr::Literal(literal) => Self::Scalar(match literal.kind() {
                 ast::LiteralKind::IntNumber(num) => {
                     num.value().map(ConstRef::UInt).unwrap_or(ConstRef::Unknown)
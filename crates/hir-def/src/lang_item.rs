@@ -76,9 +76,32 @@ impl LangItemTarget {
     }
 }
 
+/// Items which the crates linked into a binary can define at most once, and which binaries without
+/// `std` have to define themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryItem {
+    /// A `#[panic_handler]` function, which is also the `panic_impl` lang item.
+    PanicHandler,
+    /// A `#[global_allocator]` static.
+    GlobalAllocator,
+    /// An `#[alloc_error_handler]` function.
+    AllocErrorHandler,
+}
+
+impl EntryItem {
+    pub fn attr_name(self) -> &'static str {
+        match self {
+            EntryItem::PanicHandler => "panic_handler",
+            EntryItem::GlobalAllocator => "global_allocator",
+            EntryItem::AllocErrorHandler => "alloc_error_handler",
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct LangItems {
     items: FxHashMap<LangItem, LangItemTarget>,
+    entry_items: Vec<(EntryItem, LangItemTarget)>,
 }
 
 impl LangItems {
@@ -86,6 +109,11 @@ impl LangItems {
         self.items.get(&item).copied()
     }
 
+    /// Returns all the entry items of the crate, including duplicates.
+    pub fn entry_items(&self) -> &[(EntryItem, LangItemTarget)] {
+        &self.entry_items
+    }
+
     /// Salsa query. This will look for lang items in a specific crate.
     pub(crate) fn crate_lang_items_query(db: &dyn DefDatabase, krate: CrateId) -> Arc<LangItems> {
         let _p = profile::span("crate_lang_items_query");
@@ -138,9 +166,21 @@ impl LangItems {
                     }
                     ModuleDefId::FunctionId(f) => {
                         lang_items.collect_lang_item(db, f, LangItemTarget::Function);
+                        lang_items.collect_entry_item(
+                            db,
+                            f,
+                            &[EntryItem::PanicHandler, EntryItem::AllocErrorHandler],
+                            LangItemTarget::Function,
+                        );
                     }
                     ModuleDefId::StaticId(s) => {
                         lang_items.collect_lang_item(db, s, LangItemTarget::Static);
+                        lang_items.collect_entry_item(
+                            db,
+                            s,
+                            &[EntryItem::GlobalAllocator],
+                            LangItemTarget::Static,
+                        );
                     }
                     ModuleDefId::TypeAliasId(t) => {
                         lang_items.collect_lang_item(db, t, LangItemTarget::TypeAlias);
@@ -150,6 +190,7 @@ impl LangItems {
             }
         }
 
+        lang_items.entry_items.shrink_to_fit();
         Arc::new(lang_items)
     }
 
@@ -185,10 +226,29 @@ impl LangItems {
             self.items.entry(lang_item).or_insert_with(|| constructor(item));
         }
     }
+
+    fn collect_entry_item<T>(
+        &mut self,
+        db: &dyn DefDatabase,
+        item: T,
+        kinds: &[EntryItem],
+        constructor: fn(T) -> LangItemTarget,
+    ) where
+        T: Into<AttrDefId> + Copy,
+    {
+        let attrs = db.attrs(item.into());
+        if let Some(&kind) = kinds.iter().find(|kind| attrs.by_key(kind.attr_name()).exists()) {
+            self.entry_items.push((kind, constructor(item)));
+        }
+    }
 }
 
 pub fn lang_attr(db: &dyn DefDatabase, item: impl Into<AttrDefId> + Copy) -> Option<LangItem> {
     let attrs = db.attrs(item.into());
+    if attrs.by_key("panic_handler").exists() {
+        // Like rustc, treat the panic handler as the `panic_impl` lang item.
+        return Some(LangItem::PanicImpl);
+    }
     attrs.by_key("lang").string_value().cloned().and_then(|it| LangItem::from_str(&it))
 }
 
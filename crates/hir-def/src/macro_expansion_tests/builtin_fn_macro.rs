@@ -235,6 +235,36 @@ fn main() {
     );
 }
 
+#[test]
+fn test_format_args_expand_without_placeholders() {
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+
+fn main() {
+    format_args!("no placeholders");
+    format_args!("{{escaped}}");
+}
+"#,
+        expect![[r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+
+fn main() {
+    $crate::fmt::Arguments::new_v1(&["no placeholders"], &[]);
+    $crate::fmt::Arguments::new_v1(&[], &[]);
+}
+"#]],
+    );
+}
+
 #[test]
 fn test_format_args_expand_with_broken_member_access() {
     check(
@@ -266,6 +296,34 @@ $crate::fmt::Arguments::new_v1(&[], &[$crate::fmt::ArgumentV1::new(&(a.), $crate
     );
 }
 
+#[test]
+fn test_asm_expand() {
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! asm {() => {}}
+
+fn main() {
+    asm!("mov {0}, {x}", in(reg) a, x = inout(reg) b => c, out(reg) _, options(nostack));
+}
+"#,
+        expect![[r##"
+#[rustc_builtin_macro]
+macro_rules! asm {() => {}}
+
+fn main() {
+     {
+        $crate::format_args!("mov {0}, {x}");
+        let _ = a;
+        let _ = b;
+        let _ = c;
+        loop {}
+    };
+}
+"##]],
+    );
+}
+
 #[test]
 fn test_include_bytes_expand() {
     check(
@@ -122,6 +122,10 @@ pub struct DefMap {
     unstable_features: FxHashSet<SmolStr>,
     /// #[rustc_coherence_is_core]
     rustc_coherence_is_core: bool,
+    /// `#![no_std]`
+    no_std: bool,
+    /// `#![no_main]`, the crate doesn't have a `main` function as its entry point.
+    no_main: bool,
 
     edition: Edition,
     recursion_limit: Option<u32>,
@@ -288,6 +292,8 @@ impl DefMap {
             unstable_features: FxHashSet::default(),
             diagnostics: Vec::new(),
             rustc_coherence_is_core: false,
+            no_std: false,
+            no_main: false,
         }
     }
 
@@ -503,6 +509,8 @@ impl DefMap {
             prelude: _,
             root: _,
             rustc_coherence_is_core: _,
+            no_std: _,
+            no_main: _,
         } = self;
 
         extern_prelude.shrink_to_fit();
@@ -528,6 +536,14 @@ impl DefMap {
     pub fn recursion_limit(&self) -> Option<u32> {
         self.recursion_limit
     }
+
+    pub fn is_no_std(&self) -> bool {
+        self.no_std
+    }
+
+    pub fn is_no_main(&self) -> bool {
+        self.no_main
+    }
 }
 
 impl ModuleData {
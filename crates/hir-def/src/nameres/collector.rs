@@ -319,6 +319,16 @@ impl DefCollector<'_> {
                     continue;
                 }
 
+                if attr_name.as_text().as_deref() == Some("no_std") {
+                    self.def_map.no_std = true;
+                    continue;
+                }
+
+                if attr_name.as_text().as_deref() == Some("no_main") {
+                    self.def_map.no_main = true;
+                    continue;
+                }
+
                 if *attr_name == hir_expand::name![feature] {
                     let hygiene = &Hygiene::new_unhygienic();
                     let features = attr
@@ -61,6 +61,7 @@ pub fn find_builtin_derive(ident: &name::Name) -> Option<BuiltinDeriveExpander>
 
 struct BasicAdtInfo {
     name: tt::Ident,
+    shape: AdtShape,
     /// first field is the name, and
     /// second field is `Some(ty)` if it's a const param of type `ty`, `None` if it's a type param.
     /// third fields is where bounds, if any
@@ -68,6 +69,97 @@ struct BasicAdtInfo {
     associated_types: Vec<tt::Subtree>,
 }
 
+enum AdtShape {
+    Struct(VariantShape),
+    Enum { variants: Vec<(tt::Ident, VariantShape)> },
+    Union,
+}
+
+enum VariantShape {
+    Struct(Vec<tt::Ident>),
+    Tuple(usize),
+    Unit,
+}
+
+impl AdtShape {
+    /// Returns the paths of the variants of the ADT `name`, with their shapes.
+    fn variants(&self, name: &tt::Ident) -> Vec<(tt::Subtree, &VariantShape)> {
+        match self {
+            AdtShape::Struct(shape) => vec![(quote! { #name }, shape)],
+            AdtShape::Enum { variants } => variants
+                .iter()
+                .map(|(variant, shape)| (quote! { #name :: #variant }, shape))
+                .collect(),
+            AdtShape::Union => Vec::new(),
+        }
+    }
+}
+
+impl VariantShape {
+    fn from_field_list(field_list: Option<ast::FieldList>, token_map: &mbe::TokenMap) -> Self {
+        match field_list {
+            Some(ast::FieldList::RecordFieldList(it)) => VariantShape::Struct(
+                it.fields().filter_map(|it| Some(name_to_token(token_map, it.name()?))).collect(),
+            ),
+            Some(ast::FieldList::TupleFieldList(it)) => VariantShape::Tuple(it.fields().count()),
+            None => VariantShape::Unit,
+        }
+    }
+
+    /// Returns the names of the fields, `f0`, `f1`, etc. for tuple variants.
+    fn field_names(&self) -> Vec<tt::Ident> {
+        match self {
+            VariantShape::Struct(names) => names.clone(),
+            VariantShape::Tuple(count) => (0..*count)
+                .map(|it| tt::Ident { text: format!("f{it}").into(), span: TokenId::unspecified() })
+                .collect(),
+            VariantShape::Unit => Vec::new(),
+        }
+    }
+
+    /// Returns a pattern matching the variant at `path`, which binds each field `x` to `x_suffix`.
+    fn as_pattern(&self, path: tt::Subtree, suffix: &str) -> tt::Subtree {
+        match self {
+            VariantShape::Struct(names) => {
+                let fields = names.iter().map(|name| {
+                    let binding = binding(name, suffix);
+                    quote! { #name : #binding , }
+                });
+                quote! { #path { ##fields } }
+            }
+            VariantShape::Tuple(_) => {
+                let fields = self.field_names().into_iter().map(|name| {
+                    let binding = binding(&name, suffix);
+                    quote! { #binding , }
+                });
+                quote! { #path ( ##fields ) }
+            }
+            VariantShape::Unit => path,
+        }
+    }
+
+    /// Returns a pattern matching the variant at `path`, ignoring its fields.
+    fn as_wildcard(&self, path: tt::Subtree) -> tt::Subtree {
+        match self {
+            VariantShape::Struct(_) => quote! { #path { .. } },
+            VariantShape::Tuple(_) => quote! { #path ( .. ) },
+            VariantShape::Unit => path,
+        }
+    }
+}
+
+/// Returns the name of the binding of the field `name` in the patterns of [`VariantShape`].
+fn binding(name: &tt::Ident, suffix: &str) -> tt::Ident {
+    let name = name.text.trim_start_matches("r#");
+    tt::Ident { text: format!("{name}_{suffix}").into(), span: TokenId::unspecified() }
+}
+
+fn name_to_token(token_map: &mbe::TokenMap, name: ast::Name) -> tt::Ident {
+    let name_token_id =
+        token_map.token_by_range(name.syntax().text_range()).unwrap_or_else(TokenId::unspecified);
+    tt::Ident { span: name_token_id, text: name.text().into() }
+}
+
 fn parse_adt(tt: &tt::Subtree) -> Result<BasicAdtInfo, ExpandError> {
     let (parsed, token_map) = mbe::token_tree_to_syntax_node(tt, mbe::TopEntryPoint::MacroItems);
     let macro_items = ast::MacroItems::cast(parsed.syntax_node()).ok_or_else(|| {
@@ -79,11 +171,26 @@ fn parse_adt(tt: &tt::Subtree) -> Result<BasicAdtInfo, ExpandError> {
         ExpandError::Other("no item found".into())
     })?;
     let node = item.syntax();
-    let (name, params) = match_ast! {
+    let (name, params, shape) = match_ast! {
         match node {
-            ast::Struct(it) => (it.name(), it.generic_param_list()),
-            ast::Enum(it) => (it.name(), it.generic_param_list()),
-            ast::Union(it) => (it.name(), it.generic_param_list()),
+            ast::Struct(it) => (
+                it.name(),
+                it.generic_param_list(),
+                AdtShape::Struct(VariantShape::from_field_list(it.field_list(), &token_map)),
+            ),
+            ast::Enum(it) => {
+                let variants = it
+                    .variant_list()
+                    .into_iter()
+                    .flat_map(|it| it.variants())
+                    .filter_map(|it| {
+                        let name = name_to_token(&token_map, it.name()?);
+                        Some((name, VariantShape::from_field_list(it.field_list(), &token_map)))
+                    })
+                    .collect();
+                (it.name(), it.generic_param_list(), AdtShape::Enum { variants })
+            },
+            ast::Union(it) => (it.name(), it.generic_param_list(), AdtShape::Union),
             _ => {
                 debug!("unexpected node is {:?}", node);
                 return Err(ExpandError::Other("expected struct, enum or union".into()))
@@ -158,10 +265,8 @@ fn parse_adt(tt: &tt::Subtree) -> Result<BasicAdtInfo, ExpandError> {
         debug!("parsed item has no name");
         ExpandError::Other("missing name".into())
     })?;
-    let name_token_id =
-        token_map.token_by_range(name.syntax().text_range()).unwrap_or_else(TokenId::unspecified);
-    let name_token = tt::Ident { span: name_token_id, text: name.text().into() };
-    Ok(BasicAdtInfo { name: name_token, param_types, associated_types })
+    let name_token = name_to_token(&token_map, name);
+    Ok(BasicAdtInfo { name: name_token, shape, param_types, associated_types })
 }
 
 /// Given that we are deriving a trait `DerivedTrait` for a type like:
@@ -194,12 +299,18 @@ fn parse_adt(tt: &tt::Subtree) -> Result<BasicAdtInfo, ExpandError> {
 /// ```
 ///
 /// where B1, ..., BN are the bounds given by `bounds_paths`.'. Z is a phantom type, and
-/// therefore does not get bound by the derived trait.
-fn expand_simple_derive(tt: &tt::Subtree, trait_path: tt::Subtree) -> ExpandResult<tt::Subtree> {
+/// therefore does not get bound by the derived trait. The items of the impl are built by
+/// `make_trait_body`.
+fn expand_simple_derive(
+    tt: &tt::Subtree,
+    trait_path: tt::Subtree,
+    make_trait_body: impl FnOnce(&BasicAdtInfo) -> tt::Subtree,
+) -> ExpandResult<tt::Subtree> {
     let info = match parse_adt(tt) {
         Ok(info) => info,
         Err(e) => return ExpandResult::new(tt::Subtree::empty(), e),
     };
+    let trait_body = make_trait_body(&info).token_trees;
     let mut where_block = vec![];
     let (params, args): (Vec<_>, Vec<_>) = info
         .param_types
@@ -227,7 +338,7 @@ fn expand_simple_derive(tt: &tt::Subtree, trait_path: tt::Subtree) -> ExpandResu
 
     let name = info.name;
     let expanded = quote! {
-        impl < ##params > #trait_path for #name < ##args > where ##where_block {}
+        impl < ##params > #trait_path for #name < ##args > where ##where_block { ##trait_body }
     };
     ExpandResult::ok(expanded)
 }
@@ -254,7 +365,7 @@ fn copy_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::marker::Copy })
+    expand_simple_derive(tt, quote! { #krate::marker::Copy }, |_| quote! {})
 }
 
 fn clone_expand(
@@ -263,7 +374,7 @@ fn clone_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::clone::Clone })
+    expand_simple_derive(tt, quote! { #krate::clone::Clone }, |_| quote! {})
 }
 
 fn default_expand(
@@ -272,7 +383,7 @@ fn default_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::default::Default })
+    expand_simple_derive(tt, quote! { #krate::default::Default }, |_| quote! {})
 }
 
 fn debug_expand(
@@ -281,7 +392,7 @@ fn debug_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::fmt::Debug })
+    expand_simple_derive(tt, quote! { #krate::fmt::Debug }, |_| quote! {})
 }
 
 fn hash_expand(
@@ -290,7 +401,7 @@ fn hash_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::hash::Hash })
+    expand_simple_derive(tt, quote! { #krate::hash::Hash }, |_| quote! {})
 }
 
 fn eq_expand(
@@ -299,7 +410,7 @@ fn eq_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::Eq })
+    expand_simple_derive(tt, quote! { #krate::cmp::Eq }, |_| quote! {})
 }
 
 fn partial_eq_expand(
@@ -308,7 +419,27 @@ fn partial_eq_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::PartialEq })
+    expand_simple_derive(tt, quote! { #krate::cmp::PartialEq }, |adt| {
+        let arms = adt.shape.variants(&adt.name).into_iter().map(|(path, shape)| {
+            let self_pattern = shape.as_pattern(path.clone(), "self");
+            let other_pattern = shape.as_pattern(path, "other");
+            let fields = shape.field_names().into_iter().map(|name| {
+                let (self_field, other_field) = (binding(&name, "self"), binding(&name, "other"));
+                quote! { && *#self_field == *#other_field }
+            });
+            quote! { ( #self_pattern , #other_pattern ) => true ##fields , }
+        });
+        let other_variants =
+            matches!(adt.shape, AdtShape::Enum { .. }).then(|| quote! { _unused => false , });
+        quote! {
+            fn eq(&self, other: &Self) -> bool {
+                match (self, other) {
+                    ##arms
+                    ##other_variants
+                }
+            }
+        }
+    })
 }
 
 fn ord_expand(
@@ -316,8 +447,22 @@ fn ord_expand(
     id: MacroCallId,
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
-    let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::Ord })
+    let krate = &find_builtin_crate(db, id);
+    expand_simple_derive(tt, quote! { #krate::cmp::Ord }, |adt| {
+        let ordering = |it: tt::Subtree| quote! { #krate::cmp::Ordering::#it };
+        let body = compare_fields(
+            adt,
+            quote! { #krate::cmp::Ord::cmp },
+            ordering(quote! { Equal }),
+            ordering(quote! { Less }),
+            ordering(quote! { Greater }),
+        );
+        quote! {
+            fn cmp(&self, other: &Self) -> #krate::cmp::Ordering {
+                #body
+            }
+        }
+    })
 }
 
 fn partial_ord_expand(
@@ -325,6 +470,77 @@ fn partial_ord_expand(
     id: MacroCallId,
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
-    let krate = find_builtin_crate(db, id);
-    expand_simple_derive(tt, quote! { #krate::cmp::PartialOrd })
+    let krate = &find_builtin_crate(db, id);
+    expand_simple_derive(tt, quote! { #krate::cmp::PartialOrd }, |adt| {
+        let ordering =
+            |it: tt::Subtree| quote! { #krate::option::Option::Some(#krate::cmp::Ordering::#it) };
+        let body = compare_fields(
+            adt,
+            quote! { #krate::cmp::PartialOrd::partial_cmp },
+            ordering(quote! { Equal }),
+            ordering(quote! { Less }),
+            ordering(quote! { Greater }),
+        );
+        quote! {
+            fn partial_cmp(&self, other: &Self) -> #krate::option::Option<#krate::cmp::Ordering> {
+                #body
+            }
+        }
+    })
+}
+
+/// Builds the body of `Ord::cmp` or `PartialOrd::partial_cmp`, which compares the variants, then
+/// their fields in order with `compare`. The `equal`, `less` and `greater` results are also
+/// patterns.
+fn compare_fields(
+    adt: &BasicAdtInfo,
+    compare: tt::Subtree,
+    equal: tt::Subtree,
+    less: tt::Subtree,
+    greater: tt::Subtree,
+) -> tt::Subtree {
+    let (compare, equal) = (&compare, &equal);
+    let variants = adt.shape.variants(&adt.name);
+    let arms = variants.iter().map(|(path, shape)| {
+        let self_pattern = shape.as_pattern(path.clone(), "self");
+        let other_pattern = shape.as_pattern(path.clone(), "other");
+        let body = shape.field_names().into_iter().rev().fold(equal.clone(), |rest, name| {
+            let (self_field, other_field) = (binding(&name, "self"), binding(&name, "other"));
+            quote! {
+                match #compare(#self_field, #other_field) {
+                    #equal => #rest ,
+                    cmp => cmp ,
+                }
+            }
+        });
+        quote! { ( #self_pattern , #other_pattern ) => #body , }
+    });
+    // FIXME: Variants are compared by their order, not by their discriminants.
+    let other_variants = match adt.shape {
+        AdtShape::Enum { .. } if variants.len() > 1 => {
+            let index = |it: tt::Subtree| {
+                let arms = variants.iter().enumerate().map(|(idx, (path, shape))| {
+                    let pattern = shape.as_wildcard(path.clone());
+                    quote! { #pattern => #idx , }
+                });
+                quote! { match #it { ##arms } }
+            };
+            let (self_index, other_index) = (index(quote! { self }), index(quote! { other }));
+            Some(quote! {
+                _unused => {
+                    let self_index = #self_index ;
+                    let other_index = #other_index ;
+                    if self_index < other_index { #less } else { #greater }
+                }
+            })
+        }
+        AdtShape::Enum { .. } => Some(quote! { _unused => #equal , }),
+        AdtShape::Struct(_) | AdtShape::Union => None,
+    };
+    quote! {
+        match (self, other) {
+            ##arms
+            ##other_variants
+        }
+    }
 }
@@ -4,6 +4,7 @@ use base_db::{AnchoredPath, Edition, FileId};
 use cfg::CfgExpr;
 use either::Either;
 use mbe::{parse_exprs_with_sep, parse_to_token_tree, TokenMap};
+use stdx::format_to;
 use syntax::{
     ast::{self, AstToken},
     SmolStr,
@@ -104,6 +105,7 @@ register_builtin! {
     (unreachable, Unreachable) => unreachable_expand,
     (log_syntax, LogSyntax) => log_syntax_expand,
     (trace_macros, TraceMacros) => trace_macros_expand,
+    (offset_of, OffsetOf) => offset_of_expand,
 
     EAGER:
     (compile_error, CompileError) => compile_error_expand,
@@ -173,6 +175,26 @@ fn stringify_expand(
     ExpandResult::ok(expanded)
 }
 
+fn offset_of_expand(
+    _db: &dyn ExpandDatabase,
+    _id: MacroCallId,
+    tt: &tt::Subtree,
+) -> ExpandResult<tt::Subtree> {
+    // We expand `offset_of!(Type, field)` to `builtin # offset_of(Type, field)`, the offset is
+    // computed from the layout of the type once it is lowered.
+    let pound =
+        tt::Punct { char: '#', spacing: tt::Spacing::Alone, span: tt::TokenId::unspecified() };
+    let args = tt::Subtree {
+        delimiter: tt::Delimiter {
+            kind: tt::DelimiterKind::Parenthesis,
+            open: tt::TokenId::unspecified(),
+            close: tt::TokenId::unspecified(),
+        },
+        token_trees: tt.token_trees.clone(),
+    };
+    ExpandResult::ok(quote! { builtin #pound offset_of #args })
+}
+
 fn column_expand(
     _db: &dyn ExpandDatabase,
     _id: MacroCallId,
@@ -262,12 +284,22 @@ fn format_args_expand(
             }
         }
     }
-    let _format_string = args.remove(0);
+    let format_string = args.remove(0);
+    // A format string without placeholders is its only piece, which const eval renders as the
+    // message of a panic.
+    let piece = match format_string.token_trees.as_slice() {
+        [tt::TokenTree::Leaf(tt::Leaf::Literal(lit))]
+            if args.is_empty() && lit.text.starts_with('"') && !lit.text.contains(['{', '}']) =>
+        {
+            Some(format_string)
+        }
+        _ => None,
+    };
     let arg_tts = args.into_iter().flat_map(|arg| {
         quote! { #DOLLAR_CRATE::fmt::ArgumentV1::new(&(#arg), #DOLLAR_CRATE::fmt::Display::fmt), }
     }.token_trees);
     let expanded = quote! {
-        #DOLLAR_CRATE::fmt::Arguments::new_v1(&[], &[##arg_tts])
+        #DOLLAR_CRATE::fmt::Arguments::new_v1(&[##piece], &[##arg_tts])
     };
     ExpandResult::ok(expanded)
 }
@@ -278,28 +310,82 @@ fn asm_expand(
     tt: &tt::Subtree,
 ) -> ExpandResult<tt::Subtree> {
     // We expand all assembly snippets to `format_args!` invocations to get format syntax
-    // highlighting for them.
+    // highlighting for them, and the operand expressions to `let _ = expr;` statements so that
+    // they are type checked and can be navigated.
 
     let mut literals = Vec::new();
-    for tt in tt.token_trees.chunks(2) {
-        match tt {
-            [tt::TokenTree::Leaf(tt::Leaf::Literal(lit))]
-            | [tt::TokenTree::Leaf(tt::Leaf::Literal(lit)), tt::TokenTree::Leaf(tt::Leaf::Punct(tt::Punct { char: ',', span: _, spacing: _ }))] =>
-            {
+    let mut operands = Vec::new();
+    for arg in tt.token_trees.split(|it| is_punct(it, ',')) {
+        match arg {
+            [] => {}
+            [tt::TokenTree::Leaf(tt::Leaf::Literal(lit))] if operands.is_empty() => {
                 let krate = DOLLAR_CRATE.clone();
                 literals.push(quote!(#krate::format_args!(#lit);));
             }
-            _ => break,
+            _ => operands.extend(asm_operand_exprs(arg).into_iter().map(|expr| {
+                let expr = tt::Subtree {
+                    delimiter: tt::Delimiter::unspecified(),
+                    token_trees: expr.to_vec(),
+                };
+                quote!(let _ = #expr;)
+            })),
         }
     }
 
     let expanded = quote! {{
         ##literals
+        ##operands
         loop {}
     }};
     ExpandResult::ok(expanded)
 }
 
+/// Returns the expressions of an `asm!` operand, like `a` and `b` for `x = inout(reg) a => b`.
+fn asm_operand_exprs(operand: &[tt::TokenTree]) -> Vec<&[tt::TokenTree]> {
+    let operand = match operand {
+        [tt::TokenTree::Leaf(tt::Leaf::Ident(_)), eq, rest @ ..]
+            if is_punct(eq, '=') && !rest.first().map_or(false, |it| is_punct(it, '>')) =>
+        {
+            rest
+        }
+        _ => operand,
+    };
+    let exprs = match operand {
+        [tt::TokenTree::Leaf(tt::Leaf::Ident(kw)), tt::TokenTree::Subtree(_), exprs @ ..]
+            if matches!(&*kw.text, "in" | "out" | "lateout" | "inout" | "inlateout") =>
+        {
+            exprs
+        }
+        [tt::TokenTree::Leaf(tt::Leaf::Ident(kw)), exprs @ ..]
+            if matches!(&*kw.text, "const" | "sym") =>
+        {
+            exprs
+        }
+        _ => return Vec::new(),
+    };
+
+    let mut acc = Vec::new();
+    let mut start = 0;
+    for (idx, window) in exprs.windows(2).enumerate() {
+        // Split `in_expr => out_expr`.
+        if idx >= start && is_punct(&window[0], '=') && is_punct(&window[1], '>') {
+            acc.push(&exprs[start..idx]);
+            start = idx + 2;
+        }
+    }
+    acc.push(&exprs[start..]);
+    acc.retain(|expr| match expr {
+        [] => false,
+        [tt::TokenTree::Leaf(tt::Leaf::Ident(it))] => it.text != "_",
+        _ => true,
+    });
+    acc
+}
+
+fn is_punct(tt: &tt::TokenTree, c: char) -> bool {
+    matches!(tt, tt::TokenTree::Leaf(tt::Leaf::Punct(p)) if p.char == c)
+}
+
 fn global_asm_expand(
     _db: &dyn ExpandDatabase,
     _id: MacroCallId,
@@ -580,28 +666,54 @@ fn include_expand(
 }
 
 fn include_bytes_expand(
-    _db: &dyn ExpandDatabase,
-    _arg_id: MacroCallId,
+    db: &dyn ExpandDatabase,
+    arg_id: MacroCallId,
     tt: &tt::Subtree,
 ) -> ExpandResult<ExpandedEager> {
-    if let Err(e) = parse_string(tt) {
-        return ExpandResult::new(
-            ExpandedEager { subtree: tt::Subtree::empty(), included_file: None },
-            e,
-        );
-    }
+    let path = match parse_string(tt) {
+        Ok(it) => it,
+        Err(e) => {
+            return ExpandResult::new(
+                ExpandedEager { subtree: tt::Subtree::empty(), included_file: None },
+                e,
+            )
+        }
+    };
 
-    // FIXME: actually read the file here if the user asked for macro expansion
+    // Like for `include_str!`, files which aren't loaded expand to an empty literal. The contents
+    // of the loaded ones are needed by const evaluation, which materializes the literal.
+    let bytes = match relative_file(db, arg_id, &path, true) {
+        Ok(file_id) => byte_string_literal(db.file_text(file_id).as_bytes()),
+        Err(_) => r#"b"""#.to_owned(),
+    };
     let res = tt::Subtree {
         delimiter: tt::Delimiter::unspecified(),
         token_trees: vec![tt::TokenTree::Leaf(tt::Leaf::Literal(tt::Literal {
-            text: r#"b"""#.into(),
+            text: bytes.into(),
             span: tt::TokenId::unspecified(),
         }))],
     };
     ExpandResult::ok(ExpandedEager::new(res))
 }
 
+/// Renders `bytes` as a byte string literal, escaping everything but printable ASCII characters.
+fn byte_string_literal(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity(bytes.len() + 3);
+    res.push_str("b\"");
+    for &b in bytes {
+        match b {
+            b'"' | b'\\' => {
+                res.push('\\');
+                res.push(b as char);
+            }
+            b' '..=b'~' => res.push(b as char),
+            _ => format_to!(res, "\\x{b:02x}"),
+        }
+    }
+    res.push('"');
+    res
+}
+
 fn include_str_expand(
     db: &dyn ExpandDatabase,
     arg_id: MacroCallId,
@@ -326,6 +326,7 @@ pub mod known {
         llvm_asm,
         log_syntax,
         module_path,
+        offset_of,
         option_env,
         std_panic,
         stringify,
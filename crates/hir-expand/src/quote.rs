@@ -97,7 +97,13 @@ macro_rules! __quote {
     // Puncts
     // FIXME: Not all puncts are handled
     ( -> ) => {$crate::__quote!(@PUNCT '-', '>')};
+    ( => ) => {$crate::__quote!(@PUNCT '=', '>')};
     ( & ) => {$crate::__quote!(@PUNCT '&')};
+    ( && ) => {$crate::__quote!(@PUNCT '&', '&')};
+    ( * ) => {$crate::__quote!(@PUNCT '*')};
+    ( = ) => {$crate::__quote!(@PUNCT '=')};
+    ( == ) => {$crate::__quote!(@PUNCT '=', '=')};
+    ( .. ) => {$crate::__quote!(@PUNCT '.', '.')};
     ( , ) => {$crate::__quote!(@PUNCT ',')};
     ( : ) => {$crate::__quote!(@PUNCT ':')};
     ( ; ) => {$crate::__quote!(@PUNCT ';')};
@@ -162,12 +168,24 @@ impl ToTokenTree for crate::tt::TokenTree {
     }
 }
 
+impl ToTokenTree for &crate::tt::TokenTree {
+    fn to_token(self) -> crate::tt::TokenTree {
+        self.clone()
+    }
+}
+
 impl ToTokenTree for crate::tt::Subtree {
     fn to_token(self) -> crate::tt::TokenTree {
         self.into()
     }
 }
 
+impl ToTokenTree for &crate::tt::Subtree {
+    fn to_token(self) -> crate::tt::TokenTree {
+        self.clone().into()
+    }
+}
+
 macro_rules! impl_to_to_tokentrees {
     ($($ty:ty => $this:ident $im:block);*) => {
         $(
@@ -216,6 +234,11 @@ mod tests {
         assert_eq!(quote!(struct).to_string(), "struct");
     }
 
+    #[test]
+    fn test_quote_puncts() {
+        assert_eq!(quote!(a => *b == c && d .. e = f).to_string(), "a => * b == c && d .. e = f");
+    }
+
     #[test]
     fn test_quote_hash_simple_literal() {
         let a = 20;
@@ -0,0 +1,326 @@
+//! Explains why a type doesn't implement an auto trait like `Send`, `Sync` or `Unpin`, by finding
+//! the field, the capture or the local of an async block which doesn't implement it, like rustc
+//! does in the "future cannot be sent between threads safely" errors.
+//!
+//! The explanation follows the rules of auto traits: a type implements an auto trait if it has an
+//! explicit impl of it whose where clauses hold, or if it has no explicit impl and all of its
+//! constituent types implement it. The constituent types of closures and futures aren't known to
+//! the trait solver, so they are computed from the captures of closures and from the locals held
+//! across an `.await` in async blocks.
+
+use std::sync::Arc;
+
+use chalk_ir::cast::Cast;
+use hir_def::{
+    hir::{Expr, ExprId, Statement},
+    lang_item::{lang_attr, LangItem},
+    AdtId, BindingId, DefWithBodyId, FieldId, TraitId, VariantId,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    chalk_db::convert_where_clauses, db::HirDatabase, from_chalk_trait_id,
+    infer::unify::InferenceTable, method_resolution::TyFingerprint, AliasTy, ImplTraitId, Interner,
+    OpaqueTy, TraitEnvironment, Ty, TyBuilder, TyKind, WhereClause,
+};
+
+/// Why a type doesn't implement an auto trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoTraitBlocker {
+    /// The types leading from the type to the one blocking the auto trait, which is the last one.
+    /// Empty if the type itself blocks the auto trait, like `Rc<T>` which isn't `Send`.
+    pub path: Vec<BlockingStep>,
+}
+
+/// A type on the path to the type blocking an auto trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockingStep {
+    /// How the type is reached from the previous one.
+    pub kind: BlockingStepKind,
+    pub ty: Ty,
+    /// The auto trait the type doesn't implement, which is a different one than the one of the
+    /// previous type for where clauses like the one of `impl<T: Sync> Send for &T`.
+    pub trait_: TraitId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockingStepKind {
+    /// A field of a struct, a union or an enum variant.
+    Field(FieldId),
+    TupleField(usize),
+    /// The element of an array or a slice, or the parameter of `PhantomData`.
+    Element,
+    /// A capture of a closure, by the local it captures.
+    Capture(DefWithBodyId, BindingId),
+    /// A local of an async block or an async fn, held across an `.await`.
+    HeldAcrossAwait(DefWithBodyId, BindingId),
+    /// The hidden type of a return position `impl Trait`.
+    HiddenType,
+    /// A type bound by a where clause of an explicit impl of the auto trait.
+    WhereClause,
+}
+
+/// Returns why `ty` doesn't implement the auto trait `trait_`, or `None` if it implements it.
+pub(crate) fn auto_trait_blocker_query(
+    db: &dyn HirDatabase,
+    ty: Ty,
+    trait_: TraitId,
+    env: Arc<TraitEnvironment>,
+) -> Option<Arc<AutoTraitBlocker>> {
+    let _p = profile::span("auto_trait_blocker_query");
+    let mut ctx = Context { db, env, results: FxHashMap::default() };
+    ctx.blocker(&ty, trait_).map(|path| Arc::new(AutoTraitBlocker { path }))
+}
+
+struct Context<'a> {
+    db: &'a dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    /// The blockers of the types already visited. A type being visited is assumed to implement the
+    /// auto trait, like the trait solver does for recursive types.
+    results: FxHashMap<(Ty, TraitId), Option<Vec<BlockingStep>>>,
+}
+
+impl Context<'_> {
+    fn blocker(&mut self, ty: &Ty, trait_: TraitId) -> Option<Vec<BlockingStep>> {
+        let key = (ty.clone(), trait_);
+        if let Some(result) = self.results.get(&key) {
+            return result.clone();
+        }
+        self.results.insert(key.clone(), None);
+        let result = self.compute_blocker(ty, trait_);
+        self.results.insert(key, result.clone());
+        result
+    }
+
+    fn compute_blocker(&mut self, ty: &Ty, trait_: TraitId) -> Option<Vec<BlockingStep>> {
+        if let Some(result) = self.explicit_impls_blocker(ty, trait_) {
+            return result;
+        }
+        let db = self.db;
+        match ty.kind(Interner) {
+            TyKind::Adt(chalk_ir::AdtId(adt), subst) => {
+                if lang_attr(db.upcast(), *adt) == Some(LangItem::PhantomData) {
+                    let param = subst.at(Interner, 0).ty(Interner)?;
+                    return self.step(BlockingStepKind::Element, param, trait_);
+                }
+                let variants: Vec<VariantId> = match *adt {
+                    AdtId::StructId(it) => vec![it.into()],
+                    AdtId::UnionId(it) => vec![it.into()],
+                    AdtId::EnumId(it) => {
+                        let data = db.enum_data(it);
+                        data.variants.iter().map(|(local_id, _)| (it, local_id).into()).collect()
+                    }
+                };
+                variants.into_iter().find_map(|variant| {
+                    let field_types = db.field_types(variant);
+                    field_types.iter().find_map(|(local_id, field_ty)| {
+                        let field_ty = field_ty.clone().substitute(Interner, subst);
+                        let field = FieldId { parent: variant, local_id };
+                        self.step(BlockingStepKind::Field(field), &field_ty, trait_)
+                    })
+                })
+            }
+            TyKind::Tuple(_, subst) => {
+                subst.iter(Interner).filter_map(|it| it.ty(Interner)).enumerate().find_map(
+                    |(idx, field_ty)| {
+                        self.step(BlockingStepKind::TupleField(idx), field_ty, trait_)
+                    },
+                )
+            }
+            TyKind::Array(element, _) | TyKind::Slice(element) => {
+                self.step(BlockingStepKind::Element, element, trait_)
+            }
+            TyKind::Closure(closure, _) => {
+                let (owner, _) = db.lookup_intern_closure((*closure).into());
+                let infer = db.infer(owner);
+                let (captures, _) = infer.closure_info(closure);
+                captures.iter().find_map(|capture| {
+                    let kind = BlockingStepKind::Capture(owner, capture.local());
+                    self.step(kind, capture.ty(), trait_)
+                })
+            }
+            TyKind::OpaqueType(opaque, _)
+            | TyKind::Alias(AliasTy::Opaque(OpaqueTy { opaque_ty_id: opaque, .. })) => {
+                match db.lookup_intern_impl_trait_id((*opaque).into()) {
+                    ImplTraitId::AsyncBlockTypeImplTrait(owner, expr) => {
+                        let infer = db.infer(owner);
+                        held_across_await(db, owner, expr).into_iter().find_map(|binding| {
+                            let kind = BlockingStepKind::HeldAcrossAwait(owner, binding);
+                            self.step(kind, &infer.type_of_binding[binding], trait_)
+                        })
+                    }
+                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
+                        let infer = db.infer(func.into());
+                        let hidden = infer.type_of_rpit.get(idx)?.clone();
+                        self.step(BlockingStepKind::HiddenType, &hidden, trait_)
+                    }
+                    // FIXME: explain the hidden types of type alias impl traits.
+                    ImplTraitId::TypeAliasImplTrait(..) => self.solve(ty, trait_),
+                }
+            }
+            _ => self.solve(ty, trait_),
+        }
+    }
+
+    fn step(
+        &mut self,
+        kind: BlockingStepKind,
+        ty: &Ty,
+        trait_: TraitId,
+    ) -> Option<Vec<BlockingStep>> {
+        let mut path = self.blocker(ty, trait_)?;
+        path.insert(0, BlockingStep { kind, ty: ty.clone(), trait_ });
+        Some(path)
+    }
+
+    /// Returns the blocker of `ty` for its explicit impls of `trait_`, or `None` if it doesn't have
+    /// explicit impls and its constituent types decide whether it implements the auto trait.
+    fn explicit_impls_blocker(
+        &mut self,
+        ty: &Ty,
+        trait_: TraitId,
+    ) -> Option<Option<Vec<BlockingStep>>> {
+        let db = self.db;
+        let impls = db.trait_impls_in_deps(self.env.krate);
+        let candidates: Vec<_> = match TyFingerprint::for_trait_impl(ty) {
+            Some(fp) => impls.for_trait_and_self_ty(trait_, fp).collect(),
+            None => impls.for_trait(trait_).collect(),
+        };
+
+        let mut has_impl = false;
+        let mut blocker = None;
+        for impl_ in candidates {
+            let mut table = InferenceTable::new(db, self.env.clone());
+            let subst = TyBuilder::subst_for_def(db, impl_, None)
+                .fill_with_inference_vars(&mut table)
+                .build();
+            let self_ty = db.impl_self_ty(impl_).substitute(Interner, &subst);
+            if !table.unify(&self_ty, ty) {
+                continue;
+            }
+            if db.impl_data(impl_).is_negative {
+                return Some(Some(Vec::new()));
+            }
+            has_impl = true;
+            let where_clauses = convert_where_clauses(db, impl_.into(), &subst);
+            let unsatisfied = where_clauses.into_iter().find_map(|wc| {
+                let WhereClause::Implemented(trait_ref) = wc.skip_binders() else { return None };
+                let bound_trait = from_chalk_trait_id(trait_ref.trait_id);
+                let bound_ty = table.resolve_completely(trait_ref.self_type_parameter(Interner));
+                if db.trait_data(bound_trait).is_auto {
+                    self.step(BlockingStepKind::WhereClause, &bound_ty, bound_trait)
+                } else if table.try_obligation(wc.clone().cast(Interner)).is_none() {
+                    let step = BlockingStep {
+                        kind: BlockingStepKind::WhereClause,
+                        ty: bound_ty,
+                        trait_: bound_trait,
+                    };
+                    Some(vec![step])
+                } else {
+                    None
+                }
+            });
+            match unsatisfied {
+                None => return Some(None),
+                Some(path) => blocker = blocker.or(Some(path)),
+            }
+        }
+        has_impl.then_some(blocker)
+    }
+
+    /// Asks the trait solver whether `ty` implements `trait_`, for the types whose constituent
+    /// types aren't explained.
+    fn solve(&self, ty: &Ty, trait_: TraitId) -> Option<Vec<BlockingStep>> {
+        let canonical = crate::Canonical {
+            value: ty.clone(),
+            binders: chalk_ir::CanonicalVarKinds::empty(Interner),
+        };
+        let implemented = crate::method_resolution::implements_trait(
+            &canonical,
+            self.db,
+            self.env.clone(),
+            trait_,
+        );
+        (!implemented).then(Vec::new)
+    }
+}
+
+/// Returns the locals of the async block `expr` which are held across an `.await`: the locals
+/// declared by a statement followed by an `.await` in the same block or in an enclosing block of
+/// the async block, and the parameters of async fns.
+fn held_across_await(db: &dyn HirDatabase, owner: DefWithBodyId, expr: ExprId) -> Vec<BindingId> {
+    let body = db.body(owner);
+    let mut held = Vec::new();
+    if body.body_expr == expr && matches!(owner, DefWithBodyId::FunctionId(_)) {
+        // The body of an async fn takes ownership of the parameters.
+        for &param in body.params.iter() {
+            body.walk_bindings_in_pat(param, |it| held.push(it));
+        }
+    }
+    if let Expr::Async { statements, tail, .. } = &body[expr] {
+        collect_held_in_block(&body, statements, *tail, &mut held);
+    }
+    let mut seen = FxHashSet::default();
+    held.retain(|it| seen.insert(*it));
+    held
+}
+
+fn collect_held_in_block(
+    body: &hir_def::body::Body,
+    statements: &[Statement],
+    tail: Option<ExprId>,
+    held: &mut Vec<BindingId>,
+) {
+    let mut live = Vec::new();
+    let mut visit = |expr: ExprId, live: &[BindingId], held: &mut Vec<BindingId>| {
+        if contains_await(body, expr) {
+            held.extend_from_slice(live);
+        }
+        collect_held_in_nested_blocks(body, expr, held);
+    };
+    for stmt in statements {
+        match stmt {
+            Statement::Let { pat, initializer, else_branch, .. } => {
+                for &expr in initializer.iter().chain(else_branch) {
+                    visit(expr, &live, held);
+                }
+                body.walk_bindings_in_pat(*pat, |it| live.push(it));
+            }
+            Statement::Expr { expr, .. } => visit(*expr, &live, held),
+        }
+    }
+    if let Some(tail) = tail {
+        visit(tail, &live, held);
+    }
+}
+
+fn collect_held_in_nested_blocks(
+    body: &hir_def::body::Body,
+    expr: ExprId,
+    held: &mut Vec<BindingId>,
+) {
+    match &body[expr] {
+        Expr::Block { statements, tail, .. } | Expr::Unsafe { statements, tail, .. } => {
+            collect_held_in_block(body, statements, *tail, held)
+        }
+        // The `.await`s of these don't suspend the async block.
+        Expr::Closure { .. } | Expr::Async { .. } | Expr::Const { .. } => {}
+        it => it.walk_child_exprs(|it| collect_held_in_nested_blocks(body, it, held)),
+    }
+}
+
+/// Returns whether evaluating `expr` can suspend the async block it is in.
+fn contains_await(body: &hir_def::body::Body, expr: ExprId) -> bool {
+    match &body[expr] {
+        Expr::Await { .. } => true,
+        Expr::Closure { .. } | Expr::Async { .. } | Expr::Const { .. } => false,
+        it => {
+            let mut found = false;
+            it.walk_child_exprs(|it| found |= contains_await(body, it));
+            found
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,212 @@
+use base_db::fixture::WithFixture;
+use expect_test::{expect, Expect};
+use hir_def::{db::DefDatabase, ModuleDefId};
+use itertools::Itertools;
+
+use crate::{auto_trait::BlockingStepKind, db::HirDatabase, display::HirDisplay, test_db::TestDB};
+
+/// Explains why the local `value` of `main` isn't `Send`, with the `Send` trait of the fixture.
+fn check(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let def_map = module.def_map(&db);
+    let scope = &def_map[module.local_id].scope;
+    let send = scope
+        .declarations()
+        .find_map(|decl| match decl {
+            ModuleDefId::TraitId(it) if db.trait_data(it).name.to_smol_str() == "Send" => Some(it),
+            _ => None,
+        })
+        .unwrap();
+    let main = scope
+        .declarations()
+        .find_map(|decl| match decl {
+            ModuleDefId::FunctionId(it) if db.function_data(it).name.to_smol_str() == "main" => {
+                Some(it)
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    let body = db.body(main.into());
+    let infer = db.infer(main.into());
+    let (value, _) =
+        body.bindings.iter().find(|(_, binding)| binding.name.to_smol_str() == "value").unwrap();
+    let env = db.trait_environment_for_body(main.into());
+    let actual = match db.auto_trait_blocker(infer.type_of_binding[value].clone(), send, env) {
+        None => "implemented\n".to_owned(),
+        Some(blocker) => blocker
+            .path
+            .iter()
+            .map(|step| {
+                let kind = match step.kind {
+                    BlockingStepKind::Field(field) => {
+                        let data = field.parent.variant_data(db.upcast());
+                        format!("field {}", data.fields()[field.local_id].name.to_smol_str())
+                    }
+                    BlockingStepKind::TupleField(idx) => format!("field {idx}"),
+                    BlockingStepKind::Element => "element".to_owned(),
+                    BlockingStepKind::Capture(owner, binding) => {
+                        format!("capture {}", db.body(owner).bindings[binding].name.to_smol_str())
+                    }
+                    BlockingStepKind::HeldAcrossAwait(owner, binding) => {
+                        format!("held {}", db.body(owner).bindings[binding].name.to_smol_str())
+                    }
+                    BlockingStepKind::HiddenType => "hidden type".to_owned(),
+                    BlockingStepKind::WhereClause => "where clause".to_owned(),
+                };
+                let trait_ = db.trait_data(step.trait_).name.to_smol_str();
+                format!("{kind}: {}: {trait_}\n", step.ty.display_test(&db))
+            })
+            .join(""),
+    };
+    expect.assert_eq(if actual.is_empty() { "blocked\n" } else { &actual });
+}
+
+#[test]
+fn implemented() {
+    check(
+        r#"
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+struct Foo { a: i32, b: (u8, [bool; 2]) }
+fn main() {
+    let value = Foo { a: 0, b: (0, [true; 2]) };
+}
+"#,
+        expect![[r#"
+            implemented
+        "#]],
+    );
+}
+
+#[test]
+fn negative_impl() {
+    check(
+        r#"
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+struct Rc;
+impl !Send for Rc {}
+fn main() {
+    let value = Rc;
+}
+"#,
+        expect![[r#"
+            blocked
+        "#]],
+    );
+}
+
+#[test]
+fn fields() {
+    check(
+        r#"
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+struct Rc;
+impl !Send for Rc {}
+struct Inner<T> { ok: u32, rc: T }
+enum Outer { A(u32), B { inner: (u8, [Inner<Rc>; 1]) } }
+fn main() {
+    let value = Outer::A(0);
+}
+"#,
+        expect![[r#"
+            field inner: (u8, [Inner<Rc>; 1]): Send
+            field 1: [Inner<Rc>; 1]: Send
+            element: Inner<Rc>: Send
+            field rc: Rc: Send
+        "#]],
+    );
+}
+
+#[test]
+fn where_clauses_of_explicit_impls() {
+    check(
+        r#"
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+auto trait Sync {}
+struct Cell;
+impl !Sync for Cell {}
+struct Arc<T> { ptr: *const T }
+unsafe impl<T: Send + Sync> Send for Arc<T> {}
+fn main() {
+    let value = Arc::<Cell> { ptr: loop {} };
+}
+"#,
+        expect![[r#"
+            where clause: Cell: Sync
+        "#]],
+    );
+}
+
+#[test]
+fn recursive_types() {
+    check(
+        r#"
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+struct Rc;
+impl !Send for Rc {}
+struct List<T> { next: Option<Box<List<T>>>, value: T }
+enum Option<T> { None, Some(T) }
+struct Box<T>(T);
+fn main() {
+    let value = List { next: Option::None, value: Rc };
+}
+"#,
+        expect![[r#"
+            field value: Rc: Send
+        "#]],
+    );
+}
+
+#[test]
+fn closure_captures() {
+    check(
+        r#"
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+struct Rc;
+impl !Send for Rc {}
+fn main() {
+    let ok = 1;
+    let rc = Rc;
+    let value = move || { ok; rc; };
+}
+"#,
+        expect![[r#"
+            capture rc: Rc: Send
+        "#]],
+    );
+}
+
+#[test]
+fn locals_held_across_await() {
+    check(
+        r#"
+//- minicore: future
+#![feature(auto_traits, negative_impls)]
+auto trait Send {}
+struct Rc;
+impl !Send for Rc {}
+async fn yield_now() {}
+async fn work(param: u32) {
+    {
+        let scoped = Rc;
+    }
+    let held = Rc;
+    yield_now().await;
+}
+fn main() {
+    let value = work(0);
+}
+"#,
+        expect![[r#"
+            hidden type: impl Future<Output = ()>: Send
+            held held: Rc: Send
+        "#]],
+    );
+}
@@ -44,12 +44,20 @@ pub(crate) struct Autoderef<'a, 'db> {
     ty: Ty,
     at_start: bool,
     steps: Vec<(AutoderefKind, Ty)>,
+    /// Whether to also follow `Receiver::Target` for types which don't deref, to find the self
+    /// types of methods with arbitrary self types like `self: MyPtr<Self>`.
+    use_receiver_trait: bool,
 }
 
 impl<'a, 'db> Autoderef<'a, 'db> {
     pub(crate) fn new(table: &'a mut InferenceTable<'db>, ty: Ty) -> Self {
         let ty = table.resolve_ty_shallow(&ty);
-        Autoderef { table, ty, at_start: true, steps: Vec::new() }
+        Autoderef { table, ty, at_start: true, steps: Vec::new(), use_receiver_trait: false }
+    }
+
+    pub(crate) fn use_receiver_trait(mut self) -> Self {
+        self.use_receiver_trait = true;
+        self
     }
 
     pub(crate) fn step_count(&self) -> usize {
@@ -78,7 +86,10 @@ impl Iterator for Autoderef<'_, '_> {
             return None;
         }
 
-        let (kind, new_ty) = autoderef_step(self.table, self.ty.clone())?;
+        let (kind, new_ty) = match self.use_receiver_trait {
+            true => receiver_step(self.table, self.ty.clone())?,
+            false => autoderef_step(self.table, self.ty.clone())?,
+        };
 
         self.steps.push((kind, self.ty.clone()));
         self.ty = new_ty;
@@ -98,6 +109,15 @@ pub(crate) fn autoderef_step(
     }
 }
 
+/// Like [`autoderef_step`], but falls back to `Receiver::Target` for the types which implement
+/// `Receiver` without implementing `Deref`.
+fn receiver_step(table: &mut InferenceTable<'_>, ty: Ty) -> Option<(AutoderefKind, Ty)> {
+    autoderef_step(table, ty.clone()).or_else(|| {
+        let target = target_by_trait(table, ty, LangItem::Receiver)?;
+        Some((AutoderefKind::Overloaded, target))
+    })
+}
+
 pub(crate) fn builtin_deref<'ty>(
     table: &mut InferenceTable<'_>,
     ty: &'ty Ty,
@@ -118,25 +138,31 @@ pub(crate) fn builtin_deref<'ty>(
     }
 }
 
-pub(crate) fn deref_by_trait(
+pub(crate) fn deref_by_trait(table: &mut InferenceTable<'_>, ty: Ty) -> Option<Ty> {
+    let _p = profile::span("deref_by_trait");
+    target_by_trait(table, ty, LangItem::Deref)
+}
+
+/// Returns the `Target` of `ty` for the `Deref` or the `Receiver` trait.
+fn target_by_trait(
     table @ &mut InferenceTable { db, .. }: &mut InferenceTable<'_>,
     ty: Ty,
+    trait_: LangItem,
 ) -> Option<Ty> {
-    let _p = profile::span("deref_by_trait");
     if table.resolve_ty_shallow(&ty).inference_var(Interner).is_some() {
         // don't try to deref unknown variables
         return None;
     }
 
-    let deref_trait =
-        db.lang_item(table.trait_env.krate, LangItem::Deref).and_then(|l| l.as_trait())?;
+    let deref_trait = db.lang_item(table.trait_env.krate, trait_).and_then(|l| l.as_trait())?;
+    // The `Receiver` trait only has a `Target` with `arbitrary_self_types`.
     let target = db.trait_data(deref_trait).associated_type_by_name(&name![Target])?;
 
     let projection = {
         let b = TyBuilder::subst_for_def(db, deref_trait, None);
         if b.remaining() != 1 {
-            // the Target type + Deref trait should only have one generic parameter,
-            // namely Deref's Self type
+            // the Target type + trait should only have one generic parameter,
+            // namely the trait's Self type
             return None;
         }
         let deref_subst = b.push(ty).build();
@@ -215,11 +215,10 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
     fn opaque_ty_data(&self, id: chalk_ir::OpaqueTyId<Interner>) -> Arc<OpaqueTyDatum> {
         let full_id = self.db.lookup_intern_impl_trait_id(id.into());
         let bound = match full_id {
-            crate::ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                let datas = self
-                    .db
-                    .return_type_impl_traits(func)
-                    .expect("impl trait id without impl traits");
+            crate::ImplTraitId::ReturnTypeImplTrait(..)
+            | crate::ImplTraitId::TypeAliasImplTrait(..) => {
+                let (datas, idx) =
+                    full_id.impl_traits(self.db).expect("impl trait id without impl traits");
                 let (datas, binders) = (*datas).as_ref().into_value_and_skipped_binders();
                 let data = &datas.impl_traits[idx];
                 let bound = OpaqueTyDatumBound {
@@ -785,22 +784,16 @@ pub(crate) fn fn_def_datum_query(
 
 pub(crate) fn fn_def_variance_query(db: &dyn HirDatabase, fn_def_id: FnDefId) -> Variances {
     let callable_def: CallableDefId = from_chalk(db, fn_def_id);
-    let generic_params = generics(db.upcast(), callable_def.into());
-    Variances::from_iter(
-        Interner,
-        std::iter::repeat(chalk_ir::Variance::Invariant).take(generic_params.len()),
-    )
+    let variances = db.variances_of(callable_def.into());
+    Variances::from_iter(Interner, variances.params.iter().map(|it| it.to_chalk()))
 }
 
 pub(crate) fn adt_variance_query(
     db: &dyn HirDatabase,
     chalk_ir::AdtId(adt_id): AdtId,
 ) -> Variances {
-    let generic_params = generics(db.upcast(), adt_id.into());
-    Variances::from_iter(
-        Interner,
-        std::iter::repeat(chalk_ir::Variance::Invariant).take(generic_params.len()),
-    )
+    let variances = db.variances_of(adt_id.into());
+    Variances::from_iter(Interner, variances.params.iter().map(|it| it.to_chalk()))
 }
 
 pub(super) fn convert_where_clauses(
@@ -844,18 +837,19 @@ pub(super) fn generic_predicate_to_inline_bound(
             if projection_ty.self_type_parameter(db) != self_ty_shifted_in {
                 return None;
             }
-            let args_no_self = projection_ty.substitution.as_slice(Interner)[1..]
-                .iter()
-                .map(|ty| ty.clone().cast(Interner))
-                .collect();
+            // The arguments of a generic associated type come before the ones of its trait.
+            let generics =
+                generics(db.upcast(), from_assoc_type_id(projection_ty.associated_ty_id).into());
+            let (assoc_args, trait_args) =
+                projection_ty.substitution.as_slice(Interner).split_at(generics.len_self());
             let alias_eq_bound = rust_ir::AliasEqBound {
                 value: ty.clone(),
                 trait_bound: rust_ir::TraitBound {
                     trait_id: to_chalk_trait_id(trait_),
-                    args_no_self,
+                    args_no_self: trait_args[1..].to_vec(),
                 },
                 associated_ty_id: projection_ty.associated_ty_id,
-                parameters: Vec::new(), // FIXME we don't support generic associated types yet
+                parameters: assoc_args.to_vec(),
             };
             Some(chalk_ir::Binders::new(
                 binders,
@@ -246,8 +246,9 @@ impl TyExt for Ty {
                             None
                         }
                     }
-                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                        db.return_type_impl_traits(func).map(|it| {
+                    id @ (ImplTraitId::ReturnTypeImplTrait(..)
+                    | ImplTraitId::TypeAliasImplTrait(..)) => {
+                        id.impl_traits(db).map(|(it, idx)| {
                             let data =
                                 (*it).as_ref().map(|rpit| rpit.impl_traits[idx].bounds.clone());
                             data.substitute(Interner, &subst).into_value_and_skipped_binders().0
@@ -258,8 +259,9 @@ impl TyExt for Ty {
             TyKind::Alias(AliasTy::Opaque(opaque_ty)) => {
                 let predicates = match db.lookup_intern_impl_trait_id(opaque_ty.opaque_ty_id.into())
                 {
-                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                        db.return_type_impl_traits(func).map(|it| {
+                    id @ (ImplTraitId::ReturnTypeImplTrait(..)
+                    | ImplTraitId::TypeAliasImplTrait(..)) => {
+                        id.impl_traits(db).map(|(it, idx)| {
                             let data =
                                 (*it).as_ref().map(|rpit| rpit.impl_traits[idx].bounds.clone());
                             data.substitute(Interner, &opaque_ty.substitution)
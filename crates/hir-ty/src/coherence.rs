@@ -0,0 +1,86 @@
+//! Checks that the trait impls of a crate don't overlap, that is that no trait ref is implemented
+//! by two impls, like rustc does in `rustc_trait_selection::traits::coherence`.
+//!
+//! Two impls overlap if their trait refs unify, and if none of the where clauses of the two impls
+//! is known not to hold for the unified trait ref. Unlike rustc, a where clause which doesn't hold
+//! now is assumed to never hold, even if a dependency could add an impl making it hold later.
+
+use std::sync::Arc;
+
+use base_db::CrateId;
+use chalk_ir::cast::Cast;
+use hir_def::{HasModule, ImplId};
+
+use crate::{
+    chalk_db::convert_where_clauses, db::HirDatabase, infer::unify::InferenceTable,
+    method_resolution::TyFingerprint, Interner, TraitEnvironment, TyBuilder, TyExt,
+};
+
+/// Returns the pairs of overlapping impls of the same trait, where the first impl is an impl of
+/// `krate` and the second one an impl of `krate` or of one of its dependencies.
+pub(crate) fn conflicting_impls_query(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+) -> Arc<[(ImplId, ImplId)]> {
+    let _p = profile::span("conflicting_impls_query").detail(|| format!("{krate:?}"));
+    let local_impls = db.trait_impls_in_crate(krate);
+    let all_impls = db.trait_impls_in_deps(krate);
+
+    let mut conflicts = Vec::new();
+    for trait_ in local_impls.traits() {
+        if db.attrs(trait_.into()).by_key("marker").exists() {
+            // Impls of marker traits are allowed to overlap.
+            continue;
+        }
+        let impls: Vec<_> = all_impls
+            .for_trait(trait_)
+            .filter_map(|impl_| {
+                let trait_ref = db.impl_trait(impl_)?;
+                let substitution = &trait_ref.skip_binders().substitution;
+                if substitution.type_parameters(Interner).any(|it| it.contains_unknown()) {
+                    // Unresolved types unify with anything.
+                    return None;
+                }
+                let self_ty = db.impl_self_ty(impl_);
+                let fingerprint = TyFingerprint::for_trait_impl(self_ty.skip_binders());
+                Some((impl_, fingerprint, impl_.module(db.upcast()).krate() == krate))
+            })
+            .collect();
+        for (i, &(first, first_fp, first_local)) in impls.iter().enumerate() {
+            for &(second, second_fp, second_local) in &impls[i + 1..] {
+                if !first_local && !second_local {
+                    continue;
+                }
+                if matches!((first_fp, second_fp), (Some(a), Some(b)) if a != b) {
+                    continue;
+                }
+                if impls_overlap(db, krate, first, second) {
+                    conflicts.push(if first_local { (first, second) } else { (second, first) });
+                }
+            }
+        }
+    }
+    conflicts.into()
+}
+
+fn impls_overlap(db: &dyn HirDatabase, krate: CrateId, a: ImplId, b: ImplId) -> bool {
+    let mut table = InferenceTable::new(db, Arc::new(TraitEnvironment::empty(krate)));
+    let instantiate = |table: &mut InferenceTable<'_>, impl_: ImplId| {
+        let subst =
+            TyBuilder::subst_for_def(db, impl_, None).fill_with_inference_vars(table).build();
+        let trait_ref = db.impl_trait(impl_).map(|it| it.substitute(Interner, &subst));
+        let where_clauses = convert_where_clauses(db, impl_.into(), &subst);
+        (trait_ref, where_clauses)
+    };
+    let (Some(a_trait_ref), a_where_clauses) = instantiate(&mut table, a) else { return false };
+    let (Some(b_trait_ref), b_where_clauses) = instantiate(&mut table, b) else { return false };
+    if !table.unify(&a_trait_ref, &b_trait_ref) {
+        return false;
+    }
+    // It's fine if some where clauses are ambiguous, they may hold for some of the types the trait
+    // ref unifies with.
+    a_where_clauses
+        .into_iter()
+        .chain(b_where_clauses)
+        .all(|wc| table.try_obligation(wc.cast(Interner)).is_some())
+}
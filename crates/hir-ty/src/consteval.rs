@@ -1,7 +1,7 @@
 //! Constant evaluation details
 
 use base_db::CrateId;
-use chalk_ir::{BoundVar, DebruijnIndex, GenericArgData};
+use chalk_ir::{BoundVar, DebruijnIndex, GenericArgData, Scalar, TyKind};
 use hir_def::{
     hir::Expr,
     path::Path,
@@ -20,6 +20,10 @@ use crate::{
 
 use super::mir::{interpret_mir, lower_to_mir, pad16, MirEvalError, MirLowerError};
 
+mod render;
+
+pub(crate) use render::render_const_scalar;
+
 /// Extension trait for [`Const`]
 pub trait ConstExt {
     /// Is a [`Const`] unknown?
@@ -165,6 +169,28 @@ pub fn try_const_usize(c: &Const) -> Option<u128> {
     }
 }
 
+/// Returns the value of `c` if it's an integer, a `bool` or a `char`. Signed integers are sign
+/// extended.
+pub fn try_const_scalar(c: &Const) -> Option<i128> {
+    let data = c.data(Interner);
+    let is_signed = match data.ty.kind(Interner) {
+        TyKind::Scalar(Scalar::Int(_)) => true,
+        TyKind::Scalar(Scalar::Uint(_) | Scalar::Bool | Scalar::Char) => false,
+        _ => return None,
+    };
+    match &data.value {
+        ConstValue::Concrete(c) => match &c.interned {
+            ConstScalar::Bytes(x, _) if (1..=16).contains(&x.len()) => {
+                let value = u128::from_le_bytes(pad16(x, false)) as i128;
+                let shift = 128 - 8 * x.len() as u32;
+                Some(if is_signed { (value << shift) >> shift } else { value })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub(crate) fn const_eval_recover(
     _: &dyn HirDatabase,
     _: &[String],
@@ -187,6 +213,7 @@ pub(crate) fn const_eval_query(
     const_id: ConstId,
     subst: Substitution,
 ) -> Result<Const, ConstEvalError> {
+    let _span = tracing::info_span!("const_eval_query", ?const_id).entered();
     let def = const_id.into();
     let body = db.mir_body(def)?;
     let c = interpret_mir(db, &body, subst, false)?;
@@ -197,6 +224,7 @@ pub(crate) fn const_eval_discriminant_variant(
     db: &dyn HirDatabase,
     variant_id: EnumVariantId,
 ) -> Result<i128, ConstEvalError> {
+    let _span = tracing::info_span!("const_eval_discriminant_variant", ?variant_id).entered();
     let def = variant_id.into();
     let body = db.body(def);
     if body.exprs[body.body_expr] == Expr::Missing {
@@ -0,0 +1,206 @@
+//! Renders the values of evaluated consts, like `Some("foo")` for a `const X: Option<&str>`.
+
+use base_db::CrateId;
+use chalk_ir::{Scalar, TyKind};
+use hir_def::{data::adt::VariantData, AdtId, EnumVariantId, HasModule, VariantId};
+
+use crate::{
+    display::{HirDisplay, HirDisplayError, HirFormatter},
+    layout::{layout_of_ty, Layout, RustcEnumVariantIdx, Variants},
+    mir::{detect_variant, pad16},
+    Interner, MemoryMap, Substitution, Ty,
+};
+
+/// Renders the value `b` of type `ty`, with the memory its references point to in `memory_map`.
+/// Values which can't be rendered show up as placeholders like `<ref-not-supported>`.
+pub(crate) fn render_const_scalar(
+    f: &mut HirFormatter<'_>,
+    b: &[u8],
+    memory_map: &MemoryMap,
+    ty: &Ty,
+) -> Result<(), HirDisplayError> {
+    match ty.kind(Interner) {
+        TyKind::Scalar(s) => match s {
+            Scalar::Bool => write!(f, "{}", if b[0] == 0 { false } else { true }),
+            Scalar::Char => {
+                let x = u128::from_le_bytes(pad16(b, false)) as u32;
+                let Ok(c) = char::try_from(x) else {
+                    return f.write_str("<unicode-error>");
+                };
+                write!(f, "{c:?}")
+            }
+            Scalar::Int(_) => {
+                let x = i128::from_le_bytes(pad16(b, true));
+                write!(f, "{x}")
+            }
+            Scalar::Uint(_) => {
+                let x = u128::from_le_bytes(pad16(b, false));
+                write!(f, "{x}")
+            }
+            Scalar::Float(fl) => match fl {
+                chalk_ir::FloatTy::F32 => {
+                    let x = f32::from_le_bytes(b.try_into().unwrap());
+                    write!(f, "{x:?}")
+                }
+                chalk_ir::FloatTy::F64 => {
+                    let x = f64::from_le_bytes(b.try_into().unwrap());
+                    write!(f, "{x:?}")
+                }
+            },
+        },
+        TyKind::Ref(_, _, t) => match t.kind(Interner) {
+            TyKind::Str => {
+                let addr = u128::from_le_bytes(pad16(&b[0..b.len() / 2], false)) as usize;
+                let bytes = memory_map.0.get(&addr).map(|x| &**x).unwrap_or(&[]);
+                let s = std::str::from_utf8(bytes).unwrap_or("<utf8-error>");
+                write!(f, "{s:?}")
+            }
+            TyKind::Slice(t) => {
+                let addr = u128::from_le_bytes(pad16(&b[0..b.len() / 2], false)) as usize;
+                let Some(bytes) = memory_map.0.get(&addr) else {
+                    return f.write_str("<ref-not-supported>");
+                };
+                f.write_str("&")?;
+                render_elements(f, bytes, memory_map, t)
+            }
+            _ => {
+                let addr = u128::from_le_bytes(pad16(b, false)) as usize;
+                let Some(bytes) = memory_map.0.get(&addr) else {
+                    return f.write_str("<ref-not-supported>");
+                };
+                f.write_str("&")?;
+                render_const_scalar(f, bytes, memory_map, t)
+            }
+        },
+        TyKind::Array(t, _) => render_elements(f, b, memory_map, t),
+        TyKind::Tuple(_, subst) => {
+            let Ok(layout) = layout_of_ty(f.db, ty, any_krate(f)) else {
+                return f.write_str("<layout-error>");
+            };
+            f.write_str("(")?;
+            let mut first = true;
+            for (id, ty) in subst.iter(Interner).enumerate() {
+                if first {
+                    first = false;
+                } else {
+                    f.write_str(", ")?;
+                }
+                let ty = ty.assert_ty_ref(Interner); // Tuple only has type argument
+                let offset = layout.fields.offset(id).bytes_usize();
+                let Ok(layout) = layout_of_ty(f.db, &ty, any_krate(f)) else {
+                    f.write_str("<layout-error>")?;
+                    continue;
+                };
+                let size = layout.size.bytes_usize();
+                render_const_scalar(f, &b[offset..offset + size], memory_map, &ty)?;
+            }
+            f.write_str(")")
+        }
+        TyKind::Adt(adt, subst) => {
+            let Ok(layout) = f.db.layout_of_adt(adt.0, subst.clone()) else {
+                return f.write_str("<layout-error>");
+            };
+            match adt.0 {
+                AdtId::StructId(s) => render_variant(f, b, memory_map, s.into(), &layout, subst),
+                AdtId::UnionId(u) => write!(f, "{}", f.db.union_data(u).name),
+                AdtId::EnumId(e) => {
+                    let krate = adt.0.module(f.db.upcast()).krate();
+                    let Some(local_id) = detect_variant(f.db, krate, e, &layout, b) else {
+                        return f.write_str("<invalid-enum-variant>");
+                    };
+                    let variant_layout = match &layout.variants {
+                        Variants::Single { .. } => &layout,
+                        Variants::Multiple { variants, .. } => {
+                            &variants[RustcEnumVariantIdx(local_id)]
+                        }
+                    };
+                    let variant = EnumVariantId { parent: e, local_id };
+                    render_variant(f, b, memory_map, variant.into(), variant_layout, subst)
+                }
+            }
+        }
+        TyKind::FnDef(..) => ty.hir_fmt(f),
+        _ => f.write_str("<not-supported>"),
+    }
+}
+
+/// Renders `b` as `[a, b, ..]`, a list of elements of type `ty`.
+fn render_elements(
+    f: &mut HirFormatter<'_>,
+    b: &[u8],
+    memory_map: &MemoryMap,
+    ty: &Ty,
+) -> Result<(), HirDisplayError> {
+    let Ok(layout) = layout_of_ty(f.db, ty, any_krate(f)) else {
+        return f.write_str("<layout-error>");
+    };
+    let size = layout.size.bytes_usize();
+    f.write_str("[")?;
+    if size == 0 {
+        // The number of zero sized elements isn't known here.
+        return f.write_str("..]");
+    }
+    for (i, element) in b.chunks(size).enumerate() {
+        if i != 0 {
+            f.write_str(", ")?;
+        }
+        render_const_scalar(f, element, memory_map, ty)?;
+    }
+    f.write_str("]")
+}
+
+/// Renders the fields of a struct or an enum variant, stored in `b` with the layout `layout`.
+fn render_variant(
+    f: &mut HirFormatter<'_>,
+    b: &[u8],
+    memory_map: &MemoryMap,
+    variant: VariantId,
+    layout: &Layout,
+    subst: &Substitution,
+) -> Result<(), HirDisplayError> {
+    let (name, variant_data) = match variant {
+        VariantId::StructId(s) => {
+            let data = f.db.struct_data(s);
+            (data.name.clone(), data.variant_data.clone())
+        }
+        VariantId::EnumVariantId(v) => {
+            let data = f.db.enum_data(v.parent);
+            let variant = &data.variants[v.local_id];
+            (variant.name.clone(), variant.variant_data.clone())
+        }
+        VariantId::UnionId(u) => return write!(f, "{}", f.db.union_data(u).name),
+    };
+    let fields = match &*variant_data {
+        VariantData::Record(fields) | VariantData::Tuple(fields) => fields,
+        VariantData::Unit => return write!(f, "{name}"),
+    };
+    let field_types = f.db.field_types(variant);
+    let krate = variant.adt_id().module(f.db.upcast()).krate();
+    let is_record = matches!(&*variant_data, VariantData::Record(_));
+    write!(f, "{name}{}", if is_record { " {" } else { "(" })?;
+    for (i, (id, data)) in fields.iter().enumerate() {
+        match (i, is_record) {
+            (0, true) => write!(f, " {}: ", data.name)?,
+            (_, true) => write!(f, ", {}: ", data.name)?,
+            (0, false) => {}
+            (_, false) => f.write_str(", ")?,
+        }
+        let offset = layout.fields.offset(u32::from(id.into_raw()) as usize).bytes_usize();
+        let ty = field_types[id].clone().substitute(Interner, subst);
+        let Ok(layout) = layout_of_ty(f.db, &ty, krate) else {
+            f.write_str("<layout-error>")?;
+            continue;
+        };
+        let size = layout.size.bytes_usize();
+        render_const_scalar(f, &b[offset..offset + size], memory_map, &ty)?;
+    }
+    f.write_str(if is_record { " }" } else { ")" })
+}
+
+// FIXME: Remove this function. If the target data layout is independent of the krate, the
+// `db.target_data_layout` and its callers like `layout_of_ty` don't need to get krate. Otherwise, we
+// need to get krate from the final callers of the hir display infrastructure and have it here as a
+// field on `f`.
+fn any_krate(f: &HirFormatter<'_>) -> CrateId {
+    *f.db.crate_graph().crates_in_topological_order().last().unwrap()
+}
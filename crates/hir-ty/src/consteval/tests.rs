@@ -1,14 +1,18 @@
-use base_db::fixture::WithFixture;
+use base_db::{fixture::WithFixture, FileId};
 use chalk_ir::Substitution;
-use hir_def::db::DefDatabase;
+use hir_def::{db::DefDatabase, ConstId, DefWithBodyId};
 
 use crate::{
-    consteval::try_const_usize, db::HirDatabase, mir::pad16, test_db::TestDB, Const, ConstScalar,
-    Interner,
+    consteval::try_const_usize, db::HirDatabase, display::HirDisplay, mir::pad16, test_db::TestDB,
+    Const, ConstScalar, Interner,
 };
 
 use super::{
-    super::mir::{MirEvalError, MirLowerError},
+    super::mir::{
+        interpret_mir_with_external_values, interpret_mir_with_global_allocator,
+        interpret_mir_with_profile, BuildProfile, ExternalCall, ExternalValueProvider,
+        MirEvalError, MirLowerError, DEFAULT_SEED,
+    },
     ConstEvalError,
 };
 
@@ -47,25 +51,40 @@ fn check_number(ra_fixture: &str, answer: i128) {
     }
 }
 
+#[track_caller]
+fn check_render(ra_fixture: &str, expected: &str) {
+    let (db, files) = TestDB::with_many_files(ra_fixture);
+    let r = db.const_eval(goal_const(&db, &files), Substitution::empty(Interner)).unwrap();
+    assert_eq!(r.display(&db).to_string(), expected);
+}
+
+/// Evaluates the `GOAL` const of the fixture. With several crates, like
+/// `//- /lib.rs crate:dep` and `//- /main.rs crate:main deps:dep`, it can be in any of them.
 fn eval_goal(ra_fixture: &str) -> Result<Const, ConstEvalError> {
-    let (db, file_id) = TestDB::with_single_file(ra_fixture);
-    let module_id = db.module_for_file(file_id);
-    let def_map = module_id.def_map(&db);
-    let scope = &def_map[module_id.local_id].scope;
-    let const_id = scope
-        .declarations()
-        .find_map(|x| match x {
-            hir_def::ModuleDefId::ConstId(x) => {
-                if db.const_data(x).name.as_ref()?.to_string() == "GOAL" {
-                    Some(x)
-                } else {
-                    None
+    let (db, files) = TestDB::with_many_files(ra_fixture);
+    db.const_eval(goal_const(&db, &files), Substitution::empty(Interner))
+}
+
+/// Finds the `GOAL` const in the first of `files` which declares one.
+fn goal_const(db: &TestDB, files: &[FileId]) -> ConstId {
+    files
+        .iter()
+        .find_map(|&file_id| {
+            let module_id = db.module_for_file_opt(file_id)?;
+            let def_map = module_id.def_map(db);
+            let scope = &def_map[module_id.local_id].scope;
+            scope.declarations().find_map(|x| match x {
+                hir_def::ModuleDefId::ConstId(x) => {
+                    if db.const_data(x).name.as_ref()?.to_string() == "GOAL" {
+                        Some(x)
+                    } else {
+                        None
+                    }
                 }
-            }
-            _ => None,
+                _ => None,
+            })
         })
-        .unwrap();
-    db.const_eval(const_id, Substitution::empty(Interner))
+        .expect("no `GOAL` const in the fixture")
 }
 
 #[test]
@@ -371,6 +390,34 @@ fn trait_method() {
     );
 }
 
+#[test]
+fn generic_associated_types() {
+    check_number(
+        r#"
+    trait Container {
+        type Of<T>;
+        fn wrap<T>(x: T) -> Self::Of<T>;
+    }
+
+    struct Wrapper<T>(T);
+    struct Wrapping;
+
+    impl Container for Wrapping {
+        type Of<T> = Wrapper<T>;
+        fn wrap<T>(x: T) -> Self::Of<T> {
+            Wrapper(x)
+        }
+    }
+
+    const GOAL: i32 = {
+        let w: <Wrapping as Container>::Of<i32> = <Wrapping as Container>::wrap(5);
+        w.0
+    };
+    "#,
+        5,
+    );
+}
+
 #[test]
 fn generic_fn() {
     check_number(
@@ -725,6 +772,39 @@ fn unions() {
         "#,
         0x0123ABCD * 2 + 0x0123DCBA,
     );
+    check_number(
+        r#"
+        union U {
+            f1: u64,
+            f2: (u8, u8),
+        }
+
+        const GOAL: u64 = {
+            let mut p = U { f1: 0x0123_4567_89AB_CDEF };
+            unsafe {
+                p.f2.0 = 0x10;
+                p.f1
+            }
+        };
+        "#,
+        0x0123_4567_89AB_CD10,
+    );
+    check_fail(
+        r#"
+        union U {
+            f1: u8,
+            f2: u32,
+        }
+
+        const GOAL: u32 = {
+            let p = U { f1: 5 };
+            unsafe { p.f2 }
+        };
+        "#,
+        ConstEvalError::MirEvalError(MirEvalError::UndefinedBehavior(
+            "read of uninitialized bytes of a union",
+        )),
+    );
 }
 
 #[test]
@@ -834,6 +914,61 @@ fn pattern_matching_literal() {
     );
 }
 
+#[test]
+fn pattern_matching_range() {
+    check_number(
+        r#"
+    const fn classify(b: u8) -> u32 {
+        match b {
+            b'0'..=b'9' => 1,
+            b'a'..=b'z' | b'A'..=b'Z' => 2,
+            0..32 => 3,
+            _ => 4,
+        }
+    }
+    const GOAL: u32 = classify(b'5') + classify(b'q') * 10 + classify(b'\n') * 100 + classify(b'~');
+        "#,
+        325,
+    );
+    check_number(
+        r#"
+    const LOW: i32 = -1000;
+    const fn sign(x: i32) -> i32 {
+        match x {
+            LOW..=-1 => -1,
+            0 => 0,
+            1.. => 1,
+            _ => 5,
+        }
+    }
+    const GOAL: i32 = sign(-5) * 100 + sign(0) * 10 + sign(7) + sign(-2000) * 1000;
+        "#,
+        4901,
+    );
+    check_number(
+        r#"
+    const fn kind(c: char) -> u32 {
+        match c {
+            'a'..='z' => 1,
+            '0'..'9' => 2,
+            _ => 3,
+        }
+    }
+    const GOAL: u32 = kind('m') + kind('0') * 10 + kind('9') * 100;
+        "#,
+        321,
+    );
+    check_number(
+        r#"
+    const GOAL: u8 = match 340282366920938463463374607431768211455u128 {
+        0..=100 => 1,
+        101.. => 2,
+    };
+        "#,
+        2,
+    );
+}
+
 #[test]
 fn pattern_matching_ergonomics() {
     check_number(
@@ -967,6 +1102,77 @@ fn match_guards() {
     );
 }
 
+#[test]
+fn derived_comparisons() {
+    check_number(
+        r#"
+    //- minicore: derive, eq, option
+    #[derive(PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+    #[derive(PartialEq)]
+    enum Shape {
+        Dot(Point),
+        Line { from: Point, to: Point },
+        Empty,
+    }
+    fn f(s: Shape) -> i32 {
+        let line = Shape::Line { from: Point { x: 0, y: 0 }, to: Point { x: 1, y: 1 } };
+        match s {
+            s if s == Shape::Empty => 1,
+            s if s == Shape::Dot(Point { x: 1, y: 2 }) => 10,
+            s if s != line => 100,
+            _ => 1000,
+        }
+    }
+    const GOAL: i32 = f(Shape::Empty)
+        + f(Shape::Dot(Point { x: 1, y: 2 }))
+        + f(Shape::Dot(Point { x: 2, y: 2 }))
+        + f(Shape::Line { from: Point { x: 0, y: 0 }, to: Point { x: 1, y: 1 } });
+        "#,
+        1111,
+    );
+    check_number(
+        r#"
+    //- minicore: derive, ord, option
+    use core::cmp::Ordering;
+    impl PartialOrd for i32 {
+        fn partial_cmp(&self, other: &i32) -> Option<Ordering> {
+            Some(if *self < *other {
+                Ordering::Less
+            } else if *self == *other {
+                Ordering::Equal
+            } else {
+                Ordering::Greater
+            })
+        }
+    }
+    #[derive(PartialEq, PartialOrd)]
+    struct Point(i32, i32);
+    #[derive(PartialEq, PartialOrd)]
+    enum Shape {
+        Dot(Point),
+        Empty,
+    }
+    fn cmp(a: Shape, b: Shape) -> i32 {
+        match a.partial_cmp(&b) {
+            Some(Ordering::Less) => 1,
+            Some(Ordering::Equal) => 2,
+            Some(Ordering::Greater) => 3,
+            None => 4,
+        }
+    }
+    const GOAL: i32 = cmp(Shape::Dot(Point(1, 5)), Shape::Dot(Point(2, 0)))
+        + 10 * cmp(Shape::Dot(Point(1, 5)), Shape::Dot(Point(1, 5)))
+        + 100 * cmp(Shape::Empty, Shape::Dot(Point(1, 5)))
+        + 1000 * cmp(Shape::Dot(Point(1, 5)), Shape::Dot(Point(1, 4)));
+        "#,
+        3321,
+    );
+}
+
 #[test]
 fn options() {
     check_number(
@@ -1020,6 +1226,186 @@ fn options() {
     );
 }
 
+#[test]
+fn let_chains_and_short_circuits() {
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const fn first_positive(v: &[i32]) -> i32 {
+        let mut i = 0;
+        while i < v.len() && v[i] <= 0 {
+            i = i + 1;
+        }
+        if i >= v.len() || v[i] <= 0 {
+            -1
+        } else {
+            v[i]
+        }
+    }
+    const GOAL: i32 = first_positive(&[-1, 0, 5, 2]) * 10 + first_positive(&[-3]);
+        "#,
+        49,
+    );
+    check_number(
+        r#"
+    //- minicore: option
+    const GOAL: i32 = {
+        let values = [Some(1), Some(2), None, Some(4)];
+        let mut i = 0;
+        let mut sum = 0;
+        while let Some(x) = values[i] && x < 3 {
+            sum = sum + x;
+            i = i + 1;
+        }
+        sum
+    };
+        "#,
+        3,
+    );
+    check_number(
+        r#"
+    //- minicore: option
+    fn f(x: Option<Option<i32>>, y: bool) -> i32 {
+        if let Some(x) = x && let Some(x) = x && (y || x > 5) {
+            x
+        } else if let Some(None) = x {
+            -1
+        } else {
+            0
+        }
+    }
+    const GOAL: i32 = f(Some(Some(10)), false)
+        + f(Some(Some(2)), true) * 100
+        + f(Some(Some(2)), false)
+        + f(Some(None), true) * 1000
+        + f(None, true);
+        "#,
+        -790,
+    );
+}
+
+#[test]
+fn niche_encoded_enums() {
+    check_number(
+        r#"
+    //- minicore: option
+    const GOAL: Option<Option<bool>> = None;
+        "#,
+        3,
+    );
+    check_number(
+        r#"
+    //- minicore: option
+    const GOAL: Option<Option<bool>> = Some(None);
+        "#,
+        2,
+    );
+    check_number(
+        r#"
+    //- minicore: option, non_zero
+    use core::num::NonZeroU8;
+    const GOAL: Option<NonZeroU8> = NonZeroU8::new(0);
+        "#,
+        0,
+    );
+    check_number(
+        r#"
+    //- minicore: option
+    enum E { A(bool), B, C }
+    const GOAL: i32 = {
+        let values = [E::C, E::A(true), E::B];
+        let mut result = 0;
+        let mut i = 0;
+        while i < 3 {
+            result = result * 10 + match values[i] {
+                E::A(true) => 1,
+                E::A(false) => 2,
+                E::B => 3,
+                E::C => 4,
+            };
+            i = i + 1;
+        }
+        result
+    };
+        "#,
+        413,
+    );
+}
+
+#[test]
+fn non_zero_and_unwrap() {
+    check_number(
+        r#"
+    //- minicore: option, non_zero
+    use core::num::NonZeroU8;
+    const GOAL: u8 = NonZeroU8::new(5).unwrap().get();
+        "#,
+        5,
+    );
+    check_number(
+        r#"
+    //- minicore: option, non_zero
+    use core::num::NonZeroU8;
+    extern "rust-intrinsic" {
+        pub fn size_of<T>() -> usize;
+    }
+    const GOAL: usize = {
+        let none = NonZeroU8::new(0);
+        let some = NonZeroU8::new(7);
+        let size = size_of::<Option<NonZeroU8>>();
+        match (none, some) {
+            (None, Some(x)) => size * 10 + x.get() as usize,
+            _ => 0,
+        }
+    };
+        "#,
+        17,
+    );
+    check_number(
+        r#"
+    //- minicore: result, panic
+    const GOAL: i32 = {
+        let x: Result<i32, u8> = Ok(4);
+        x.unwrap()
+    };
+        "#,
+        4,
+    );
+}
+
+#[test]
+fn panic_messages() {
+    check_fail(
+        r#"
+    //- minicore: option, non_zero, fmt
+    use core::num::NonZeroU8;
+    const GOAL: u8 = NonZeroU8::new(0).unwrap().get();
+        "#,
+        ConstEvalError::MirEvalError(MirEvalError::Panic(
+            "called `Option::unwrap()` on a `None` value".to_string(),
+        )),
+    );
+    check_fail(
+        r#"
+    //- minicore: result, panic, fmt
+    const GOAL: i32 = {
+        let x: Result<i32, u8> = Err(2);
+        x.unwrap()
+    };
+        "#,
+        ConstEvalError::MirEvalError(MirEvalError::Panic(
+            "called `Result::unwrap()` on an `Err` value".to_string(),
+        )),
+    );
+    check_fail(
+        r#"
+    //- minicore: panic, fmt
+    const GOAL: u8 = panic!("custom message");
+        "#,
+        ConstEvalError::MirEvalError(MirEvalError::Panic("custom message".to_string())),
+    );
+}
+
 #[test]
 fn from_trait() {
     check_number(
@@ -1180,6 +1566,88 @@ fn closures() {
     );
 }
 
+#[test]
+fn async_blocks() {
+    // We accept `.await` outside of async contexts, which lets the goal poll futures directly.
+    check_number(
+        r#"
+    //- minicore: future, copy
+    async fn add(x: i32, y: i32) -> i32 {
+        x + y
+    }
+    const GOAL: i32 = {
+        let y = 5;
+        let f = async move {
+            let x = async { 2 }.await;
+            add(x, y).await * 2
+        };
+        f.await
+    };
+        "#,
+        14,
+    );
+    check_number(
+        r#"
+    //- minicore: future, copy
+    const GOAL: i32 = {
+        let mut y = 5;
+        async {
+            y = y + 3;
+        }
+        .await;
+        y
+    };
+        "#,
+        8,
+    );
+    check_number(
+        r#"
+    //- minicore: future, copy
+    async fn double(x: i32) -> i32 {
+        x * 2
+    }
+    async fn sum(x: i32) -> i32 {
+        let a = double(x).await;
+        a + double(a).await
+    }
+    async fn outer<T>(x: i32) -> i32 {
+        sum(x).await + sum(x + 1).await
+    }
+    const GOAL: i32 = outer::<u8>(1).await;
+        "#,
+        18,
+    );
+}
+
+#[test]
+fn const_blocks() {
+    check_number(
+        r#"
+    const fn square(x: i32) -> i32 {
+        x * x
+    }
+    const GOAL: i32 = {
+        let x = const { square(3) };
+        let y = const {
+            let a = 2;
+            a + 1
+        };
+        x + y
+    };
+        "#,
+        12,
+    );
+    check_number(
+        r#"
+    const GOAL: u8 = match 5 {
+        const { 2 + 3 } => 1,
+        _ => 0,
+    };
+        "#,
+        1,
+    );
+}
+
 #[test]
 fn or_pattern() {
     check_number(
@@ -1433,21 +1901,160 @@ fn dyn_trait() {
 }
 
 #[test]
-fn array_and_index() {
+fn dyn_trait_upcasting() {
     check_number(
         r#"
-    //- minicore: coerce_unsized, index, slice
+    //- minicore: coerce_unsized
+    trait Foo {
+        fn foo(&self) -> u8;
+    }
+    trait Bar: Foo {
+        fn bar(&self) -> u8;
+    }
+    struct S(u8);
+    impl Foo for S {
+        fn foo(&self) -> u8 { self.0 }
+    }
+    impl Bar for S {
+        fn bar(&self) -> u8 { 2 * self.0 }
+    }
     const GOAL: u8 = {
-        let a = [10, 20, 3, 15];
-        let x: &[u8] = &a;
-        x[1]
+        let x: &dyn Bar = &S(5);
+        let y = x as &dyn Foo;
+        let z: &dyn Foo = x;
+        x.bar() + x.foo() + y.foo() + z.foo()
     };
         "#,
-        20,
+        25,
     );
+}
+
+#[test]
+fn dyn_trait_behind_custom_pointer() {
     check_number(
         r#"
-    //- minicore: coerce_unsized, index, slice
+    //- minicore: coerce_unsized, deref
+    use core::{marker::Unsize, ops::{CoerceUnsized, Deref}};
+
+    trait Foo {
+        fn foo(&self) -> u8;
+    }
+    struct S1;
+    struct S2(u8);
+    impl Foo for S1 {
+        fn foo(&self) -> u8 { 1 }
+    }
+    impl Foo for S2 {
+        fn foo(&self) -> u8 { self.0 }
+    }
+
+    struct NonNull<T: ?Sized> {
+        pointer: *const T,
+    }
+    impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<NonNull<U>> for NonNull<T> {}
+
+    struct Rc<T: ?Sized> {
+        tag: u8,
+        ptr: NonNull<T>,
+    }
+    impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Rc<U>> for Rc<T> {}
+    impl<T: ?Sized> Deref for Rc<T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            unsafe { &*self.ptr.pointer }
+        }
+    }
+
+    fn rc<T>(value: &T) -> Rc<T> {
+        Rc { tag: 5, ptr: NonNull { pointer: value } }
+    }
+
+    const GOAL: u8 = {
+        let x = rc(&S1);
+        let x: Rc<dyn Foo> = x;
+        let y = rc(&S2(20));
+        let y: Rc<dyn Foo> = y;
+        x.foo() + y.foo() + x.tag
+    };
+        "#,
+        26,
+    );
+}
+
+#[test]
+fn function_pointer_to_method() {
+    check_number(
+        r#"
+    //- minicore: coerce_unsized
+    trait Foo {
+        fn foo(&self) -> u8;
+    }
+    struct S1;
+    struct S2;
+    impl Foo for S1 {
+        fn foo(&self) -> u8 { 1 }
+    }
+    impl Foo for S2 {
+        fn foo(&self) -> u8 { 2 }
+    }
+    impl S2 {
+        fn inherent(&self) -> u8 { 3 }
+    }
+    const GOAL: u8 = {
+        let f: fn(&dyn Foo) -> u8 = <dyn Foo as Foo>::foo;
+        let g: fn(&S2) -> u8 = <S2 as Foo>::foo;
+        let h: fn(&S2) -> u8 = S2::inherent;
+        f(&S1) + f(&S2) * 10 + g(&S2) * 20 + h(&S2) * 30
+    };
+        "#,
+        151,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized
+    trait Foo {
+        fn foo(&self) -> u8;
+    }
+    struct S1;
+    struct S2;
+    impl Foo for S1 {
+        fn foo(&self) -> u8 { 1 }
+    }
+    impl Foo for S2 {
+        fn foo(&self) -> u8 { 2 }
+    }
+    fn foo_of<T: Foo>() -> fn(&T) -> u8 {
+        T::foo
+    }
+    fn as_dyn<T: Foo>(x: &T) -> &dyn Foo {
+        x
+    }
+    const GOAL: u8 = {
+        let f = foo_of::<S2>();
+        let g: fn(&dyn Foo) -> u8 = <dyn Foo as Foo>::foo;
+        f(&S2) + g(as_dyn(&S1)) * 10
+    };
+        "#,
+        12,
+    );
+}
+
+#[test]
+fn array_and_index() {
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const GOAL: u8 = {
+        let a = [10, 20, 3, 15];
+        let x: &[u8] = &a;
+        x[1]
+    };
+        "#,
+        20,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
     const GOAL: usize = [1, 2, 3][2];"#,
         3,
     );
@@ -1476,6 +2083,144 @@ fn array_and_index() {
     );
 }
 
+#[test]
+fn array_repeat() {
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const GOAL: u8 = {
+        let a = [5u8; 10_000_000];
+        a[9_999_999]
+    };
+        "#,
+        5,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const GOAL: u16 = {
+        let a = [258u16; 10];
+        a[3]
+    };
+        "#,
+        258,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const GOAL: u8 = {
+        let mut a = [0u8; 1_000_000];
+        a[500_000] = 3;
+        let b = a;
+        b[499_999] + b[500_000] + b[500_001]
+    };
+        "#,
+        3,
+    );
+}
+
+#[test]
+fn slice_patterns() {
+    check_number(
+        r#"
+    const GOAL: u8 = {
+        let arr = [1, 2, 3, 4, 5];
+        let [first, .., last] = arr;
+        first + last
+    };
+        "#,
+        6,
+    );
+    check_number(
+        r#"
+    const GOAL: i32 = {
+        let [a, mid @ .., b] = [1, 2, 3, 4];
+        let [c, d] = mid;
+        a + b * 10 + c * 100 + d * 1000
+    };
+        "#,
+        3241,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const fn f(s: &[u8]) -> u8 {
+        match s {
+            [] => 0,
+            [a] => *a,
+            [a, b, rest @ ..] => *a + *b + f(rest),
+        }
+    }
+    const GOAL: u8 = f(&[1, 2, 3, 4, 5]) + f(&[10]) * 2;
+        "#,
+        35,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const fn f(s: &[i32]) -> i32 {
+        match s {
+            [.., x, y] if *x > *y => 1,
+            [first, .., last] => *first - *last,
+            _ => 100,
+        }
+    }
+    const GOAL: i32 = f(&[1, 5, 2]) + f(&[3, 4, 9]) + f(&[7]);
+        "#,
+        95,
+    );
+}
+
+#[test]
+fn target_pointer_width_and_endianness() {
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    //- /main.rs target_data_layout:e-m:e-p:32:32-i64:64-n32-S128
+    const GOAL: usize = {
+        let a = [1, 2, 3];
+        let x: &[i32] = &a;
+        x.len() + x[2] as usize + 12 as *const i32 as usize
+    };"#,
+        18,
+    );
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    //- /main.rs target_data_layout:E-m:e-p:32:32-i64:64-n32-S128
+    const GOAL: usize = {
+        let a = [1, 2, 3];
+        let x: &[i32] = &a;
+        x.len() + x[2] as usize + 12 as *const i32 as usize
+    };"#,
+        18,
+    );
+    check_number(
+        r#"
+    //- /main.rs target_data_layout:E-m:e-p:32:32-i64:64-n32-S128
+    const A: &u16 = &0x1234;
+    const GOAL: u32 = {
+        let b = -(*A as i32);
+        (-b as u32) * 0x100 + 0x56
+    };"#,
+        0x123456,
+    );
+    check_number(
+        r#"
+    //- minicore: index, slice
+    //- /main.rs target_data_layout:E-m:e-p:32:32-i64:64-n32-S128
+    extern "rust-intrinsic" {
+        pub fn transmute<T, U>(e: T) -> U;
+    }
+
+    const GOAL: u8 = {
+        let x: [u8; 4] = unsafe { transmute(0x01020304u32) };
+        x[0]
+    };"#,
+        1,
+    );
+}
+
 #[test]
 fn byte_string() {
     check_number(
@@ -1491,6 +2236,37 @@ fn byte_string() {
     );
 }
 
+#[test]
+fn include_bytes_and_env() {
+    check_number(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    //- /main.rs
+    #[rustc_builtin_macro]
+    macro_rules! include_bytes {() => {}}
+
+    const TABLE: &[u8] = include_bytes!("table.bin");
+    const GOAL: u32 = TABLE[1] as u32 * 1000000 + TABLE[2] as u32 * 1000 + TABLE[3] as u32;
+    //- /table.bin
+    a"\é
+    "#,
+        34_092_195,
+    );
+    check_number(
+        r#"
+    //- /main.rs env:TABLE_NAME=lookup
+    #[rustc_builtin_macro]
+    macro_rules! env {() => {}}
+    extern "rust-intrinsic" {
+        pub fn transmute<T, U>(e: T) -> U;
+    }
+
+    const GOAL: usize = unsafe { transmute::<&str, (usize, usize)>(env!("TABLE_NAME")).1 };
+    "#,
+        6,
+    );
+}
+
 #[test]
 fn consts() {
     check_number(
@@ -1607,10 +2383,47 @@ fn const_generic_subst_assoc_const_impl() {
     );
 }
 
+#[test]
+fn const_generic_subst_self_in_impl() {
+    check_number(
+        r#"
+    extern "rust-intrinsic" {
+        pub fn size_of<T>() -> usize;
+    }
+    struct Buf<T, const N: usize>([T; N]);
+    impl<T, const N: usize> Buf<T, N> {
+        const LEN: usize = N * 2;
+        const SIZE: usize = size_of::<Self>();
+        fn total<U>(_: U) -> usize {
+            Self::LEN + Self::SIZE + size_of::<Self>()
+        }
+    }
+    const GOAL: usize = Buf::<u16, 3>::total(0u8);
+    "#,
+        18,
+    );
+    check_number(
+        r#"
+    extern "rust-intrinsic" {
+        pub fn size_of<T>() -> usize;
+    }
+    trait Layout {
+        const SIZE: usize = size_of::<Self>();
+    }
+    struct Pair<T>(T, T);
+    impl<T> Layout for Pair<T> {}
+    const fn size<T: Layout>() -> usize {
+        T::SIZE
+    }
+    const GOAL: usize = size::<Pair<u32>>() + <Pair<u8> as Layout>::SIZE;
+    "#,
+        10,
+    );
+}
+
 #[test]
 fn const_trait_assoc() {
-    // FIXME: this should evaluate to 0
-    check_fail(
+    check_number(
         r#"
     struct U0;
     trait ToConst {
@@ -1621,18 +2434,642 @@ fn const_trait_assoc() {
     }
     const GOAL: usize = U0::VAL;
     "#,
-        ConstEvalError::MirLowerError(MirLowerError::IncompleteExpr),
+        0,
+    );
+    check_number(
+        r#"
+    struct U5;
+    trait ToConst {
+        const VAL: usize;
+    }
+    impl ToConst for U5 {
+        const VAL: usize = 5;
+    }
+    const fn to_const<T: ToConst>() -> usize {
+        T::VAL + 1
+    }
+    const GOAL: usize = to_const::<U5>();
+    "#,
+        6,
     );
 }
 
 #[test]
-fn exec_limits() {
+fn const_trait_impl() {
+    check_number(
+        r#"
+    trait ToConst {
+        const VAL: usize;
+        fn double(&self) -> usize;
+    }
+    struct U5;
+    impl const ToConst for U5 {
+        const VAL: usize = 5;
+        fn double(&self) -> usize {
+            Self::VAL * 2
+        }
+    }
+    const fn f<T: ~const ToConst>(x: T) -> usize {
+        T::VAL + x.double()
+    }
+    const GOAL: usize = f(U5);
+    "#,
+        15,
+    );
+}
+
+#[test]
+fn offset_of() {
+    check_number(
+        r#"
+    //- minicore: offset_of
+    #[repr(C)]
+    struct Inner {
+        x: u8,
+        y: (u32, u32),
+    }
+    #[repr(C)]
+    struct S {
+        a: u8,
+        b: u32,
+        inner: Inner,
+    }
+    const GOAL: usize = core::mem::offset_of!(S, b) * 100 + core::mem::offset_of!(S, inner.y.1);
+    "#,
+        416,
+    );
+    check_number(
+        r#"
+    //- minicore: offset_of
+    #[repr(C)]
+    union U {
+        a: u8,
+        b: u64,
+    }
+    #[repr(C)]
+    struct S {
+        a: u8,
+        u: U,
+    }
+    const GOAL: usize = core::mem::offset_of!(U, b) + core::mem::offset_of!(S, u.b);
+    "#,
+        8,
+    );
+}
+
+#[test]
+fn strict_provenance() {
+    check_number(
+        r#"
+    //- minicore: ptr
+    const GOAL: i32 = {
+        let x = 5;
+        let p = &x as *const i32;
+        unsafe { *p.with_addr(p.addr()) }
+    };
+    "#,
+        5,
+    );
+    check_number(
+        r#"
+    //- minicore: ptr, fn
+    const GOAL: u16 = {
+        let x = 0x1234u16;
+        let tagged = (&x as *const u16).map_addr(|a| a | 1);
+        let tag = tagged.addr() & 1;
+        let p = tagged.map_addr(|a| a & !1);
+        unsafe { *p + tag as u16 }
+    };
+    "#,
+        0x1235,
+    );
+    check_fail(
+        r#"
+    //- minicore: ptr
+    const GOAL: i32 = {
+        let x = 5;
+        let p: *const i32 = core::ptr::invalid((&x as *const i32).addr());
+        unsafe { *p }
+    };
+    "#,
+        ConstEvalError::MirEvalError(MirEvalError::UndefinedBehavior(
+            "dereference of a pointer without provenance",
+        )),
+    );
+}
+
+#[test]
+fn pointer_arithmetic() {
+    check_number(
+        r#"
+    //- minicore: ptr
+    const GOAL: u8 = {
+        let arr = [1u8, 2, 3, 4, 5];
+        let start = &arr as *const [u8; 5] as *const u8;
+        let end = unsafe { start.add(5) };
+        let mut p = start;
+        let mut sum = 0;
+        while p < end {
+            sum = sum + unsafe { *p };
+            p = unsafe { p.offset(1) };
+        }
+        sum + unsafe { *end.sub(2) + end.offset_from(start) as u8 }
+    };
+    "#,
+        24,
+    );
+    check_number(
+        r#"
+    //- minicore: ptr
+    const GOAL: u16 = {
+        let arr = [1u16, 2, 3, 4];
+        let p = &arr as *const [u16; 4] as *const u16;
+        let far = p.wrapping_add(100).wrapping_sub(98);
+        let third = unsafe { p.byte_add(4) };
+        unsafe { *far * 10 + *third }
+    };
+    "#,
+        33,
+    );
+    check_number(
+        r#"
+    //- minicore: ptr
+    const GOAL: isize = {
+        let arr = [1u32, 2, 3, 4];
+        let p = &arr as *const [u32; 4] as *const u32;
+        let q = unsafe { p.add(3) };
+        unsafe { p.offset_from(q) * 10 + (p <= q) as isize + (p > q) as isize }
+    };
+    "#,
+        -29,
+    );
     check_fail(
         r#"
-    const GOAL: usize = loop {};
+    //- minicore: ptr
+    const GOAL: isize = {
+        let arr = [1u32, 2];
+        let p = &arr as *const [u32; 2] as *const u32;
+        let q = unsafe { p.byte_add(2) };
+        unsafe { q.offset_from(p) }
+    };
+    "#,
+        ConstEvalError::MirEvalError(MirEvalError::UndefinedBehavior(
+            "ptr_offset_from with a distance which isn't a multiple of the type size",
+        )),
+    );
+}
+
+#[test]
+fn global_allocator() {
+    check_number(
+        r#"
+    //- minicore: ptr
+    extern "Rust" {
+        fn __rust_alloc(size: usize, align: usize) -> *mut u8;
+        fn __rust_realloc(ptr: *mut u8, old_size: usize, align: usize, new_size: usize) -> *mut u8;
+        fn __rust_dealloc(ptr: *mut u8, size: usize, align: usize);
+    }
+
+    struct Layout {
+        size: usize,
+        align: usize,
+    }
+
+    unsafe trait GlobalAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8;
+    }
+
+    struct Global;
+
+    unsafe impl GlobalAlloc for Global {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            __rust_alloc(layout.size, layout.align)
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            __rust_dealloc(ptr, layout.size, layout.align)
+        }
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            __rust_realloc(ptr, layout.size, layout.align, new_size)
+        }
+    }
+
+    struct Vec<A: GlobalAlloc> {
+        ptr: *mut i32,
+        cap: usize,
+        len: usize,
+        alloc: A,
+    }
+
+    impl<A: GlobalAlloc> Vec<A> {
+        fn with_capacity_in(cap: usize, alloc: A) -> Self {
+            let ptr = unsafe { alloc.alloc(Layout { size: cap * 4, align: 4 }) } as *mut i32;
+            Vec { ptr, cap, len: 0, alloc }
+        }
+
+        fn push(&mut self, x: i32) {
+            if self.len == self.cap {
+                let layout = Layout { size: self.cap * 4, align: 4 };
+                let ptr = unsafe { self.alloc.realloc(self.ptr as *mut u8, layout, self.cap * 8) };
+                self.ptr = ptr as *mut i32;
+                self.cap = self.cap * 2;
+            }
+            unsafe { *self.ptr.add(self.len) = x };
+            self.len = self.len + 1;
+        }
+
+        fn sum(&self) -> i32 {
+            let mut sum = 0;
+            let mut i = 0;
+            while i < self.len {
+                sum = sum + unsafe { *self.ptr.add(i) };
+                i = i + 1;
+            }
+            sum
+        }
+
+        fn free(self) {
+            let layout = Layout { size: self.cap * 4, align: 4 };
+            unsafe { self.alloc.dealloc(self.ptr as *mut u8, layout) };
+        }
+    }
+
+    const GOAL: i32 = {
+        let mut v = Vec::with_capacity_in(1, Global);
+        let mut i = 1;
+        while i <= 10 {
+            v.push(i);
+            i = i + 1;
+        }
+        let sum = v.sum();
+        v.free();
+        sum
+    };
+    "#,
+        55,
+    );
+    check_number(
+        r#"
+    extern "Rust" {
+        fn __rust_alloc(size: usize, align: usize) -> *mut u8;
+    }
+
+    const GOAL: usize = unsafe {
+        let _ = __rust_alloc(3, 1);
+        let p = __rust_alloc(8, 64);
+        *p = 5;
+        p as usize % 64 + *p as usize
+    };
+    "#,
+        5,
+    );
+    check_fail(
+        r#"
+    extern "Rust" {
+        fn __rust_alloc(size: usize, align: usize) -> *mut u8;
+    }
+
+    const GOAL: u8 = unsafe { *__rust_alloc(1, 3) };
+    "#,
+        ConstEvalError::MirEvalError(MirEvalError::UndefinedBehavior(
+            "allocation with an invalid alignment",
+        )),
+    );
+}
+
+
+#[test]
+fn deterministic_entropy() {
+    check_number(
+        r#"
+    extern "C" {
+        fn getrandom(buf: *mut u8, buflen: usize, flags: u32) -> isize;
+    }
+    const GOAL: u16 = {
+        let mut buf = [0u8; 2];
+        let written = unsafe { getrandom(&mut buf as *mut [u8; 2] as *mut u8, 2, 0) };
+        if written == 2 { buf[0] as u16 | (buf[1] as u16) << 8 } else { 0 }
+    };
+    "#,
+        54263,
+    );
+}
+
+#[test]
+fn extern_types() {
+    check_number(
+        r#"
+    extern "rust-intrinsic" {
+        pub fn size_of<T>() -> usize;
+    }
+    extern "C" {
+        type Opaque;
+    }
+    struct Header(u8, Opaque);
+    struct Nested(u16, Header);
+    struct SliceTail(u8, (u16, [u8]));
+    const GOAL: usize = {
+        let x = 5u8;
+        let opaque = &x as *const u8 as *const Opaque;
+        let nested = opaque as *const Nested;
+        let value = unsafe { *(nested as *const u8) } as usize;
+        size_of::<*const Opaque>() + size_of::<&Nested>() + size_of::<&SliceTail>() + value
+    };
+    "#,
+        37,
+    );
+    let by_value = eval_goal(
+        r#"
+    extern "C" {
+        type Opaque;
+    }
+    const GOAL: u8 = {
+        let opaque = &5u8 as *const u8 as *const Opaque;
+        let value = unsafe { *opaque };
+        0
+    };
+    "#,
+    );
+    assert!(matches!(
+        by_value.map_err(simplify),
+        Err(ConstEvalError::MirLowerError(MirLowerError::ExternTypeByValue(_)))
+    ));
+}
+
+#[test]
+fn external_values() {
+    struct Registers;
+    impl ExternalValueProvider for Registers {
+        fn provides(&self, path: &str) -> bool {
+            matches!(path, "main::hw::read_register" | "main::Config::get")
+        }
+
+        fn call(&self, call: &ExternalCall) -> Result<Vec<u8>, String> {
+            let args: Vec<_> = call.args.iter().map(|it| (&*it.ty, &*it.bytes)).collect();
+            match (&*call.path, &*args, &*call.ret_ty) {
+                ("main::hw::read_register", [("u32", id)], "u32") => {
+                    let id = u32::from_le_bytes(<[u8; 4]>::try_from(*id).unwrap());
+                    Ok(id.pow(2).to_le_bytes().to_vec())
+                }
+                ("main::Config::get", [("&str", b"name")], "&[u8]") => Ok(b"device".to_vec()),
+                _ => Err(format!("unexpected call {call:?}")),
+            }
+        }
+    }
+
+    let (db, files) = TestDB::with_many_files(
+        r#"
+    //- minicore: slice
+    //- /main.rs crate:main
+    mod hw {
+        extern "C" {
+            pub fn read_register(id: u32) -> u32;
+        }
+    }
+    struct Config;
+    impl Config {
+        fn get(key: &str) -> &'static [u8] {
+            loop {}
+        }
+    }
+    const GOAL: usize = {
+        let register = unsafe { hw::read_register(3) } as usize;
+        register * 10 + Config::get("name").len()
+    };
+    "#,
+    );
+    let body = db.mir_body(goal_const(&db, &files).into()).unwrap();
+    let eval = |db: &TestDB, provider: &dyn ExternalValueProvider| {
+        let r = interpret_mir_with_external_values(
+            db,
+            &body,
+            Substitution::empty(Interner),
+            false,
+            DEFAULT_SEED,
+            provider,
+        );
+        r.map(|it| try_const_usize(&it))
+    };
+    assert_eq!(eval(&db, &Registers), Ok(Some(96)));
+
+    struct Failing;
+    impl ExternalValueProvider for Failing {
+        fn provides(&self, path: &str) -> bool {
+            path == "main::hw::read_register"
+        }
+
+        fn call(&self, _: &ExternalCall) -> Result<Vec<u8>, String> {
+            Err("no device".to_owned())
+        }
+    }
+    let e = eval(&db, &Failing).map_err(|e| simplify(ConstEvalError::MirEvalError(e)));
+    let Err(ConstEvalError::MirEvalError(MirEvalError::ExternalCallFailed(_, msg))) = e else {
+        panic!("expected the external call to fail, got {e:?}");
+    };
+    assert_eq!(msg, "no device");
+}
+
+#[test]
+fn global_allocator_routing() {
+    let (db, files) = TestDB::with_many_files(
+        r#"
+    //- /main.rs crate:main
+    extern "Rust" {
+        fn __rust_alloc(size: usize, align: usize) -> *mut u8;
+        fn __rust_dealloc(ptr: *mut u8, size: usize, align: usize);
+    }
+
+    struct Layout {
+        size: usize,
+        align: usize,
+    }
+
+    unsafe trait GlobalAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+    }
+
+    struct Limited {
+        limit: usize,
+    }
+
+    unsafe impl GlobalAlloc for Limited {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            if layout.size > self.limit {
+                return 0 as *mut u8;
+            }
+            layout.align as *mut u8
+        }
+        unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+    }
+
+    #[global_allocator]
+    static ALLOC: Limited = Limited { limit: 16 };
+
+    const GOAL: usize = unsafe {
+        let small = __rust_alloc(4, 4);
+        __rust_dealloc(small, 4, 4);
+        __rust_alloc(32, 8) as usize + small as usize * 10
+    };
+    "#,
+    );
+    let body = db.mir_body(goal_const(&db, &files).into()).unwrap();
+    let r = interpret_mir_with_global_allocator(
+        &db,
+        &body,
+        Substitution::empty(Interner),
+        false,
+        DEFAULT_SEED,
+    );
+    assert_eq!(r.map(|it| try_const_usize(&it)), Ok(Some(40)));
+}
+
+#[test]
+fn build_profiles() {
+    let (db, files) = TestDB::with_many_files(
+        r#"
+    //- /main.rs crate:main
+    fn add(a: u8, b: u8) -> u8 {
+        a + b
+    }
+    fn shift(x: u32, n: u32) -> u32 {
+        x << n
+    }
+    const GOAL: u32 = add(200, 100) as u32 + shift(1, 33) * 1000;
     "#,
-        ConstEvalError::MirEvalError(MirEvalError::ExecutionLimitExceeded),
     );
+    let body = db.mir_body(goal_const(&db, &files).into()).unwrap();
+    let eval = |profile| {
+        interpret_mir_with_profile(
+            &db,
+            &body,
+            Substitution::empty(Interner),
+            false,
+            DEFAULT_SEED,
+            profile,
+        )
+        .map(|it| try_const_usize(&it))
+        .map_err(|e| simplify(ConstEvalError::MirEvalError(e)))
+    };
+    let overflow = MirEvalError::Panic("Overflow in Add".to_string());
+    assert_eq!(eval(BuildProfile::Debug), Err(ConstEvalError::MirEvalError(overflow)));
+    // Without overflow checks, the sum wraps around and the shift amount is masked.
+    assert_eq!(eval(BuildProfile::Release), Ok(Some(2044)));
+}
+
+#[test]
+fn cross_crate() {
+    check_number(
+        r#"
+    //- /lib.rs crate:dep
+    pub const X: i32 = 2;
+    pub struct S(pub i32);
+    impl S {
+        pub const fn get(&self) -> i32 {
+            self.0 * X
+        }
+    }
+    pub const fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+    //- /main.rs crate:main deps:dep
+    use dep::S;
+    const GOAL: i32 = dep::add(dep::X, S(5).get());
+        "#,
+        12,
+    );
+    check_number(
+        r#"
+    //- /lib.rs crate:dep
+    pub trait Tr {
+        fn f(&self) -> i32;
+        fn twice(&self) -> i32 {
+            2 * self.f()
+        }
+    }
+    pub fn call<T: Tr>(t: T) -> i32 {
+        t.twice()
+    }
+    //- /main.rs crate:main deps:dep
+    struct L(i32);
+    impl dep::Tr for L {
+        fn f(&self) -> i32 {
+            self.0 + 1
+        }
+    }
+    const GOAL: i32 = dep::call(L(3));
+        "#,
+        8,
+    );
+    check_render(
+        r#"
+    //- /lib.rs crate:dep
+    pub enum E { A(u8), B }
+    pub const fn make(x: u8) -> E {
+        if x > 0 { E::A(x) } else { E::B }
+    }
+    //- /main.rs crate:main deps:dep
+    const GOAL: [dep::E; 2] = [dep::make(4), dep::make(0)];
+        "#,
+        "[A(4), B]",
+    );
+}
+
+#[test]
+fn unlowerable_callees() {
+    let (db, files) = TestDB::with_many_files(
+        r#"
+    //- /lib.rs crate:dep
+    pub fn first() -> i32 {
+        'a: loop {
+            continue 'a;
+        }
+    }
+    fn second() -> i32 {
+        'b: loop {
+            continue 'b;
+        }
+    }
+    pub fn lowerable(x: i32) -> i32 {
+        if x > 0 { second() } else { x }
+    }
+    //- /main.rs crate:main deps:dep
+    const GOAL: i32 = dep::first() + dep::lowerable(2);
+        "#,
+    );
+    let e = db.const_eval(goal_const(&db, &files), Substitution::empty(Interner));
+    let Err(ConstEvalError::MirEvalError(MirEvalError::UnlowerableCallees(callees))) = e else {
+        panic!("expected the unlowerable callees, found {e:?}");
+    };
+    let callees = callees
+        .iter()
+        .map(|(f, e)| (db.function_data(*f).name.to_smol_str(), e.clone()))
+        .collect::<Vec<_>>();
+    let unsupported = MirLowerError::NotSupported("continue with label".to_owned());
+    assert_eq!(callees, [("first".into(), unsupported.clone()), ("second".into(), unsupported)]);
+}
+
+#[test]
+fn exec_limits() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+    const fn spin() -> usize {
+        loop {}
+    }
+    const GOAL: usize = spin();
+    "#,
+    );
+    let goal = goal_const(&db, &[file_id]);
+    let e = db.const_eval(goal, Substitution::empty(Interner)).map_err(simplify);
+    let Err(ConstEvalError::MirEvalError(MirEvalError::ExecutionLimitExceeded(steps))) = e else {
+        panic!("expected the execution limit to be exceeded, got {e:?}");
+    };
+    // The steps of the function which loops are listed first.
+    assert!(
+        matches!(steps[..], [(DefWithBodyId::FunctionId(_), _), (owner, _)] if owner == goal.into())
+    );
+    assert_eq!(steps.iter().map(|&(_, steps)| steps).sum::<usize>(), 100_000);
     check_fail(
         r#"
     const fn f(x: i32) -> i32 {
@@ -1676,3 +3113,74 @@ fn type_error() {
     );
     assert!(matches!(e, Err(ConstEvalError::MirLowerError(MirLowerError::TypeMismatch(_)))));
 }
+
+#[test]
+fn cfg_macro() {
+    check_number(
+        r#"
+//- /main.rs cfg:feature=x
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+const GOAL: u8 = {
+    let mut x = 0;
+    if cfg!(feature = "x") {
+        x = x + 1;
+    }
+    if cfg!(all(feature = "x", not(test))) {
+        x = x + 2;
+    }
+    if cfg!(feature = "y") {
+        x = x + 4;
+    }
+    x
+};
+"#,
+        3,
+    );
+}
+
+#[test]
+fn render_references() {
+    check_render(r#"const GOAL: &str = "foo";"#, "\"foo\"");
+    check_render(r#"const GOAL: &i32 = &5;"#, "&5");
+    check_render(
+        r#"
+    //- minicore: coerce_unsized, slice
+    const GOAL: &[i32] = &[1, 2, 3];
+        "#,
+        "&[1, 2, 3]",
+    );
+    check_render(r#"const GOAL: &&u8 = &&2;"#, "&&2");
+}
+
+#[test]
+fn render_adts() {
+    check_render(
+        r#"
+    //- minicore: option
+    const GOAL: Option<&str> = Some("foo");
+        "#,
+        "Some(\"foo\")",
+    );
+    check_render(
+        r#"
+    //- minicore: option
+    const GOAL: Option<&str> = None;
+        "#,
+        "None",
+    );
+    check_render(
+        r#"
+    struct Point { x: i32, y: (bool, char) }
+    const GOAL: Point = Point { x: -1, y: (true, 'a') };
+        "#,
+        "Point { x: -1, y: (true, 'a') }",
+    );
+    check_render(
+        r#"
+    enum Shape { Empty, Rect { w: u8, h: u8 }, Circle(u16) }
+    const GOAL: [Shape; 3] = [Shape::Rect { w: 2, h: 3 }, Shape::Empty, Shape::Circle(4)];
+        "#,
+        "[Rect { w: 2, h: 3 }, Empty, Circle(4)]",
+    );
+}
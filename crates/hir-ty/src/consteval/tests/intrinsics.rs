@@ -44,13 +44,43 @@ fn const_eval_select() {
         }
 
         fn in_rt(x: i32, y: i32) -> i32 {
-            x + y
+            x * y
         }
 
         const GOAL: i32 = const_eval_select((2, 3), in_const, in_rt);
         "#,
         5,
     );
+    check_number(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn const_eval_select<ARG, F, G, RET>(arg: ARG, called_in_const: F, called_at_rt: G) -> RET
+            where
+                G: FnOnce<ARG, Output = RET>,
+                F: FnOnce<ARG, Output = RET>;
+        }
+
+        const fn id_in_const<T: Copy>(x: T, _: bool) -> T {
+            x
+        }
+
+        fn id_in_rt<T: Copy>(_: T, _: bool) -> T {
+            loop {}
+        }
+
+        const fn one() -> u64 {
+            1
+        }
+
+        fn two() -> u64 {
+            2
+        }
+
+        const GOAL: u64 = const_eval_select((7u64, true), id_in_const, id_in_rt)
+            + const_eval_select((), one, two);
+        "#,
+        8,
+    );
 }
 
 #[test]
@@ -67,6 +97,83 @@ fn wrapping_add() {
     );
 }
 
+#[test]
+fn integer_arithmetic() {
+    check_number(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn saturating_add<T>(a: T, b: T) -> T;
+            pub fn saturating_sub<T>(a: T, b: T) -> T;
+            pub fn wrapping_mul<T>(a: T, b: T) -> T;
+            pub fn unchecked_shl<T>(a: T, b: T) -> T;
+            pub fn unchecked_shr<T>(a: T, b: T) -> T;
+            pub fn exact_div<T>(a: T, b: T) -> T;
+        }
+
+        const GOAL: i32 = unsafe {
+            saturating_add(250u8, 10) as i32
+                + saturating_sub(-100i8, 100) as i32
+                + wrapping_mul(100u8, 3) as i32
+                + unchecked_shl(1i32, 10)
+                + unchecked_shr(-64i16, 3) as i32
+                + exact_div(-42i64, 7) as i32
+        };
+        "#,
+        255 - 128 + 44 + 1024 - 8 - 6,
+    );
+    check_number(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn add_with_overflow<T>(x: T, y: T) -> (T, bool);
+            pub fn mul_with_overflow<T>(x: T, y: T) -> (T, bool);
+            pub fn carrying_add<T>(a: T, b: T, carry: bool) -> (T, bool);
+            pub fn borrowing_sub<T>(a: T, b: T, borrow: bool) -> (T, bool);
+        }
+
+        fn flag(b: bool, bit: u32) -> u32 {
+            if b { 1 << bit } else { 0 }
+        }
+
+        const GOAL: u32 = unsafe {
+            let (a, a_overflow) = add_with_overflow(200u8, 100);
+            let (b, b_overflow) = mul_with_overflow(-1000i32, 1000);
+            let (c, c_overflow) = carrying_add(0xFFFF_FFFFu32, 0, true);
+            let (d, d_overflow) = borrowing_sub(0u16, 0, true);
+            let (e, e_overflow) = carrying_add(127i8, 0, true);
+            let flags = flag(a_overflow, 0)
+                + flag(b_overflow, 1)
+                + flag(c_overflow, 2)
+                + flag(d_overflow, 3)
+                + flag(e_overflow, 4);
+            a as u32 + (b / -1000) as u32 + c + d as u32 + (e as i32 + 128) as u32 + flags * 100000
+        };
+        "#,
+        44 + 1000 + 0xFFFF + 0b11101 * 100000,
+    );
+    check_fail(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn unchecked_add<T>(a: T, b: T) -> T;
+        }
+
+        const GOAL: u8 = unsafe { unchecked_add(200, 100) };
+        "#,
+        ConstEvalError::MirEvalError(MirEvalError::UndefinedBehavior(
+            "unchecked arithmetic overflowed",
+        )),
+    );
+    check_fail(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn exact_div<T>(a: T, b: T) -> T;
+        }
+
+        const GOAL: i32 = unsafe { exact_div(7, 2) };
+        "#,
+        ConstEvalError::MirEvalError(MirEvalError::UndefinedBehavior("exact_div with a remainder")),
+    );
+}
+
 #[test]
 fn offset() {
     check_number(
@@ -160,3 +267,92 @@ fn copy() {
         19,
     );
 }
+
+#[test]
+fn write_bytes() {
+    check_number(
+        r#"
+        //- minicore: coerce_unsized, index, slice
+        extern "rust-intrinsic" {
+            pub fn write_bytes<T>(dst: *mut T, val: u8, count: usize);
+        }
+
+        const GOAL: u32 = unsafe {
+            let mut x = [0u32; 100_000];
+            write_bytes((&mut x as *mut _) as *mut u32, 1, 50_000);
+            x[0] + x[49_999] + x[50_000]
+        };
+        "#,
+        0x01010101 * 2,
+    );
+}
+
+#[test]
+fn float_math() {
+    check_number(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn sqrtf64(x: f64) -> f64;
+            pub fn floorf64(x: f64) -> f64;
+            pub fn fmaf64(a: f64, b: f64, c: f64) -> f64;
+        }
+
+        const GOAL: f64 = unsafe { fmaf64(sqrtf64(16.0), floorf64(2.7), 1.5) };
+        "#,
+        9.5f64.to_bits() as i128,
+    );
+    check_number(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn powif32(a: f32, x: i32) -> f32;
+        }
+
+        const GOAL: f32 = unsafe { powif32(1.5, 3) };
+        "#,
+        3.375f32.to_bits() as i128,
+    );
+}
+
+#[test]
+fn try_intrinsic() {
+    check_number(
+        r#"
+        extern "rust-intrinsic" {
+            pub fn r#try(try_fn: fn(*mut u8), data: *mut u8, catch_fn: fn(*mut u8, *mut u8)) -> i32;
+            pub fn transmute<T, U>(e: T) -> U;
+        }
+
+        trait Any {}
+
+        extern "Rust" {
+            fn __rust_panic_cleanup(payload: *mut u8) -> *mut dyn Any;
+        }
+
+        fn overflow(data: *mut u8) {
+            unsafe { *data = 1 };
+            let _ = 255u8 + unsafe { *data };
+        }
+
+        fn no_panic(data: *mut u8) {
+            unsafe { *data = 2 };
+        }
+
+        fn first_byte_of_message(data: *mut u8, payload: *mut u8) {
+            unsafe {
+                let message = *(__rust_panic_cleanup(payload) as *mut &str);
+                let (ptr, _): (*const u8, usize) = transmute(message);
+                *data = *data + *ptr;
+            }
+        }
+
+        const GOAL: i32 = {
+            let mut x = 0u8;
+            let mut y = 0u8;
+            let caught = unsafe { r#try(overflow, &mut x as *mut u8, first_byte_of_message) };
+            let not_caught = unsafe { r#try(no_panic, &mut y as *mut u8, first_byte_of_message) };
+            caught * 1000 + not_caught * 100 + (x - b'O') as i32 + y as i32
+        };
+        "#,
+        1003,
+    );
+}
@@ -7,7 +7,7 @@ use base_db::{impl_intern_key, salsa, CrateId, Upcast};
 use hir_def::{
     db::DefDatabase, hir::ExprId, layout::TargetDataLayout, AdtId, BlockId, ConstId, ConstParamId,
     DefWithBodyId, EnumVariantId, FunctionId, GenericDefId, ImplId, LifetimeParamId, LocalFieldId,
-    TypeOrConstParamId, VariantId,
+    TraitId, TypeOrConstParamId, VariantId,
 };
 use la_arena::ArenaMap;
 use smallvec::SmallVec;
@@ -17,7 +17,7 @@ use crate::{
     consteval::ConstEvalError,
     layout::{Layout, LayoutError},
     method_resolution::{InherentImpls, TraitImpls, TyFingerprint},
-    mir::{BorrowckResult, MirBody, MirLowerError},
+    mir::{BorrowckResult, MirBody, MirLowerError, RegionInferenceResult},
     Binders, CallableDefId, ClosureId, Const, FnDefId, GenericArg, ImplTraitId, InferenceResult,
     Interner, PolyFnSig, QuantifiedWhereClause, ReturnTypeImplTraits, Substitution, TraitRef, Ty,
     TyDefId, ValueTyDefId,
@@ -43,6 +43,12 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::mir::borrowck_query)]
     fn borrowck(&self, def: DefWithBodyId) -> Result<Arc<[BorrowckResult]>, MirLowerError>;
 
+    #[salsa::invoke(crate::mir::region_inference_query)]
+    fn region_inference(
+        &self,
+        def: DefWithBodyId,
+    ) -> Result<Arc<[RegionInferenceResult]>, MirLowerError>;
+
     #[salsa::invoke(crate::lower::ty_query)]
     #[salsa::cycle(crate::lower::ty_recover)]
     fn ty(&self, def: TyDefId) -> Binders<Ty>;
@@ -87,6 +93,12 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
         def: FunctionId,
     ) -> Option<Arc<Binders<ReturnTypeImplTraits>>>;
 
+    #[salsa::invoke(crate::lower::type_alias_impl_traits)]
+    fn type_alias_impl_traits(
+        &self,
+        def: TypeAliasId,
+    ) -> Option<Arc<Binders<ReturnTypeImplTraits>>>;
+
     #[salsa::invoke(crate::lower::generic_predicates_for_param_query)]
     #[salsa::cycle(crate::lower::generic_predicates_for_param_recover)]
     fn generic_predicates_for_param(
@@ -97,6 +109,7 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     ) -> Arc<[Binders<QuantifiedWhereClause>]>;
 
     #[salsa::invoke(crate::lower::generic_predicates_query)]
+    #[salsa::cycle(crate::lower::generic_predicates_recover)]
     fn generic_predicates(&self, def: GenericDefId) -> Arc<[Binders<QuantifiedWhereClause>]>;
 
     #[salsa::invoke(crate::lower::trait_environment_for_body_query)]
@@ -106,6 +119,10 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::trait_environment_query)]
     fn trait_environment(&self, def: GenericDefId) -> Arc<crate::TraitEnvironment>;
 
+    #[salsa::invoke(crate::variance::variances_of_query)]
+    #[salsa::cycle(crate::variance::variances_of_recover)]
+    fn variances_of(&self, def: GenericDefId) -> Arc<crate::variance::Variances>;
+
     #[salsa::invoke(crate::lower::generic_defaults_query)]
     #[salsa::cycle(crate::lower::generic_defaults_recover)]
     fn generic_defaults(&self, def: GenericDefId) -> Arc<[Binders<GenericArg>]>;
@@ -136,6 +153,19 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(TraitImpls::trait_impls_in_deps_query)]
     fn trait_impls_in_deps(&self, krate: CrateId) -> Arc<TraitImpls>;
 
+    /// Returns the pairs of overlapping trait impls, where the first impl is an impl of `krate`.
+    #[salsa::invoke(crate::coherence::conflicting_impls_query)]
+    fn conflicting_impls(&self, krate: CrateId) -> Arc<[(ImplId, ImplId)]>;
+
+    /// Returns why `ty` doesn't implement the auto trait `trait_`, or `None` if it implements it.
+    #[salsa::invoke(crate::auto_trait::auto_trait_blocker_query)]
+    fn auto_trait_blocker(
+        &self,
+        ty: Ty,
+        trait_: TraitId,
+        env: Arc<crate::TraitEnvironment>,
+    ) -> Option<Arc<crate::auto_trait::AutoTraitBlocker>>;
+
     // Interned IDs for Chalk integration
     #[salsa::interned]
     fn intern_callable_def(&self, callable_def: CallableDefId) -> InternedCallableDefId;
@@ -3,12 +3,14 @@ mod expr;
 mod match_check;
 mod unsafe_check;
 mod decl_check;
+mod transmute_check;
 
 pub use crate::diagnostics::{
     decl_check::{incorrect_case, IncorrectCase},
     expr::{
         record_literal_missing_fields, record_pattern_missing_fields, BodyValidationDiagnostic,
     },
+    transmute_check::{transmute_mismatches, TransmuteMismatch, TransmuteMismatchKind},
     unsafe_check::{missing_unsafe, unsafe_expressions, UnsafeExpr},
 };
 
@@ -43,6 +43,9 @@ pub enum BodyValidationDiagnostic {
     MissingMatchArms {
         match_expr: ExprId,
         uncovered_patterns: String,
+        /// The patterns not covered by the arms, like `Some(Err(_))`, written like in the module
+        /// of the match so they can be inserted as arms. Empty if one of them can't be written.
+        witnesses: Vec<String>,
     },
 }
 
@@ -212,9 +215,16 @@ impl ExprValidator {
 
         let witnesses = report.non_exhaustiveness_witnesses;
         if !witnesses.is_empty() {
+            let module = self.owner.module(db.upcast());
+            let witness_pats = witnesses
+                .iter()
+                .map(|witness| witness.to_pat(&cx).display_source_code(db, module, false).ok())
+                .collect::<Option<Vec<_>>>()
+                .unwrap_or_default();
             self.diagnostics.push(BodyValidationDiagnostic::MissingMatchArms {
                 match_expr,
                 uncovered_patterns: missing_match_arms(&cx, scrut_ty, witnesses, arms),
+                witnesses: witness_pats,
             });
         }
     }
@@ -227,7 +237,7 @@ impl ExprValidator {
         body: &Body,
         have_errors: &mut bool,
     ) -> &'p DeconstructedPat<'p> {
-        let mut patcx = match_check::PatCtxt::new(db, &self.infer, body);
+        let mut patcx = match_check::PatCtxt::new(db, self.owner, &self.infer, body);
         let pattern = patcx.lower_pattern(pat);
         let pattern = cx.pattern_arena.alloc(DeconstructedPat::from_pat(cx, &pattern));
         if !patcx.errors.is_empty() {
@@ -10,22 +10,29 @@ mod pat_util;
 pub(crate) mod deconstruct_pat;
 pub(crate) mod usefulness;
 
-use chalk_ir::Mutability;
+use chalk_ir::{IntTy, Mutability, Scalar, UintTy};
 use hir_def::{
-    body::Body, data::adt::VariantData, hir::PatId, AdtId, EnumVariantId, LocalFieldId, VariantId,
+    body::Body,
+    data::adt::VariantData,
+    find_path::find_path,
+    hir::{Expr, ExprId, Literal, PatId, RangeOp},
+    item_scope::ItemInNs,
+    resolver::{resolver_for_expr, ValueNs},
+    AdtId, AssocItemId, DefWithBodyId, EnumVariantId, LocalFieldId, ModuleDefId, VariantId,
 };
 use hir_expand::name::Name;
 use stdx::{always, never};
 
 use crate::{
+    consteval::try_const_scalar,
     db::HirDatabase,
-    display::{HirDisplay, HirDisplayError, HirFormatter},
+    display::{DisplaySourceCodeError, HirDisplay, HirDisplayError, HirFormatter},
     infer::BindingMode,
     lang_items::is_box,
     InferenceResult, Interner, Substitution, Ty, TyExt, TyKind,
 };
 
-use self::pat_util::EnumerateAndAdjustIterator;
+use self::{deconstruct_pat::IntRange, pat_util::EnumerateAndAdjustIterator};
 
 pub(crate) use self::usefulness::MatchArm;
 
@@ -36,6 +43,7 @@ pub(crate) enum PatternError {
     UnresolvedVariant,
     MissingField,
     ExtraFields,
+    EmptyRange,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -80,11 +88,26 @@ pub(crate) enum PatKind {
         subpattern: Pat,
     },
 
-    // FIXME: for now, only bool literals are implemented
+    // FIXME: string and float literals are not implemented
     LiteralBool {
         value: bool,
     },
 
+    /// An integer or `char` literal, or a range of them like `0..10`, `'a'..='z'` or `5..`.
+    /// The bounds are inclusive and encoded like the ones of [`deconstruct_pat::IntRange`].
+    Range {
+        lo: u128,
+        hi: u128,
+    },
+
+    /// Matches against a slice or an array, checking the length and extracting elements.
+    /// `slice` is the pattern for the middle `..` part, if present.
+    Slice {
+        prefix: Vec<Pat>,
+        slice: Option<Pat>,
+        suffix: Vec<Pat>,
+    },
+
     /// An or-pattern, e.g. `p | q`.
     /// Invariant: `pats.len() >= 2`.
     Or {
@@ -94,14 +117,20 @@ pub(crate) enum PatKind {
 
 pub(crate) struct PatCtxt<'a> {
     db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
     infer: &'a InferenceResult,
     body: &'a Body,
     pub(crate) errors: Vec<PatternError>,
 }
 
 impl<'a> PatCtxt<'a> {
-    pub(crate) fn new(db: &'a dyn HirDatabase, infer: &'a InferenceResult, body: &'a Body) -> Self {
-        Self { db, infer, body, errors: Vec::new() }
+    pub(crate) fn new(
+        db: &'a dyn HirDatabase,
+        owner: DefWithBodyId,
+        infer: &'a InferenceResult,
+        body: &'a Body,
+    ) -> Self {
+        Self { db, owner, infer, body, errors: Vec::new() }
     }
 
     pub(crate) fn lower_pattern(&mut self, pat: PatId) -> Pat {
@@ -127,7 +156,11 @@ impl<'a> PatCtxt<'a> {
         let kind = match self.body[pat] {
             hir_def::hir::Pat::Wild => PatKind::Wild,
 
-            hir_def::hir::Pat::Lit(expr) => self.lower_lit(expr),
+            hir_def::hir::Pat::Lit(expr) => self.lower_lit(ty, expr),
+
+            hir_def::hir::Pat::Range { start, end, range_type } => {
+                self.lower_range(ty, start, end, range_type)
+            }
 
             hir_def::hir::Pat::Path(ref path) => {
                 return self.lower_path(pat, path);
@@ -158,6 +191,9 @@ impl<'a> PatCtxt<'a> {
                     }
                     _ => (),
                 }
+                // `rest @ ..` in slice patterns has a missing subpattern.
+                let subpat =
+                    subpat.filter(|&it| !matches!(self.body[it], hir_def::hir::Pat::Missing));
                 PatKind::Binding { name: name.clone(), subpattern: self.lower_opt_pattern(subpat) }
             }
 
@@ -194,6 +230,25 @@ impl<'a> PatCtxt<'a> {
 
             hir_def::hir::Pat::Or(ref pats) => PatKind::Or { pats: self.lower_patterns(pats) },
 
+            hir_def::hir::Pat::Slice { ref prefix, slice, ref suffix } => {
+                if !matches!(ty.kind(Interner), TyKind::Array(..) | TyKind::Slice(..)) {
+                    never!("unexpected type for slice pattern: {:?}", ty);
+                    self.errors.push(PatternError::UnexpectedType);
+                    return Pat { ty: ty.clone(), kind: PatKind::Wild.into() };
+                }
+                PatKind::Slice {
+                    prefix: self.lower_patterns(prefix),
+                    slice: slice.map(|it| match self.body[it] {
+                        // A plain `..`
+                        hir_def::hir::Pat::Missing => {
+                            Pat { ty: self.infer[it].clone(), kind: PatKind::Wild.into() }
+                        }
+                        _ => self.lower_pattern(it),
+                    }),
+                    suffix: self.lower_patterns(suffix),
+                }
+            }
+
             _ => {
                 self.errors.push(PatternError::Unimplemented);
                 PatKind::Wild
@@ -279,17 +334,85 @@ impl<'a> PatCtxt<'a> {
         }
     }
 
-    fn lower_lit(&mut self, expr: hir_def::hir::ExprId) -> PatKind {
-        use hir_def::hir::{Expr, Literal::Bool};
+    fn lower_lit(&mut self, ty: &Ty, expr: ExprId) -> PatKind {
+        if let Expr::Literal(Literal::Bool(value)) = self.body[expr] {
+            return PatKind::LiteralBool { value };
+        }
+        match as_scalar(ty).and_then(|scalar| self.lower_range_bound(scalar, expr)) {
+            Some(value) => PatKind::Range { lo: value, hi: value },
+            None => {
+                self.errors.push(PatternError::Unimplemented);
+                PatKind::Wild
+            }
+        }
+    }
 
-        match self.body[expr] {
-            Expr::Literal(Bool(value)) => PatKind::LiteralBool { value },
+    fn lower_range(
+        &mut self,
+        ty: &Ty,
+        start: Option<ExprId>,
+        end: Option<ExprId>,
+        range_type: RangeOp,
+    ) -> PatKind {
+        let Some((scalar, (min, max))) =
+            as_scalar(ty).and_then(|scalar| Some((scalar, IntRange::type_bounds(scalar)?)))
+        else {
+            self.errors.push(PatternError::Unimplemented);
+            return PatKind::Wild;
+        };
+        let lo = match start {
+            Some(start) => self.lower_range_bound(scalar, start),
+            None => Some(min),
+        };
+        let hi = match (end, range_type) {
+            (Some(end), RangeOp::Inclusive) => self.lower_range_bound(scalar, end),
+            // `lo..0` is empty, like any range ending before it starts.
+            (Some(end), RangeOp::Exclusive) => match self.lower_range_bound(scalar, end) {
+                Some(0) => {
+                    self.errors.push(PatternError::EmptyRange);
+                    return PatKind::Wild;
+                }
+                end => end.map(|it| it - 1),
+            },
+            (None, _) => Some(max),
+        };
+        match (lo, hi) {
+            (Some(lo), Some(hi)) if lo <= hi => PatKind::Range { lo, hi },
+            (Some(_), Some(_)) => {
+                self.errors.push(PatternError::EmptyRange);
+                PatKind::Wild
+            }
             _ => {
                 self.errors.push(PatternError::Unimplemented);
                 PatKind::Wild
             }
         }
     }
+
+    /// Returns the encoded value of a literal, or of a constant, bounding a range pattern.
+    fn lower_range_bound(&self, scalar: Scalar, expr: ExprId) -> Option<u128> {
+        let bits = match &self.body[expr] {
+            &Expr::Literal(Literal::Int(value, _)) => value as u128,
+            &Expr::Literal(Literal::Uint(value, _)) => value,
+            &Expr::Literal(Literal::Char(value)) => value as u128,
+            Expr::Path(path) => {
+                let (konst, subst) = match self.infer.assoc_resolutions_for_expr(expr) {
+                    Some((AssocItemId::ConstId(konst), subst)) => (konst, subst),
+                    Some(_) => return None,
+                    None => {
+                        let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr);
+                        match resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path)? {
+                            ValueNs::ConstId(konst) => (konst, Substitution::empty(Interner)),
+                            _ => return None,
+                        }
+                    }
+                };
+                try_const_scalar(&self.db.const_eval(konst, subst).ok()?)? as u128
+            }
+            _ => return None,
+        };
+        IntRange::encode(bits, scalar)
+    }
 }
 
 impl HirDisplay for Pat {
@@ -315,13 +438,32 @@ impl HirDisplay for Pat {
                 };
 
                 if let Some(variant) = variant {
-                    match variant {
-                        VariantId::EnumVariantId(v) => {
+                    match (variant, f.source_code_module()) {
+                        // Variants are written with their paths to be inserted in source files,
+                        // like `Option::Some` or `Some` if it's in scope.
+                        (_, Some(module_id)) => {
+                            let def = match variant {
+                                VariantId::EnumVariantId(v) => ModuleDefId::EnumVariantId(v),
+                                VariantId::StructId(s) => ModuleDefId::AdtId(s.into()),
+                                VariantId::UnionId(u) => ModuleDefId::AdtId(u.into()),
+                            };
+                            let Some(path) =
+                                find_path(f.db.upcast(), ItemInNs::Types(def), module_id, false)
+                            else {
+                                return Err(HirDisplayError::DisplaySourceCodeError(
+                                    DisplaySourceCodeError::PathNotFound,
+                                ));
+                            };
+                            write!(f, "{path}")?;
+                        }
+                        (VariantId::EnumVariantId(v), None) => {
                             let data = f.db.enum_data(v.parent);
                             write!(f, "{}", data.variants[v.local_id].name)?;
                         }
-                        VariantId::StructId(s) => write!(f, "{}", f.db.struct_data(s).name)?,
-                        VariantId::UnionId(u) => write!(f, "{}", f.db.union_data(u).name)?,
+                        (VariantId::StructId(s), None) => {
+                            write!(f, "{}", f.db.struct_data(s).name)?
+                        }
+                        (VariantId::UnionId(u), None) => write!(f, "{}", f.db.union_data(u).name)?,
                     };
 
                     let variant_data = variant.variant_data(f.db.upcast());
@@ -388,11 +530,68 @@ impl HirDisplay for Pat {
                 subpattern.hir_fmt(f)
             }
             PatKind::LiteralBool { value } => write!(f, "{value}"),
+            &PatKind::Range { lo, hi } => {
+                let Some((scalar, (min, max))) =
+                    as_scalar(&self.ty).and_then(|it| Some((it, IntRange::type_bounds(it)?)))
+                else {
+                    never!("{:?} is a bad range pattern type", self.ty);
+                    return write!(f, "_");
+                };
+                if lo == hi {
+                    return write!(f, "{}", IntRange::render(lo, scalar));
+                }
+                // Like rustc, the bounds of pointer-sized integers are left open as they depend on
+                // the target, and the other bounds of integers are written with their names.
+                let signed = matches!(scalar, Scalar::Int(_));
+                let pointer_sized =
+                    matches!(scalar, Scalar::Int(IntTy::Isize) | Scalar::Uint(UintTy::Usize));
+                if lo != min || !signed {
+                    write!(f, "{}", IntRange::render(lo, scalar))?;
+                } else if !pointer_sized {
+                    self.ty.hir_fmt(f)?;
+                    write!(f, "::MIN")?;
+                }
+                write!(f, "..")?;
+                if hi != max || matches!(scalar, Scalar::Char) {
+                    write!(f, "={}", IntRange::render(hi, scalar))?;
+                } else if !pointer_sized {
+                    write!(f, "=")?;
+                    self.ty.hir_fmt(f)?;
+                    write!(f, "::MAX")?;
+                }
+                Ok(())
+            }
+            PatKind::Slice { prefix, slice, suffix } => {
+                write!(f, "[")?;
+                f.write_joined(prefix.iter(), ", ")?;
+                if let Some(slice) = slice {
+                    if !prefix.is_empty() {
+                        write!(f, ", ")?;
+                    }
+                    if !matches!(*slice.kind, PatKind::Wild) {
+                        slice.hir_fmt(f)?;
+                        write!(f, " @ ")?;
+                    }
+                    write!(f, "..")?;
+                }
+                if !suffix.is_empty() && (slice.is_some() || !prefix.is_empty()) {
+                    write!(f, ", ")?;
+                }
+                f.write_joined(suffix.iter(), ", ")?;
+                write!(f, "]")
+            }
             PatKind::Or { pats } => f.write_joined(pats.iter(), " | "),
         }
     }
 }
 
+fn as_scalar(ty: &Ty) -> Option<Scalar> {
+    match ty.kind(Interner) {
+        &TyKind::Scalar(scalar) => Some(scalar),
+        _ => None,
+    }
+}
+
 struct WriteWith<F>(F)
 where
     F: Fn(&mut HirFormatter<'_>) -> Result<(), HirDisplayError>;
@@ -496,6 +695,12 @@ impl PatternFoldable for PatKind {
                 PatKind::Deref { subpattern: subpattern.fold_with(folder) }
             }
             &PatKind::LiteralBool { value } => PatKind::LiteralBool { value },
+            &PatKind::Range { lo, hi } => PatKind::Range { lo, hi },
+            PatKind::Slice { prefix, slice, suffix } => PatKind::Slice {
+                prefix: prefix.fold_with(folder),
+                slice: slice.fold_with(folder),
+                suffix: suffix.fold_with(folder),
+            },
             PatKind::Or { pats } => PatKind::Or { pats: pats.fold_with(folder) },
         }
     }
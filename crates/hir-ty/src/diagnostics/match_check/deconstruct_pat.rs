@@ -39,7 +39,8 @@
 //!
 //! Splitting is implemented in the [`Constructor::split`] function. We don't do splitting for
 //! or-patterns; instead we just try the alternatives one-by-one. For details on splitting
-//! wildcards, see [`SplitWildcard`]; for integer ranges, see [`SplitIntRange`].
+//! wildcards, see [`SplitWildcard`]; for integer ranges, see [`SplitIntRange`]; for slices, see
+//! [`SplitVarLenSlice`].
 
 use std::{
     cell::Cell,
@@ -48,13 +49,14 @@ use std::{
     ops::RangeInclusive,
 };
 
+use chalk_ir::{IntTy, UintTy};
 use hir_def::{EnumVariantId, HasModule, LocalFieldId, VariantId};
 use smallvec::{smallvec, SmallVec};
 use stdx::never;
 
 use crate::{
-    infer::normalize, inhabitedness::is_enum_variant_uninhabited_from, AdtId, Interner, Scalar, Ty,
-    TyExt, TyKind,
+    consteval::try_const_usize, infer::normalize, inhabitedness::is_enum_variant_uninhabited_from,
+    AdtId, Interner, Scalar, Ty, TyExt, TyKind,
 };
 
 use super::{
@@ -63,7 +65,7 @@ use super::{
     FieldPat, Pat, PatKind,
 };
 
-use self::Constructor::*;
+use self::{Constructor::*, SliceKind::*};
 
 /// Recursively expand this pattern into its subpatterns. Only useful for or-patterns.
 fn expand_or_pat(pat: &Pat) -> Vec<&Pat> {
@@ -125,11 +127,70 @@ impl IntRange {
         IntRange { range: val..=val }
     }
 
+    /// The range of encoded values `lo..=hi`, see [`IntRange::encode`].
     #[inline]
-    fn from_range(lo: u128, hi: u128, scalar_ty: Scalar) -> IntRange {
-        match scalar_ty {
-            Scalar::Bool => IntRange { range: lo..=hi },
-            _ => unimplemented!(),
+    fn from_range(lo: u128, hi: u128) -> IntRange {
+        IntRange { range: lo..=hi }
+    }
+
+    /// The size of the integral type, in bits. Pointer-sized integers are checked as if they were
+    /// 128 bits wide, so that their matches don't depend on the target: like in rustc, covering
+    /// their maximum takes an open range like `0..`.
+    fn size(scalar: Scalar) -> Option<u32> {
+        Some(match scalar {
+            Scalar::Bool => 1,
+            Scalar::Int(IntTy::I8) | Scalar::Uint(UintTy::U8) => 8,
+            Scalar::Int(IntTy::I16) | Scalar::Uint(UintTy::U16) => 16,
+            Scalar::Char | Scalar::Int(IntTy::I32) | Scalar::Uint(UintTy::U32) => 32,
+            Scalar::Int(IntTy::I64) | Scalar::Uint(UintTy::U64) => 64,
+            Scalar::Int(IntTy::I128 | IntTy::Isize)
+            | Scalar::Uint(UintTy::U128 | UintTy::Usize) => 128,
+            Scalar::Float(_) => return None,
+        })
+    }
+
+    /// The value to flip the bits of signed integers with, so that their minimum is encoded as `0`.
+    fn signed_bias(scalar: Scalar) -> u128 {
+        match (scalar, Self::size(scalar)) {
+            (Scalar::Int(_), Some(size)) => 1u128 << (size - 1),
+            _ => 0,
+        }
+    }
+
+    /// Encodes the value of type `scalar` whose bits, sign extended to 128 bits, are `bits`.
+    /// Returns `None` if the value doesn't fit in the type.
+    pub(super) fn encode(bits: u128, scalar: Scalar) -> Option<u128> {
+        let shift = 128 - Self::size(scalar)?;
+        let truncated = bits << shift >> shift;
+        let fits = match scalar {
+            Scalar::Int(_) => ((truncated << shift) as i128 >> shift) as u128 == bits,
+            Scalar::Char => truncated <= char::MAX as u128,
+            _ => truncated == bits,
+        };
+        fits.then_some(truncated ^ Self::signed_bias(scalar))
+    }
+
+    /// The encoded minimum and maximum of the integral type.
+    pub(super) fn type_bounds(scalar: Scalar) -> Option<(u128, u128)> {
+        match scalar {
+            Scalar::Char => Some((0, char::MAX as u128)),
+            _ => Some((0, u128::MAX >> (128 - Self::size(scalar)?))),
+        }
+    }
+
+    /// Renders the encoded `value` of type `scalar` as a literal.
+    pub(super) fn render(value: u128, scalar: Scalar) -> String {
+        let bits = value ^ Self::signed_bias(scalar);
+        match scalar {
+            Scalar::Int(_) => {
+                let shift = 128 - Self::size(scalar).unwrap_or(128);
+                (((bits << shift) as i128) >> shift).to_string()
+            }
+            Scalar::Char => match char::from_u32(bits as u32) {
+                Some(c) => format!("{c:?}"),
+                None => bits.to_string(),
+            },
+            _ => bits.to_string(),
         }
     }
 
@@ -161,7 +222,19 @@ impl IntRange {
                 };
                 Pat { ty, kind: kind.into() }
             }
-            _ => unimplemented!(),
+            &TyKind::Scalar(scalar) => {
+                let (lo, hi) = self.boundaries();
+                let kind = if IntRange::type_bounds(scalar) == Some((lo, hi)) {
+                    PatKind::Wild
+                } else {
+                    PatKind::Range { lo, hi }
+                };
+                Pat { ty, kind: kind.into() }
+            }
+            _ => {
+                never!("bad type for integer range: {:?}", ty);
+                Pat { ty, kind: PatKind::Wild.into() }
+            }
         }
     }
 
@@ -273,20 +346,199 @@ impl SplitIntRange {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum SliceKind {
+    /// Patterns of length `n` (`[x, y]`).
+    FixedLen(usize),
+    /// Patterns using the `..` notation (`[x, .., y]`).
+    /// Captures any array constructor of `length >= i + j`.
+    /// In the case where `array_len` is `Some(_)`,
+    /// this indicates that we only care about the first `i` and the last `j` values of the array,
+    /// and everything in between is a wildcard `_`.
+    VarLen(usize, usize),
+}
+
+impl SliceKind {
+    fn arity(self) -> usize {
+        match self {
+            FixedLen(length) => length,
+            VarLen(prefix, suffix) => prefix + suffix,
+        }
+    }
+
+    /// Whether this pattern includes patterns of length `other_len`.
+    fn covers_length(self, other_len: usize) -> bool {
+        match self {
+            FixedLen(len) => len == other_len,
+            VarLen(prefix, suffix) => prefix + suffix <= other_len,
+        }
+    }
+}
+
 /// A constructor for array and slice patterns.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(super) struct Slice {
-    _unimplemented: Void,
+    /// `None` if the matched value is a slice, `Some(n)` if it is an array of size `n`.
+    array_len: Option<usize>,
+    /// The kind of pattern it is: fixed-length `[x, y]` or variable length `[x, .., y]`.
+    kind: SliceKind,
 }
 
 impl Slice {
+    fn new(array_len: Option<usize>, kind: SliceKind) -> Self {
+        let kind = match (array_len, kind) {
+            // If the middle `..` is empty, we effectively have a fixed-length pattern.
+            (Some(len), VarLen(prefix, suffix)) if prefix + suffix >= len => FixedLen(len),
+            _ => kind,
+        };
+        Slice { array_len, kind }
+    }
+
     fn arity(self) -> usize {
-        match self._unimplemented {}
+        self.kind.arity()
     }
 
     /// See `Constructor::is_covered_by`
-    fn is_covered_by(self, _other: Self) -> bool {
-        match self._unimplemented {}
+    fn is_covered_by(self, other: Self) -> bool {
+        other.kind.covers_length(self.arity())
+    }
+}
+
+/// This computes constructor splitting for variable-length slices, as explained at the top of the
+/// file.
+///
+/// A slice pattern `[x, .., y]` behaves like the infinite or-pattern `[x, y] | [x, _, y] | [x, _,
+/// _, y] | ...`. The corresponding value constructors are fixed-length array constructors above a
+/// given minimum length. We obviously can't list this infinitude of constructors. Thankfully,
+/// it turns out that for each finite set of slice patterns, all sufficiently large array lengths
+/// are equivalent.
+///
+/// Let's look at an example, where we are trying to split the last pattern:
+/// ```
+/// # fn foo(x: &[bool]) {
+/// match x {
+///     [true, true, ..] => {}
+///     [.., false, false] => {}
+///     [..] => {}
+/// }
+/// # }
+/// ```
+/// Here are the results of specialization for the first few lengths:
+/// ```
+/// # fn foo(x: &[bool]) { match x {
+/// // length 0
+/// [] => {}
+/// // length 1
+/// [_] => {}
+/// // length 2
+/// [true, true] => {}
+/// [false, false] => {}
+/// [_, _] => {}
+/// // length 3
+/// [true, true,  _    ] => {}
+/// [_,    false, false] => {}
+/// [_,    _,     _    ] => {}
+/// // length 4
+/// [true, true, _,     _    ] => {}
+/// [_,    _,    false, false] => {}
+/// [_,    _,    _,     _    ] => {}
+/// // length 5
+/// [true, true, _, _,     _    ] => {}
+/// [_,    _,    _, false, false] => {}
+/// [_,    _,    _, _,     _    ] => {}
+/// # _ => {}
+/// # }}
+/// ```
+///
+/// If we went above length 5, we would simply be inserting more columns full of wildcards in the
+/// middle. This means that the set of witnesses for length `l >= 5` if equivalent to the set for
+/// any other `l' >= 5`: simply add or remove wildcards in the middle to convert between them.
+///
+/// This applies to any set of slice patterns: there will be a length `L` above which all lengths
+/// behave the same. This is exactly what we need for constructor splitting. Therefore a
+/// variable-length slice can be split into a variable-length slice of minimal length `L`, and many
+/// fixed-length slices of lengths `< L`.
+///
+/// For each variable-length pattern `p` with a prefix of length `plₚ` and suffix of length `slₚ`,
+/// only the first `plₚ` and the last `slₚ` elements are examined. Therefore, as long as `L` is
+/// positive (to avoid concerns about empty types), all elements after the maximum prefix length
+/// and before the maximum suffix length are not examined by any variable-length pattern, and
+/// therefore can be added/removed without affecting them - creating equivalent patterns from any
+/// sufficiently-large length.
+///
+/// Of course, if fixed-length patterns exist, we must be sure that our length is large enough to
+/// miss them all, so we can pick `L = max(max(FIXED_LEN)+1, max(PREFIX_LEN) + max(SUFFIX_LEN))`
+///
+/// `max_slice` below will be made to have arity `L`.
+#[derive(Debug)]
+struct SplitVarLenSlice {
+    /// If the type is an array, this is its size.
+    array_len: Option<usize>,
+    /// The arity of the input slice.
+    arity: usize,
+    /// The smallest slice bigger than any slice seen. `max_slice.arity()` is the length `L`
+    /// described above.
+    max_slice: SliceKind,
+}
+
+impl SplitVarLenSlice {
+    fn new(prefix: usize, suffix: usize, array_len: Option<usize>) -> Self {
+        SplitVarLenSlice { array_len, arity: prefix + suffix, max_slice: VarLen(prefix, suffix) }
+    }
+
+    /// Pass a set of slices relative to which to split this one.
+    fn split(&mut self, slices: impl Iterator<Item = SliceKind>) {
+        let (max_prefix_len, max_suffix_len) = match &mut self.max_slice {
+            VarLen(prefix, suffix) => (prefix, suffix),
+            // No need to split
+            FixedLen(_) => return,
+        };
+        // We grow `self.max_slice` to be larger than all slices encountered, as described above.
+        // For diagnostics, we keep the prefix and suffix lengths separate, but grow them so that
+        // `L = max_prefix_len + max_suffix_len`.
+        let mut max_fixed_len = 0;
+        for slice in slices {
+            match slice {
+                FixedLen(len) => {
+                    max_fixed_len = max(max_fixed_len, len);
+                }
+                VarLen(prefix, suffix) => {
+                    *max_prefix_len = max(*max_prefix_len, prefix);
+                    *max_suffix_len = max(*max_suffix_len, suffix);
+                }
+            }
+        }
+        // We want `L = max(L, max_fixed_len + 1)`, modulo the fact that we keep prefix and
+        // suffix separate.
+        if max_fixed_len + 1 >= *max_prefix_len + *max_suffix_len {
+            // The subtraction can't overflow thanks to the above check.
+            // The new `max_prefix_len` is larger than its previous value.
+            *max_prefix_len = max_fixed_len + 1 - *max_suffix_len;
+        }
+
+        // We cap the arity of `max_slice` at the array size.
+        match self.array_len {
+            Some(len) if self.max_slice.arity() >= len => self.max_slice = FixedLen(len),
+            _ => {}
+        }
+    }
+
+    /// Iterate over the partition of this slice.
+    fn iter(&self) -> impl Iterator<Item = Slice> + Captures<'_> {
+        let smaller_lengths = match self.array_len {
+            // The only admissible fixed-length slice is one of the array size. Whether `max_slice`
+            // is fixed-length or variable-length, it will be the only relevant slice to output
+            // here.
+            Some(_) => 0..0, // empty range
+            // We cover all arities in the range `(self.arity..infinity)`. We split that range into
+            // two: lengths smaller than `max_slice.arity()` are treated independently as
+            // fixed-lengths slices, and lengths above are captured by `max_slice`.
+            None => self.arity..self.max_slice.arity(),
+        };
+        smaller_lengths
+            .map(FixedLen)
+            .chain(once(self.max_slice))
+            .map(move |kind| Slice::new(self.array_len, kind))
     }
 }
 
@@ -445,7 +697,12 @@ impl Constructor {
                 split_range.split(int_ranges.cloned());
                 split_range.iter().map(IntRange).collect()
             }
-            Slice(slice) => match slice._unimplemented {},
+            &Slice(Slice { kind: VarLen(self_prefix, self_suffix), array_len }) => {
+                let mut split_self = SplitVarLenSlice::new(self_prefix, self_suffix, array_len);
+                let slices = ctors.filter_map(|c| c.as_slice()).map(|s| s.kind);
+                split_self.split(slices);
+                split_self.iter().map(Slice).collect()
+            }
             // Any other constructor can be used unchanged.
             _ => smallvec![self.clone()],
         }
@@ -542,7 +799,7 @@ pub(super) struct SplitWildcard {
 impl SplitWildcard {
     pub(super) fn new(pcx: PatCtxt<'_, '_>) -> Self {
         let cx = pcx.cx;
-        let make_range = |start, end, scalar| IntRange(IntRange::from_range(start, end, scalar));
+        let make_range = |start, end| IntRange(IntRange::from_range(start, end));
 
         // Unhandled types are treated as non-exhaustive. Being explicit here instead of falling
         // to catchall arm to ease further implementation.
@@ -557,9 +814,20 @@ impl SplitWildcard {
         // Invariant: this is empty if and only if the type is uninhabited (as determined by
         // `cx.is_uninhabited()`).
         let all_ctors = match pcx.ty.kind(Interner) {
-            TyKind::Scalar(Scalar::Bool) => smallvec![make_range(0, 1, Scalar::Bool)],
-            // TyKind::Array(..) if ... => unhandled(),
-            TyKind::Array(..) | TyKind::Slice(..) => unhandled(),
+            TyKind::Scalar(Scalar::Bool) => smallvec![make_range(0, 1)],
+            TyKind::Array(sub_ty, len) if try_const_usize(len).is_some() => {
+                let len = try_const_usize(len).unwrap() as usize;
+                if len != 0 && cx.is_uninhabited(sub_ty) {
+                    smallvec![]
+                } else {
+                    smallvec![Slice(Slice::new(Some(len), VarLen(0, 0)))]
+                }
+            }
+            // Treat arrays of a constant but unknown length like slices.
+            TyKind::Array(sub_ty, _) | TyKind::Slice(sub_ty) => {
+                let kind = if cx.is_uninhabited(sub_ty) { FixedLen(0) } else { VarLen(0, 0) };
+                smallvec![Slice(Slice::new(None, kind))]
+            }
             TyKind::Adt(AdtId(hir_def::AdtId::EnumId(enum_id)), subst) => {
                 let enum_data = cx.db.enum_data(*enum_id);
 
@@ -610,8 +878,17 @@ impl SplitWildcard {
                 }
                 ctors
             }
-            TyKind::Scalar(Scalar::Char) => unhandled(),
-            TyKind::Scalar(Scalar::Int(..) | Scalar::Uint(..)) => unhandled(),
+            TyKind::Scalar(Scalar::Char) => smallvec![
+                // The valid Unicode Scalar Values, without the surrogates.
+                make_range('\u{0000}' as u128, '\u{D7FF}' as u128),
+                make_range('\u{E000}' as u128, '\u{10FFFF}' as u128),
+            ],
+            &TyKind::Scalar(scalar @ (Scalar::Int(..) | Scalar::Uint(..))) => {
+                match IntRange::type_bounds(scalar) {
+                    Some((lo, hi)) => smallvec![make_range(lo, hi)],
+                    None => unhandled(),
+                }
+            }
             TyKind::Never if !cx.feature_exhaustive_patterns() && !pcx.is_top_level => {
                 smallvec![NonExhaustive]
             }
@@ -817,7 +1094,16 @@ impl<'p> Fields<'p> {
                     Fields::wildcards_from_tys(cx, once(ty.clone()))
                 }
             },
-            Slice(slice) => match slice._unimplemented {},
+            Slice(slice) => match ty.kind(Interner) {
+                TyKind::Slice(ty) | TyKind::Array(ty, _) => {
+                    let arity = slice.arity();
+                    Fields::wildcards_from_tys(cx, (0..arity).map(|_| ty.clone()))
+                }
+                _ => {
+                    never!("bad slice pattern {:?} {:?}", constructor, ty);
+                    Fields::empty()
+                }
+            },
             Str(..)
             | FloatRange(..)
             | IntRange(..)
@@ -970,6 +1256,23 @@ impl<'p> DeconstructedPat<'p> {
                 ctor = IntRange(IntRange::from_bool(value));
                 fields = Fields::empty();
             }
+            &PatKind::Range { lo, hi } => {
+                ctor = IntRange(IntRange::from_range(lo, hi));
+                fields = Fields::empty();
+            }
+            PatKind::Slice { prefix, slice, suffix } => {
+                let array_len = match pat.ty.kind(Interner) {
+                    TyKind::Array(_, len) => try_const_usize(len).map(|len| len as usize),
+                    _ => None,
+                };
+                let kind = if slice.is_some() {
+                    VarLen(prefix.len(), suffix.len())
+                } else {
+                    FixedLen(prefix.len() + suffix.len())
+                };
+                ctor = Slice(Slice::new(array_len, kind));
+                fields = Fields::from_iter(cx, prefix.iter().chain(suffix).map(mkpat));
+            }
             PatKind::Or { .. } => {
                 ctor = Or;
                 let pats: SmallVec<[_; 2]> = expand_or_pat(pat).into_iter().map(mkpat).collect();
@@ -1021,7 +1324,31 @@ impl<'p> DeconstructedPat<'p> {
                     PatKind::Wild
                 }
             },
-            &Slice(slice) => match slice._unimplemented {},
+            &Slice(slice) => match slice.kind {
+                FixedLen(_) => {
+                    PatKind::Slice { prefix: subpatterns.collect(), slice: None, suffix: vec![] }
+                }
+                VarLen(prefix, _) => {
+                    let mut subpatterns = subpatterns.peekable();
+                    let mut prefix: Vec<_> = subpatterns.by_ref().take(prefix).collect();
+                    if slice.array_len.is_some() {
+                        // Improves diagnostics a bit: if the type is a known-size array, instead
+                        // of reporting `[x, _, .., _, y]`, we prefer to report `[x, .., y]`.
+                        // This is incorrect if the size is not known, since `[_, ..]` captures
+                        // arrays of lengths `>= 1` whereas `[..]` captures any length.
+                        while prefix.last().map_or(false, |p| matches!(*p.kind, PatKind::Wild)) {
+                            prefix.pop();
+                        }
+                        while subpatterns.peek().map_or(false, |p| matches!(*p.kind, PatKind::Wild))
+                        {
+                            subpatterns.next();
+                        }
+                    }
+                    let suffix: Vec<_> = subpatterns.collect();
+                    let wild = Pat { ty: self.ty.clone(), kind: Box::new(PatKind::Wild) };
+                    PatKind::Slice { prefix, slice: Some(wild), suffix }
+                }
+            },
             &Str(void) => match void {},
             &FloatRange(void) => match void {},
             IntRange(range) => return range.to_pat(cx, self.ty.clone()),
@@ -1072,7 +1399,31 @@ impl<'p> DeconstructedPat<'p> {
             (Slice(self_slice), Slice(other_slice))
                 if self_slice.arity() != other_slice.arity() =>
             {
-                match self_slice._unimplemented {}
+                // The only non-trivial case: two slices of different arity. `other_slice` is
+                // guaranteed to have a larger arity, so we fill the middle part with enough
+                // wildcards to reach the length of the new, larger slice.
+                match self_slice.kind {
+                    FixedLen(_) => {
+                        never!("{:?} doesn't cover {:?}", self_slice, other_slice);
+                        self.fields.iter_patterns().collect()
+                    }
+                    VarLen(prefix, suffix) => {
+                        let inner_ty = match self.ty.kind(Interner) {
+                            TyKind::Slice(ty) | TyKind::Array(ty, _) => ty.clone(),
+                            _ => {
+                                never!("bad slice pattern {:?} {:?}", self.ctor, self.ty);
+                                return self.fields.iter_patterns().collect();
+                            }
+                        };
+                        let prefix = &self.fields.fields[..prefix];
+                        let suffix = &self.fields.fields[self_slice.arity() - suffix..];
+                        let wildcard: &_ =
+                            cx.pattern_arena.alloc(DeconstructedPat::wildcard(inner_ty));
+                        let extra_wildcards = other_slice.arity() - self_slice.arity();
+                        let extra_wildcards = (0..extra_wildcards).map(|_| wildcard);
+                        prefix.iter().chain(extra_wildcards).chain(suffix).collect()
+                    }
+                }
             }
             _ => self.fields.iter_patterns().collect(),
         }
@@ -0,0 +1,91 @@
+//! Checks the calls of `transmute` against the layouts of their types for the target: the types
+//! must have the same size (rustc's E0512), and transmuting pointers shouldn't make them point to
+//! values needing a greater alignment than the ones they pointed to.
+
+use hir_def::{
+    hir::{Expr, ExprId},
+    DefWithBodyId, FunctionId, HasModule, ItemContainerId, Lookup,
+};
+
+use crate::{db::HirDatabase, layout::layout_of_ty, Interner, Ty, TyExt, TyKind};
+
+pub struct TransmuteMismatch {
+    /// The call of `transmute`.
+    pub call: ExprId,
+    pub from: Ty,
+    pub to: Ty,
+    pub kind: TransmuteMismatchKind,
+}
+
+pub enum TransmuteMismatchKind {
+    /// The types don't have the same size, in bytes.
+    Size { from: u64, to: u64 },
+    /// The types are pointers, and the pointee of `to` needs a greater alignment than the one of
+    /// `from`, in bytes.
+    Alignment { from: u64, to: u64 },
+}
+
+/// Returns the calls of `transmute` in the body of `def` whose types have different sizes or
+/// alignments. Types whose layout isn't known, like generic ones, are not checked.
+pub fn transmute_mismatches(db: &dyn HirDatabase, def: DefWithBodyId) -> Vec<TransmuteMismatch> {
+    let infer = db.infer(def);
+    let body = db.body(def);
+    let krate = def.module(db.upcast()).krate();
+    let mut res = Vec::new();
+    for (call, expr) in body.exprs.iter() {
+        let &Expr::Call { callee, .. } = expr else { continue };
+        let callee_ty = &infer[callee];
+        let TyKind::FnDef(_, subst) = callee_ty.kind(Interner) else { continue };
+        let Some(func) = callee_ty.as_fn_def(db) else { continue };
+        if !is_transmute(db, func) {
+            continue;
+        }
+        let mut args = subst.iter(Interner).filter_map(|it| it.ty(Interner));
+        let (Some(from), Some(to)) = (args.next(), args.next()) else { continue };
+        let (Ok(from_layout), Ok(to_layout)) =
+            (layout_of_ty(db, from, krate), layout_of_ty(db, to, krate))
+        else {
+            continue;
+        };
+        let (from_size, to_size) = (from_layout.size.bytes(), to_layout.size.bytes());
+        let kind = if from_size != to_size {
+            TransmuteMismatchKind::Size { from: from_size, to: to_size }
+        } else {
+            let (Some((from_pointee, ..)), Some((to_pointee, ..))) =
+                (from.as_reference_or_ptr(), to.as_reference_or_ptr())
+            else {
+                continue;
+            };
+            let (Ok(from_pointee), Ok(to_pointee)) =
+                (layout_of_ty(db, from_pointee, krate), layout_of_ty(db, to_pointee, krate))
+            else {
+                continue;
+            };
+            let (from_align, to_align) =
+                (from_pointee.align.abi.bytes(), to_pointee.align.abi.bytes());
+            if from_align >= to_align {
+                continue;
+            }
+            TransmuteMismatchKind::Alignment { from: from_align, to: to_align }
+        };
+        res.push(TransmuteMismatch { call, from: from.clone(), to: to.clone(), kind });
+    }
+    res
+}
+
+fn is_transmute(db: &dyn HirDatabase, func: FunctionId) -> bool {
+    let data = db.function_data(func);
+    if data.name.as_text().as_deref() != Some("transmute") {
+        return false;
+    }
+    match data.abi.as_deref() {
+        Some(abi) => abi == "rust-intrinsic",
+        None => match func.lookup(db.upcast()).container {
+            ItemContainerId::ExternBlockId(block) => {
+                let id = block.lookup(db.upcast()).id;
+                id.item_tree(db.upcast())[id.value].abi.as_deref() == Some("rust-intrinsic")
+            }
+            _ => false,
+        },
+    }
+}
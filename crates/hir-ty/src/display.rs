@@ -7,7 +7,6 @@ use std::fmt::{self, Debug};
 use base_db::CrateId;
 use chalk_ir::{BoundVar, TyKind};
 use hir_def::{
-    data::adt::VariantData,
     db::DefDatabase,
     find_path,
     generics::{TypeOrConstParamData, TypeParamProvenance},
@@ -16,7 +15,7 @@ use hir_def::{
     path::{Path, PathKind},
     type_ref::{TraitBoundModifier, TypeBound, TypeRef},
     visibility::Visibility,
-    HasModule, ItemContainerId, LocalFieldId, Lookup, ModuleDefId, ModuleId, TraitId,
+    HasModule, ItemContainerId, Lookup, ModuleDefId, ModuleId, TraitId,
 };
 use hir_expand::{hygiene::Hygiene, name::Name};
 use intern::{Internable, Interned};
@@ -25,9 +24,9 @@ use smallvec::SmallVec;
 use stdx::never;
 
 use crate::{
+    consteval::render_const_scalar,
     db::HirDatabase,
     from_assoc_type_id, from_foreign_def_id, from_placeholder_idx,
-    layout::layout_of_ty,
     lt_from_placeholder_idx,
     mapping::from_chalk,
     mir::pad16,
@@ -35,7 +34,7 @@ use crate::{
     utils::{self, generics},
     AdtId, AliasEq, AliasTy, Binders, CallableDefId, CallableSig, Const, ConstScalar, ConstValue,
     DomainGoal, GenericArg, ImplTraitId, Interner, Lifetime, LifetimeData, LifetimeOutlives,
-    MemoryMap, Mutability, OpaqueTy, ProjectionTy, ProjectionTyExt, QuantifiedWhereClause, Scalar,
+    Mutability, OpaqueTy, ProjectionTy, ProjectionTyExt, QuantifiedWhereClause, Scalar,
     Substitution, TraitRef, TraitRefExt, Ty, TyExt, WhereClause,
 };
 
@@ -239,6 +238,14 @@ impl<'a> HirFormatter<'a> {
     pub fn omit_verbose_types(&self) -> bool {
         self.omit_verbose_types
     }
+
+    /// Returns the module paths are written from when displaying for source files.
+    pub(crate) fn source_code_module(&self) -> Option<ModuleId> {
+        match self.display_target {
+            DisplayTarget::SourceCode { module_id, .. } => Some(module_id),
+            DisplayTarget::Diagnostics | DisplayTarget::Test => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -420,6 +427,10 @@ impl HirDisplay for Const {
             ConstValue::Concrete(c) => match &c.interned {
                 ConstScalar::Bytes(b, m) => render_const_scalar(f, &b, m, &data.ty),
                 ConstScalar::Unknown => f.write_char('_'),
+                ConstScalar::UnevaluatedConst(c, _) => match &f.db.const_data(*c).name {
+                    Some(name) => write!(f, "{name}"),
+                    None => f.write_char('_'),
+                },
             },
         }
     }
@@ -447,137 +458,6 @@ impl HirDisplay for HexifiedConst {
     }
 }
 
-fn render_const_scalar(
-    f: &mut HirFormatter<'_>,
-    b: &[u8],
-    memory_map: &MemoryMap,
-    ty: &Ty,
-) -> Result<(), HirDisplayError> {
-    match ty.kind(Interner) {
-        chalk_ir::TyKind::Scalar(s) => match s {
-            Scalar::Bool => write!(f, "{}", if b[0] == 0 { false } else { true }),
-            Scalar::Char => {
-                let x = u128::from_le_bytes(pad16(b, false)) as u32;
-                let Ok(c) = char::try_from(x) else {
-                    return f.write_str("<unicode-error>");
-                };
-                write!(f, "{c:?}")
-            }
-            Scalar::Int(_) => {
-                let x = i128::from_le_bytes(pad16(b, true));
-                write!(f, "{x}")
-            }
-            Scalar::Uint(_) => {
-                let x = u128::from_le_bytes(pad16(b, false));
-                write!(f, "{x}")
-            }
-            Scalar::Float(fl) => match fl {
-                chalk_ir::FloatTy::F32 => {
-                    let x = f32::from_le_bytes(b.try_into().unwrap());
-                    write!(f, "{x:?}")
-                }
-                chalk_ir::FloatTy::F64 => {
-                    let x = f64::from_le_bytes(b.try_into().unwrap());
-                    write!(f, "{x:?}")
-                }
-            },
-        },
-        chalk_ir::TyKind::Ref(_, _, t) => match t.kind(Interner) {
-            chalk_ir::TyKind::Str => {
-                let addr = usize::from_le_bytes(b[0..b.len() / 2].try_into().unwrap());
-                let bytes = memory_map.0.get(&addr).map(|x| &**x).unwrap_or(&[]);
-                let s = std::str::from_utf8(bytes).unwrap_or("<utf8-error>");
-                write!(f, "{s:?}")
-            }
-            _ => f.write_str("<ref-not-supported>"),
-        },
-        chalk_ir::TyKind::Tuple(_, subst) => {
-            // FIXME: Remove this line. If the target data layout is independent
-            // of the krate, the `db.target_data_layout` and its callers like `layout_of_ty` don't need
-            // to get krate. Otherwise, we need to get krate from the final callers of the hir display
-            // infrastructure and have it here as a field on `f`.
-            let krate = *f.db.crate_graph().crates_in_topological_order().last().unwrap();
-            let Ok(layout) = layout_of_ty(f.db, ty, krate) else {
-                return f.write_str("<layout-error>");
-            };
-            f.write_str("(")?;
-            let mut first = true;
-            for (id, ty) in subst.iter(Interner).enumerate() {
-                if first {
-                    first = false;
-                } else {
-                    f.write_str(", ")?;
-                }
-                let ty = ty.assert_ty_ref(Interner); // Tuple only has type argument
-                let offset = layout.fields.offset(id).bytes_usize();
-                let Ok(layout) = layout_of_ty(f.db, &ty, krate) else {
-                    f.write_str("<layout-error>")?;
-                    continue;
-                };
-                let size = layout.size.bytes_usize();
-                render_const_scalar(f, &b[offset..offset + size], memory_map, &ty)?;
-            }
-            f.write_str(")")
-        }
-        chalk_ir::TyKind::Adt(adt, subst) => match adt.0 {
-            hir_def::AdtId::StructId(s) => {
-                let data = f.db.struct_data(s);
-                let Ok(layout) = f.db.layout_of_adt(adt.0, subst.clone()) else {
-                    return f.write_str("<layout-error>");
-                };
-                match data.variant_data.as_ref() {
-                    VariantData::Record(fields) | VariantData::Tuple(fields) => {
-                        let field_types = f.db.field_types(s.into());
-                        let krate = adt.0.module(f.db.upcast()).krate();
-                        let render_field = |f: &mut HirFormatter<'_>, id: LocalFieldId| {
-                            let offset = layout
-                                .fields
-                                .offset(u32::from(id.into_raw()) as usize)
-                                .bytes_usize();
-                            let ty = field_types[id].clone().substitute(Interner, subst);
-                            let Ok(layout) = layout_of_ty(f.db, &ty, krate) else {
-                                return f.write_str("<layout-error>");
-                            };
-                            let size = layout.size.bytes_usize();
-                            render_const_scalar(f, &b[offset..offset + size], memory_map, &ty)
-                        };
-                        let mut it = fields.iter();
-                        if matches!(data.variant_data.as_ref(), VariantData::Record(_)) {
-                            write!(f, "{} {{", data.name)?;
-                            if let Some((id, data)) = it.next() {
-                                write!(f, " {}: ", data.name)?;
-                                render_field(f, id)?;
-                            }
-                            for (id, data) in it {
-                                write!(f, ", {}: ", data.name)?;
-                                render_field(f, id)?;
-                            }
-                            write!(f, " }}")?;
-                        } else {
-                            let mut it = it.map(|x| x.0);
-                            write!(f, "{}(", data.name)?;
-                            if let Some(id) = it.next() {
-                                render_field(f, id)?;
-                            }
-                            for id in it {
-                                write!(f, ", ")?;
-                                render_field(f, id)?;
-                            }
-                            write!(f, ")")?;
-                        }
-                        return Ok(());
-                    }
-                    VariantData::Unit => write!(f, "{}", data.name),
-                }
-            }
-            hir_def::AdtId::UnionId(u) => write!(f, "{}", f.db.union_data(u).name),
-            hir_def::AdtId::EnumId(_) => f.write_str("<enum-not-supported>"),
-        },
-        chalk_ir::TyKind::FnDef(..) => ty.hir_fmt(f),
-        _ => f.write_str("<not-supported>"),
-    }
-}
-
 impl HirDisplay for BoundVar {
     fn hir_fmt(&self, f: &mut HirFormatter<'_>) -> Result<(), HirDisplayError> {
         write!(f, "?{}.{}", self.debruijn.depth(), self.index)
@@ -656,10 +536,7 @@ impl HirDisplay for Ty {
                     }))
                     | TyKind::OpaqueType(opaque_ty_id, parameters) => {
                         let impl_trait_id = db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
-                        if let ImplTraitId::ReturnTypeImplTrait(func, idx) = impl_trait_id {
-                            let datas = db
-                                .return_type_impl_traits(func)
-                                .expect("impl trait id without data");
+                        if let Some((datas, idx)) = impl_trait_id.impl_traits(db) {
                             let data =
                                 (*datas).as_ref().map(|rpit| rpit.impl_traits[idx].bounds.clone());
                             let bounds = data.substitute(Interner, parameters);
@@ -667,9 +544,8 @@ impl HirDisplay for Ty {
 
                             // Don't count Sized but count when it absent
                             // (i.e. when explicit ?Sized bound is set).
-                            let default_sized = SizedByDefault::Sized {
-                                anchor: func.lookup(db.upcast()).module(db.upcast()).krate(),
-                            };
+                            let default_sized =
+                                SizedByDefault::Sized { anchor: impl_trait_id.krate(db) };
                             let sized_bounds = bounds
                                 .skip_binders()
                                 .iter()
@@ -922,13 +798,13 @@ impl HirDisplay for Ty {
                 }
                 let impl_trait_id = db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
                 match impl_trait_id {
-                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                        let datas =
-                            db.return_type_impl_traits(func).expect("impl trait id without data");
+                    ImplTraitId::ReturnTypeImplTrait(..) | ImplTraitId::TypeAliasImplTrait(..) => {
+                        let (datas, idx) =
+                            impl_trait_id.impl_traits(db).expect("impl trait id without data");
                         let data =
                             (*datas).as_ref().map(|rpit| rpit.impl_traits[idx].bounds.clone());
                         let bounds = data.substitute(Interner, &parameters);
-                        let krate = func.lookup(db.upcast()).module(db.upcast()).krate();
+                        let krate = impl_trait_id.krate(db);
                         write_bounds_like_dyn_trait_with_prefix(
                             f,
                             "impl",
@@ -1080,13 +956,13 @@ impl HirDisplay for Ty {
                 }
                 let impl_trait_id = db.lookup_intern_impl_trait_id(opaque_ty.opaque_ty_id.into());
                 match impl_trait_id {
-                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                        let datas =
-                            db.return_type_impl_traits(func).expect("impl trait id without data");
+                    ImplTraitId::ReturnTypeImplTrait(..) | ImplTraitId::TypeAliasImplTrait(..) => {
+                        let (datas, idx) =
+                            impl_trait_id.impl_traits(db).expect("impl trait id without data");
                         let data =
                             (*datas).as_ref().map(|rpit| rpit.impl_traits[idx].bounds.clone());
                         let bounds = data.substitute(Interner, &opaque_ty.substitution);
-                        let krate = func.lookup(db.upcast()).module(db.upcast()).krate();
+                        let krate = impl_trait_id.krate(db);
                         write_bounds_like_dyn_trait_with_prefix(
                             f,
                             "impl",
@@ -16,7 +16,7 @@
 use std::sync::Arc;
 use std::{convert::identity, ops::Index};
 
-use chalk_ir::{cast::Cast, DebruijnIndex, Mutability, Safety, Scalar, TypeFlags};
+use chalk_ir::{cast::Cast, BoundVar, DebruijnIndex, Mutability, Safety, Scalar, TypeFlags};
 use either::Either;
 use hir_def::{
     body::Body,
@@ -29,8 +29,8 @@ use hir_def::{
     path::{ModPath, Path},
     resolver::{HasResolver, ResolveValueResult, Resolver, TypeNs, ValueNs},
     type_ref::TypeRef,
-    AdtId, AssocItemId, DefWithBodyId, EnumVariantId, FieldId, FunctionId, ItemContainerId, Lookup,
-    TraitId, TypeAliasId, VariantId,
+    AdtId, AssocItemId, DefWithBodyId, EnumVariantId, FieldId, FunctionId, HasModule,
+    ItemContainerId, Lookup, ModuleDefId, TraitId, TypeAliasId, VariantId,
 };
 use hir_expand::name::{name, Name};
 use la_arena::{ArenaMap, Entry};
@@ -39,9 +39,9 @@ use stdx::{always, never};
 
 use crate::{
     db::HirDatabase, fold_tys, infer::coerce::CoerceMany, lower::ImplTraitLoweringMode,
-    static_lifetime, to_assoc_type_id, traits::FnTrait, AliasEq, AliasTy, ClosureId, DomainGoal,
-    GenericArg, Goal, ImplTraitId, InEnvironment, Interner, ProjectionTy, RpitId, Substitution,
-    TraitRef, Ty, TyBuilder, TyExt, TyKind,
+    make_binders, static_lifetime, traits::FnTrait, utils::generics, AliasEq, AliasTy, Binders,
+    ClosureId, DomainGoal, GenericArg, Goal, ImplTraitId, InEnvironment, Interner, RpitId,
+    Substitution, TraitRef, Ty, TyBuilder, TyExt, TyKind,
 };
 
 // This lint has a false positive here. See the link below for details.
@@ -52,7 +52,8 @@ pub use coerce::could_coerce;
 #[allow(unreachable_pub)]
 pub use unify::could_unify;
 
-pub(crate) use self::closure::{CaptureKind, CapturedItem, CapturedItemWithoutTy};
+pub(crate) use self::closure::CapturedItemWithoutTy;
+pub use self::closure::{CaptureKind, CapturedItem};
 
 pub(crate) mod unify;
 mod path;
@@ -65,6 +66,7 @@ mod mutability;
 /// The entry point of type inference.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
     let _p = profile::span("infer_query");
+    let _span = tracing::info_span!("infer_query", ?def).entered();
     let resolver = def.resolver(db.upcast());
     let body = db.body(def);
     let mut ctx = InferenceContext::new(db, def, &body, resolver);
@@ -127,6 +129,59 @@ pub(crate) fn normalize(db: &dyn HirDatabase, owner: DefWithBodyId, ty: Ty) -> T
     table.resolve_completely(ty_with_vars)
 }
 
+/// Returns the hidden type of the `idx`th `impl Trait` of the type alias `alias`, bound by the
+/// generic parameters of the alias. It's inferred by the first body in the module of the alias, or
+/// one of its descendants, which is a defining use of it.
+// FIXME: Bodies in the block an alias is defined in aren't searched.
+pub(crate) fn type_alias_impl_trait_hidden_type(
+    db: &dyn HirDatabase,
+    alias: TypeAliasId,
+    idx: RpitId,
+) -> Option<Binders<Ty>> {
+    let module = alias.module(db.upcast());
+    let def_map = module.def_map(db.upcast());
+    let mut modules = vec![module.local_id];
+    while let Some(local_id) = modules.pop() {
+        let scope = &def_map[local_id].scope;
+        modules.extend(def_map[local_id].children.values().copied());
+        let items = scope.declarations().filter_map(|def| match def {
+            ModuleDefId::FunctionId(it) => Some(AssocItemId::FunctionId(it)),
+            ModuleDefId::ConstId(it) => Some(AssocItemId::ConstId(it)),
+            _ => None,
+        });
+        let impl_items = scope.impls().flat_map(|it| db.impl_data(it).items.clone());
+        for item in items.chain(impl_items) {
+            let body = match item {
+                AssocItemId::FunctionId(it) => DefWithBodyId::from(it),
+                AssocItemId::ConstId(it) => DefWithBodyId::from(it),
+                AssocItemId::TypeAliasId(_) => continue,
+            };
+            let infer = db.infer(body);
+            let Some((subst, ty)) = infer.type_of_tait.get(&(alias, idx)) else { continue };
+            // Replace the generic parameters of the body the alias is used with by the ones of
+            // the alias.
+            let ty = fold_tys(
+                ty.clone(),
+                |ty, binders| match ty.kind(Interner) {
+                    TyKind::Placeholder(_) => subst
+                        .iter(Interner)
+                        .position(|arg| arg.ty(Interner) == Some(&ty))
+                        .map_or_else(
+                            || TyKind::Error.intern(Interner),
+                            |index| {
+                                TyKind::BoundVar(BoundVar::new(binders, index)).intern(Interner)
+                            },
+                        ),
+                    _ => ty,
+                },
+                DebruijnIndex::INNERMOST,
+            );
+            return Some(make_binders(db, &generics(db.upcast(), alias.into()), ty));
+        }
+    }
+    None
+}
+
 /// Binding modes inferred for patterns.
 /// <https://doc.rust-lang.org/reference/patterns.html#binding-modes>
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -375,6 +430,9 @@ pub struct InferenceResult {
     pub type_of_pat: ArenaMap<PatId, Ty>,
     pub type_of_binding: ArenaMap<BindingId, Ty>,
     pub type_of_rpit: ArenaMap<RpitId, Ty>,
+    /// For each `impl Trait` of a type alias this body is a defining use of, records the generic
+    /// arguments of the alias and the hidden type.
+    pub type_of_tait: FxHashMap<(TypeAliasId, RpitId), (Substitution, Ty)>,
     /// Type of the result of `.into_iter()` on the for. `ExprId` is the one of the whole for loop.
     pub type_of_for_iterator: FxHashMap<ExprId, Ty>,
     type_mismatches: FxHashMap<ExprOrPatId, TypeMismatch>,
@@ -423,9 +481,14 @@ impl InferenceResult {
             _ => None,
         })
     }
-    pub(crate) fn closure_info(&self, closure: &ClosureId) -> &(Vec<CapturedItem>, FnTrait) {
+    pub fn closure_info(&self, closure: &ClosureId) -> &(Vec<CapturedItem>, FnTrait) {
         self.closure_info.get(closure).unwrap()
     }
+    /// Returns whether the closure captures anything from its environment, according to the
+    /// closure capture analysis.
+    pub fn closure_has_captures(&self, closure: &ClosureId) -> bool {
+        !self.closure_info(closure).0.is_empty()
+    }
 }
 
 impl Index<ExprId> for InferenceResult {
@@ -585,6 +648,10 @@ impl<'a> InferenceContext<'a> {
         for ty in result.type_of_rpit.values_mut() {
             *ty = table.resolve_completely(ty.clone());
         }
+        for (subst, ty) in result.type_of_tait.values_mut() {
+            *subst = table.resolve_completely(subst.clone());
+            *ty = table.resolve_completely(ty.clone());
+        }
         for ty in result.type_of_for_iterator.values_mut() {
             *ty = table.resolve_completely(ty.clone());
         }
@@ -662,6 +729,7 @@ impl<'a> InferenceContext<'a> {
         }
         for (ty, pat) in param_tys.into_iter().zip(self.body.params.iter()) {
             let ty = self.insert_type_vars(ty);
+            let ty = self.insert_inference_vars_for_tait(ty);
             let ty = self.normalize_associated_types_in(ty);
 
             self.infer_top_pat(*pat, &ty);
@@ -672,6 +740,7 @@ impl<'a> InferenceContext<'a> {
             .with_impl_trait_mode(ImplTraitLoweringMode::Opaque);
         let return_ty = ctx.lower_ty(return_ty);
         let return_ty = self.insert_type_vars(return_ty);
+        let return_ty = self.insert_inference_vars_for_tait(return_ty);
 
         let return_ty = if let Some(rpits) = self.db.return_type_impl_traits(func) {
             // RPIT opaque types use substitution of their parent function.
@@ -712,7 +781,8 @@ impl<'a> InferenceContext<'a> {
                 };
                 let idx = match self.db.lookup_intern_impl_trait_id(opaque_ty_id.into()) {
                     ImplTraitId::ReturnTypeImplTrait(_, idx) => idx,
-                    _ => unreachable!(),
+                    // Type alias impl traits outside of their defining scope.
+                    _ => return ty,
                 };
                 let bounds = (*rpits)
                     .map_ref(|rpits| rpits.impl_traits[idx].bounds.map_ref(|it| it.into_iter()));
@@ -738,6 +808,68 @@ impl<'a> InferenceContext<'a> {
         )
     }
 
+    /// Replaces the `impl Trait`s of type aliases this body is in the defining scope of with
+    /// inference variables, which all the uses in the body share, to infer their hidden types.
+    fn insert_inference_vars_for_tait<T>(&mut self, t: T) -> T
+    where
+        T: crate::HasInterner<Interner = Interner> + crate::TypeFoldable<Interner>,
+    {
+        fold_tys(
+            t,
+            |ty, _| {
+                let (opaque_ty_id, subst) = match ty.kind(Interner) {
+                    TyKind::OpaqueType(opaque_ty_id, subst) => (*opaque_ty_id, subst),
+                    _ => return ty,
+                };
+                let (alias, idx) = match self.db.lookup_intern_impl_trait_id(opaque_ty_id.into()) {
+                    ImplTraitId::TypeAliasImplTrait(alias, idx) => (alias, idx),
+                    _ => return ty,
+                };
+                if let Some((_, var)) = self.result.type_of_tait.get(&(alias, idx)) {
+                    return var.clone();
+                }
+                let taits = match self.db.type_alias_impl_traits(alias) {
+                    Some(taits) if self.is_defining_scope_of(alias) => taits,
+                    _ => return ty,
+                };
+                let var = self.table.new_type_var();
+                self.result.type_of_tait.insert((alias, idx), (subst.clone(), var.clone()));
+                let bounds = (*taits)
+                    .map_ref(|taits| taits.impl_traits[idx].bounds.map_ref(|it| it.into_iter()));
+                let var_subst = Substitution::from1(Interner, var.clone());
+                for bound in bounds {
+                    let predicate = bound.map(|it| it.cloned()).substitute(Interner, subst);
+                    let (var_predicate, binders) =
+                        predicate.substitute(Interner, &var_subst).into_value_and_skipped_binders();
+                    always!(binders.is_empty(Interner)); // quantified where clauses not yet handled
+                    let var_predicate = self.insert_inference_vars_for_tait(var_predicate);
+                    self.push_obligation(var_predicate.cast(Interner));
+                }
+                var
+            },
+            DebruijnIndex::INNERMOST,
+        )
+    }
+
+    /// Whether this body may constrain the hidden types of the `impl Trait`s of `alias`, that is
+    /// whether it's inside the module or the block the alias is defined in.
+    fn is_defining_scope_of(&self, alias: TypeAliasId) -> bool {
+        let alias_module = alias.module(self.db.upcast());
+        if let Some(block) = alias_module.containing_block() {
+            if self.body.blocks(self.db.upcast()).any(|(it, _)| it == block) {
+                return true;
+            }
+        }
+        let mut module = Some(self.owner.module(self.db.upcast()));
+        while let Some(it) = module {
+            if it == alias_module {
+                return true;
+            }
+            module = it.containing_module(self.db.upcast());
+        }
+        false
+    }
+
     fn infer_body(&mut self) {
         match self.return_coercion {
             Some(_) => self.infer_return(self.body.body_expr),
@@ -786,6 +918,7 @@ impl<'a> InferenceContext<'a> {
         let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
         let ty = ctx.lower_ty(type_ref);
         let ty = self.insert_type_vars(ty);
+        let ty = self.insert_inference_vars_for_tait(ty);
         self.normalize_associated_types_in(ty)
     }
 
@@ -891,8 +1024,7 @@ impl<'a> InferenceContext<'a> {
         &mut self,
         inner_ty: Ty,
         assoc_ty: Option<TypeAliasId>,
-        // FIXME(GATs): these are args for the trait ref, args for assoc type itself should be
-        // handled when we support them.
+        // These are the args of the trait ref, the args of generic associated types are inferred.
         params: &[GenericArg],
     ) -> Ty {
         match assoc_ty {
@@ -907,13 +1039,14 @@ impl<'a> InferenceContext<'a> {
                     .push(inner_ty)
                     .fill(|_| param_iter.next().unwrap())
                     .build();
-                let alias_eq = AliasEq {
-                    alias: AliasTy::Projection(ProjectionTy {
-                        associated_ty_id: to_assoc_type_id(res_assoc_ty),
-                        substitution: trait_ref.substitution.clone(),
-                    }),
-                    ty: ty.clone(),
-                };
+                let projection = TyBuilder::assoc_type_projection(
+                    self.db,
+                    res_assoc_ty,
+                    Some(trait_ref.substitution.clone()),
+                )
+                .fill_with_inference_vars(&mut self.table)
+                .build();
+                let alias_eq = AliasEq { alias: AliasTy::Projection(projection), ty: ty.clone() };
                 self.push_obligation(trait_ref.cast(Interner));
                 self.push_obligation(alias_eq.cast(Interner));
                 ty
@@ -1,6 +1,6 @@
 //! Inference of closure parameter types based on the closure's expected type.
 
-use std::{cmp, collections::HashMap, convert::Infallible, mem};
+use std::{cmp, collections::HashMap, convert::Infallible, iter, mem};
 
 use chalk_ir::{cast::Cast, AliasEq, AliasTy, FnSubst, Mutability, TyKind, WhereClause};
 use hir_def::{
@@ -10,14 +10,14 @@ use hir_def::{
     },
     lang_item::LangItem,
     resolver::{resolver_for_expr, ResolveValueResult, ValueNs},
-    FieldId, HasModule, VariantId,
+    DefWithBodyId, FieldId, HasModule, VariantId,
 };
 use hir_expand::name;
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
-use stdx::never;
 
 use crate::{
+    db::HirDatabase,
     mir::{BorrowKind, MirSpan, ProjectionElem},
     static_lifetime, to_chalk_trait_id,
     traits::FnTrait,
@@ -42,6 +42,14 @@ impl InferenceContext<'_> {
             None => return,
         };
 
+        // Deduce the signature from the `Fn` bounds of an expected type variable first, so that the
+        // parameters get their types even when the trait solver can't make progress on the bounds
+        // yet, like with the argument of `fn apply<F: for<'a> Fn(&'a T) -> &'a U>(f: F)`.
+        if let Some(sig) = self.deduce_sig_from_obligations(&expected_ty) {
+            let expected_sig_ty = TyKind::Function(sig).intern(Interner);
+            self.unify(sig_ty, &expected_sig_ty);
+        }
+
         // Deduction from where-clauses in scope, as well as fn-pointer coercion are handled here.
         let _ = self.coerce(Some(closure_expr), closure_ty, &expected_ty);
 
@@ -60,6 +68,46 @@ impl InferenceContext<'_> {
         }
     }
 
+    /// Deduces a signature from the pending `FnX<Args>` and `<_ as FnOnce<Args>>::Output == Ret`
+    /// obligations of the type variable `expected_ty`.
+    fn deduce_sig_from_obligations(&mut self, expected_ty: &Ty) -> Option<FnPointer> {
+        let fn_traits: SmallVec<[ChalkTraitId; 3]> =
+            utils::fn_traits(self.db.upcast(), self.owner.module(self.db.upcast()).krate())
+                .map(to_chalk_trait_id)
+                .collect();
+
+        let mut params = None;
+        let mut ret = None;
+        for clause in self.table.obligations_for_self_ty(expected_ty) {
+            match clause {
+                WhereClause::Implemented(trait_ref) if fn_traits.contains(&trait_ref.trait_id) => {
+                    let args = trait_ref.substitution.as_slice(Interner).get(1)?;
+                    params = Some(args.ty(Interner)?.clone());
+                }
+                WhereClause::AliasEq(AliasEq { alias: AliasTy::Projection(projection), ty }) => {
+                    let assoc_data = self.db.associated_ty_data(projection.associated_ty_id);
+                    if fn_traits.contains(&assoc_data.trait_id) {
+                        let args = projection.substitution.as_slice(Interner).get(1)?;
+                        params = Some(args.ty(Interner)?.clone());
+                        ret = Some(ty);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let params = params?;
+        let params = params.as_tuple()?.iter(Interner).map(|it| it.ty(Interner).cloned());
+        let ret = ret.unwrap_or_else(|| self.table.new_type_var());
+        let sig_tys = params.chain(iter::once(Some(ret))).collect::<Option<Vec<_>>>()?;
+        cov_mark::hit!(fn_bound_informs_call_site_closure_signature);
+        Some(FnPointer {
+            num_binders: 0,
+            sig: FnSig { abi: (), safety: chalk_ir::Safety::Safe, variadic: false },
+            substitution: FnSubst(Substitution::from_iter(Interner, sig_tys)),
+        })
+    }
+
     fn deduce_sig_from_dyn_ty(&self, dyn_ty: &DynTy) -> Option<FnPointer> {
         // Search for a predicate like `<$self as FnX<Args>>::Output == Ret`
 
@@ -115,9 +163,14 @@ impl HirPlace {
     fn ty(&self, ctx: &mut InferenceContext<'_>) -> Ty {
         let mut ty = ctx.table.resolve_completely(ctx.result[self.local].clone());
         for p in &self.projections {
-            ty = p.projected_ty(ty, ctx.db, |_, _| {
-                unreachable!("Closure field only happens in MIR");
-            });
+            ty = p.projected_ty(
+                ty,
+                ctx.db,
+                |_, _| {
+                    unreachable!("Closure field only happens in MIR");
+                },
+                ctx.owner.module(ctx.db.upcast()).krate(),
+            );
         }
         ty.clone()
     }
@@ -140,19 +193,62 @@ impl HirPlace {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub(crate) enum CaptureKind {
+pub enum CaptureKind {
     ByRef(BorrowKind),
     ByValue,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct CapturedItem {
+pub struct CapturedItem {
     pub(crate) place: HirPlace,
     pub(crate) kind: CaptureKind,
     pub(crate) span: MirSpan,
     pub(crate) ty: Ty,
 }
 
+impl CapturedItem {
+    /// The local binding whose (possibly projected) place is captured.
+    pub fn local(&self) -> BindingId {
+        self.place.local
+    }
+
+    pub fn kind(&self) -> CaptureKind {
+        self.kind
+    }
+
+    /// The type of the captured value, a reference to the place for captures by reference.
+    pub fn ty(&self) -> &Ty {
+        &self.ty
+    }
+
+    /// Renders the captured place, like `*a.b.0`. With disjoint captures (RFC 2229), closures
+    /// capture the fields they use instead of the whole local.
+    pub fn display_place(&self, owner: DefWithBodyId, db: &dyn HirDatabase) -> String {
+        let body = db.body(owner);
+        let mut result = body[self.place.local].name.to_smol_str().to_string();
+        for proj in &self.place.projections {
+            let field = match proj {
+                ProjectionElem::Deref => {
+                    result = format!("*{result}");
+                    continue;
+                }
+                ProjectionElem::Field(field) => {
+                    let data = field.parent.variant_data(db.upcast());
+                    data.fields()[field.local_id].name.to_smol_str().to_string()
+                }
+                ProjectionElem::TupleOrClosureField(idx) => idx.to_string(),
+                // Captured places don't index, see `place_of_expr`.
+                _ => continue,
+            };
+            if result.starts_with('*') {
+                result = format!("({result})");
+            }
+            result = format!("{result}.{field}");
+        }
+        result
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CapturedItemWithoutTy {
     pub(crate) place: HirPlace,
@@ -318,6 +414,40 @@ impl InferenceContext<'_> {
         }
     }
 
+    fn walk_block(&mut self, statements: &[Statement], tail: Option<ExprId>) {
+        for s in statements.iter() {
+            match s {
+                Statement::Let { pat, type_ref: _, initializer, else_branch } => {
+                    if let Some(else_branch) = else_branch {
+                        self.consume_expr(*else_branch);
+                        if let Some(initializer) = initializer {
+                            self.consume_expr(*initializer);
+                        }
+                        return;
+                    }
+                    if let Some(initializer) = initializer {
+                        self.walk_expr(*initializer);
+                        if let Some(place) = self.place_of_expr(*initializer) {
+                            let ty = self.expr_ty(*initializer);
+                            self.consume_with_pat(
+                                place,
+                                ty,
+                                BindingAnnotation::Unannotated,
+                                *pat,
+                            );
+                        }
+                    }
+                }
+                Statement::Expr { expr, has_semi: _ } => {
+                    self.consume_expr(*expr);
+                }
+            }
+        }
+        if let Some(tail) = tail {
+            self.consume_expr(tail);
+        }
+    }
+
     fn walk_expr_without_adjust(&mut self, tgt_expr: ExprId) {
         match &self.body[tgt_expr] {
             Expr::If { condition, then_branch, else_branch } => {
@@ -327,41 +457,10 @@ impl InferenceContext<'_> {
                     self.consume_expr(expr);
                 }
             }
-            Expr::Async { statements, tail, .. }
-            | Expr::Const { statements, tail, .. }
+            Expr::Const { statements, tail, .. }
             | Expr::Unsafe { statements, tail, .. }
             | Expr::Block { statements, tail, .. } => {
-                for s in statements.iter() {
-                    match s {
-                        Statement::Let { pat, type_ref: _, initializer, else_branch } => {
-                            if let Some(else_branch) = else_branch {
-                                self.consume_expr(*else_branch);
-                                if let Some(initializer) = initializer {
-                                    self.consume_expr(*initializer);
-                                }
-                                return;
-                            }
-                            if let Some(initializer) = initializer {
-                                self.walk_expr(*initializer);
-                                if let Some(place) = self.place_of_expr(*initializer) {
-                                    let ty = self.expr_ty(*initializer);
-                                    self.consume_with_pat(
-                                        place,
-                                        ty,
-                                        BindingAnnotation::Unannotated,
-                                        *pat,
-                                    );
-                                }
-                            }
-                        }
-                        Statement::Expr { expr, has_semi: _ } => {
-                            self.consume_expr(*expr);
-                        }
-                    }
-                }
-                if let Some(tail) = tail {
-                    self.consume_expr(*tail);
-                }
+                self.walk_block(statements, *tail);
             }
             Expr::While { condition, body, label: _ }
             | Expr::For { iterable: condition, pat: _, body, label: _ } => {
@@ -462,14 +561,10 @@ impl InferenceContext<'_> {
                 self.select_from_expr(*base);
                 self.consume_expr(*index);
             }
-            Expr::Closure { .. } => {
-                let ty = self.expr_ty(tgt_expr);
-                let TyKind::Closure(id, _) = ty.kind(Interner) else {
-                    never!("closure type is always closure");
-                    return;
-                };
+            Expr::Closure { .. } | Expr::Async { .. } => {
+                let id = self.db.intern_closure((self.owner, tgt_expr)).into();
                 let (captures, _) =
-                    self.result.closure_info.get(id).expect(
+                    self.result.closure_info.get(&id).expect(
                         "We sort closures, so we should always have data for inner closures",
                     );
                 let mut cc = mem::take(&mut self.current_captures);
@@ -486,7 +581,8 @@ impl InferenceContext<'_> {
             | Expr::Continue { .. }
             | Expr::Path(_)
             | Expr::Literal(_)
-            | Expr::Underscore => (),
+            | Expr::Underscore
+            | Expr::OffsetOf(_) => (),
         }
     }
 
@@ -738,10 +834,17 @@ impl InferenceContext<'_> {
     fn analyze_closure(&mut self, closure: ClosureId) -> FnTrait {
         let (_, root) = self.db.lookup_intern_closure(closure.into());
         self.current_closure = Some(closure);
-        let Expr::Closure { body, capture_by, .. } = &self.body[root] else {
-            unreachable!("Closure expression id is always closure");
+        let capture_by = match &self.body[root] {
+            &Expr::Closure { body, capture_by, .. } => {
+                self.consume_expr(body);
+                capture_by
+            }
+            Expr::Async { statements, tail, capture_by, .. } => {
+                self.walk_block(statements, *tail);
+                *capture_by
+            }
+            _ => unreachable!("Closure expression id is always a closure or an async block"),
         };
-        self.consume_expr(*body);
         for item in &self.current_captures {
             if matches!(item.kind, CaptureKind::ByRef(BorrowKind::Mut { .. }))
                 && !item.place.projections.contains(&ProjectionElem::Deref)
@@ -17,12 +17,16 @@ use stdx::always;
 use crate::{
     autoderef::{Autoderef, AutoderefKind},
     db::HirDatabase,
+    from_chalk_trait_id,
     infer::{
         Adjust, Adjustment, AutoBorrow, InferOk, InferenceContext, OverloadedDeref, PointerCast,
         TypeError, TypeMismatch,
     },
-    static_lifetime, Canonical, DomainGoal, FnPointer, FnSig, Guidance, InEnvironment, Interner,
-    Solution, Substitution, TraitEnvironment, Ty, TyBuilder, TyExt,
+    static_lifetime,
+    utils::all_super_trait_refs,
+    Binders, Canonical, DomainGoal, DynTy, FnPointer, FnSig, Guidance, InEnvironment, Interner,
+    QuantifiedWhereClause, QuantifiedWhereClauses, Solution, Substitution, TraitEnvironment,
+    TraitRef, Ty, TyBuilder, TyExt, WhereClause,
 };
 
 use super::unify::InferenceTable;
@@ -618,13 +622,24 @@ impl<'a> InferenceTable<'a> {
             _ => return Err(TypeError),
         };
 
+        let mut goals = Vec::new();
         let coerce_unsized_tref = {
             let b = TyBuilder::trait_ref(self.db, coerce_unsized_trait);
             if b.remaining() != 2 {
                 // The CoerceUnsized trait should have two generic params: Self and T.
                 return Err(TypeError);
             }
-            b.push(coerce_from).push(to_ty.clone()).build()
+            // Chalk doesn't know about trait object upcasting, so `dyn Sub: Unsize<dyn Super>` is
+            // checked here, and the solver is asked to unsize to `dyn Sub` with the auto traits
+            // of the target instead.
+            let mut upcasts = Vec::new();
+            let target = self
+                .upcasting_target(&coerce_from, to_ty, &mut upcasts)
+                .unwrap_or_else(|| to_ty.clone());
+            for (super_args, target_args) in upcasts {
+                goals.extend(self.try_unify(&super_args, &target_args)?.goals);
+            }
+            b.push(coerce_from).push(target).build()
         };
 
         let goal: InEnvironment<DomainGoal> =
@@ -665,7 +680,104 @@ impl<'a> InferenceTable<'a> {
             None => vec![unsize],
             Some((deref, autoref)) => vec![deref, autoref, unsize],
         };
-        success(adjustments, to_ty.clone(), vec![])
+        success(adjustments, to_ty.clone(), goals)
+    }
+
+    /// If `to_ty` is `from_ty` with a trait object upcast to one of its supertraits, like
+    /// `&dyn Super` for `&dyn Sub`, returns `from_ty` with the principal trait kept and the auto
+    /// traits and lifetime of the target instead, which the solver can unsize to.
+    ///
+    /// The generic arguments of the supertrait, which have to match those of the target, are
+    /// pushed to `upcasts`.
+    fn upcasting_target(
+        &mut self,
+        from_ty: &Ty,
+        to_ty: &Ty,
+        upcasts: &mut Vec<(Substitution, Substitution)>,
+    ) -> Option<Ty> {
+        let from_ty = self.resolve_ty_shallow(from_ty);
+        let to_ty = self.resolve_ty_shallow(to_ty);
+        let ty = match (from_ty.kind(Interner), to_ty.kind(Interner)) {
+            (TyKind::Ref(_, _, from_inner), TyKind::Ref(mt, lt, to_inner)) => {
+                let inner = self.upcasting_target(from_inner, to_inner, upcasts)?;
+                TyKind::Ref(*mt, lt.clone(), inner)
+            }
+            (TyKind::Raw(_, from_inner), TyKind::Raw(mt, to_inner)) => {
+                TyKind::Raw(*mt, self.upcasting_target(from_inner, to_inner, upcasts)?)
+            }
+            // Smart pointers like `Box<dyn Sub>`, where only one of the arguments is unsized.
+            (TyKind::Adt(from_id, from_subst), TyKind::Adt(to_id, to_subst))
+                if from_id == to_id =>
+            {
+                let mut upcast = false;
+                let mut subst = Vec::with_capacity(to_subst.len(Interner));
+                for (from_arg, to_arg) in from_subst.iter(Interner).zip(to_subst.iter(Interner)) {
+                    let arg = match (from_arg.ty(Interner), to_arg.ty(Interner)) {
+                        (Some(from_arg), Some(to_arg)) if !upcast => {
+                            self.upcasting_target(from_arg, to_arg, upcasts)
+                        }
+                        _ => None,
+                    };
+                    upcast |= arg.is_some();
+                    subst.push(arg.map_or_else(|| to_arg.clone(), |it| it.cast(Interner)));
+                }
+                if !upcast {
+                    return None;
+                }
+                TyKind::Adt(*to_id, Substitution::from_iter(Interner, subst))
+            }
+            (TyKind::Dyn(from_dyn), TyKind::Dyn(to_dyn)) => {
+                let from_principal = principal_trait_ref(&from_ty, from_dyn)?;
+                let to_principal = principal_trait_ref(&to_ty, to_dyn)?;
+                if from_principal.trait_id == to_principal.trait_id {
+                    return None;
+                }
+                let super_ref = all_super_trait_refs(self.db, from_principal, |it| {
+                    (it.trait_id == to_principal.trait_id).then_some(it)
+                })?;
+                // The `Self` types differ, only the other arguments have to match.
+                let args = |it: TraitRef| {
+                    Substitution::from_iter(Interner, it.substitution.iter(Interner).skip(1))
+                };
+                upcasts.push((args(super_ref), args(to_principal)));
+
+                // FIXME: The associated type bounds of the target, like the `Item = u32` of
+                // `dyn Iterator<Item = u32>`, aren't checked.
+                let db = self.db;
+                let is_auto = |clause: &QuantifiedWhereClause| match clause.skip_binders() {
+                    WhereClause::Implemented(trait_ref) => {
+                        db.trait_data(from_chalk_trait_id(trait_ref.trait_id)).is_auto
+                    }
+                    _ => false,
+                };
+                let bounds = from_dyn
+                    .bounds
+                    .skip_binders()
+                    .iter(Interner)
+                    .filter(|it| !is_auto(it))
+                    .chain(to_dyn.bounds.skip_binders().iter(Interner).filter(|it| is_auto(it)))
+                    .cloned();
+                TyKind::Dyn(DynTy {
+                    bounds: Binders::new(
+                        from_dyn.bounds.binders.clone(),
+                        QuantifiedWhereClauses::from_iter(Interner, bounds),
+                    ),
+                    lifetime: to_dyn.lifetime.clone(),
+                })
+            }
+            _ => return None,
+        };
+        Some(ty.intern(Interner))
+    }
+}
+
+/// Returns the principal trait of the trait object `ty`, with `ty` as its `Self` type.
+fn principal_trait_ref(ty: &Ty, dyn_ty: &DynTy) -> Option<TraitRef> {
+    let bounds =
+        dyn_ty.bounds.clone().substitute(Interner, &Substitution::from1(Interner, ty.clone()));
+    match bounds.interned().first()?.skip_binders() {
+        WhereClause::Implemented(trait_ref) => Some(trait_ref.clone()),
+        _ => None,
     }
 }
 
@@ -38,8 +38,8 @@ use crate::{
     static_lifetime, to_chalk_trait_id,
     traits::FnTrait,
     utils::{generics, Generics},
-    Adjust, Adjustment, AdtId, AutoBorrow, Binders, CallableDefId, FnPointer, FnSig, FnSubst,
-    Interner, Rawness, Scalar, Substitution, TraitRef, Ty, TyBuilder, TyExt,
+    Adjust, Adjustment, AdtId, AutoBorrow, Binders, CallableDefId, ClosureId, FnPointer, FnSig,
+    FnSubst, Interner, Rawness, Scalar, Substitution, TraitRef, Ty, TyBuilder, TyExt,
 };
 
 use super::{
@@ -170,7 +170,7 @@ impl<'a> InferenceContext<'a> {
                 })
                 .1
             }
-            Expr::Async { id, statements, tail } => {
+            Expr::Async { id, statements, tail, .. } => {
                 self.infer_async_block(tgt_expr, id, statements, tail)
             }
             &Expr::Loop { body, label } => {
@@ -276,19 +276,19 @@ impl<'a> InferenceContext<'a> {
                         let generator_id = self.db.intern_generator((self.owner, tgt_expr)).into();
                         let generator_ty = TyKind::Generator(generator_id, subst).intern(Interner);
 
-                        (None, generator_ty, Some((resume_ty, yield_ty)))
+                        // Generators capture their environment like closures do, so they share
+                        // the closure machinery for capture analysis.
+                        let closure_id = self.register_closure_like(tgt_expr);
+
+                        (Some(closure_id), generator_ty, Some((resume_ty, yield_ty)))
                     }
                     ClosureKind::Closure | ClosureKind::Async => {
-                        let closure_id = self.db.intern_closure((self.owner, tgt_expr)).into();
+                        let closure_id = self.register_closure_like(tgt_expr);
                         let closure_ty = TyKind::Closure(
                             closure_id,
                             Substitution::from1(Interner, sig_ty.clone()),
                         )
                         .intern(Interner);
-                        self.deferred_closures.entry(closure_id).or_default();
-                        if let Some(c) = self.current_closure {
-                            self.closure_dependencies.entry(c).or_default().push(closure_id);
-                        }
                         (Some(closure_id), closure_ty, None)
                     }
                 };
@@ -396,6 +396,7 @@ impl<'a> InferenceContext<'a> {
                     &indices_to_skip,
                     is_varargs,
                 );
+                let ret_ty = self.ret_ty_of_impl_method(&derefed_callee, ret_ty);
                 self.normalize_associated_types_in(ret_ty)
             }
             Expr::MethodCall { receiver, args, method_name, generic_args } => self
@@ -849,6 +850,7 @@ impl<'a> InferenceContext<'a> {
                 // expression reaching this branch is an error.
                 self.err_ty()
             }
+            Expr::OffsetOf(_) => TyKind::Scalar(Scalar::Uint(UintTy::Usize)).intern(Interner),
         };
         // use a new type variable if we got unknown here
         let ty = self.insert_type_vars_shallow(ty);
@@ -868,7 +870,9 @@ impl<'a> InferenceContext<'a> {
         tail: &Option<ExprId>,
     ) -> Ty {
         let ret_ty = self.table.new_type_var();
+        let closure_id = self.register_closure_like(tgt_expr);
         let prev_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
+        let prev_closure = mem::replace(&mut self.current_closure, Some(closure_id));
         let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
         let prev_ret_coercion =
             mem::replace(&mut self.return_coercion, Some(CoerceMany::new(ret_ty.clone())));
@@ -878,12 +882,24 @@ impl<'a> InferenceContext<'a> {
         });
 
         self.diverges = prev_diverges;
+        self.current_closure = prev_closure;
         self.return_ty = prev_ret_ty;
         self.return_coercion = prev_ret_coercion;
 
         self.lower_async_block_type_impl_trait(inner_ty, tgt_expr)
     }
 
+    /// Interns a closure id for a closure, generator or async block, and schedules its capture
+    /// analysis.
+    fn register_closure_like(&mut self, tgt_expr: ExprId) -> ClosureId {
+        let closure_id = self.db.intern_closure((self.owner, tgt_expr)).into();
+        self.deferred_closures.entry(closure_id).or_default();
+        if let Some(c) = self.current_closure {
+            self.closure_dependencies.entry(c).or_default().push(closure_id);
+        }
+        closure_id
+    }
+
     pub(crate) fn lower_async_block_type_impl_trait(
         &mut self,
         inner_ty: Ty,
@@ -1546,9 +1562,37 @@ impl<'a> InferenceContext<'a> {
             self.expected_inputs_for_expected_output(expected, ret_ty.clone(), param_tys.clone());
 
         self.check_call_arguments(tgt_expr, args, &expected_inputs, &param_tys, &[], is_varargs);
+        let ret_ty = self.ret_ty_of_impl_method(&method_ty, ret_ty);
         self.normalize_associated_types_in(ret_ty)
     }
 
+    /// Returns the return type of the impl method a call to the trait method `callee_ty` resolves
+    /// to, if the trait method returns `impl Trait`, like `async fn`s in traits do. The opaque
+    /// types of the trait method have no hidden types, unlike the ones of the impl method.
+    fn ret_ty_of_impl_method(&mut self, callee_ty: &Ty, ret_ty: Ty) -> Ty {
+        let callee_ty = self.table.resolve_completely(callee_ty.clone());
+        let TyKind::FnDef(fn_def, subst) = callee_ty.kind(Interner) else { return ret_ty };
+        let CallableDefId::FunctionId(func) = from_chalk(self.db, *fn_def) else { return ret_ty };
+        if !matches!(func.lookup(self.db.upcast()).container, ItemContainerId::TraitId(_))
+            || self.db.return_type_impl_traits(func).is_none()
+            || callee_ty.contains_unknown()
+        {
+            return ret_ty;
+        }
+        let (impl_func, impl_subst) = method_resolution::lookup_impl_method(
+            self.db,
+            self.table.trait_env.clone(),
+            func,
+            subst.clone(),
+        );
+        if impl_func == func {
+            return ret_ty;
+        }
+        let sig =
+            self.db.callable_item_signature(impl_func.into()).substitute(Interner, &impl_subst);
+        self.insert_type_vars(sig.ret().clone())
+    }
+
     fn expected_inputs_for_expected_output(
         &mut self,
         expected_output: &Expectation,
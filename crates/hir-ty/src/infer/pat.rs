@@ -255,9 +255,12 @@ impl<'a> InferenceContext<'a> {
                 self.infer_slice_pat(&expected, prefix, slice, suffix, default_bm)
             }
             Pat::Wild => expected.clone(),
-            Pat::Range { start, end } => {
-                let start_ty = self.infer_expr(*start, &Expectation::has_type(expected.clone()));
-                self.infer_expr(*end, &Expectation::has_type(start_ty))
+            Pat::Range { start, end, .. } => {
+                let mut ty = expected.clone();
+                for bound in start.iter().chain(end) {
+                    ty = self.infer_expr(*bound, &Expectation::has_type(ty));
+                }
+                ty
             }
             &Pat::Lit(expr) => {
                 // Don't emit type mismatches again, the expression lowering already did that.
@@ -18,7 +18,7 @@ use crate::{
     traits::FnTrait, AliasEq, AliasTy, BoundVar, Canonical, Const, ConstValue, DebruijnIndex,
     GenericArg, GenericArgData, Goal, Guidance, InEnvironment, InferenceVar, Interner, Lifetime,
     ParamKind, ProjectionTy, ProjectionTyExt, Scalar, Solution, Substitution, TraitEnvironment, Ty,
-    TyBuilder, TyExt, TyKind, VariableKind,
+    TyBuilder, TyExt, TyKind, VariableKind, WhereClause,
 };
 
 impl<'a> InferenceContext<'a> {
@@ -485,6 +485,50 @@ impl<'a> InferenceTable<'a> {
         infer_ok.goals.into_iter().for_each(|goal| self.register_obligation_in_env(goal));
     }
 
+    /// Returns the pending obligations whose self type is the type variable `ty`, like the bounds
+    /// of the generic parameter of a call whose argument is being inferred.
+    pub(crate) fn obligations_for_self_ty(&mut self, ty: &Ty) -> Vec<WhereClause> {
+        let root = |table: &mut Self, ty: &Ty| {
+            let ty = table
+                .var_unification_table
+                .normalize_ty_shallow(Interner, ty)
+                .unwrap_or_else(|| ty.clone());
+            match ty.kind(Interner) {
+                TyKind::InferenceVar(var, _) => {
+                    Some(table.var_unification_table.inference_var_root(*var))
+                }
+                _ => None,
+            }
+        };
+        let Some(var) = root(self, ty) else {
+            return Vec::new();
+        };
+        let mut result = Vec::new();
+        for canonicalized in self.pending_obligations.clone() {
+            let obligation: InEnvironment<Goal> = chalk_ir::Substitute::apply(
+                &canonicalized.free_vars,
+                canonicalized.value.value,
+                Interner,
+            );
+            let chalk_ir::GoalData::DomainGoal(chalk_ir::DomainGoal::Holds(clause)) =
+                obligation.goal.data(Interner)
+            else {
+                continue;
+            };
+            let self_ty = match clause {
+                WhereClause::Implemented(trait_ref) => trait_ref.self_type_parameter(Interner),
+                WhereClause::AliasEq(AliasEq { alias: AliasTy::Projection(it), .. }) => {
+                    it.self_type_parameter(self.db)
+                }
+                _ => continue,
+            };
+            if root(self, &self_ty) == Some(var) {
+                result.push(clause.clone());
+            }
+        }
+        result
+    }
+
     pub(crate) fn resolve_obligations_as_possible(&mut self) {
         let _span = profile::span("resolve_obligations_as_possible");
         let mut changed = true;
@@ -1,5 +1,7 @@
 //! Compute the binary representation of a type
 
+use std::iter;
+
 use base_db::CrateId;
 use chalk_ir::{AdtId, TyKind};
 use hir_def::{
@@ -13,8 +15,8 @@ use la_arena::{Idx, RawIdx};
 use stdx::never;
 
 use crate::{
-    consteval::try_const_usize, db::HirDatabase, layout::adt::struct_variant_idx, Interner,
-    Substitution, Ty,
+    consteval::try_const_usize, db::HirDatabase, infer::type_alias_impl_trait_hidden_type,
+    layout::adt::struct_variant_idx, mir::generator_closure_id, Interner, Substitution, Ty,
 };
 
 pub use self::{
@@ -56,6 +58,8 @@ pub enum LayoutError {
     HasPlaceholder,
     HasErrorType,
     NotImplemented,
+    /// The type is an extern type, which has no size nor alignment, even dynamically.
+    ExternType,
     Unknown,
 }
 
@@ -240,15 +244,20 @@ pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty, krate: CrateId) -> Result<Lay
             ptr.valid_range_mut().start = 1;
             Layout::scalar(dl, ptr)
         }
-        TyKind::OpaqueType(opaque_ty_id, _) => {
+        TyKind::OpaqueType(opaque_ty_id, subst) => {
             let impl_trait_id = db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
             match impl_trait_id {
                 crate::ImplTraitId::ReturnTypeImplTrait(func, idx) => {
                     let infer = db.infer(func.into());
                     layout_of_ty(db, &infer.type_of_rpit[idx], krate)?
                 }
+                crate::ImplTraitId::TypeAliasImplTrait(alias, idx) => {
+                    let hidden_ty = type_alias_impl_trait_hidden_type(db, alias, idx)
+                        .ok_or(LayoutError::Unknown)?;
+                    layout_of_ty(db, &hidden_ty.substitute(Interner, subst), krate)?
+                }
                 crate::ImplTraitId::AsyncBlockTypeImplTrait(_, _) => {
-                    return Err(LayoutError::NotImplemented)
+                    layout_of_generator(db, &cx, ty, krate)?
                 }
             }
         }
@@ -265,9 +274,8 @@ pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty, krate: CrateId) -> Result<Lay
             cx.univariant(dl, &fields, &ReprOptions::default(), StructKind::AlwaysSized)
                 .ok_or(LayoutError::Unknown)?
         }
-        TyKind::Generator(_, _) | TyKind::GeneratorWitness(_, _) => {
-            return Err(LayoutError::NotImplemented)
-        }
+        TyKind::Generator(_, _) => layout_of_generator(db, &cx, ty, krate)?,
+        TyKind::GeneratorWitness(_, _) => return Err(LayoutError::NotImplemented),
         TyKind::Error => return Err(LayoutError::HasErrorType),
         TyKind::AssociatedType(_, _)
         | TyKind::Alias(_)
@@ -277,6 +285,45 @@ pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty, krate: CrateId) -> Result<Lay
     })
 }
 
+/// Generators and async blocks store their upvars, followed by their state and the locals that are
+/// saved across suspension points.
+fn layout_of_generator(
+    db: &dyn HirDatabase,
+    cx: &LayoutCx<'_>,
+    ty: &Ty,
+    krate: CrateId,
+) -> Result<Layout, LayoutError> {
+    let dl = &*cx.current_data_layout();
+    let Some(closure) = generator_closure_id(db, ty) else {
+        return Err(LayoutError::Unknown);
+    };
+    let (def, _) = db.lookup_intern_closure(closure.into());
+    let infer = db.infer(def);
+    let (captures, _) = infer.closure_info(&closure);
+    let body = db.mir_body_for_closure(closure).map_err(|_| LayoutError::Unknown)?;
+    let Some(generator_layout) = &body.generator_layout else {
+        return Err(LayoutError::Unknown);
+    };
+    let mut state = scalar_unit(dl, Primitive::Int(Integer::I8, false));
+    // Unresumed, returned, poisoned and one state per suspension point.
+    state.valid_range_mut().end = 2 + generator_layout.resume_blocks.len() as u128;
+    let fields = captures
+        .iter()
+        .map(|x| layout_of_ty(db, &x.ty, krate))
+        .chain(iter::once(Ok(Layout::scalar(dl, state))))
+        .chain(
+            generator_layout
+                .saved_locals
+                .iter()
+                .map(|&local| layout_of_ty(db, &body.locals[local].ty, krate)),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+    let fields = fields.iter().collect::<Vec<_>>();
+    let fields = fields.iter().collect::<Vec<_>>();
+    cx.univariant(dl, &fields, &ReprOptions::default(), StructKind::AlwaysSized)
+        .ok_or(LayoutError::Unknown)
+}
+
 fn layout_of_unit(cx: &LayoutCx<'_>, dl: &TargetDataLayout) -> Result<Layout, LayoutError> {
     cx.univariant::<RustcEnumVariantIdx, &&Layout>(
         dl,
@@ -287,18 +334,32 @@ fn layout_of_unit(cx: &LayoutCx<'_>, dl: &TargetDataLayout) -> Result<Layout, La
     .ok_or(LayoutError::Unknown)
 }
 
+/// Returns the type of the last field of `pointee`, going through nested structs and tuples, which
+/// decides whether pointers to it carry metadata. Extern types are their own tail, and pointers to
+/// them are thin like the ones to sized types.
 fn struct_tail_erasing_lifetimes(db: &dyn HirDatabase, pointee: Ty) -> Ty {
-    match pointee.kind(Interner) {
-        TyKind::Adt(AdtId(hir_def::AdtId::StructId(i)), subst) => {
-            let data = db.struct_data(*i);
-            let mut it = data.variant_data.fields().iter().rev();
-            match it.next() {
-                Some((f, _)) => field_ty(db, (*i).into(), f, subst),
-                None => pointee,
+    // Bounds the walk for infinitely sized types like `struct S(u8, S);`.
+    const MAX_DEPTH: usize = 64;
+    let mut tail = pointee;
+    for _ in 0..MAX_DEPTH {
+        let next = match tail.kind(Interner) {
+            TyKind::Adt(AdtId(hir_def::AdtId::StructId(i)), subst) => {
+                let data = db.struct_data(*i);
+                let mut it = data.variant_data.fields().iter().rev();
+                match it.next() {
+                    Some((f, _)) => field_ty(db, (*i).into(), f, subst),
+                    None => return tail,
+                }
             }
-        }
-        _ => pointee,
+            TyKind::Tuple(_, subst) => match subst.iter(Interner).last() {
+                Some(it) => it.assert_ty_ref(Interner).clone(),
+                None => return tail,
+            },
+            _ => return tail,
+        };
+        tail = next;
     }
+    tail
 }
 
 fn field_ty(
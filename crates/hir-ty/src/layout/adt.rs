@@ -4,8 +4,9 @@ use std::{cmp, ops::Bound};
 
 use hir_def::{
     data::adt::VariantData,
+    hir::Expr,
     layout::{Integer, LayoutCalculator, ReprOptions, TargetDataLayout},
-    AdtId, EnumVariantId, HasModule, LocalEnumVariantId, VariantId,
+    AdtId, EnumId, EnumVariantId, HasModule, LocalEnumVariantId, VariantId,
 };
 use la_arena::RawIdx;
 use smallvec::SmallVec;
@@ -85,16 +86,12 @@ pub fn layout_of_adt_query(
                     db.const_eval_discriminant(EnumVariantId { parent: e, local_id: id.0 }).ok()?;
                 Some((id, d))
             }),
-            // FIXME: The current code for niche-filling relies on variant indices
+            // The current code for niche-filling relies on variant indices
             // instead of actual discriminants, so enums with
-            // explicit discriminants (RFC #2363) would misbehave and we should disable
-            // niche optimization for them.
-            // The code that do it in rustc:
-            // repr.inhibit_enum_layout_opt() || def
-            //     .variants()
-            //     .iter_enumerated()
-            //     .any(|(i, v)| v.discr != ty::VariantDiscr::Relative(i.as_u32()))
-            repr.inhibit_enum_layout_opt(),
+            // explicit discriminants (RFC #2363) would misbehave and we disable
+            // niche optimization for them, like rustc does.
+            repr.inhibit_enum_layout_opt()
+                || matches!(def, AdtId::EnumId(e) if has_explicit_discriminants(db, e)),
             !is_enum
                 && variants
                     .iter()
@@ -106,6 +103,13 @@ pub fn layout_of_adt_query(
     }
 }
 
+fn has_explicit_discriminants(db: &dyn HirDatabase, e: EnumId) -> bool {
+    db.enum_data(e).variants.iter().any(|(local_id, _)| {
+        let body = db.body(EnumVariantId { parent: e, local_id }.into());
+        body.exprs[body.body_expr] != Expr::Missing
+    })
+}
+
 fn layout_scalar_valid_range(db: &dyn HirDatabase, def: AdtId) -> (Bound<u128>, Bound<u128>) {
     let attrs = db.attrs(def.into());
     let get = |name| {
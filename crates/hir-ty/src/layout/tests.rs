@@ -6,7 +6,7 @@ use hir_def::db::DefDatabase;
 
 use crate::{
     db::HirDatabase,
-    layout::{Layout, LayoutError},
+    layout::{Layout, LayoutError, RustcEnumVariantIdx, TagEncoding, Variants},
     test_db::TestDB,
     Interner, Substitution,
 };
@@ -291,6 +291,35 @@ fn return_position_impl_trait() {
     }
 }
 
+#[test]
+fn type_alias_impl_trait() {
+    // Not stable yet, so these can't be compared with rustc.
+    check_size_and_align_expr(
+        r#"
+        trait T {}
+        impl T for i64 {}
+        type Alias = impl T;
+        fn foo() -> Alias { 2i64 }
+        foo()
+        "#,
+        "",
+        8,
+        8,
+    );
+    check_size_and_align_expr(
+        r#"
+        trait T {}
+        impl T for u8 {}
+        type Alias<X> = (X, impl T);
+        fn foo() -> Alias<u16> { (1, 2u8) }
+        foo()
+        "#,
+        "",
+        4,
+        2,
+    );
+}
+
 #[test]
 fn enums() {
     size_and_align! {
@@ -323,6 +352,11 @@ fn non_zero() {
         use core::num::NonZeroU8;
         struct Goal(Option<NonZeroU8>);
     }
+    size_and_align! {
+        minicore: non_zero, option;
+        use core::num::NonZeroU32;
+        struct Goal(Option<NonZeroU32>);
+    }
 }
 
 #[test]
@@ -335,6 +369,38 @@ fn niche_optimization() {
         minicore: option;
         struct Goal(Option<Option<bool>>);
     }
+    size_and_align! {
+        minicore: option;
+        struct Goal(Option<Option<Option<char>>>);
+    }
+    size_and_align! {
+        minicore: option;
+        struct Goal(Option<(u32, &'static [u8])>);
+    }
+    size_and_align! {
+        minicore: option;
+        enum Goal {
+            A(&'static u8),
+            B,
+            C,
+        }
+    }
+}
+
+#[test]
+fn niche_encoding() {
+    let layout = eval_goal("enum Goal { A(bool), B, C }", "").unwrap();
+    let Variants::Multiple { tag_encoding, .. } = &layout.variants else {
+        panic!("expected a multi variant layout, got {:?}", layout.variants);
+    };
+    let TagEncoding::Niche { untagged_variant, niche_variants, niche_start } = tag_encoding else {
+        panic!("expected a niche encoding, got {tag_encoding:?}");
+    };
+    // `B` and `C` are stored as the invalid values `2` and `3` of the `bool`.
+    let index = |variant: RustcEnumVariantIdx| u32::from(variant.0.into_raw());
+    assert_eq!(index(*untagged_variant), 0);
+    assert_eq!((index(*niche_variants.start()), index(*niche_variants.end())), (1, 2));
+    assert_eq!(*niche_start, 2);
 }
 
 #[test]
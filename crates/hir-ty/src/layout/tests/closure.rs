@@ -206,3 +206,28 @@ fn ellipsis_pattern() {
         }
     }
 }
+
+#[test]
+fn async_block_simple() {
+    size_and_align_expr! {
+        async { 5 }
+    }
+}
+
+#[test]
+fn async_block_captures() {
+    size_and_align_expr! {
+        minicore: copy;
+        stmts: [
+            let y: i64 = 5;
+        ]
+        async move { y + 2 }
+    }
+    size_and_align_expr! {
+        minicore: copy;
+        stmts: [
+            let y: i64 = 5;
+        ]
+        async { y + 2 }
+    }
+}
@@ -10,6 +10,7 @@ macro_rules! eprintln {
 mod builder;
 mod chalk_db;
 mod chalk_ext;
+mod coherence;
 mod infer;
 mod inhabitedness;
 mod interner;
@@ -18,6 +19,7 @@ mod mapping;
 mod tls;
 mod utils;
 
+pub mod auto_trait;
 pub mod autoderef;
 pub mod consteval;
 pub mod db;
@@ -25,10 +27,12 @@ pub mod diagnostics;
 pub mod display;
 pub mod lang_items;
 pub mod layout;
+pub mod lifetime_elision;
 pub mod method_resolution;
 pub mod mir;
 pub mod primitive;
 pub mod traits;
+pub mod variance;
 
 #[cfg(test)]
 mod tests;
@@ -37,6 +41,7 @@ mod test_db;
 
 use std::{collections::HashMap, hash::Hash, sync::Arc};
 
+use base_db::CrateId;
 use chalk_ir::{
     fold::{Shift, TypeFoldable},
     interner::HasInterner,
@@ -44,7 +49,7 @@ use chalk_ir::{
     NoSolution, TyData,
 };
 use either::Either;
-use hir_def::{hir::ExprId, type_ref::Rawness, TypeOrConstParamId};
+use hir_def::{hir::ExprId, type_ref::Rawness, ConstId, HasModule, TypeOrConstParamId};
 use hir_expand::name;
 use la_arena::{Arena, Idx};
 use mir::MirEvalError;
@@ -60,9 +65,10 @@ pub use autoderef::autoderef;
 pub use builder::{ParamKind, TyBuilder};
 pub use chalk_ext::*;
 pub use infer::{
-    could_coerce, could_unify, Adjust, Adjustment, AutoBorrow, BindingMode, InferenceDiagnostic,
-    InferenceResult, OverloadedDeref, PointerCast,
+    could_coerce, could_unify, Adjust, Adjustment, AutoBorrow, BindingMode, CaptureKind,
+    CapturedItem, InferenceDiagnostic, InferenceResult, OverloadedDeref, PointerCast,
 };
+pub use inhabitedness::is_ty_uninhabited_from;
 pub use interner::Interner;
 pub use lower::{
     associated_type_shorthand_candidates, CallableDefId, ImplTraitLoweringMode, TyDefId,
@@ -179,6 +185,9 @@ pub enum ConstScalar {
     // https://github.com/rust-lang/rust-analyzer/pull/8813#issuecomment-840679177
     // https://rust-lang.zulipchat.com/#narrow/stream/144729-wg-traits/topic/Handling.20non.20evaluatable.20constants'.20equality/near/238386348
     Unknown,
+    /// An associated constant of a trait, which can't be resolved to an impl before the generic
+    /// parameters it is used with are known, like `T::VAL` in a generic function.
+    UnevaluatedConst(ConstId, Substitution),
 }
 
 impl Hash for ConstScalar {
@@ -337,9 +346,39 @@ impl TypeFoldable<Interner> for CallableSig {
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 pub enum ImplTraitId {
     ReturnTypeImplTrait(hir_def::FunctionId, RpitId),
+    /// An `impl Trait` in the aliased type of a type alias, like `type Foo = impl Trait;`. Its
+    /// hidden type is inferred from the defining uses, see `infer::type_alias_impl_trait_hidden_type`.
+    TypeAliasImplTrait(hir_def::TypeAliasId, RpitId),
     AsyncBlockTypeImplTrait(hir_def::DefWithBodyId, ExprId),
 }
 
+impl ImplTraitId {
+    /// Returns the `impl Trait`s of the function or type alias this one belongs to, together with
+    /// its index among them.
+    pub(crate) fn impl_traits(
+        self,
+        db: &dyn HirDatabase,
+    ) -> Option<(Arc<Binders<ReturnTypeImplTraits>>, RpitId)> {
+        match self {
+            ImplTraitId::ReturnTypeImplTrait(func, idx) => {
+                Some((db.return_type_impl_traits(func)?, idx))
+            }
+            ImplTraitId::TypeAliasImplTrait(alias, idx) => {
+                Some((db.type_alias_impl_traits(alias)?, idx))
+            }
+            ImplTraitId::AsyncBlockTypeImplTrait(..) => None,
+        }
+    }
+
+    pub(crate) fn krate(self, db: &dyn HirDatabase) -> CrateId {
+        match self {
+            ImplTraitId::ReturnTypeImplTrait(func, _) => func.module(db.upcast()).krate(),
+            ImplTraitId::TypeAliasImplTrait(alias, _) => alias.module(db.upcast()).krate(),
+            ImplTraitId::AsyncBlockTypeImplTrait(body, _) => body.module(db.upcast()).krate(),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct ReturnTypeImplTraits {
     pub(crate) impl_traits: Arena<ReturnTypeImplTrait>,
@@ -681,3 +720,52 @@ where
     value.visit_with(&mut collector, DebruijnIndex::INNERMOST);
     collector.placeholders.into_iter().collect()
 }
+
+struct TypeLengthCounter {
+    length: usize,
+    limit: usize,
+}
+
+impl TypeVisitor<Interner> for TypeLengthCounter {
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn TypeVisitor<Interner, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn interner(&self) -> Interner {
+        Interner
+    }
+
+    fn visit_ty(
+        &mut self,
+        ty: &Ty,
+        outer_binder: DebruijnIndex,
+    ) -> std::ops::ControlFlow<Self::BreakTy> {
+        self.length += 1;
+        if self.length > self.limit {
+            return std::ops::ControlFlow::Break(());
+        }
+        ty.super_visit_with(self, outer_binder)
+    }
+
+    fn visit_const(
+        &mut self,
+        _constant: &chalk_ir::Const<Interner>,
+        _outer_binder: DebruijnIndex,
+    ) -> std::ops::ControlFlow<Self::BreakTy> {
+        self.length += 1;
+        if self.length > self.limit {
+            return std::ops::ControlFlow::Break(());
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+
+/// Returns the number of types and consts `ty` is made of, like rustc's type length. Counting stops
+/// once the length exceeds `limit`, as walking big types is expensive.
+pub fn type_length(ty: &Ty, limit: usize) -> usize {
+    let mut counter = TypeLengthCounter { length: 0, limit };
+    ty.visit_with(&mut counter, DebruijnIndex::INNERMOST);
+    counter.length
+}
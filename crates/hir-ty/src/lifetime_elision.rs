@@ -0,0 +1,89 @@
+//! Resolves the lifetimes of the references in function signatures, applying the elision rules:
+//! each elided lifetime of the parameters is a distinct lifetime, and the elided lifetimes of the
+//! return type are the one of `&self`, or else the only lifetime of the parameters.
+//!
+//! Only the lifetimes of references are resolved, the ones of the lifetime arguments of paths,
+//! like `Foo<'_>`, are not.
+
+use hir_def::{
+    type_ref::{LifetimeRef, TypeRef},
+    FunctionId,
+};
+use hir_expand::name::Name;
+
+use crate::db::HirDatabase;
+
+/// The lifetime of a reference in a signature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SignatureLifetime {
+    Static,
+    /// A named lifetime, like `'a`, of the function or of its parent.
+    Named(Name),
+    /// The `n`th elided lifetime of the signature.
+    Elided(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureLifetimes {
+    /// The lifetimes of the references in the type of each parameter, including `self`, in the
+    /// order of [`TypeRef::walk`].
+    pub params: Vec<Vec<SignatureLifetime>>,
+    /// The lifetimes of the references in the return type. An elided one which can't be
+    /// resolved gets a lifetime of its own.
+    pub ret: Vec<SignatureLifetime>,
+    /// The number of distinct elided lifetimes.
+    pub elided_count: usize,
+}
+
+pub fn signature_lifetimes(db: &dyn HirDatabase, func: FunctionId) -> SignatureLifetimes {
+    let data = db.function_data(func);
+    let mut elided_count = 0;
+    let mut fresh = || {
+        elided_count += 1;
+        SignatureLifetime::Elided(elided_count - 1)
+    };
+    let params: Vec<Vec<_>> = data
+        .params
+        .iter()
+        .map(|type_ref| {
+            reference_lifetimes(type_ref)
+                .into_iter()
+                .map(|it| it.unwrap_or_else(&mut fresh))
+                .collect()
+        })
+        .collect();
+
+    let self_lifetime = match data.params.first() {
+        Some(type_ref) if data.has_self_param() && matches!(**type_ref, TypeRef::Reference(..)) => {
+            params[0].first().cloned()
+        }
+        _ => None,
+    };
+    let mut inputs = params.iter().flatten();
+    let only_input = inputs.next().filter(|&first| inputs.all(|it| it == first)).cloned();
+    let output = self_lifetime.or(only_input);
+    let ret = reference_lifetimes(&data.ret_type)
+        .into_iter()
+        .map(|it| it.or_else(|| output.clone()).unwrap_or_else(&mut fresh))
+        .collect();
+    SignatureLifetimes { params, ret, elided_count }
+}
+
+/// Returns the lifetimes of the references in `type_ref`, `None` standing for elided ones.
+fn reference_lifetimes(type_ref: &TypeRef) -> Vec<Option<SignatureLifetime>> {
+    let mut res = Vec::new();
+    type_ref.walk(&mut |it| {
+        if let TypeRef::Reference(_, lifetime, _) = it {
+            res.push(lifetime.as_ref().and_then(lifetime_of));
+        }
+    });
+    res
+}
+
+fn lifetime_of(lifetime: &LifetimeRef) -> Option<SignatureLifetime> {
+    match lifetime.name.to_smol_str().as_str() {
+        "'static" => Some(SignatureLifetime::Static),
+        "'_" => None,
+        _ => Some(SignatureLifetime::Named(lifetime.name.clone())),
+    }
+}
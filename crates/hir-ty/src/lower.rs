@@ -13,7 +13,12 @@ use std::{
 
 use base_db::CrateId;
 use chalk_ir::{
-    cast::Cast, fold::Shift, fold::TypeFoldable, interner::HasInterner, Mutability, Safety,
+    cast::Cast,
+    fold::Shift,
+    fold::TypeFoldable,
+    interner::HasInterner,
+    visit::{TypeSuperVisitable, TypeVisitable, TypeVisitor},
+    Mutability, Safety,
 };
 
 use either::Either;
@@ -41,7 +46,7 @@ use stdx::{impl_from, never};
 use syntax::ast;
 
 use crate::{
-    all_super_traits,
+    all_super_traits, collect_placeholders,
     consteval::{intern_const_ref, path_to_const, unknown_const, unknown_const_as_generic},
     db::HirDatabase,
     make_binders,
@@ -51,8 +56,9 @@ use crate::{
     utils::{all_super_trait_refs, associated_type_by_name_including_super_traits, generics},
     AliasEq, AliasTy, Binders, BoundVar, CallableSig, Const, DebruijnIndex, DynTy, FnPointer,
     FnSig, FnSubst, GenericArgData, ImplTraitId, Interner, ParamKind, PolyFnSig, ProjectionTy,
-    QuantifiedWhereClause, QuantifiedWhereClauses, ReturnTypeImplTrait, ReturnTypeImplTraits,
-    Substitution, TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder, TyKind, WhereClause,
+    ProjectionTyExt, QuantifiedWhereClause, QuantifiedWhereClauses, ReturnTypeImplTrait,
+    ReturnTypeImplTraits, Substitution, TraitEnvironment, TraitRef, TraitRefExt, Ty, TyBuilder,
+    TyKind, WhereClause,
 };
 
 #[derive(Debug)]
@@ -273,8 +279,9 @@ impl<'a> TyLoweringContext<'a> {
             TypeRef::ImplTrait(bounds) => {
                 match &self.impl_trait_mode {
                     ImplTraitLoweringState::Opaque(opaque_type_data) => {
-                        let func = match self.resolver.generic_def() {
-                            Some(GenericDefId::FunctionId(f)) => f,
+                        let def = match self.resolver.generic_def() {
+                            Some(def @ GenericDefId::FunctionId(_))
+                            | Some(def @ GenericDefId::TypeAliasId(_)) => def,
                             _ => panic!("opaque impl trait lowering in non-function"),
                         };
 
@@ -293,15 +300,24 @@ impl<'a> TyLoweringContext<'a> {
                         // other, but separately. So if the `T` refers to a type
                         // parameter of the outer function, it's just one binder
                         // away instead of two.
+                        let krate = def.module(self.db.upcast()).krate();
                         let actual_opaque_type_data = self
                             .with_debruijn(DebruijnIndex::INNERMOST, |ctx| {
-                                ctx.lower_impl_trait(bounds, func)
+                                ctx.lower_impl_trait(bounds, krate)
                             });
                         opaque_type_data.borrow_mut()[idx] = actual_opaque_type_data;
 
-                        let impl_trait_id = ImplTraitId::ReturnTypeImplTrait(func, idx);
+                        let impl_trait_id = match def {
+                            GenericDefId::TypeAliasId(alias) => {
+                                ImplTraitId::TypeAliasImplTrait(alias, idx)
+                            }
+                            GenericDefId::FunctionId(func) => {
+                                ImplTraitId::ReturnTypeImplTrait(func, idx)
+                            }
+                            _ => unreachable!(),
+                        };
                         let opaque_ty_id = self.db.intern_impl_trait_id(impl_trait_id).into();
-                        let generics = generics(self.db.upcast(), func.into());
+                        let generics = generics(self.db.upcast(), def);
                         let parameters = generics.bound_vars_subst(self.db, self.in_binders);
                         TyKind::OpaqueType(opaque_ty_id, parameters).intern(Interner)
                     }
@@ -904,7 +920,7 @@ impl<'a> TyLoweringContext<'a> {
         explicit_self_ty: Option<Ty>,
     ) -> Option<TraitRef> {
         let resolved = match self.resolver.resolve_path_in_type_ns_fully(self.db.upcast(), path)? {
-            // FIXME(trait_alias): We need to handle trait alias here.
+            // Trait aliases are expanded into their bounds by `lower_trait_alias_bound()`.
             TypeNs::TraitId(tr) => tr,
             _ => return None,
         };
@@ -912,6 +928,25 @@ impl<'a> TyLoweringContext<'a> {
         Some(self.lower_trait_ref_from_resolved_path(resolved, segment, explicit_self_ty))
     }
 
+    /// Expands the bound `self_ty: Alias<Args>` into the bounds of the trait alias, like
+    /// `self_ty: Foo` and `self_ty: Send` for `trait Alias = Foo + Send;`. Returns nothing if
+    /// `path` doesn't resolve to a trait alias.
+    fn lower_trait_alias_bound(&self, path: &Path, self_ty: Ty) -> Vec<QuantifiedWhereClause> {
+        let Some(TypeNs::TraitAliasId(alias)) =
+            self.resolver.resolve_path_in_type_ns_fully(self.db.upcast(), path)
+        else {
+            return Vec::new();
+        };
+        let segment = path.segments().last().expect("path should have at least one segment");
+        let substs =
+            self.substs_from_path_segment(segment, Some(alias.into()), false, Some(self_ty));
+        self.db
+            .generic_predicates(alias.into())
+            .iter()
+            .map(|pred| pred.clone().substitute(Interner, &substs))
+            .collect()
+    }
+
     pub(crate) fn lower_trait_ref_from_resolved_path(
         &self,
         resolved: TraitId,
@@ -982,9 +1017,13 @@ impl<'a> TyLoweringContext<'a> {
         ignore_bindings: bool,
     ) -> impl Iterator<Item = QuantifiedWhereClause> + 'a {
         let mut bindings = None;
+        let mut alias_bounds = Vec::new();
         let trait_ref = match bound {
             TypeBound::Path(path, TraitBoundModifier::None) => {
-                bindings = self.lower_trait_ref_from_path(path, Some(self_ty));
+                bindings = self.lower_trait_ref_from_path(path, Some(self_ty.clone()));
+                if bindings.is_none() {
+                    alias_bounds = self.lower_trait_alias_bound(path, self_ty);
+                }
                 bindings
                     .clone()
                     .filter(|tr| {
@@ -1021,15 +1060,29 @@ impl<'a> TyLoweringContext<'a> {
             }
             TypeBound::ForLifetime(_, path) => {
                 // FIXME Don't silently drop the hrtb lifetimes here
-                bindings = self.lower_trait_ref_from_path(path, Some(self_ty));
+                bindings = self.lower_trait_ref_from_path(path, Some(self_ty.clone()));
+                if bindings.is_none() {
+                    alias_bounds = self.lower_trait_alias_bound(path, self_ty);
+                }
                 bindings.clone().map(WhereClause::Implemented).map(crate::wrap_empty_binders)
             }
             TypeBound::Lifetime(_) => None,
             TypeBound::Error => None,
         };
-        trait_ref.into_iter().chain(
+        // The associated type bindings of a trait alias bound, like `Alias<Item = u8>`, bind the
+        // associated types of the traits the alias expands to.
+        let alias_trait_refs: Vec<_> = alias_bounds
+            .iter()
+            .filter(|it| it.binders.is_empty(Interner))
+            .filter_map(|it| match it.skip_binders() {
+                WhereClause::Implemented(tr) => Some(tr.clone()),
+                _ => None,
+            })
+            .collect();
+        trait_ref.into_iter().chain(alias_bounds).chain(
             bindings
                 .into_iter()
+                .chain(alias_trait_refs)
                 .filter(move |_| !ignore_bindings)
                 .flat_map(move |tr| self.assoc_type_bindings_from_type_bound(bound, tr)),
         )
@@ -1113,9 +1166,21 @@ impl<'a> TyLoweringContext<'a> {
         // INVARIANT: If this function returns `DynTy`, there should be at least one trait bound.
         // These invariants are utilized by `TyExt::dyn_trait()` and chalk.
         let bounds = self.with_shifted_in(DebruijnIndex::ONE, |ctx| {
+            let bound_self_ty = self_ty.clone().shifted_in(Interner);
             let mut bounds: Vec<_> = bounds
                 .iter()
                 .flat_map(|b| ctx.lower_type_bound(b, self_ty.clone(), false))
+                // Trait aliases may also bound other types, like their parameters, which aren't
+                // part of the `dyn` type.
+                .filter(|b| match b.skip_binders() {
+                    WhereClause::Implemented(tr) => {
+                        tr.self_type_parameter(Interner) == bound_self_ty
+                    }
+                    WhereClause::AliasEq(AliasEq { alias: AliasTy::Projection(proj), .. }) => {
+                        proj.self_type_parameter(ctx.db) == bound_self_ty
+                    }
+                    _ => true,
+                })
                 .collect();
 
             let mut multiple_regular_traits = false;
@@ -1188,7 +1253,7 @@ impl<'a> TyLoweringContext<'a> {
     fn lower_impl_trait(
         &self,
         bounds: &[Interned<TypeBound>],
-        func: FunctionId,
+        krate: CrateId,
     ) -> ReturnTypeImplTrait {
         cov_mark::hit!(lower_rpit);
         let self_ty = TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, 0)).intern(Interner);
@@ -1199,7 +1264,6 @@ impl<'a> TyLoweringContext<'a> {
                 .collect();
 
             if !ctx.unsized_types.borrow().contains(&self_ty) {
-                let krate = func.lookup(ctx.db.upcast()).module(ctx.db.upcast()).krate();
                 let sized_trait = ctx
                     .db
                     .lang_item(krate, LangItem::Sized)
@@ -1406,6 +1470,9 @@ pub(crate) fn generic_predicates_for_param_query(
                         };
                         let tr = match resolver.resolve_path_in_type_ns_fully(db.upcast(), path) {
                             Some(TypeNs::TraitId(tr)) => tr,
+                            // Trait aliases are expanded into the bounds they stand for when
+                            // lowered, so they may bound the associated type too.
+                            Some(TypeNs::TraitAliasId(_)) => return true,
                             _ => return false,
                         };
 
@@ -1434,6 +1501,14 @@ pub(crate) fn generic_predicates_for_param_query(
     predicates.into()
 }
 
+pub(crate) fn generic_predicates_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &GenericDefId,
+) -> Arc<[Binders<QuantifiedWhereClause>]> {
+    Arc::new([])
+}
+
 pub(crate) fn generic_predicates_for_param_recover(
     _db: &dyn HirDatabase,
     _cycle: &[String],
@@ -1498,6 +1573,16 @@ pub(crate) fn trait_environment_query(
     }
 
     let subst = generics(db.upcast(), def).placeholder_subst(db);
+    if let GenericDefId::FunctionId(func) = def {
+        for pred in implied_bounds(db, func, &subst) {
+            if let WhereClause::Implemented(tr) = &pred.skip_binders() {
+                traits_in_scope.push((tr.self_type_parameter(Interner).clone(), tr.hir_trait_id()));
+            }
+            let program_clause: chalk_ir::ProgramClause<Interner> = pred.cast(Interner);
+            clauses.push(program_clause.into_from_env_clause(Interner));
+        }
+    }
+
     let explicitly_unsized_tys = ctx.unsized_types.into_inner();
     let implicitly_sized_clauses =
         implicitly_sized_clauses(db, def, &explicitly_unsized_tys, &subst, &resolver).map(|pred| {
@@ -1513,6 +1598,64 @@ pub(crate) fn trait_environment_query(
     Arc::new(TraitEnvironment { krate, block: None, traits_from_clauses: traits_in_scope, env })
 }
 
+/// Returns the where clauses implied by the signature of `func`, which are the ones of the ADTs
+/// appearing in it, like `T: Clone` for a parameter of type `Wrapper<T>` with
+/// `struct Wrapper<T: Clone>`. Only the clauses about the generic parameters of `func`, given as
+/// placeholders by `subst`, are returned.
+fn implied_bounds(
+    db: &dyn HirDatabase,
+    func: FunctionId,
+    subst: &Substitution,
+) -> Vec<QuantifiedWhereClause> {
+    struct AdtCollector(Vec<(AdtId, Substitution)>);
+
+    impl TypeVisitor<Interner> for AdtCollector {
+        type BreakTy = ();
+
+        fn as_dyn(&mut self) -> &mut dyn TypeVisitor<Interner, BreakTy = Self::BreakTy> {
+            self
+        }
+
+        fn interner(&self) -> Interner {
+            Interner
+        }
+
+        fn visit_ty(
+            &mut self,
+            ty: &Ty,
+            outer_binder: DebruijnIndex,
+        ) -> std::ops::ControlFlow<Self::BreakTy> {
+            // The bounds of ADTs under binders, like in function pointers, don't hold for every
+            // instantiation of the binders.
+            if let TyKind::Adt(chalk_ir::AdtId(adt), subst) = ty.kind(Interner) {
+                if outer_binder == DebruijnIndex::INNERMOST {
+                    self.0.push((*adt, subst.clone()));
+                }
+            }
+            ty.super_visit_with(self, outer_binder)
+        }
+    }
+
+    let sig = db.callable_item_signature(func.into()).substitute(Interner, subst);
+    let mut collector = AdtCollector(Vec::new());
+    for ty in sig.params_and_return.iter() {
+        ty.visit_with(&mut collector, DebruijnIndex::INNERMOST);
+    }
+    let mut seen = FxHashSet::default();
+    collector
+        .0
+        .into_iter()
+        .filter(|it| seen.insert(it.clone()))
+        .flat_map(|(adt, adt_subst)| {
+            db.generic_predicates(adt.into())
+                .iter()
+                .map(|pred| pred.clone().substitute(Interner, &adt_subst))
+                .collect::<Vec<_>>()
+        })
+        .filter(|pred| !collect_placeholders(pred, db).is_empty())
+        .collect()
+}
+
 /// Resolve the where clause(s) of an item with generics.
 pub(crate) fn generic_predicates_query(
     db: &dyn HirDatabase,
@@ -1540,7 +1683,7 @@ pub(crate) fn generic_predicates_query(
 }
 
 /// Generate implicit `: Sized` predicates for all generics that has no `?Sized` bound.
-/// Exception is Self of a trait def.
+/// Exception is Self of a trait or trait alias def.
 fn implicitly_sized_clauses<'a>(
     db: &dyn HirDatabase,
     def: GenericDefId,
@@ -1548,7 +1691,7 @@ fn implicitly_sized_clauses<'a>(
     substitution: &'a Substitution,
     resolver: &Resolver,
 ) -> impl Iterator<Item = WhereClause> + 'a {
-    let is_trait_def = matches!(def, GenericDefId::TraitId(..));
+    let is_trait_def = matches!(def, GenericDefId::TraitId(..) | GenericDefId::TraitAliasId(..));
     let generic_args = &substitution.as_slice(Interner)[is_trait_def as usize..];
     let sized_trait = db
         .lang_item(resolver.krate(), LangItem::Sized)
@@ -1585,11 +1728,23 @@ pub(crate) fn generic_defaults_query(
         .map(|(idx, (id, p))| {
             let p = match p {
                 TypeOrConstParamData::TypeParamData(p) => p,
-                TypeOrConstParamData::ConstParamData(_) => {
-                    // FIXME: implement const generic defaults
-                    let val = unknown_const_as_generic(
-                        db.const_param_ty(ConstParamId::from_unchecked(id)),
-                    );
+                TypeOrConstParamData::ConstParamData(p) => {
+                    let ty = db.const_param_ty(ConstParamId::from_unchecked(id));
+                    let val = match &p.default {
+                        Some(default) => const_or_path_to_chalk(
+                            db,
+                            &resolver,
+                            ty,
+                            default,
+                            ParamLoweringMode::Variable,
+                            || generics(db.upcast(), def),
+                            DebruijnIndex::INNERMOST,
+                        )
+                        .cast(Interner),
+                        None => unknown_const_as_generic(ty),
+                    };
+                    // Like type defaults, const defaults can only refer to previous parameters.
+                    let val = fallback_bound_vars(val, idx, parent_start_idx);
                     return make_binders(db, &generic_params, val);
                 }
             };
@@ -1748,8 +1903,9 @@ fn type_for_adt(db: &dyn HirDatabase, adt: AdtId) -> Binders<Ty> {
 fn type_for_type_alias(db: &dyn HirDatabase, t: TypeAliasId) -> Binders<Ty> {
     let generics = generics(db.upcast(), t.into());
     let resolver = t.resolver(db.upcast());
-    let ctx =
-        TyLoweringContext::new(db, &resolver).with_type_param_mode(ParamLoweringMode::Variable);
+    let ctx = TyLoweringContext::new(db, &resolver)
+        .with_impl_trait_mode(ImplTraitLoweringMode::Opaque)
+        .with_type_param_mode(ParamLoweringMode::Variable);
     if db.type_alias_data(t).is_extern {
         Binders::empty(Interner, TyKind::Foreign(crate::to_foreign_def_id(t)).intern(Interner))
     } else {
@@ -1938,6 +2094,32 @@ pub(crate) fn return_type_impl_traits(
     }
 }
 
+pub(crate) fn type_alias_impl_traits(
+    db: &dyn HirDatabase,
+    def: TypeAliasId,
+) -> Option<Arc<Binders<ReturnTypeImplTraits>>> {
+    let data = db.type_alias_data(def);
+    let resolver = def.resolver(db.upcast());
+    let ctx = TyLoweringContext::new(db, &resolver)
+        .with_impl_trait_mode(ImplTraitLoweringMode::Opaque)
+        .with_type_param_mode(ParamLoweringMode::Variable);
+    if let Some(type_ref) = &data.type_ref {
+        let _ty = ctx.lower_ty(type_ref);
+    }
+    let generics = generics(db.upcast(), def.into());
+    let type_alias_impl_traits = ReturnTypeImplTraits {
+        impl_traits: match ctx.impl_trait_mode {
+            ImplTraitLoweringState::Opaque(x) => x.into_inner(),
+            _ => unreachable!(),
+        },
+    };
+    if type_alias_impl_traits.impl_traits.is_empty() {
+        None
+    } else {
+        Some(Arc::new(make_binders(db, &generics, type_alias_impl_traits)))
+    }
+}
+
 pub(crate) fn lower_to_chalk_mutability(m: hir_def::type_ref::Mutability) -> Mutability {
     match m {
         hir_def::type_ref::Mutability::Shared => Mutability::Not,
@@ -229,6 +229,11 @@ impl TraitImpls {
             .flat_map(|it| it.iter().copied())
     }
 
+    /// Queries all traits with impls.
+    pub fn traits(&self) -> impl Iterator<Item = TraitId> + '_ {
+        self.map.keys().copied()
+    }
+
     /// Queries all impls of the given trait.
     pub fn for_trait(&self, trait_: TraitId) -> impl Iterator<Item = ImplId> + '_ {
         self.map
@@ -685,26 +690,54 @@ pub fn lookup_impl_method(
     func: FunctionId,
     fn_subst: Substitution,
 ) -> (FunctionId, Substitution) {
-    let ItemContainerId::TraitId(trait_id) = func.lookup(db.upcast()).container else {
+    let Some((trait_ref, name)) = method_trait_ref(db, func, &fn_subst) else {
         return (func, fn_subst)
     };
+    lookup_impl_assoc_items_for_trait_ref(trait_ref, db, env, &name, false)
+        .into_iter()
+        .find_map(|assoc| match assoc {
+            (AssocItemId::FunctionId(id), subst) => Some((id, subst)),
+            _ => None,
+        })
+        .unwrap_or((func, fn_subst))
+}
+
+/// Like [`lookup_impl_method`], but returns the methods of all the impls which apply, the most
+/// specific first. There are several of them when impls specialize others, and no method when the
+/// one of the trait is used.
+pub fn lookup_impl_method_candidates(
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    func: FunctionId,
+    fn_subst: &Substitution,
+) -> Vec<(FunctionId, Substitution)> {
+    let Some((trait_ref, name)) = method_trait_ref(db, func, fn_subst) else {
+        return Vec::new();
+    };
+    lookup_impl_assoc_items_for_trait_ref(trait_ref, db, env, &name, true)
+        .into_iter()
+        .filter_map(|assoc| match assoc {
+            (AssocItemId::FunctionId(id), subst) => Some((id, subst)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn method_trait_ref(
+    db: &dyn HirDatabase,
+    func: FunctionId,
+    fn_subst: &Substitution,
+) -> Option<(TraitRef, Name)> {
+    let ItemContainerId::TraitId(trait_id) = func.lookup(db.upcast()).container else {
+        return None;
+    };
     let trait_params = db.generic_params(trait_id.into()).type_or_consts.len();
     let fn_params = fn_subst.len(Interner) - trait_params;
     let trait_ref = TraitRef {
         trait_id: to_chalk_trait_id(trait_id),
         substitution: Substitution::from_iter(Interner, fn_subst.iter(Interner).skip(fn_params)),
     };
-
-    let name = &db.function_data(func).name;
-    lookup_impl_assoc_item_for_trait_ref(trait_ref, db, env, name)
-        .and_then(|assoc| {
-            if let (AssocItemId::FunctionId(id), subst) = assoc {
-                Some((id, subst))
-            } else {
-                None
-            }
-        })
-        .unwrap_or((func, fn_subst))
+    Some((trait_ref, db.function_data(func).name.clone()))
 }
 
 fn lookup_impl_assoc_item_for_trait_ref(
@@ -713,15 +746,69 @@ fn lookup_impl_assoc_item_for_trait_ref(
     env: Arc<TraitEnvironment>,
     name: &Name,
 ) -> Option<(AssocItemId, Substitution)> {
+    lookup_impl_assoc_items_for_trait_ref(trait_ref, db, env, name, false).into_iter().next()
+}
+
+/// Returns the items named `name` of the impls applying to `trait_ref`, the item of the most
+/// specific impl first. An impl without the item uses the one of the impl it specializes.
+///
+/// Unless `all` is set, only the item which is used is returned, and the impls are only all checked
+/// when the first applying one can be specialized, that is when its item is `default` or missing.
+fn lookup_impl_assoc_items_for_trait_ref(
+    trait_ref: TraitRef,
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    name: &Name,
+    all: bool,
+) -> Vec<(AssocItemId, Substitution)> {
     let self_ty = trait_ref.self_type_parameter(Interner);
-    let self_ty_fp = TyFingerprint::for_trait_impl(&self_ty)?;
+    let Some(self_ty_fp) = TyFingerprint::for_trait_impl(&self_ty) else { return Vec::new() };
     let impls = db.trait_impls_in_deps(env.krate);
     let impls = impls.for_trait_and_self_ty(trait_ref.hir_trait_id(), self_ty_fp);
 
-    let table = InferenceTable::new(db, env);
+    let mut table = InferenceTable::new(db, env);
+    let mut matching = Vec::new();
+    for impl_ in impls {
+        let Some(impl_subst) = impl_matches(&mut table, impl_, &trait_ref) else { continue };
+        let item = impl_item_by_name(db, impl_, name);
+        let is_final = match item {
+            Some(AssocItemId::FunctionId(f)) => !db.function_data(f).has_default_kw(),
+            // FIXME: `default` consts aren't recorded, so they are assumed to be final.
+            Some(_) => true,
+            None => false,
+        };
+        matching.push((impl_, item, impl_subst));
+        if !all && is_final && matching.len() == 1 {
+            break;
+        }
+    }
+
+    if matching.len() > 1 {
+        // The more applying impls an impl specializes, the more specific it is.
+        let specialized: Vec<usize> = matching
+            .iter()
+            .map(|&(impl_, ..)| {
+                matching
+                    .iter()
+                    .filter(|&&(other, ..)| other != impl_ && impl_specializes(db, impl_, other))
+                    .count()
+            })
+            .collect();
+        let mut ranked: Vec<_> = specialized.into_iter().zip(matching).collect();
+        ranked.sort_by_key(|&(specialized, _)| std::cmp::Reverse(specialized));
+        matching = ranked.into_iter().map(|(_, it)| it).collect();
+    }
+
+    let items = matching.into_iter().filter_map(|(_, item, subst)| Some((item?, subst)));
+    if all {
+        items.collect()
+    } else {
+        items.take(1).collect()
+    }
+}
 
-    let (impl_data, impl_subst) = find_matching_impl(impls, table, trait_ref)?;
-    let item = impl_data.items.iter().find_map(|&it| match it {
+fn impl_item_by_name(db: &dyn HirDatabase, impl_: ImplId, name: &Name) -> Option<AssocItemId> {
+    db.impl_data(impl_).items.iter().find_map(|&it| match it {
         AssocItemId::FunctionId(f) => {
             (db.function_data(f).name == *name).then_some(AssocItemId::FunctionId(f))
         }
@@ -732,41 +819,43 @@ fn lookup_impl_assoc_item_for_trait_ref(
             .map(|n| n == name)
             .and_then(|result| if result { Some(AssocItemId::ConstId(c)) } else { None }),
         AssocItemId::TypeAliasId(_) => None,
-    })?;
-    Some((item, impl_subst))
+    })
 }
 
-fn find_matching_impl(
-    mut impls: impl Iterator<Item = ImplId>,
-    mut table: InferenceTable<'_>,
-    actual_trait_ref: TraitRef,
-) -> Option<(Arc<ImplData>, Substitution)> {
+/// Returns the substitution of `impl_` for `actual_trait_ref`, if it applies to it.
+fn impl_matches(
+    table: &mut InferenceTable<'_>,
+    impl_: ImplId,
+    actual_trait_ref: &TraitRef,
+) -> Option<Substitution> {
     let db = table.db;
-    loop {
-        let impl_ = impls.next()?;
-        let r = table.run_in_snapshot(|table| {
-            let impl_data = db.impl_data(impl_);
-            let impl_substs =
-                TyBuilder::subst_for_def(db, impl_, None).fill_with_inference_vars(table).build();
-            let trait_ref = db
-                .impl_trait(impl_)
-                .expect("non-trait method in find_matching_impl")
-                .substitute(Interner, &impl_substs);
-
-            if !table.unify(&trait_ref, &actual_trait_ref) {
-                return None;
-            }
-
-            let wcs = crate::chalk_db::convert_where_clauses(db, impl_.into(), &impl_substs)
-                .into_iter()
-                .map(|b| b.cast(Interner));
-            let goal = crate::Goal::all(Interner, wcs);
-            table.try_obligation(goal).map(|_| (impl_data, table.resolve_completely(impl_substs)))
-        });
-        if r.is_some() {
-            break r;
+    table.run_in_snapshot(|table| {
+        let impl_substs =
+            TyBuilder::subst_for_def(db, impl_, None).fill_with_inference_vars(table).build();
+        let trait_ref = db
+            .impl_trait(impl_)
+            .expect("non-trait method in impl_matches")
+            .substitute(Interner, &impl_substs);
+
+        if !table.unify(&trait_ref, actual_trait_ref) {
+            return None;
         }
-    }
+
+        let wcs = crate::chalk_db::convert_where_clauses(db, impl_.into(), &impl_substs)
+            .into_iter()
+            .map(|b| b.cast(Interner));
+        let goal = crate::Goal::all(Interner, wcs);
+        table.try_obligation(goal).map(|_| table.resolve_completely(impl_substs))
+    })
+}
+
+/// Returns whether `impl_` specializes `parent`, that is whether `parent` applies wherever `impl_`
+/// does.
+fn impl_specializes(db: &dyn HirDatabase, impl_: ImplId, parent: ImplId) -> bool {
+    let Some(trait_ref) = db.impl_trait(impl_) else { return false };
+    let trait_ref = trait_ref.substitute(Interner, &TyBuilder::placeholder_subst(db, impl_));
+    let mut table = InferenceTable::new(db, db.trait_environment(impl_.into()));
+    impl_matches(&mut table, parent, &trait_ref).is_some()
 }
 
 fn is_inherent_impl_coherent(
@@ -992,8 +1081,10 @@ fn iterate_method_candidates_by_receiver(
     let snapshot = table.snapshot();
     // We're looking for methods with *receiver* type receiver_ty. These could
     // be found in any of the derefs of receiver_ty, so we have to go through
-    // that.
-    let mut autoderef = autoderef::Autoderef::new(&mut table, receiver_ty.clone());
+    // that. Custom smart pointers which only implement `Receiver` are walked
+    // through too, for methods like `fn f(self: MyPtr<Self>)`.
+    let mut autoderef =
+        autoderef::Autoderef::new(&mut table, receiver_ty.clone()).use_receiver_trait();
     while let Some((self_ty, _)) = autoderef.next() {
         iterate_inherent_methods(
             &self_ty,
@@ -1008,7 +1099,8 @@ fn iterate_method_candidates_by_receiver(
 
     table.rollback_to(snapshot);
 
-    let mut autoderef = autoderef::Autoderef::new(&mut table, receiver_ty.clone());
+    let mut autoderef =
+        autoderef::Autoderef::new(&mut table, receiver_ty.clone()).use_receiver_trait();
     while let Some((self_ty, _)) = autoderef.next() {
         iterate_trait_method_candidates(
             &self_ty,
@@ -3,10 +3,12 @@
 use std::{fmt::Display, iter};
 
 use crate::{
-    db::HirDatabase, infer::PointerCast, ClosureId, Const, ConstScalar, InferenceResult, Interner,
-    MemoryMap, Substitution, Ty, TyKind,
+    db::HirDatabase, infer::PointerCast, ClosureId, Const, ConstScalar, ImplTraitId,
+    InferenceResult, Interner, MemoryMap, Substitution, Ty, TyKind,
 };
 use chalk_ir::Mutability;
+use base_db::CrateId;
+use cfg::CfgExpr;
 use hir_def::{
     hir::{BindingId, Expr, ExprId, Ordering, PatId},
     DefWithBodyId, FieldId, UnionId, VariantId,
@@ -17,16 +19,24 @@ mod eval;
 mod lower;
 mod borrowck;
 mod pretty;
-
-pub use borrowck::{borrowck_query, BorrowckResult, MutabilityReason};
-pub use eval::{interpret_mir, pad16, Evaluator, MirEvalError};
+mod region;
+
+pub use borrowck::{borrowck_query, BorrowckResult, DeadStore, MutabilityReason};
+pub(crate) use eval::detect_variant;
+pub use eval::{
+    interpret_mir, interpret_mir_until_bound, interpret_mir_with_external_values,
+    interpret_mir_with_global_allocator, interpret_mir_with_profile, interpret_mir_with_seed,
+    interpret_mir_with_step_profile, interpret_mir_with_trace, pad16, BuildProfile, Evaluator,
+    ExternalCall, ExternalValue, ExternalValueProvider, MirEvalError, DEFAULT_SEED,
+};
 pub use lower::{
     lower_to_mir, mir_body_for_closure_query, mir_body_query, mir_body_recover, MirLowerError,
 };
+pub use region::{region_inference_query, Loan, RegionData, RegionId, RegionInferenceResult};
 use smallvec::{smallvec, SmallVec};
 use stdx::{impl_from, never};
 
-use super::consteval::{intern_const_scalar, try_const_usize};
+use super::consteval::{intern_const_scalar, try_const_usize, usize_const};
 
 pub type BasicBlockId = Idx<BasicBlock>;
 pub type LocalId = Idx<Local>;
@@ -111,6 +121,7 @@ impl<V, T> ProjectionElem<V, T> {
         base: Ty,
         db: &dyn HirDatabase,
         closure_field: impl FnOnce(ClosureId, usize) -> Ty,
+        krate: CrateId,
     ) -> Ty {
         match self {
             ProjectionElem::Deref => match &base.data(Interner).kind {
@@ -140,6 +151,15 @@ impl<V, T> ProjectionElem<V, T> {
                         TyKind::Error.intern(Interner)
                     }),
                 TyKind::Closure(id, _) => closure_field(*id, *f),
+                TyKind::Generator(..) | TyKind::OpaqueType(..) => {
+                    match generator_closure_id(db, &base) {
+                        Some(id) => closure_field(id, *f),
+                        None => {
+                            never!("Only generators and async blocks have upvar fields");
+                            return TyKind::Error.intern(Interner);
+                        }
+                    }
+                }
                 _ => {
                     never!("Only tuple or closure has tuple or closure field");
                     return TyKind::Error.intern(Interner);
@@ -152,9 +172,29 @@ impl<V, T> ProjectionElem<V, T> {
                     return TyKind::Error.intern(Interner);
                 }
             },
-            ProjectionElem::ConstantIndex { .. }
-            | ProjectionElem::Subslice { .. }
-            | ProjectionElem::OpaqueCast(_) => {
+            ProjectionElem::ConstantIndex { .. } => match &base.data(Interner).kind {
+                TyKind::Array(inner, _) | TyKind::Slice(inner) => inner.clone(),
+                _ => {
+                    never!("Constant index on non array/slice type");
+                    return TyKind::Error.intern(Interner);
+                }
+            },
+            &ProjectionElem::Subslice { from, to, from_end } => match &base.data(Interner).kind {
+                TyKind::Array(inner, len) => {
+                    let len = if from_end {
+                        try_const_usize(len).and_then(|len| len.checked_sub((from + to) as u128))
+                    } else {
+                        to.checked_sub(from).map(|len| len as u128)
+                    };
+                    TyKind::Array(inner.clone(), usize_const(db, len, krate)).intern(Interner)
+                }
+                TyKind::Slice(_) => base.clone(),
+                _ => {
+                    never!("Subslice on non array/slice type");
+                    return TyKind::Error.intern(Interner);
+                }
+            },
+            ProjectionElem::OpaqueCast(_) => {
                 never!("We don't emit these yet");
                 return TyKind::Error.intern(Interner);
             }
@@ -185,7 +225,8 @@ pub enum AggregateKind {
     Adt(VariantId, Substitution),
     Union(UnionId, FieldId),
     Closure(Ty),
-    //Generator(LocalDefId, SubstsRef, Movability),
+    /// The type is of the generator or the async block
+    Generator(Ty),
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -433,6 +474,14 @@ pub enum Terminator {
         drop: Option<BasicBlockId>,
     },
 
+    /// Suspends the current generator until `future` completes, then writes its output to
+    /// `destination` and continues at `target`.
+    ///
+    /// rustc desugars `.await` into a loop that polls the future and yields while it is pending.
+    /// Our evaluator has no executor and always polls futures to completion, so we keep `.await`
+    /// as a single suspension point instead.
+    Await { future: Operand, destination: Place, target: BasicBlockId },
+
     /// Indicates the end of dropping a generator.
     ///
     /// Semantically just a `return` (from the generators drop glue). Only permitted in the same situations
@@ -720,7 +769,7 @@ pub enum Rvalue {
     /// Corresponds to source code like `[x; 32]`.
     ///
     /// [#74836]: https://github.com/rust-lang/rust/issues/74836
-    //Repeat(Operand, ty::Const),
+    Repeat(Operand, Const),
 
     /// Creates a reference of the indicated kind to the place.
     ///
@@ -799,6 +848,13 @@ pub enum Rvalue {
     /// Other combinations of types and operators are unsupported.
     CheckedBinaryOp(BinOp, Operand, Operand),
 
+    /// Yields whether the predicate of a `cfg!` call checking `debug_assertions` holds. The other
+    /// `cfg!` calls are literals, but this one depends on the build profile the body is
+    /// interpreted under, see [`Body::debug_assertions_cfgs`].
+    ///
+    /// [`Body::debug_assertions_cfgs`]: hir_def::body::Body::debug_assertions_cfgs
+    CheckCfg(CfgExpr),
+
     /// Computes a value as described by the operation.
     //NullaryOp(NullOp, Ty),
 
@@ -911,10 +967,62 @@ pub struct MirBody {
     /// This field stores the closures directly owned by this body. It is used
     /// in traversing every mir body.
     pub closures: Vec<ClosureId>,
+    /// The state machine layout, if this is the body of a generator or an async block.
+    pub generator_layout: Option<GeneratorLayout>,
+}
+
+/// The state machine a generator or an async block is lowered to.
+///
+/// A generator value stores its upvars, followed by a `u8` state (0 is unresumed, 1 is returned,
+/// 2 is poisoned and `3 + i` means suspended at the `i`th suspension point), followed by the
+/// locals which have to survive a suspension.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GeneratorLayout {
+    /// The blocks that execution continues at after each suspension point.
+    pub resume_blocks: Vec<BasicBlockId>,
+    /// The locals which are live across at least one suspension point.
+    pub saved_locals: Vec<LocalId>,
+}
+
+impl Terminator {
+    fn successors(&self) -> Vec<BasicBlockId> {
+        match self {
+            Terminator::Goto { target } | Terminator::Await { target, .. } => vec![*target],
+            Terminator::SwitchInt { targets, .. } => targets.all_targets().to_vec(),
+            Terminator::Resume
+            | Terminator::Abort
+            | Terminator::Return
+            | Terminator::Unreachable
+            | Terminator::GeneratorDrop => vec![],
+            Terminator::Drop { target, unwind, .. }
+            | Terminator::DropAndReplace { target, unwind, .. } => {
+                iter::once(target).chain(unwind).copied().collect()
+            }
+            Terminator::Call { target, cleanup, .. } => {
+                target.iter().chain(cleanup).copied().collect()
+            }
+            Terminator::Assert { target, cleanup, .. } => {
+                iter::once(target).chain(cleanup).copied().collect()
+            }
+            Terminator::Yield { resume, drop, .. } => {
+                iter::once(resume).chain(drop).copied().collect()
+            }
+            Terminator::FalseEdge { real_target, imaginary_target } => {
+                vec![*real_target, *imaginary_target]
+            }
+            Terminator::FalseUnwind { real_target, unwind } => {
+                iter::once(real_target).chain(unwind).copied().collect()
+            }
+        }
+    }
 }
 
 impl MirBody {
     fn walk_places(&mut self, mut f: impl FnMut(&mut Place)) {
+        self.walk_places_in_blocks(|_, p| f(p))
+    }
+
+    fn walk_places_in_blocks(&mut self, mut f: impl FnMut(BasicBlockId, &mut Place)) {
         fn for_operand(op: &mut Operand, f: &mut impl FnMut(&mut Place)) {
             match op {
                 Operand::Copy(p) | Operand::Move(p) => {
@@ -923,7 +1031,8 @@ impl MirBody {
                 Operand::Constant(_) => (),
             }
         }
-        for (_, block) in self.basic_blocks.iter_mut() {
+        for (block_id, block) in self.basic_blocks.iter_mut() {
+            let mut f = |p: &mut Place| f(block_id, p);
             for statement in &mut block.statements {
                 match &mut statement.kind {
                     StatementKind::Assign(p, r) => {
@@ -932,6 +1041,7 @@ impl MirBody {
                             Rvalue::ShallowInitBox(o, _)
                             | Rvalue::UnaryOp(_, o)
                             | Rvalue::Cast(_, o, _)
+                            | Rvalue::Repeat(o, _)
                             | Rvalue::Use(o) => for_operand(o, &mut f),
                             Rvalue::CopyForDeref(p)
                             | Rvalue::Discriminant(p)
@@ -941,6 +1051,7 @@ impl MirBody {
                                 for_operand(o1, &mut f);
                                 for_operand(o2, &mut f);
                             }
+                            Rvalue::CheckCfg(_) => (),
                             Rvalue::Aggregate(_, ops) => {
                                 for op in ops {
                                     for_operand(op, &mut f);
@@ -984,11 +1095,104 @@ impl MirBody {
                         for_operand(value, &mut f);
                         f(resume_arg);
                     }
+                    Terminator::Await { future, destination, .. } => {
+                        for_operand(future, &mut f);
+                        f(destination);
+                    }
                 },
                 None => (),
             }
         }
     }
+
+    /// Computes the state machine layout of a generator body. A local is saved in the generator if
+    /// it is mentioned both in a block that can reach a suspension point and in a block reachable
+    /// from where that suspension resumes. This is coarser than a real liveness analysis.
+    fn compute_generator_layout(&mut self) -> GeneratorLayout {
+        let mut mentioned: ArenaMap<BasicBlockId, Vec<LocalId>> = ArenaMap::default();
+        self.walk_places_in_blocks(|b, p| {
+            mentioned.entry(b).or_default().push(p.local);
+        });
+        let mut predecessors: ArenaMap<BasicBlockId, Vec<BasicBlockId>> = ArenaMap::default();
+        for (id, block) in self.basic_blocks.iter() {
+            for succ in block.terminator.iter().flat_map(|x| x.successors()) {
+                predecessors.entry(succ).or_default().push(id);
+            }
+        }
+        fn reachable(
+            start: BasicBlockId,
+            edges: impl Fn(BasicBlockId) -> Vec<BasicBlockId>,
+        ) -> Vec<BasicBlockId> {
+            let mut seen: ArenaMap<BasicBlockId, ()> = ArenaMap::default();
+            let mut stack = vec![start];
+            while let Some(b) = stack.pop() {
+                if seen.insert(b, ()).is_none() {
+                    stack.extend(edges(b));
+                }
+            }
+            seen.iter().map(|(b, _)| b).collect()
+        }
+        let locals_of = |blocks: Vec<BasicBlockId>| -> Vec<LocalId> {
+            blocks
+                .into_iter()
+                .flat_map(|b| mentioned.get(b).into_iter().flatten().copied())
+                .collect()
+        };
+        let mut resume_blocks = vec![];
+        let mut not_saved = vec![];
+        let mut saved = ArenaMap::<LocalId, ()>::default();
+        for (id, block) in self.basic_blocks.iter() {
+            let resume = match &block.terminator {
+                Some(Terminator::Yield { resume, resume_arg, .. }) => {
+                    not_saved.push(resume_arg.local);
+                    *resume
+                }
+                Some(Terminator::Await { future, target, destination }) => {
+                    // The awaited future itself is kept in the generator while it is pending.
+                    if let Operand::Copy(p) | Operand::Move(p) = future {
+                        saved.insert(p.local, ());
+                    }
+                    not_saved.push(destination.local);
+                    *target
+                }
+                _ => continue,
+            };
+            resume_blocks.push(resume);
+            let before = reachable(id, |b| predecessors.get(b).cloned().unwrap_or_default());
+            let after = reachable(resume, |b| {
+                self.basic_blocks[b].terminator.iter().flat_map(|x| x.successors()).collect()
+            });
+            let before = locals_of(before);
+            for local in locals_of(after) {
+                if before.contains(&local) {
+                    saved.insert(local, ());
+                }
+            }
+        }
+        let saved_locals = saved
+            .iter()
+            .map(|(l, _)| l)
+            // The return slot and the arguments are never saved.
+            .filter(|l| u32::from(l.into_raw()) as usize > self.arg_count)
+            .filter(|l| !not_saved.contains(l))
+            .collect();
+        GeneratorLayout { resume_blocks, saved_locals }
+    }
+}
+
+/// Returns the closure which holds the captures of a generator or an async block type.
+pub(crate) fn generator_closure_id(db: &dyn HirDatabase, ty: &Ty) -> Option<ClosureId> {
+    let (owner, expr) = match ty.kind(Interner) {
+        TyKind::Generator(id, _) => db.lookup_intern_generator((*id).into()),
+        TyKind::OpaqueType(id, _) => match db.lookup_intern_impl_trait_id((*id).into()) {
+            ImplTraitId::AsyncBlockTypeImplTrait(owner, expr) => (owner, expr),
+            ImplTraitId::ReturnTypeImplTrait(..) | ImplTraitId::TypeAliasImplTrait(..) => {
+                return None
+            }
+        },
+        _ => return None,
+    };
+    Some(db.intern_closure((owner, expr)).into())
 }
 
 fn const_as_usize(c: &Const) -> usize {
@@ -1,19 +1,24 @@
-//! MIR borrow checker, which is used in diagnostics like `unused_mut`
+//! MIR borrow checker, which is used in diagnostics like `unused_mut` and `dead_store`
 
 // Currently it is an ad-hoc implementation, only useful for mutability analysis. Feel free to remove all of these
 // if needed for implementing a proper borrow checker.
 
 use std::{iter, sync::Arc};
 
-use hir_def::DefWithBodyId;
+use hir_def::{
+    body::Body,
+    hir::{BinaryOp, BindingId, Expr, ExprId, Statement, UnaryOp},
+    DefWithBodyId,
+};
 use la_arena::ArenaMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use stdx::never;
 
-use crate::{db::HirDatabase, ClosureId};
+use crate::{db::HirDatabase, ClosureId, InferenceResult, Interner, TyKind};
 
 use super::{
-    BasicBlockId, BorrowKind, LocalId, MirBody, MirLowerError, MirSpan, Place, ProjectionElem,
-    Rvalue, StatementKind, Terminator,
+    BasicBlock, BasicBlockId, BorrowKind, LocalId, MirBody, MirLowerError, MirSpan, Operand, Place,
+    ProjectionElem, Rvalue, StatementKind, Terminator,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,9 +32,18 @@ pub enum MutabilityReason {
 pub struct BorrowckResult {
     pub mir_body: Arc<MirBody>,
     pub mutability_of_locals: ArenaMap<LocalId, MutabilityReason>,
+    pub dead_stores: Vec<DeadStore>,
 }
 
-fn all_mir_bodies(
+/// A side effect free value assigned to a binding, which is always overwritten before it is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadStore {
+    pub binding: BindingId,
+    /// The initializer of the `let` statement, or the right hand side of the assignment.
+    pub value: ExprId,
+}
+
+pub(super) fn all_mir_bodies(
     db: &dyn HirDatabase,
     def: DefWithBodyId,
 ) -> Box<dyn Iterator<Item = Result<Arc<MirBody>, MirLowerError>> + '_> {
@@ -67,7 +81,11 @@ pub fn borrowck_query(
     let r = all_mir_bodies(db, def)
         .map(|body| {
             let body = body?;
-            Ok(BorrowckResult { mutability_of_locals: mutability_of_locals(&body), mir_body: body })
+            Ok(BorrowckResult {
+                mutability_of_locals: mutability_of_locals(&body),
+                dead_stores: dead_stores(db, &body),
+                mir_body: body,
+            })
         })
         .collect::<Result<Vec<_>, MirLowerError>>()?;
     Ok(r.into())
@@ -154,10 +172,21 @@ fn ever_initialized_map(body: &MirBody) -> ArenaMap<BasicBlockId, ArenaMap<Local
                 }
                 target.into_iter().chain(cleanup.into_iter()).copied().collect()
             }
+            Terminator::Yield { resume, resume_arg: destination, drop, .. } => {
+                if destination.projection.len() == 0 && destination.local == l {
+                    is_ever_initialized = true;
+                }
+                iter::once(resume).chain(drop.into_iter()).copied().collect()
+            }
+            Terminator::Await { destination, target, .. } => {
+                if destination.projection.len() == 0 && destination.local == l {
+                    is_ever_initialized = true;
+                }
+                vec![*target]
+            }
             Terminator::Drop { .. }
             | Terminator::DropAndReplace { .. }
             | Terminator::Assert { .. }
-            | Terminator::Yield { .. }
             | Terminator::GeneratorDrop
             | Terminator::FalseEdge { .. }
             | Terminator::FalseUnwind { .. } => {
@@ -240,9 +269,10 @@ fn mutability_of_locals(body: &MirBody) -> ArenaMap<LocalId, MutabilityReason> {
             | Terminator::SwitchInt { .. }
             | Terminator::Drop { .. }
             | Terminator::DropAndReplace { .. }
-            | Terminator::Assert { .. }
-            | Terminator::Yield { .. } => (),
-            Terminator::Call { destination, .. } => {
+            | Terminator::Assert { .. } => (),
+            Terminator::Call { destination, .. }
+            | Terminator::Yield { resume_arg: destination, .. }
+            | Terminator::Await { destination, .. } => {
                 if destination.projection.len() == 0 {
                     if ever_init_map.get(destination.local).copied().unwrap_or_default() {
                         push_mut_span(destination.local, MirSpan::Unknown);
@@ -255,3 +285,237 @@ fn mutability_of_locals(body: &MirBody) -> ArenaMap<LocalId, MutabilityReason> {
     }
     result
 }
+
+fn place_reads(place: &Place, is_write: bool, f: &mut impl FnMut(LocalId)) {
+    // Writing to a part of a local doesn't kill it, so we treat it as a read.
+    if !is_write || !place.projection.is_empty() {
+        f(place.local);
+    }
+    for proj in &place.projection {
+        if let ProjectionElem::Index(local) = proj {
+            f(*local);
+        }
+    }
+}
+
+fn operand_reads(operand: &Operand, f: &mut impl FnMut(LocalId)) {
+    match operand {
+        Operand::Copy(p) | Operand::Move(p) => place_reads(p, false, f),
+        Operand::Constant(_) => (),
+    }
+}
+
+fn rvalue_reads(rvalue: &Rvalue, f: &mut impl FnMut(LocalId)) {
+    match rvalue {
+        Rvalue::ShallowInitBox(o, _)
+        | Rvalue::UnaryOp(_, o)
+        | Rvalue::Cast(_, o, _)
+        | Rvalue::Repeat(o, _)
+        | Rvalue::Use(o) => operand_reads(o, f),
+        Rvalue::CopyForDeref(p) | Rvalue::Discriminant(p) | Rvalue::Len(p) | Rvalue::Ref(_, p) => {
+            place_reads(p, false, f)
+        }
+        Rvalue::CheckedBinaryOp(_, o1, o2) => {
+            operand_reads(o1, f);
+            operand_reads(o2, f);
+        }
+        Rvalue::Aggregate(_, ops) => ops.iter().for_each(|op| operand_reads(op, f)),
+        Rvalue::CheckCfg(_) => (),
+    }
+}
+
+/// Returns the local the terminator writes to as a whole, and calls `f` with the locals it reads.
+fn terminator_effects(terminator: &Terminator, f: &mut impl FnMut(LocalId)) -> Option<LocalId> {
+    let destination = match terminator {
+        Terminator::Goto { .. }
+        | Terminator::Resume
+        | Terminator::Abort
+        | Terminator::Return
+        | Terminator::Unreachable
+        | Terminator::GeneratorDrop
+        | Terminator::FalseEdge { .. }
+        | Terminator::FalseUnwind { .. } => None,
+        Terminator::SwitchInt { discr, .. } => {
+            operand_reads(discr, f);
+            None
+        }
+        Terminator::Drop { place, .. } => {
+            place_reads(place, false, f);
+            None
+        }
+        Terminator::DropAndReplace { place, value, .. } => {
+            place_reads(place, false, f);
+            operand_reads(value, f);
+            None
+        }
+        Terminator::Assert { cond, .. } => {
+            operand_reads(cond, f);
+            None
+        }
+        Terminator::Call { func, args, destination, .. } => {
+            operand_reads(func, f);
+            args.iter().for_each(|arg| operand_reads(arg, f));
+            Some(destination)
+        }
+        Terminator::Yield { value, resume_arg: destination, .. } => {
+            operand_reads(value, f);
+            Some(destination)
+        }
+        Terminator::Await { future, destination, .. } => {
+            operand_reads(future, f);
+            Some(destination)
+        }
+    }?;
+    place_reads(destination, true, f);
+    destination.projection.is_empty().then_some(destination.local)
+}
+
+/// Updates `live`, the set of locals which are live at the end of `block`, to the set of locals
+/// which are live at its start. `on_dead_store` is called for the statements assigning to a local
+/// which isn't live after them.
+fn block_liveness(
+    block: &BasicBlock,
+    live: &mut FxHashSet<LocalId>,
+    mut on_dead_store: impl FnMut(LocalId, MirSpan),
+) {
+    if let Some(terminator) = &block.terminator {
+        let mut reads = Vec::new();
+        if let Some(local) = terminator_effects(terminator, &mut |l| reads.push(l)) {
+            live.remove(&local);
+        }
+        live.extend(reads);
+    }
+    for statement in block.statements.iter().rev() {
+        if let StatementKind::Assign(place, value) = &statement.kind {
+            if place.projection.is_empty() {
+                if !live.remove(&place.local) {
+                    on_dead_store(place.local, statement.span);
+                }
+            } else {
+                place_reads(place, true, &mut |l| {
+                    live.insert(l);
+                });
+            }
+            rvalue_reads(value, &mut |l| {
+                live.insert(l);
+            });
+        }
+    }
+}
+
+/// Finds the side effect free values assigned to bindings which are overwritten on all the paths
+/// before they are read. Bindings which are never read or borrowed are ignored, as the former are
+/// already reported by rustc and the latter can be read through the reference.
+fn dead_stores(db: &dyn HirDatabase, body: &MirBody) -> Vec<DeadStore> {
+    let mut borrowed = FxHashSet::default();
+    let mut read = FxHashSet::default();
+    for (_, block) in body.basic_blocks.iter() {
+        for statement in &block.statements {
+            if let StatementKind::Assign(place, value) = &statement.kind {
+                if let Rvalue::Ref(_, p) = value {
+                    borrowed.insert(p.local);
+                }
+                place_reads(place, true, &mut |l| {
+                    read.insert(l);
+                });
+                rvalue_reads(value, &mut |l| {
+                    read.insert(l);
+                });
+            }
+        }
+        if let Some(terminator) = &block.terminator {
+            terminator_effects(terminator, &mut |l| {
+                read.insert(l);
+            });
+        }
+    }
+
+    let mut live_in: ArenaMap<BasicBlockId, FxHashSet<LocalId>> =
+        body.basic_blocks.iter().map(|(id, _)| (id, FxHashSet::default())).collect();
+    let live_out = |live_in: &ArenaMap<BasicBlockId, FxHashSet<LocalId>>, block: &BasicBlock| {
+        let successors = block.terminator.iter().flat_map(|it| it.successors());
+        successors.flat_map(|it| live_in[it].iter().copied()).collect::<FxHashSet<_>>()
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (id, block) in body.basic_blocks.iter().rev() {
+            let mut live = live_out(&live_in, block);
+            block_liveness(block, &mut live, |_, _| ());
+            if live != live_in[id] {
+                live_in.insert(id, live);
+                changed = true;
+            }
+        }
+    }
+
+    let bindings: FxHashMap<LocalId, BindingId> =
+        body.binding_locals.iter().map(|(binding, &local)| (local, binding)).collect();
+    let hir_body = db.body(body.owner);
+    let infer = db.infer(body.owner);
+    let mut result = Vec::new();
+    for (_, block) in body.basic_blocks.iter() {
+        let mut live = live_out(&live_in, block);
+        block_liveness(block, &mut live, |local, span| {
+            let Some(&binding) = bindings.get(&local) else { return };
+            if borrowed.contains(&local) || !read.contains(&local) {
+                return;
+            }
+            if hir_body.bindings[binding].name.as_str().map_or(true, |it| it.starts_with('_')) {
+                return;
+            }
+            let Some(value) = stored_value(&hir_body, span) else { return };
+            if is_side_effect_free(&hir_body, &infer, value) {
+                result.push(DeadStore { binding, value });
+            }
+        });
+    }
+    result
+}
+
+/// Returns the value stored by the statement with `span`, if it's the initializer of a `let`
+/// statement binding a single name, or a plain assignment.
+fn stored_value(body: &Body, span: MirSpan) -> Option<ExprId> {
+    match span {
+        MirSpan::PatId(pat) => body.exprs.iter().find_map(|(_, expr)| match expr {
+            Expr::Block { statements, .. }
+            | Expr::Async { statements, .. }
+            | Expr::Const { statements, .. }
+            | Expr::Unsafe { statements, .. } => statements.iter().find_map(|it| match it {
+                Statement::Let { pat: p, initializer, else_branch: None, .. } if *p == pat => {
+                    *initializer
+                }
+                _ => None,
+            }),
+            _ => None,
+        }),
+        MirSpan::ExprId(expr) => match &body[expr] {
+            Expr::BinaryOp { rhs, op: Some(BinaryOp::Assignment { op: None }), .. } => Some(*rhs),
+            _ => None,
+        },
+        MirSpan::Unknown => None,
+    }
+}
+
+fn is_side_effect_free(body: &Body, infer: &InferenceResult, expr: ExprId) -> bool {
+    let is_scalar = |expr: ExprId| matches!(infer[expr].kind(Interner), TyKind::Scalar(_));
+    let all = |exprs: &[ExprId]| exprs.iter().all(|&it| is_side_effect_free(body, infer, it));
+    match &body[expr] {
+        Expr::Literal(_) | Expr::Path(_) | Expr::Closure { .. } => true,
+        Expr::Tuple { exprs, .. } => all(exprs),
+        Expr::Array(hir_def::hir::Array::ElementList { elements, .. }) => all(elements),
+        Expr::Array(hir_def::hir::Array::Repeat { initializer, .. }) => {
+            is_side_effect_free(body, infer, *initializer)
+        }
+        Expr::Ref { expr, .. } | Expr::Cast { expr, .. } => is_side_effect_free(body, infer, *expr),
+        Expr::UnaryOp { expr, op: UnaryOp::Not | UnaryOp::Neg } => {
+            is_scalar(*expr) && is_side_effect_free(body, infer, *expr)
+        }
+        Expr::BinaryOp { lhs, rhs, op: Some(op) } => {
+            !matches!(op, BinaryOp::Assignment { .. })
+                && infer.method_resolution(expr).is_none()
+                && all(&[*lhs, *rhs])
+        }
+        _ => false,
+    }
+}
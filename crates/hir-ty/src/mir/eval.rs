@@ -1,46 +1,102 @@
 //! This module provides a MIR interpreter, which is used in const eval.
 
-use std::{borrow::Cow, collections::HashMap, iter, ops::Range, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    iter,
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
 
-use base_db::CrateId;
+use base_db::{CrateId, CrateOrigin};
+use cfg::{CfgAtom, CfgDiff};
 use chalk_ir::{
     fold::{FallibleTypeFolder, TypeFoldable, TypeSuperFoldable},
-    DebruijnIndex, TyKind,
+    DebruijnIndex, Scalar, TyKind,
 };
 use hir_def::{
     builtin_type::BuiltinType,
+    hir::BindingId,
     lang_item::{lang_attr, LangItem},
-    layout::{TagEncoding, Variants},
-    AdtId, DefWithBodyId, EnumVariantId, FunctionId, HasModule, ItemContainerId, Lookup, VariantId,
+    layout::{Endian, TagEncoding, Variants},
+    AdtId, DefWithBodyId, EnumId, EnumVariantId, FieldId, FunctionId, HasModule, ItemContainerId,
+    LocalEnumVariantId, Lookup, StaticId, UnionId, VariantId,
 };
 use intern::Interned;
 use la_arena::ArenaMap;
+use rustc_hash::FxHashSet;
+use rustc_index::vec::Idx;
 
 use crate::{
-    consteval::{intern_const_scalar, ConstEvalError},
+    consteval::{intern_const_scalar, try_const_usize, ConstEvalError},
     db::HirDatabase,
+    display::HirDisplay,
     from_placeholder_idx,
-    infer::{normalize, PointerCast},
+    infer::{normalize, type_alias_impl_trait_hidden_type, PointerCast},
     layout::{layout_of_ty, Layout, LayoutError, RustcEnumVariantIdx},
     mapping::from_chalk,
-    method_resolution::{is_dyn_method, lookup_impl_method},
+    method_resolution::{is_dyn_method, lookup_impl_const, lookup_impl_method},
     traits::FnTrait,
-    CallableDefId, ClosureId, Const, ConstScalar, FnDefId, GenericArgData, Interner, MemoryMap,
-    Substitution, TraitEnvironment, Ty, TyBuilder, TyExt,
+    utils::{generics, Generics},
+    CallableDefId, ClosureId, Const, ConstScalar, FnDefId, GenericArg, GenericArgData, Interner,
+    MemoryMap, Substitution, TraitEnvironment, Ty, TyBuilder, TyExt,
 };
 
 use super::{
-    const_as_usize, return_slot, AggregateKind, BinOp, CastKind, LocalId, MirBody, MirLowerError,
-    Operand, Place, ProjectionElem, Rvalue, StatementKind, Terminator, UnOp,
+    const_as_usize, generator_closure_id, return_slot, AggregateKind, BinOp, CastKind, LocalId,
+    MirBody, MirLowerError, Operand, Place, ProjectionElem, Rvalue, StatementKind, Terminator,
+    UnOp,
 };
 
-macro_rules! from_bytes {
-    ($ty:tt, $value:expr) => {
-        ($ty::from_le_bytes(match ($value).try_into() {
-            Ok(x) => x,
-            Err(_) => return Err(MirEvalError::TypeError("mismatched size")),
-        }))
-    };
+mod external;
+mod global_allocator;
+mod memory;
+mod shim;
+
+pub use external::{ExternalCall, ExternalValue, ExternalValueProvider};
+use global_allocator::GlobalAllocator;
+use memory::{Memory, PAGE_SIZE};
+use shim::Rng;
+
+/// The number of executed blocks between two checks for cancellation.
+const CANCELLATION_CHECK_INTERVAL: usize = 1000;
+/// The maximum number of executed blocks.
+const EXECUTION_LIMIT: usize = 100_000;
+/// The maximum number of nested function calls.
+const STACK_DEPTH_LIMIT: usize = 100;
+/// The number of functions listed in [`MirEvalError::ExecutionLimitExceeded`].
+const STEP_PROFILE_LEN: usize = 5;
+/// The seed of the entropy of the evaluations which aren't given one, see
+/// [`interpret_mir_with_seed`].
+pub const DEFAULT_SEED: u64 = 0x5eed_5eed_5eed_5eed;
+
+/// The cargo profile whose semantics the evaluated code has, see [`interpret_mir_with_profile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildProfile {
+    Debug,
+    Release,
+}
+
+impl BuildProfile {
+    /// Whether `cfg(debug_assertions)` holds, which gates the `debug_assert!`s, in the crates
+    /// built with the profile. The sysroot crates are prebuilt without it in both profiles.
+    pub fn debug_assertions(self) -> bool {
+        self == BuildProfile::Debug
+    }
+
+    /// Whether overflowing arithmetic panics, instead of wrapping around.
+    pub fn overflow_checks(self) -> bool {
+        self == BuildProfile::Debug
+    }
+}
+
+impl std::fmt::Display for BuildProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BuildProfile::Debug => "debug",
+            BuildProfile::Release => "release",
+        })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -63,18 +119,13 @@ impl VTableMap {
     fn ty(&self, id: usize) -> Result<&Ty> {
         self.id_to_ty.get(id).ok_or(MirEvalError::InvalidVTableId(id))
     }
-
-    fn ty_of_bytes(&self, bytes: &[u8]) -> Result<&Ty> {
-        let id = from_bytes!(usize, bytes);
-        self.ty(id)
-    }
 }
 
 pub struct Evaluator<'a> {
     db: &'a dyn HirDatabase,
     trait_env: Arc<TraitEnvironment>,
-    stack: Vec<u8>,
-    heap: Vec<u8>,
+    stack: Memory,
+    heap: Memory,
     /// We don't really have function pointers, i.e. pointers to some assembly instructions that we can run. Instead, we
     /// store the type as an interned id in place of function and vtable pointers, and we recover back the type at the
     /// time of use.
@@ -86,6 +137,52 @@ pub struct Evaluator<'a> {
     execution_limit: usize,
     /// An additional limit on stack depth, to prevent stack overflow
     stack_depth_limit: usize,
+    /// The number of executed blocks of each function, in the order they were first called, to
+    /// tell which functions used up the execution limit.
+    steps: Vec<(DefWithBodyId, usize)>,
+    /// The index of each function in `steps`.
+    step_slots: HashMap<DefWithBodyId, usize>,
+    /// Pointer size of the target. Memory of the interpreter is laid out for the target, so pointers,
+    /// `usize` and `isize` values are this many bytes wide.
+    ptr_size: usize,
+    /// The executed assignments and the values they stored, if tracing is enabled. See
+    /// [`interpret_mir_with_trace`].
+    trace: Option<Vec<String>>,
+    /// Called with the number of executed blocks with every check for cancellation. The evaluation
+    /// stops with [`MirEvalError::Interrupted`] when it returns `false`.
+    progress: Option<&'a dyn Fn(usize) -> bool>,
+    /// The binding whose first value is captured, see [`interpret_mir_until_bound`].
+    watch: Option<Watch>,
+    /// Byte order of the target, which is used for every scalar in the interpreter memory. Note that
+    /// the bytes of a [`Const`] are always little endian, see [`Evaluator::convert_endianness`].
+    endian: Endian,
+    /// The source of the entropy of the shims needing some, like the ones of the random keys of
+    /// `HashMap`s. It's deterministic, so that evaluations can be reproduced and cached.
+    rng: Rng,
+    /// Answers the calls of the functions it provides, see [`interpret_mir_with_external_values`].
+    external_values: Option<&'a dyn ExternalValueProvider>,
+    /// The paths of the functions answered by `external_values`, or `None` for the other called
+    /// functions.
+    external_paths: HashMap<FunctionId, Option<String>>,
+    /// The `#[global_allocator]` the allocation shims call, see
+    /// [`interpret_mir_with_global_allocator`].
+    global_allocator: Option<GlobalAllocator>,
+    /// The `#[alloc_error_handler]` the `__rust_alloc_error_handler` shim calls.
+    alloc_error_handler: Option<FunctionId>,
+    /// Whether overflowing arithmetic panics, see [`BuildProfile::overflow_checks`]. Consts are
+    /// evaluated separately, always with overflow checks, like rustc does.
+    overflow_checks: bool,
+    /// Whether the `cfg!` calls of the crates outside of the sysroot see `debug_assertions`, see
+    /// [`BuildProfile::debug_assertions`], or `None` to check them like the crate graph does.
+    debug_assertions: Option<bool>,
+}
+
+/// A binding of a body, and the first value it got. The evaluation stops with
+/// [`MirEvalError::Interrupted`] once the binding has a value.
+struct Watch {
+    owner: DefWithBodyId,
+    binding: BindingId,
+    value: Option<Const>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -114,7 +211,7 @@ impl Interval {
         Self { addr, size }
     }
 
-    fn get<'a>(&self, memory: &'a Evaluator<'a>) -> Result<&'a [u8]> {
+    fn get<'a>(&self, memory: &'a Evaluator<'a>) -> Result<Cow<'a, [u8]>> {
         memory.read_memory(self.addr, self.size)
     }
 
@@ -123,9 +220,21 @@ impl Interval {
     }
 
     fn write_from_interval(&self, memory: &mut Evaluator<'_>, interval: Interval) -> Result<()> {
-        // FIXME: this could be more efficient
-        let bytes = &interval.get(memory)?.to_vec();
-        memory.write_memory(self.addr, bytes)
+        // Copy page by page, so that copying a large value doesn't materialize its memory at once.
+        // Like `memmove`, we go backwards if the destination is after the source, in case they overlap.
+        let backwards = self.addr.to_usize() > interval.addr.to_usize();
+        let chunk_count = (interval.size + PAGE_SIZE - 1) / PAGE_SIZE;
+        let no_provenance = memory.no_provenance_marks(interval);
+        for i in 0..chunk_count {
+            let start = if backwards { chunk_count - 1 - i } else { i } * PAGE_SIZE;
+            let end = interval.size.min(start + PAGE_SIZE);
+            let bytes = interval.slice(start..end).get(memory)?.into_owned();
+            memory.write_memory(self.addr.offset(start), &bytes)?;
+        }
+        for offset in no_provenance {
+            memory.mark_no_provenance(self.addr.offset(offset));
+        }
+        Ok(())
     }
 
     fn slice(self, range: Range<usize>) -> Interval {
@@ -134,7 +243,7 @@ impl Interval {
 }
 
 impl IntervalAndTy {
-    fn get<'a>(&self, memory: &'a Evaluator<'a>) -> Result<&'a [u8]> {
+    fn get<'a>(&self, memory: &'a Evaluator<'a>) -> Result<Cow<'a, [u8]>> {
         memory.read_memory(self.interval.addr, self.interval.size)
     }
 
@@ -152,39 +261,55 @@ impl IntervalAndTy {
 enum IntervalOrOwned {
     Owned(Vec<u8>),
     Borrowed(Interval),
+    /// `count` copies of `element`, which is kept apart so that large arrays made of repeated bytes
+    /// can be written without materializing them.
+    Repeated {
+        element: Vec<u8>,
+        count: usize,
+    },
 }
 impl IntervalOrOwned {
-    pub(crate) fn to_vec(self, memory: &Evaluator<'_>) -> Result<Vec<u8>> {
-        Ok(match self {
-            IntervalOrOwned::Owned(o) => o,
-            IntervalOrOwned::Borrowed(b) => b.get(memory)?.to_vec(),
-        })
+    fn write_to(self, memory: &mut Evaluator<'_>, addr: Address) -> Result<()> {
+        match self {
+            IntervalOrOwned::Owned(o) => memory.write_memory(addr, &o),
+            IntervalOrOwned::Borrowed(b) => {
+                Interval::new(addr, b.size).write_from_interval(memory, b)
+            }
+            IntervalOrOwned::Repeated { element, count } => match element.split_first() {
+                Some((&first, rest)) if rest.iter().all(|&x| x == first) => {
+                    memory.fill_memory(addr, element.len() * count, first)
+                }
+                _ => {
+                    for i in 0..count {
+                        memory.write_memory(addr.offset(i * element.len()), &element)?;
+                    }
+                    Ok(())
+                }
+            },
+        }
     }
 }
 
-impl Address {
-    fn from_bytes(x: &[u8]) -> Result<Self> {
-        Ok(Address::from_usize(from_bytes!(usize, x)))
-    }
+// These offsets should fit in the pointer of every target we support (that is, in 32 bits), so that
+// the encoded address survives a round trip through the memory of the interpreter.
+const STACK_OFFSET: usize = 1 << 30;
+const HEAP_OFFSET: usize = 1 << 29;
 
+impl Address {
     fn from_usize(x: usize) -> Self {
-        if x > usize::MAX / 2 {
-            Stack(x - usize::MAX / 2)
-        } else if x > usize::MAX / 4 {
-            Heap(x - usize::MAX / 4)
+        if x >= STACK_OFFSET {
+            Stack(x - STACK_OFFSET)
+        } else if x >= HEAP_OFFSET {
+            Heap(x - HEAP_OFFSET)
         } else {
             Invalid(x)
         }
     }
 
-    fn to_bytes(&self) -> Vec<u8> {
-        usize::to_le_bytes(self.to_usize()).to_vec()
-    }
-
     fn to_usize(&self) -> usize {
         let as_num = match self {
-            Stack(x) => *x + usize::MAX / 2,
-            Heap(x) => *x + usize::MAX / 4,
+            Stack(x) => *x + STACK_OFFSET,
+            Heap(x) => *x + HEAP_OFFSET,
             Invalid(x) => *x,
         };
         as_num
@@ -215,14 +340,26 @@ pub enum MirEvalError {
     Panic(String),
     MirLowerError(FunctionId, MirLowerError),
     MirLowerErrorForClosure(ClosureId, MirLowerError),
+    /// The evaluation reached a function whose body can't be lowered. Holds all such functions
+    /// which can be called from the evaluated body, not only the reached one.
+    UnlowerableCallees(Vec<(FunctionId, MirLowerError)>),
     TypeIsUnsized(Ty, &'static str),
     NotSupported(String),
     InvalidConst(Const),
     InFunction(FunctionId, Box<MirEvalError>),
-    ExecutionLimitExceeded,
+    /// Holds the functions which executed the most blocks, the costliest first, with their
+    /// numbers of executed blocks.
+    ExecutionLimitExceeded(Vec<(DefWithBodyId, usize)>),
     StackOverflow,
+    /// The evaluation was stopped by its progress callback, see [`interpret_mir_with_trace`], or
+    /// because the watched binding got its value, see [`interpret_mir_until_bound`].
+    Interrupted,
     TargetDataLayoutNotAvailable,
     InvalidVTableId(usize),
+    /// The external value provider failed to answer the call of the function, with this message.
+    ExternalCallFailed(FunctionId, String),
+    /// The `#[global_allocator]` static can't be used by the allocation shims, for this reason.
+    InvalidGlobalAllocator(StaticId, &'static str),
 }
 
 impl std::fmt::Debug for MirEvalError {
@@ -239,15 +376,31 @@ impl std::fmt::Debug for MirEvalError {
             Self::Panic(msg) => write!(f, "Panic with message:\n{msg:?}"),
             Self::TargetDataLayoutNotAvailable => write!(f, "TargetDataLayoutNotAvailable"),
             Self::TypeIsUnsized(ty, it) => write!(f, "{ty:?} is unsized. {it} should be sized."),
-            Self::ExecutionLimitExceeded => write!(f, "execution limit exceeded"),
+            Self::ExecutionLimitExceeded(steps) => {
+                write!(f, "execution limit exceeded")?;
+                if !steps.is_empty() {
+                    write!(f, ", most executed blocks in: {steps:?}")?;
+                }
+                Ok(())
+            }
             Self::StackOverflow => write!(f, "stack overflow"),
+            Self::Interrupted => write!(f, "interrupted"),
             Self::MirLowerError(arg0, arg1) => {
                 f.debug_tuple("MirLowerError").field(arg0).field(arg1).finish()
             }
             Self::MirLowerErrorForClosure(arg0, arg1) => {
                 f.debug_tuple("MirLowerError").field(arg0).field(arg1).finish()
             }
+            Self::UnlowerableCallees(arg0) => {
+                f.debug_tuple("UnlowerableCallees").field(arg0).finish()
+            }
             Self::InvalidVTableId(arg0) => f.debug_tuple("InvalidVTableId").field(arg0).finish(),
+            Self::ExternalCallFailed(func, msg) => {
+                f.debug_tuple("ExternalCallFailed").field(func).field(msg).finish()
+            }
+            Self::InvalidGlobalAllocator(static_, reason) => {
+                f.debug_tuple("InvalidGlobalAllocator").field(static_).field(reason).finish()
+            }
             Self::NotSupported(arg0) => f.debug_tuple("NotSupported").field(arg0).finish(),
             Self::InvalidConst(arg0) => {
                 let data = &arg0.data(Interner);
@@ -299,15 +452,121 @@ pub fn interpret_mir(
     // (and probably should) do better here, for example by excluding bindings outside of the target expression.
     assert_placeholder_ty_is_unused: bool,
 ) -> Result<Const> {
-    let ty = body.locals[return_slot()].ty.clone();
     let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
-    let bytes = evaluator.interpret_mir(&body, None.into_iter(), subst.clone())?;
-    let memory_map = evaluator.create_memory_map(
-        &bytes,
-        &ty,
-        &Locals { ptr: &ArenaMap::new(), body: &body, subst: &subst },
-    )?;
-    return Ok(intern_const_scalar(ConstScalar::Bytes(bytes, memory_map), ty));
+    evaluator.interpret_mir_to_const(body, subst)
+}
+
+/// Like [`interpret_mir`], with the entropy of the evaluation drawn from a generator seeded with
+/// `seed` instead of [`DEFAULT_SEED`]. Evaluations with the same seed have the same results.
+pub fn interpret_mir_with_seed(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    subst: Substitution,
+    assert_placeholder_ty_is_unused: bool,
+    seed: u64,
+) -> Result<Const> {
+    let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
+    evaluator.rng = Rng::new(seed);
+    evaluator.interpret_mir_to_const(body, subst)
+}
+
+/// Like [`interpret_mir_with_seed`], with the calls of the functions provided by `external_values`
+/// answered by it instead of being executed. Consts used by the body are evaluated separately,
+/// without it.
+pub fn interpret_mir_with_external_values(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    subst: Substitution,
+    assert_placeholder_ty_is_unused: bool,
+    seed: u64,
+    external_values: &dyn ExternalValueProvider,
+) -> Result<Const> {
+    let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
+    evaluator.rng = Rng::new(seed);
+    evaluator.external_values = Some(external_values);
+    evaluator.interpret_mir_to_const(body, subst)
+}
+
+/// Like [`interpret_mir_with_seed`], with the allocation shims calling the `#[global_allocator]`
+/// and the `#[alloc_error_handler]` of the crate graph, if they are defined, instead of allocating
+/// on the heap of the evaluator. Their MIR is interpreted under the same execution limit.
+pub fn interpret_mir_with_global_allocator(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    subst: Substitution,
+    assert_placeholder_ty_is_unused: bool,
+    seed: u64,
+) -> Result<Const> {
+    let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
+    evaluator.rng = Rng::new(seed);
+    evaluator.use_entry_allocator()?;
+    evaluator.interpret_mir_to_const(body, subst)
+}
+
+/// Like [`interpret_mir_with_seed`], with the arithmetic overflows and the `cfg!(debug_assertions)`
+/// checks, like the ones of `debug_assert!`, of the body and of the functions it calls handled like
+/// under `profile`. The code gated by `#[cfg(debug_assertions)]` attributes is the one of the crate
+/// graph.
+pub fn interpret_mir_with_profile(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    subst: Substitution,
+    assert_placeholder_ty_is_unused: bool,
+    seed: u64,
+    profile: BuildProfile,
+) -> Result<Const> {
+    let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
+    evaluator.rng = Rng::new(seed);
+    evaluator.overflow_checks = profile.overflow_checks();
+    evaluator.debug_assertions = Some(profile.debug_assertions());
+    evaluator.interpret_mir_to_const(body, subst)
+}
+
+/// Like [`interpret_mir`], but also returns the number of blocks executed in each function, the
+/// costliest first. Consts used by the body are evaluated separately, so their blocks are not
+/// counted.
+pub fn interpret_mir_with_step_profile(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    subst: Substitution,
+    assert_placeholder_ty_is_unused: bool,
+) -> (Result<Const>, Vec<(DefWithBodyId, usize)>) {
+    let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
+    let result = evaluator.interpret_mir_to_const(body, subst);
+    (result, evaluator.step_profile(usize::MAX))
+}
+
+/// Like [`interpret_mir`], but also returns a trace of the executed assignments, in the order they
+/// were executed, with the values they stored. Consts used by the body are evaluated separately,
+/// so their statements are not part of the trace.
+pub fn interpret_mir_with_trace(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    subst: Substitution,
+    assert_placeholder_ty_is_unused: bool,
+    progress: &dyn Fn(usize) -> bool,
+) -> (Result<Const>, Vec<String>) {
+    let mut evaluator = Evaluator::new(db, body, assert_placeholder_ty_is_unused);
+    evaluator.trace = Some(Vec::new());
+    evaluator.progress = Some(progress);
+    let result = evaluator.interpret_mir_to_const(body, subst);
+    (result, evaluator.trace.unwrap_or_default())
+}
+
+/// Like [`interpret_mir`], but stops as soon as the binding `binding` of the owner of `body` gets
+/// its first value, and returns that value. Returns `None` if the body returns before that.
+pub fn interpret_mir_until_bound(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    binding: BindingId,
+) -> Result<Option<Const>> {
+    let mut evaluator = Evaluator::new(db, body, false);
+    evaluator.watch = Some(Watch { owner: body.owner, binding, value: None });
+    let result = evaluator.interpret_mir_to_const(body, Substitution::empty(Interner));
+    match evaluator.watch.and_then(|it| it.value) {
+        Some(value) => Ok(Some(value)),
+        None => result.map(|_| None),
+    }
 }
 
 impl Evaluator<'_> {
@@ -318,19 +577,50 @@ impl Evaluator<'_> {
     ) -> Evaluator<'a> {
         let crate_id = body.owner.module(db.upcast()).krate();
         let trait_env = db.trait_environment_for_body(body.owner);
+        let (ptr_size, endian) = match db.target_data_layout(crate_id) {
+            Some(x) => (x.pointer_size.bytes_usize(), x.endian),
+            None => (8, Endian::Little),
+        };
         Evaluator {
-            stack: vec![0],
-            heap: vec![0],
+            stack: Memory::zeroed(1),
+            heap: Memory::zeroed(1),
             vtable_map: VTableMap::default(),
             db,
             trait_env,
             crate_id,
             assert_placeholder_ty_is_unused,
-            stack_depth_limit: 100,
-            execution_limit: 100_000,
+            stack_depth_limit: STACK_DEPTH_LIMIT,
+            execution_limit: EXECUTION_LIMIT,
+            steps: Vec::new(),
+            step_slots: HashMap::new(),
+            ptr_size,
+            trace: None,
+            progress: None,
+            watch: None,
+            endian,
+            rng: Rng::new(DEFAULT_SEED),
+            external_values: None,
+            external_paths: HashMap::new(),
+            global_allocator: None,
+            alloc_error_handler: None,
+            overflow_checks: true,
+            debug_assertions: None,
         }
     }
 
+    fn interpret_mir_to_const(&mut self, body: &MirBody, subst: Substitution) -> Result<Const> {
+        let ty = body.locals[return_slot()].ty.clone();
+        let result = match self.interpret_mir(&body, None.into_iter(), subst.clone()) {
+            Ok(it) => it,
+            Err(e) => return Err(self.with_unlowerable_callees(e, body, &subst)),
+        };
+        let mut bytes = result.get(self)?.into_owned();
+        let locals = &Locals { ptr: &ArenaMap::new(), body: &body, subst: &subst };
+        let memory_map = self.create_memory_map(&bytes, &ty, locals)?;
+        self.convert_endianness(&mut bytes, &ty, locals)?;
+        Ok(intern_const_scalar(ConstScalar::Bytes(bytes, memory_map), ty))
+    }
+
     fn place_addr(&self, p: &Place, locals: &Locals<'_>) -> Result<Address> {
         Ok(self.place_addr_and_ty_and_metadata(p, locals)?.0)
     }
@@ -348,42 +638,87 @@ impl Evaluator<'_> {
     }
 
     fn ptr_size(&self) -> usize {
-        match self.db.target_data_layout(self.crate_id) {
-            Some(x) => x.pointer_size.bytes_usize(),
-            None => 8,
+        self.ptr_size
+    }
+
+    /// Extends an integer stored in the target byte order to 16 bytes, in little endian order.
+    fn pad16(&self, x: &[u8], is_signed: bool) -> [u8; 16] {
+        match self.endian {
+            Endian::Little => pad16(x, is_signed),
+            Endian::Big => {
+                let mut x = x.to_vec();
+                x.reverse();
+                pad16(&x, is_signed)
+            }
         }
     }
 
+    /// Truncates a 16 bytes little endian integer to `size` bytes in the target byte order. It is
+    /// the inverse of [`Evaluator::pad16`].
+    fn truncate16(&self, x: [u8; 16], size: usize) -> Vec<u8> {
+        let mut r = x[0..size].to_vec();
+        if self.endian == Endian::Big {
+            r.reverse();
+        }
+        r
+    }
+
+    fn read_usize(&self, x: &[u8]) -> Result<usize> {
+        if x.len() != self.ptr_size() {
+            return Err(MirEvalError::TypeError("mismatched size"));
+        }
+        Ok(u128::from_le_bytes(self.pad16(x, false)) as usize)
+    }
+
+    fn usize_to_bytes(&self, x: usize) -> Vec<u8> {
+        self.truncate16((x as u128).to_le_bytes(), self.ptr_size())
+    }
+
+    fn read_address(&self, x: &[u8]) -> Result<Address> {
+        Ok(Address::from_usize(self.read_usize(x)?))
+    }
+
+    fn address_to_bytes(&self, addr: Address) -> Vec<u8> {
+        self.usize_to_bytes(addr.to_usize())
+    }
+
     fn place_addr_and_ty_and_metadata<'a>(
         &'a self,
         p: &Place,
         locals: &'a Locals<'a>,
-    ) -> Result<(Address, Ty, Option<Interval>)> {
+    ) -> Result<(Address, Ty, Option<IntervalOrOwned>)> {
         let mut addr = locals.ptr[p.local];
         let mut ty: Ty =
             self.ty_filler(&locals.body.locals[p.local].ty, locals.subst, locals.body.owner)?;
-        let mut metadata = None; // locals are always sized
+        let mut metadata: Option<IntervalOrOwned> = None; // locals are always sized
         for proj in &p.projection {
             let prev_ty = ty.clone();
-            ty = proj.projected_ty(ty, self.db, |c, f| {
-                let (def, _) = self.db.lookup_intern_closure(c.into());
-                let infer = self.db.infer(def);
-                let (captures, _) = infer.closure_info(&c);
-                captures.get(f).expect("broken closure field").ty.clone()
-            });
+            ty = proj.projected_ty(
+                ty,
+                self.db,
+                |c, f| {
+                    let (def, _) = self.db.lookup_intern_closure(c.into());
+                    let infer = self.db.infer(def);
+                    let (captures, _) = infer.closure_info(&c);
+                    captures.get(f).expect("broken closure field").ty.clone()
+                },
+                self.crate_id,
+            );
             match proj {
                 ProjectionElem::Deref => {
                     metadata = if self.size_of(&ty, locals)?.is_none() {
-                        Some(Interval { addr: addr.offset(self.ptr_size()), size: self.ptr_size() })
+                        Some(IntervalOrOwned::Borrowed(Interval {
+                            addr: addr.offset(self.ptr_size()),
+                            size: self.ptr_size(),
+                        }))
                     } else {
                         None
                     };
-                    let x = from_bytes!(usize, self.read_memory(addr, self.ptr_size())?);
-                    addr = Address::from_usize(x);
+                    addr = self.read_pointer(Interval::new(addr, self.ptr_size()))?;
                 }
                 ProjectionElem::Index(op) => {
                     let offset =
-                        from_bytes!(usize, self.read_memory(locals.ptr[*op], self.ptr_size())?);
+                        self.read_usize(&self.read_memory(locals.ptr[*op], self.ptr_size())?)?;
                     metadata = None; // Result of index is always sized
                     let ty_size =
                         self.size_of_sized(&ty, locals, "array inner type should be sized")?;
@@ -420,16 +755,58 @@ impl Evaluator<'_> {
                     // FIXME: support structs with unsized fields
                     metadata = None;
                 }
-                ProjectionElem::ConstantIndex { .. } => {
-                    not_supported!("constant index")
+                &ProjectionElem::ConstantIndex { offset, from_end, .. } => {
+                    let offset = if from_end {
+                        let len = self.array_or_slice_len(&prev_ty, metadata.as_ref())?;
+                        len - offset as usize
+                    } else {
+                        offset as usize
+                    };
+                    metadata = None; // Result of index is always sized
+                    let ty_size =
+                        self.size_of_sized(&ty, locals, "array inner type should be sized")?;
+                    addr = addr.offset(ty_size * offset);
+                }
+                &ProjectionElem::Subslice { from, to, from_end } => {
+                    let inner_ty = match prev_ty.kind(Interner) {
+                        TyKind::Array(inner, _) | TyKind::Slice(inner) => inner.clone(),
+                        _ => return Err(MirEvalError::TypeError("subslice of non array/slice")),
+                    };
+                    // Subslices of arrays are arrays, so only slices need a new length.
+                    metadata = match metadata {
+                        Some(m) if from_end => {
+                            let len = self.array_or_slice_len(&prev_ty, Some(&m))?;
+                            let len = len - from as usize - to as usize;
+                            Some(IntervalOrOwned::Owned(self.usize_to_bytes(len)))
+                        }
+                        _ => None,
+                    };
+                    let ty_size =
+                        self.size_of_sized(&inner_ty, locals, "array inner type should be sized")?;
+                    addr = addr.offset(ty_size * from as usize);
                 }
-                ProjectionElem::Subslice { .. } => not_supported!("subslice"),
                 ProjectionElem::OpaqueCast(_) => not_supported!("opaque cast"),
             }
         }
         Ok((addr, ty, metadata))
     }
 
+    /// Returns the length of an array or slice, given the metadata of the place if it is a slice.
+    fn array_or_slice_len(&self, ty: &Ty, metadata: Option<&IntervalOrOwned>) -> Result<usize> {
+        match ty.kind(Interner) {
+            TyKind::Array(_, len) => match try_const_usize(len) {
+                Some(len) => Ok(len as usize),
+                None => not_supported!("array with unknown length"),
+            },
+            TyKind::Slice(_) => match metadata {
+                Some(IntervalOrOwned::Borrowed(m)) => self.read_usize(&m.get(self)?),
+                Some(IntervalOrOwned::Owned(m)) => self.read_usize(m),
+                _ => Err(MirEvalError::TypeError("slice without length metadata")),
+            },
+            _ => Err(MirEvalError::TypeError("length of non array/slice type")),
+        }
+    }
+
     fn layout(&self, ty: &Ty) -> Result<Layout> {
         layout_of_ty(self.db, ty, self.crate_id)
             .map_err(|e| MirEvalError::LayoutError(e, ty.clone()))
@@ -462,14 +839,15 @@ impl Evaluator<'_> {
     fn interpret_mir(
         &mut self,
         body: &MirBody,
-        args: impl Iterator<Item = Vec<u8>>,
+        args: impl Iterator<Item = IntervalOrOwned>,
         subst: Substitution,
-    ) -> Result<Vec<u8>> {
+    ) -> Result<Interval> {
         if let Some(x) = self.stack_depth_limit.checked_sub(1) {
             self.stack_depth_limit = x;
         } else {
             return Err(MirEvalError::StackOverflow);
         }
+        let _span = tracing::debug_span!("interpret_mir", owner = ?body.owner).entered();
         let mut current_block_idx = body.start_block;
         let mut locals = Locals { ptr: &ArenaMap::new(), body: &body, subst: &subst };
         let (locals_ptr, stack_size) = {
@@ -489,10 +867,10 @@ impl Evaluator<'_> {
             (addr, stack_size)
         };
         locals.ptr = &locals_ptr;
-        self.stack.extend(iter::repeat(0).take(stack_size));
+        self.stack.extend_zeroed(stack_size);
         let mut remain_args = body.arg_count;
         for ((_, addr), value) in locals_ptr.iter().skip(1).zip(args) {
-            self.write_memory(*addr, &value)?;
+            value.write_to(self, *addr)?;
             if remain_args == 0 {
                 return Err(MirEvalError::TypeError("more arguments provided"));
             }
@@ -501,19 +879,52 @@ impl Evaluator<'_> {
         if remain_args > 0 {
             return Err(MirEvalError::TypeError("not enough arguments provided"));
         }
+        if self.trace.is_some() {
+            let line = format!("// {}", self.body_name(body.owner));
+            self.push_trace(line);
+        }
+        let step_slot = match self.step_slots.get(&body.owner) {
+            Some(&it) => it,
+            None => {
+                self.steps.push((body.owner, 0));
+                self.step_slots.insert(body.owner, self.steps.len() - 1);
+                self.steps.len() - 1
+            }
+        };
         loop {
             let current_block = &body.basic_blocks[current_block_idx];
             if let Some(x) = self.execution_limit.checked_sub(1) {
                 self.execution_limit = x;
             } else {
-                return Err(MirEvalError::ExecutionLimitExceeded);
+                return Err(MirEvalError::ExecutionLimitExceeded(
+                    self.step_profile(STEP_PROFILE_LEN),
+                ));
+            }
+            self.steps[step_slot].1 += 1;
+            if self.execution_limit % CANCELLATION_CHECK_INTERVAL == 0 {
+                tracing::debug!(steps = EXECUTION_LIMIT - self.execution_limit, "evaluating");
+                // Don't keep a stale evaluation running until the limit is hit.
+                self.db.unwind_if_cancelled();
+                if let Some(progress) = self.progress {
+                    if !progress(EXECUTION_LIMIT - self.execution_limit) {
+                        return Err(MirEvalError::Interrupted);
+                    }
+                }
             }
             for statement in &current_block.statements {
                 match &statement.kind {
                     StatementKind::Assign(l, r) => {
                         let addr = self.place_addr(l, &locals)?;
-                        let result = self.eval_rvalue(r, &locals)?.to_vec(&self)?;
-                        self.write_memory(addr, &result)?;
+                        self.eval_rvalue(r, &locals)?.write_to(self, addr)?;
+                        if let Rvalue::Aggregate(AggregateKind::Union(union, field), values) = r {
+                            self.mark_union_padding(addr, *union, *field, &values[0], &locals)?;
+                        }
+                        if self.trace.is_some() {
+                            self.trace_assignment(l, r, &locals);
+                        }
+                        if self.watch.is_some() {
+                            self.check_watch(l, &locals)?;
+                        }
                     }
                     StatementKind::Deinit(_) => not_supported!("de-init statement"),
                     StatementKind::StorageLive(_)
@@ -555,25 +966,29 @@ impl Evaluator<'_> {
                     current_block_idx = target.expect("broken mir, function without target");
                 }
                 Terminator::SwitchInt { discr, targets } => {
-                    let val = u128::from_le_bytes(pad16(
-                        self.eval_operand(discr, &locals)?.get(&self)?,
-                        false,
-                    ));
+                    let val = u128::from_le_bytes(
+                        self.pad16(&self.eval_operand(discr, &locals)?.get(&self)?, false),
+                    );
                     current_block_idx = targets.target_for_value(val);
                 }
                 Terminator::Return => {
                     let ty = body.locals[return_slot()].ty.clone();
                     self.stack_depth_limit += 1;
-                    return Ok(self
-                        .read_memory(
-                            locals.ptr[return_slot()],
-                            self.size_of_sized(&ty, &locals, "return type")?,
-                        )?
-                        .to_owned());
+                    // The stack never shrinks, so the return slot stays valid for the caller.
+                    return Ok(Interval::new(
+                        locals.ptr[return_slot()],
+                        self.size_of_sized(&ty, &locals, "return type")?,
+                    ));
                 }
                 Terminator::Unreachable => {
                     return Err(MirEvalError::UndefinedBehavior("unreachable executed"));
                 }
+                Terminator::Await { future, destination, target } => {
+                    let destination = self.place_interval(destination, &locals)?;
+                    let future = self.operand_ty_and_eval(future, &locals)?;
+                    self.exec_await(future, destination)?;
+                    current_block_idx = *target;
+                }
                 _ => not_supported!("unknown terminator"),
             }
         }
@@ -589,20 +1004,36 @@ impl Evaluator<'_> {
             Rvalue::Use(x) => Borrowed(self.eval_operand(x, locals)?),
             Rvalue::Ref(_, p) => {
                 let (addr, _, metadata) = self.place_addr_and_ty_and_metadata(p, locals)?;
-                let mut r = addr.to_bytes();
-                if let Some(metadata) = metadata {
-                    r.extend(metadata.get(self)?);
+                let mut r = self.address_to_bytes(addr);
+                match metadata {
+                    Some(Borrowed(metadata)) => r.extend_from_slice(&metadata.get(self)?),
+                    Some(Owned(metadata)) => r.extend(metadata),
+                    Some(Repeated { .. }) => {
+                        return Err(MirEvalError::TypeError("repeated pointer metadata"))
+                    }
+                    None => (),
                 }
                 Owned(r)
             }
-            Rvalue::Len(_) => not_supported!("rvalue len"),
+            Rvalue::Len(p) => {
+                let (_, ty, metadata) = self.place_addr_and_ty_and_metadata(p, locals)?;
+                let len = self.array_or_slice_len(&ty, metadata.as_ref())?;
+                Owned(self.usize_to_bytes(len))
+            }
+            Rvalue::Repeat(x, len) => {
+                let Some(count) = try_const_usize(len) else {
+                    not_supported!("array repeat with unknown length");
+                };
+                let element = self.eval_operand(x, locals)?.get(&self)?.into_owned();
+                Repeated { element, count: count as usize }
+            }
             Rvalue::UnaryOp(op, val) => {
                 let mut c = self.eval_operand(val, locals)?.get(&self)?;
                 let mut ty = self.operand_ty(val, locals)?;
                 while let TyKind::Ref(_, _, z) = ty.kind(Interner) {
                     ty = z.clone();
                     let size = self.size_of_sized(&ty, locals, "operand of unary op")?;
-                    c = self.read_memory(Address::from_bytes(c)?, size)?;
+                    c = self.read_memory(self.read_address(&c)?, size)?;
                 }
                 let mut c = c.to_vec();
                 if ty.as_builtin() == Some(BuiltinType::Bool) {
@@ -611,14 +1042,8 @@ impl Evaluator<'_> {
                     match op {
                         UnOp::Not => c.iter_mut().for_each(|x| *x = !*x),
                         UnOp::Neg => {
-                            c.iter_mut().for_each(|x| *x = !*x);
-                            for k in c.iter_mut() {
-                                let o;
-                                (*k, o) = k.overflowing_add(1);
-                                if !o {
-                                    break;
-                                }
-                            }
+                            let x = i128::from_le_bytes(self.pad16(&c, false));
+                            c = self.truncate16(x.wrapping_neg().to_le_bytes(), c.len());
                         }
                     }
                 }
@@ -633,28 +1058,32 @@ impl Evaluator<'_> {
                 while let TyKind::Ref(_, _, z) = ty.kind(Interner) {
                     ty = z.clone();
                     let size = if ty.kind(Interner) == &TyKind::Str {
-                        let ns = from_bytes!(usize, &lc[self.ptr_size()..self.ptr_size() * 2]);
-                        lc = &lc[..self.ptr_size()];
-                        rc = &rc[..self.ptr_size()];
-                        ns
+                        self.read_usize(&lc[self.ptr_size()..self.ptr_size() * 2])?
                     } else {
                         self.size_of_sized(&ty, locals, "operand of binary op")?
                     };
-                    lc = self.read_memory(Address::from_bytes(lc)?, size)?;
-                    rc = self.read_memory(Address::from_bytes(rc)?, size)?;
+                    // Only the address part of the (possibly fat) pointers is needed here.
+                    lc = self.read_memory(self.read_address(&lc[..self.ptr_size()])?, size)?;
+                    rc = self.read_memory(self.read_address(&rc[..self.ptr_size()])?, size)?;
                 }
                 let is_signed = matches!(ty.as_builtin(), Some(BuiltinType::Int(_)));
-                let l128 = i128::from_le_bytes(pad16(lc, is_signed));
-                let r128 = i128::from_le_bytes(pad16(rc, is_signed));
+                let l128 = i128::from_le_bytes(self.pad16(&lc, is_signed));
+                let r128 = i128::from_le_bytes(self.pad16(&rc, is_signed));
                 match op {
                     BinOp::Ge | BinOp::Gt | BinOp::Le | BinOp::Lt | BinOp::Eq | BinOp::Ne => {
+                        // Unsigned values above `i128::MAX` wrap around when padded to an `i128`.
+                        let ordering = if is_signed {
+                            l128.cmp(&r128)
+                        } else {
+                            (l128 as u128).cmp(&(r128 as u128))
+                        };
                         let r = match op {
-                            BinOp::Ge => l128 >= r128,
-                            BinOp::Gt => l128 > r128,
-                            BinOp::Le => l128 <= r128,
-                            BinOp::Lt => l128 < r128,
-                            BinOp::Eq => l128 == r128,
-                            BinOp::Ne => l128 != r128,
+                            BinOp::Ge => ordering.is_ge(),
+                            BinOp::Gt => ordering.is_gt(),
+                            BinOp::Le => ordering.is_le(),
+                            BinOp::Lt => ordering.is_lt(),
+                            BinOp::Eq => ordering.is_eq(),
+                            BinOp::Ne => ordering.is_ne(),
                             _ => unreachable!(),
                         };
                         let r = r as u8;
@@ -684,17 +1113,23 @@ impl Evaluator<'_> {
                             _ => unreachable!(),
                         };
                         let r = r.to_le_bytes();
-                        for &k in &r[lc.len()..] {
-                            if k != 0 && (k != 255 || !is_signed) {
-                                return Err(MirEvalError::Panic(format!("Overflow in {op:?}")));
+                        // Without overflow checks, the result wraps around. Division by zero
+                        // panics regardless.
+                        if self.overflow_checks {
+                            for &k in &r[lc.len()..] {
+                                if k != 0 && (k != 255 || !is_signed) {
+                                    return Err(MirEvalError::Panic(format!("Overflow in {op:?}")));
+                                }
                             }
                         }
-                        Owned(r[0..lc.len()].into())
+                        Owned(self.truncate16(r, lc.len()))
                     }
                     BinOp::Shl | BinOp::Shr => {
-                        let shift_amount = if r128 < 0 {
-                            return Err(MirEvalError::Panic(format!("Overflow in {op:?}")));
-                        } else if r128 > 128 {
+                        let bits = lc.len() as i128 * 8;
+                        let shift_amount = if !self.overflow_checks {
+                            // Like `wrapping_shl`, the amount is masked to the bits of the type.
+                            (r128 & (bits - 1)) as u8
+                        } else if r128 < 0 || r128 >= bits {
                             return Err(MirEvalError::Panic(format!("Overflow in {op:?}")));
                         } else {
                             r128 as u8
@@ -704,10 +1139,35 @@ impl Evaluator<'_> {
                             BinOp::Shr => l128 >> shift_amount,
                             _ => unreachable!(),
                         };
-                        Owned(r.to_le_bytes()[0..lc.len()].into())
+                        Owned(self.truncate16(r.to_le_bytes(), lc.len()))
+                    }
+                    BinOp::Offset => {
+                        let TyKind::Raw(_, pointee) = ty.kind(Interner) else {
+                            return Err(MirEvalError::TypeError("offset of a non pointer"));
+                        };
+                        let size = self.size_of_sized(pointee, locals, "offset ptr type")?;
+                        let offset = i128::from_le_bytes(self.pad16(&rc, true));
+                        let r = l128.wrapping_add(offset.wrapping_mul(size as i128));
+                        Owned(self.truncate16(r.to_le_bytes(), lc.len()))
+                    }
+                }
+            }
+            Rvalue::CheckCfg(cfg) => {
+                let krate = locals.body.owner.module(self.db.upcast()).krate();
+                let crate_data = &self.db.crate_graph()[krate];
+                let mut cfg_options = crate_data.cfg_options.clone();
+                match self.debug_assertions {
+                    Some(enabled) if !matches!(crate_data.origin, CrateOrigin::Lang(_)) => {
+                        let atom = CfgAtom::Flag("debug_assertions".into());
+                        let (enable, disable) =
+                            if enabled { (vec![atom], vec![]) } else { (vec![], vec![atom]) };
+                        // A single atom can't conflict with itself.
+                        cfg_options.apply_diff(CfgDiff::new(enable, disable).unwrap());
                     }
-                    BinOp::Offset => not_supported!("offset binop"),
+                    _ => (),
                 }
+                let enabled = cfg_options.check(cfg) != Some(false);
+                Owned(vec![enabled as u8])
             }
             Rvalue::Discriminant(p) => {
                 let ty = self.place_ty(p, locals)?;
@@ -721,7 +1181,7 @@ impl Evaluator<'_> {
                         },
                         _ => (),
                     }
-                    return Ok(Owned(0u128.to_le_bytes().to_vec()));
+                    return Ok(Owned(vec![0; 16]));
                 };
                 match layout.variants {
                     Variants::Single { index } => {
@@ -729,7 +1189,7 @@ impl Evaluator<'_> {
                             parent: enum_id,
                             local_id: index.0,
                         })?;
-                        Owned(r.to_le_bytes().to_vec())
+                        Owned(self.truncate16(r.to_le_bytes(), 16))
                     }
                     Variants::Multiple { tag, tag_encoding, .. } => {
                         let Some(target_data_layout) = self.db.target_data_layout(self.crate_id) else {
@@ -740,30 +1200,26 @@ impl Evaluator<'_> {
                         match tag_encoding {
                             TagEncoding::Direct => {
                                 let tag = &bytes[offset..offset + size];
-                                Owned(pad16(tag, false).to_vec())
+                                Owned(self.truncate16(self.pad16(tag, false), 16))
                             }
-                            TagEncoding::Niche { untagged_variant, niche_start, .. } => {
+                            TagEncoding::Niche {
+                                untagged_variant,
+                                niche_variants,
+                                niche_start,
+                            } => {
                                 let tag = &bytes[offset..offset + size];
-                                let candidate_discriminant = i128::from_le_bytes(pad16(tag, false))
-                                    .wrapping_sub(niche_start as i128);
-                                let enum_data = self.db.enum_data(enum_id);
-                                let result = 'b: {
-                                    for (local_id, _) in enum_data.variants.iter() {
-                                        if candidate_discriminant
-                                            == self.db.const_eval_discriminant(EnumVariantId {
-                                                parent: enum_id,
-                                                local_id,
-                                            })?
-                                        {
-                                            break 'b candidate_discriminant;
-                                        }
-                                    }
-                                    self.db.const_eval_discriminant(EnumVariantId {
-                                        parent: enum_id,
-                                        local_id: untagged_variant.0,
-                                    })?
-                                };
-                                Owned(result.to_le_bytes().to_vec())
+                                let local_id = niche_variant(
+                                    i128::from_le_bytes(self.pad16(tag, false)),
+                                    size,
+                                    &niche_variants,
+                                    niche_start,
+                                    untagged_variant,
+                                );
+                                let result = self.db.const_eval_discriminant(EnumVariantId {
+                                    parent: enum_id,
+                                    local_id,
+                                })?;
+                                Owned(self.truncate16(result.to_le_bytes(), 16))
                             }
                         }
                     }
@@ -781,7 +1237,7 @@ impl Evaluator<'_> {
                         let mut r = vec![];
                         for x in values {
                             let value = x.get(&self)?;
-                            r.extend(value);
+                            r.extend_from_slice(&value);
                         }
                         Owned(r)
                     }
@@ -802,7 +1258,7 @@ impl Evaluator<'_> {
                             .bytes_usize();
                         let op = values[0].get(&self)?;
                         let mut result = vec![0; layout.size.bytes_usize()];
-                        result[offset..offset + op.len()].copy_from_slice(op);
+                        result[offset..offset + op.len()].copy_from_slice(&op);
                         Owned(result)
                     }
                     AggregateKind::Adt(x, subst) => {
@@ -816,7 +1272,8 @@ impl Evaluator<'_> {
                             values.iter().copied(),
                         )?)
                     }
-                    AggregateKind::Closure(ty) => {
+                    AggregateKind::Closure(ty) | AggregateKind::Generator(ty) => {
+                        // Generators start in the unresumed state, which is encoded as zero.
                         let layout = self.layout(&ty)?;
                         Owned(self.make_by_layout(
                             layout.size.bytes_usize(),
@@ -832,55 +1289,20 @@ impl Evaluator<'_> {
                     PointerCast::ReifyFnPointer => {
                         let current_ty = self.operand_ty(operand, locals)?;
                         if let TyKind::FnDef(_, _) = &current_ty.data(Interner).kind {
+                            // The pointer may be called from a function with other generic
+                            // params, so they are substituted before storing the type.
+                            let current_ty =
+                                self.ty_filler(&current_ty, locals.subst, locals.body.owner)?;
                             let id = self.vtable_map.id(current_ty);
-                            let ptr_size = self.ptr_size();
-                            Owned(id.to_le_bytes()[0..ptr_size].to_vec())
+                            Owned(self.usize_to_bytes(id))
                         } else {
                             not_supported!("ReifyFnPointer cast of a non FnDef type");
                         }
                     }
                     PointerCast::Unsize => {
                         let current_ty = self.operand_ty(operand, locals)?;
-                        match &target_ty.data(Interner).kind {
-                            TyKind::Raw(_, ty) | TyKind::Ref(_, _, ty) => {
-                                match &ty.data(Interner).kind {
-                                    TyKind::Slice(_) => match &current_ty.data(Interner).kind {
-                                        TyKind::Raw(_, ty) | TyKind::Ref(_, _, ty) => {
-                                            match &ty.data(Interner).kind {
-                                                TyKind::Array(_, size) => {
-                                                    let addr = self
-                                                        .eval_operand(operand, locals)?
-                                                        .get(&self)?;
-                                                    let len = const_as_usize(size);
-                                                    let mut r = Vec::with_capacity(16);
-                                                    r.extend(addr.iter().copied());
-                                                    r.extend(len.to_le_bytes().into_iter());
-                                                    Owned(r)
-                                                }
-                                                _ => {
-                                                    not_supported!("slice unsizing from non arrays")
-                                                }
-                                            }
-                                        }
-                                        _ => not_supported!("slice unsizing from non pointers"),
-                                    },
-                                    TyKind::Dyn(_) => match &current_ty.data(Interner).kind {
-                                        TyKind::Raw(_, ty) | TyKind::Ref(_, _, ty) => {
-                                            let vtable = self.vtable_map.id(ty.clone());
-                                            let addr =
-                                                self.eval_operand(operand, locals)?.get(&self)?;
-                                            let mut r = Vec::with_capacity(16);
-                                            r.extend(addr.iter().copied());
-                                            r.extend(vtable.to_le_bytes().into_iter());
-                                            Owned(r)
-                                        }
-                                        _ => not_supported!("dyn unsizing from non pointers"),
-                                    },
-                                    _ => not_supported!("unknown unsized cast"),
-                                }
-                            }
-                            _ => not_supported!("unsized cast on unknown pointer type"),
-                        }
+                        let addr = self.eval_operand(operand, locals)?;
+                        Owned(self.coerce_unsized(addr, &current_ty, target_ty, locals)?)
                     }
                     x => not_supported!("pointer cast {x:?}"),
                 },
@@ -889,25 +1311,127 @@ impl Evaluator<'_> {
                 | CastKind::PointerExposeAddress
                 | CastKind::PointerFromExposedAddress => {
                     // FIXME: handle signed cast
-                    let current = pad16(self.eval_operand(operand, locals)?.get(&self)?, false);
+                    let current =
+                        self.pad16(&self.eval_operand(operand, locals)?.get(&self)?, false);
                     let dest_size =
                         self.size_of_sized(target_ty, locals, "destination of int to int cast")?;
-                    Owned(current[0..dest_size].to_vec())
+                    Owned(self.truncate16(current, dest_size))
                 }
                 CastKind::FloatToInt => not_supported!("float to int cast"),
                 CastKind::FloatToFloat => not_supported!("float to float cast"),
                 CastKind::IntToFloat => not_supported!("float to int cast"),
                 CastKind::PtrToPtr => {
-                    let current = pad16(self.eval_operand(operand, locals)?.get(&self)?, false);
+                    // The address is the first part of both thin and fat pointers, so we can just
+                    // drop the metadata if the destination is thin. The result is borrowed so that
+                    // the provenance of the pointer is kept.
+                    let current = self.eval_operand(operand, locals)?;
                     let dest_size =
                         self.size_of_sized(target_ty, locals, "destination of ptr to ptr cast")?;
-                    Owned(current[0..dest_size].to_vec())
+                    Borrowed(current.slice(0..dest_size))
                 }
                 CastKind::FnPtrToPtr => not_supported!("fn ptr to ptr cast"),
             },
         })
     }
 
+    /// Converts the value at `addr` from `current_ty` to `target_ty`, which must be related by a
+    /// `CoerceUnsized` impl. Besides pointers, this handles structs like `Rc<T>`, in which exactly
+    /// one field is unsized along with the struct.
+    fn coerce_unsized(
+        &mut self,
+        addr: Interval,
+        current_ty: &Ty,
+        target_ty: &Ty,
+        locals: &Locals<'_>,
+    ) -> Result<Vec<u8>> {
+        Ok(match (current_ty.kind(Interner), target_ty.kind(Interner)) {
+            (
+                TyKind::Raw(_, current_pointee) | TyKind::Ref(_, _, current_pointee),
+                TyKind::Raw(_, target_pointee) | TyKind::Ref(_, _, target_pointee),
+            ) if matches!(
+                (current_pointee.kind(Interner), target_pointee.kind(Interner)),
+                (TyKind::Dyn(_), TyKind::Dyn(_))
+            ) =>
+            {
+                // Trait object upcasting. The vtable is identified by the concrete type, so it
+                // serves the supertraits as well.
+                addr.get(&self)?.into_owned()
+            }
+            (
+                TyKind::Raw(_, current_pointee) | TyKind::Ref(_, _, current_pointee),
+                TyKind::Raw(_, target_pointee) | TyKind::Ref(_, _, target_pointee),
+            ) => {
+                let mut r = addr.get(&self)?.into_owned();
+                r.extend(self.unsizing_metadata(current_pointee, target_pointee, locals)?);
+                r
+            }
+            (TyKind::Adt(current_id, current_subst), TyKind::Adt(target_id, target_subst))
+                if current_id == target_id =>
+            {
+                let AdtId::StructId(s) = current_id.0 else {
+                    not_supported!("unsizing of enums and unions");
+                };
+                let current_layout = self.layout_adt(current_id.0, current_subst.clone())?;
+                let target_layout = self.layout_adt(target_id.0, target_subst.clone())?;
+                let mut r = vec![0; target_layout.size.bytes_usize()];
+                let field_types = self.db.field_types(s.into());
+                for (id, field_ty) in field_types.iter() {
+                    let idx = u32::from(id.into_raw()) as usize;
+                    let current_field_ty = field_ty.clone().substitute(Interner, current_subst);
+                    let target_field_ty = field_ty.clone().substitute(Interner, target_subst);
+                    let offset = current_layout.fields.offset(idx).bytes_usize();
+                    let size = self.size_of_sized(&current_field_ty, locals, "struct field")?;
+                    let field = addr.slice(offset..offset + size);
+                    let field = if current_field_ty == target_field_ty {
+                        field.get(&self)?.into_owned()
+                    } else {
+                        self.coerce_unsized(field, &current_field_ty, &target_field_ty, locals)?
+                    };
+                    let offset = target_layout.fields.offset(idx).bytes_usize();
+                    r[offset..offset + field.len()].copy_from_slice(&field);
+                }
+                r
+            }
+            _ => not_supported!("unsized cast on unknown pointer type"),
+        })
+    }
+
+    /// Computes the metadata of a pointer to `target_pointee`, which is the unsized version of
+    /// `current_pointee`.
+    fn unsizing_metadata(
+        &mut self,
+        current_pointee: &Ty,
+        target_pointee: &Ty,
+        locals: &Locals<'_>,
+    ) -> Result<Vec<u8>> {
+        Ok(match (current_pointee.kind(Interner), target_pointee.kind(Interner)) {
+            (TyKind::Array(_, size), TyKind::Slice(_)) => self.usize_to_bytes(const_as_usize(size)),
+            (_, TyKind::Slice(_)) => not_supported!("slice unsizing from non arrays"),
+            (_, TyKind::Dyn(_)) => {
+                let ty = self.ty_filler(current_pointee, locals.subst, locals.body.owner)?;
+                let vtable = self.vtable_map.id(ty);
+                self.usize_to_bytes(vtable)
+            }
+            // Structs with an unsized last field, like `RcBox<dyn Trait>`, use the metadata of that
+            // field.
+            (TyKind::Adt(current_id, current_subst), TyKind::Adt(target_id, target_subst))
+                if current_id == target_id =>
+            {
+                let AdtId::StructId(s) = current_id.0 else {
+                    not_supported!("unsizing of enums and unions");
+                };
+                let field_types = self.db.field_types(s.into());
+                let Some((_, last_field)) = field_types.iter().last() else {
+                    not_supported!("unsizing of a struct without fields");
+                };
+                let current_field_ty = last_field.clone().substitute(Interner, current_subst);
+                let target_field_ty = last_field.clone().substitute(Interner, target_subst);
+                self.unsizing_metadata(&current_field_ty, &target_field_ty, locals)?
+            }
+            _ => not_supported!("unknown unsized cast"),
+        })
+    }
+
     fn layout_of_variant(
         &mut self,
         x: VariantId,
@@ -942,8 +1466,9 @@ impl Evaluator<'_> {
                 let variant_layout = variants[rustc_enum_variant_idx].clone();
                 let have_tag = match tag_encoding {
                     TagEncoding::Direct => true,
-                    TagEncoding::Niche { untagged_variant, niche_variants: _, niche_start } => {
-                        discriminant = discriminant.wrapping_add(niche_start as i128);
+                    TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+                        discriminant =
+                            niche_tag(rustc_enum_variant_idx, &niche_variants, niche_start);
                         untagged_variant != rustc_enum_variant_idx
                     }
                 };
@@ -973,19 +1498,31 @@ impl Evaluator<'_> {
     ) -> Result<Vec<u8>> {
         let mut result = vec![0; size];
         if let Some((offset, size, value)) = tag {
-            result[offset..offset + size].copy_from_slice(&value.to_le_bytes()[0..size]);
+            result[offset..offset + size]
+                .copy_from_slice(&self.truncate16(value.to_le_bytes(), size));
         }
         for (i, op) in values.enumerate() {
             let offset = variant_layout.fields.offset(i).bytes_usize();
             let op = op.get(&self)?;
-            result[offset..offset + op.len()].copy_from_slice(op);
+            result[offset..offset + op.len()].copy_from_slice(&op);
         }
         Ok(result)
     }
 
     fn eval_operand(&mut self, x: &Operand, locals: &Locals<'_>) -> Result<Interval> {
         Ok(match x {
-            Operand::Copy(p) | Operand::Move(p) => self.eval_place(p, locals)?,
+            Operand::Copy(p) | Operand::Move(p) => {
+                let interval = self.eval_place(p, locals)?;
+                let is_union_field = p.projection.iter().any(|it| {
+                    matches!(it, ProjectionElem::Field(f) if matches!(f.parent, VariantId::UnionId(_)))
+                });
+                if is_union_field && !self.is_initialized(interval) {
+                    return Err(MirEvalError::UndefinedBehavior(
+                        "read of uninitialized bytes of a union",
+                    ));
+                }
+                interval
+            }
             Operand::Constant(konst) => {
                 let data = &konst.data(Interner);
                 match &data.value {
@@ -1021,11 +1558,27 @@ impl Evaluator<'_> {
                                     return Err(MirEvalError::InvalidConst(konst.clone()));
                                 }
                             }
+                            if self.endian != Endian::Little {
+                                let mut owned = v.into_owned();
+                                self.convert_endianness(&mut owned, &data.ty, locals)?;
+                                v = Cow::Owned(owned);
+                            }
                             let addr = self.heap_allocate(size);
                             self.write_memory(addr, &v)?;
                             self.patch_addresses(&patch_map, addr, &data.ty, locals)?;
                             Interval::new(addr, size)
                         }
+                        ConstScalar::UnevaluatedConst(const_id, subst) => {
+                            let subst = self.subst_filler(subst, locals);
+                            let (const_id, subst) = lookup_impl_const(
+                                self.db,
+                                self.trait_env.clone(),
+                                *const_id,
+                                subst,
+                            );
+                            let c = self.db.const_eval(const_id, subst)?;
+                            self.eval_operand(&Operand::Constant(c), locals)?
+                        }
                         ConstScalar::Unknown => not_supported!("evaluating unknown const"),
                     },
                 }
@@ -1041,7 +1594,7 @@ impl Evaluator<'_> {
         ))
     }
 
-    fn read_memory(&self, addr: Address, size: usize) -> Result<&[u8]> {
+    fn read_memory(&self, addr: Address, size: usize) -> Result<Cow<'_, [u8]>> {
         let (mem, pos) = match addr {
             Stack(x) => (&self.stack, x),
             Heap(x) => (&self.heap, x),
@@ -1049,7 +1602,7 @@ impl Evaluator<'_> {
                 return Err(MirEvalError::UndefinedBehavior("read invalid memory address"))
             }
         };
-        mem.get(pos..pos + size).ok_or(MirEvalError::UndefinedBehavior("out of bound memory read"))
+        mem.read(pos, size).ok_or(MirEvalError::UndefinedBehavior("out of bound memory read"))
     }
 
     fn write_memory(&mut self, addr: Address, r: &[u8]) -> Result<()> {
@@ -1060,12 +1613,91 @@ impl Evaluator<'_> {
                 return Err(MirEvalError::UndefinedBehavior("write invalid memory address"))
             }
         };
-        mem.get_mut(pos..pos + r.len())
-            .ok_or(MirEvalError::UndefinedBehavior("out of bound memory write"))?
-            .copy_from_slice(r);
+        mem.write(pos, r).ok_or(MirEvalError::UndefinedBehavior("out of bound memory write"))
+    }
+
+    fn fill_memory(&mut self, addr: Address, size: usize, byte: u8) -> Result<()> {
+        let (mem, pos) = match addr {
+            Stack(x) => (&mut self.stack, x),
+            Heap(x) => (&mut self.heap, x),
+            Invalid(_) => {
+                return Err(MirEvalError::UndefinedBehavior("write invalid memory address"))
+            }
+        };
+        mem.fill(pos, size, byte)
+            .ok_or(MirEvalError::UndefinedBehavior("out of bound memory write"))
+    }
+
+    /// Returns whether the pointer stored at `addr` has a provenance, i.e. whether it's allowed to
+    /// dereference it.
+    fn has_provenance(&self, addr: Address) -> bool {
+        match addr {
+            Stack(x) => self.stack.has_provenance(x),
+            Heap(x) => self.heap.has_provenance(x),
+            Invalid(_) => true,
+        }
+    }
+
+    fn mark_no_provenance(&mut self, addr: Address) {
+        match addr {
+            Stack(x) => self.stack.mark_no_provenance(x),
+            Heap(x) => self.heap.mark_no_provenance(x),
+            Invalid(_) => (),
+        }
+    }
+
+    fn is_initialized(&self, interval: Interval) -> bool {
+        match interval.addr {
+            Stack(x) => self.stack.is_init(x, interval.size),
+            Heap(x) => self.heap.is_init(x, interval.size),
+            Invalid(_) => true,
+        }
+    }
+
+    /// Marks the bytes of the union at `addr` outside of `field` as uninitialized, after the union
+    /// was created by an expression initializing `field` with `value`.
+    fn mark_union_padding(
+        &mut self,
+        addr: Address,
+        union: UnionId,
+        field: FieldId,
+        value: &Operand,
+        locals: &Locals<'_>,
+    ) -> Result<()> {
+        let layout = self.layout_adt(union.into(), Substitution::empty(Interner))?;
+        let size = layout.size.bytes_usize();
+        let start =
+            layout.fields.offset(u32::from(field.local_id.into_raw()) as usize).bytes_usize();
+        let value_ty = self.operand_ty(value, locals)?;
+        let end = start + self.size_of_sized(&value_ty, locals, "union field")?;
+        let (mem, pos) = match addr {
+            Stack(x) => (&mut self.stack, x),
+            Heap(x) => (&mut self.heap, x),
+            Invalid(_) => return Ok(()),
+        };
+        mem.mark_uninit(pos, start);
+        mem.mark_uninit(pos + end, size.saturating_sub(end));
         Ok(())
     }
 
+    fn no_provenance_marks(&self, interval: Interval) -> Vec<usize> {
+        match interval.addr {
+            Stack(x) => self.stack.no_provenance_marks(x, interval.size),
+            Heap(x) => self.heap.no_provenance_marks(x, interval.size),
+            Invalid(_) => vec![],
+        }
+    }
+
+    /// Reads the address of the pointer stored in `ptr`, which is going to be dereferenced.
+    fn read_pointer(&self, ptr: Interval) -> Result<Address> {
+        if !self.has_provenance(ptr.addr) {
+            return Err(MirEvalError::UndefinedBehavior(
+                "dereference of a pointer without provenance",
+            ));
+        }
+        self.read_address(&ptr.get(self)?)
+    }
+
     fn size_of(&self, ty: &Ty, locals: &Locals<'_>) -> Result<Option<usize>> {
         if let DefWithBodyId::VariantId(f) = locals.body.owner {
             if let Some((adt, _)) = ty.as_adt() {
@@ -1096,7 +1728,7 @@ impl Evaluator<'_> {
         }
     }
 
-    /// Uses `ty_filler` to fill an entire subst
+    /// Uses `ty_filler` and `const_filler` to fill an entire subst
     fn subst_filler(&self, subst: &Substitution, locals: &Locals<'_>) -> Substitution {
         Substitution::from_iter(
             Interner,
@@ -1107,19 +1739,51 @@ impl Evaluator<'_> {
                     };
                     chalk_ir::GenericArgData::Ty(ty).intern(Interner)
                 }
+                chalk_ir::GenericArgData::Const(c) => {
+                    let Ok(c) = self.const_filler(c, locals.subst, locals.body.owner) else {
+                        return x.clone();
+                    };
+                    chalk_ir::GenericArgData::Const(c).intern(Interner)
+                }
                 _ => x.clone(),
             }),
         )
     }
 
+    fn ty_filler(&self, ty: &Ty, subst: &Substitution, owner: DefWithBodyId) -> Result<Ty> {
+        Ok(normalize(self.db, owner, self.filler(ty.clone(), subst, owner)?))
+    }
+
+    fn const_filler(&self, c: &Const, subst: &Substitution, owner: DefWithBodyId) -> Result<Const> {
+        self.filler(c.clone(), subst, owner)
+    }
+
     /// This function substitutes placeholders of the body with the provided subst, effectively plays
     /// the rule of monomorphization. In addition to placeholders, it substitutes opaque types (return
     /// position impl traits) with their underlying type.
-    fn ty_filler(&self, ty: &Ty, subst: &Substitution, owner: DefWithBodyId) -> Result<Ty> {
+    fn filler<T: TypeFoldable<Interner>>(
+        &self,
+        t: T,
+        subst: &Substitution,
+        owner: DefWithBodyId,
+    ) -> Result<T> {
         struct Filler<'a> {
             db: &'a dyn HirDatabase,
             subst: &'a Substitution,
-            skip_params: usize,
+            /// The generics `subst` is for. The parameters of the parent of the body, like the
+            /// `Self` type of an impl, come after the ones of the body in `subst`.
+            generics: Option<Generics>,
+        }
+        impl Filler<'_> {
+            fn generic_arg(&self, idx: chalk_ir::PlaceholderIndex) -> Result<&GenericArg> {
+                let x = from_placeholder_idx(self.db, idx);
+                let idx = match &self.generics {
+                    Some(generics) => generics.param_idx(x),
+                    None => Some(u32::from(x.local_id.into_raw()) as usize),
+                };
+                idx.and_then(|idx| self.subst.as_slice(Interner).get(idx))
+                    .ok_or(MirEvalError::TypeError("Generic arg not provided"))
+            }
         }
         impl FallibleTypeFolder<Interner> for Filler<'_> {
             type Error = MirEvalError;
@@ -1143,11 +1807,21 @@ impl Evaluator<'_> {
                         match impl_trait_id {
                             crate::ImplTraitId::ReturnTypeImplTrait(func, idx) => {
                                 let infer = self.db.infer(func.into());
-                                let filler = &mut Filler { db: self.db, subst, skip_params: 0 };
+                                let generics = Some(generics(self.db.upcast(), func.into()));
+                                let filler = &mut Filler { db: self.db, subst, generics };
                                 filler.try_fold_ty(infer.type_of_rpit[idx].clone(), outer_binder)
                             }
+                            crate::ImplTraitId::TypeAliasImplTrait(alias, idx) => {
+                                match type_alias_impl_trait_hidden_type(self.db, alias, idx) {
+                                    Some(hidden_ty) => self.try_fold_ty(
+                                        hidden_ty.substitute(Interner, subst),
+                                        outer_binder,
+                                    ),
+                                    None => ty.try_super_fold_with(self.as_dyn(), outer_binder),
+                                }
+                            }
                             crate::ImplTraitId::AsyncBlockTypeImplTrait(_, _) => {
-                                not_supported!("async block impl trait");
+                                ty.try_super_fold_with(self.as_dyn(), outer_binder)
                             }
                         }
                     }
@@ -1160,28 +1834,122 @@ impl Evaluator<'_> {
                 idx: chalk_ir::PlaceholderIndex,
                 _outer_binder: DebruijnIndex,
             ) -> std::result::Result<Ty, Self::Error> {
-                let x = from_placeholder_idx(self.db, idx);
                 Ok(self
-                    .subst
-                    .as_slice(Interner)
-                    .get((u32::from(x.local_id.into_raw()) as usize) + self.skip_params)
-                    .and_then(|x| x.ty(Interner))
+                    .generic_arg(idx)?
+                    .ty(Interner)
+                    .ok_or(MirEvalError::TypeError("Generic arg not provided"))?
+                    .clone())
+            }
+
+            fn try_fold_free_placeholder_const(
+                &mut self,
+                _ty: Ty,
+                idx: chalk_ir::PlaceholderIndex,
+                _outer_binder: DebruijnIndex,
+            ) -> std::result::Result<Const, Self::Error> {
+                Ok(self
+                    .generic_arg(idx)?
+                    .constant(Interner)
                     .ok_or(MirEvalError::TypeError("Generic arg not provided"))?
                     .clone())
             }
         }
-        let filler = &mut Filler { db: self.db, subst, skip_params: 0 };
-        Ok(normalize(self.db, owner, ty.clone().try_fold_with(filler, DebruijnIndex::INNERMOST)?))
+        let generics = owner.as_generic_def_id().map(|def| generics(self.db.upcast(), def));
+        let filler = &mut Filler { db: self.db, subst, generics };
+        t.try_fold_with(filler, DebruijnIndex::INNERMOST)
     }
 
     fn heap_allocate(&mut self, s: usize) -> Address {
         let pos = self.heap.len();
-        self.heap.extend(iter::repeat(0).take(s));
+        self.heap.extend_zeroed(s);
         Address::Heap(pos)
     }
 
+    /// Like [`Evaluator::heap_allocate`], but the address of the allocation is a multiple of
+    /// `align`, which must be a power of two.
+    fn heap_allocate_aligned(&mut self, s: usize, align: usize) -> Result<Address> {
+        if !align.is_power_of_two() || align > HEAP_OFFSET {
+            return Err(MirEvalError::UndefinedBehavior("allocation with an invalid alignment"));
+        }
+        let padding = (HEAP_OFFSET + self.heap.len()).wrapping_neg() & (align - 1);
+        self.heap.extend_zeroed(padding);
+        Ok(self.heap_allocate(s))
+    }
+
     pub fn interpret_mir_with_no_arg(&mut self, body: &MirBody) -> Result<Vec<u8>> {
-        self.interpret_mir(&body, vec![].into_iter(), Substitution::empty(Interner))
+        let result = self.interpret_mir(&body, iter::empty(), Substitution::empty(Interner))?;
+        Ok(result.get(self)?.into_owned())
+    }
+
+    /// Returns the `len` functions which executed the most blocks, with their numbers of executed
+    /// blocks.
+    fn step_profile(&self, len: usize) -> Vec<(DefWithBodyId, usize)> {
+        let mut steps = self.steps.clone();
+        // The sort is stable, so ties are broken by the order of the first calls.
+        steps.sort_by(|(_, a), (_, b)| b.cmp(a));
+        steps.truncate(len);
+        steps
+    }
+
+    fn body_name(&self, owner: DefWithBodyId) -> String {
+        match owner {
+            DefWithBodyId::FunctionId(it) => format!("fn {}", self.db.function_data(it).name),
+            DefWithBodyId::StaticId(it) => format!("static {}", self.db.static_data(it).name),
+            DefWithBodyId::ConstId(it) => match &self.db.const_data(it).name {
+                Some(name) => format!("const {name}"),
+                None => "const _".to_string(),
+            },
+            DefWithBodyId::VariantId(it) => {
+                let enum_data = self.db.enum_data(it.parent);
+                let variant = &enum_data.variants[it.local_id].name;
+                format!("discriminant of {}::{variant}", enum_data.name)
+            }
+        }
+    }
+
+    /// Captures the value of the watched binding and stops the evaluation, if `place` is that
+    /// binding.
+    fn check_watch(&mut self, place: &Place, locals: &Locals<'_>) -> Result<()> {
+        let Some(watch) = &self.watch else { return Ok(()) };
+        if locals.body.owner != watch.owner
+            || !place.projection.is_empty()
+            || locals.body.binding_locals.get(watch.binding) != Some(&place.local)
+        {
+            return Ok(());
+        }
+        let ty = locals.body.locals[place.local].ty.clone();
+        let mut bytes = self.place_interval(place, locals)?.get(self)?.into_owned();
+        let memory_map = self.create_memory_map(&bytes, &ty, locals)?;
+        self.convert_endianness(&mut bytes, &ty, locals)?;
+        if let Some(watch) = &mut self.watch {
+            watch.value = Some(intern_const_scalar(ConstScalar::Bytes(bytes, memory_map), ty));
+        }
+        Err(MirEvalError::Interrupted)
+    }
+
+    /// Records `place = rvalue` in the trace, together with the value it stored in `place`.
+    fn trace_assignment(&mut self, place: &Place, rvalue: &Rvalue, locals: &Locals<'_>) {
+        let mut line = locals.body.pretty_print_assignment(self.db, place, rvalue);
+        if let Some(value) = self.render_place(place, locals) {
+            line += &format!(" // {value}");
+        }
+        self.push_trace(line);
+    }
+
+    fn render_place(&self, place: &Place, locals: &Locals<'_>) -> Option<String> {
+        let ty = self.place_ty(place, locals).ok()?;
+        let mut bytes = self.place_interval(place, locals).ok()?.get(self).ok()?.into_owned();
+        self.convert_endianness(&mut bytes, &ty, locals).ok()?;
+        let value = intern_const_scalar(ConstScalar::Bytes(bytes, MemoryMap::default()), ty);
+        Some(value.display(self.db).to_string())
+    }
+
+    /// Appends `line` to the trace, indented by the depth of the current call.
+    fn push_trace(&mut self, line: String) {
+        let depth = STACK_DEPTH_LIMIT.saturating_sub(self.stack_depth_limit + 1);
+        if let Some(trace) = &mut self.trace {
+            trace.push(format!("{}{line}", "    ".repeat(depth)));
+        }
     }
 
     fn detect_lang_function(&self, def: FunctionId) -> Option<LangItem> {
@@ -1194,6 +1962,121 @@ impl Evaluator<'_> {
         None
     }
 
+    fn is_intrinsic(&self, def: FunctionId) -> bool {
+        match &self.db.function_data(def).abi {
+            Some(abi) => *abi == Interned::new_str("rust-intrinsic"),
+            None => match def.lookup(self.db.upcast()).container {
+                hir_def::ItemContainerId::ExternBlockId(block) => {
+                    let id = block.lookup(self.db.upcast()).id;
+                    id.item_tree(self.db.upcast())[id.value].abi.as_deref()
+                        == Some("rust-intrinsic")
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Replaces an error caused by reaching a function whose body can't be lowered with the list
+    /// of all the unlowerable functions which `body` can call, so that the user knows whether
+    /// stubbing the reached one is enough to evaluate the body.
+    fn with_unlowerable_callees(
+        &self,
+        e: MirEvalError,
+        body: &MirBody,
+        subst: &Substitution,
+    ) -> MirEvalError {
+        let mut root = &e;
+        while let MirEvalError::InFunction(_, inner) = root {
+            root = inner;
+        }
+        let MirEvalError::MirLowerError(reached, reached_error) = root else {
+            return e;
+        };
+        let mut callees = self.unlowerable_callees(body, subst);
+        if !callees.iter().any(|(it, _)| it == reached) {
+            // It was called indirectly, through a function pointer or a trait object.
+            callees.insert(0, (*reached, reached_error.clone()));
+        }
+        if callees.len() == 1 {
+            // Keep the stack of the error.
+            return e;
+        }
+        MirEvalError::UnlowerableCallees(callees)
+    }
+
+    /// Walks the call graph from `body`, and returns, in the order they are found, the functions whose bodies can't be lowered,
+    /// with the reason. Only direct calls are followed, not the ones through function pointers and
+    /// trait objects. The calls of generic functions are resolved with the generic arguments of
+    /// the first visited call of the function.
+    fn unlowerable_callees(
+        &self,
+        body: &MirBody,
+        subst: &Substitution,
+    ) -> Vec<(FunctionId, MirLowerError)> {
+        let mut result = vec![];
+        let mut visited = FxHashSet::default();
+        let mut calls = VecDeque::new();
+        self.push_calls(body, subst, &mut calls);
+        while let Some((def, generic_args)) = calls.pop_front() {
+            if self.is_intrinsic(def)
+                || self.detect_lang_function(def).is_some()
+                || self.detect_fn_trait(def).is_some()
+                || matches!(
+                    def.lookup(self.db.upcast()).container,
+                    ItemContainerId::ExternBlockId(_)
+                )
+                || is_dyn_method(self.db, self.trait_env.clone(), def, generic_args.clone())
+                    .is_some()
+            {
+                // These are executed by the evaluator itself, or depend on runtime values.
+                continue;
+            }
+            let (imp, generic_args) =
+                lookup_impl_method(self.db, self.trait_env.clone(), def, generic_args);
+            if !visited.insert(imp) {
+                continue;
+            }
+            if !self.db.function_data(imp).has_body() {
+                // A trait method whose implementation depends on the generic parameters of the
+                // evaluated body.
+                continue;
+            }
+            match self.db.mir_body(imp.into()) {
+                Ok(body) => self.push_calls(&body, &generic_args, &mut calls),
+                Err(e) => result.push((imp, e)),
+            }
+        }
+        result
+    }
+
+    /// Pushes the functions called by `body` and its closures, with their generic arguments.
+    fn push_calls(
+        &self,
+        body: &MirBody,
+        subst: &Substitution,
+        calls: &mut VecDeque<(FunctionId, Substitution)>,
+    ) {
+        let locals = Locals { ptr: &ArenaMap::new(), body, subst };
+        for (_, block) in body.basic_blocks.iter() {
+            let Some(Terminator::Call { func: Operand::Constant(func), .. }) = &block.terminator
+            else {
+                continue;
+            };
+            let TyKind::FnDef(def, generic_args) = func.data(Interner).ty.kind(Interner) else {
+                continue;
+            };
+            if let CallableDefId::FunctionId(def) = from_chalk(self.db, *def) {
+                calls.push_back((def, self.subst_filler(generic_args, &locals)));
+            }
+        }
+        for &closure in &body.closures {
+            // Closures which can't be lowered are reported when they are reached.
+            if let Ok(body) = self.db.mir_body_for_closure(closure) {
+                self.push_calls(&body, subst, calls);
+            }
+        }
+    }
+
     fn detect_fn_trait(&self, def: FunctionId) -> Option<FnTrait> {
         use LangItem::*;
         let ItemContainerId::TraitId(parent) = self.db.lookup_intern_function(def).container else {
@@ -1209,18 +2092,26 @@ impl Evaluator<'_> {
     }
 
     fn create_memory_map(&self, bytes: &[u8], ty: &Ty, locals: &Locals<'_>) -> Result<MemoryMap> {
-        // FIXME: support indirect references
         let mut mm = MemoryMap::default();
+        self.add_referenced_memory(bytes, ty, locals, &mut mm)?;
+        Ok(mm)
+    }
+
+    /// Adds the memory referenced by the value `bytes` of type `ty` to `mm`. This follows the
+    /// references in the fields of tuples, structs, enums and arrays, and in the referenced memory.
+    fn add_referenced_memory(
+        &self,
+        bytes: &[u8],
+        ty: &Ty,
+        locals: &Locals<'_>,
+        mm: &mut MemoryMap,
+    ) -> Result<()> {
         match ty.kind(Interner) {
             TyKind::Ref(_, _, t) => {
-                let size = self.size_of(t, locals)?;
-                match size {
+                let (addr, mut value) = match self.size_of(t, locals)? {
                     Some(size) => {
-                        let addr_usize = from_bytes!(usize, bytes);
-                        mm.insert(
-                            addr_usize,
-                            self.read_memory(Address::from_usize(addr_usize), size)?.to_vec(),
-                        )
+                        let addr = Address::from_usize(self.read_usize(bytes)?);
+                        (addr, self.read_memory(addr, size)?.to_vec())
                     }
                     None => {
                         let element_size = match t.kind(Interner) {
@@ -1228,18 +2119,164 @@ impl Evaluator<'_> {
                             TyKind::Slice(t) => {
                                 self.size_of_sized(t, locals, "slice inner type")?
                             }
-                            _ => return Ok(mm), // FIXME: support other kind of unsized types
+                            _ => return Ok(()), // FIXME: support other kind of unsized types
                         };
                         let (addr, meta) = bytes.split_at(bytes.len() / 2);
-                        let size = element_size * from_bytes!(usize, meta);
-                        let addr = Address::from_bytes(addr)?;
-                        mm.insert(addr.to_usize(), self.read_memory(addr, size)?.to_vec());
+                        let size = element_size * self.read_usize(meta)?;
+                        let addr = self.read_address(addr)?;
+                        (addr, self.read_memory(addr, size)?.to_vec())
+                    }
+                };
+                if mm.0.contains_key(&addr.to_usize()) {
+                    return Ok(());
+                }
+                match t.kind(Interner) {
+                    TyKind::Str => (),
+                    TyKind::Slice(t) => {
+                        let element_size = self.size_of_sized(t, locals, "slice inner type")?;
+                        for element in value.chunks_mut(element_size.max(1)) {
+                            self.add_referenced_memory(element, t, locals, mm)?;
+                            self.convert_endianness(element, t, locals)?;
+                        }
+                    }
+                    _ => {
+                        self.add_referenced_memory(&value, t, locals, mm)?;
+                        self.convert_endianness(&mut value, t, locals)?;
+                    }
+                }
+                mm.insert(addr.to_usize(), value);
+            }
+            TyKind::Tuple(_, subst) => {
+                let layout = self.layout(ty)?;
+                for (i, field) in subst.iter(Interner).enumerate() {
+                    let field = field.assert_ty_ref(Interner);
+                    let offset = layout.fields.offset(i).bytes_usize();
+                    let size = self.size_of_sized(field, locals, "tuple field")?;
+                    self.add_referenced_memory(&bytes[offset..offset + size], field, locals, mm)?;
+                }
+            }
+            TyKind::Array(inner, _) => {
+                let size = self.size_of_sized(inner, locals, "array inner type")?;
+                for x in bytes.chunks(size.max(1)) {
+                    self.add_referenced_memory(x, inner, locals, mm)?;
+                }
+            }
+            TyKind::Adt(adt, subst) => {
+                let layout = self.layout_adt(adt.0, subst.clone())?;
+                // The values of enums being evaluated have a dummy size, see `size_of`.
+                if layout.size.bytes_usize() != bytes.len() {
+                    return Ok(());
+                }
+                let (variant, layout) = match adt.0 {
+                    AdtId::StructId(s) => (s.into(), &layout),
+                    AdtId::EnumId(e) if self.endian == Endian::Little => {
+                        let Some(local_id) =
+                            detect_variant(self.db, self.crate_id, e, &layout, bytes)
+                        else {
+                            return Ok(());
+                        };
+                        let variant_layout = match &layout.variants {
+                            Variants::Single { .. } => &layout,
+                            Variants::Multiple { variants, .. } => {
+                                &variants[RustcEnumVariantIdx(local_id)]
+                            }
+                        };
+                        (EnumVariantId { parent: e, local_id }.into(), variant_layout)
                     }
+                    // FIXME: support enums on big endian targets, and unions
+                    AdtId::EnumId(_) | AdtId::UnionId(_) => return Ok(()),
+                };
+                let field_types = self.db.field_types(variant);
+                for (id, field_ty) in field_types.iter() {
+                    let field_ty = field_ty.clone().substitute(Interner, subst);
+                    let offset =
+                        layout.fields.offset(u32::from(id.into_raw()) as usize).bytes_usize();
+                    let size = self.size_of_sized(&field_ty, locals, "struct field")?;
+                    self.add_referenced_memory(
+                        &bytes[offset..offset + size],
+                        &field_ty,
+                        locals,
+                        mm,
+                    )?;
                 }
             }
             _ => (),
         }
-        Ok(mm)
+        Ok(())
+    }
+
+    /// The bytes of a [`Const`] are always little endian, but the memory of the interpreter uses the byte
+    /// order of the target. This function converts a value of type `ty` between these two representations
+    /// by reversing each scalar in it. The conversion is its own inverse, so it works in both directions.
+    fn convert_endianness(&self, bytes: &mut [u8], ty: &Ty, locals: &Locals<'_>) -> Result<()> {
+        if self.endian == Endian::Little || bytes.is_empty() {
+            return Ok(());
+        }
+        let ty = &self.ty_filler(ty, locals.subst, locals.body.owner)?;
+        match ty.kind(Interner) {
+            TyKind::Scalar(_) => bytes.reverse(),
+            // Pointers are either thin, or an address followed by a pointer sized metadata (a slice length
+            // or a vtable id), so reversing each pointer sized chunk is enough.
+            TyKind::Ref(..) | TyKind::Raw(..) | TyKind::Function(_) => {
+                bytes.chunks_mut(self.ptr_size()).for_each(|x| x.reverse())
+            }
+            TyKind::Array(inner, _) => {
+                let size = self.size_of_sized(inner, locals, "array inner type")?;
+                for x in bytes.chunks_mut(size.max(1)) {
+                    self.convert_endianness(x, inner, locals)?;
+                }
+            }
+            TyKind::Tuple(_, subst) => {
+                let layout = self.layout(ty)?;
+                for (i, field) in subst.iter(Interner).enumerate() {
+                    let field = field.assert_ty_ref(Interner);
+                    let offset = layout.fields.offset(i).bytes_usize();
+                    let size = self.size_of_sized(field, locals, "tuple field")?;
+                    self.convert_endianness(&mut bytes[offset..offset + size], field, locals)?;
+                }
+            }
+            TyKind::Adt(adt, subst) => match adt.0 {
+                AdtId::StructId(s) => {
+                    let layout = self.layout_adt(adt.0, subst.clone())?;
+                    let field_types = self.db.field_types(s.into());
+                    for (id, field_ty) in field_types.iter() {
+                        let field_ty = field_ty.clone().substitute(Interner, subst);
+                        let offset =
+                            layout.fields.offset(u32::from(id.into_raw()) as usize).bytes_usize();
+                        let size = self.size_of_sized(&field_ty, locals, "struct field")?;
+                        self.convert_endianness(
+                            &mut bytes[offset..offset + size],
+                            &field_ty,
+                            locals,
+                        )?;
+                    }
+                }
+                AdtId::EnumId(e) => match locals.body.owner {
+                    // The values of enums being evaluated have a dummy size, see `size_of`.
+                    DefWithBodyId::VariantId(f) if f.parent == e => bytes.reverse(),
+                    // FIXME: support enums, which need their active variant to be known
+                    _ => not_supported!("byte order conversion of enums"),
+                },
+                AdtId::UnionId(_) => not_supported!("byte order conversion of unions"),
+            },
+            _ => not_supported!("byte order conversion of {ty:?}"),
+        }
+        Ok(())
+    }
+
+    fn convert_endianness_in_memory(
+        &mut self,
+        addr: Address,
+        size: usize,
+        ty: &Ty,
+        locals: &Locals<'_>,
+    ) -> Result<()> {
+        if self.endian == Endian::Little {
+            return Ok(());
+        }
+        let mut bytes = self.read_memory(addr, size)?.to_vec();
+        self.convert_endianness(&mut bytes, ty, locals)?;
+        self.write_memory(addr, &bytes)
     }
 
     fn patch_addresses(
@@ -1255,16 +2292,37 @@ impl Evaluator<'_> {
             TyKind::Ref(_, _, t) => {
                 let size = self.size_of(t, locals)?;
                 match size {
-                    Some(_) => {
-                        let current = from_bytes!(usize, self.read_memory(addr, my_size)?);
+                    Some(size) => {
+                        let current = self.read_usize(&self.read_memory(addr, my_size)?)?;
                         if let Some(x) = patch_map.get(&current) {
-                            self.write_memory(addr, &x.to_le_bytes())?;
+                            self.write_memory(addr, &self.usize_to_bytes(*x))?;
+                            self.convert_endianness_in_memory(
+                                Address::from_usize(*x),
+                                size,
+                                t,
+                                locals,
+                            )?;
                         }
                     }
                     None => {
-                        let current = from_bytes!(usize, self.read_memory(addr, my_size / 2)?);
+                        let current = self.read_usize(&self.read_memory(addr, my_size / 2)?)?;
                         if let Some(x) = patch_map.get(&current) {
-                            self.write_memory(addr, &x.to_le_bytes())?;
+                            self.write_memory(addr, &self.usize_to_bytes(*x))?;
+                            if let TyKind::Slice(t) = t.kind(Interner) {
+                                let element_size =
+                                    self.size_of_sized(t, locals, "slice inner type")?;
+                                let len = self.read_usize(
+                                    &self.read_memory(addr.offset(my_size / 2), my_size / 2)?,
+                                )?;
+                                for i in 0..len {
+                                    self.convert_endianness_in_memory(
+                                        Address::from_usize(*x + i * element_size),
+                                        element_size,
+                                        t,
+                                        locals,
+                                    )?;
+                                }
+                            }
                         }
                     }
                 }
@@ -1288,16 +2346,8 @@ impl Evaluator<'_> {
                     return Err(MirEvalError::TypeError("size_of generic arg is not provided"));
                 };
                 let size = self.size_of_sized(ty, locals, "size_of arg")?;
-                destination.write_from_bytes(self, &size.to_le_bytes()[0..destination.size])
-            }
-            "wrapping_add" => {
-                let [lhs, rhs] = args else {
-                    return Err(MirEvalError::TypeError("const_eval_select args are not provided"));
-                };
-                let lhs = u128::from_le_bytes(pad16(lhs.get(self)?, false));
-                let rhs = u128::from_le_bytes(pad16(rhs.get(self)?, false));
-                let ans = lhs.wrapping_add(rhs);
-                destination.write_from_bytes(self, &ans.to_le_bytes()[0..destination.size])
+                let size = self.truncate16((size as u128).to_le_bytes(), destination.size);
+                destination.write_from_bytes(self, &size)
             }
             "copy" | "copy_nonoverlapping" => {
                 let [src, dst, offset] = args else {
@@ -1306,15 +2356,28 @@ impl Evaluator<'_> {
                 let Some(ty) = generic_args.as_slice(Interner).get(0).and_then(|x| x.ty(Interner)) else {
                     return Err(MirEvalError::TypeError("copy_nonoverlapping generic arg is not provided"));
                 };
-                let src = Address::from_bytes(src.get(self)?)?;
-                let dst = Address::from_bytes(dst.get(self)?)?;
-                let offset = from_bytes!(usize, offset.get(self)?);
+                let src = self.read_pointer(src.interval)?;
+                let dst = self.read_pointer(dst.interval)?;
+                let offset = self.read_usize(&offset.get(self)?)?;
                 let size = self.size_of_sized(ty, locals, "copy_nonoverlapping ptr type")?;
                 let size = offset * size;
                 let src = Interval { addr: src, size };
                 let dst = Interval { addr: dst, size };
                 dst.write_from_interval(self, src)
             }
+            "write_bytes" => {
+                let [dst, val, count] = args else {
+                    return Err(MirEvalError::TypeError("write_bytes args are not provided"));
+                };
+                let Some(ty) = generic_args.as_slice(Interner).get(0).and_then(|x| x.ty(Interner)) else {
+                    return Err(MirEvalError::TypeError("write_bytes generic arg is not provided"));
+                };
+                let dst = self.read_pointer(dst.interval)?;
+                let val = val.get(self)?[0];
+                let count = self.read_usize(&count.get(self)?)?;
+                let size = self.size_of_sized(ty, locals, "write_bytes ptr type")?;
+                self.fill_memory(dst, count * size, val)
+            }
             "offset" | "arith_offset" => {
                 let [ptr, offset] = args else {
                     return Err(MirEvalError::TypeError("offset args are not provided"));
@@ -1322,11 +2385,59 @@ impl Evaluator<'_> {
                 let Some(ty) = generic_args.as_slice(Interner).get(0).and_then(|x| x.ty(Interner)) else {
                     return Err(MirEvalError::TypeError("offset generic arg is not provided"));
                 };
-                let ptr = u128::from_le_bytes(pad16(ptr.get(self)?, false));
-                let offset = u128::from_le_bytes(pad16(offset.get(self)?, false));
+                let has_provenance = self.has_provenance(ptr.interval.addr);
+                let ptr = u128::from_le_bytes(self.pad16(&ptr.get(self)?, false));
+                let offset = u128::from_le_bytes(self.pad16(&offset.get(self)?, false));
                 let size = self.size_of_sized(ty, locals, "offset ptr type")? as u128;
                 let ans = ptr + offset * size;
-                destination.write_from_bytes(self, &ans.to_le_bytes()[0..destination.size])
+                let ans = self.truncate16(ans.to_le_bytes(), destination.size);
+                destination.write_from_bytes(self, &ans)?;
+                if !has_provenance {
+                    self.mark_no_provenance(destination.addr);
+                }
+                Ok(())
+            }
+            "ptr_offset_from" | "ptr_offset_from_unsigned" => {
+                let [ptr, base] = args else {
+                    return Err(MirEvalError::TypeError("ptr_offset_from args are not provided"));
+                };
+                let Some(ty) = generic_args.as_slice(Interner).get(0).and_then(|x| x.ty(Interner))
+                else {
+                    return Err(MirEvalError::TypeError(
+                        "ptr_offset_from generic arg is not provided",
+                    ));
+                };
+                let size = self.size_of_sized(ty, locals, "ptr_offset_from ptr type")? as i128;
+                let ptr = self.read_address(&ptr.get(self)?)?;
+                let base = self.read_address(&base.get(self)?)?;
+                // We don't track the allocations, so only the pointers into different memories are
+                // known to be unrelated.
+                if !matches!(
+                    (ptr, base),
+                    (Stack(_), Stack(_)) | (Heap(_), Heap(_)) | (Invalid(_), Invalid(_))
+                ) {
+                    return Err(MirEvalError::UndefinedBehavior(
+                        "ptr_offset_from between pointers into different allocations",
+                    ));
+                }
+                if size == 0 {
+                    return Err(MirEvalError::UndefinedBehavior(
+                        "ptr_offset_from with a zero sized type",
+                    ));
+                }
+                let distance = ptr.to_usize() as i128 - base.to_usize() as i128;
+                if distance % size != 0 {
+                    return Err(MirEvalError::UndefinedBehavior(
+                        "ptr_offset_from with a distance which isn't a multiple of the type size",
+                    ));
+                }
+                if as_str == "ptr_offset_from_unsigned" && distance < 0 {
+                    return Err(MirEvalError::UndefinedBehavior(
+                        "ptr_offset_from_unsigned with a negative distance",
+                    ));
+                }
+                let ans = self.truncate16((distance / size).to_le_bytes(), destination.size);
+                destination.write_from_bytes(self, &ans)
             }
             "assert_inhabited" | "assert_zero_valid" | "assert_uninit_valid" => {
                 // FIXME: We should actually implement these checks
@@ -1340,7 +2451,24 @@ impl Evaluator<'_> {
                 let [arg] = args else {
                     return Err(MirEvalError::TypeError("trasmute arg is not provided"));
                 };
-                destination.write_from_interval(self, arg.interval)
+                destination.write_from_interval(self, arg.interval)?;
+                // Unlike an `as` cast, transmuting an integer into a pointer doesn't pick up the
+                // provenance of any exposed pointer, so the result can't be dereferenced.
+                let from_int = matches!(
+                    arg.ty.kind(Interner),
+                    TyKind::Scalar(Scalar::Int(_) | Scalar::Uint(_))
+                );
+                let to_ptr = generic_args
+                    .as_slice(Interner)
+                    .get(1)
+                    .and_then(|x| x.ty(Interner))
+                    .map_or(false, |ty| {
+                        matches!(ty.kind(Interner), TyKind::Raw(..) | TyKind::Ref(..))
+                    });
+                if from_int && to_ptr {
+                    self.mark_no_provenance(destination.addr);
+                }
+                Ok(())
             }
             "const_eval_select" => {
                 let [tuple, const_fn, _] = args else {
@@ -1359,7 +2487,15 @@ impl Evaluator<'_> {
                 }
                 self.exec_fn_trait(&args, destination, locals)
             }
-            _ => not_supported!("unknown intrinsic {as_str}"),
+            "try" | "r#try" => self.exec_try_intrinsic(args, destination, locals),
+            _ => {
+                if self.exec_int_intrinsic(as_str, args, destination)?
+                    || self.exec_float_intrinsic(as_str, args, destination)?
+                {
+                    return Ok(());
+                }
+                not_supported!("unknown intrinsic {as_str}")
+            }
         }
     }
 
@@ -1370,7 +2506,7 @@ impl Evaluator<'_> {
         args: &[IntervalAndTy],
         locals: &Locals<'_>,
     ) -> Result<()> {
-        let id = from_bytes!(usize, bytes.get(self)?);
+        let id = self.read_usize(&bytes.get(self)?)?;
         let next_ty = self.vtable_map.ty(id)?.clone();
         if let TyKind::FnDef(def, generic_args) = &next_ty.data(Interner).kind {
             self.exec_fn_def(*def, generic_args, destination, args, &locals)?;
@@ -1392,11 +2528,40 @@ impl Evaluator<'_> {
             .db
             .mir_body_for_closure(closure)
             .map_err(|x| MirEvalError::MirLowerErrorForClosure(closure, x))?;
-        let arg_bytes = iter::once(Ok(closure_data.get(self)?.to_owned()))
-            .chain(args.iter().map(|x| Ok(x.get(&self)?.to_owned())))
-            .collect::<Result<Vec<_>>>()?;
-        let bytes = self.interpret_mir(&mir_body, arg_bytes.into_iter(), generic_args.clone())?;
-        destination.write_from_bytes(self, &bytes)
+        let args = iter::once(closure_data)
+            .chain(args.iter().map(|x| x.interval))
+            .map(IntervalOrOwned::Borrowed);
+        let result = self.interpret_mir(&mir_body, args, generic_args.clone())?;
+        destination.write_from_interval(self, result)
+    }
+
+    /// Polls the future to completion. We have no executor, so only futures that never return
+    /// `Pending` on their own, i.e. async blocks and async fns, are supported.
+    fn exec_await(&mut self, future: IntervalAndTy, destination: Interval) -> Result<()> {
+        let mut ty = future.ty;
+        // The future of an async fn is its opaque return type, whose hidden type is the async block
+        // of its body.
+        if let TyKind::OpaqueType(id, subst) = ty.kind(Interner) {
+            if let crate::ImplTraitId::ReturnTypeImplTrait(func, idx) =
+                self.db.lookup_intern_impl_trait_id((*id).into())
+            {
+                let hidden_ty = self.db.infer(func.into()).type_of_rpit[idx].clone();
+                ty = self.ty_filler(&hidden_ty, subst, func.into())?;
+            }
+        }
+        let TyKind::OpaqueType(_, subst) = ty.kind(Interner) else {
+            not_supported!("awaiting a future which is not an async block");
+        };
+        let Some(closure) = generator_closure_id(self.db, &ty) else {
+            not_supported!("awaiting a future which is not an async block");
+        };
+        let mir_body = self
+            .db
+            .mir_body_for_closure(closure)
+            .map_err(|x| MirEvalError::MirLowerErrorForClosure(closure, x))?;
+        let future = IntervalOrOwned::Borrowed(future.interval);
+        let result = self.interpret_mir(&mir_body, iter::once(future), subst.clone())?;
+        destination.write_from_interval(self, result)
     }
 
     fn exec_fn_def(
@@ -1452,18 +2617,7 @@ impl Evaluator<'_> {
         destination: Interval,
     ) -> Result<()> {
         let function_data = self.db.function_data(def);
-        let is_intrinsic = match &function_data.abi {
-            Some(abi) => *abi == Interned::new_str("rust-intrinsic"),
-            None => match def.lookup(self.db.upcast()).container {
-                hir_def::ItemContainerId::ExternBlockId(block) => {
-                    let id = block.lookup(self.db.upcast()).id;
-                    id.item_tree(self.db.upcast())[id.value].abi.as_deref()
-                        == Some("rust-intrinsic")
-                }
-                _ => false,
-            },
-        };
-        if is_intrinsic {
+        if self.is_intrinsic(def) {
             return self.exec_intrinsic(
                 function_data.name.as_text().unwrap_or_default().as_str(),
                 args,
@@ -1472,56 +2626,66 @@ impl Evaluator<'_> {
                 &locals,
             );
         }
+        if self.exec_external_call(def, args, &generic_args, locals, destination)? {
+            return Ok(());
+        }
+        if let ItemContainerId::ExternBlockId(_) = def.lookup(self.db.upcast()).container {
+            return self.exec_extern_fn(
+                function_data.name.as_text().unwrap_or_default().as_str(),
+                args,
+                locals,
+                destination,
+            );
+        }
         let arg_bytes =
-            args.iter().map(|x| Ok(x.get(&self)?.to_owned())).collect::<Result<Vec<_>>>()?;
-        let result = if let Some(x) = self.detect_lang_function(def) {
-            self.exec_lang_item(x, &arg_bytes)?
-        } else {
-            if let Some(self_ty_idx) =
-                is_dyn_method(self.db, self.trait_env.clone(), def, generic_args.clone())
-            {
-                // In the layout of current possible receiver, which at the moment of writing this code is one of
-                // `&T`, `&mut T`, `Box<T>`, `Rc<T>`, `Arc<T>`, and `Pin<P>` where `P` is one of possible receivers,
-                // the vtable is exactly in the `[ptr_size..2*ptr_size]` bytes. So we can use it without branching on
-                // the type.
-                let ty = self
-                    .vtable_map
-                    .ty_of_bytes(&arg_bytes[0][self.ptr_size()..self.ptr_size() * 2])?;
-                let mut args_for_target = args.to_vec();
-                args_for_target[0] = IntervalAndTy {
-                    interval: args_for_target[0].interval.slice(0..self.ptr_size()),
-                    ty: ty.clone(),
-                };
-                let ty = GenericArgData::Ty(ty.clone()).intern(Interner);
-                let generics_for_target = Substitution::from_iter(
-                    Interner,
-                    generic_args.iter(Interner).enumerate().map(|(i, x)| {
-                        if i == self_ty_idx {
-                            &ty
-                        } else {
-                            x
-                        }
-                    }),
-                );
-                return self.exec_fn_with_args(
-                    def,
-                    &args_for_target,
-                    generics_for_target,
-                    locals,
-                    destination,
-                );
-            }
-            let (imp, generic_args) =
-                lookup_impl_method(self.db, self.trait_env.clone(), def, generic_args);
-            let generic_args = self.subst_filler(&generic_args, &locals);
-            let def = imp.into();
-            let mir_body =
-                self.db.mir_body(def).map_err(|e| MirEvalError::MirLowerError(imp, e))?;
-            self.interpret_mir(&mir_body, arg_bytes.iter().cloned(), generic_args)
-                .map_err(|e| MirEvalError::InFunction(imp, Box::new(e)))?
-        };
-        destination.write_from_bytes(self, &result)?;
-        Ok(())
+            args.iter().map(|x| Ok(x.get(&self)?.into_owned())).collect::<Result<Vec<_>>>()?;
+        if let Some(x) = self.detect_lang_function(def) {
+            let result = self.exec_lang_item(x, args, &arg_bytes)?;
+            return destination.write_from_bytes(self, &result);
+        }
+        if let Some(self_ty_idx) =
+            is_dyn_method(self.db, self.trait_env.clone(), def, generic_args.clone())
+        {
+            // In the layout of current possible receiver, which at the moment of writing this code is one of
+            // `&T`, `&mut T`, `Box<T>`, `Rc<T>`, `Arc<T>`, and `Pin<P>` where `P` is one of possible receivers,
+            // the vtable is exactly in the `[ptr_size..2*ptr_size]` bytes. So we can use it without branching on
+            // the type.
+            let id = self.read_usize(&arg_bytes[0][self.ptr_size()..self.ptr_size() * 2])?;
+            let ty = self.vtable_map.ty(id)?;
+            let mut args_for_target = args.to_vec();
+            args_for_target[0] = IntervalAndTy {
+                interval: args_for_target[0].interval.slice(0..self.ptr_size()),
+                ty: ty.clone(),
+            };
+            let ty = GenericArgData::Ty(ty.clone()).intern(Interner);
+            let generics_for_target = Substitution::from_iter(
+                Interner,
+                generic_args.iter(Interner).enumerate().map(|(i, x)| {
+                    if i == self_ty_idx {
+                        &ty
+                    } else {
+                        x
+                    }
+                }),
+            );
+            return self.exec_fn_with_args(
+                def,
+                &args_for_target,
+                generics_for_target,
+                locals,
+                destination,
+            );
+        }
+        let (imp, generic_args) =
+            lookup_impl_method(self.db, self.trait_env.clone(), def, generic_args);
+        let generic_args = self.subst_filler(&generic_args, &locals);
+        let def = imp.into();
+        let mir_body = self.db.mir_body(def).map_err(|e| MirEvalError::MirLowerError(imp, e))?;
+        let args = args.iter().map(|x| IntervalOrOwned::Borrowed(x.interval));
+        let result = self
+            .interpret_mir(&mir_body, args, generic_args)
+            .map_err(|e| MirEvalError::InFunction(imp, Box::new(e)))?;
+        destination.write_from_interval(self, result)
     }
 
     fn exec_fn_trait(
@@ -1537,12 +2701,12 @@ impl Evaluator<'_> {
             func_ty = z.clone();
             if matches!(func_ty.kind(Interner), TyKind::Dyn(_)) {
                 let id =
-                    from_bytes!(usize, &func_data.get(self)?[self.ptr_size()..self.ptr_size() * 2]);
+                    self.read_usize(&func_data.get(self)?[self.ptr_size()..self.ptr_size() * 2])?;
                 func_data = func_data.slice(0..self.ptr_size());
                 func_ty = self.vtable_map.ty(id)?.clone();
             }
             let size = self.size_of_sized(&func_ty, locals, "self type of fn trait")?;
-            func_data = Interval { addr: Address::from_bytes(func_data.get(self)?)?, size };
+            func_data = Interval { addr: self.read_address(&func_data.get(self)?)?, size };
         }
         match &func_ty.data(Interner).kind {
             TyKind::FnDef(def, subst) => {
@@ -1559,17 +2723,24 @@ impl Evaluator<'_> {
         Ok(())
     }
 
-    fn exec_lang_item(&self, x: LangItem, args: &[Vec<u8>]) -> Result<Vec<u8>> {
+    fn exec_lang_item(
+        &self,
+        x: LangItem,
+        args: &[IntervalAndTy],
+        arg_bytes: &[Vec<u8>],
+    ) -> Result<Vec<u8>> {
         use LangItem::*;
-        let mut args = args.iter();
         match x {
-            // FIXME: we want to find the panic message from arguments, but it wouldn't work
-            // currently even if we do that, since macro expansion of panic related macros
-            // is dummy.
-            PanicFmt | BeginPanic => Err(MirEvalError::Panic("<format-args>".to_string())),
+            PanicFmt | BeginPanic => {
+                let message = match args.first() {
+                    Some(arg) => self.panic_message(arg)?,
+                    None => None,
+                };
+                Err(MirEvalError::Panic(message.unwrap_or_else(|| "<format-args>".to_string())))
+            }
             SliceLen => {
-                let arg = args
-                    .next()
+                let arg = arg_bytes
+                    .first()
                     .ok_or(MirEvalError::TypeError("argument of <[T]>::len() is not provided"))?;
                 let ptr_size = arg.len() / 2;
                 Ok(arg[ptr_size..].into())
@@ -1577,6 +2748,113 @@ impl Evaluator<'_> {
             x => not_supported!("Executing lang item {x:?}"),
         }
     }
+
+    /// Renders the message of a panic from the argument of `panic_fmt`, a `fmt::Arguments`, or of
+    /// `begin_panic`. `format_args!` only expands to the pieces of format strings without
+    /// placeholders, the messages of the others aren't rendered.
+    fn panic_message(&self, arg: &IntervalAndTy) -> Result<Option<String>> {
+        let bytes = arg.get(self)?;
+        match arg.ty.kind(Interner) {
+            TyKind::Ref(_, _, inner) if matches!(inner.kind(Interner), TyKind::Str) => {
+                Ok(Some(self.read_str(&bytes)?))
+            }
+            TyKind::Adt(chalk_ir::AdtId(AdtId::StructId(id)), subst) => {
+                let data = self.db.struct_data(*id);
+                let pieces = data.variant_data.fields().iter().find_map(|(field, data)| {
+                    (data.name.to_smol_str() == "pieces").then_some(field)
+                });
+                let Some(pieces) = pieces else { return Ok(None) };
+                let layout = self.layout_adt((*id).into(), subst.clone())?;
+                let offset =
+                    layout.fields.offset(u32::from(pieces.into_raw()) as usize).bytes_usize();
+                let ptr_size = self.ptr_size();
+                let addr = self.read_address(&bytes[offset..offset + ptr_size])?;
+                let len = self.read_usize(&bytes[offset + ptr_size..offset + 2 * ptr_size])?;
+                if len == 0 {
+                    return Ok(None);
+                }
+                let pieces = self.read_memory(addr, len * 2 * ptr_size)?.into_owned();
+                let mut message = String::new();
+                for piece in pieces.chunks(2 * ptr_size) {
+                    message.push_str(&self.read_str(piece)?);
+                }
+                Ok(Some(message))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Reads the string a `&str` with the bytes `bytes` points to.
+    fn read_str(&self, bytes: &[u8]) -> Result<String> {
+        let ptr_size = self.ptr_size();
+        let addr = self.read_address(&bytes[..ptr_size])?;
+        let len = self.read_usize(&bytes[ptr_size..2 * ptr_size])?;
+        Ok(String::from_utf8_lossy(&self.read_memory(addr, len)?).into_owned())
+    }
+}
+
+/// Returns the variant of the enum `enum_id` stored in `bytes`, a little endian value with the
+/// layout `layout`, or `None` if the tag doesn't belong to any variant.
+pub(crate) fn detect_variant(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    enum_id: EnumId,
+    layout: &Layout,
+    bytes: &[u8],
+) -> Option<LocalEnumVariantId> {
+    let (tag, tag_encoding) = match &layout.variants {
+        Variants::Single { index } => return Some(index.0),
+        Variants::Multiple { tag, tag_encoding, .. } => (tag, tag_encoding),
+    };
+    let target_data_layout = db.target_data_layout(krate)?;
+    let size = tag.size(&*target_data_layout).bytes_usize();
+    let offset = layout.fields.offset(0).bytes_usize(); // The only field on enum variants is the tag field
+    let tag = i128::from_le_bytes(pad16(bytes.get(offset..offset + size)?, false));
+    let enum_data = db.enum_data(enum_id);
+    let mut variants = enum_data.variants.iter().map(|(local_id, _)| local_id);
+    let discriminant =
+        |local_id| db.const_eval_discriminant(EnumVariantId { parent: enum_id, local_id }).ok();
+    match tag_encoding {
+        TagEncoding::Direct => {
+            let mask = if size >= 16 { -1 } else { (1 << (size * 8)) - 1 };
+            variants.find(|&it| discriminant(it).map_or(false, |it| it & mask == tag))
+        }
+        TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+            Some(niche_variant(tag, size, niche_variants, *niche_start, *untagged_variant))
+        }
+    }
+}
+
+/// Returns the variant of a niche encoded enum whose tag of `size` bytes is `tag`. The tags of the
+/// variants in `niche_variants` are their index relative to the first of them, offset by
+/// `niche_start` and wrapping around in the size of the tag, like rustc does in
+/// `rustc_codegen_ssa::mir::place::codegen_get_discr`. Other tags are values of the untagged
+/// variant.
+fn niche_variant(
+    tag: i128,
+    size: usize,
+    niche_variants: &RangeInclusive<RustcEnumVariantIdx>,
+    niche_start: u128,
+    untagged_variant: RustcEnumVariantIdx,
+) -> LocalEnumVariantId {
+    let mask = if size >= 16 { u128::MAX } else { (1 << (size * 8)) - 1 };
+    let relative = (tag as u128).wrapping_sub(niche_start) & mask;
+    let (start, end) = (niche_variants.start().index(), niche_variants.end().index());
+    match usize::try_from(relative) {
+        Ok(relative) if relative <= end - start => RustcEnumVariantIdx::new(start + relative).0,
+        _ => untagged_variant.0,
+    }
+}
+
+/// Returns the tag of `variant`, a variant of a niche encoded enum in `niche_variants`. The
+/// inverse of [`niche_variant`].
+fn niche_tag(
+    variant: RustcEnumVariantIdx,
+    niche_variants: &RangeInclusive<RustcEnumVariantIdx>,
+    niche_start: u128,
+) -> i128 {
+    let relative = variant.index().wrapping_sub(niche_variants.start().index());
+    (relative as i128).wrapping_add(niche_start as i128)
 }
 
 pub fn pad16(x: &[u8], is_signed: bool) -> [u8; 16] {
@@ -0,0 +1,172 @@
+//! The hook letting an embedding client answer the calls the evaluator can't execute, like reading
+//! a config file or the default value of a hardware register.
+//!
+//! Values cross the boundary as bytes, in the layout of their type for the target, except strings
+//! and byte slices which are passed as their contents. Other pointers can't cross it, so the client
+//! never sees addresses of the memory of the evaluator.
+
+use std::panic::RefUnwindSafe;
+
+use chalk_ir::{Scalar, TyKind, UintTy};
+use hir_def::{AdtId, FunctionId, HasModule, ItemContainerId, Lookup};
+use itertools::Itertools;
+
+use crate::{display::HirDisplay, CallableDefId, Interner, Substitution, Ty, TyExt};
+
+use super::{Evaluator, Interval, IntervalAndTy, Locals, MirEvalError, Result};
+
+/// Answers the calls of some functions instead of the evaluator, see [`ExternalCall`].
+pub trait ExternalValueProvider: RefUnwindSafe {
+    /// Returns whether the calls of the function at `path`, like `my_crate::hw::read_register`,
+    /// are answered by [`ExternalValueProvider::call`] instead of being executed. It's called once
+    /// per function and evaluation.
+    fn provides(&self, path: &str) -> bool;
+
+    /// Returns the bytes of the result of `call`, or an error message which stops the evaluation.
+    fn call(&self, call: &ExternalCall) -> std::result::Result<Vec<u8>, String>;
+}
+
+/// A call answered by an [`ExternalValueProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalCall {
+    /// The path of the function, with the name of the trait or of the self type for associated
+    /// functions, like `my_crate::hw::Register::read`.
+    pub path: String,
+    pub args: Vec<ExternalValue>,
+    /// The type of the result, as displayed.
+    pub ret_ty: String,
+}
+
+/// An argument of an [`ExternalCall`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalValue {
+    /// The type of the value, as displayed.
+    pub ty: String,
+    /// The bytes of the value, or the contents of `&str` and `&[u8]` values.
+    pub bytes: Vec<u8>,
+}
+
+impl Evaluator<'_> {
+    /// Lets the external value provider answer the call of `def`, if it provides the function.
+    /// Returns whether it did.
+    pub(super) fn exec_external_call(
+        &mut self,
+        def: FunctionId,
+        args: &[IntervalAndTy],
+        generic_args: &Substitution,
+        locals: &Locals<'_>,
+        destination: Interval,
+    ) -> Result<bool> {
+        let Some(provider) = self.external_values else {
+            return Ok(false);
+        };
+        let path = match self.external_paths.get(&def) {
+            Some(path) => path.clone(),
+            None => {
+                let path = self.external_path(def);
+                let path = provider.provides(&path).then_some(path);
+                self.external_paths.insert(def, path.clone());
+                path
+            }
+        };
+        let Some(path) = path else {
+            return Ok(false);
+        };
+
+        let args = args
+            .iter()
+            .map(|arg| {
+                let bytes = match is_contents(&arg.ty) {
+                    true => self.read_contents(&arg.interval.get(self)?)?,
+                    false => arg.interval.get(self)?.into_owned(),
+                };
+                Ok(ExternalValue { ty: arg.ty.display(self.db).to_string(), bytes })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ret_ty = self
+            .db
+            .callable_item_signature(CallableDefId::FunctionId(def))
+            .substitute(Interner, generic_args)
+            .ret()
+            .clone();
+        let ret_ty = self.ty_filler(&ret_ty, locals.subst, locals.body.owner)?;
+        let call = ExternalCall { path, args, ret_ty: ret_ty.display(self.db).to_string() };
+        let bytes = provider.call(&call).map_err(|e| MirEvalError::ExternalCallFailed(def, e))?;
+
+        if is_contents(&ret_ty) {
+            let addr = self.heap_allocate(bytes.len());
+            self.write_memory(addr, &bytes)?;
+            let mut ptr = self.usize_to_bytes(addr.to_usize());
+            ptr.extend(self.usize_to_bytes(bytes.len()));
+            destination.write_from_bytes(self, &ptr)?;
+        } else {
+            let size = self.size_of_sized(&ret_ty, locals, "result of an external call")?;
+            if bytes.len() != size {
+                return Err(MirEvalError::ExternalCallFailed(
+                    def,
+                    format!("expected {size} bytes for `{}`, got {}", call.ret_ty, bytes.len()),
+                ));
+            }
+            destination.write_from_bytes(self, &bytes)?;
+        }
+        Ok(true)
+    }
+
+    /// Reads the contents of a `&str` or a `&[u8]`.
+    fn read_contents(&self, fat_ptr: &[u8]) -> Result<Vec<u8>> {
+        let ptr_size = self.ptr_size();
+        let addr = self.read_address(&fat_ptr[..ptr_size])?;
+        let len = self.read_usize(&fat_ptr[ptr_size..])?;
+        Ok(self.read_memory(addr, len)?.into_owned())
+    }
+
+    /// Returns the path of `def` passed to the external value provider.
+    fn external_path(&self, def: FunctionId) -> String {
+        let db = self.db;
+        let mut segments = vec![db.function_data(def).name.to_smol_str()];
+        match def.lookup(db.upcast()).container {
+            ItemContainerId::TraitId(it) => segments.push(db.trait_data(it).name.to_smol_str()),
+            ItemContainerId::ImplId(it) => {
+                let self_ty = db.impl_self_ty(it).skip_binders().clone();
+                segments.push(match self_ty.as_adt() {
+                    Some((AdtId::StructId(it), _)) => db.struct_data(it).name.to_smol_str(),
+                    Some((AdtId::UnionId(it), _)) => db.union_data(it).name.to_smol_str(),
+                    Some((AdtId::EnumId(it), _)) => db.enum_data(it).name.to_smol_str(),
+                    None => self_ty.display(db).to_string().into(),
+                });
+            }
+            ItemContainerId::ModuleId(_) | ItemContainerId::ExternBlockId(_) => (),
+        }
+        let fn_module = def.module(db.upcast());
+        let mut module = Some(fn_module);
+        while let Some(it) = module {
+            let def_map = it.def_map(db.upcast());
+            if let Some(parent) = def_map[it.local_id].parent {
+                let name = def_map[parent].children.iter().find(|(_, &child)| child == it.local_id);
+                if let Some((name, _)) = name {
+                    segments.push(name.to_smol_str());
+                }
+            }
+            module = it.containing_module(db.upcast());
+        }
+        let crate_graph = db.crate_graph();
+        let krate = &crate_graph[fn_module.krate()].display_name;
+        let krate = krate.as_ref().map_or("crate", |name| name.canonical_name());
+        segments.push(krate.into());
+        segments.iter().rev().join("::")
+    }
+}
+
+/// Returns whether the values of `ty` cross the boundary as their contents.
+fn is_contents(ty: &Ty) -> bool {
+    let TyKind::Ref(_, _, pointee) = ty.kind(Interner) else {
+        return false;
+    };
+    match pointee.kind(Interner) {
+        TyKind::Str => true,
+        TyKind::Slice(elem) => {
+            matches!(elem.kind(Interner), TyKind::Scalar(Scalar::Uint(UintTy::U8)))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,262 @@
+//! Routes the allocation shims through the `#[global_allocator]` and the `#[alloc_error_handler]`
+//! of the crate graph, so that the evaluation reflects their behavior, like the alignment of the
+//! blocks they hand out. Their MIR is interpreted like the one of any other function, under the
+//! same execution limit.
+
+use std::iter;
+
+use chalk_ir::{Mutability, TyKind};
+use hir_def::{
+    lang_item::{EntryItem, LangItemTarget},
+    AdtId, AssocItemId, DefWithBodyId, FunctionId, HasModule, StaticId, TraitId,
+};
+
+use crate::{
+    db::HirDatabase, layout::Layout, method_resolution::TyFingerprint, static_lifetime,
+    CallableDefId, Interner, Substitution, TraitRefExt, Ty, TyExt,
+};
+
+use super::{Address, Evaluator, Interval, IntervalAndTy, Locals, MirEvalError, Result};
+
+/// The `#[global_allocator]` static, and its methods from the `GlobalAlloc` trait.
+pub(super) struct GlobalAllocator {
+    static_: StaticId,
+    ty: Ty,
+    alloc: FunctionId,
+    alloc_zeroed: Option<FunctionId>,
+    realloc: Option<FunctionId>,
+    dealloc: FunctionId,
+    /// The type of the `layout` parameter of the methods.
+    layout_ty: Ty,
+    /// The address of the value of the static, once evaluated.
+    value: Option<Address>,
+}
+
+impl Evaluator<'_> {
+    /// Makes the allocation shims call the `#[global_allocator]` and the `#[alloc_error_handler]`
+    /// of the crate graph instead of allocating on the heap of the evaluator themselves, if they
+    /// are defined. Fails if the allocator doesn't implement `GlobalAlloc` as expected.
+    pub(super) fn use_entry_allocator(&mut self) -> Result<()> {
+        let db = self.db;
+        for krate in db.crate_graph().transitive_deps(self.crate_id) {
+            for &(kind, target) in db.crate_lang_items(krate).entry_items() {
+                match (kind, target) {
+                    (EntryItem::GlobalAllocator, LangItemTarget::Static(it))
+                        if self.global_allocator.is_none() =>
+                    {
+                        self.global_allocator = Some(self.resolve_global_allocator(it)?);
+                    }
+                    (EntryItem::AllocErrorHandler, LangItemTarget::Function(it)) => {
+                        self.alloc_error_handler.get_or_insert(it);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_global_allocator(&self, static_: StaticId) -> Result<GlobalAllocator> {
+        let db = self.db;
+        let invalid = |reason| MirEvalError::InvalidGlobalAllocator(static_, reason);
+        let ty = db.value_ty(static_.into()).substitute(Interner, &Substitution::empty(Interner));
+        let krate = DefWithBodyId::from(static_).module(db.upcast()).krate();
+        let impls = db.trait_impls_in_deps(krate);
+        let trait_ = TyFingerprint::for_trait_impl(&ty)
+            .and_then(|fp| {
+                impls.for_self_ty_without_blanket_impls(fp).find_map(|it| {
+                    let trait_ = db.impl_trait(it)?.skip_binders().hir_trait_id();
+                    (db.trait_data(trait_).name.to_smol_str() == "GlobalAlloc").then_some(trait_)
+                })
+            })
+            .ok_or_else(|| invalid("its type doesn't implement `GlobalAlloc`"))?;
+        let method = |name| trait_method(db, trait_, name);
+        let (Some(alloc), Some(dealloc)) = (method("alloc"), method("dealloc")) else {
+            return Err(invalid("`GlobalAlloc` doesn't have `alloc` and `dealloc` methods"));
+        };
+        let sig = db.callable_item_signature(CallableDefId::FunctionId(alloc));
+        let sig = sig.substitute(Interner, &Substitution::from1(Interner, ty.clone()));
+        let [_, layout_ty] = sig.params() else {
+            return Err(invalid("`GlobalAlloc::alloc` doesn't take a `Layout`"));
+        };
+        if !matches!(sig.ret().kind(Interner), TyKind::Raw(..)) || !is_layout(self, layout_ty) {
+            return Err(invalid("`GlobalAlloc::alloc` doesn't map a `Layout` to a pointer"));
+        }
+        Ok(GlobalAllocator {
+            static_,
+            ty,
+            alloc,
+            alloc_zeroed: method("alloc_zeroed"),
+            realloc: method("realloc"),
+            dealloc,
+            layout_ty: layout_ty.clone(),
+            value: None,
+        })
+    }
+
+    /// Executes an allocation shim with the `#[global_allocator]`, or the
+    /// `__rust_alloc_error_handler` shim with the `#[alloc_error_handler]`. Returns whether they
+    /// are defined.
+    pub(super) fn exec_entry_allocator(
+        &mut self,
+        name: &str,
+        args: &[IntervalAndTy],
+        locals: &Locals<'_>,
+        destination: Interval,
+    ) -> Result<bool> {
+        if name == "__rust_alloc_error_handler" {
+            let Some(handler) = self.alloc_error_handler else {
+                return Ok(false);
+            };
+            let [size, align] = args else {
+                return Err(MirEvalError::TypeError(
+                    "__rust_alloc_error_handler args are not provided",
+                ));
+            };
+            let sig = self.db.callable_item_signature(CallableDefId::FunctionId(handler));
+            let sig = sig.substitute(Interner, &Substitution::empty(Interner));
+            let [layout_ty] = sig.params() else {
+                return Err(MirEvalError::TypeError("alloc_error_handler doesn't take a `Layout`"));
+            };
+            let layout = self.layout_arg(layout_ty, size, align)?;
+            let subst = Substitution::empty(Interner);
+            self.exec_fn_with_args(handler, &[layout], subst, locals, destination)?;
+            return Ok(true);
+        }
+
+        let Some(allocator) = &self.global_allocator else {
+            return Ok(false);
+        };
+        if !matches!(
+            name,
+            "__rust_alloc" | "__rust_alloc_zeroed" | "__rust_realloc" | "__rust_dealloc"
+        ) {
+            return Ok(false);
+        }
+        let (alloc, alloc_zeroed, realloc, dealloc) =
+            (allocator.alloc, allocator.alloc_zeroed, allocator.realloc, allocator.dealloc);
+        let (ty, layout_ty) = (allocator.ty.clone(), allocator.layout_ty.clone());
+        let receiver = self.global_allocator_receiver()?;
+        let subst = Substitution::from1(Interner, ty);
+        let (method, args) = match (name, args) {
+            ("__rust_alloc", [size, align]) => {
+                (alloc, vec![self.layout_arg(&layout_ty, size, align)?])
+            }
+            ("__rust_alloc_zeroed", [size, align]) => {
+                (alloc_zeroed.unwrap_or(alloc), vec![self.layout_arg(&layout_ty, size, align)?])
+            }
+            ("__rust_realloc", [ptr, old_size, align, new_size]) => {
+                let Some(realloc) = realloc else {
+                    return Err(MirEvalError::TypeError("`GlobalAlloc` has no `realloc` method"));
+                };
+                let layout = self.layout_arg(&layout_ty, old_size, align)?;
+                (realloc, vec![ptr.clone(), layout, new_size.clone()])
+            }
+            ("__rust_dealloc", [ptr, size, align]) => {
+                (dealloc, vec![ptr.clone(), self.layout_arg(&layout_ty, size, align)?])
+            }
+            _ => return Err(MirEvalError::TypeError("allocation shim args are not provided")),
+        };
+        let args: Vec<_> = iter::once(receiver).chain(args).collect();
+        self.exec_fn_with_args(method, &args, subst, locals, destination)?;
+        Ok(true)
+    }
+
+    /// Returns the `&self` argument of the methods of the `#[global_allocator]`, evaluating the
+    /// static on the first call.
+    fn global_allocator_receiver(&mut self) -> Result<IntervalAndTy> {
+        let Some(allocator) = &self.global_allocator else {
+            return Err(MirEvalError::TypeError("no global allocator"));
+        };
+        let (static_, ty, value) = (allocator.static_, allocator.ty.clone(), allocator.value);
+        let value = match value {
+            Some(value) => value,
+            None => {
+                let body = self.db.mir_body(static_.into()).map_err(|_| {
+                    MirEvalError::InvalidGlobalAllocator(static_, "its value can't be lowered")
+                })?;
+                let result =
+                    self.interpret_mir(&body, iter::empty(), Substitution::empty(Interner))?;
+                let bytes = result.get(self)?.into_owned();
+                let align = self.layout(&ty)?.align.abi.bytes() as usize;
+                let value = self.heap_allocate_aligned(bytes.len(), align)?;
+                if !bytes.is_empty() {
+                    self.write_memory(value, &bytes)?;
+                }
+                if let Some(allocator) = &mut self.global_allocator {
+                    allocator.value = Some(value);
+                }
+                value
+            }
+        };
+        let receiver = self.heap_allocate(self.ptr_size());
+        self.write_memory(receiver, &self.address_to_bytes(value))?;
+        Ok(IntervalAndTy {
+            interval: Interval::new(receiver, self.ptr_size()),
+            ty: TyKind::Ref(Mutability::Not, static_lifetime(), ty).intern(Interner),
+        })
+    }
+
+    /// Builds a `Layout` of type `layout_ty` from the `size` and `align` arguments of a shim.
+    fn layout_arg(
+        &mut self,
+        layout_ty: &Ty,
+        size: &IntervalAndTy,
+        align: &IntervalAndTy,
+    ) -> Result<IntervalAndTy> {
+        let layout = self.layout(layout_ty)?;
+        let (Some(size_offset), Some(align_offset)) = (
+            field_offset(self, layout_ty, &layout, &["size", "size_"]),
+            field_offset(self, layout_ty, &layout, &["align", "align_"]),
+        ) else {
+            return Err(MirEvalError::TypeError("`Layout` doesn't have size and align fields"));
+        };
+        let ptr_size = self.ptr_size();
+        let mut bytes = vec![0; layout.size.bytes_usize()];
+        bytes[size_offset..size_offset + ptr_size].copy_from_slice(&size.get(self)?);
+        // `Alignment` is a `usize`-sized enum whose discriminants are the alignments.
+        bytes[align_offset..align_offset + ptr_size].copy_from_slice(&align.get(self)?);
+        let addr = self.heap_allocate(bytes.len());
+        self.write_memory(addr, &bytes)?;
+        Ok(IntervalAndTy { interval: Interval::new(addr, bytes.len()), ty: layout_ty.clone() })
+    }
+}
+
+fn trait_method(db: &dyn HirDatabase, trait_: TraitId, name: &str) -> Option<FunctionId> {
+    db.trait_data(trait_).items.iter().find_map(|(item_name, item)| match item {
+        AssocItemId::FunctionId(it) if item_name.to_smol_str() == name => Some(*it),
+        _ => None,
+    })
+}
+
+/// Returns whether `ty` is a struct with a size and an alignment fields, like `Layout`.
+fn is_layout(evaluator: &Evaluator<'_>, ty: &Ty) -> bool {
+    match evaluator.layout(ty) {
+        Ok(layout) => {
+            field_offset(evaluator, ty, &layout, &["size", "size_"]).is_some()
+                && field_offset(evaluator, ty, &layout, &["align", "align_"]).is_some()
+        }
+        Err(_) => false,
+    }
+}
+
+/// Returns the offset of the `usize`-sized field of the struct `ty` with one of the `names`.
+fn field_offset(
+    evaluator: &Evaluator<'_>,
+    ty: &Ty,
+    layout: &Layout,
+    names: &[&str],
+) -> Option<usize> {
+    let Some((AdtId::StructId(id), _)) = ty.as_adt() else {
+        return None;
+    };
+    let data = evaluator.db.struct_data(id);
+    let (field, _) = data
+        .variant_data
+        .fields()
+        .iter()
+        .find(|(_, field)| names.contains(&field.name.to_smol_str().as_str()))?;
+    let idx = u32::from(field.into_raw()) as usize;
+    let offset = layout.fields.offset(idx).bytes_usize();
+    (offset + evaluator.ptr_size() <= layout.size.bytes_usize()).then_some(offset)
+}
@@ -0,0 +1,195 @@
+//! The memory of the MIR interpreter.
+//!
+//! Memory is split into pages, and pages in which every byte has the same value are stored as that
+//! single byte. This way a large array repeat expression like `[0; 10_000_000]`, or a `memset`-like
+//! write of a large region, doesn't need a real allocation until some part of it is written with
+//! distinct values.
+//!
+//! Next to the bytes, the memory keeps track of the pointers stored in it which have no provenance,
+//! like a pointer transmuted from an integer. Pointers are assumed to have a valid provenance unless
+//! they are in this set, so losing track of a pointer (e.g. when it's copied into an aggregate) can
+//! only hide an invalid dereference, never report a false one.
+//!
+//! Similarly, the memory keeps track of the uninitialized bytes of unions, which are the bytes
+//! outside of the field a union expression initializes. They are initialized by any write.
+
+use std::{borrow::Cow, collections::BTreeSet, iter, ops::Range};
+
+pub(super) const PAGE_SIZE: usize = 4096;
+
+/// The widest pointer of the targets we support.
+const MAX_PTR_SIZE: usize = 8;
+
+static ZERO_PAGE: [u8; PAGE_SIZE] = [0; PAGE_SIZE];
+
+#[derive(Debug, Clone)]
+enum Page {
+    /// Every byte of the page has this value.
+    Filled(u8),
+    Bytes(Box<[u8; PAGE_SIZE]>),
+}
+
+impl Page {
+    fn bytes_mut(&mut self) -> &mut [u8; PAGE_SIZE] {
+        if let Page::Filled(b) = *self {
+            *self = Page::Bytes(Box::new([b; PAGE_SIZE]));
+        }
+        match self {
+            Page::Bytes(bytes) => bytes,
+            Page::Filled(_) => unreachable!(),
+        }
+    }
+}
+
+/// A growable, zero initialized memory. Bytes past `len` in the last page are always zero.
+#[derive(Debug, Default)]
+pub(super) struct Memory {
+    pages: Vec<Page>,
+    len: usize,
+    /// Start positions of the pointers without provenance.
+    no_provenance: BTreeSet<usize>,
+    /// Positions of the uninitialized bytes.
+    uninit: BTreeSet<usize>,
+}
+
+impl Memory {
+    pub(super) fn zeroed(len: usize) -> Memory {
+        let mut memory = Memory::default();
+        memory.extend_zeroed(len);
+        memory
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Appends `size` zero bytes to the end of the memory.
+    pub(super) fn extend_zeroed(&mut self, size: usize) {
+        self.len += size;
+        let page_count = (self.len + PAGE_SIZE - 1) / PAGE_SIZE;
+        self.pages.resize(page_count, Page::Filled(0));
+    }
+
+    pub(super) fn read(&self, pos: usize, size: usize) -> Option<Cow<'_, [u8]>> {
+        self.check_bounds(pos, size)?;
+        if size == 0 {
+            return Some(Cow::Borrowed(&[]));
+        }
+        // Most of the reads are of scalars, which are inside a single page, so we can borrow them.
+        if pos / PAGE_SIZE == (pos + size - 1) / PAGE_SIZE {
+            let range = pos % PAGE_SIZE..pos % PAGE_SIZE + size;
+            return Some(match &self.pages[pos / PAGE_SIZE] {
+                Page::Bytes(bytes) => Cow::Borrowed(&bytes[range]),
+                Page::Filled(0) => Cow::Borrowed(&ZERO_PAGE[range]),
+                Page::Filled(b) => Cow::Owned(vec![*b; size]),
+            });
+        }
+        let mut result = Vec::with_capacity(size);
+        for (page, range) in segments(pos, size) {
+            match &self.pages[page] {
+                Page::Bytes(bytes) => result.extend_from_slice(&bytes[range]),
+                Page::Filled(b) => result.resize(result.len() + range.len(), *b),
+            }
+        }
+        Some(Cow::Owned(result))
+    }
+
+    pub(super) fn write(&mut self, pos: usize, mut bytes: &[u8]) -> Option<()> {
+        self.check_bounds(pos, bytes.len())?;
+        self.clear_provenance_marks(pos, bytes.len());
+        self.clear_uninit_marks(pos, bytes.len());
+        for (page, range) in segments(pos, bytes.len()) {
+            let (chunk, rest) = bytes.split_at(range.len());
+            bytes = rest;
+            let page = &mut self.pages[page];
+            match *page {
+                Page::Filled(b) if chunk.iter().all(|&x| x == b) => (),
+                _ if range.len() == PAGE_SIZE && chunk.iter().all(|&x| x == chunk[0]) => {
+                    *page = Page::Filled(chunk[0])
+                }
+                _ => page.bytes_mut()[range].copy_from_slice(chunk),
+            }
+        }
+        Some(())
+    }
+
+    /// Sets `size` bytes starting from `pos` to `byte`. Pages which are entirely covered by the
+    /// range are not materialized.
+    pub(super) fn fill(&mut self, pos: usize, size: usize, byte: u8) -> Option<()> {
+        self.check_bounds(pos, size)?;
+        self.clear_provenance_marks(pos, size);
+        self.clear_uninit_marks(pos, size);
+        for (page, range) in segments(pos, size) {
+            let page = &mut self.pages[page];
+            match *page {
+                Page::Filled(b) if b == byte => (),
+                _ if range.len() == PAGE_SIZE => *page = Page::Filled(byte),
+                _ => page.bytes_mut()[range].fill(byte),
+            }
+        }
+        Some(())
+    }
+
+    /// Marks the pointer stored at `pos` as having no provenance. The mark is cleared by any write
+    /// which overlaps `pos`.
+    pub(super) fn mark_no_provenance(&mut self, pos: usize) {
+        self.no_provenance.insert(pos);
+    }
+
+    pub(super) fn has_provenance(&self, pos: usize) -> bool {
+        !self.no_provenance.contains(&pos)
+    }
+
+    /// Returns the offsets, relative to `pos`, of the pointers without provenance which are stored
+    /// in `pos..pos + size`.
+    pub(super) fn no_provenance_marks(&self, pos: usize, size: usize) -> Vec<usize> {
+        self.no_provenance.range(pos..pos + size).map(|x| x - pos).collect()
+    }
+
+    fn clear_provenance_marks(&mut self, pos: usize, size: usize) {
+        // A write into the middle of a pointer also invalidates its mark.
+        let start = pos.saturating_sub(MAX_PTR_SIZE - 1);
+        if self.no_provenance.range(start..pos + size).next().is_none() {
+            return;
+        }
+        let rest = self.no_provenance.split_off(&start);
+        self.no_provenance.extend(rest.into_iter().filter(|&x| x >= pos + size));
+    }
+
+    /// Marks `size` bytes starting from `pos` as uninitialized, until they are written.
+    pub(super) fn mark_uninit(&mut self, pos: usize, size: usize) {
+        self.uninit.extend(pos..pos + size);
+    }
+
+    pub(super) fn is_init(&self, pos: usize, size: usize) -> bool {
+        self.uninit.range(pos..pos + size).next().is_none()
+    }
+
+    fn clear_uninit_marks(&mut self, pos: usize, size: usize) {
+        if self.is_init(pos, size) {
+            return;
+        }
+        let rest = self.uninit.split_off(&pos);
+        self.uninit.extend(rest.into_iter().filter(|&x| x >= pos + size));
+    }
+
+    fn check_bounds(&self, pos: usize, size: usize) -> Option<()> {
+        (pos.checked_add(size)? <= self.len).then_some(())
+    }
+}
+
+/// Splits `pos..pos + size` into the index of each page it overlaps, and the range inside that page.
+fn segments(pos: usize, size: usize) -> impl Iterator<Item = (usize, Range<usize>)> {
+    let end = pos + size;
+    let mut current = pos;
+    iter::from_fn(move || {
+        if current >= end {
+            return None;
+        }
+        let start = current % PAGE_SIZE;
+        let len = (PAGE_SIZE - start).min(end - current);
+        let page = current / PAGE_SIZE;
+        current += len;
+        Some((page, start..start + len))
+    })
+}
@@ -0,0 +1,414 @@
+//! Implementations of the intrinsics which are executed on the host, like the float math functions
+//! and the integer arithmetic, and of the functions of the runtime which have no body, like
+//! `__rust_panic_cleanup`, the functions of the global allocator and the sources of entropy.
+
+use chalk_ir::{Mutability, Scalar, TyKind};
+
+use crate::{static_lifetime, Interner, Substitution, TyBuilder};
+
+use super::{Evaluator, Interval, IntervalAndTy, Locals, MirEvalError, Result};
+
+macro_rules! float_intrinsic {
+    ($this:expr, $float:ident, $bits:ident, $op:expr, $args:expr) => {{
+        let this: &Evaluator<'_> = $this;
+        let arg = |i: usize| {
+            $args.get(i).ok_or(MirEvalError::TypeError("float intrinsic args are not provided"))
+        };
+        let float = |i: usize| -> Result<$float> {
+            let bits = u128::from_le_bytes(this.pad16(&arg(i)?.get(this)?, false));
+            Ok($float::from_bits(bits as $bits))
+        };
+        match $op {
+            "sqrt" => float(0)?.sqrt(),
+            "sin" => float(0)?.sin(),
+            "cos" => float(0)?.cos(),
+            "exp" => float(0)?.exp(),
+            "exp2" => float(0)?.exp2(),
+            "log" => float(0)?.ln(),
+            "log10" => float(0)?.log10(),
+            "log2" => float(0)?.log2(),
+            "fabs" => float(0)?.abs(),
+            "floor" => float(0)?.floor(),
+            "ceil" => float(0)?.ceil(),
+            "trunc" => float(0)?.trunc(),
+            "round" => float(0)?.round(),
+            "rint" | "nearbyint" => {
+                // Rounds half-way cases to even, like the default rounding mode of the FPU.
+                let x = float(0)?;
+                let r = x.round();
+                if (x - x.trunc()).abs() == 0.5 {
+                    2.0 * (x / 2.0).round()
+                } else {
+                    r
+                }
+            }
+            "pow" => float(0)?.powf(float(1)?),
+            "powi" => {
+                let exp = i128::from_le_bytes(this.pad16(&arg(1)?.get(this)?, true));
+                float(0)?.powi(exp as i32)
+            }
+            "minnum" => float(0)?.min(float(1)?),
+            "maxnum" => float(0)?.max(float(1)?),
+            "copysign" => float(0)?.copysign(float(1)?),
+            "fma" => float(0)?.mul_add(float(1)?, float(2)?),
+            _ => return Ok(false),
+        }
+    }};
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+}
+
+/// How an integer intrinsic handles results which don't fit in the type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntMode {
+    Wrapping,
+    Saturating,
+    /// Overflowing is undefined behavior, like dividing with a remainder for `exact_div`.
+    Unchecked,
+    Exact,
+    /// Returns the wrapped result and whether it overflowed.
+    WithOverflow,
+    /// Like `WithOverflow`, with a third operand which is a carry or a borrow.
+    Carrying,
+}
+
+fn int_intrinsic(name: &str) -> Option<(IntOp, IntMode)> {
+    use IntMode::*;
+    use IntOp::*;
+    let it = match name {
+        "wrapping_add" => (Add, Wrapping),
+        "wrapping_sub" => (Sub, Wrapping),
+        "wrapping_mul" => (Mul, Wrapping),
+        "saturating_add" => (Add, Saturating),
+        "saturating_sub" => (Sub, Saturating),
+        "unchecked_add" => (Add, Unchecked),
+        "unchecked_sub" => (Sub, Unchecked),
+        "unchecked_mul" => (Mul, Unchecked),
+        "unchecked_div" => (Div, Unchecked),
+        "unchecked_rem" => (Rem, Unchecked),
+        "unchecked_shl" => (Shl, Unchecked),
+        "unchecked_shr" => (Shr, Unchecked),
+        "exact_div" => (Div, Exact),
+        "add_with_overflow" => (Add, WithOverflow),
+        "sub_with_overflow" => (Sub, WithOverflow),
+        "mul_with_overflow" => (Mul, WithOverflow),
+        "carrying_add" => (Add, Carrying),
+        "borrowing_sub" => (Sub, Carrying),
+        _ => return None,
+    };
+    Some(it)
+}
+
+/// Evaluates an integer intrinsic with `$int`, which is `i128` or `u128`, and returns the bytes of
+/// the result and, for the intrinsics returning a tuple, whether it overflowed.
+macro_rules! int_intrinsic {
+    ($this:expr, $int:ident, $signed:expr, $op:expr, $mode:expr, $args:expr) => {{
+        let this: &Evaluator<'_> = $this;
+        let args: &[IntervalAndTy] = $args;
+        let read = |i: usize| -> Result<$int> {
+            let arg = args
+                .get(i)
+                .ok_or(MirEvalError::TypeError("integer intrinsic args are not provided"))?;
+            Ok($int::from_le_bytes(this.pad16(&arg.get(this)?, $signed)))
+        };
+        let bits = args[0].interval.size as u32 * 8;
+        let (min, max) = ($int::MIN >> (128 - bits), $int::MAX >> (128 - bits));
+        let in_range = |x: Option<$int>| x.filter(|x| (min..=max).contains(x));
+        // Wraps a value computed with 128 bits around to the bits of the type.
+        let wrap = |x: $int| (x << (128 - bits)) >> (128 - bits);
+        let overflowing = |op: IntOp, lhs: $int, rhs: $int| match op {
+            IntOp::Add => (wrap(lhs.wrapping_add(rhs)), in_range(lhs.checked_add(rhs)).is_none()),
+            IntOp::Sub => (wrap(lhs.wrapping_sub(rhs)), in_range(lhs.checked_sub(rhs)).is_none()),
+            _ => (wrap(lhs.wrapping_mul(rhs)), in_range(lhs.checked_mul(rhs)).is_none()),
+        };
+
+        let (lhs, rhs) = (read(0)?, read(1)?);
+        let (result, overflow): ($int, Option<bool>) = match ($op, $mode) {
+            (IntOp::Shl | IntOp::Shr, _) => {
+                if !(0..bits as $int).contains(&rhs) {
+                    return Err(MirEvalError::UndefinedBehavior("shift amount is out of range"));
+                }
+                match $op {
+                    IntOp::Shl => (wrap(lhs << rhs), None),
+                    _ => (lhs >> rhs, None),
+                }
+            }
+            (IntOp::Div | IntOp::Rem, mode) => {
+                if rhs == 0 {
+                    return Err(MirEvalError::UndefinedBehavior("division by zero"));
+                }
+                let result = match $op {
+                    IntOp::Div => in_range(lhs.checked_div(rhs)),
+                    _ => in_range(lhs.checked_rem(rhs)),
+                };
+                let Some(result) = result else {
+                    return Err(MirEvalError::UndefinedBehavior("division overflowed"));
+                };
+                if mode == IntMode::Exact && lhs % rhs != 0 {
+                    return Err(MirEvalError::UndefinedBehavior("exact_div with a remainder"));
+                }
+                (result, None)
+            }
+            (op, IntMode::Wrapping) => (overflowing(op, lhs, rhs).0, None),
+            (op, IntMode::Saturating) => match overflowing(op, lhs, rhs) {
+                (result, false) => (result, None),
+                // Adding a positive number or subtracting a negative one overflows upwards.
+                (_, true) if (op == IntOp::Add) == (rhs > 0) => (max, None),
+                (_, true) => (min, None),
+            },
+            (op, IntMode::Unchecked | IntMode::Exact) => match overflowing(op, lhs, rhs) {
+                (result, false) => (result, None),
+                (_, true) => {
+                    return Err(MirEvalError::UndefinedBehavior("unchecked arithmetic overflowed"))
+                }
+            },
+            (op, IntMode::WithOverflow) => {
+                let (result, overflow) = overflowing(op, lhs, rhs);
+                (result, Some(overflow))
+            }
+            (op, IntMode::Carrying) => {
+                let carry = read(2)? & 1;
+                let (result, first) = overflowing(op, lhs, rhs);
+                let (result, second) = overflowing(op, result, carry);
+                // Like in `core`, a signed result overflows if exactly one of the steps does.
+                (result, Some(if $signed { first != second } else { first || second }))
+            }
+        };
+        (result.to_le_bytes(), overflow)
+    }};
+}
+
+impl Evaluator<'_> {
+    /// Executes a float math intrinsic, like `sqrtf64` or `powif32`, using the float operations of
+    /// the host. Returns `false` if `name` isn't such an intrinsic.
+    pub(super) fn exec_float_intrinsic(
+        &mut self,
+        name: &str,
+        args: &[IntervalAndTy],
+        destination: Interval,
+    ) -> Result<bool> {
+        let bits = if let Some(op) = name.strip_suffix("f32") {
+            float_intrinsic!(self, f32, u32, op, args).to_bits() as u128
+        } else if let Some(op) = name.strip_suffix("f64") {
+            float_intrinsic!(self, f64, u64, op, args).to_bits() as u128
+        } else {
+            return Ok(false);
+        };
+        let result = self.truncate16(bits.to_le_bytes(), destination.size);
+        destination.write_from_bytes(self, &result)?;
+        Ok(true)
+    }
+
+    /// Executes an integer arithmetic intrinsic, like `saturating_add`, `mul_with_overflow` or
+    /// `unchecked_shl`, whose undefined behaviors are reported as errors. Returns `false` if
+    /// `name` isn't such an intrinsic.
+    pub(super) fn exec_int_intrinsic(
+        &mut self,
+        name: &str,
+        args: &[IntervalAndTy],
+        destination: Interval,
+    ) -> Result<bool> {
+        let Some((op, mode)) = int_intrinsic(name) else {
+            return Ok(false);
+        };
+        let Some(ty) = args.first().map(|it| it.ty.clone()) else {
+            return Err(MirEvalError::TypeError("integer intrinsic args are not provided"));
+        };
+        let (bytes, overflow) = match ty.kind(Interner) {
+            TyKind::Scalar(Scalar::Int(_)) => int_intrinsic!(self, i128, true, op, mode, args),
+            TyKind::Scalar(Scalar::Uint(_)) => int_intrinsic!(self, u128, false, op, mode, args),
+            _ => return Err(MirEvalError::TypeError("integer intrinsic on a non-integer type")),
+        };
+        let size = args[0].interval.size;
+        let result = self.truncate16(bytes, size);
+        let Some(overflow) = overflow else {
+            destination.write_from_bytes(self, &result)?;
+            return Ok(true);
+        };
+
+        // The result is a `(T, bool)` tuple.
+        let tuple = TyKind::Tuple(2, Substitution::from_iter(Interner, [ty, TyBuilder::bool()]))
+            .intern(Interner);
+        let layout = self.layout(&tuple)?;
+        let (value_offset, flag_offset) =
+            (layout.fields.offset(0).bytes_usize(), layout.fields.offset(1).bytes_usize());
+        destination.slice(value_offset..value_offset + size).write_from_bytes(self, &result)?;
+        destination
+            .slice(flag_offset..flag_offset + 1)
+            .write_from_bytes(self, &[overflow as u8])?;
+        Ok(true)
+    }
+
+    /// Executes the `try` intrinsic, which `catch_unwind` is built on. It calls `try_fn(data)`,
+    /// and if that panics, `catch_fn(data, payload)`, where `payload` can be turned into the
+    /// `Box<dyn Any + Send>` of the panic by `__rust_panic_cleanup`.
+    pub(super) fn exec_try_intrinsic(
+        &mut self,
+        args: &[IntervalAndTy],
+        destination: Interval,
+        locals: &Locals<'_>,
+    ) -> Result<()> {
+        let [try_fn, data, catch_fn] = args else {
+            return Err(MirEvalError::TypeError("try args are not provided"));
+        };
+        let stack_depth_limit = self.stack_depth_limit;
+        let unit = destination.slice(0..0);
+        let message = match self.exec_fn_pointer(try_fn.interval, unit, &[data.clone()], locals) {
+            Ok(()) => return destination.write_from_bytes(self, &vec![0; destination.size]),
+            Err(e) => match panic_message(&e) {
+                Some(message) => message.to_owned(),
+                None => return Err(e),
+            },
+        };
+        // The frames of the unwound functions never returned.
+        self.stack_depth_limit = stack_depth_limit;
+
+        // The payload is a `&'static str` of the message, like the payload of `panic!("...")`.
+        let bytes = self.heap_allocate(message.len());
+        self.write_memory(bytes, message.as_bytes())?;
+        let payload = self.heap_allocate(self.ptr_size() * 2);
+        let mut fat_ptr = self.address_to_bytes(bytes);
+        fat_ptr.extend(self.usize_to_bytes(message.len()));
+        self.write_memory(payload, &fat_ptr)?;
+
+        let payload_arg = self.heap_allocate(self.ptr_size());
+        self.write_memory(payload_arg, &self.address_to_bytes(payload))?;
+        let payload_arg = IntervalAndTy {
+            interval: Interval::new(payload_arg, self.ptr_size()),
+            ty: data.ty.clone(),
+        };
+        self.exec_fn_pointer(catch_fn.interval, unit, &[data.clone(), payload_arg], locals)?;
+        let result = self.truncate16(1u128.to_le_bytes(), destination.size);
+        destination.write_from_bytes(self, &result)
+    }
+
+    /// Executes a function of an `extern` block which isn't an intrinsic, and so has no body.
+    pub(super) fn exec_extern_fn(
+        &mut self,
+        name: &str,
+        args: &[IntervalAndTy],
+        locals: &Locals<'_>,
+        destination: Interval,
+    ) -> Result<()> {
+        if self.exec_entry_allocator(name, args, locals, destination)? {
+            return Ok(());
+        }
+        match name {
+            "__rust_panic_cleanup" => {
+                // Returns the payload of `exec_try_intrinsic` as a `*mut (dyn Any + Send)`.
+                let [payload] = args else {
+                    return Err(MirEvalError::TypeError(
+                        "__rust_panic_cleanup arg is not provided",
+                    ));
+                };
+                let str_ref =
+                    TyKind::Ref(Mutability::Not, static_lifetime(), TyKind::Str.intern(Interner))
+                        .intern(Interner);
+                let mut result = payload.get(self)?.into_owned();
+                result.extend(self.usize_to_bytes(self.vtable_map.id(str_ref)));
+                destination.write_from_bytes(self, &result)
+            }
+            // The functions of the global allocator, which the `Allocator` impl of `Global` and so
+            // the standard collections are built on. Unless the evaluation uses the
+            // `#[global_allocator]`, see `exec_entry_allocator`, all allocations are made on the
+            // heap of the evaluator.
+            "__rust_alloc" | "__rust_alloc_zeroed" => {
+                let [size, align] = args else {
+                    return Err(MirEvalError::TypeError("__rust_alloc args are not provided"));
+                };
+                let size = self.read_usize(&size.get(self)?)?;
+                let align = self.read_usize(&align.get(self)?)?;
+                let result = self.heap_allocate_aligned(size, align)?;
+                let result = self.address_to_bytes(result);
+                destination.write_from_bytes(self, &result)
+            }
+            "__rust_realloc" => {
+                let [ptr, old_size, align, new_size] = args else {
+                    return Err(MirEvalError::TypeError("__rust_realloc args are not provided"));
+                };
+                let ptr = self.read_pointer(ptr.interval)?;
+                let old_size = self.read_usize(&old_size.get(self)?)?;
+                let align = self.read_usize(&align.get(self)?)?;
+                let new_size = self.read_usize(&new_size.get(self)?)?;
+                let result = self.heap_allocate_aligned(new_size, align)?;
+                let size = old_size.min(new_size);
+                Interval::new(result, size).write_from_interval(self, Interval::new(ptr, size))?;
+                let result = self.address_to_bytes(result);
+                destination.write_from_bytes(self, &result)
+            }
+            "__rust_dealloc" => {
+                // The heap of the evaluator only grows, so there's nothing to free.
+                Ok(())
+            }
+            "__rust_alloc_error_handler" => {
+                Err(MirEvalError::Panic("memory allocation failed".to_string()))
+            }
+            // The sources of entropy of the platforms, which the random keys of `HashMap`s come
+            // from. They are filled from the deterministic generator of the evaluator instead.
+            "getrandom" | "getentropy" | "arc4random_buf" | "ProcessPrng" => {
+                let [buf, len, ..] = args else {
+                    return Err(MirEvalError::TypeError("entropy source args are not provided"));
+                };
+                let buf = self.read_pointer(buf.interval)?;
+                let len = self.read_usize(&len.get(self)?)?;
+                let mut bytes = vec![0; len];
+                self.rng.fill_bytes(&mut bytes);
+                self.write_memory(buf, &bytes)?;
+                // `getrandom` returns the number of written bytes, `getentropy` zero for success
+                // and `ProcessPrng` a true `BOOL`.
+                let result: u128 = match name {
+                    "getrandom" => len as u128,
+                    "ProcessPrng" => 1,
+                    _ => 0,
+                };
+                let result = self.truncate16(result.to_le_bytes(), destination.size);
+                destination.write_from_bytes(self, &result)
+            }
+            _ => Err(MirEvalError::NotSupported(format!("extern function {name}"))),
+        }
+    }
+}
+
+fn panic_message(e: &MirEvalError) -> Option<&str> {
+    match e {
+        MirEvalError::Panic(message) => Some(message),
+        MirEvalError::InFunction(_, e) => panic_message(e),
+        _ => None,
+    }
+}
+
+/// A deterministic pseudo random number generator, SplitMix64, for the shims needing entropy.
+#[derive(Debug, Clone)]
+pub(super) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(super) fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub(super) fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(8) {
+            let random = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&random[..chunk.len()]);
+        }
+    }
+}
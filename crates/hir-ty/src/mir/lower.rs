@@ -7,8 +7,8 @@ use hir_def::{
     body::Body,
     data::adt::{StructKind, VariantData},
     hir::{
-        Array, BindingAnnotation, BindingId, ExprId, LabelId, Literal, MatchArm, Pat, PatId,
-        RecordFieldPat, RecordLitField,
+        Array, BindingAnnotation, BindingId, ExprId, LabelId, Literal, MatchArm, OffsetOf, Pat,
+        PatId, RecordFieldPat, RecordLitField,
     },
     lang_item::{LangItem, LangItemTarget},
     path::Path,
@@ -23,7 +23,7 @@ use crate::{
     consteval::ConstEvalError,
     db::HirDatabase,
     display::HirDisplay,
-    infer::{CaptureKind, CapturedItem, TypeMismatch},
+    infer::{CaptureKind, CapturedItem, PointerCast, TypeMismatch},
     inhabitedness::is_ty_uninhabited_from,
     layout::{layout_of_ty, LayoutError},
     mapping::ToChalk,
@@ -86,6 +86,8 @@ pub enum MirLowerError {
     UnresolvedLabel,
     UnresolvedUpvar(Place),
     UnaccessableLocal,
+    /// A value of an extern type is used by value, while it can only be used behind a pointer.
+    ExternTypeByValue(Ty),
 }
 
 macro_rules! not_supported {
@@ -148,6 +150,7 @@ impl<'ctx> MirLowerCtx<'ctx> {
             owner,
             arg_count: body.params.len(),
             closures: vec![],
+            generator_layout: None,
         };
         let ctx = MirLowerCtx {
             result: mir,
@@ -166,6 +169,7 @@ impl<'ctx> MirLowerCtx<'ctx> {
         if matches!(ty.kind(Interner), TyKind::Slice(_) | TyKind::Dyn(_)) {
             implementation_error!("unsized temporaries");
         }
+        check_not_extern_type(&ty)?;
         Ok(self.result.locals.alloc(Local { ty }))
     }
 
@@ -176,7 +180,7 @@ impl<'ctx> MirLowerCtx<'ctx> {
     ) -> Result<Option<(Operand, BasicBlockId)>> {
         if !self.has_adjustments(expr_id) {
             match &self.body.exprs[expr_id] {
-                Expr::Literal(l) => {
+                Expr::Literal(l) if !self.body.debug_assertions_cfgs.contains_key(&expr_id) => {
                     let ty = self.expr_ty(expr_id);
                     return Ok(Some((self.lower_literal_to_operand(ty, l)?, current)));
                 }
@@ -282,12 +286,19 @@ impl<'ctx> MirLowerCtx<'ctx> {
                         {
                             match assoc {
                                 hir_def::AssocItemId::ConstId(c) => {
-                                    self.lower_const(c, current, place, subst, expr_id.into())?;
-                                    return Ok(Some(current))
-                                },
+                                    self.lower_const(
+                                        c,
+                                        current,
+                                        place,
+                                        subst,
+                                        expr_id.into(),
+                                        self.expr_ty(expr_id),
+                                    )?;
+                                    return Ok(Some(current));
+                                }
                                 hir_def::AssocItemId::FunctionId(_) => {
                                     // FnDefs are zero sized, no action is needed.
-                                    return Ok(Some(current))
+                                    return Ok(Some(current));
                                 }
                                 hir_def::AssocItemId::TypeAliasId(_) => {
                                     // FIXME: If it is unreachable, use proper error instead of `not_supported`.
@@ -319,7 +330,14 @@ impl<'ctx> MirLowerCtx<'ctx> {
                         Ok(Some(current))
                     }
                     ValueNs::ConstId(const_id) => {
-                        self.lower_const(const_id, current, place, Substitution::empty(Interner), expr_id.into())?;
+                        self.lower_const(
+                            const_id,
+                            current,
+                            place,
+                            Substitution::empty(Interner),
+                            expr_id.into(),
+                            self.expr_ty(expr_id),
+                        )?;
                         Ok(Some(current))
                     }
                     ValueNs::EnumVariantId(variant_id) => {
@@ -653,7 +671,26 @@ impl<'ctx> MirLowerCtx<'ctx> {
                 self.set_terminator(current, Terminator::Return);
                 Ok(None)
             }
-            Expr::Yield { .. } => not_supported!("yield"),
+            Expr::Yield { expr } => {
+                let value = match expr {
+                    Some(expr) => {
+                        let Some((value, c)) = self.lower_expr_to_some_operand(*expr, current)?
+                        else {
+                            return Ok(None);
+                        };
+                        current = c;
+                        value
+                    }
+                    None => Operand::from_bytes(vec![], TyBuilder::unit()),
+                };
+                // The value of a `yield` expression is the argument the generator is resumed with.
+                let resume = self.new_basic_block();
+                self.set_terminator(
+                    current,
+                    Terminator::Yield { value, resume, resume_arg: place, drop: None },
+                );
+                Ok(Some(resume))
+            }
             Expr::RecordLit { fields, path, spread, ellipsis: _, is_assignee_expr: _ } => {
                 let spread_place = match spread {
                     &Some(x) => {
@@ -731,10 +768,37 @@ impl<'ctx> MirLowerCtx<'ctx> {
                     }
                 }
             }
-            Expr::Await { .. } => not_supported!("await"),
+            Expr::Await { expr } => {
+                let Some((future, current)) = self.lower_expr_to_some_operand(*expr, current)?
+                else {
+                    return Ok(None);
+                };
+                let target = self.new_basic_block();
+                self.set_terminator(
+                    current,
+                    Terminator::Await { future, destination: place, target },
+                );
+                Ok(Some(target))
+            }
             Expr::Yeet { .. } => not_supported!("yeet"),
-            Expr::Async { .. } => not_supported!("async block"),
-            Expr::Const { .. } => not_supported!("anonymous const block"),
+            Expr::Async { .. } => {
+                let ty = self.expr_ty(expr_id);
+                let id = self.db.intern_closure((self.owner, expr_id)).into();
+                let operands = self.lower_captures(id, current)?;
+                self.push_assignment(
+                    current,
+                    place,
+                    Rvalue::Aggregate(AggregateKind::Generator(ty), operands),
+                    expr_id.into(),
+                );
+                Ok(Some(current))
+            }
+            Expr::Const { id: _, statements, tail } => {
+                // Inline const blocks can't refer to the locals around them, so we can just
+                // evaluate them in place.
+                self.alloc_locals_of_binding_owner(expr_id);
+                self.lower_block_to_place(statements, current, *tail, place)
+            }
             Expr::Cast { expr, type_ref: _ } => {
                 let Some((x, current)) = self.lower_expr_to_some_operand(*expr, current)? else {
                     return Ok(None);
@@ -799,6 +863,30 @@ impl<'ctx> MirLowerCtx<'ctx> {
                     self.push_assignment(current, lhs_place, rhs_op.into(), expr_id.into());
                     return Ok(Some(current));
                 }
+                if let hir_def::hir::BinaryOp::LogicOp(op) = op {
+                    return self.lower_logic_op(op, *lhs, *rhs, place, current, expr_id);
+                }
+                if let hir_def::hir::BinaryOp::CmpOp(_) = op {
+                    // Comparisons of ADTs call their `PartialEq` and `PartialOrd` impls, like the
+                    // derived ones, instead of comparing their bytes.
+                    if let Some((func_id, generic_args)) = self.infer.method_resolution(expr_id) {
+                        if matches!(self.expr_ty(*lhs).kind(Interner), TyKind::Adt(..)) {
+                            let ty = chalk_ir::TyKind::FnDef(
+                                CallableDefId::FunctionId(func_id).to_chalk(self.db),
+                                generic_args,
+                            )
+                            .intern(Interner);
+                            let func = Operand::from_bytes(vec![], ty);
+                            return self.lower_call_and_args(
+                                func,
+                                [*lhs, *rhs].into_iter(),
+                                place,
+                                current,
+                                self.is_uninhabited(expr_id),
+                            );
+                        }
+                    }
+                }
                 let Some((lhs_op, current)) = self.lower_expr_to_some_operand(*lhs, current)? else {
                     return Ok(None);
                 };
@@ -810,13 +898,10 @@ impl<'ctx> MirLowerCtx<'ctx> {
                     place,
                     Rvalue::CheckedBinaryOp(
                         match op {
-                            hir_def::hir::BinaryOp::LogicOp(op) => match op {
-                                hir_def::hir::LogicOp::And => BinOp::BitAnd, // FIXME: make these short circuit
-                                hir_def::hir::LogicOp::Or => BinOp::BitOr,
-                            },
                             hir_def::hir::BinaryOp::ArithOp(op) => BinOp::from(op),
                             hir_def::hir::BinaryOp::CmpOp(op) => BinOp::from(op),
-                            hir_def::hir::BinaryOp::Assignment { .. } => unreachable!(), // handled above
+                            hir_def::hir::BinaryOp::LogicOp(_)
+                            | hir_def::hir::BinaryOp::Assignment { .. } => unreachable!(), // handled above
                         },
                         lhs_op,
                         rhs_op,
@@ -870,45 +955,19 @@ impl<'ctx> MirLowerCtx<'ctx> {
             },
             Expr::Closure { .. } => {
                 let ty = self.expr_ty(expr_id);
-                let TyKind::Closure(id, _) = ty.kind(Interner) else {
-                    not_supported!("closure with non closure type");
+                let (id, kind) = match ty.kind(Interner) {
+                    TyKind::Closure(id, _) => (*id, AggregateKind::Closure(ty.clone())),
+                    TyKind::Generator(..) => (
+                        self.db.intern_closure((self.owner, expr_id)).into(),
+                        AggregateKind::Generator(ty.clone()),
+                    ),
+                    _ => not_supported!("closure with non closure type"),
                 };
-                self.result.closures.push(*id);
-                let (captures, _) = self.infer.closure_info(id);
-                let mut operands = vec![];
-                for capture in captures.iter() {
-                    let p = Place {
-                        local: self.binding_local(capture.place.local)?,
-                        projection: capture.place.projections.clone().into_iter().map(|x| {
-                            match x {
-                                ProjectionElem::Deref => ProjectionElem::Deref,
-                                ProjectionElem::Field(x) => ProjectionElem::Field(x),
-                                ProjectionElem::TupleOrClosureField(x) => ProjectionElem::TupleOrClosureField(x),
-                                ProjectionElem::ConstantIndex { offset, min_length, from_end } => ProjectionElem::ConstantIndex { offset, min_length, from_end },
-                                ProjectionElem::Subslice { from, to, from_end } => ProjectionElem::Subslice { from, to, from_end },
-                                ProjectionElem::OpaqueCast(x) => ProjectionElem::OpaqueCast(x),
-                                ProjectionElem::Index(x) => match x { },
-                            }
-                        }).collect(),
-                    };
-                    match &capture.kind {
-                        CaptureKind::ByRef(bk) => {
-                            let tmp: Place = self.temp(capture.ty.clone())?.into();
-                            self.push_assignment(
-                                current,
-                                tmp.clone(),
-                                Rvalue::Ref(bk.clone(), p),
-                                capture.span,
-                            );
-                            operands.push(Operand::Move(tmp));
-                        },
-                        CaptureKind::ByValue => operands.push(Operand::Move(p)),
-                    }
-                }
+                let operands = self.lower_captures(id, current)?;
                 self.push_assignment(
                     current,
                     place,
-                    Rvalue::Aggregate(AggregateKind::Closure(ty), operands),
+                    Rvalue::Aggregate(kind, operands),
                     expr_id.into(),
                 );
                 Ok(Some(current))
@@ -964,16 +1023,87 @@ impl<'ctx> MirLowerCtx<'ctx> {
                     self.push_assignment(current, place, r, expr_id.into());
                     Ok(Some(current))
                 }
-                Array::Repeat { .. } => not_supported!("array repeat"),
+                Array::Repeat { initializer, .. } => {
+                    let len = match &self.expr_ty(expr_id).data(Interner).kind {
+                        TyKind::Array(_, len) => len.clone(),
+                        _ => {
+                            return Err(MirLowerError::TypeError(
+                                "Array repeat expression with non array type",
+                            ))
+                        }
+                    };
+                    let Some((init, current)) = self.lower_expr_to_some_operand(*initializer, current)? else {
+                        return Ok(None);
+                    };
+                    let r = Rvalue::Repeat(init, len);
+                    self.push_assignment(current, place, r, expr_id.into());
+                    Ok(Some(current))
+                }
             },
             Expr::Literal(l) => {
-                let ty = self.expr_ty(expr_id);
-                let op = self.lower_literal_to_operand(ty, l)?;
-                self.push_assignment(current, place, op.into(), expr_id.into());
+                let r = match self.body.debug_assertions_cfgs.get(&expr_id) {
+                    Some(cfg) => Rvalue::CheckCfg(cfg.clone()),
+                    None => self.lower_literal_to_operand(self.expr_ty(expr_id), l)?.into(),
+                };
+                self.push_assignment(current, place, r, expr_id.into());
                 Ok(Some(current))
             }
             Expr::Underscore => not_supported!("underscore"),
+            Expr::OffsetOf(offset_of) => {
+                let ty = self.expr_ty(expr_id);
+                let size = layout_of_ty(self.db, &ty, self.owner.module(self.db.upcast()).krate())?
+                    .size
+                    .bytes_usize();
+                let offset = self.lower_offset_of(offset_of, expr_id)?;
+                let bytes = offset.to_le_bytes()[0..size].into();
+                self.write_bytes_to_place(current, place, bytes, ty, expr_id.into())?;
+                Ok(Some(current))
+            }
+        }
+    }
+
+    /// Computes the offset of the field in `builtin # offset_of(Container, fields)` from the
+    /// layouts of the container and of the types of the intermediate fields.
+    fn lower_offset_of(&self, offset_of: &OffsetOf, expr_id: ExprId) -> Result<u64> {
+        let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr_id);
+        let krate = self.owner.module(self.db.upcast()).krate();
+        let mut ty =
+            crate::lower::TyLoweringContext::new(self.db, &resolver).lower_ty(&offset_of.container);
+        let mut offset = 0;
+        for field in offset_of.fields.iter() {
+            let layout = layout_of_ty(self.db, &ty, krate)?;
+            let (idx, field_ty) = match ty.kind(Interner) {
+                TyKind::Tuple(_, subst) => {
+                    let idx = field
+                        .as_tuple_index()
+                        .ok_or(MirLowerError::TypeError("named field on tuple"))?;
+                    let field_ty = subst
+                        .as_slice(Interner)
+                        .get(idx)
+                        .and_then(|it| it.ty(Interner))
+                        .ok_or(MirLowerError::UnresolvedField)?;
+                    (idx, field_ty.clone())
+                }
+                TyKind::Adt(chalk_ir::AdtId(adt), subst) => {
+                    let variant: VariantId = match *adt {
+                        AdtId::StructId(it) => it.into(),
+                        AdtId::UnionId(it) => it.into(),
+                        AdtId::EnumId(_) => not_supported!("offset_of on enums"),
+                    };
+                    let local_id = variant
+                        .variant_data(self.db.upcast())
+                        .field(field)
+                        .ok_or(MirLowerError::UnresolvedField)?;
+                    let field_ty =
+                        self.db.field_types(variant)[local_id].clone().substitute(Interner, subst);
+                    (u32::from(local_id.into_raw()) as usize, field_ty)
+                }
+                _ => return Err(MirLowerError::TypeError("offset_of on a type without fields")),
+            };
+            offset += layout.fields.offset(idx).bytes();
+            ty = field_ty;
         }
+        Ok(offset)
     }
 
     fn push_field_projection(&self, place: &mut Place, expr_id: ExprId) -> Result<()> {
@@ -1042,8 +1172,16 @@ impl<'ctx> MirLowerCtx<'ctx> {
         place: Place,
         subst: Substitution,
         span: MirSpan,
+        ty: Ty,
     ) -> Result<()> {
-        let c = self.db.const_eval(const_id, subst)?;
+        let c = if subst.len(Interner) != 0 {
+            // The substitution may still contain generic parameters of this body, so associated
+            // constants of traits are resolved to their impl and evaluated in the evaluator, when
+            // the parameters are known.
+            intern_const_scalar(ConstScalar::UnevaluatedConst(const_id, subst), ty)
+        } else {
+            self.db.const_eval(const_id, subst)?
+        };
         self.write_const_to_place(c, prev_block, place, span)
     }
 
@@ -1058,6 +1196,43 @@ impl<'ctx> MirLowerCtx<'ctx> {
         Ok(())
     }
 
+    /// Lowers `lhs && rhs` or `lhs || rhs`, evaluating `rhs` only when `lhs` doesn't decide the
+    /// result. The bindings of `let` expressions in `lhs` are then initialized when `rhs` runs, as
+    /// in the let-chains of `if` and `while` conditions.
+    fn lower_logic_op(
+        &mut self,
+        op: hir_def::hir::LogicOp,
+        lhs: ExprId,
+        rhs: ExprId,
+        place: Place,
+        current: BasicBlockId,
+        expr_id: ExprId,
+    ) -> Result<Option<BasicBlockId>> {
+        let Some((discr, current)) = self.lower_expr_to_some_operand(lhs, current)? else {
+            return Ok(None);
+        };
+        let start_of_rhs = self.new_basic_block();
+        let short_circuit = self.new_basic_block();
+        let (short_circuit_value, targets) = match op {
+            hir_def::hir::LogicOp::And => {
+                (0, SwitchTargets::static_if(1, start_of_rhs, short_circuit))
+            }
+            hir_def::hir::LogicOp::Or => {
+                (1, SwitchTargets::static_if(1, short_circuit, start_of_rhs))
+            }
+        };
+        self.set_terminator(current, Terminator::SwitchInt { discr, targets });
+        self.write_bytes_to_place(
+            short_circuit,
+            place.clone(),
+            vec![short_circuit_value],
+            TyBuilder::bool(),
+            expr_id.into(),
+        )?;
+        let end_of_rhs = self.lower_expr_to_place(rhs, place, start_of_rhs)?;
+        Ok(self.merge_blocks(Some(short_circuit), end_of_rhs))
+    }
+
     fn write_bytes_to_place(
         &mut self,
         prev_block: BasicBlockId,
@@ -1311,6 +1486,54 @@ impl<'ctx> MirLowerCtx<'ctx> {
         self.db.lang_item(crate_id, item).ok_or(MirLowerError::LangItemNotFound(item))
     }
 
+    /// Pushes the captures of a closure, generator or async block and returns them as the operands
+    /// of its aggregate.
+    fn lower_captures(&mut self, id: ClosureId, current: BasicBlockId) -> Result<Vec<Operand>> {
+        self.result.closures.push(id);
+        let (captures, _) = self.infer.closure_info(&id);
+        let mut operands = vec![];
+        for capture in captures.iter() {
+            let p = Place {
+                local: self.binding_local(capture.place.local)?,
+                projection: capture
+                    .place
+                    .projections
+                    .clone()
+                    .into_iter()
+                    .map(|x| match x {
+                        ProjectionElem::Deref => ProjectionElem::Deref,
+                        ProjectionElem::Field(x) => ProjectionElem::Field(x),
+                        ProjectionElem::TupleOrClosureField(x) => {
+                            ProjectionElem::TupleOrClosureField(x)
+                        }
+                        ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                            ProjectionElem::ConstantIndex { offset, min_length, from_end }
+                        }
+                        ProjectionElem::Subslice { from, to, from_end } => {
+                            ProjectionElem::Subslice { from, to, from_end }
+                        }
+                        ProjectionElem::OpaqueCast(x) => ProjectionElem::OpaqueCast(x),
+                        ProjectionElem::Index(x) => match x {},
+                    })
+                    .collect(),
+            };
+            match &capture.kind {
+                CaptureKind::ByRef(bk) => {
+                    let tmp: Place = self.temp(capture.ty.clone())?.into();
+                    self.push_assignment(
+                        current,
+                        tmp.clone(),
+                        Rvalue::Ref(bk.clone(), p),
+                        capture.span,
+                    );
+                    operands.push(Operand::Move(tmp));
+                }
+                CaptureKind::ByValue => operands.push(Operand::Move(p)),
+            }
+        }
+        Ok(operands)
+    }
+
     fn lower_block_to_place(
         &mut self,
         statements: &[hir_def::hir::Statement],
@@ -1381,6 +1604,9 @@ impl<'ctx> MirLowerCtx<'ctx> {
         params: impl Iterator<Item = (PatId, Ty)> + Clone,
         pick_binding: impl Fn(BindingId) -> bool,
     ) -> Result<BasicBlockId> {
+        for (_, ty) in params.clone() {
+            check_not_extern_type(&ty)?;
+        }
         let base_param_count = self.result.param_locals.len();
         self.result.param_locals.extend(params.clone().map(|(x, ty)| {
             let local_id = self.result.locals.alloc(Local { ty });
@@ -1400,6 +1626,7 @@ impl<'ctx> MirLowerCtx<'ctx> {
                 continue;
             }
             if !self.result.binding_locals.contains_idx(id) {
+                check_not_extern_type(&self.infer[id])?;
                 self.result
                     .binding_locals
                     .insert(id, self.result.locals.alloc(Local { ty: self.infer[id].clone() }));
@@ -1430,6 +1657,17 @@ impl<'ctx> MirLowerCtx<'ctx> {
         Ok(current)
     }
 
+    /// Allocates locals for the bindings of an expression which is lowered in place, but owns its
+    /// bindings, like a const block.
+    fn alloc_locals_of_binding_owner(&mut self, owner: ExprId) {
+        for (id, binding) in self.body.bindings.iter() {
+            if binding.owner == Some(owner) && !self.result.binding_locals.contains_idx(id) {
+                let local = self.result.locals.alloc(Local { ty: self.infer[id].clone() });
+                self.result.binding_locals.insert(id, local);
+            }
+        }
+    }
+
     fn binding_local(&self, b: BindingId) -> Result<LocalId> {
         match self.result.binding_locals.get(b) {
             Some(x) => Ok(*x),
@@ -1438,6 +1676,14 @@ impl<'ctx> MirLowerCtx<'ctx> {
     }
 }
 
+/// Extern types have no size, so they can't be stored in locals, only pointed to.
+fn check_not_extern_type(ty: &Ty) -> Result<()> {
+    match ty.kind(Interner) {
+        TyKind::Foreign(_) => Err(MirLowerError::ExternTypeByValue(ty.clone())),
+        _ => Ok(()),
+    }
+}
+
 fn cast_kind(source_ty: &Ty, target_ty: &Ty) -> Result<CastKind> {
     Ok(match (source_ty.kind(Interner), target_ty.kind(Interner)) {
         (TyKind::Scalar(s), TyKind::Scalar(t)) => match (s, t) {
@@ -1448,9 +1694,18 @@ fn cast_kind(source_ty: &Ty, target_ty: &Ty) -> Result<CastKind> {
         },
         (TyKind::Scalar(_), TyKind::Raw(..)) => CastKind::PointerFromExposedAddress,
         (TyKind::Raw(..), TyKind::Scalar(_)) => CastKind::PointerExposeAddress,
-        (TyKind::Raw(..) | TyKind::Ref(..), TyKind::Raw(..) | TyKind::Ref(..)) => {
-            CastKind::PtrToPtr
-        }
+        (
+            TyKind::Raw(_, source_pointee) | TyKind::Ref(_, _, source_pointee),
+            TyKind::Raw(_, target_pointee) | TyKind::Ref(_, _, target_pointee),
+        ) => match (source_pointee.kind(Interner), target_pointee.kind(Interner)) {
+            // Unsizing casts, like `&S as &dyn Trait`, `&dyn Sub as &dyn Super` or
+            // `&[T; N] as &[T]`.
+            (_, TyKind::Dyn(_)) if source_pointee != target_pointee => {
+                CastKind::Pointer(PointerCast::Unsize)
+            }
+            (TyKind::Array(..), TyKind::Slice(_)) => CastKind::Pointer(PointerCast::Unsize),
+            _ => CastKind::PtrToPtr,
+        },
         // Enum to int casts
         (TyKind::Scalar(_), TyKind::Adt(..)) | (TyKind::Adt(..), TyKind::Scalar(_)) => {
             CastKind::IntToInt
@@ -1463,29 +1718,61 @@ pub fn mir_body_for_closure_query(
     db: &dyn HirDatabase,
     closure: ClosureId,
 ) -> Result<Arc<MirBody>> {
+    let _span = tracing::info_span!("mir_body_for_closure_query", ?closure).entered();
     let (owner, expr) = db.lookup_intern_closure(closure.into());
     let body = db.body(owner);
     let infer = db.infer(owner);
-    let Expr::Closure { args, body: root, .. } = &body[expr] else {
-        implementation_error!("closure expression is not closure");
-    };
-    let TyKind::Closure(_, substs) = &infer[expr].kind(Interner) else {
-        implementation_error!("closure expression is not closure");
-    };
     let (captures, _) = infer.closure_info(&closure);
     let mut ctx = MirLowerCtx::new(db, owner, &body, &infer);
-    ctx.result.arg_count = args.len() + 1;
-    // 0 is return local
-    ctx.result.locals.alloc(Local { ty: infer[*root].clone() });
-    ctx.result.locals.alloc(Local { ty: infer[expr].clone() });
-    let Some(sig) = substs.at(Interner, 0).assert_ty_ref(Interner).callable_sig(db) else {
-        implementation_error!("closure has not callable sig");
+    let is_generator;
+    let end = match &body[expr] {
+        Expr::Closure { args, body: root, .. } => {
+            let param_tys = match infer[expr].kind(Interner) {
+                TyKind::Closure(_, substs) => {
+                    let Some(sig) = substs.at(Interner, 0).assert_ty_ref(Interner).callable_sig(db)
+                    else {
+                        implementation_error!("closure has not callable sig");
+                    };
+                    is_generator = false;
+                    sig.params().to_vec()
+                }
+                TyKind::Generator(_, substs) => {
+                    // The generator substitution ends with the resume, yield and return types.
+                    let substs = substs.as_slice(Interner);
+                    let Some(resume_ty) = substs.len().checked_sub(3).map(|i| &substs[i]) else {
+                        implementation_error!("generator without resume type");
+                    };
+                    is_generator = true;
+                    vec![resume_ty.assert_ty_ref(Interner).clone()]
+                }
+                _ => implementation_error!("closure expression is not closure"),
+            };
+            ctx.result.arg_count = args.len() + 1;
+            // 0 is return local
+            ctx.result.locals.alloc(Local { ty: infer[*root].clone() });
+            ctx.result.locals.alloc(Local { ty: infer[expr].clone() });
+            let current = ctx.lower_params_and_bindings(
+                args.iter().zip(param_tys).map(|(x, y)| (*x, y)),
+                |_| true,
+            )?;
+            ctx.lower_expr_to_place(*root, return_slot().into(), current)?
+        }
+        Expr::Async { statements, tail, .. } => {
+            let TyKind::OpaqueType(_, substs) = infer[expr].kind(Interner) else {
+                implementation_error!("async block type is not opaque");
+            };
+            is_generator = true;
+            ctx.result.arg_count = 1;
+            // 0 is return local, and it holds the output of the future
+            let output_ty = substs.at(Interner, 0).assert_ty_ref(Interner).clone();
+            ctx.result.locals.alloc(Local { ty: output_ty });
+            ctx.result.locals.alloc(Local { ty: infer[expr].clone() });
+            let current = ctx.lower_params_and_bindings([].into_iter(), |_| true)?;
+            ctx.lower_block_to_place(statements, current, *tail, return_slot().into())?
+        }
+        _ => implementation_error!("closure expression is not closure"),
     };
-    let current = ctx.lower_params_and_bindings(
-        args.iter().zip(sig.params().iter()).map(|(x, y)| (*x, y.clone())),
-        |_| true,
-    )?;
-    if let Some(b) = ctx.lower_expr_to_place(*root, return_slot().into(), current)? {
+    if let Some(b) = end {
         ctx.set_terminator(b, Terminator::Return);
     }
     let mut upvar_map: FxHashMap<LocalId, Vec<(&CapturedItem, usize)>> = FxHashMap::default();
@@ -1537,6 +1824,9 @@ pub fn mir_body_for_closure_query(
     if let Some(err) = err {
         return Err(MirLowerError::UnresolvedUpvar(err));
     }
+    if is_generator {
+        ctx.result.generator_layout = Some(ctx.result.compute_generator_layout());
+    }
     Ok(Arc::new(ctx.result))
 }
 
@@ -1551,6 +1841,7 @@ pub fn mir_body_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Result<Arc<Mi
             db.enum_data(it.parent).variants[it.local_id].name.to_string()
         }
     });
+    let _span = tracing::info_span!("mir_body_query", ?def).entered();
     let body = db.body(def);
     let infer = db.infer(def);
     let result = lower_to_mir(db, def, &body, &infer, body.body_expr)?;
@@ -1579,6 +1870,7 @@ pub fn lower_to_mir(
     }
     let mut ctx = MirLowerCtx::new(db, owner, body, infer);
     // 0 is return local
+    check_not_extern_type(&infer[root_expr])?;
     ctx.result.locals.alloc(Local { ty: infer[root_expr].clone() });
     let binding_picker = |b: BindingId| {
         if root_expr == body.body_expr {
@@ -1,5 +1,7 @@
 //! MIR lowering for patterns
 
+use hir_def::hir::RangeOp;
+
 use crate::utils::pattern_matching_dereference_count;
 
 use super::*;
@@ -104,8 +106,44 @@ impl MirLowerCtx<'_> {
                     AdtPatternShape::Record { args: &*args },
                 )?
             }
-            Pat::Range { .. } => not_supported!("range pattern"),
-            Pat::Slice { .. } => not_supported!("slice pattern"),
+            Pat::Range { start, end, range_type } => {
+                pattern_matching_dereference(&mut cond_ty, &mut binding_mode, &mut cond_place);
+                // The pattern matches if `start <= value` and `value <= end`, or `value < end` for
+                // exclusive ranges.
+                let end_op = match range_type {
+                    RangeOp::Inclusive => BinOp::Ge,
+                    RangeOp::Exclusive => BinOp::Gt,
+                };
+                let bounds = start.iter().map(|&it| (it, BinOp::Le));
+                for (bound, op) in bounds.chain(end.iter().map(|&it| (it, end_op))) {
+                    let Some((c, next)) = self.lower_expr_to_some_operand(bound, current)? else {
+                        return Err(MirLowerError::IncompleteExpr);
+                    };
+                    (current, current_else) = self.pattern_match_compare(
+                        current_else,
+                        next,
+                        op,
+                        c,
+                        cond_place.clone(),
+                        pattern,
+                    )?;
+                }
+                (current, current_else)
+            }
+            Pat::Slice { prefix, slice, suffix } => {
+                pattern_matching_dereference(&mut cond_ty, &mut binding_mode, &mut cond_place);
+                self.pattern_match_slice(
+                    current,
+                    current_else,
+                    cond_place,
+                    cond_ty,
+                    prefix,
+                    *slice,
+                    suffix,
+                    binding_mode,
+                    pattern.into(),
+                )?
+            }
             Pat::Path(_) => {
                 let Some(variant) = self.infer.variant_resolution_for_pat(pattern) else {
                     not_supported!("unresolved variant");
@@ -131,6 +169,8 @@ impl MirLowerCtx<'_> {
             Pat::Bind { id, subpat } => {
                 let target_place = self.result.binding_locals[*id];
                 let mode = self.body.bindings[*id].mode;
+                // `rest @ ..` in slice patterns has a missing subpattern.
+                let subpat = subpat.filter(|it| !matches!(self.body.pats[*it], Pat::Missing));
                 if let Some(subpat) = subpat {
                     (current, current_else) = self.pattern_match(
                         current,
@@ -194,7 +234,13 @@ impl MirLowerCtx<'_> {
                 }
             }
             Pat::Box { .. } => not_supported!("box pattern"),
-            Pat::ConstBlock(_) => not_supported!("const block pattern"),
+            Pat::ConstBlock(expr) => {
+                self.alloc_locals_of_binding_owner(*expr);
+                let Some((c, next)) = self.lower_expr_to_some_operand(*expr, current)? else {
+                    return Err(MirLowerError::IncompleteExpr);
+                };
+                self.pattern_match_const(current_else, next, c, cond_place, pattern)?
+            }
         })
     }
 
@@ -205,6 +251,19 @@ impl MirLowerCtx<'_> {
         c: Operand,
         cond_place: Place,
         pattern: Idx<Pat>,
+    ) -> Result<(BasicBlockId, Option<BasicBlockId>)> {
+        self.pattern_match_compare(current_else, current, BinOp::Eq, c, cond_place, pattern)
+    }
+
+    /// Continues with the matched path if `c <op> cond_place` holds.
+    fn pattern_match_compare(
+        &mut self,
+        current_else: Option<BasicBlockId>,
+        current: BasicBlockId,
+        op: BinOp,
+        c: Operand,
+        cond_place: Place,
+        pattern: Idx<Pat>,
     ) -> Result<(BasicBlockId, Option<BasicBlockId>)> {
         let then_target = self.new_basic_block();
         let else_target = current_else.unwrap_or_else(|| self.new_basic_block());
@@ -212,7 +271,7 @@ impl MirLowerCtx<'_> {
         self.push_assignment(
             current,
             discr.clone(),
-            Rvalue::CheckedBinaryOp(BinOp::Eq, c, Operand::Copy(cond_place)),
+            Rvalue::CheckedBinaryOp(op, c, Operand::Copy(cond_place)),
             pattern.into(),
         );
         let discr = Operand::Copy(discr);
@@ -226,6 +285,117 @@ impl MirLowerCtx<'_> {
         Ok((then_target, Some(else_target)))
     }
 
+    fn pattern_match_slice(
+        &mut self,
+        mut current: BasicBlockId,
+        mut current_else: Option<BasicBlockId>,
+        cond_place: Place,
+        cond_ty: Ty,
+        prefix: &[PatId],
+        slice: Option<PatId>,
+        suffix: &[PatId],
+        binding_mode: BindingAnnotation,
+        span: MirSpan,
+    ) -> Result<(BasicBlockId, Option<BasicBlockId>)> {
+        let min_length = (prefix.len() + suffix.len()) as u64;
+        let (elem_ty, array_len) = match cond_ty.kind(Interner) {
+            TyKind::Array(elem_ty, len) => {
+                (elem_ty.clone(), try_const_usize(len).map(|x| x as u64))
+            }
+            TyKind::Slice(elem_ty) => {
+                // The length of arrays is checked by the type system, but slices need a runtime check.
+                let len: Place = self.temp(TyBuilder::usize())?.into();
+                self.push_assignment(current, len.clone(), Rvalue::Len(cond_place.clone()), span);
+                let min_length_op = self.lower_literal_to_operand(
+                    TyBuilder::usize(),
+                    &Literal::Uint(min_length.into(), None),
+                )?;
+                let op = if slice.is_some() { BinOp::Ge } else { BinOp::Eq };
+                let discr: Place = self.temp(TyBuilder::bool())?.into();
+                self.push_assignment(
+                    current,
+                    discr.clone(),
+                    Rvalue::CheckedBinaryOp(op, Operand::Copy(len), min_length_op),
+                    span,
+                );
+                let then_target = self.new_basic_block();
+                let else_target = current_else.unwrap_or_else(|| self.new_basic_block());
+                self.set_terminator(
+                    current,
+                    Terminator::SwitchInt {
+                        discr: Operand::Copy(discr),
+                        targets: SwitchTargets::static_if(1, then_target, else_target),
+                    },
+                );
+                current = then_target;
+                current_else = Some(else_target);
+                (elem_ty.clone(), None)
+            }
+            _ => {
+                return Err(MirLowerError::TypeError(
+                    "non array or slice type matched with slice pattern",
+                ))
+            }
+        };
+
+        let suffix_offset = |i: usize| {
+            let from_end = (suffix.len() - i) as u64;
+            match array_len {
+                Some(len) => ProjectionElem::ConstantIndex {
+                    offset: len - from_end,
+                    min_length,
+                    from_end: false,
+                },
+                None => {
+                    ProjectionElem::ConstantIndex { offset: from_end, min_length, from_end: true }
+                }
+            }
+        };
+        let elements = prefix
+            .iter()
+            .enumerate()
+            .map(|(i, &pat)| {
+                (
+                    ProjectionElem::ConstantIndex { offset: i as u64, min_length, from_end: false },
+                    pat,
+                    elem_ty.clone(),
+                )
+            })
+            .chain(
+                suffix.iter().enumerate().map(|(i, &pat)| (suffix_offset(i), pat, elem_ty.clone())),
+            )
+            .collect::<Vec<_>>();
+        (current, current_else) = self.pattern_match_adt(
+            current,
+            current_else,
+            elements.into_iter(),
+            &cond_place,
+            binding_mode,
+        )?;
+
+        if let Some(slice) = slice {
+            if !matches!(self.body.pats[slice], Pat::Missing) {
+                let (from, to) = (prefix.len() as u64, suffix.len() as u64);
+                let subslice = match array_len {
+                    Some(len) => ProjectionElem::Subslice { from, to: len - to, from_end: false },
+                    None => ProjectionElem::Subslice { from, to, from_end: true },
+                };
+                let mut slice_place = cond_place.clone();
+                slice_place.projection.push(subslice);
+                let slice_ty = self.infer[slice].clone();
+                (current, current_else) = self.pattern_match(
+                    current,
+                    current_else,
+                    slice_place,
+                    slice_ty,
+                    slice,
+                    binding_mode,
+                )?;
+            }
+        }
+        Ok((current, current_else))
+    }
+
     pub(super) fn pattern_matching_variant(
         &mut self,
         mut cond_ty: Ty,
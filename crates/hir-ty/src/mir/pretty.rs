@@ -27,6 +27,22 @@ impl MirBody {
         ctx.result
     }
 
+    /// Renders `place = rvalue` with the local names of this body, for execution traces.
+    pub(crate) fn pretty_print_assignment(
+        &self,
+        db: &dyn HirDatabase,
+        place: &Place,
+        rvalue: &Rvalue,
+    ) -> String {
+        let hir_body = db.body(self.owner);
+        let mut ctx = MirPrettyCtx::new(self, &hir_body, db);
+        ctx.place(place);
+        ctx.write(" = ");
+        ctx.rvalue(rvalue);
+        ctx.write(";");
+        ctx.result
+    }
+
     // String with lines is rendered poorly in `dbg` macros, which I use very much, so this
     // function exists to solve that.
     pub fn dbg(&self, db: &dyn HirDatabase) -> impl Debug {
@@ -233,6 +249,18 @@ impl<'a> MirPrettyCtx<'a> {
                                 wln!(this, ",");
                             });
                         }
+                        Terminator::Await { future, destination, target } => {
+                            w!(this, "Await ");
+                            this.with_block(|this| {
+                                w!(this, "future: ");
+                                this.operand(future);
+                                wln!(this, ",");
+                                w!(this, "destination: ");
+                                this.place(destination);
+                                wln!(this, ",");
+                                wln!(this, "target: {},", this.basic_block_id(*target));
+                            });
+                        }
                         _ => wln!(this, "{:?};", terminator),
                     },
                     None => wln!(this, "<no-terminator>;"),
@@ -279,6 +307,16 @@ impl<'a> MirPrettyCtx<'a> {
                     f(this, local, head);
                     w!(this, "[{}]", this.local_name(*l));
                 }
+                ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                    f(this, local, head);
+                    let sign = if *from_end { "-" } else { "" };
+                    w!(this, "[{sign}{offset} of {min_length}]");
+                }
+                ProjectionElem::Subslice { from, to, from_end } => {
+                    f(this, local, head);
+                    let sign = if *from_end { "-" } else { "" };
+                    w!(this, "[{from}:{sign}{to}]");
+                }
                 x => {
                     f(this, local, head);
                     w!(this, ".{:?}", x);
@@ -311,6 +349,11 @@ impl<'a> MirPrettyCtx<'a> {
                 }
                 self.place(p);
             }
+            Rvalue::Repeat(op, len) => {
+                w!(self, "[");
+                self.operand(op);
+                w!(self, "; {}]", len.display(self.db));
+            }
             Rvalue::Aggregate(AggregateKind::Tuple(_), x) => {
                 w!(self, "(");
                 self.operand_list(x);
@@ -331,6 +374,11 @@ impl<'a> MirPrettyCtx<'a> {
                 self.operand_list(x);
                 w!(self, ")");
             }
+            Rvalue::Aggregate(AggregateKind::Generator(_), x) => {
+                w!(self, "Generator(");
+                self.operand_list(x);
+                w!(self, ")");
+            }
             Rvalue::Aggregate(AggregateKind::Union(_, _), x) => {
                 w!(self, "Union(");
                 self.operand_list(x);
@@ -359,6 +407,9 @@ impl<'a> MirPrettyCtx<'a> {
                 w!(self, "{u} ");
                 self.operand(o);
             }
+            Rvalue::CheckCfg(cfg) => {
+                w!(self, "CheckCfg({cfg:?})");
+            }
             Rvalue::Discriminant(p) => {
                 w!(self, "Discriminant(");
                 self.place(p);
@@ -0,0 +1,504 @@
+//! Region inference over MIR: collects the outlives constraints between the regions of the
+//! references of a body, and solves them into the relations the body requires between the
+//! lifetimes of its signature, and between its borrows and these lifetimes. This is the ground
+//! for reporting dangling borrows and lifetimes which are too short.
+//!
+//! Types are lifetime-erased after inference, so the regions of a local are the ones of the
+//! references in its type, in the order of a walk of the type, and the lifetimes of the signature
+//! are recovered from its type references. The solver is location insensitive: a region contains
+//! every point of the body, which is enough for the facts involving the signature. The regions in
+//! the lifetime arguments of ADTs, like the one of `Foo<'a>`, are not tracked.
+
+use std::sync::Arc;
+
+use base_db::CrateId;
+use chalk_ir::Mutability;
+use hir_def::{DefWithBodyId, HasModule};
+use hir_expand::name::Name;
+use la_arena::{Arena, ArenaMap, Idx};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    db::HirDatabase,
+    lifetime_elision::{signature_lifetimes, SignatureLifetime},
+    Interner, Ty, TyKind,
+};
+
+use super::{
+    borrowck::all_mir_bodies, return_slot, AggregateKind, BorrowKind, LocalId, MirBody,
+    MirLowerError, MirSpan, Operand, Place, ProjectionElem, Rvalue, StatementKind, Terminator,
+};
+
+pub type RegionId = Idx<RegionData>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionData {
+    Static,
+    /// A lifetime parameter used in the signature, like `'a`.
+    Named(Name),
+    /// The `n`th elided lifetime of the signature.
+    Elided(usize),
+    /// The region of a reference in the type of a local.
+    Local(LocalId),
+    /// The region of a borrow, whose index in [`RegionInferenceResult::loans`] is given.
+    Loan(usize),
+    /// A region standing for regions the solver can't follow, like the ones in the fields of ADTs.
+    Existential,
+}
+
+impl RegionData {
+    /// Returns whether the region comes from the signature, so that it outlives the body.
+    pub fn is_universal(&self) -> bool {
+        matches!(self, RegionData::Static | RegionData::Named(_) | RegionData::Elided(_))
+    }
+}
+
+/// A borrow in a body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loan {
+    pub kind: BorrowKind,
+    pub place: Place,
+    pub span: MirSpan,
+    pub region: RegionId,
+    /// The universal regions the borrow has to outlive. The borrowed place dangles when it is a
+    /// local of the body and this isn't empty.
+    pub outlives: Vec<RegionId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionInferenceResult {
+    pub mir_body: Arc<MirBody>,
+    pub regions: Arena<RegionData>,
+    /// The regions of the references in the type of each local, in the order of a walk of the type.
+    pub local_regions: ArenaMap<LocalId, Vec<RegionId>>,
+    /// The constraints collected from the body, `(a, b)` meaning `'a: 'b`.
+    pub constraints: Vec<(RegionId, RegionId)>,
+    pub loans: Vec<Loan>,
+    /// The relations between distinct universal regions the body requires, `(a, b)` meaning
+    /// `'a: 'b`. The ones `'static` satisfies by itself are left out.
+    pub universal_outlives: Vec<(RegionId, RegionId)>,
+}
+
+pub fn region_inference_query(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+) -> Result<Arc<[RegionInferenceResult]>, MirLowerError> {
+    let _p = profile::span("region_inference_query");
+    let r = all_mir_bodies(db, def)
+        .enumerate()
+        // Only the first body is the one of `def`, the other ones are closures which don't have
+        // a signature to take universal regions from.
+        .map(|(idx, body)| Ok(infer_regions(db, body?, idx == 0)))
+        .collect::<Result<Vec<_>, MirLowerError>>()?;
+    Ok(r.into())
+}
+
+fn infer_regions(
+    db: &dyn HirDatabase,
+    body: Arc<MirBody>,
+    has_signature: bool,
+) -> RegionInferenceResult {
+    let mut regions = Arena::new();
+    let static_region = regions.alloc(RegionData::Static);
+    let mut ctx = RegionCtx {
+        db,
+        krate: body.owner.module(db.upcast()).krate(),
+        body: &body,
+        regions,
+        static_region,
+        local_regions: ArenaMap::default(),
+        constraints: Vec::new(),
+        loans: Vec::new(),
+    };
+    for (local, data) in body.locals.iter() {
+        let regions =
+            (0..region_count(&data.ty)).map(|_| ctx.regions.alloc(RegionData::Local(local)));
+        let regions = regions.collect();
+        ctx.local_regions.insert(local, regions);
+    }
+    if has_signature {
+        ctx.add_universal_regions();
+    }
+    ctx.add_body_constraints();
+    let universal_outlives = ctx.solve();
+    RegionInferenceResult {
+        regions: ctx.regions,
+        local_regions: ctx.local_regions,
+        constraints: ctx.constraints,
+        loans: ctx.loans,
+        universal_outlives,
+        mir_body: body,
+    }
+}
+
+struct RegionCtx<'a> {
+    db: &'a dyn HirDatabase,
+    krate: CrateId,
+    body: &'a MirBody,
+    regions: Arena<RegionData>,
+    static_region: RegionId,
+    local_regions: ArenaMap<LocalId, Vec<RegionId>>,
+    constraints: Vec<(RegionId, RegionId)>,
+    loans: Vec<Loan>,
+}
+
+/// The regions of a place, and the ones of the references it goes through.
+struct PlaceRegions {
+    ty: Ty,
+    regions: Vec<RegionId>,
+    derefs: Vec<RegionId>,
+}
+
+impl RegionCtx<'_> {
+    fn outlives(&mut self, a: RegionId, b: RegionId) {
+        if a != b {
+            self.constraints.push((a, b));
+        }
+    }
+
+    fn equate(&mut self, a: RegionId, b: RegionId) {
+        self.outlives(a, b);
+        self.outlives(b, a);
+    }
+
+    /// Adds the constraints of a value whose regions are `from` flowing into a place of type `ty`
+    /// whose regions are `to`.
+    fn relate(&mut self, from: &[RegionId], to: &[RegionId], ty: &Ty, invariant: bool) {
+        if from.len() != to.len() {
+            // The types differ, like in casts, so any region may flow into any other.
+            self.flow_all(from, to);
+            return;
+        }
+        let mut variances = Vec::new();
+        region_variances(ty, invariant, &mut variances);
+        for (idx, (&a, &b)) in from.iter().zip(to).enumerate() {
+            self.outlives(a, b);
+            if variances.get(idx).copied().unwrap_or(true) {
+                self.outlives(b, a);
+            }
+        }
+    }
+
+    fn flow_all(&mut self, from: &[RegionId], to: &[RegionId]) {
+        for &a in from {
+            for &b in to {
+                self.outlives(a, b);
+            }
+        }
+    }
+
+    fn fresh_regions(&mut self, ty: &Ty) -> Vec<RegionId> {
+        (0..region_count(ty)).map(|_| self.regions.alloc(RegionData::Existential)).collect()
+    }
+
+    fn place_regions(&mut self, place: &Place) -> PlaceRegions {
+        let mut ty = self.body.locals[place.local].ty.clone();
+        let mut regions = self.local_regions[place.local].clone();
+        let mut derefs = Vec::new();
+        for proj in &place.projection {
+            let base_ty = ty.clone();
+            ty = proj.projected_ty(
+                ty,
+                self.db,
+                |c, f| {
+                    let (def, _) = self.db.lookup_intern_closure(c.into());
+                    let infer = self.db.infer(def);
+                    let (captures, _) = infer.closure_info(&c);
+                    captures.get(f).expect("broken closure field").ty.clone()
+                },
+                self.krate,
+            );
+            let projected = match (proj, base_ty.kind(Interner)) {
+                (ProjectionElem::Deref, TyKind::Ref(..)) => {
+                    regions.split_first().map(|(&reference, pointee)| {
+                        derefs.push(reference);
+                        pointee.to_vec()
+                    })
+                }
+                (ProjectionElem::TupleOrClosureField(f), TyKind::Tuple(_, subst)) => {
+                    let start: usize = subst
+                        .iter(Interner)
+                        .take(*f)
+                        .filter_map(|it| it.ty(Interner))
+                        .map(region_count)
+                        .sum();
+                    regions.get(start..start + region_count(&ty)).map(<[_]>::to_vec)
+                }
+                // Raw pointers and arrays have the regions of their pointee and elements.
+                (ProjectionElem::Deref, _)
+                | (
+                    ProjectionElem::Index(_)
+                    | ProjectionElem::ConstantIndex { .. }
+                    | ProjectionElem::Subslice { .. },
+                    _,
+                ) => Some(regions.clone()),
+                _ => None,
+            };
+            regions = match projected {
+                Some(it) => it,
+                None => {
+                    // The regions of the fields of ADTs and closures aren't the ones of their
+                    // type, so any of them may be any region of the base.
+                    let fresh = self.fresh_regions(&ty);
+                    for &a in &regions {
+                        for &b in &fresh {
+                            self.equate(a, b);
+                        }
+                    }
+                    fresh
+                }
+            };
+        }
+        PlaceRegions { ty, regions, derefs }
+    }
+
+    fn operand_regions(&mut self, operand: &Operand) -> Vec<RegionId> {
+        match operand {
+            Operand::Copy(p) | Operand::Move(p) => self.place_regions(p).regions,
+            // Constants only hold `'static` references, like string literals.
+            Operand::Constant(c) => vec![self.static_region; region_count(&c.data(Interner).ty)],
+        }
+    }
+
+    /// Ties the regions of the parameters and of the return place of the body to the lifetimes of
+    /// its signature.
+    fn add_universal_regions(&mut self) {
+        let func = match self.body.owner {
+            DefWithBodyId::FunctionId(it) => it,
+            DefWithBodyId::ConstId(_) | DefWithBodyId::StaticId(_) => {
+                for region in self.local_regions[return_slot()].clone() {
+                    self.equate(region, self.static_region);
+                }
+                return;
+            }
+            DefWithBodyId::VariantId(_) => return,
+        };
+        let signature = signature_lifetimes(self.db, func);
+        let body = self.body;
+        let mut universals =
+            UniversalRegions { next_elided: signature.elided_count, ..Default::default() };
+        for (lifetimes, &local) in signature.params.iter().zip(&body.param_locals) {
+            self.equate_with_signature(local, lifetimes, &mut universals);
+        }
+        // The return place of async functions holds the output of the future, which may borrow
+        // from the parameters without the lifetimes of the signature saying so.
+        if !self.db.function_data(func).has_async_kw() {
+            self.equate_with_signature(return_slot(), &signature.ret, &mut universals);
+        }
+    }
+
+    /// Equates the regions of `local` with the universal regions of the `lifetimes` of its type in
+    /// the signature.
+    fn equate_with_signature(
+        &mut self,
+        local: LocalId,
+        lifetimes: &[SignatureLifetime],
+        universals: &mut UniversalRegions,
+    ) {
+        let slots = self.local_regions[local].clone();
+        // When the type reference doesn't match the type, like with type aliases, its lifetimes
+        // can't be matched with the references of the type.
+        let matches_type = lifetimes.len() == slots.len();
+        for (idx, slot) in slots.into_iter().enumerate() {
+            let region = match matches_type.then(|| &lifetimes[idx]) {
+                Some(SignatureLifetime::Static) => self.static_region,
+                Some(SignatureLifetime::Named(name)) => universals.named(&mut self.regions, name),
+                Some(&SignatureLifetime::Elided(idx)) => universals.elided(&mut self.regions, idx),
+                None => universals.fresh_elided(&mut self.regions),
+            };
+            self.equate(slot, region);
+        }
+    }
+
+    fn add_body_constraints(&mut self) {
+        let body = self.body;
+        for (_, block) in body.basic_blocks.iter() {
+            for statement in &block.statements {
+                if let StatementKind::Assign(place, rvalue) = &statement.kind {
+                    self.add_assign_constraints(place, rvalue, statement.span);
+                }
+            }
+            match &block.terminator {
+                Some(Terminator::Call { func, args, destination, .. }) => {
+                    // Without the signature of the callee, its result may borrow from any of its
+                    // arguments.
+                    let destination = self.place_regions(destination).regions;
+                    for operand in args.iter().chain([func]) {
+                        let from = self.operand_regions(operand);
+                        self.flow_all(&from, &destination);
+                    }
+                }
+                Some(Terminator::Await { future, destination, .. }) => {
+                    let destination = self.place_regions(destination).regions;
+                    let from = self.operand_regions(future);
+                    self.flow_all(&from, &destination);
+                }
+                Some(Terminator::DropAndReplace { place, value, .. }) => {
+                    let dest = self.place_regions(place);
+                    let from = self.operand_regions(value);
+                    self.relate(&from, &dest.regions, &dest.ty, false);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn add_assign_constraints(&mut self, place: &Place, rvalue: &Rvalue, span: MirSpan) {
+        let dest = self.place_regions(place);
+        match rvalue {
+            Rvalue::Use(operand)
+            | Rvalue::Repeat(operand, _)
+            | Rvalue::Cast(_, operand, _)
+            | Rvalue::ShallowInitBox(operand, _) => {
+                let from = self.operand_regions(operand);
+                self.relate(&from, &dest.regions, &dest.ty, false);
+            }
+            Rvalue::CopyForDeref(p) => {
+                let from = self.place_regions(p).regions;
+                self.relate(&from, &dest.regions, &dest.ty, false);
+            }
+            Rvalue::Ref(kind, borrowed) => {
+                let borrowed_regions = self.place_regions(borrowed);
+                let region = self.regions.alloc(RegionData::Loan(self.loans.len()));
+                if let (Some((&reference, pointee)), TyKind::Ref(_, _, pointee_ty)) =
+                    (dest.regions.split_first(), dest.ty.kind(Interner))
+                {
+                    self.outlives(region, reference);
+                    let invariant = matches!(kind, BorrowKind::Mut { .. });
+                    self.relate(&borrowed_regions.regions, pointee, pointee_ty, invariant);
+                }
+                // A reborrow through a reference can't outlive it.
+                for deref in borrowed_regions.derefs {
+                    self.outlives(deref, region);
+                }
+                self.loans.push(Loan {
+                    kind: *kind,
+                    place: borrowed.clone(),
+                    span,
+                    region,
+                    outlives: Vec::new(),
+                });
+            }
+            Rvalue::Aggregate(kind, operands) => {
+                let mut from = Vec::new();
+                for operand in operands {
+                    from.push(self.operand_regions(operand));
+                }
+                match kind {
+                    AggregateKind::Tuple(_) => {
+                        let from = from.concat();
+                        self.relate(&from, &dest.regions, &dest.ty, false);
+                    }
+                    AggregateKind::Array(elem) => {
+                        for from in from {
+                            self.relate(&from, &dest.regions, elem, false);
+                        }
+                    }
+                    AggregateKind::Adt(..)
+                    | AggregateKind::Union(..)
+                    | AggregateKind::Closure(_)
+                    | AggregateKind::Generator(_) => self.flow_all(&from.concat(), &dest.regions),
+                }
+            }
+            Rvalue::Len(_)
+            | Rvalue::CheckCfg(_)
+            | Rvalue::CheckedBinaryOp(..)
+            | Rvalue::UnaryOp(..)
+            | Rvalue::Discriminant(_) => (),
+        }
+    }
+
+    /// Propagates the constraints, filling the regions each loan outlives, and returns the
+    /// relations between universal regions.
+    fn solve(&mut self) -> Vec<(RegionId, RegionId)> {
+        let index = |region: RegionId| u32::from(region.into_raw()) as usize;
+        let mut predecessors = vec![Vec::new(); self.regions.len()];
+        for &(a, b) in &self.constraints {
+            predecessors[index(b)].push(a);
+        }
+        let mut universal_outlives = Vec::new();
+        let universals: Vec<_> =
+            self.regions.iter().filter(|(_, it)| it.is_universal()).map(|(id, _)| id).collect();
+        for &universal in &universals {
+            // The regions outliving `universal` are the ones it is reachable from.
+            let mut seen = vec![false; self.regions.len()];
+            let mut stack = vec![universal];
+            seen[index(universal)] = true;
+            while let Some(region) = stack.pop() {
+                for &pred in &predecessors[index(region)] {
+                    if !seen[index(pred)] {
+                        seen[index(pred)] = true;
+                        stack.push(pred);
+                    }
+                }
+            }
+            for &other in &universals {
+                if other != universal && other != self.static_region && seen[index(other)] {
+                    universal_outlives.push((other, universal));
+                }
+            }
+            for loan in &mut self.loans {
+                if seen[index(loan.region)] {
+                    loan.outlives.push(universal);
+                }
+            }
+        }
+        universal_outlives
+    }
+}
+
+#[derive(Default)]
+struct UniversalRegions {
+    named: FxHashMap<Name, RegionId>,
+    elided: FxHashMap<usize, RegionId>,
+    /// The index of the next elided lifetime which isn't one of the signature.
+    next_elided: usize,
+}
+
+impl UniversalRegions {
+    fn named(&mut self, regions: &mut Arena<RegionData>, name: &Name) -> RegionId {
+        *self
+            .named
+            .entry(name.clone())
+            .or_insert_with(|| regions.alloc(RegionData::Named(name.clone())))
+    }
+
+    fn elided(&mut self, regions: &mut Arena<RegionData>, idx: usize) -> RegionId {
+        *self.elided.entry(idx).or_insert_with(|| regions.alloc(RegionData::Elided(idx)))
+    }
+
+    fn fresh_elided(&mut self, regions: &mut Arena<RegionData>) -> RegionId {
+        self.next_elided += 1;
+        self.elided(regions, self.next_elided - 1)
+    }
+}
+
+fn region_count(ty: &Ty) -> usize {
+    let mut variances = Vec::new();
+    region_variances(ty, false, &mut variances);
+    variances.len()
+}
+
+/// Pushes, for each reference in `ty` in the order of a walk of the type, whether its region is
+/// invariant, which it is behind mutable references and pointers.
+fn region_variances(ty: &Ty, invariant: bool, res: &mut Vec<bool>) {
+    match ty.kind(Interner) {
+        TyKind::Ref(mutability, _, inner) => {
+            res.push(invariant);
+            region_variances(inner, invariant || *mutability == Mutability::Mut, res);
+        }
+        TyKind::Raw(mutability, inner) => {
+            region_variances(inner, invariant || *mutability == Mutability::Mut, res)
+        }
+        TyKind::Array(inner, _) | TyKind::Slice(inner) => region_variances(inner, invariant, res),
+        TyKind::Tuple(_, subst) | TyKind::Adt(_, subst) => {
+            for ty in subst.iter(Interner).filter_map(|it| it.ty(Interner)) {
+                region_variances(ty, invariant, res);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+use base_db::fixture::WithFixture;
+use expect_test::{expect, Expect};
+use hir_def::{db::DefDatabase, ModuleDefId};
+
+use crate::{db::HirDatabase, mir::ProjectionElem, test_db::TestDB};
+
+use super::{RegionData, RegionId};
+
+/// Renders the outlives facts of the body of the `goal` function.
+fn check(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module_id = db.module_for_file(file_id);
+    let def_map = module_id.def_map(&db);
+    let func = def_map[module_id.local_id]
+        .scope
+        .declarations()
+        .find_map(|it| match it {
+            ModuleDefId::FunctionId(it) if db.function_data(it).name.to_smol_str() == "goal" => {
+                Some(it)
+            }
+            _ => None,
+        })
+        .expect("no `goal` function in the fixture");
+    let results = db.region_inference(func.into()).unwrap();
+    let result = &results[0];
+    let body = db.body(func.into());
+
+    let name = |region: RegionId| match &result.regions[region] {
+        RegionData::Static => "'static".to_owned(),
+        RegionData::Named(name) => name.to_smol_str().to_string(),
+        RegionData::Elided(idx) => format!("'_{idx}"),
+        it => format!("{it:?}"),
+    };
+    let mut actual = String::new();
+    for &(a, b) in &result.universal_outlives {
+        actual += &format!("{}: {}\n", name(a), name(b));
+    }
+    for loan in &result.loans {
+        if loan.outlives.is_empty() {
+            continue;
+        }
+        let derefs = loan.place.projection.iter().filter(|it| **it == ProjectionElem::Deref);
+        let local = result
+            .mir_body
+            .binding_locals
+            .iter()
+            .find(|(_, &local)| local == loan.place.local)
+            .map_or_else(
+                || "_".to_owned(),
+                |(it, _)| body.bindings[it].name.to_smol_str().to_string(),
+            );
+        let outlives: Vec<_> = loan.outlives.iter().map(|&it| name(it)).collect();
+        actual +=
+            &format!("borrow of {}{local}: {}\n", "*".repeat(derefs.count()), outlives.join(" + "));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn returned_parameter() {
+    check(
+        r#"
+fn goal<'a, 'b>(x: &'a u8, y: &'b u8) -> &'b u8 {
+    if *x > 0 { y } else { x }
+}
+"#,
+        expect![[r#"
+            'a: 'b
+        "#]],
+    );
+    check(
+        r#"
+fn goal<'a>(x: &'a u8, y: &'a u8) -> &'a u8 {
+    if *x > 0 { y } else { x }
+}
+"#,
+        expect![[""]],
+    );
+}
+
+#[test]
+fn elided_lifetimes() {
+    check(
+        r#"
+fn goal(x: &u8) -> &u8 {
+    x
+}
+"#,
+        expect![[""]],
+    );
+    check(
+        r#"
+fn goal<'a>(x: &'a u8, y: &(u8, &u8)) -> &'a u8 {
+    y.1
+}
+"#,
+        expect![[r#"
+            '_1: 'a
+        "#]],
+    );
+}
+
+#[test]
+fn static_lifetime() {
+    check(
+        r#"
+fn goal<'a>(x: &'a str) -> &'static str {
+    if true { "literal" } else { x }
+}
+"#,
+        expect![[r#"
+            'a: 'static
+        "#]],
+    );
+}
+
+#[test]
+fn dangling_borrow() {
+    check(
+        r#"
+fn goal<'a>(x: &'a u8) -> &'a u8 {
+    let local = *x;
+    &local
+}
+"#,
+        expect![[r#"
+            borrow of local: 'a
+        "#]],
+    );
+}
+
+#[test]
+fn reborrow() {
+    check(
+        r#"
+fn goal<'a, 'b>(x: &'a mut &'b u8) -> &'a u8 {
+    &**x
+}
+"#,
+        expect![[r#"
+            'b: 'a
+            borrow of **x: 'a
+        "#]],
+    );
+}
@@ -941,3 +941,27 @@ fn main() {
 "#,
     )
 }
+
+#[test]
+fn trait_upcasting() {
+    check_no_mismatches(
+        r#"
+//- minicore: coerce_unsized, send
+use core::{marker::Unsize, ops::CoerceUnsized};
+
+trait Super<T> {}
+trait Sub: Super<u32> {}
+
+struct Ptr<T: ?Sized>(*const T);
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Ptr<U>> for Ptr<T> {}
+
+fn test(a: &dyn Sub, b: &mut (dyn Sub + Send), c: *const dyn Sub, d: Ptr<dyn Sub>) {
+    let _: &dyn Super<u32> = a;
+    let _: &mut (dyn Super<u32> + Send) = b;
+    let _: &dyn Super<_> = b;
+    let _: *const dyn Super<u32> = c;
+    let _: Ptr<dyn Super<u32>> = d;
+}
+"#,
+    );
+}
@@ -956,7 +956,7 @@ fn infer_builtin_macros_env() {
         }
         "#,
         expect![[r#"
-            !0..22 '"__RA_...TED__"': &str
+            !0..5 '"bar"': &str
             62..90 '{     ...o"); }': ()
             72..73 'x': &str
         "#]],
@@ -1939,3 +1939,40 @@ fn foo() {
 "#,
     );
 }
+
+#[test]
+fn arbitrary_self_types() {
+    check_types(
+        r#"
+//- minicore: deref, pin, receiver
+use core::{ops::{Deref, Receiver}, pin::Pin};
+
+struct Rc<T>(T);
+impl<T> Deref for Rc<T> {
+    type Target = T;
+    fn deref(&self) -> &T { loop {} }
+}
+
+struct MyPtr<T>(*const T);
+impl<T> Receiver for MyPtr<T> {
+    type Target = T;
+}
+
+struct Foo;
+impl Foo {
+    fn by_rc(self: Rc<Self>) -> u8 { loop {} }
+    fn by_pin(self: Pin<&mut Self>) -> u16 { loop {} }
+    fn by_ptr(self: MyPtr<Self>) -> u32 { loop {} }
+}
+
+fn f(rc: Rc<Foo>, pin: Pin<&mut Foo>, ptr: MyPtr<Foo>) {
+    rc.by_rc();
+  //^^^^^^^^^^ u8
+    pin.by_pin();
+  //^^^^^^^^^^^^ u16
+    ptr.by_ptr();
+  //^^^^^^^^^^^^ u32
+}
+"#,
+    );
+}
@@ -2095,6 +2095,23 @@ async fn main() {
     )
 }
 
+#[test]
+fn inline_const_in_array_length() {
+    check_types(
+        r#"
+const fn three() -> usize { 3 }
+fn main() {
+    let a = [0u8; const { three() + 2 }];
+      //^ [u8; 5]
+    let b: [u8; { 4 }] = [0; 4];
+      //^ [u8; 4]
+    let c = [0u8; const { let x: usize = 3; x * 2 }];
+      //^ [u8; 6]
+}
+"#,
+    );
+}
+
 #[test]
 fn async_fn_and_try_operator() {
     check_no_mismatches(
@@ -2513,6 +2530,30 @@ fn generic_default_depending_on_other_type_arg_forward() {
     );
 }
 
+#[test]
+fn const_generic_default() {
+    check_types(
+        r#"
+struct Array<T = u8, const N: usize = 4>([T; N]);
+struct Square<const N: usize, const M: usize = N>([[u8; M]; N]);
+struct Forward<const N: usize = M, const M: usize = 2>([u8; N]);
+
+fn test(a: Array, b: Array<u16>, c: Array<u16, 2>, d: Square<3>, e: Forward) {
+    a;
+  //^ Array<u8, 4>
+    b.0;
+  //^^^ [u16; 4]
+    c;
+  //^ Array<u16, 2>
+    d.0;
+  //^^^ [[u8; 3]; 3]
+    e.0;
+  //^^^ [u8; _]
+}
+"#,
+    );
+}
+
 #[test]
 fn infer_operator_overload() {
     check_types(
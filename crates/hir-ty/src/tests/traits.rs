@@ -177,6 +177,47 @@ fn not_send() -> Box<dyn Future<Output = ()> + 'static> {
     );
 }
 
+#[test]
+fn async_fn_in_trait() {
+    check_types(
+        r#"
+//- minicore: future
+trait Trait {
+    type Item;
+    async fn foo(&self) -> u32;
+    async fn bar(&self) -> Self::Item;
+}
+
+struct S;
+impl Trait for S {
+    type Item = i64;
+    async fn foo(&self) -> u32 { 0 }
+    async fn bar(&self) -> i64 { 0 }
+}
+
+fn generic<T: Trait>(t: T) {
+    async {
+        let foo = t.foo().await;
+          //^^^ u32
+        let bar = t.bar().await;
+          //^^^ <T as Trait>::Item
+    };
+}
+
+async fn test() {
+    S.foo();
+  //^^^^^^^ impl Future<Output = u32>
+    let foo = S.foo().await;
+      //^^^ u32
+    let bar = S.bar().await;
+      //^^^ i64
+    let baz = Trait::foo(&S).await;
+      //^^^ u32
+}
+"#,
+    );
+}
+
 #[test]
 fn into_future_trait() {
     check_types(
@@ -950,6 +991,29 @@ fn test<T: Trait>(t: T) { *t; }
     );
 }
 
+#[test]
+fn generic_param_env_implied_by_adt_where_clauses() {
+    check_types(
+        r#"
+trait Clone {}
+trait Trait { fn foo(self) -> u128; }
+impl<T> Trait for T where T: Clone {}
+struct Wrapper<T: Clone>(T);
+fn test<T>(w: &Wrapper<T>, t: T) { t.foo(); }
+                                 //^^^^^^^ u128
+"#,
+    );
+    check_types(
+        r#"
+trait Super { fn sup(&self) -> u8; }
+trait Sub: Super {}
+struct Wrapper<T> where T: Sub { t: T }
+fn test<T>(w: Wrapper<T>) { w.t.sup(); }
+                          //^^^^^^^^^ u8
+"#,
+    );
+}
+
 #[test]
 fn associated_type_placeholder() {
     // inside the generic function, the associated type gets normalized to a placeholder `ApplL::Out<T>` [https://rust-lang.github.io/rustc-guide/traits/associated-types.html#placeholder-associated-types].
@@ -1362,6 +1426,58 @@ fn foo<X>() -> Foo<impl Future<Output = ()>> {
     )
 }
 
+#[test]
+fn type_alias_impl_trait() {
+    check_types(
+        r#"
+//- minicore: sized
+trait Trait {
+    fn foo(&self) -> u32;
+}
+struct S;
+impl Trait for S {
+    fn foo(&self) -> u32 { 0 }
+}
+type Alias = impl Trait;
+fn make() -> Alias {
+    S
+}
+fn test() {
+    let x = make();
+    x;
+  //^ impl Trait
+    x.foo();
+  //^^^^^^^ u32
+}
+"#,
+    );
+}
+
+#[test]
+fn type_alias_impl_trait_defining_uses() {
+    check_no_mismatches(
+        r#"
+//- minicore: sized, iterator
+mod m {
+    pub type Alias = impl Iterator<Item = u32>;
+    pub fn make() -> Alias {
+        let it: Alias = Counter(0);
+        it
+    }
+    pub struct Counter(u32);
+    impl Iterator for Counter {
+        type Item = u32;
+        fn next(&mut self) -> Option<u32> { None }
+    }
+}
+fn test() {
+    let mut it: m::Alias = m::make();
+    let item: Option<u32> = it.next();
+}
+"#,
+    );
+}
+
 #[test]
 fn dyn_trait() {
     check_infer(
@@ -3011,6 +3127,26 @@ fn f() {
     );
 }
 
+#[test]
+fn fn_bound_informs_call_site_closure_signature() {
+    cov_mark::check!(fn_bound_informs_call_site_closure_signature);
+    check_types(
+        r#"
+//- minicore: fn
+struct S { field: u32 }
+impl S {
+    fn inherent(&self) -> u8 { 0 }
+}
+fn apply<T, U, F: for<'a> Fn(&'a T) -> &'a U>(f: F, t: T) {}
+
+fn f() {
+    apply(|x| { x.inherent(); &x.field }, S { field: 0 });
+              //^^^^^^^^^^^^ u8
+}
+        "#,
+    );
+}
+
 #[test]
 fn infer_fn_trait_arg() {
     check_infer_with_mismatches(
@@ -4049,6 +4185,25 @@ fn g<P: PointerFamily>(p: <P as PointerFamily>::Pointer<i32>) {
     );
 }
 
+#[test]
+fn gats_in_associated_type_bound() {
+    check_types(
+        r#"
+trait Family {
+    type Member<T>;
+}
+trait Trait {
+    type Fam: Family<Member<u32> = i64>;
+}
+
+fn f<T: Trait>(m: <T::Fam as Family>::Member<u32>) {
+    m;
+  //^ i64
+}
+        "#,
+    );
+}
+
 #[test]
 fn gats_with_impl_trait() {
     // FIXME: the last function (`fn i()`) is not valid Rust as of this writing because you cannot
@@ -4122,6 +4277,62 @@ fn f<'a>(v: &dyn Trait<Assoc<i32> = &'a i32>) {
     );
 }
 
+#[test]
+fn gats_normalized_with_impls() {
+    check_types(
+        r#"
+trait Trait {
+    type Assoc<U>;
+    fn get<U>(&self) -> Self::Assoc<U>;
+}
+
+struct S<T>(T);
+impl<T> Trait for S<T> {
+    type Assoc<U> = (T, U);
+    fn get<U>(&self) -> Self::Assoc<U> { loop {} }
+}
+
+fn f(s: S<i32>, a: <S<u16> as Trait>::Assoc<()>) {
+    let b = s.get::<u8>();
+      //^ (i32, u8)
+    let c = a;
+      //^ (u16, ())
+}
+    "#,
+    );
+}
+
+#[test]
+fn gats_lending_iterator() {
+    check_types(
+        r#"
+//- minicore: option
+trait LendingIterator {
+    type Item<'a> where Self: 'a;
+    fn next<'a>(&'a mut self) -> Option<Self::Item<'a>>;
+}
+
+struct WindowsMut<'t> {
+    slice: &'t mut [u8],
+}
+impl<'t> LendingIterator for WindowsMut<'t> {
+    type Item<'a> = &'a mut [u8] where Self: 'a;
+    fn next<'a>(&'a mut self) -> Option<Self::Item<'a>> { loop {} }
+}
+
+fn f(mut w: WindowsMut<'_>) {
+    let a = w.next();
+      //^ Option<&mut [u8]>
+}
+fn g<I: LendingIterator>(mut i: I) {
+    while let Some(a) = i.next() {
+                 //^ LendingIterator::Item<I>
+    }
+}
+    "#,
+    );
+}
+
 #[test]
 fn gats_in_associated_type_binding() {
     check_types(
@@ -4375,3 +4586,63 @@ fn derive_macro_bounds() {
         "#,
     );
 }
+
+#[test]
+fn trait_alias_bounds() {
+    check_types(
+        r#"
+//- minicore: iterator
+trait Foo {
+    fn foo(&self) -> u8;
+}
+trait Bar<T> {
+    fn bar(&self) -> T;
+}
+auto trait Marker {}
+trait Alias<T> = Foo + Bar<T>;
+trait Nested = Alias<i64>;
+trait DynAlias = Bar<bool> + Marker;
+trait Numbers = Iterator<Item = u16>;
+trait Iter = Iterator;
+
+fn generic<T: Nested>(t: T) {
+    t.foo();
+  //^^^^^^^ u8
+    t.bar();
+  //^^^^^^^ i64
+}
+fn impl_trait(t: impl Alias<u32>) {
+    t.bar();
+  //^^^^^^^ u32
+}
+fn dyn_trait(t: &dyn DynAlias) {
+    t.bar();
+  //^^^^^^^ bool
+}
+fn assoc_bindings<I: Numbers, J: Iter<Item = char>>(mut i: I, mut j: J) {
+    i.next();
+  //^^^^^^^^ Option<u16>
+    j.next();
+  //^^^^^^^^ Option<char>
+}
+fn assoc_shorthand<I: Iter>(x: I::Item) {
+    x;
+  //^ Iterator::Item<I>
+}
+"#,
+    );
+}
+
+#[test]
+fn trait_alias_cycle() {
+    check_types(
+        r#"
+trait A = B;
+trait B = A;
+fn f<T: A>(t: T) {
+    t;
+  //^ T
+}
+"#,
+    );
+}
@@ -118,6 +118,7 @@ fn solve(
     goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<Interner>>>,
 ) -> Option<chalk_solve::Solution<Interner>> {
     let context = ChalkContext { db, krate, block };
+    let _span = tracing::debug_span!("solve", ?krate).entered();
     tracing::debug!("solve goal: {:?}", goal);
     let mut solver = create_chalk_solver();
 
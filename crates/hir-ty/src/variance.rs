@@ -0,0 +1,370 @@
+//! Computes the variances of the generic parameters of ADTs, type aliases and functions from the
+//! way the parameters are used in their fields and signatures, like rustc does in
+//! `rustc_hir_analysis::variance`.
+//!
+//! Unlike rustc, which solves the constraints of all the items of a crate together, the variances
+//! are computed one item at a time. A recursive item is iterated on until its variances don't
+//! change anymore, and items referring to each other through cycles are considered invariant in all
+//! their parameters.
+
+use std::sync::Arc;
+
+use hir_def::{
+    data::adt::VariantData,
+    generics::TypeOrConstParamData,
+    lang_item::{lang_attr, LangItem},
+    path::{GenericArg, GenericArgs, Path},
+    resolver::{HasResolver, Resolver, TypeNs},
+    type_ref::{LifetimeRef, Mutability, TypeBound, TypeRef},
+    AdtId, GenericDefId,
+};
+use intern::Interned;
+
+use crate::{
+    db::HirDatabase,
+    utils::{generics, Generics},
+};
+
+/// The variance of a generic parameter, which describes how the subtyping relation of the item
+/// follows the one of the parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variance {
+    /// `Foo<A>` is a subtype of `Foo<B>` if `A` is a subtype of `B`.
+    Covariant,
+    /// `Foo<A>` is a subtype of `Foo<B>` only if `A` and `B` are the same.
+    Invariant,
+    /// `Foo<A>` is a subtype of `Foo<B>` if `B` is a subtype of `A`.
+    Contravariant,
+    /// `Foo<A>` is a subtype of `Foo<B>` for any `A` and `B`, because the parameter isn't used.
+    Bivariant,
+}
+
+impl Variance {
+    /// Returns the variance of a parameter used with the variance `v` in a position which itself
+    /// has the variance `self`, like `T` in `fn(Vec<T>)`.
+    pub fn xform(self, v: Variance) -> Variance {
+        match (self, v) {
+            (Variance::Covariant, v) => v,
+            (Variance::Contravariant, v) => v.flip(),
+            (Variance::Invariant, _) => Variance::Invariant,
+            (Variance::Bivariant, _) => Variance::Bivariant,
+        }
+    }
+
+    /// Returns the variance of a parameter used both with the variances `self` and `v`.
+    pub fn glb(self, v: Variance) -> Variance {
+        match (self, v) {
+            (Variance::Bivariant, v) | (v, Variance::Bivariant) => v,
+            (a, b) if a == b => a,
+            _ => Variance::Invariant,
+        }
+    }
+
+    fn flip(self) -> Variance {
+        match self {
+            Variance::Covariant => Variance::Contravariant,
+            Variance::Contravariant => Variance::Covariant,
+            it @ (Variance::Invariant | Variance::Bivariant) => it,
+        }
+    }
+
+    /// Converts the variance for chalk, which has no bivariance.
+    pub(crate) fn to_chalk(self) -> chalk_ir::Variance {
+        match self {
+            Variance::Covariant => chalk_ir::Variance::Covariant,
+            Variance::Contravariant => chalk_ir::Variance::Contravariant,
+            Variance::Invariant | Variance::Bivariant => chalk_ir::Variance::Invariant,
+        }
+    }
+}
+
+/// The variances of the generic parameters of an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variances {
+    /// The variances of the type and const parameters, in the order of the substitutions of the
+    /// item, which includes the parameters of the parent of associated functions.
+    pub params: Box<[Variance]>,
+    /// The variances of the lifetime parameters of the item itself, in declaration order.
+    pub lifetimes: Box<[Variance]>,
+}
+
+impl Variances {
+    fn invariant(generics: &Generics) -> Variances {
+        Variances {
+            params: vec![Variance::Invariant; generics.len()].into(),
+            lifetimes: vec![Variance::Invariant; generics.params.lifetimes.len()].into(),
+        }
+    }
+}
+
+pub(crate) fn variances_of_query(db: &dyn HirDatabase, def: GenericDefId) -> Arc<Variances> {
+    let generics = generics(db.upcast(), def);
+    let types: Vec<_> = match def {
+        GenericDefId::AdtId(adt) => {
+            // Like rustc, these are defined by their variances rather than by their fields.
+            match lang_attr(db.upcast(), adt) {
+                Some(LangItem::PhantomData) => {
+                    return Arc::new(Variances {
+                        params: vec![Variance::Covariant; generics.len()].into(),
+                        lifetimes: Box::new([]),
+                    });
+                }
+                Some(LangItem::UnsafeCell) => return Arc::new(Variances::invariant(&generics)),
+                _ => {}
+            }
+            adt_variants(db, adt)
+                .iter()
+                .flat_map(|variant| variant.fields().iter())
+                .map(|(_, field)| (field.type_ref.clone(), Variance::Covariant))
+                .collect()
+        }
+        GenericDefId::TypeAliasId(alias) => db
+            .type_alias_data(alias)
+            .type_ref
+            .clone()
+            .map(|it| (it, Variance::Covariant))
+            .into_iter()
+            .collect(),
+        GenericDefId::FunctionId(func) => {
+            let data = db.function_data(func);
+            data.params
+                .iter()
+                .map(|it| (it.clone(), Variance::Contravariant))
+                .chain([(data.ret_type.clone(), Variance::Covariant)])
+                .collect()
+        }
+        GenericDefId::EnumVariantId(variant) => return db.variances_of(variant.parent.into()),
+        GenericDefId::TraitId(_)
+        | GenericDefId::TraitAliasId(_)
+        | GenericDefId::ImplId(_)
+        | GenericDefId::ConstId(_) => return Arc::new(Variances::invariant(&generics)),
+    };
+
+    let initial = Variances {
+        params: generics
+            .iter()
+            .map(|(_, data)| match data {
+                // Like rustc, const parameters are always invariant.
+                TypeOrConstParamData::ConstParamData(_) => Variance::Invariant,
+                TypeOrConstParamData::TypeParamData(_) => Variance::Bivariant,
+            })
+            .collect(),
+        lifetimes: vec![Variance::Bivariant; generics.params.lifetimes.len()].into(),
+    };
+    let resolver = def.resolver(db.upcast());
+    let mut current = initial.clone();
+    // Uses of the item in its own fields are constrained by the variances computed so far, so
+    // iterate until the variances don't change anymore. This terminates because the variances
+    // only ever move towards `Invariant`.
+    loop {
+        let mut ctx = Context {
+            db,
+            def,
+            resolver: &resolver,
+            generics: &generics,
+            current: &current,
+            next: initial.clone(),
+        };
+        for (type_ref, variance) in &types {
+            ctx.add_type_ref(type_ref, *variance);
+        }
+        if ctx.next == current {
+            return Arc::new(current);
+        }
+        current = ctx.next;
+    }
+}
+
+pub(crate) fn variances_of_recover(
+    db: &dyn HirDatabase,
+    _cycle: &[String],
+    def: &GenericDefId,
+) -> Arc<Variances> {
+    Arc::new(Variances::invariant(&generics(db.upcast(), *def)))
+}
+
+fn adt_variants(db: &dyn HirDatabase, adt: AdtId) -> Vec<Arc<VariantData>> {
+    match adt {
+        AdtId::StructId(it) => vec![db.struct_data(it).variant_data.clone()],
+        AdtId::UnionId(it) => vec![db.union_data(it).variant_data.clone()],
+        AdtId::EnumId(it) => {
+            db.enum_data(it).variants.iter().map(|(_, it)| it.variant_data.clone()).collect()
+        }
+    }
+}
+
+struct Context<'a> {
+    db: &'a dyn HirDatabase,
+    def: GenericDefId,
+    resolver: &'a Resolver,
+    generics: &'a Generics,
+    /// The variances of the previous iteration, for the uses of `def` in its own fields.
+    current: &'a Variances,
+    next: Variances,
+}
+
+impl Context<'_> {
+    fn add_type_ref(&mut self, type_ref: &TypeRef, variance: Variance) {
+        match type_ref {
+            TypeRef::Path(path) => self.add_path(path, variance),
+            TypeRef::Reference(inner, lifetime, mutability) => {
+                if let Some(lifetime) = lifetime {
+                    self.add_lifetime(lifetime, variance);
+                }
+                let inner_variance = match mutability {
+                    Mutability::Shared => variance,
+                    Mutability::Mut => Variance::Invariant,
+                };
+                self.add_type_ref(inner, inner_variance);
+            }
+            TypeRef::RawPtr(inner, mutability) => {
+                let inner_variance = match mutability {
+                    Mutability::Shared => variance,
+                    Mutability::Mut => Variance::Invariant,
+                };
+                self.add_type_ref(inner, inner_variance);
+            }
+            TypeRef::Array(inner, _) | TypeRef::Slice(inner) => self.add_type_ref(inner, variance),
+            TypeRef::Tuple(fields) => {
+                fields.iter().for_each(|it| self.add_type_ref(it, variance));
+            }
+            TypeRef::Fn(params, ..) => {
+                if let Some(((_, ret), params)) = params.split_last() {
+                    for (_, param) in params {
+                        self.add_type_ref(param, variance.xform(Variance::Contravariant));
+                    }
+                    self.add_type_ref(ret, variance);
+                }
+            }
+            TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
+                self.add_bounds(bounds, variance)
+            }
+            TypeRef::Macro(_) => {
+                // FIXME: expand the macro. Until then, we can't know how the parameters are used.
+                self.next.params.iter_mut().for_each(|it| *it = Variance::Invariant);
+                self.next.lifetimes.iter_mut().for_each(|it| *it = Variance::Invariant);
+            }
+            TypeRef::Never | TypeRef::Placeholder | TypeRef::Error => {}
+        }
+    }
+
+    fn add_path(&mut self, path: &Path, variance: Variance) {
+        let resolution = match self.resolver.resolve_path_in_type_ns(self.db.upcast(), path) {
+            Some((resolution, None)) if path.type_anchor().is_none() => resolution,
+            // Projections like `T::Assoc` and `<T as Trait>::Assoc` can be anything.
+            _ => return self.add_path_args(path, Variance::Invariant),
+        };
+        let def: GenericDefId = match resolution {
+            TypeNs::GenericParam(param) => {
+                if let Some(idx) = self.generics.param_idx(param.into()) {
+                    self.next.params[idx] = self.next.params[idx].glb(variance);
+                }
+                return;
+            }
+            TypeNs::AdtSelfType(adt) if GenericDefId::from(adt) == self.def => {
+                // `Self` is the item with its own parameters.
+                for idx in 0..self.next.params.len() {
+                    let v = variance.xform(self.current.params[idx]);
+                    self.next.params[idx] = self.next.params[idx].glb(v);
+                }
+                for idx in 0..self.next.lifetimes.len() {
+                    let v = variance.xform(self.current.lifetimes[idx]);
+                    self.next.lifetimes[idx] = self.next.lifetimes[idx].glb(v);
+                }
+                return;
+            }
+            TypeNs::AdtId(adt) => adt.into(),
+            TypeNs::TypeAliasId(alias) => alias.into(),
+            TypeNs::SelfType(_)
+            | TypeNs::AdtSelfType(_)
+            | TypeNs::EnumVariantId(_)
+            | TypeNs::BuiltinType(_)
+            | TypeNs::TraitId(_)
+            | TypeNs::TraitAliasId(_) => return self.add_path_args(path, Variance::Invariant),
+        };
+        let Some(args) = path.segments().last().and_then(|it| it.args_and_bindings) else {
+            return;
+        };
+        let variances = if def == self.def {
+            Arc::new(self.current.clone())
+        } else {
+            self.db.variances_of(def)
+        };
+        self.add_args(args, variance, &variances);
+    }
+
+    /// Adds the generic arguments of a path to an item whose parameters have `variances`.
+    fn add_args(&mut self, args: &GenericArgs, variance: Variance, variances: &Variances) {
+        let (mut param_idx, mut lifetime_idx) = (0, 0);
+        for arg in args.args.iter() {
+            match arg {
+                GenericArg::Type(type_ref) => {
+                    let v = variances.params.get(param_idx).copied();
+                    self.add_type_ref(type_ref, variance.xform(v.unwrap_or(Variance::Invariant)));
+                    param_idx += 1;
+                }
+                GenericArg::Lifetime(lifetime) => {
+                    let v = variances.lifetimes.get(lifetime_idx).copied();
+                    self.add_lifetime(lifetime, variance.xform(v.unwrap_or(Variance::Invariant)));
+                    lifetime_idx += 1;
+                }
+                GenericArg::Const(_) => param_idx += 1,
+            }
+        }
+        self.add_bindings(args);
+    }
+
+    /// Adds all the generic arguments of all the segments of `path` with the same variance.
+    fn add_path_args(&mut self, path: &Path, variance: Variance) {
+        if let Some(anchor) = path.type_anchor() {
+            self.add_type_ref(anchor, variance);
+        }
+        for args in path.segments().iter().filter_map(|it| it.args_and_bindings) {
+            for arg in args.args.iter() {
+                match arg {
+                    GenericArg::Type(type_ref) => self.add_type_ref(type_ref, variance),
+                    GenericArg::Lifetime(lifetime) => self.add_lifetime(lifetime, variance),
+                    GenericArg::Const(_) => {}
+                }
+            }
+            self.add_bindings(args);
+        }
+    }
+
+    /// Associated type bindings like `Item = T` are invariant.
+    fn add_bindings(&mut self, args: &GenericArgs) {
+        for binding in args.bindings.iter() {
+            if let Some(type_ref) = &binding.type_ref {
+                self.add_type_ref(type_ref, Variance::Invariant);
+            }
+            self.add_bounds(&binding.bounds, Variance::Invariant);
+        }
+    }
+
+    /// The lifetime of a trait object or an `impl Trait` has the variance of the type, the generic
+    /// arguments of the traits are invariant.
+    fn add_bounds(&mut self, bounds: &[Interned<TypeBound>], variance: Variance) {
+        for bound in bounds {
+            match &**bound {
+                TypeBound::Lifetime(lifetime) => self.add_lifetime(lifetime, variance),
+                TypeBound::Path(path, _) | TypeBound::ForLifetime(_, path) => {
+                    self.add_path_args(path, Variance::Invariant);
+                }
+                TypeBound::Error => {}
+            }
+        }
+    }
+
+    fn add_lifetime(&mut self, lifetime: &LifetimeRef, variance: Variance) {
+        // FIXME: lifetimes of the parent of associated functions aren't tracked.
+        let lifetimes = &self.generics.params.lifetimes;
+        let Some((local_id, _)) = lifetimes.iter().find(|(_, it)| it.name == lifetime.name) else {
+            return;
+        };
+        let idx = u32::from(local_id.into_raw()) as usize;
+        self.next.lifetimes[idx] = self.next.lifetimes[idx].glb(variance);
+    }
+}
+
+#[cfg(test)]
+mod tests;
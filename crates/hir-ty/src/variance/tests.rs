@@ -0,0 +1,140 @@
+use base_db::fixture::WithFixture;
+use expect_test::{expect, Expect};
+use hir_def::{db::DefDatabase, AdtId, GenericDefId, ModuleDefId};
+use itertools::Itertools;
+
+use crate::{db::HirDatabase, test_db::TestDB};
+
+fn check(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let def_map = module.def_map(&db);
+    let mut lines = def_map[module.local_id]
+        .scope
+        .declarations()
+        .filter_map(|decl| {
+            let (def, name): (GenericDefId, _) = match decl {
+                ModuleDefId::AdtId(AdtId::StructId(it)) => {
+                    (it.into(), db.struct_data(it).name.clone())
+                }
+                ModuleDefId::AdtId(AdtId::EnumId(it)) => (it.into(), db.enum_data(it).name.clone()),
+                ModuleDefId::AdtId(AdtId::UnionId(it)) => {
+                    (it.into(), db.union_data(it).name.clone())
+                }
+                ModuleDefId::TypeAliasId(it) => (it.into(), db.type_alias_data(it).name.clone()),
+                ModuleDefId::FunctionId(it) => (it.into(), db.function_data(it).name.clone()),
+                _ => return None,
+            };
+            let params = db.generic_params(def);
+            let variances = db.variances_of(def);
+            let lifetimes = params.lifetimes.iter().map(|(_, it)| it.name.clone());
+            let type_or_consts = params
+                .type_or_consts
+                .iter()
+                .map(|(_, it)| it.name().cloned().unwrap_or_else(hir_expand::name::Name::missing));
+            let variances = lifetimes
+                .zip(variances.lifetimes.iter())
+                .chain(type_or_consts.zip(variances.params.iter()))
+                .map(|(name, variance)| format!("{name}: {variance:?}"))
+                .join(", ");
+            Some(format!("{name}[{variances}]\n"))
+        })
+        .collect::<Vec<_>>();
+    lines.sort();
+    expect.assert_eq(&lines.concat());
+}
+
+#[test]
+fn fields_and_references() {
+    check(
+        r#"
+struct Shared<'a, T> { x: &'a T }
+struct Unique<'a, T> { x: &'a mut T }
+struct Pointers<T, U> { a: *const T, b: *mut U }
+struct Unused<'a, T>;
+struct Const<T, const N: usize> { a: [T; N] }
+"#,
+        expect![[r#"
+            Const[T: Covariant, N: Invariant]
+            Pointers[T: Covariant, U: Invariant]
+            Shared['a: Covariant, T: Covariant]
+            Unique['a: Covariant, T: Invariant]
+            Unused['a: Bivariant, T: Bivariant]
+        "#]],
+    );
+}
+
+#[test]
+fn function_pointers_and_functions() {
+    check(
+        r#"
+struct Callback<A, R> { f: fn(A) -> R }
+struct Both<T> { a: T, f: fn(T) }
+fn call<A, R>(f: fn(A) -> R) {}
+fn first<'a, T>(x: &'a [T]) -> &'a T { loop {} }
+"#,
+        expect![[r#"
+            Both[T: Invariant]
+            Callback[A: Contravariant, R: Covariant]
+            call[A: Covariant, R: Contravariant]
+            first['a: Invariant, T: Invariant]
+        "#]],
+    );
+}
+
+#[test]
+fn nested_items_and_aliases() {
+    check(
+        r#"
+struct Wrapper<T> { x: T }
+struct Cell<T> { x: *mut T }
+type Reader<T> = fn() -> T;
+type Writer<T> = fn(T);
+struct Uses<A, B, C, D> { a: Wrapper<A>, b: Cell<B>, c: Reader<C>, d: Writer<fn(D)> }
+"#,
+        expect![[r#"
+            Cell[T: Invariant]
+            Reader[T: Covariant]
+            Uses[A: Covariant, B: Invariant, C: Covariant, D: Covariant]
+            Wrapper[T: Covariant]
+            Writer[T: Contravariant]
+        "#]],
+    );
+}
+
+#[test]
+fn recursive_items() {
+    check(
+        r#"
+enum List<T> { Nil, Cons(T, *const List<T>) }
+struct Flip<A, B> { f: fn(A), next: *const Flip<B, A> }
+struct SelfRef<T> { x: T, next: *const Self }
+"#,
+        expect![[r#"
+            Flip[A: Contravariant, B: Contravariant]
+            List[T: Covariant]
+            SelfRef[T: Covariant]
+        "#]],
+    );
+}
+
+#[test]
+fn lang_items_and_trait_objects() {
+    check(
+        r#"
+#[lang = "phantom_data"]
+struct PhantomData<T>;
+#[lang = "unsafe_cell"]
+struct UnsafeCell<T> { value: T }
+trait Trait<T> {}
+struct Uses<'a, A, B, C> { a: PhantomData<A>, b: UnsafeCell<B>, c: &'a dyn Trait<C> }
+struct Object<'a> { x: *const (dyn Trait<u8> + 'a) }
+"#,
+        expect![[r#"
+            Object['a: Covariant]
+            PhantomData[T: Covariant]
+            Uses['a: Covariant, A: Covariant, B: Invariant, C: Invariant]
+            UnsafeCell[T: Invariant]
+        "#]],
+    );
+}
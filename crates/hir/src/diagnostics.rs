@@ -5,14 +5,14 @@
 //! be expressed in terms of hir types themselves.
 pub use hir_ty::diagnostics::{IncoherentImpl, IncorrectCase};
 
-use base_db::CrateId;
+use base_db::{CrateId, FileId};
 use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_def::path::ModPath;
 use hir_expand::{name::Name, HirFileId, InFile};
 use syntax::{ast, AstPtr, SyntaxError, SyntaxNodePtr, TextRange};
 
-use crate::{AssocItem, Field, Local, MacroKind, Type};
+use crate::{AssocItem, Field, Impl, Local, MacroKind, Trait, Type};
 
 macro_rules! diagnostics {
     ($($diag:ident,)*) => {
@@ -33,6 +33,9 @@ macro_rules! diagnostics {
 
 diagnostics![
     BreakOutsideOfLoop,
+    ConflictingImpls,
+    DeadStore,
+    DuplicateEntryItem,
     ExpectedFunction,
     InactiveCode,
     IncorrectCase,
@@ -45,13 +48,16 @@ diagnostics![
     MismatchedArgCount,
     MissingFields,
     MissingMatchArms,
+    MissingPanicHandler,
     MissingUnsafe,
     NeedMut,
     NoSuchField,
     PrivateAssocItem,
     PrivateField,
     ReplaceFilterMapNextWithFindMap,
+    TransmuteSizeMismatch,
     TypeMismatch,
+    UnalignedTransmute,
     UndeclaredLabel,
     UnimplementedBuiltinMacro,
     UnreachableLabel,
@@ -72,6 +78,28 @@ pub struct BreakOutsideOfLoop {
     pub bad_value_break: bool,
 }
 
+#[derive(Debug)]
+pub struct ConflictingImpls {
+    pub impl_: InFile<AstPtr<ast::Impl>>,
+    /// The impl overlapping with `impl_`, which may be in another crate.
+    pub other: Impl,
+    pub trait_: Trait,
+    pub self_ty: Type,
+}
+
+#[derive(Debug)]
+pub struct DuplicateEntryItem {
+    pub name: InFile<AstPtr<ast::Name>>,
+    /// The attribute marking the item, like `panic_handler`.
+    pub attr: &'static str,
+}
+
+#[derive(Debug)]
+pub struct MissingPanicHandler {
+    /// The root file of the `#![no_std]` crate.
+    pub file: FileId,
+}
+
 #[derive(Debug)]
 pub struct UnresolvedModule {
     pub decl: InFile<AstPtr<ast::Module>>,
@@ -233,6 +261,9 @@ pub struct MismatchedArgCount {
 pub struct MissingMatchArms {
     pub scrutinee_expr: InFile<AstPtr<ast::Expr>>,
     pub uncovered_patterns: String,
+    /// The uncovered patterns, written to be inserted as arms of the match, or nothing if they
+    /// can't all be written.
+    pub witnesses: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -242,6 +273,27 @@ pub struct TypeMismatch {
     pub actual: Type,
 }
 
+#[derive(Debug)]
+pub struct TransmuteSizeMismatch {
+    pub call: InFile<AstPtr<ast::Expr>>,
+    pub from: Type,
+    pub to: Type,
+    /// The sizes of the types for the target, in bytes.
+    pub from_size: u64,
+    pub to_size: u64,
+}
+
+#[derive(Debug)]
+pub struct UnalignedTransmute {
+    pub call: InFile<AstPtr<ast::Expr>>,
+    /// The pointer types.
+    pub from: Type,
+    pub to: Type,
+    /// The alignments of the pointees for the target, in bytes.
+    pub from_align: u64,
+    pub to_align: u64,
+}
+
 #[derive(Debug)]
 pub struct NeedMut {
     pub local: Local,
@@ -252,3 +304,10 @@ pub struct NeedMut {
 pub struct UnusedMut {
     pub local: Local,
 }
+
+#[derive(Debug)]
+pub struct DeadStore {
+    pub local: Local,
+    /// The initializer of the `let` statement, or the right hand side of the assignment.
+    pub value: InFile<AstPtr<ast::Expr>>,
+}
@@ -345,6 +345,9 @@ fn write_generic_params(
                     delim(f)?;
                     write!(f, "const {name}: ")?;
                     c.ty.hir_fmt(f)?;
+                    if let Some(default) = &c.default {
+                        write!(f, " = {default}")?;
+                    }
                 }
             }
         }
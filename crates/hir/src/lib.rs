@@ -41,11 +41,14 @@ use either::Either;
 use hir_def::{
     body::{BodyDiagnostic, SyntheticSyntax},
     data::adt::VariantData,
-    generics::{LifetimeParamData, TypeOrConstParamData, TypeParamProvenance},
+    generics::{
+        LifetimeParamData, TypeOrConstParamData, TypeParamProvenance, WherePredicate,
+        WherePredicateTypeTarget,
+    },
     hir::{BindingAnnotation, BindingId, ExprOrPatId, LabelId, Pat},
     item_tree::ItemTreeNode,
-    lang_item::{LangItem, LangItemTarget},
-    layout::ReprOptions,
+    lang_item::{EntryItem, LangItem, LangItemTarget},
+    layout::{ReprOptions, TargetDataLayout},
     macro_id_to_def_id,
     nameres::{self, diagnostics::DefDiagnostic, ModuleOrigin},
     per_ns::PerNs,
@@ -58,15 +61,22 @@ use hir_def::{
 };
 use hir_expand::{name::name, MacroCallKind};
 use hir_ty::{
-    all_super_traits, autoderef,
-    consteval::{try_const_usize, unknown_const_as_generic, ConstEvalError, ConstExt},
+    all_super_traits,
+    auto_trait::BlockingStepKind,
+    autoderef,
+    consteval::{
+        try_const_scalar, try_const_usize, unknown_const_as_generic, ConstEvalError, ConstExt,
+    },
     diagnostics::BodyValidationDiagnostic,
     display::HexifiedConst,
-    layout::{layout_of_ty, Layout, LayoutError},
+    layout::{layout_of_ty, Layout as TyLayout, TagEncoding, Variants},
     method_resolution::{self, TyFingerprint},
-    mir::{self, interpret_mir},
+    mir::{
+        self, interpret_mir, interpret_mir_until_bound, interpret_mir_with_external_values,
+        interpret_mir_with_global_allocator, interpret_mir_with_profile, interpret_mir_with_seed,
+        interpret_mir_with_step_profile, interpret_mir_with_trace,
+    },
     primitive::UintTy,
-    traits::FnTrait,
     AliasTy, CallableDefId, CallableSig, Canonical, CanonicalVarKinds, Cast, ClosureId,
     GenericArgData, Interner, ParamKind, QuantifiedWhereClause, Scalar, Substitution,
     TraitEnvironment, TraitRefExt, Ty, TyBuilder, TyDefId, TyExt, TyKind, WhereClause,
@@ -75,7 +85,7 @@ use itertools::Itertools;
 use nameres::diagnostics::DefDiagnosticKind;
 use once_cell::unsync::Lazy;
 use rustc_hash::FxHashSet;
-use stdx::{impl_from, never};
+use stdx::{format_to, impl_from, never};
 use syntax::{
     ast::{self, HasAttrs as _, HasDocComments, HasName},
     AstNode, AstPtr, SmolStr, SyntaxNode, SyntaxNodePtr, TextRange, T,
@@ -86,16 +96,18 @@ use crate::db::{DefDatabase, HirDatabase};
 pub use crate::{
     attrs::{HasAttrs, Namespace},
     diagnostics::{
-        AnyDiagnostic, BreakOutsideOfLoop, ExpectedFunction, InactiveCode, IncoherentImpl,
-        IncorrectCase, InvalidDeriveTarget, MacroDefError, MacroError, MacroExpansionParseError,
-        MalformedDerive, MismatchedArgCount, MissingFields, MissingMatchArms, MissingUnsafe,
-        NeedMut, NoSuchField, PrivateAssocItem, PrivateField, ReplaceFilterMapNextWithFindMap,
-        TypeMismatch, UndeclaredLabel, UnimplementedBuiltinMacro, UnreachableLabel,
-        UnresolvedExternCrate, UnresolvedField, UnresolvedImport, UnresolvedMacroCall,
-        UnresolvedMethodCall, UnresolvedModule, UnresolvedProcMacro, UnusedMut,
+        AnyDiagnostic, BreakOutsideOfLoop, ConflictingImpls, DeadStore, DuplicateEntryItem,
+        ExpectedFunction, InactiveCode, IncoherentImpl, IncorrectCase, InvalidDeriveTarget,
+        MacroDefError, MacroError, MacroExpansionParseError, MalformedDerive, MismatchedArgCount,
+        MissingFields, MissingMatchArms, MissingPanicHandler, MissingUnsafe, NeedMut, NoSuchField,
+        PrivateAssocItem, PrivateField, ReplaceFilterMapNextWithFindMap, TransmuteSizeMismatch,
+        TypeMismatch, UnalignedTransmute, UndeclaredLabel, UnimplementedBuiltinMacro,
+        UnreachableLabel, UnresolvedExternCrate, UnresolvedField, UnresolvedImport,
+        UnresolvedMacroCall, UnresolvedMethodCall, UnresolvedModule, UnresolvedProcMacro,
+        UnusedMut,
     },
     has_source::HasSource,
-    semantics::{PathResolution, Semantics, SemanticsScope, TypeInfo, VisibleTraits},
+    semantics::{PatTypeInfo, PathResolution, Semantics, SemanticsScope, TypeInfo, VisibleTraits},
 };
 
 // Be careful with these re-exports.
@@ -131,7 +143,10 @@ pub use {
     },
     hir_ty::{
         display::{ClosureStyle, HirDisplay, HirDisplayError, HirWrite},
-        mir::MirEvalError,
+        layout::LayoutError,
+        lifetime_elision::{SignatureLifetime, SignatureLifetimes},
+        mir::{BuildProfile, ExternalCall, ExternalValue, ExternalValueProvider, MirEvalError},
+        traits::FnTrait,
         PointerCast, Safety,
     },
 };
@@ -167,6 +182,11 @@ impl Crate {
         matches!(self.origin(db), CrateOrigin::Lang(_))
     }
 
+    /// Whether the crate is `#![no_main]`, and so doesn't use its `main` function as entry point.
+    pub fn is_no_main(self, db: &dyn HirDatabase) -> bool {
+        db.crate_def_map(self.id).is_no_main()
+    }
+
     pub fn dependencies(self, db: &dyn HirDatabase) -> Vec<CrateDependency> {
         db.crate_graph()[self.id]
             .dependencies
@@ -608,14 +628,31 @@ impl Module {
             }
         }
         self.legacy_macros(db).into_iter().for_each(|m| emit_macro_def_diagnostics(db, acc, m));
+        emit_entry_item_diagnostics(db, acc, self);
 
         let inherent_impls = db.inherent_impls_in_crate(self.id.krate());
+        let conflicting_impls = db.conflicting_impls(self.id.krate());
 
         for impl_def in self.impl_defs(db) {
             for diag in db.impl_data_with_diagnostics(impl_def.id).1.iter() {
                 emit_def_diagnostic(db, acc, diag);
             }
 
+            for &(_, other) in conflicting_impls.iter().filter(|(it, _)| *it == impl_def.id) {
+                let (Some(src), Some(trait_)) = (impl_def.source(db), impl_def.trait_(db)) else {
+                    continue;
+                };
+                acc.push(
+                    ConflictingImpls {
+                        impl_: src.map(|it| AstPtr::new(&it)),
+                        other: other.into(),
+                        trait_,
+                        self_ty: impl_def.self_ty(db),
+                    }
+                    .into(),
+                );
+            }
+
             if inherent_impls.invalid_impls().contains(&impl_def.id) {
                 let loc = impl_def.id.lookup(db.upcast());
                 let tree = loc.id.item_tree(db.upcast());
@@ -689,6 +726,61 @@ impl Module {
     }
 }
 
+/// Reports the panic handlers, global allocators and allocation error handlers of `module` which
+/// are also defined elsewhere in the crate graph, and a missing panic handler if `module` is the
+/// root of a `#![no_std]` binary.
+fn emit_entry_item_diagnostics(db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>, module: Module) {
+    let krate = module.id.krate();
+    let def_map = module.id.def_map(db.upcast());
+    let needs_panic_handler = module.id.block.is_none()
+        && module.id.local_id == def_map.root()
+        && def_map.is_no_std()
+        && def_map.is_no_main();
+    let lang_items = db.crate_lang_items(krate);
+    let own_items = lang_items
+        .entry_items()
+        .iter()
+        .filter(|(_, target)| match *target {
+            LangItemTarget::Function(it) => Function::from(it).module(db) == module,
+            LangItemTarget::Static(it) => Static::from(it).module(db) == module,
+            _ => false,
+        })
+        .collect::<Vec<_>>();
+    if own_items.is_empty() && !needs_panic_handler {
+        return;
+    }
+
+    let all_items = db
+        .crate_graph()
+        .transitive_deps(krate)
+        .flat_map(|it| {
+            db.crate_lang_items(it).entry_items().iter().map(|(kind, _)| *kind).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    for &(kind, target) in own_items {
+        if all_items.iter().filter(|&&it| it == kind).count() < 2 {
+            continue;
+        }
+        let name = match target {
+            LangItemTarget::Function(it) => Function::from(it).source(db).and_then(|src| {
+                let name = src.value.name()?;
+                Some(src.with_value(AstPtr::new(&name)))
+            }),
+            LangItemTarget::Static(it) => Static::from(it).source(db).and_then(|src| {
+                let name = src.value.name()?;
+                Some(src.with_value(AstPtr::new(&name)))
+            }),
+            _ => None,
+        };
+        if let Some(name) = name {
+            acc.push(DuplicateEntryItem { name, attr: kind.attr_name() }.into());
+        }
+    }
+    if needs_panic_handler && !all_items.contains(&EntryItem::PanicHandler) {
+        acc.push(MissingPanicHandler { file: db.crate_graph()[krate].root_file_id }.into());
+    }
+}
+
 fn emit_macro_def_diagnostics(db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>, m: Macro) {
     let id = macro_id_to_def_id(db.upcast(), m.id);
     if let Err(e) = db.macro_def(id) {
@@ -956,7 +1048,7 @@ impl Field {
     }
 
     pub fn layout(&self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
-        layout_of_ty(db, &self.ty(db).ty, self.parent.module(db).krate().into())
+        self.ty(db).layout(db)
     }
 
     pub fn parent_def(&self, _db: &dyn HirDatabase) -> VariantDef {
@@ -1202,7 +1294,9 @@ impl Adt {
         if db.generic_params(self.into()).iter().count() != 0 {
             return Err(LayoutError::HasPlaceholder);
         }
-        db.layout_of_adt(self.into(), Substitution::empty(Interner))
+        let krate = self.module(db).krate().id;
+        let layout = db.layout_of_adt(self.into(), Substitution::empty(Interner))?;
+        Layout::new(db, layout, krate)
     }
 
     /// Turns this ADT into a type. Any type parameters of the ADT will be
@@ -1389,6 +1483,45 @@ impl DefWithBody {
         }
     }
 
+    /// A trace of the statements executed when evaluating this def's body, with the values they
+    /// stored, followed by the result or the error. For debugging const evaluation.
+    ///
+    /// `progress` is called periodically with the number of executed blocks, and interrupts the
+    /// evaluation when it returns `false`.
+    pub fn debug_eval_trace(
+        self,
+        db: &dyn HirDatabase,
+        progress: &dyn Fn(usize) -> bool,
+    ) -> String {
+        let body = match db.mir_body(self.id()) {
+            Ok(body) => body,
+            Err(e) => return format!("error:\n{e:?}"),
+        };
+        if body.arg_count != 0 {
+            return "error: functions with parameters can't be evaluated".to_string();
+        }
+        let (result, trace) =
+            interpret_mir_with_trace(db, &body, Substitution::empty(Interner), false, progress);
+        let mut text = trace.join("\n");
+        match result {
+            Ok(value) => format_to!(text, "\n// result: {}", HexifiedConst(value).display(db)),
+            Err(e) => format_to!(text, "\n// error: {e:?}"),
+        }
+        text
+    }
+
+    /// The bodies executed when evaluating this def's body, with their numbers of executed MIR
+    /// blocks, the costliest first. For finding what exhausts the execution limit.
+    pub fn eval_step_profile(self, db: &dyn HirDatabase) -> Vec<(DefWithBody, usize)> {
+        let Ok(body) = db.mir_body(self.id()) else { return Vec::new() };
+        if body.arg_count != 0 {
+            return Vec::new();
+        }
+        let (_, profile) =
+            interpret_mir_with_step_profile(db, &body, Substitution::empty(Interner), false);
+        profile.into_iter().map(|(owner, steps)| (owner.into(), steps)).collect()
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
         let krate = self.module(db).id.krate();
 
@@ -1568,6 +1701,22 @@ impl DefWithBody {
             }
         }
 
+        for mismatch in hir_ty::diagnostics::transmute_mismatches(db, self.into()) {
+            let Ok(call) = source_map.expr_syntax(mismatch.call) else { continue };
+            let from = Type::new(db, DefWithBodyId::from(self), mismatch.from);
+            let to = Type::new(db, DefWithBodyId::from(self), mismatch.to);
+            match mismatch.kind {
+                hir_ty::diagnostics::TransmuteMismatchKind::Size {
+                    from: from_size,
+                    to: to_size,
+                } => acc.push(TransmuteSizeMismatch { call, from, to, from_size, to_size }.into()),
+                hir_ty::diagnostics::TransmuteMismatchKind::Alignment {
+                    from: from_align,
+                    to: to_align,
+                } => acc.push(UnalignedTransmute { call, from, to, from_align, to_align }.into()),
+            }
+        }
+
         let hir_body = db.body(self.into());
 
         if let Ok(borrowck_results) = db.borrowck(self.into()) {
@@ -1612,6 +1761,12 @@ impl DefWithBody {
                         }
                     }
                 }
+                for dead_store in &borrowck_result.dead_stores {
+                    let local = Local { parent: self.into(), binding_id: dead_store.binding };
+                    if let Ok(value) = source_map.expr_syntax(dead_store.value) {
+                        acc.push(DeadStore { local, value }.into());
+                    }
+                }
             }
         }
 
@@ -1692,30 +1847,31 @@ impl DefWithBody {
                         );
                     }
                 }
-                BodyValidationDiagnostic::MissingMatchArms { match_expr, uncovered_patterns } => {
-                    match source_map.expr_syntax(match_expr) {
-                        Ok(source_ptr) => {
-                            let root = source_ptr.file_syntax(db.upcast());
-                            if let ast::Expr::MatchExpr(match_expr) =
-                                &source_ptr.value.to_node(&root)
-                            {
-                                if let Some(scrut_expr) = match_expr.expr() {
-                                    acc.push(
-                                        MissingMatchArms {
-                                            scrutinee_expr: InFile::new(
-                                                source_ptr.file_id,
-                                                AstPtr::new(&scrut_expr),
-                                            ),
-                                            uncovered_patterns,
-                                        }
-                                        .into(),
-                                    );
-                                }
+                BodyValidationDiagnostic::MissingMatchArms {
+                    match_expr,
+                    uncovered_patterns,
+                    witnesses,
+                } => match source_map.expr_syntax(match_expr) {
+                    Ok(source_ptr) => {
+                        let root = source_ptr.file_syntax(db.upcast());
+                        if let ast::Expr::MatchExpr(match_expr) = &source_ptr.value.to_node(&root) {
+                            if let Some(scrut_expr) = match_expr.expr() {
+                                acc.push(
+                                    MissingMatchArms {
+                                        scrutinee_expr: InFile::new(
+                                            source_ptr.file_id,
+                                            AstPtr::new(&scrut_expr),
+                                        ),
+                                        uncovered_patterns,
+                                        witnesses,
+                                    }
+                                    .into(),
+                                );
                             }
                         }
-                        Err(SyntheticSyntax) => (),
                     }
-                }
+                    Err(SyntheticSyntax) => (),
+                },
             }
         }
 
@@ -1822,6 +1978,11 @@ impl Function {
             .collect()
     }
 
+    /// Returns the lifetimes of the references in the signature, with the elided ones resolved.
+    pub fn signature_lifetimes(self, db: &dyn HirDatabase) -> SignatureLifetimes {
+        hir_ty::lifetime_elision::signature_lifetimes(db, self.id)
+    }
+
     pub fn is_const(self, db: &dyn HirDatabase) -> bool {
         db.function_data(self.id).has_const_kw()
     }
@@ -1856,13 +2017,105 @@ impl Function {
         def_map.fn_as_proc_macro(self.id).map(|id| Macro { id: id.into() })
     }
 
-    pub fn eval(self, db: &dyn HirDatabase) -> Result<(), MirEvalError> {
-        let body = db
+    /// Runs the function, like a test. The entropy the function uses, like the random keys of
+    /// `HashMap`s, is drawn from a deterministic generator seeded with `seed`, or with a fixed
+    /// default seed, so that runs can be reproduced.
+    pub fn eval(self, db: &dyn HirDatabase, seed: Option<u64>) -> FunctionEval {
+        let seed = seed.unwrap_or(mir::DEFAULT_SEED);
+        let result = db
             .mir_body(self.id.into())
-            .map_err(|e| MirEvalError::MirLowerError(self.id.into(), e))?;
-        interpret_mir(db, &body, Substitution::empty(Interner), false)?;
-        Ok(())
+            .map_err(|e| MirEvalError::MirLowerError(self.id.into(), e))
+            .and_then(|body| {
+                interpret_mir_with_seed(db, &body, Substitution::empty(Interner), false, seed)
+            })
+            .map(drop);
+        FunctionEval { seed, result }
     }
+
+    /// Like [`Function::eval`], with the calls of the functions provided by `external_values`,
+    /// like the ones reading files or devices, answered by it instead of being executed.
+    pub fn eval_with_external_values(
+        self,
+        db: &dyn HirDatabase,
+        seed: Option<u64>,
+        external_values: &dyn ExternalValueProvider,
+    ) -> FunctionEval {
+        let seed = seed.unwrap_or(mir::DEFAULT_SEED);
+        let result = db
+            .mir_body(self.id.into())
+            .map_err(|e| MirEvalError::MirLowerError(self.id.into(), e))
+            .and_then(|body| {
+                interpret_mir_with_external_values(
+                    db,
+                    &body,
+                    Substitution::empty(Interner),
+                    false,
+                    seed,
+                    external_values,
+                )
+            })
+            .map(drop);
+        FunctionEval { seed, result }
+    }
+
+    /// Like [`Function::eval`], with the allocations made by the `#[global_allocator]` of the
+    /// crate graph, so that they behave like they do at runtime.
+    pub fn eval_with_global_allocator(
+        self,
+        db: &dyn HirDatabase,
+        seed: Option<u64>,
+    ) -> FunctionEval {
+        let seed = seed.unwrap_or(mir::DEFAULT_SEED);
+        let result = db
+            .mir_body(self.id.into())
+            .map_err(|e| MirEvalError::MirLowerError(self.id.into(), e))
+            .and_then(|body| {
+                interpret_mir_with_global_allocator(
+                    db,
+                    &body,
+                    Substitution::empty(Interner),
+                    false,
+                    seed,
+                )
+            })
+            .map(drop);
+        FunctionEval { seed, result }
+    }
+
+    /// Like [`Function::eval`], with the arithmetic overflows and the `cfg!(debug_assertions)`
+    /// checks handled like under `profile`. The code gated by `#[cfg(debug_assertions)]` attributes
+    /// is the one of the crate graph of `db`.
+    pub fn eval_with_profile(
+        self,
+        db: &dyn HirDatabase,
+        seed: Option<u64>,
+        profile: BuildProfile,
+    ) -> FunctionEval {
+        let seed = seed.unwrap_or(mir::DEFAULT_SEED);
+        let result = db
+            .mir_body(self.id.into())
+            .map_err(|e| MirEvalError::MirLowerError(self.id.into(), e))
+            .and_then(|body| {
+                interpret_mir_with_profile(
+                    db,
+                    &body,
+                    Substitution::empty(Interner),
+                    false,
+                    seed,
+                    profile,
+                )
+            })
+            .map(drop);
+        FunctionEval { seed, result }
+    }
+}
+
+/// The result of running a function with [`Function::eval`].
+#[derive(Debug)]
+pub struct FunctionEval {
+    /// The seed of the entropy of the run, which reproduces it.
+    pub seed: u64,
+    pub result: Result<(), MirEvalError>,
 }
 
 // Note: logically, this belongs to `hir_ty`, but we are not using it there yet.
@@ -2007,16 +2260,13 @@ impl Const {
 
     pub fn render_eval(self, db: &dyn HirDatabase) -> Result<String, ConstEvalError> {
         let c = db.const_eval(self.id, Substitution::empty(Interner))?;
-        let r = format!("{}", HexifiedConst(c).display(db));
-        // We want to see things like `<utf8-error>` and `<layout-error>` as they are probably bug in our
-        // implementation, but there is no need to show things like `<enum-not-supported>` or `<ref-not-supported>` to
-        // the user.
-        if r.contains("not-supported>") {
-            return Err(ConstEvalError::MirEvalError(MirEvalError::NotSupported(
-                "rendering complex constants".to_string(),
-            )));
-        }
-        return Ok(r);
+        render_const_value(db, c)
+    }
+
+    /// Evaluates the const, and returns its value if it's an integer, a `bool` or a `char`.
+    pub fn eval_scalar(self, db: &dyn HirDatabase) -> Option<i128> {
+        let c = db.const_eval(self.id, Substitution::empty(Interner)).ok()?;
+        try_const_scalar(&c)
     }
 }
 
@@ -2026,6 +2276,19 @@ impl HasVisibility for Const {
     }
 }
 
+fn render_const_value(db: &dyn HirDatabase, c: hir_ty::Const) -> Result<String, ConstEvalError> {
+    let r = format!("{}", HexifiedConst(c).display(db));
+    // We want to see things like `<utf8-error>` and `<layout-error>` as they are probably bug in our
+    // implementation, but there is no need to show things like `<enum-not-supported>` or `<ref-not-supported>` to
+    // the user.
+    if r.contains("not-supported>") {
+        return Err(ConstEvalError::MirEvalError(MirEvalError::NotSupported(
+            "rendering complex constants".to_string(),
+        )));
+    }
+    Ok(r)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Static {
     pub(crate) id: StaticId,
@@ -2055,6 +2318,23 @@ impl Static {
         let ty = ctx.lower_ty(&data.type_ref);
         Type::new_with_resolver_inner(db, &resolver, ty)
     }
+
+    /// Evaluates the initializer of the static, and renders its value like
+    /// [`Const::render_eval`].
+    pub fn render_eval(self, db: &dyn HirDatabase) -> Result<String, ConstEvalError> {
+        render_const_value(db, self.eval(db)?)
+    }
+
+    /// Evaluates the initializer of the static, and returns its value if it's an integer, a
+    /// `bool` or a `char`.
+    pub fn eval_scalar(self, db: &dyn HirDatabase) -> Option<i128> {
+        try_const_scalar(&self.eval(db).ok()?)
+    }
+
+    fn eval(self, db: &dyn HirDatabase) -> Result<hir_ty::Const, ConstEvalError> {
+        let body = db.mir_body(self.id.into())?;
+        Ok(interpret_mir(db, &body, Substitution::empty(Interner), false)?)
+    }
 }
 
 impl HasVisibility for Static {
@@ -2657,6 +2937,23 @@ impl Local {
         matches!(body[self.binding_id].mode, BindingAnnotation::Ref | BindingAnnotation::RefMut)
     }
 
+    /// Interprets the body of this local until the local gets its first value, and returns that
+    /// value rendered, or `None` if the body returns before that. Only bodies without parameters,
+    /// like the ones of consts or of `fn main`, can be interpreted.
+    pub fn eval_first_value(self, db: &dyn HirDatabase) -> Result<Option<String>, MirEvalError> {
+        let body = db.mir_body(self.parent).map_err(|e| match self.parent {
+            DefWithBodyId::FunctionId(f) => MirEvalError::MirLowerError(f, e),
+            _ => MirEvalError::ConstEvalError(Box::new(e.into())),
+        })?;
+        if body.arg_count != 0 {
+            return Err(MirEvalError::NotSupported(
+                "evaluating functions with parameters".to_string(),
+            ));
+        }
+        let value = interpret_mir_until_bound(db, &body, self.binding_id)?;
+        Ok(value.map(|it| it.display(db).to_string()))
+    }
+
     pub fn parent(self, _db: &dyn HirDatabase) -> DefWithBody {
         self.parent.into()
     }
@@ -2915,6 +3212,36 @@ impl TypeParam {
             .collect()
     }
 
+    /// Returns the names of the lifetimes bounding the type parameter, like `'static` for
+    /// `T: 'static`. Like [`TypeParam::trait_bounds`], this only looks at the item defining it.
+    pub fn lifetime_bounds(self, db: &dyn HirDatabase) -> Vec<Name> {
+        let parent = self.id.parent();
+        let params = db.generic_params(parent);
+        let local_id = self.id.local_id();
+        let name = params.type_or_consts[local_id].name();
+        params
+            .where_predicates
+            .iter()
+            .filter_map(|pred| match pred {
+                WherePredicate::TypeBound { target, bound } => Some((target, bound)),
+                _ => None,
+            })
+            .filter(|(target, _)| match target {
+                WherePredicateTypeTarget::TypeOrConstParam(id) => *id == local_id,
+                WherePredicateTypeTarget::TypeRef(type_ref) => match &**type_ref {
+                    TypeRef::Path(path) => {
+                        name.is_some() && path.mod_path().and_then(ModPath::as_ident) == name
+                    }
+                    _ => false,
+                },
+            })
+            .filter_map(|(_, bound)| match &**bound {
+                hir_def::type_ref::TypeBound::Lifetime(lifetime) => Some(lifetime.name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn default(self, db: &dyn HirDatabase) -> Option<Type> {
         let params = db.generic_defaults(self.id.parent());
         let local_idx = hir_ty::param_idx(db, self.id.into())?;
@@ -3122,6 +3449,17 @@ impl Impl {
         db.impl_data(self.id).is_negative
     }
 
+    /// Returns the impls of the same trait overlapping with this impl, in its crate and in the
+    /// dependencies of its crate.
+    pub fn conflicting_impls(self, db: &dyn HirDatabase) -> Vec<Impl> {
+        let krate = self.module(db).krate();
+        db.conflicting_impls(krate.id)
+            .iter()
+            .filter(|(it, _)| *it == self.id)
+            .map(|&(_, other)| other.into())
+            .collect()
+    }
+
     pub fn module(self, db: &dyn HirDatabase) -> Module {
         self.id.lookup(db.upcast()).container.into()
     }
@@ -3294,6 +3632,17 @@ impl Type {
         }
     }
 
+    /// Returns `true` if no value of this type can exist, as far as can be seen from `module`.
+    pub fn is_uninhabited_from(&self, db: &dyn HirDatabase, module: Module) -> bool {
+        hir_ty::is_ty_uninhabited_from(&self.ty, module.id, db)
+    }
+
+    /// Returns the number of types and consts this type is made of, or a number bigger than
+    /// `limit` if there are more than that.
+    pub fn type_length(&self, limit: usize) -> usize {
+        hir_ty::type_length(&self.ty, limit)
+    }
+
     pub fn remove_ref(&self) -> Option<Type> {
         match &self.ty.kind(Interner) {
             TyKind::Ref(.., ty) => Some(self.derived(ty.clone())),
@@ -3324,8 +3673,28 @@ impl Type {
     /// `std::future::Future`.
     /// This function is used in `.await` syntax completion.
     pub fn impls_into_future(&self, db: &dyn HirDatabase) -> bool {
-        let trait_ = db
-            .lang_item(self.env.krate, LangItem::IntoFutureIntoFuture)
+        let trait_ = match self.into_future_trait(db) {
+            Some(it) => it,
+            None => return false,
+        };
+
+        let canonical_ty =
+            Canonical { value: self.ty.clone(), binders: CanonicalVarKinds::empty(Interner) };
+        method_resolution::implements_trait(&canonical_ty, db, self.env.clone(), trait_)
+    }
+
+    /// Returns the type of `.await`ing a value of this type, if it implements `IntoFuture`.
+    pub fn into_future_output(&self, db: &dyn HirDatabase) -> Option<Type> {
+        if !self.impls_into_future(db) {
+            return None;
+        }
+        let trait_ = self.into_future_trait(db)?;
+        let output = db.trait_data(trait_).associated_type_by_name(&name![Output])?;
+        self.normalize_trait_assoc_type(db, &[], output.into())
+    }
+
+    fn into_future_trait(&self, db: &dyn HirDatabase) -> Option<TraitId> {
+        db.lang_item(self.env.krate, LangItem::IntoFutureIntoFuture)
             .and_then(|it| {
                 let into_future_fn = it.as_function()?;
                 let assoc_item = as_assoc_item(db, AssocItem::Function, into_future_fn)?;
@@ -3335,16 +3704,7 @@ impl Type {
             .or_else(|| {
                 let future_trait = db.lang_item(self.env.krate, LangItem::Future)?;
                 future_trait.as_trait()
-            });
-
-        let trait_ = match trait_ {
-            Some(it) => it,
-            None => return false,
-        };
-
-        let canonical_ty =
-            Canonical { value: self.ty.clone(), binders: CanonicalVarKinds::empty(Interner) };
-        method_resolution::implements_trait(&canonical_ty, db, self.env.clone(), trait_)
+            })
     }
 
     /// Checks that particular type `ty` implements `std::ops::FnOnce`.
@@ -3391,6 +3751,55 @@ impl Type {
         db.trait_solve(self.env.krate, self.env.block, goal).is_some()
     }
 
+    /// Explains why the type doesn't implement the auto trait `trait_`, like `Send`, by the types
+    /// leading to the one blocking the auto trait. Returns `None` if it implements the auto trait,
+    /// and an empty path if the type itself blocks it.
+    pub fn auto_trait_blocker(
+        &self,
+        db: &dyn HirDatabase,
+        trait_: Trait,
+    ) -> Option<Vec<AutoTraitBlockingStep>> {
+        let blocker = db.auto_trait_blocker(self.ty.clone(), trait_.id, self.env.clone())?;
+        let steps = blocker
+            .path
+            .iter()
+            .map(|step| {
+                let kind = match step.kind {
+                    BlockingStepKind::Field(it) => AutoTraitBlockingStepKind::Field(it.into()),
+                    BlockingStepKind::TupleField(it) => AutoTraitBlockingStepKind::TupleField(it),
+                    BlockingStepKind::Element => AutoTraitBlockingStepKind::Element,
+                    BlockingStepKind::Capture(parent, binding_id) => {
+                        AutoTraitBlockingStepKind::Capture(Local { parent, binding_id })
+                    }
+                    BlockingStepKind::HeldAcrossAwait(parent, binding_id) => {
+                        AutoTraitBlockingStepKind::HeldAcrossAwait(Local { parent, binding_id })
+                    }
+                    BlockingStepKind::HiddenType => AutoTraitBlockingStepKind::HiddenType,
+                    BlockingStepKind::WhereClause => AutoTraitBlockingStepKind::WhereClause,
+                };
+                AutoTraitBlockingStep {
+                    kind,
+                    ty: self.derived(step.ty.clone()),
+                    trait_: step.trait_.into(),
+                }
+            })
+            .collect();
+        Some(steps)
+    }
+
+    /// Returns the memory layout of the type, for the target of the crate the type comes from.
+    /// Extern types have none.
+    pub fn layout(&self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
+        if let TyKind::Foreign(_) = self.ty.kind(Interner) {
+            return Err(LayoutError::ExternType);
+        }
+        let layout = layout_of_ty(db, &self.ty, self.env.krate)?;
+        Layout::new(db, layout, self.env.krate)
+    }
+
+    /// Normalizes `<Self as Trait<..>>::Alias<..>`. `args` are the generic arguments of the trait
+    /// followed by the ones of the associated type, the missing arguments of the associated type
+    /// are unknown.
     pub fn normalize_trait_assoc_type(
         &self,
         db: &dyn HirDatabase,
@@ -3414,8 +3823,17 @@ impl Type {
                 }
             })
             .build();
-        // FIXME: We don't handle GATs yet.
-        let projection = TyBuilder::assoc_type_projection(db, alias.id, Some(parent_subst)).build();
+        let projection = TyBuilder::assoc_type_projection(db, alias.id, Some(parent_subst))
+            .fill(|x| match x {
+                ParamKind::Type => {
+                    let ty = args
+                        .next()
+                        .map_or_else(|| TyKind::Error.intern(Interner), |it| it.ty.clone());
+                    GenericArgData::Ty(ty).intern(Interner)
+                }
+                ParamKind::Const(ty) => unknown_const_as_generic(ty.clone()),
+            })
+            .build();
 
         let ty = db.normalize_projection(projection, self.env.clone());
         if ty.is_unknown() {
@@ -3463,6 +3881,20 @@ impl Type {
         matches!(self.ty.kind(Interner), TyKind::Closure { .. })
     }
 
+    pub fn as_closure(&self) -> Option<Closure> {
+        match self.ty.kind(Interner) {
+            TyKind::Closure(id, _) => Some(Closure { id: *id }),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this is the type of a closure capturing variables of its environment.
+    pub fn is_capturing_closure(&self, db: &dyn HirDatabase) -> bool {
+        let TyKind::Closure(id, _) = self.ty.kind(Interner) else { return false };
+        let (owner, _) = db.lookup_intern_closure((*id).into());
+        db.infer(owner).closure_has_captures(id)
+    }
+
     pub fn is_fn(&self) -> bool {
         matches!(self.ty.kind(Interner), TyKind::FnDef(..) | TyKind::Function { .. })
     }
@@ -4018,6 +4450,53 @@ impl Type {
     }
 }
 
+/// The memory layout of a type, see [`Type::layout`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Layout(Arc<TyLayout>, Arc<TargetDataLayout>);
+
+impl Layout {
+    fn new(db: &dyn HirDatabase, layout: TyLayout, krate: CrateId) -> Result<Layout, LayoutError> {
+        let target = db.target_data_layout(krate).ok_or(LayoutError::TargetLayoutNotAvailable)?;
+        Ok(Layout(Arc::new(layout), target))
+    }
+
+    pub fn size(&self) -> u64 {
+        self.0.size.bytes()
+    }
+
+    pub fn align(&self) -> u64 {
+        self.0.align.abi.bytes()
+    }
+
+    /// Returns the number of invalid values of the largest niche of the type, which enums
+    /// containing it can use to store their tag without taking more space.
+    pub fn niches(&self) -> Option<u128> {
+        Some(self.0.largest_niche?.available(&*self.1))
+    }
+
+    /// Returns the offset of the `field`th field in source order, for types with a single
+    /// variant.
+    pub fn field_offset(&self, field: usize) -> Option<u64> {
+        match self.0.variants {
+            Variants::Single { .. } if field < self.0.fields.count() => {
+                Some(self.0.fields.offset(field).bytes())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the size of the tag of enums with several variants, and `None` for other types
+    /// and for enums storing their tag in a niche of a variant.
+    pub fn enum_tag_size(&self) -> Option<u64> {
+        match &self.0.variants {
+            Variants::Multiple { tag, tag_encoding: TagEncoding::Direct, .. } => {
+                Some(tag.size(&*self.1).bytes())
+            }
+            _ => None,
+        }
+    }
+}
+
 // FIXME: Document this
 #[derive(Debug)]
 pub struct Callable {
@@ -4125,6 +4604,103 @@ fn closure_source(db: &dyn HirDatabase, closure: ClosureId) -> Option<ast::Closu
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Closure {
+    id: ClosureId,
+}
+
+impl Closure {
+    /// Returns the places captured by this closure, as computed by the closure capture analysis of
+    /// type inference.
+    pub fn captured_items(&self, db: &dyn HirDatabase) -> Vec<ClosureCapture> {
+        let (owner, _) = db.lookup_intern_closure(self.id.into());
+        let infer = db.infer(owner);
+        let (captures, _) = infer.closure_info(&self.id);
+        captures.iter().cloned().map(|capture| ClosureCapture { owner, capture }).collect()
+    }
+
+    /// Returns the most general of the `Fn` traits the closure implements, as inferred from the
+    /// way it uses its captures before `move` turns them all into captures by value.
+    pub fn fn_trait(&self, db: &dyn HirDatabase) -> FnTrait {
+        let (owner, _) = db.lookup_intern_closure(self.id.into());
+        let infer = db.infer(owner);
+        let (_, kind) = infer.closure_info(&self.id);
+        *kind
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClosureCapture {
+    owner: DefWithBodyId,
+    capture: hir_ty::CapturedItem,
+}
+
+impl ClosureCapture {
+    pub fn local(&self) -> Local {
+        Local { parent: self.owner, binding_id: self.capture.local() }
+    }
+
+    pub fn kind(&self) -> CaptureKind {
+        match self.capture.kind() {
+            hir_ty::CaptureKind::ByRef(
+                hir_ty::mir::BorrowKind::Shallow | hir_ty::mir::BorrowKind::Shared,
+            ) => CaptureKind::SharedRef,
+            hir_ty::CaptureKind::ByRef(hir_ty::mir::BorrowKind::Unique) => {
+                CaptureKind::UniqueSharedRef
+            }
+            hir_ty::CaptureKind::ByRef(hir_ty::mir::BorrowKind::Mut { .. }) => {
+                CaptureKind::MutableRef
+            }
+            hir_ty::CaptureKind::ByValue => CaptureKind::Move,
+        }
+    }
+
+    /// The type the closure stores for this capture, a reference for captures by reference.
+    pub fn ty(&self, db: &dyn HirDatabase) -> Type {
+        Type::new(db, self.owner, self.capture.ty().clone())
+    }
+
+    /// Renders the captured place, like `*a.b.0` for a closure using only a field of `a`.
+    pub fn display_place(&self, db: &dyn HirDatabase) -> String {
+        self.capture.display_place(self.owner, db)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptureKind {
+    SharedRef,
+    UniqueSharedRef,
+    MutableRef,
+    Move,
+}
+
+/// A type on the path to the type keeping a type from implementing an auto trait, see
+/// [`Type::auto_trait_blocker`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutoTraitBlockingStep {
+    /// How the type is reached from the previous one.
+    pub kind: AutoTraitBlockingStepKind,
+    pub ty: Type,
+    /// The auto trait the type doesn't implement.
+    pub trait_: Trait,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoTraitBlockingStepKind {
+    Field(Field),
+    TupleField(usize),
+    /// The element of an array or a slice, or the parameter of `PhantomData`.
+    Element,
+    /// The type a closure captures the local as.
+    Capture(Local),
+    /// A local of an async block or an async fn, held across an `.await`.
+    HeldAcrossAwait(Local),
+    /// The hidden type of a return position `impl Trait`.
+    HiddenType,
+    /// A type bound by a where clause of an explicit impl of the auto trait.
+    WhereClause,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum BindingMode {
     Move,
@@ -4210,6 +4786,7 @@ impl From<ItemInNs> for ScopeDef {
     }
 }
 
+/// An implicit conversion of an expression, see [`Semantics::expr_adjustments`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Adjustment {
     pub source: Type,
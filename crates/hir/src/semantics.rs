@@ -5,6 +5,7 @@ mod source_to_def;
 use std::{cell::RefCell, fmt, iter, mem, ops};
 
 use base_db::{FileId, FileRange};
+use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_def::{
     hir::Expr,
@@ -15,9 +16,10 @@ use hir_def::{
     AsMacroCall, DefWithBodyId, FieldId, FunctionId, MacroId, TraitId, VariantId,
 };
 use hir_expand::{
+    builtin_fn_macro::BuiltinFnLikeExpander,
     db::ExpandDatabase,
     name::{known, AsName},
-    ExpansionInfo, MacroCallId,
+    ExpansionInfo, MacroCallId, MacroDefKind,
 };
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -108,6 +110,17 @@ impl TypeInfo {
     }
 }
 
+/// The type of a pattern, with the match ergonomics applied to it.
+#[derive(Debug)]
+pub struct PatTypeInfo {
+    /// The type the pattern matches, once the references it is matched through are dereferenced.
+    pub ty: Type,
+    /// The reference types the pattern is implicitly matched through, the outermost first.
+    pub adjustments: SmallVec<[Type; 1]>,
+    /// The binding mode of the pattern, if it binds a name.
+    pub binding_mode: Option<BindingMode>,
+}
+
 /// Primary API to get semantic information, like types, from syntax trees.
 pub struct Semantics<'db, DB> {
     pub db: &'db DB,
@@ -148,6 +161,15 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.expand(macro_call)
     }
 
+    /// Returns the predicate of a `cfg!` invocation, and the cfg options of the crate it is
+    /// evaluated against.
+    pub fn cfg_macro_predicate(
+        &self,
+        macro_call: &ast::MacroCall,
+    ) -> Option<(CfgExpr, CfgOptions)> {
+        self.imp.cfg_macro_predicate(macro_call)
+    }
+
     /// If `item` has an attribute macro attached to it, expands it.
     pub fn expand_attr_macro(&self, item: &ast::Item) -> Option<SyntaxNode> {
         self.imp.expand_attr_macro(item)
@@ -338,6 +360,9 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.resolve_trait(trait_)
     }
 
+    /// Returns the adjustments inference applied to `expr`, in the order they were applied, like
+    /// the autoderefs and the autoref of a method receiver or the coercions at a coercion site.
+    /// The `source` of each adjustment is the `target` of the previous one.
     pub fn expr_adjustments(&self, expr: &ast::Expr) -> Option<Vec<Adjustment>> {
         self.imp.expr_adjustments(expr)
     }
@@ -358,6 +383,13 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.pattern_adjustments(pat)
     }
 
+    /// Returns the type of `pat` with the references match ergonomics matched it through, and the
+    /// binding mode they gave to it, like the implicit `ref mut` of `x` in
+    /// `let (x,) = &mut (0,);`.
+    pub fn type_of_pat_with_adjustments(&self, pat: &ast::Pat) -> Option<PatTypeInfo> {
+        self.imp.type_of_pat_with_adjustments(pat)
+    }
+
     pub fn binding_mode_of_pat(&self, pat: &ast::IdentPat) -> Option<BindingMode> {
         self.imp.binding_mode_of_pat(pat)
     }
@@ -366,6 +398,18 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.resolve_method_call(call).map(Function::from)
     }
 
+    /// Resolves the method call to the methods of all the impls which apply, the one which is
+    /// called first. There are several of them when impls specialize others, like a `default`
+    /// method of a blanket impl and the method of a more specific impl.
+    pub fn resolve_method_call_candidates(&self, call: &ast::MethodCallExpr) -> Vec<Function> {
+        self.imp
+            .resolve_method_call_candidates(call)
+            .unwrap_or_default()
+            .into_iter()
+            .map(Function::from)
+            .collect()
+    }
+
     /// Attempts to resolve this call expression as a method call falling back to resolving it as a field.
     pub fn resolve_method_call_field_fallback(
         &self,
@@ -531,6 +575,18 @@ impl<'db> SemanticsImpl<'db> {
         Some(node)
     }
 
+    fn cfg_macro_predicate(&self, macro_call: &ast::MacroCall) -> Option<(CfgExpr, CfgOptions)> {
+        let sa = self.analyze_no_infer(macro_call.syntax())?;
+        let file_id = sa.expand(self.db, InFile::new(sa.file_id, macro_call))?;
+        let macro_call_id = file_id.macro_file()?.macro_call_id;
+        let loc = self.db.lookup_intern_macro_call(macro_call_id);
+        if !matches!(loc.def.kind, MacroDefKind::BuiltIn(BuiltinFnLikeExpander::Cfg, _)) {
+            return None;
+        }
+        let arg = self.db.macro_arg(macro_call_id)?;
+        Some((CfgExpr::parse(&arg.0), Crate { id: loc.krate }.cfg(self.db)))
+    }
+
     fn expand_attr_macro(&self, item: &ast::Item) -> Option<SyntaxNode> {
         let src = self.wrap_node_infile(item.clone());
         let macro_call_id = self.with_ctx(|ctx| ctx.item_to_macro_call(src))?;
@@ -1148,6 +1204,17 @@ impl<'db> SemanticsImpl<'db> {
             .unwrap_or_default()
     }
 
+    fn type_of_pat_with_adjustments(&self, pat: &ast::Pat) -> Option<PatTypeInfo> {
+        let analyzer = self.analyze(pat.syntax())?;
+        let (ty, _) = analyzer.type_of_pat(self.db, pat)?;
+        let adjustments = analyzer.pattern_adjustments(self.db, pat).unwrap_or_default();
+        let binding_mode = match pat {
+            ast::Pat::IdentPat(it) => analyzer.binding_mode_of_pat(self.db, it),
+            _ => None,
+        };
+        Some(PatTypeInfo { ty, adjustments, binding_mode })
+    }
+
     fn binding_mode_of_pat(&self, pat: &ast::IdentPat) -> Option<BindingMode> {
         self.analyze(pat.syntax())?.binding_mode_of_pat(self.db, pat)
     }
@@ -1156,6 +1223,13 @@ impl<'db> SemanticsImpl<'db> {
         self.analyze(call.syntax())?.resolve_method_call(self.db, call)
     }
 
+    fn resolve_method_call_candidates(
+        &self,
+        call: &ast::MethodCallExpr,
+    ) -> Option<Vec<FunctionId>> {
+        self.analyze(call.syntax())?.resolve_method_call_candidates(self.db, call)
+    }
+
     fn resolve_method_call_fallback(
         &self,
         call: &ast::MethodCallExpr,
@@ -1488,7 +1562,11 @@ impl<'db> SemanticsImpl<'db> {
     }
 
     fn is_inside_unsafe(&self, expr: &ast::Expr) -> bool {
-        let Some(enclosing_item) = expr.syntax().ancestors().find_map(Either::<ast::Item, ast::Variant>::cast) else { return false };
+        let Some(enclosing_item) =
+            expr.syntax().ancestors().find_map(Either::<ast::Item, ast::Variant>::cast)
+        else {
+            return false;
+        };
 
         let def = match &enclosing_item {
             Either::Left(ast::Item::Fn(it)) if it.unsafe_token().is_some() => return true,
@@ -268,6 +268,23 @@ impl SourceAnalyzer {
         Some(self.resolve_impl_method_or_trait_def(db, f_in_trait, substs))
     }
 
+    pub(crate) fn resolve_method_call_candidates(
+        &self,
+        db: &dyn HirDatabase,
+        call: &ast::MethodCallExpr,
+    ) -> Option<Vec<FunctionId>> {
+        let expr_id = self.expr_id(db, &call.clone().into())?;
+        let (f_in_trait, substs) = self.infer.as_ref()?.method_resolution(expr_id)?;
+        let Some(owner) = self.resolver.body_owner() else { return Some(vec![f_in_trait]) };
+        let env = db.trait_environment_for_body(owner);
+        let candidates =
+            method_resolution::lookup_impl_method_candidates(db, env, f_in_trait, &substs);
+        if candidates.is_empty() {
+            return Some(vec![f_in_trait]);
+        }
+        Some(candidates.into_iter().map(|(it, _)| it).collect())
+    }
+
     pub(crate) fn resolve_method_call_fallback(
         &self,
         db: &dyn HirDatabase,
@@ -15,4 +15,5 @@ pub struct AssistConfig {
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
     pub assist_emit_must_use: bool,
+    pub assist_extract_variable_ascribe_type: bool,
 }
@@ -0,0 +1,133 @@
+use syntax::{
+    ast::{self, BinaryOp},
+    AstNode, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: add_clarifying_parentheses
+//
+// Adds parentheses around the operands of binary operators which have a different precedence,
+// so that the order of evaluation doesn't depend on knowing the precedence rules.
+//
+// ```
+// fn main() {
+//     let _ = 1 + 2 $0* 3 << 4 as u32 == 5 && true;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let _ = (((1 + (2 * 3)) << (4 as u32)) == 5) && true;
+// }
+// ```
+pub(crate) fn add_clarifying_parentheses(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let bin_expr = ctx.find_node_at_offset::<ast::BinExpr>()?;
+    let top_expr = bin_expr
+        .syntax()
+        .ancestors()
+        .map_while(ast::BinExpr::cast)
+        .take_while(|it| !matches!(it.op_kind(), Some(BinaryOp::Assignment { .. })))
+        .last()?;
+
+    let mut ranges = Vec::new();
+    collect_unclear_operands(&ast::Expr::BinExpr(top_expr.clone()), &mut ranges);
+    if ranges.is_empty() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("add_clarifying_parentheses", AssistKind::RefactorRewrite),
+        "Add clarifying parentheses",
+        top_expr.syntax().text_range(),
+        |builder| {
+            for range in ranges {
+                builder.insert(range.start(), "(");
+                builder.insert(range.end(), ")");
+            }
+        },
+    )
+}
+
+/// Collects the ranges of the operands in `expr` which are casts, or binary expressions with an
+/// operator of a different precedence than their parent's.
+fn collect_unclear_operands(expr: &ast::Expr, acc: &mut Vec<TextRange>) {
+    let bin_expr = match expr {
+        ast::Expr::BinExpr(it) => it,
+        ast::Expr::ParenExpr(it) => {
+            if let Some(expr) = it.expr() {
+                collect_unclear_operands(&expr, acc);
+            }
+            return;
+        }
+        _ => return,
+    };
+    let is_assignment = matches!(bin_expr.op_kind(), Some(BinaryOp::Assignment { .. }));
+
+    for operand in [bin_expr.lhs(), bin_expr.rhs()].into_iter().flatten() {
+        let is_unclear = !is_assignment
+            && match &operand {
+                ast::Expr::BinExpr(it) => {
+                    !matches!(it.op_kind(), Some(BinaryOp::Assignment { .. }))
+                        && operand.binding_power() != expr.binding_power()
+                }
+                ast::Expr::CastExpr(_) => true,
+                _ => false,
+            };
+        if is_unclear {
+            acc.push(operand.syntax().text_range());
+        }
+        collect_unclear_operands(&operand, acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_without_mixed_precedence() {
+        check_assist_not_applicable(add_clarifying_parentheses, r#"fn f() { a + b $0- c + d; }"#);
+        check_assist_not_applicable(add_clarifying_parentheses, r#"fn f() { a $0&& (b || c); }"#);
+        check_assist_not_applicable(add_clarifying_parentheses, r#"fn f() { x $0= a + b; }"#);
+    }
+
+    #[test]
+    fn adds_parens_to_the_whole_chain() {
+        check_assist(
+            add_clarifying_parentheses,
+            r#"fn f() { a || b $0&& c || d; }"#,
+            r#"fn f() { a || (b && c) || d; }"#,
+        );
+        check_assist(
+            add_clarifying_parentheses,
+            r#"fn f() { a + b * c - d / e $0% f; }"#,
+            r#"fn f() { a + (b * c) - (d / e % f); }"#,
+        );
+    }
+
+    #[test]
+    fn adds_parens_inside_parens_and_assignments() {
+        check_assist(
+            add_clarifying_parentheses,
+            r#"fn f() { x += (a $0| b & c) << d; }"#,
+            r#"fn f() { x += (a | (b & c)) << d; }"#,
+        );
+        check_assist(
+            add_clarifying_parentheses,
+            r#"fn f() { x = a $0< b as u8 + 1; }"#,
+            r#"fn f() { x = a < ((b as u8) + 1); }"#,
+        );
+    }
+
+    #[test]
+    fn target_is_the_top_expression() {
+        check_assist_target(
+            add_clarifying_parentheses,
+            r#"fn f() { x = a * b $0+ c; }"#,
+            "a * b + c",
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use hir::{known, PathResolution};
+use ide_db::assists::{AssistId, AssistKind};
+use itertools::Itertools;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make,
+    },
+    AstNode, SyntaxNode,
+};
+
+use crate::assist_context::{AssistContext, Assists};
+
+// Assist: convert_closure_to_fn
+//
+// Converts a closure which doesn't capture anything into a nested function. If the closure is
+// bound to a variable, the function takes the name of the variable.
+//
+// ```
+// fn main() {
+//     let add = $0|a: u32, b| a + b;
+//     add(1, 2u32);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     fn add(a: u32, b: u32) -> u32 {
+//         a + b
+//     }
+//     add(1, 2u32);
+// }
+// ```
+pub(crate) fn convert_closure_to_fn(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let closure = ctx.find_node_at_offset::<ast::ClosureExpr>()?;
+    let param_list = closure.param_list()?;
+    if !param_list.syntax().text_range().contains_range(ctx.selection_trimmed()) {
+        return None;
+    }
+    if closure.async_token().is_some() || closure.for_token().is_some() {
+        return None;
+    }
+    let body = closure.body()?;
+
+    let db = ctx.db();
+    let closure_expr = ast::Expr::ClosureExpr(closure.clone());
+    let ty = ctx.sema.type_of_expr(&closure_expr)?.original;
+    if !ty.is_closure() {
+        return None;
+    }
+    // Only closures without captures can become functions, this is also what allows them to be
+    // coerced to `fn` pointers.
+    if ty.is_capturing_closure(db) {
+        cov_mark::hit!(convert_closure_to_fn_captures);
+        return None;
+    }
+    let callable = ty.as_callable(db)?;
+    let module = ctx.sema.scope(closure.syntax())?.module();
+    // Nested functions can't use the generic parameters of the enclosing items, whether the types
+    // are written or inferred.
+    let uses_outer_generics = |ty: &hir::Type| !ty.generic_params(db).is_empty();
+    let render_ty = |ty: &hir::Type| {
+        if ty.contains_unknown() {
+            return None;
+        }
+        ty.display_source_code(db, module.into(), false).ok()
+    };
+
+    let params = param_list
+        .params()
+        .zip(callable.params(db))
+        .map(|(param, (_, ty))| {
+            if uses_outer_generics(&ty) {
+                return None;
+            }
+            match param.ty() {
+                Some(_) => Some(param.to_string()),
+                None => Some(format!("{}: {}", param.pat()?, render_ty(&ty)?)),
+            }
+        })
+        .collect::<Option<Vec<_>>>()?
+        .join(", ");
+    let ret_ty = callable.return_type();
+    if uses_outer_generics(&ret_ty) {
+        return None;
+    }
+    let ret = match closure.ret_type() {
+        Some(ret_type) => format!(" {ret_type}"),
+        None => {
+            if ret_ty.is_unit() {
+                String::new()
+            } else {
+                format!(" -> {}", render_ty(&ret_ty)?)
+            }
+        }
+    };
+
+    // If the closure is bound to a variable, we replace the whole `let` statement, otherwise the
+    // function goes right before the statement containing the closure.
+    let let_stmt =
+        closure.syntax().parent().and_then(ast::LetStmt::cast).and_then(|stmt| {
+            match stmt.pat()? {
+                ast::Pat::IdentPat(pat) if pat.ref_token().is_none() && pat.pat().is_none() => {
+                    Some((stmt, pat.name()?))
+                }
+                _ => None,
+            }
+        });
+    let anchor = match &let_stmt {
+        Some((stmt, _)) => stmt.syntax().clone(),
+        None => statement_of(closure.syntax())?,
+    };
+    let indent = IndentLevel::from_node(&anchor);
+    let body = match body {
+        ast::Expr::BlockExpr(block) if block.modifier().is_none() => block.reset_indent(),
+        body => make::block_expr(None, Some(body.reset_indent())),
+    }
+    .indent(indent);
+
+    acc.add(
+        AssistId("convert_closure_to_fn", AssistKind::RefactorRewrite),
+        "Convert closure to fn",
+        closure.syntax().text_range(),
+        |builder| match let_stmt {
+            Some((stmt, name)) => {
+                builder.replace(
+                    stmt.syntax().text_range(),
+                    format!("fn {name}({params}){ret} {body}"),
+                );
+            }
+            None => {
+                let name = "fun_name";
+                let tabstop = if ctx.config.snippet_cap.is_some() { "$0" } else { "" };
+                let fn_def = format!("fn {tabstop}{name}({params}){ret} {body}\n\n{indent}");
+                let range = closure.syntax().text_range();
+                if anchor == *closure.syntax() {
+                    // The closure is the tail expression of a block.
+                    let text = format!("{fn_def}{name}");
+                    match ctx.config.snippet_cap {
+                        Some(cap) => builder.replace_snippet(cap, range, text),
+                        None => builder.replace(range, text),
+                    }
+                } else {
+                    let offset = anchor.text_range().start();
+                    builder.replace(range, name);
+                    match ctx.config.snippet_cap {
+                        Some(cap) => builder.insert_snippet(cap, offset, fn_def),
+                        None => builder.insert(offset, fn_def),
+                    }
+                }
+            }
+        },
+    )
+}
+
+// Assist: convert_fn_item_to_closure
+//
+// Converts a reference to a function item into a closure calling it, so that the closure can be
+// extended with captures.
+//
+// ```
+// fn add(a: u32, b: u32) -> u32 { a + b }
+// fn apply(f: impl Fn(u32, u32) -> u32) {}
+// fn main() {
+//     apply(ad$0d);
+// }
+// ```
+// ->
+// ```
+// fn add(a: u32, b: u32) -> u32 { a + b }
+// fn apply(f: impl Fn(u32, u32) -> u32) {}
+// fn main() {
+//     apply(|a, b| add(a, b));
+// }
+// ```
+pub(crate) fn convert_fn_item_to_closure(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let path_expr = ctx.find_node_at_offset::<ast::PathExpr>()?;
+    let is_callee = path_expr
+        .syntax()
+        .parent()
+        .and_then(ast::CallExpr::cast)
+        .and_then(|call| call.expr())
+        .map_or(false, |callee| callee.syntax() == path_expr.syntax());
+    if is_callee {
+        return None;
+    }
+    let path = path_expr.path()?;
+    let func = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(hir::ModuleDef::Function(func)) => func,
+        _ => return None,
+    };
+    let db = ctx.db();
+    // A closure calling an unsafe function would need an `unsafe` block around the call.
+    if func.is_unsafe_to_call(db) {
+        return None;
+    }
+
+    let args = func
+        .assoc_fn_params(db)
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| match param.name(db) {
+            Some(name) if name == known::SELF_PARAM => "this".to_owned(),
+            Some(name) => name.to_string(),
+            None => format!("arg{idx}"),
+        })
+        .join(", ");
+
+    let target = path_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_fn_item_to_closure", AssistKind::RefactorRewrite),
+        "Convert function to closure",
+        target,
+        |builder| {
+            builder.replace(target, format!("|{args}| {path}({args})"));
+        },
+    )
+}
+
+/// Returns the statement, or the tail expression of a block, which contains `node`.
+fn statement_of(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors()
+        .find(|it| it.parent().map_or(false, |parent| ast::StmtList::can_cast(parent.kind())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_closure_bound_to_variable() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let double = |x: u32|$0 x * 2;
+    double(3);
+}
+"#,
+            r#"
+fn main() {
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+    double(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_closure_with_block_body() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let log = $0|msg: &str| {
+        let _ = msg;
+    };
+    log("hello");
+}
+"#,
+            r#"
+fn main() {
+    fn log(msg: &str) {
+        let _ = msg;
+    }
+    log("hello");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_inline_closure() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn apply(f: fn(u32) -> u32) -> u32 { f(1) }
+fn main() {
+    let x = apply($0|x| x * 2);
+}
+"#,
+            r#"
+fn apply(f: fn(u32) -> u32) -> u32 { f(1) }
+fn main() {
+    fn $0fun_name(x: u32) -> u32 {
+        x * 2
+    }
+
+    let x = apply(fun_name);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_closure_in_tail_expression() {
+        check_assist(
+            convert_closure_to_fn,
+            r#"
+fn make() -> fn(u32) -> u32 {
+    $0|x| x + 1
+}
+"#,
+            r#"
+fn make() -> fn(u32) -> u32 {
+    fn $0fun_name(x: u32) -> u32 {
+        x + 1
+    }
+
+    fun_name
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_capturing_closure() {
+        cov_mark::check!(convert_closure_to_fn_captures);
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let y = 2u32;
+    let add = $0|x: u32| x + y;
+    add(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_using_outer_generics() {
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main<T>(t: T) {
+    let id = $0|x| x;
+    id(t);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main<T>(t: T) {
+    let id = $0|x: T| x;
+    id(t);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main<T: Default>() {
+    let make = $0|| -> T { T::default() };
+    make();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_closure_body() {
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let double = |x: u32| x $0* 2;
+    double(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_fn_item() {
+        check_assist(
+            convert_fn_item_to_closure,
+            r#"
+fn double(x: u32) -> u32 { x * 2 }
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    apply(dou$0ble);
+}
+"#,
+            r#"
+fn double(x: u32) -> u32 { x * 2 }
+fn apply(f: impl Fn(u32) -> u32) {}
+fn main() {
+    apply(|x| double(x));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_method_item() {
+        check_assist(
+            convert_fn_item_to_closure,
+            r#"
+struct S;
+impl S {
+    fn get(&self, (a, _): (u32, u32)) -> u32 { a }
+}
+fn main() {
+    let f = S::g$0et;
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn get(&self, (a, _): (u32, u32)) -> u32 { a }
+}
+fn main() {
+    let f = |this, arg1| S::get(this, arg1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_item_not_applicable_to_call() {
+        check_assist_not_applicable(
+            convert_fn_item_to_closure,
+            r#"
+fn double(x: u32) -> u32 { x * 2 }
+fn main() {
+    dou$0ble(2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_item_not_applicable_to_unsafe_fn() {
+        check_assist_not_applicable(
+            convert_fn_item_to_closure,
+            r#"
+unsafe fn double(x: u32) -> u32 { x * 2 }
+fn main() {
+    let f = dou$0ble;
+}
+"#,
+        );
+    }
+}
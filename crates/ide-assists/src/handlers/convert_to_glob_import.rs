@@ -0,0 +1,240 @@
+use hir::{ModuleDef, PathResolution, ScopeDef};
+use ide_db::FxHashMap;
+use syntax::{ast, AstNode, SmolStr};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_to_glob_import
+//
+// Replaces a list of imported names with a glob import. This is the reverse of
+// `expand_glob_import`, and is not offered if one of the other names of the glob would
+// shadow or conflict with a name which is already in scope.
+//
+// ```
+// mod foo {
+//     pub struct Bar;
+//     pub struct Baz;
+// }
+//
+// use foo::{Bar, $0Baz};
+//
+// fn qux(bar: Bar, baz: Baz) {}
+// ```
+// ->
+// ```
+// mod foo {
+//     pub struct Bar;
+//     pub struct Baz;
+// }
+//
+// use foo::*;
+//
+// fn qux(bar: Bar, baz: Baz) {}
+// ```
+pub(crate) fn convert_to_glob_import(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let use_tree_list = ctx.find_node_at_offset::<ast::UseTreeList>()?;
+    let path = use_tree_list.parent_use_tree().path()?;
+    let target_module = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::Module(it)) => it,
+        _ => return None,
+    };
+
+    let mut imported_names = Vec::new();
+    for use_tree in use_tree_list.use_trees() {
+        if use_tree.rename().is_some() || use_tree.use_tree_list().is_some() {
+            return None;
+        }
+        let path = use_tree.path()?;
+        if path.qualifier().is_some() {
+            return None;
+        }
+        match path.segment()?.kind()? {
+            ast::PathSegmentKind::Name(name_ref) => imported_names.push(name_ref.text().into()),
+            _ => return None,
+        }
+    }
+    if imported_names.is_empty() {
+        return None;
+    }
+
+    let scope = ctx.sema.scope(use_tree_list.syntax())?;
+    if glob_would_shadow(ctx, &scope, target_module, &imported_names) {
+        cov_mark::hit!(convert_to_glob_would_shadow);
+        return None;
+    }
+
+    acc.add(
+        AssistId("convert_to_glob_import", AssistKind::RefactorRewrite),
+        "Convert to glob import",
+        use_tree_list.syntax().text_range(),
+        |builder| builder.replace(use_tree_list.syntax().text_range(), "*"),
+    )
+}
+
+/// Returns `true` if one of the names glob-imported from `target_module`, other than
+/// `imported_names`, is already in `scope` for something else. Such a glob would shadow the
+/// prelude, or make names ambiguous with another glob. Only the items declared in the current
+/// module are known to take precedence over the glob.
+fn glob_would_shadow(
+    ctx: &AssistContext<'_>,
+    scope: &hir::SemanticsScope<'_>,
+    target_module: hir::Module,
+    imported_names: &[SmolStr],
+) -> bool {
+    let current_module = scope.module();
+    let declarations = current_module.declarations(ctx.db());
+
+    let mut names_in_scope: FxHashMap<SmolStr, Vec<ScopeDef>> = FxHashMap::default();
+    scope.process_all_names(&mut |name, def| {
+        names_in_scope.entry(name.to_smol_str()).or_default().push(def)
+    });
+
+    target_module.scope(ctx.db(), Some(current_module)).into_iter().any(|(name, def)| {
+        let name = name.to_smol_str();
+        if imported_names.contains(&name) {
+            return false;
+        }
+        let Some(defs_in_scope) = names_in_scope.get(&name) else { return false };
+        defs_in_scope.iter().any(|it| match it {
+            _ if *it == def => false,
+            ScopeDef::ModuleDef(it) => !declarations.contains(it),
+            _ => true,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_nested_list() {
+        check_assist(
+            convert_to_glob_import,
+            r#"
+mod foo {
+    pub mod bar {
+        pub struct A;
+        pub struct B;
+        pub struct C;
+    }
+    pub struct D;
+}
+
+use foo::{bar::{A, B$0}, D};
+
+fn f(_: A, _: B, _: D) {}
+"#,
+            r#"
+mod foo {
+    pub mod bar {
+        pub struct A;
+        pub struct B;
+        pub struct C;
+    }
+    pub struct D;
+}
+
+use foo::{bar::*, D};
+
+fn f(_: A, _: B, _: D) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn local_items_take_precedence() {
+        check_assist(
+            convert_to_glob_import,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+struct B;
+
+use foo::{A$0};
+"#,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+struct B;
+
+use foo::*;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_renames_and_paths() {
+        check_assist_not_applicable(
+            convert_to_glob_import,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+use foo::{A as C, B$0};
+"#,
+        );
+        check_assist_not_applicable(
+            convert_to_glob_import,
+            r#"
+mod foo {
+    pub mod bar { pub struct A; }
+    pub struct B;
+}
+
+use foo::{self, bar::A, B$0};
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_glob_shadows_prelude() {
+        cov_mark::check!(convert_to_glob_would_shadow);
+        check_assist_not_applicable(
+            convert_to_glob_import,
+            r#"
+//- minicore: option
+mod foo {
+    pub struct Option;
+    pub struct A;
+}
+
+use foo::{A$0};
+
+fn f() -> core::option::Option<A> { None }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_glob_conflicts_with_another_import() {
+        cov_mark::check!(convert_to_glob_would_shadow);
+        check_assist_not_applicable(
+            convert_to_glob_import,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct C;
+}
+mod bar {
+    pub struct C;
+}
+
+use bar::*;
+use foo::{A$0};
+
+fn f(_: C) {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,621 @@
+use std::iter;
+
+use ide_db::syntax_helpers::node_ext::single_let;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, HasName,
+    },
+    AstNode, T,
+};
+
+use crate::{
+    utils::{extract_trivial_expression, unwrap_trivial_block},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: convert_nested_match_to_tuple_match
+//
+// Merges the matches nested in the arms of a match into a single match on a tuple of both
+// scrutinees. `if let` expressions are treated like matches with a `_` arm for the `else` branch.
+//
+// ```
+// # //- minicore: copy
+// fn f(a: bool, b: bool) -> u8 {
+//     $0match a {
+//         true => match b {
+//             true => 3,
+//             false => 2,
+//         },
+//         false => 0,
+//     }
+// }
+// ```
+// ->
+// ```
+// fn f(a: bool, b: bool) -> u8 {
+//     match (a, b) {
+//         (true, true) => 3,
+//         (true, false) => 2,
+//         (false, _) => 0,
+//     }
+// }
+// ```
+pub(crate) fn convert_nested_match_to_tuple_match(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let keyword = ctx
+        .find_token_syntax_at_offset(T![match])
+        .or_else(|| ctx.find_token_syntax_at_offset(T![if]))?;
+    let expr = keyword.parent().and_then(ast::Expr::cast)?;
+    let (outer_scrutinee, outer_arms) = match_like(&expr)?;
+    // A guard failing on the outer arm continues with the next outer arm, which can't be
+    // expressed without evaluating the guard once for every inner arm.
+    if outer_arms.iter().any(|it| it.guard.is_some()) {
+        return None;
+    }
+
+    let mut inner_scrutinee: Option<ast::Expr> = None;
+    let mut nested_arms = Vec::with_capacity(outer_arms.len());
+    for arm in &outer_arms {
+        let bindings = binding_names(ctx, &arm.pat);
+        let nested = nested_match_like(&arm.body).filter(|(scrutinee, arms)| {
+            let same_scrutinee = inner_scrutinee
+                .as_ref()
+                .map_or(true, |it| it.syntax().text() == scrutinee.syntax().text());
+            // The outer bindings aren't available yet when evaluating the scrutinee, and can't be
+            // bound a second time by the inner patterns in the same tuple pattern.
+            let uses_bindings = scrutinee
+                .syntax()
+                .descendants()
+                .filter_map(ast::NameRef::cast)
+                .any(|it| bindings.iter().any(|name| it.text() == name.as_str()));
+            let rebinds = arms
+                .iter()
+                .flat_map(|it| binding_names(ctx, &it.pat))
+                .any(|it| bindings.contains(&it));
+            same_scrutinee && !uses_bindings && !rebinds
+        });
+        nested_arms.push(nested.map(|(scrutinee, arms)| {
+            inner_scrutinee.get_or_insert(scrutinee);
+            arms
+        }));
+    }
+    let inner_scrutinee = inner_scrutinee?;
+    if !is_simple_copy(ctx, &outer_scrutinee) || !is_simple_copy(ctx, &inner_scrutinee) {
+        return None;
+    }
+
+    let target = expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_nested_match_to_tuple_match", AssistKind::RefactorRewrite),
+        "Convert to match on a tuple",
+        target,
+        |edit| {
+            let mut arms = Vec::new();
+            for (arm, nested) in outer_arms.iter().zip(nested_arms) {
+                match nested {
+                    Some(nested) => arms.extend(nested.into_iter().map(|inner| {
+                        let pat = make::tuple_pat([arm.pat.clone(), inner.pat]);
+                        make::match_arm(iter::once(pat.into()), inner.guard, arm_body(&inner.body))
+                    })),
+                    None => {
+                        let pat = make::tuple_pat([arm.pat.clone(), make::wildcard_pat().into()]);
+                        let body = arm_body(&arm.body);
+                        arms.push(make::match_arm(iter::once(pat.into()), None, body))
+                    }
+                }
+            }
+            let scrutinee = make::expr_tuple([outer_scrutinee.clone(), inner_scrutinee.clone()]);
+            let match_expr = make::expr_match(scrutinee, make::match_arm_list(arms))
+                .indent(IndentLevel::from_node(expr.syntax()));
+            edit.replace(target, match_expr.to_string());
+        },
+    )
+}
+
+// Assist: convert_tuple_match_to_nested_match
+//
+// Splits a match on a tuple into a match on the first element, with matches on the remaining
+// elements nested in its arms. Only applies when the arms with the same first pattern are
+// adjacent and exhaustive on their own, so that no value falls through to another first pattern.
+//
+// ```
+// # //- minicore: copy
+// fn f(a: bool, b: bool) -> u8 {
+//     $0match (a, b) {
+//         (true, true) => 3,
+//         (true, false) => 2,
+//         (false, _) => 0,
+//     }
+// }
+// ```
+// ->
+// ```
+// fn f(a: bool, b: bool) -> u8 {
+//     match a {
+//         true => match b {
+//             true => 3,
+//             false => 2,
+//         }
+//         false => 0,
+//     }
+// }
+// ```
+pub(crate) fn convert_tuple_match_to_nested_match(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let match_expr =
+        ctx.find_token_syntax_at_offset(T![match])?.parent().and_then(ast::MatchExpr::cast)?;
+    let ast::Expr::TupleExpr(scrutinee) = match_expr.expr()? else { return None };
+    let elements: Vec<_> = scrutinee.fields().collect();
+    if elements.len() < 2 || !elements.iter().all(|it| is_simple_copy(ctx, it)) {
+        return None;
+    }
+
+    let inner_ty = match &elements[..] {
+        [_, inner] => Some(ctx.sema.type_of_expr(inner)?.original.strip_references()),
+        _ => None,
+    };
+    // Arms with the same first pattern, in order, and whether they are exhaustive so far.
+    let mut groups: Vec<(ast::Pat, Vec<Arm>, bool)> = Vec::new();
+    for arm in match_expr.match_arm_list()?.arms() {
+        let guard = match arm.guard() {
+            Some(guard) => Some(guard.condition()?),
+            None => None,
+        };
+        let pats: Vec<ast::Pat> = match arm.pat()? {
+            ast::Pat::TuplePat(it) => it.fields().collect(),
+            ast::Pat::WildcardPat(_) => {
+                iter::repeat_with(|| make::wildcard_pat().into()).take(elements.len()).collect()
+            }
+            _ => return None,
+        };
+        if pats.len() != elements.len() || pats.iter().any(|it| matches!(it, ast::Pat::RestPat(_)))
+        {
+            return None;
+        }
+        let (first, rest) = pats.split_first()?;
+        // Checked on the original patterns, as the semantics can't look into a new tuple pattern.
+        let covers_rest = guard.is_none() && rest.iter().all(|it| is_irrefutable(ctx, it));
+        let pat = match rest {
+            [pat] => pat.clone(),
+            _ => make::tuple_pat(rest.iter().cloned()).into(),
+        };
+        let inner = Arm { pat, guard, body: arm.expr()? };
+        match groups.last_mut() {
+            Some((pat, arms, exhaustive)) if pat.syntax().text() == first.syntax().text() => {
+                arms.push(inner);
+                *exhaustive |= covers_rest;
+            }
+            _ => {
+                if groups.iter().any(|(pat, ..)| pat.syntax().text() == first.syntax().text()) {
+                    return None;
+                }
+                groups.push((first.clone(), vec![inner], covers_rest));
+            }
+        }
+    }
+    let is_exhaustive = |(_, arms, exhaustive): &(_, Vec<Arm>, bool)| match &inner_ty {
+        Some(ty) => *exhaustive || covers_type(ctx, arms, ty),
+        None => *exhaustive,
+    };
+    if !groups.iter().all(is_exhaustive) {
+        return None;
+    }
+
+    let target = match_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_tuple_match_to_nested_match", AssistKind::RefactorRewrite),
+        "Convert to nested matches",
+        target,
+        |edit| {
+            let inner_scrutinee = match &elements[..] {
+                [_, inner] => inner.clone(),
+                _ => make::expr_tuple(elements[1..].iter().cloned()),
+            };
+            let arms = groups.into_iter().map(|(pat, arms, _)| {
+                let body = match &arms[..] {
+                    [Arm { pat, guard: None, body }] if is_wildcard(pat) => arm_body(body),
+                    _ => {
+                        let arms = arms.into_iter().map(|arm| {
+                            make::match_arm(iter::once(arm.pat), arm.guard, arm_body(&arm.body))
+                        });
+                        make::expr_match(inner_scrutinee.clone(), make::match_arm_list(arms))
+                            .indent(IndentLevel(1))
+                    }
+                };
+                make::match_arm(iter::once(pat), None, body)
+            });
+            let outer_scrutinee = elements[0].clone();
+            let new_match = make::expr_match(outer_scrutinee, make::match_arm_list(arms))
+                .indent(IndentLevel::from_node(match_expr.syntax()));
+            edit.replace(target, new_match.to_string());
+        },
+    )
+}
+
+struct Arm {
+    pat: ast::Pat,
+    guard: Option<ast::Expr>,
+    body: ast::Expr,
+}
+
+/// Returns the scrutinee and arms of a `match`, or of an `if let` seen as a match with a `_` arm
+/// for the `else` branch.
+fn match_like(expr: &ast::Expr) -> Option<(ast::Expr, Vec<Arm>)> {
+    match expr {
+        ast::Expr::MatchExpr(it) => {
+            let arms = it
+                .match_arm_list()?
+                .arms()
+                .map(|arm| {
+                    let guard = match arm.guard() {
+                        Some(guard) => Some(guard.condition()?),
+                        None => None,
+                    };
+                    Some(Arm { pat: arm.pat()?, guard, body: arm.expr()? })
+                })
+                .collect::<Option<_>>()?;
+            Some((it.expr()?, arms))
+        }
+        ast::Expr::IfExpr(it) => {
+            let let_expr = single_let(it.condition()?)?;
+            let else_body = match it.else_branch() {
+                Some(ast::ElseBranch::Block(block)) => unwrap_trivial_block(block),
+                Some(ast::ElseBranch::IfExpr(_)) => return None,
+                None => make::expr_empty_block(),
+            };
+            let arms = vec![
+                Arm {
+                    pat: let_expr.pat()?,
+                    guard: None,
+                    body: unwrap_trivial_block(it.then_branch()?),
+                },
+                Arm { pat: make::wildcard_pat().into(), guard: None, body: else_body },
+            ];
+            Some((let_expr.expr()?, arms))
+        }
+        _ => None,
+    }
+}
+
+/// Like `match_like`, for the body of an arm, which may wrap the match in a block.
+fn nested_match_like(body: &ast::Expr) -> Option<(ast::Expr, Vec<Arm>)> {
+    match body {
+        ast::Expr::BlockExpr(block) => match_like(&extract_trivial_expression(block)?),
+        _ => match_like(body),
+    }
+}
+
+fn arm_body(body: &ast::Expr) -> ast::Expr {
+    body.reset_indent().indent(IndentLevel(1))
+}
+
+fn binding_names(ctx: &AssistContext<'_>, pat: &ast::Pat) -> Vec<String> {
+    pat.syntax()
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .filter(|it| ctx.sema.resolve_bind_pat_to_const(it).is_none())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+/// Scrutinees are only moved between a tuple and separate matches if evaluating them has no side
+/// effects and copying them doesn't move anything.
+fn is_simple_copy(ctx: &AssistContext<'_>, expr: &ast::Expr) -> bool {
+    fn is_simple(expr: &ast::Expr) -> bool {
+        match expr {
+            ast::Expr::PathExpr(_) | ast::Expr::Literal(_) => true,
+            ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_simple(&it)),
+            ast::Expr::RefExpr(it) => it.expr().map_or(false, |it| is_simple(&it)),
+            ast::Expr::ParenExpr(it) => it.expr().map_or(false, |it| is_simple(&it)),
+            _ => false,
+        }
+    }
+    is_simple(expr) && ctx.sema.type_of_expr(expr).map_or(false, |it| it.original.is_copy(ctx.db()))
+}
+
+fn is_wildcard(pat: &ast::Pat) -> bool {
+    match pat {
+        ast::Pat::WildcardPat(_) => true,
+        ast::Pat::TuplePat(it) => it.fields().all(|it| is_wildcard(&it)),
+        _ => false,
+    }
+}
+
+/// A conservative check whether the unguarded arms match every value of type `ty`. Besides
+/// irrefutable patterns, this only knows about `bool`s and enum variants.
+fn covers_type(ctx: &AssistContext<'_>, arms: &[Arm], ty: &hir::Type) -> bool {
+    let pats: Vec<ast::Pat> = arms
+        .iter()
+        .filter(|it| it.guard.is_none())
+        .flat_map(|it| match &it.pat {
+            ast::Pat::OrPat(pat) => pat.pats().collect(),
+            pat => vec![pat.clone()],
+        })
+        .collect();
+    if ty.is_bool() {
+        let has_literal = |text: &str| {
+            pats.iter()
+                .any(|it| matches!(it, ast::Pat::LiteralPat(it) if it.syntax().text() == text))
+        };
+        return has_literal("true") && has_literal("false");
+    }
+    match ty.as_adt() {
+        Some(hir::Adt::Enum(enum_)) => {
+            let covered: Vec<_> = pats.iter().filter_map(|it| variant_of(ctx, it)).collect();
+            enum_.variants(ctx.db()).iter().all(|it| covered.contains(it))
+        }
+        _ => false,
+    }
+}
+
+fn is_irrefutable(ctx: &AssistContext<'_>, pat: &ast::Pat) -> bool {
+    match pat {
+        ast::Pat::WildcardPat(_) | ast::Pat::RestPat(_) => true,
+        ast::Pat::IdentPat(it) => {
+            ctx.sema.resolve_bind_pat_to_const(it).is_none()
+                && it.pat().map_or(true, |it| is_irrefutable(ctx, &it))
+        }
+        ast::Pat::TuplePat(it) => it.fields().all(|it| is_irrefutable(ctx, &it)),
+        ast::Pat::ParenPat(it) => it.pat().map_or(false, |it| is_irrefutable(ctx, &it)),
+        ast::Pat::RefPat(it) => it.pat().map_or(false, |it| is_irrefutable(ctx, &it)),
+        _ => false,
+    }
+}
+
+/// Returns the variant `pat` matches, if it matches all values of that variant.
+fn variant_of(ctx: &AssistContext<'_>, pat: &ast::Pat) -> Option<hir::Variant> {
+    let def = match pat {
+        ast::Pat::IdentPat(it) => ctx.sema.resolve_bind_pat_to_const(it)?,
+        ast::Pat::PathPat(it) => path_def(ctx, &it.path()?)?,
+        ast::Pat::TupleStructPat(it) => {
+            if !it.fields().all(|it| is_irrefutable(ctx, &it)) {
+                return None;
+            }
+            path_def(ctx, &it.path()?)?
+        }
+        ast::Pat::RecordPat(it) => {
+            let fields = it.record_pat_field_list()?;
+            if !fields.fields().all(|it| it.pat().map_or(false, |it| is_irrefutable(ctx, &it))) {
+                return None;
+            }
+            path_def(ctx, &it.path()?)?
+        }
+        _ => return None,
+    };
+    match def {
+        hir::ModuleDef::Variant(it) => Some(it),
+        _ => None,
+    }
+}
+
+fn path_def(ctx: &AssistContext<'_>, path: &ast::Path) -> Option<hir::ModuleDef> {
+    match ctx.sema.resolve_path(path)? {
+        hir::PathResolution::Def(it) => Some(it),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn merge_nested_match() {
+        check_assist(
+            convert_nested_match_to_tuple_match,
+            r#"
+//- minicore: copy, option
+fn f(a: Option<u8>, b: bool) -> u8 {
+    $0match a {
+        Some(x) => match b {
+            true if x > 2 => x,
+            _ => {
+                0
+            }
+        },
+        None => 1,
+    }
+}
+"#,
+            r#"
+fn f(a: Option<u8>, b: bool) -> u8 {
+    match (a, b) {
+        (Some(x), true) if x > 2 => x,
+        (Some(x), _) => {
+            0
+        }
+        (None, _) => 1,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_nested_if_let() {
+        check_assist(
+            convert_nested_match_to_tuple_match,
+            r#"
+//- minicore: copy, option
+fn f(a: Option<u8>, b: Option<u8>) -> u8 {
+    $0if let Some(x) = a {
+        if let Some(y) = b {
+            x + y
+        } else {
+            x
+        }
+    } else {
+        0
+    }
+}
+"#,
+            r#"
+fn f(a: Option<u8>, b: Option<u8>) -> u8 {
+    match (a, b) {
+        (Some(x), Some(y)) => x + y,
+        (Some(x), _) => x,
+        (_, _) => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_not_applicable_when_scrutinee_uses_outer_binding() {
+        check_assist_not_applicable(
+            convert_nested_match_to_tuple_match,
+            r#"
+//- minicore: copy, option
+fn f(a: Option<u8>) -> u8 {
+    $0match a {
+        Some(x) => match x {
+            0 => 1,
+            _ => 2,
+        },
+        None => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_not_applicable_with_outer_guard() {
+        check_assist_not_applicable(
+            convert_nested_match_to_tuple_match,
+            r#"
+//- minicore: copy
+fn f(a: u8, b: bool) -> u8 {
+    $0match a {
+        0 if b => match b {
+            true => 1,
+            false => 2,
+        },
+        _ => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_not_applicable_to_non_copy_scrutinee() {
+        check_assist_not_applicable(
+            convert_nested_match_to_tuple_match,
+            r#"
+//- minicore: copy, option
+struct S;
+fn f(a: Option<S>, b: bool) -> u8 {
+    $0match a {
+        Some(_) => match b {
+            true => 1,
+            false => 2,
+        },
+        None => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_enum_coverage() {
+        check_assist(
+            convert_tuple_match_to_nested_match,
+            r#"
+//- minicore: copy, option
+fn f(a: bool, b: Option<u8>) -> u8 {
+    $0match (a, b) {
+        (true, Some(x)) if x > 2 => x,
+        (true, Some(_)) => 2,
+        (true, None) => 1,
+        _ => 0,
+    }
+}
+"#,
+            r#"
+fn f(a: bool, b: Option<u8>) -> u8 {
+    match a {
+        true => match b {
+            Some(x) if x > 2 => x,
+            Some(_) => 2,
+            None => 1,
+        }
+        _ => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_three_elements() {
+        check_assist(
+            convert_tuple_match_to_nested_match,
+            r#"
+//- minicore: copy
+fn f(a: bool, b: u8, c: u8) -> u8 {
+    $0match (a, b, c) {
+        (true, 0, y) => y,
+        (true, x, _) => x,
+        (false, _, _) => 0,
+    }
+}
+"#,
+            r#"
+fn f(a: bool, b: u8, c: u8) -> u8 {
+    match a {
+        true => match (b, c) {
+            (0, y) => y,
+            (x, _) => x,
+        }
+        false => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_not_applicable_when_group_falls_through() {
+        check_assist_not_applicable(
+            convert_tuple_match_to_nested_match,
+            r#"
+//- minicore: copy
+fn f(a: bool, b: u8) -> u8 {
+    $0match (a, b) {
+        (true, 0) => 1,
+        (_, _) => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_not_applicable_with_non_adjacent_groups() {
+        check_assist_not_applicable(
+            convert_tuple_match_to_nested_match,
+            r#"
+//- minicore: copy
+fn f(a: bool, b: bool) -> u8 {
+    $0match (a, b) {
+        (true, true) => 3,
+        (false, _) => 0,
+        (true, false) => 2,
+    }
+}
+"#,
+        );
+    }
+}
@@ -1,15 +1,25 @@
 use either::Either;
-use ide_db::defs::{Definition, NameRefClass};
+use ide_db::{
+    defs::Definition,
+    search::{FileReference, UsageSearchResult},
+    FxHashMap, FxHashSet,
+};
+use itertools::Itertools;
 use syntax::{
-    ast::{self, AstNode, HasGenericParams, HasVisibility},
+    ast::{self, AstNode, HasGenericParams, HasName, HasVisibility},
     match_ast, SyntaxNode,
 };
 
-use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    assist_context::SourceChangeBuilder, utils::suggest_name, AssistContext, AssistId, AssistKind,
+    Assists,
+};
 
 // Assist: convert_tuple_struct_to_named_struct
 //
 // Converts tuple struct to struct with named fields, and analogously for tuple enum variants.
+// Field names are taken from the bindings and variables used with the struct, or from the field
+// types, and all patterns and constructors are rewritten to use them.
 //
 // ```
 // struct Point$0(f32, f32);
@@ -30,19 +40,19 @@ use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, Assist
 // ```
 // ->
 // ```
-// struct Point { field1: f32, field2: f32 }
+// struct Point { x: f32, y: f32 }
 //
 // impl Point {
 //     pub fn new(x: f32, y: f32) -> Self {
-//         Point { field1: x, field2: y }
+//         Point { x, y }
 //     }
 //
 //     pub fn x(&self) -> f32 {
-//         self.field1
+//         self.x
 //     }
 //
 //     pub fn y(&self) -> f32 {
-//         self.field2
+//         self.y
 //     }
 // }
 // ```
@@ -57,8 +67,8 @@ pub(crate) fn convert_tuple_struct_to_named_struct(
         ast::FieldList::RecordFieldList(_) => return None,
     };
     let strukt_def = match &strukt {
-        Either::Left(s) => Either::Left(ctx.sema.to_def(s)?),
-        Either::Right(v) => Either::Right(ctx.sema.to_def(v)?),
+        Either::Left(s) => Definition::Adt(hir::Adt::Struct(ctx.sema.to_def(s)?)),
+        Either::Right(v) => Definition::Variant(ctx.sema.to_def(v)?),
     };
     let target = strukt.as_ref().either(|s| s.syntax(), |v| v.syntax()).text_range();
 
@@ -67,14 +77,70 @@ pub(crate) fn convert_tuple_struct_to_named_struct(
         "Convert to named struct",
         target,
         |edit| {
-            let names = generate_names(tuple_fields.fields());
+            let usages = strukt_def.usages(&ctx.sema).include_self_refs().all();
+            let names = generate_names(ctx, tuple_fields.fields(), &usages);
             edit_field_references(ctx, edit, tuple_fields.fields(), &names);
-            edit_struct_references(ctx, edit, strukt_def, &names);
+            edit_struct_references(ctx, edit, usages, &names);
             edit_struct_def(ctx, edit, &strukt, tuple_fields, names);
         },
     )
 }
 
+/// A use of the tuple struct that has to be rewritten.
+enum Usage {
+    /// `Foo(a, b)` in a pattern.
+    Pat(ast::TupleStructPat),
+    /// `Foo(a, b)` in an expression, constructing the struct.
+    Call { call: ast::CallExpr, path: ast::Path },
+    /// `Foo` used as a function value, like in `.map(Foo)`.
+    Fn(ast::PathExpr),
+}
+
+fn classify_usage(reference: &FileReference) -> Option<Usage> {
+    let name_ref = reference.name.as_name_ref()?;
+    let path = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?.parent_path();
+    // Paths like `Foo::new` refer to the struct, but don't use its constructor.
+    let parent = path.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::TupleStructPat(it) => Some(Usage::Pat(it)),
+            ast::PathExpr(path_expr) => {
+                let call = path_expr
+                    .syntax()
+                    .parent()
+                    .and_then(ast::CallExpr::cast)
+                    .filter(|it| it.expr().map_or(false, |it| it.syntax() == path_expr.syntax()));
+                match call {
+                    Some(call) => Some(Usage::Call { call, path }),
+                    None => Some(Usage::Fn(path_expr)),
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Pairs the subpatterns of a tuple struct pattern with the index of the field they match, taking
+/// a `..` into account. Also returns whether the pattern has a `..`.
+fn pat_fields(pat: &ast::TupleStructPat, field_count: usize) -> (Vec<(usize, ast::Pat)>, bool) {
+    let pats: Vec<_> = pat.fields().collect();
+    let (fields, has_rest) = match pats.iter().position(|it| matches!(it, ast::Pat::RestPat(_))) {
+        Some(rest) => {
+            let after_rest = &pats[rest + 1..];
+            let offset = field_count.saturating_sub(after_rest.len());
+            let fields = pats[..rest]
+                .iter()
+                .cloned()
+                .enumerate()
+                .chain(after_rest.iter().cloned().enumerate().map(|(idx, pat)| (offset + idx, pat)))
+                .collect::<Vec<_>>();
+            (fields, true)
+        }
+        None => (pats.into_iter().enumerate().collect(), false),
+    };
+    (fields.into_iter().filter(|(idx, _)| *idx < field_count).collect(), has_rest)
+}
+
 fn edit_struct_def(
     ctx: &AssistContext<'_>,
     edit: &mut SourceChangeBuilder,
@@ -120,84 +186,89 @@ fn edit_struct_def(
 fn edit_struct_references(
     ctx: &AssistContext<'_>,
     edit: &mut SourceChangeBuilder,
-    strukt: Either<hir::Struct, hir::Variant>,
+    usages: UsageSearchResult,
     names: &[ast::Name],
 ) {
-    let strukt_def = match strukt {
-        Either::Left(s) => Definition::Adt(hir::Adt::Struct(s)),
-        Either::Right(v) => Definition::Variant(v),
-    };
-    let usages = strukt_def.usages(&ctx.sema).include_self_refs().all();
-
-    let edit_node = |edit: &mut SourceChangeBuilder, node: SyntaxNode| -> Option<()> {
-        match_ast! {
-            match node {
-                ast::TupleStructPat(tuple_struct_pat) => {
-                    edit.replace(
-                        tuple_struct_pat.syntax().text_range(),
-                        ast::make::record_pat_with_fields(
-                            tuple_struct_pat.path()?,
-                            ast::make::record_pat_field_list(tuple_struct_pat.fields().zip(names).map(
-                                |(pat, name)| {
-                                    ast::make::record_pat_field(
-                                        ast::make::name_ref(&name.to_string()),
-                                        pat,
-                                    )
-                                },
-                            )),
-                        )
-                        .to_string(),
-                    );
-                },
-                // for tuple struct creations like Foo(42)
-                ast::CallExpr(call_expr) => {
-                    let path = call_expr.syntax().descendants().find_map(ast::PathExpr::cast).and_then(|expr| expr.path())?;
-
-                    // this also includes method calls like Foo::new(42), we should skip them
-                    if let Some(name_ref) = path.segment().and_then(|s| s.name_ref()) {
-                        match NameRefClass::classify(&ctx.sema, &name_ref) {
-                            Some(NameRefClass::Definition(Definition::SelfType(_))) => {},
-                            Some(NameRefClass::Definition(def)) if def == strukt_def => {},
-                            _ => return None,
-                        };
+    let name_ref = |name: &ast::Name| ast::make::name_ref(&name.to_string());
+
+    let edit_usage = |usage: Usage| -> Option<(SyntaxNode, String)> {
+        let replacement = match &usage {
+            Usage::Pat(tuple_struct_pat) => {
+                let (fields, has_rest) = pat_fields(tuple_struct_pat, names.len());
+                let mut fields = fields
+                    .into_iter()
+                    .map(|(idx, pat)| {
+                        let name = &names[idx];
+                        if is_plain_binding_of(&pat, name) {
+                            ast::make::record_pat_field_shorthand(name_ref(name))
+                        } else {
+                            ast::make::record_pat_field(name_ref(name), pat)
+                        }
+                    })
+                    .join(", ");
+                if has_rest {
+                    if !fields.is_empty() {
+                        fields.push_str(", ");
                     }
-
-                    let arg_list = call_expr.syntax().descendants().find_map(ast::ArgList::cast)?;
-
-                    edit.replace(
-                        ctx.sema.original_range(&node).range,
-                        ast::make::record_expr(
-                            path,
-                            ast::make::record_expr_field_list(arg_list.args().zip(names).map(
-                                |(expr, name)| {
-                                    ast::make::record_expr_field(
-                                        ast::make::name_ref(&name.to_string()),
-                                        Some(expr),
-                                    )
-                                },
-                            )),
-                        )
-                        .to_string(),
+                    fields.push_str("..");
+                }
+                format!("{} {{ {fields} }}", tuple_struct_pat.path()?)
+            }
+            // for tuple struct creations like Foo(42)
+            Usage::Call { call, path } => {
+                let fields = call.arg_list()?.args().zip(names).map(|(expr, name)| {
+                    let is_shorthand = matches!(
+                        &expr,
+                        ast::Expr::PathExpr(it)
+                            if it.path().map_or(false, |it| it.to_string() == name.to_string())
                     );
-                },
-                _ => return None,
+                    ast::make::record_expr_field(name_ref(name), (!is_shorthand).then_some(expr))
+                });
+                ast::make::record_expr(path.clone(), ast::make::record_expr_field_list(fields))
+                    .to_string()
             }
-        }
-        Some(())
+            // for the constructor used as a function, like `.map(Foo)`
+            Usage::Fn(path_expr) => {
+                let fields =
+                    names.iter().map(|name| ast::make::record_expr_field(name_ref(name), None));
+                let record = ast::make::record_expr(
+                    path_expr.path()?,
+                    ast::make::record_expr_field_list(fields),
+                );
+                format!("|{}| {record}", names.iter().join(", "))
+            }
+        };
+        let node = match usage {
+            Usage::Pat(it) => it.syntax().clone(),
+            Usage::Call { call, .. } => call.syntax().clone(),
+            Usage::Fn(it) => it.syntax().clone(),
+        };
+        Some((node, replacement))
     };
 
     for (file_id, refs) in usages {
         edit.edit_file(file_id);
         for r in refs {
-            for node in r.name.syntax().ancestors() {
-                if edit_node(edit, node).is_some() {
-                    break;
-                }
+            if let Some((node, replacement)) = classify_usage(&r).and_then(edit_usage) {
+                edit.replace(ctx.sema.original_range(&node).range, replacement);
             }
         }
     }
 }
 
+/// Whether `pat` just binds `name`, so it can be written as a shorthand field pattern.
+fn is_plain_binding_of(pat: &ast::Pat, name: &ast::Name) -> bool {
+    match pat {
+        ast::Pat::IdentPat(it) => {
+            it.ref_token().is_none()
+                && it.mut_token().is_none()
+                && it.pat().is_none()
+                && it.name().map_or(false, |it| it.text() == name.text())
+        }
+        _ => false,
+    }
+}
+
 fn edit_field_references(
     ctx: &AssistContext<'_>,
     edit: &mut SourceChangeBuilder,
@@ -222,16 +293,95 @@ fn edit_field_references(
     }
 }
 
-fn generate_names(fields: impl Iterator<Item = ast::TupleField>) -> Vec<ast::Name> {
+/// Names the fields after the bindings and variables most often used for them in patterns and
+/// constructors, falling back to names derived from the field types and then to `field1`, `field2`
+/// and so on.
+fn generate_names(
+    ctx: &AssistContext<'_>,
+    fields: impl Iterator<Item = ast::TupleField>,
+    usages: &UsageSearchResult,
+) -> Vec<ast::Name> {
+    let fields: Vec<_> = fields.collect();
+    let mut used_names: Vec<FxHashMap<String, usize>> = vec![FxHashMap::default(); fields.len()];
+    for (_, refs) in usages.iter() {
+        for usage in refs.iter().filter_map(classify_usage) {
+            let candidates: Vec<(usize, String)> = match usage {
+                Usage::Pat(pat) => pat_fields(&pat, fields.len())
+                    .0
+                    .into_iter()
+                    .filter_map(|(idx, pat)| Some((idx, binding_name(ctx, &pat)?)))
+                    .collect(),
+                Usage::Call { call, .. } => call
+                    .arg_list()
+                    .into_iter()
+                    .flat_map(|it| it.args())
+                    .enumerate()
+                    .take(fields.len())
+                    .filter_map(|(idx, arg)| Some((idx, local_name(ctx, &arg)?)))
+                    .collect(),
+                Usage::Fn(_) => continue,
+            };
+            for (idx, name) in candidates {
+                *used_names[idx].entry(name).or_default() += 1;
+            }
+        }
+    }
+
+    let mut taken = FxHashSet::default();
     fields
+        .iter()
+        .zip(used_names)
         .enumerate()
-        .map(|(i, _)| {
-            let idx = i + 1;
-            ast::make::name(&format!("field{idx}"))
+        .map(|(idx, (field, used_names))| {
+            let mut used_names: Vec<_> = used_names.into_iter().collect();
+            used_names.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+            let from_type = ctx
+                .sema
+                .to_def(field)
+                .and_then(|it| suggest_name::for_type(&it.ty(ctx.db()), ctx.db()));
+            let name = used_names
+                .into_iter()
+                .map(|(name, _)| name)
+                .chain(from_type)
+                .find(|it| !taken.contains(it))
+                .unwrap_or_else(|| {
+                    let mut name = format!("field{}", idx + 1);
+                    while taken.contains(&name) {
+                        name.push('_');
+                    }
+                    name
+                });
+            taken.insert(name.clone());
+            ast::make::name(&name)
         })
         .collect()
 }
 
+/// Returns the name bound by `pat`, if it is a plain binding.
+fn binding_name(ctx: &AssistContext<'_>, pat: &ast::Pat) -> Option<String> {
+    let ast::Pat::IdentPat(pat) = pat else { return None };
+    if pat.pat().is_some() || ctx.sema.resolve_bind_pat_to_const(pat).is_some() {
+        return None;
+    }
+    non_empty_name(pat.name()?.text().trim_start_matches('_'))
+}
+
+/// Returns the name of the local variable `expr` consists of, if any.
+fn local_name(ctx: &AssistContext<'_>, expr: &ast::Expr) -> Option<String> {
+    let ast::Expr::PathExpr(expr) = expr else { return None };
+    let path = expr.path()?;
+    match ctx.sema.resolve_path(&path)? {
+        hir::PathResolution::Local(_) => {
+            non_empty_name(path.as_single_name_ref()?.text().trim_start_matches('_'))
+        }
+        _ => None,
+    }
+}
+
+fn non_empty_name(name: &str) -> Option<String> {
+    (!name.is_empty()).then(|| name.to_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -288,11 +438,11 @@ impl A {
 }"#,
             r#"
 struct Inner;
-struct A { field1: Inner }
+struct A { inner: Inner }
 
 impl A {
     fn new(inner: Inner) -> A {
-        A { field1: inner }
+        A { inner }
     }
 
     fn new_with_default() -> A {
@@ -300,7 +450,7 @@ impl A {
     }
 
     fn into_inner(self) -> Inner {
-        self.field1
+        self.inner
     }
 }"#,
         );
@@ -329,11 +479,11 @@ impl A {
 }"#,
             r#"
 struct Inner;
-struct A { field1: Inner }
+struct A { inner: Inner }
 
 impl A {
     fn new(inner: Inner) -> Self {
-        Self { field1: inner }
+        Self { inner }
     }
 
     fn new_with_default() -> Self {
@@ -341,7 +491,7 @@ impl A {
     }
 
     fn into_inner(self) -> Inner {
-        self.field1
+        self.inner
     }
 }"#,
         );
@@ -368,16 +518,16 @@ impl A {
 }"#,
             r#"
 struct Inner;
-struct A { field1: Inner }
+struct A { first: Inner }
 
 impl A {
     fn into_inner(self) -> Inner {
-        let A { field1: first } = self;
+        let A { first } = self;
         first
     }
 
     fn into_inner_via_self(self) -> Inner {
-        let Self { field1: first } = self;
+        let Self { first } = self;
         first
     }
 }"#,
@@ -446,20 +596,20 @@ impl Outer {
     }
 }"#,
             r#"
-struct Inner { field1: u32 }
+struct Inner { x: u32 }
 struct Outer(Inner);
 
 impl Outer {
     fn new() -> Self {
-        Self(Inner { field1: 42 })
+        Self(Inner { x: 42 })
     }
 
     fn into_inner(self) -> u32 {
-        (self.0).field1
+        (self.0).x
     }
 
     fn into_inner_destructed(self) -> u32 {
-        let Outer(Inner { field1: x }) = self;
+        let Outer(Inner { x }) = self;
         x
     }
 }"#,
@@ -487,19 +637,19 @@ impl Outer {
 }"#,
             r#"
 struct Inner(u32);
-struct Outer { field1: Inner }
+struct Outer { inner: Inner }
 
 impl Outer {
     fn new() -> Self {
-        Self { field1: Inner(42) }
+        Self { inner: Inner(42) }
     }
 
     fn into_inner(self) -> u32 {
-        (self.field1).0
+        (self.inner).0
     }
 
     fn into_inner_destructed(self) -> u32 {
-        let Outer { field1: Inner(x) } = self;
+        let Outer { inner: Inner(x) } = self;
         x
     }
 }"#,
@@ -526,14 +676,14 @@ fn f() {
             r#"
 //- /main.rs
 struct Inner;
-struct A { field1: Inner }
+struct A { inner: Inner }
 
 mod foo;
 
 //- /foo.rs
 use crate::{A, Inner};
 fn f() {
-    let a = A { field1: Inner };
+    let a = A { inner: Inner };
 }
 "#,
         );
@@ -618,12 +768,12 @@ impl A {
 }"#,
             r#"
 enum A {
-    Variant { field1: usize },
+    Variant { value: usize },
 }
 
 impl A {
     fn new(value: usize) -> A {
-        A::Variant { field1: value }
+        A::Variant { value }
     }
 
     fn new_with_default() -> A {
@@ -632,7 +782,7 @@ impl A {
 
     fn value(self) -> usize {
         match self {
-            A::Variant { field1: value } => value,
+            A::Variant { value } => value,
         }
     }
 }"#,
@@ -665,12 +815,12 @@ impl A {
 }"#,
             r#"
 enum A {
-    Variant { field1: usize },
+    Variant { value: usize },
 }
 
 impl A {
     fn new(value: usize) -> A {
-        Self::Variant { field1: value }
+        Self::Variant { value }
     }
 
     fn new_with_default() -> A {
@@ -679,7 +829,7 @@ impl A {
 
     fn value(self) -> usize {
         match self {
-            Self::Variant { field1: value } => value,
+            Self::Variant { value } => value,
         }
     }
 }"#,
@@ -708,17 +858,17 @@ impl A {
 }"#,
             r#"
 enum A {
-    Variant { field1: usize },
+    Variant { first: usize },
 }
 
 impl A {
     fn into_inner(self) -> usize {
-        let A::Variant { field1: first } = self;
+        let A::Variant { first } = self;
         first
     }
 
     fn into_inner_via_self(self) -> usize {
-        let Self::Variant { field1: first } = self;
+        let Self::Variant { first } = self;
         first
     }
 }"#,
@@ -749,7 +899,7 @@ impl Outer {
 }"#,
             r#"
 enum Inner {
-    Variant { field1: usize },
+    Variant { x: usize },
 }
 enum Outer {
     Variant(Inner),
@@ -757,11 +907,11 @@ enum Outer {
 
 impl Outer {
     fn new() -> Self {
-        Self::Variant(Inner::Variant { field1: 42 })
+        Self::Variant(Inner::Variant { x: 42 })
     }
 
     fn into_inner_destructed(self) -> u32 {
-        let Outer::Variant(Inner::Variant { field1: x }) = self;
+        let Outer::Variant(Inner::Variant { x }) = self;
         x
     }
 }"#,
@@ -792,16 +942,16 @@ enum Inner {
     Variant(usize),
 }
 enum Outer {
-    Variant { field1: Inner },
+    Variant { inner: Inner },
 }
 
 impl Outer {
     fn new() -> Self {
-        Self::Variant { field1: Inner::Variant(42) }
+        Self::Variant { inner: Inner::Variant(42) }
     }
 
     fn into_inner_destructed(self) -> u32 {
-        let Outer::Variant { field1: Inner::Variant(x) } = self;
+        let Outer::Variant { inner: Inner::Variant(x) } = self;
         x
     }
 }"#,
@@ -831,7 +981,7 @@ fn f() {
 //- /main.rs
 struct Inner;
 enum A {
-    Variant { field1: Inner },
+    Variant { inner: Inner },
 }
 
 mod foo;
@@ -839,7 +989,7 @@ mod foo;
 //- /foo.rs
 use crate::{A, Inner};
 fn f() {
-    let a = A::Variant { field1: Inner };
+    let a = A::Variant { inner: Inner };
 }
 "#,
         );
@@ -868,7 +1018,7 @@ fn f() {
 //- /main.rs
 struct Inner;
 enum A {
-    Variant { field1: Inner },
+    Variant { inner: Inner },
 }
 
 mod foo;
@@ -876,7 +1026,123 @@ mod foo;
 //- /foo.rs
 use crate::{A::Variant, Inner};
 fn f() {
-    let a = Variant { field1: Inner };
+    let a = Variant { inner: Inner };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_variant_with_names_from_usages() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+enum Error {
+    $0Io(u8, String),
+}
+
+fn make(code: u8, name: String) -> Error {
+    Error::Io(code, name)
+}
+
+fn describe(error: Error) -> String {
+    match error {
+        Error::Io(code, name) if code == 0 => name,
+        Error::Io(_, name) => name,
+    }
+}
+"#,
+            r#"
+enum Error {
+    Io { code: u8, name: String },
+}
+
+fn make(code: u8, name: String) -> Error {
+    Error::Io { code, name }
+}
+
+fn describe(error: Error) -> String {
+    match error {
+        Error::Io { code, name } if code == 0 => name,
+        Error::Io { code: _, name } => name,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_variant_with_rest_patterns() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+enum E {
+    $0V(u8, u16, u32),
+}
+
+fn f(e: &E) -> u32 {
+    let E::V(a, ..) = e;
+    let E::V(.., c) = e;
+    let E::V(..) = e;
+    *c
+}
+"#,
+            r#"
+enum E {
+    V { a: u8, field2: u16, c: u32 },
+}
+
+fn f(e: &E) -> u32 {
+    let E::V { a, .. } = e;
+    let E::V { c, .. } = e;
+    let E::V { .. } = e;
+    *c
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_duplicate_names_from_usages() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Pair$0(u8, u8);
+
+fn f(x: u8) -> Pair {
+    Pair(x, x)
+}
+"#,
+            r#"
+struct Pair { x: u8, field2: u8 }
+
+fn f(x: u8) -> Pair {
+    Pair { x, field2: x }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_variant_used_as_function() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+enum E {
+    $0V(u8),
+}
+
+fn f(it: Option<u8>) -> Option<E> {
+    it.map(E::V)
+}
+"#,
+            r#"
+enum E {
+    V { field1: u8 },
+}
+
+fn f(it: Option<u8>) -> Option<E> {
+    it.map(|field1| E::V { field1 })
 }
 "#,
         );
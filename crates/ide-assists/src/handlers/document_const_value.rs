@@ -0,0 +1,222 @@
+use hir::Semantics;
+use ide_db::RootDatabase;
+use syntax::{
+    ast::{self, edit::IndentLevel, HasDocComments},
+    AstNode, AstToken,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: document_const_value
+//
+// Documents the evaluated value of a constant or a static, or updates the value documented by a
+// previous run.
+//
+// ```
+// const MASK$0: u32 = 1 << 13 | 1 << 12 | 0x40;
+// ```
+// ->
+// ```
+// /// = 0x3040 (12352)
+// const MASK: u32 = 1 << 13 | 1 << 12 | 0x40;
+// ```
+pub(crate) fn document_const_value(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let item = name.syntax().parent().and_then(ast::Item::cast)?;
+    let (value, doc_comments) = match &item {
+        ast::Item::Const(it) => (render_const(&ctx.sema, it)?, it.doc_comments()),
+        ast::Item::Static(it) => (render_static(&ctx.sema, it)?, it.doc_comments()),
+        _ => return None,
+    };
+    let doc_comments: Vec<_> = doc_comments.filter(|it| it.is_outer()).collect();
+    let marker = doc_comments.iter().find(|it| is_value_marker(it));
+
+    let indent = IndentLevel::from_node(item.syntax());
+    let new_marker = format!("/// = {value}");
+    let target = item.syntax().text_range();
+    match marker {
+        Some(marker) => {
+            if marker.text() == new_marker {
+                return None;
+            }
+            let range = marker.syntax().text_range();
+            acc.add(
+                AssistId("document_const_value", AssistKind::Generate),
+                "Update the documented value",
+                target,
+                |builder| builder.replace(range, new_marker),
+            )
+        }
+        None => acc.add(
+            AssistId("document_const_value", AssistKind::Generate),
+            "Document the value",
+            target,
+            |builder| match doc_comments.last() {
+                // Keep the value in a paragraph of its own.
+                Some(last) => builder.insert(
+                    last.syntax().text_range().end(),
+                    format!("\n{indent}///\n{indent}{new_marker}"),
+                ),
+                None => builder.insert(target.start(), format!("{new_marker}\n{indent}")),
+            },
+        ),
+    }
+}
+
+/// Returns whether `comment` is a `/// = value` comment added by this assist.
+fn is_value_marker(comment: &ast::Comment) -> bool {
+    comment.kind().shape.is_line()
+        && comment.doc_comment().map_or(false, |text| text.trim_start().starts_with("= "))
+}
+
+fn render_const(sema: &Semantics<'_, RootDatabase>, it: &ast::Const) -> Option<String> {
+    let def = sema.to_def(it)?;
+    let db = sema.db;
+    render_value(&def.ty(db), || def.eval_scalar(db), || def.render_eval(db).ok())
+}
+
+fn render_static(sema: &Semantics<'_, RootDatabase>, it: &ast::Static) -> Option<String> {
+    let def = sema.to_def(it)?;
+    let db = sema.db;
+    render_value(&def.ty(db), || def.eval_scalar(db), || def.render_eval(db).ok())
+}
+
+/// Renders integers in hexadecimal followed by their decimal value, which is what register maps
+/// and protocol tables want to read, and other values like hover does.
+fn render_value(
+    ty: &hir::Type,
+    scalar: impl FnOnce() -> Option<i128>,
+    rendered: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    if !ty.is_int_or_uint() {
+        return rendered();
+    }
+    let value = scalar()?;
+    Some(if (0..10).contains(&value) || value < 0 {
+        value.to_string()
+    } else {
+        format!("{value:#X} ({value})")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn documents_integers() {
+        check_assist(
+            document_const_value,
+            r#"
+const BASE: u32 = 0x1000;
+mod regs {
+    pub const CTRL$0: u32 = super::BASE + 0x20 * 4;
+}
+"#,
+            r#"
+const BASE: u32 = 0x1000;
+mod regs {
+    /// = 0x1080 (4224)
+    pub const CTRL: u32 = super::BASE + 0x20 * 4;
+}
+"#,
+        );
+        check_assist(
+            document_const_value,
+            r#"
+const SMALL$0: i8 = 2 - 5;
+"#,
+            r#"
+/// = -3
+const SMALL: i8 = 2 - 5;
+"#,
+        );
+    }
+
+    #[test]
+    fn documents_statics_and_other_values() {
+        check_assist(
+            document_const_value,
+            r#"
+static LIMIT$0: u16 = 8000;
+"#,
+            r#"
+/// = 0x1F40 (8000)
+static LIMIT: u16 = 8000;
+"#,
+        );
+        check_assist(
+            document_const_value,
+            r#"
+const ENABLED$0: bool = 3 > 2;
+"#,
+            r#"
+/// = true
+const ENABLED: bool = 3 > 2;
+"#,
+        );
+    }
+
+    #[test]
+    fn appends_to_existing_docs() {
+        check_assist(
+            document_const_value,
+            r#"
+/// The timeout, in milliseconds.
+#[allow(dead_code)]
+const TIMEOUT$0: u32 = 8 * 1000;
+"#,
+            r#"
+/// The timeout, in milliseconds.
+///
+/// = 0x1F40 (8000)
+#[allow(dead_code)]
+const TIMEOUT: u32 = 8 * 1000;
+"#,
+        );
+    }
+
+    #[test]
+    fn updates_existing_value() {
+        check_assist(
+            document_const_value,
+            r#"
+/// The timeout, in milliseconds.
+///
+/// = 0x1388 (5000)
+const TIMEOUT$0: u32 = 8 * 1000;
+"#,
+            r#"
+/// The timeout, in milliseconds.
+///
+/// = 0x1F40 (8000)
+const TIMEOUT: u32 = 8 * 1000;
+"#,
+        );
+        check_assist_not_applicable(
+            document_const_value,
+            r#"
+/// = 0x1F40 (8000)
+const TIMEOUT$0: u32 = 8 * 1000;
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unevaluable_values() {
+        check_assist_not_applicable(
+            document_const_value,
+            r#"
+const FOO$0: u32 = unknown();
+"#,
+        );
+        check_assist_not_applicable(
+            document_const_value,
+            r#"
+fn foo$0() {}
+"#,
+        );
+    }
+}
@@ -6,7 +6,7 @@ use ide_db::{
 };
 use stdx::never;
 use syntax::{
-    ast::{self, make},
+    ast::{self, make, HasVisibility},
     ted, AstNode, Direction, SyntaxNode, SyntaxToken, T,
 };
 
@@ -17,7 +17,8 @@ use crate::{
 
 // Assist: expand_glob_import
 //
-// Expands glob imports.
+// Expands glob imports into the names which are actually used. The names of a private glob are
+// looked up in its module and submodules, and those of a `pub` glob in the whole workspace.
 //
 // ```
 // mod foo {
@@ -53,7 +54,8 @@ pub(crate) fn expand_glob_import(acc: &mut Assists, ctx: &AssistContext<'_>) ->
     let current_module = current_scope.module();
 
     let refs_in_target = find_refs_in_mod(ctx, target_module, current_module)?;
-    let imported_defs = find_imported_defs(ctx, star)?;
+    let imported_defs = find_imported_defs(ctx, star.clone())?;
+    let search_scope = glob_search_scope(ctx, &star, current_module)?;
 
     let target = parent.either(|n| n.syntax().clone(), |n| n.syntax().clone());
     acc.add(
@@ -63,7 +65,8 @@ pub(crate) fn expand_glob_import(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         |builder| {
             let use_tree = builder.make_mut(use_tree);
 
-            let names_to_import = find_names_to_import(ctx, refs_in_target, imported_defs);
+            let names_to_import =
+                find_names_to_import(ctx, &search_scope, refs_in_target, imported_defs);
             let expanded = make::use_tree_list(names_to_import.iter().map(|n| {
                 let path = make::ext::ident_path(&n.to_string());
                 make::use_tree(path, None, None, false)
@@ -112,9 +115,27 @@ fn find_parent_and_path(
     }
 }
 
-fn def_is_referenced_in(def: Definition, ctx: &AssistContext<'_>) -> bool {
-    let search_scope = SearchScope::single_file(ctx.file_id());
-    def.usages(&ctx.sema).in_scope(search_scope).at_least_one()
+/// Returns the scope where the names imported by the glob can be used, according to the
+/// visibility of its `use` item.
+fn glob_search_scope(
+    ctx: &AssistContext<'_>,
+    star: &SyntaxToken,
+    current_module: Module,
+) -> Option<SearchScope> {
+    let use_item = star.parent_ancestors().find_map(ast::Use::cast)?;
+    let db = ctx.db();
+    let scope = match use_item.visibility() {
+        None => SearchScope::module_and_children(db, current_module),
+        Some(vis) if vis.l_paren_token().is_none() => {
+            SearchScope::reverse_dependencies(db, current_module.krate())
+        }
+        Some(_) => SearchScope::krate(db, current_module.krate()),
+    };
+    Some(scope)
+}
+
+fn def_is_referenced_in(def: Definition, ctx: &AssistContext<'_>, scope: &SearchScope) -> bool {
+    def.usages(&ctx.sema).in_scope(scope.clone()).at_least_one()
 }
 
 #[derive(Debug, Clone)]
@@ -139,7 +160,7 @@ impl Ref {
 struct Refs(Vec<Ref>);
 
 impl Refs {
-    fn used_refs(&self, ctx: &AssistContext<'_>) -> Refs {
+    fn used_refs(&self, ctx: &AssistContext<'_>, scope: &SearchScope) -> Refs {
         Refs(
             self.0
                 .clone()
@@ -148,7 +169,7 @@ impl Refs {
                     if let Definition::Trait(tr) = r.def {
                         if tr.items(ctx.db()).into_iter().any(|ai| {
                             if let AssocItem::Function(f) = ai {
-                                def_is_referenced_in(Definition::Function(f), ctx)
+                                def_is_referenced_in(Definition::Function(f), ctx, scope)
                             } else {
                                 false
                             }
@@ -157,7 +178,7 @@ impl Refs {
                         }
                     }
 
-                    def_is_referenced_in(r.def, ctx)
+                    def_is_referenced_in(r.def, ctx, scope)
                 })
                 .collect(),
         )
@@ -240,10 +261,11 @@ fn find_imported_defs(ctx: &AssistContext<'_>, star: SyntaxToken) -> Option<Vec<
 
 fn find_names_to_import(
     ctx: &AssistContext<'_>,
+    scope: &SearchScope,
     refs_in_target: Refs,
     imported_defs: Vec<Definition>,
 ) -> Vec<Name> {
-    let used_refs = refs_in_target.used_refs(ctx).filter_out_by_defs(imported_defs);
+    let used_refs = refs_in_target.used_refs(ctx, scope).filter_out_by_defs(imported_defs);
     used_refs.0.iter().map(|r| r.visible_name.clone()).collect()
 }
 
@@ -816,6 +838,72 @@ fn main() {
         );
     }
 
+    #[test]
+    fn expanding_glob_import_with_uses_in_submodules() {
+        check_assist(
+            expand_glob_import,
+            r#"
+//- /main.rs
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+mod child;
+
+use foo::*$0;
+
+fn f() {}
+
+//- /child.rs
+use super::Bar;
+
+fn g(_: Bar) {}
+"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+mod child;
+
+use foo::Bar;
+
+fn f() {}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn expanding_pub_glob_import_with_uses_in_other_crates() {
+        check_assist(
+            expand_glob_import,
+            r#"
+//- /lib.rs crate:foo
+mod inner {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+pub use inner::*$0;
+
+//- /main.rs crate:main deps:foo
+fn main() {
+    let _ = foo::Baz;
+}
+"#,
+            r#"
+mod inner {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+pub use inner::Baz;
+
+"#,
+        );
+    }
+
     #[test]
     fn expanding_is_not_applicable_if_target_module_is_not_accessible_from_current_scope() {
         check_assist_not_applicable(
@@ -1,4 +1,10 @@
-use hir::TypeInfo;
+use hir::{ModuleDef, SemanticsScope, TypeInfo};
+use ide_db::{
+    helpers::mod_path_to_ast,
+    imports::insert_use::{insert_use, ImportScope},
+    FxHashSet,
+};
+use itertools::Itertools;
 use stdx::format_to;
 use syntax::{
     ast::{self, AstNode},
@@ -10,7 +16,10 @@ use syntax::{
     SyntaxNode,
 };
 
-use crate::{utils::suggest_name, AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::{suggest_name, unique_local_name},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: extract_variable
 //
@@ -47,6 +56,16 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
         .take_while(|it| ctx.selection_trimmed().contains_range(it.text_range()))
         .find_map(valid_target_expr)?;
 
+    // Moving a place out of a borrow would change which value gets borrowed, so we extract the
+    // borrow itself instead.
+    let to_extract = match to_extract.syntax().parent().and_then(ast::RefExpr::cast) {
+        Some(ref_expr) if is_place_expr(&to_extract) => {
+            cov_mark::hit!(test_extract_var_borrowed_place);
+            ast::Expr::RefExpr(ref_expr)
+        }
+        _ => to_extract,
+    };
+
     let ty = ctx.sema.type_of_expr(&to_extract).map(TypeInfo::adjusted);
     if matches!(&ty, Some(ty_info) if ty_info.is_unit()) {
         return None;
@@ -57,7 +76,7 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
         .as_ref()
         .map_or(false, |it| matches!(it, ast::Expr::FieldExpr(_) | ast::Expr::MethodCallExpr(_)));
 
-    let reference_modifier = match ty.filter(|_| needs_adjust) {
+    let reference_modifier = match ty.as_ref().filter(|_| needs_adjust) {
         Some(receiver_type) if receiver_type.is_mutable_reference() => "&mut ",
         Some(receiver_type) if receiver_type.is_reference() => "&",
         _ => "",
@@ -70,39 +89,64 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
 
     let anchor = Anchor::from(&to_extract)?;
     let indent = anchor.syntax().prev_sibling_or_token()?.as_token()?.clone();
+    let scope = ctx.sema.scope(to_extract.syntax())?;
+    let field_shorthand = to_extract
+        .syntax()
+        .parent()
+        .and_then(ast::RecordExprField::cast)
+        .and_then(|field| field.name_ref());
+    let var_name = match &field_shorthand {
+        Some(it) => it.to_string(),
+        None => unique_local_name(&scope, suggest_name::for_variable(&to_extract, &ctx.sema)),
+    };
+    let ascription = ty
+        .filter(|_| ctx.config.assist_extract_variable_ascribe_type)
+        .and_then(|ty| ascribed_type(ctx, &scope, &ty));
     let target = to_extract.syntax().text_range();
     acc.add(
         AssistId("extract_variable", AssistKind::RefactorExtract),
         "Extract into variable",
         target,
         move |edit| {
-            let field_shorthand =
-                match to_extract.syntax().parent().and_then(ast::RecordExprField::cast) {
-                    Some(field) => field.name_ref(),
-                    None => None,
-                };
-
             let mut buf = String::new();
 
-            let var_name = match &field_shorthand {
-                Some(it) => it.to_string(),
-                None => suggest_name::for_variable(&to_extract, &ctx.sema),
-            };
             let expr_range = match &field_shorthand {
                 Some(it) => it.syntax().text_range().cover(to_extract.syntax().text_range()),
                 None => to_extract.syntax().text_range(),
             };
+            let ty_annotation = match &ascription {
+                Some((ty, _)) => format!(": {ty}"),
+                None => String::new(),
+            };
 
             match anchor {
                 Anchor::Before(_) | Anchor::Replace(_) => {
-                    format_to!(buf, "let {var_modifier}{var_name} = {reference_modifier}")
+                    format_to!(
+                        buf,
+                        "let {var_modifier}{var_name}{ty_annotation} = {reference_modifier}"
+                    )
                 }
                 Anchor::WrapInBlock(_) => {
-                    format_to!(buf, "{{ let {var_name} = {reference_modifier}")
+                    format_to!(buf, "{{ let {var_name}{ty_annotation} = {reference_modifier}")
                 }
             };
             format_to!(buf, "{to_extract}");
 
+            if let Some((_, imports)) = &ascription {
+                if let Some(import_scope) =
+                    ImportScope::find_insert_use_container(to_extract.syntax(), &ctx.sema)
+                {
+                    let import_scope = match import_scope {
+                        ImportScope::File(it) => ImportScope::File(edit.make_mut(it)),
+                        ImportScope::Module(it) => ImportScope::Module(edit.make_mut(it)),
+                        ImportScope::Block(it) => ImportScope::Block(edit.make_mut(it)),
+                    };
+                    for import in imports {
+                        insert_use(&import_scope, import.clone(), &ctx.config.insert_use);
+                    }
+                }
+            }
+
             if let Anchor::Replace(stmt) = anchor {
                 cov_mark::hit!(test_extract_var_expr_stmt);
                 if stmt.semicolon_token().is_none() {
@@ -154,6 +198,82 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
     )
 }
 
+/// Whether `expr` denotes a memory location which can be borrowed in place.
+fn is_place_expr(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::FieldExpr(_) | ast::Expr::IndexExpr(_) => true,
+        ast::Expr::PrefixExpr(prefix) => prefix.op_kind() == Some(ast::UnaryOp::Deref),
+        ast::Expr::ParenExpr(paren) => paren.expr().map_or(false, |it| is_place_expr(&it)),
+        _ => false,
+    }
+}
+
+/// Appends a number to `name` if a local with that name is already in scope, as the new binding
+/// would otherwise shadow it for the rest of the statement.
+/// Renders `ty` for a type annotation at `scope`. ADTs which aren't in scope yet are imported
+/// rather than written out with their full path, so this also returns the needed imports.
+fn ascribed_type(
+    ctx: &AssistContext<'_>,
+    scope: &SemanticsScope<'_>,
+    ty: &hir::Type,
+) -> Option<(String, Vec<ast::Path>)> {
+    // Opaque types can't be named either, but `display_source_code` already refuses to render them.
+    if ty.contains_unknown() || ty.is_closure() || ty.is_never() {
+        return None;
+    }
+    let db = ctx.db();
+    let module = scope.module();
+    let mut rendered = ty.display_source_code(db, module.into(), false).ok()?;
+
+    let mut names_in_scope = FxHashSet::default();
+    scope.process_all_names(&mut |it, _| {
+        names_in_scope.insert(it.to_smol_str());
+    });
+    let mut adts = Vec::new();
+    ty.walk(db, |ty| adts.extend(ty.as_adt()));
+
+    let mut imports = Vec::new();
+    for adt in adts.into_iter().unique() {
+        let name = adt.name(db).to_smol_str();
+        // The type is either already in scope, or importing it would clash with another name.
+        if names_in_scope.contains(&name) {
+            continue;
+        }
+        let path = match module.find_use_path(db, ModuleDef::Adt(adt), ctx.config.prefer_no_std) {
+            Some(path) if !path.is_ident() => path,
+            _ => continue,
+        };
+        let import = module.find_use_path_prefixed(
+            db,
+            ModuleDef::Adt(adt),
+            ctx.config.insert_use.prefix_kind,
+            ctx.config.prefer_no_std,
+        )?;
+        rendered = replace_path(&rendered, &path.to_string(), &name);
+        names_in_scope.insert(name);
+        imports.push(mod_path_to_ast(&import));
+    }
+    Some((rendered, imports))
+}
+
+/// Replaces the occurrences of `path` in `text` which aren't part of a longer path.
+fn replace_path(text: &str, path: &str, replacement: &str) -> String {
+    let is_path_char = |c: char| c == ':' || c == '_' || c.is_alphanumeric();
+    let mut res = String::with_capacity(text.len());
+    let mut last = 0;
+    for (idx, _) in text.match_indices(path) {
+        if text[..idx].ends_with(is_path_char) || text[idx + path.len()..].starts_with(is_path_char)
+        {
+            continue;
+        }
+        res.push_str(&text[last..idx]);
+        res.push_str(replacement);
+        last = idx + path.len();
+    }
+    res.push_str(&text[last..]);
+    res
+}
+
 /// Check whether the node is a valid expression which can be extracted to a variable.
 /// In general that's true for any expression, but in some cases that would produce invalid code.
 fn valid_target_expr(node: SyntaxNode) -> Option<ast::Expr> {
@@ -231,7 +351,13 @@ impl Anchor {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+    use crate::{
+        tests::{
+            check_assist, check_assist_not_applicable, check_assist_target,
+            check_assist_with_config, TEST_CONFIG,
+        },
+        AssistConfig,
+    };
 
     use super::*;
 
@@ -1279,6 +1405,160 @@ fn foo() {
 fn foo() {
     let mut $0var_name = 0;
     let v = &mut var_name;
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_extract_var_borrowed_field() {
+        cov_mark::check!(test_extract_var_borrowed_place);
+        check_assist(
+            extract_variable,
+            r#"
+struct S { count: u32 }
+fn foo(s: &mut S) {
+    let v = &mut $0s.count$0;
+    *v += 1;
+}"#,
+            r#"
+struct S { count: u32 }
+fn foo(s: &mut S) {
+    let $0count = &mut s.count;
+    let v = count;
+    *v += 1;
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_extract_var_borrowed_index() {
+        check_assist(
+            extract_variable,
+            r#"
+//- minicore: index, slice
+fn foo(xs: &[u32]) {
+    let x = &$0xs[0]$0;
+}"#,
+            r#"
+fn foo(xs: &[u32]) {
+    let $0var_name = &xs[0];
+    let x = var_name;
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_var_name_does_not_shadow_local() {
+        check_assist(
+            extract_variable,
+            r#"
+struct Vec;
+impl Vec {
+    fn len(&self) -> usize { 0 }
+}
+fn foo(v: &Vec, len: usize) -> usize {
+    $0v.len()$0 + len
+}"#,
+            r#"
+struct Vec;
+impl Vec {
+    fn len(&self) -> usize { 0 }
+}
+fn foo(v: &Vec, len: usize) -> usize {
+    let $0len1 = v.len();
+    len1 + len
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_var_ascribe_type() {
+        check_assist_with_config(
+            extract_variable,
+            AssistConfig { assist_extract_variable_ascribe_type: true, ..TEST_CONFIG },
+            r#"
+fn bar(size: u32) {}
+fn foo() {
+    bar($01 + 1$0);
+}"#,
+            r#"
+fn bar(size: u32) {}
+fn foo() {
+    let $0size: u32 = 1 + 1;
+    bar(size);
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_var_ascribe_type_imports_adt() {
+        check_assist_with_config(
+            extract_variable,
+            AssistConfig { assist_extract_variable_ascribe_type: true, ..TEST_CONFIG },
+            r#"
+mod m {
+    pub struct Wrapper<T>(pub T);
+    pub struct Thing;
+    pub fn make() -> Wrapper<Thing> { Wrapper(Thing) }
+}
+fn foo() {
+    let w = $0m::make()$0;
+}"#,
+            r#"
+use m::{Thing, Wrapper};
+
+mod m {
+    pub struct Wrapper<T>(pub T);
+    pub struct Thing;
+    pub fn make() -> Wrapper<Thing> { Wrapper(Thing) }
+}
+fn foo() {
+    let $0make: Wrapper<Thing> = m::make();
+    let w = make;
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_var_ascribe_type_keeps_path_on_name_clash() {
+        check_assist_with_config(
+            extract_variable,
+            AssistConfig { assist_extract_variable_ascribe_type: true, ..TEST_CONFIG },
+            r#"
+mod m {
+    pub struct Thing;
+    pub fn make() -> Thing { Thing }
+}
+struct Thing;
+fn foo() {
+    let w = $0m::make()$0;
+}"#,
+            r#"
+mod m {
+    pub struct Thing;
+    pub fn make() -> Thing { Thing }
+}
+struct Thing;
+fn foo() {
+    let $0make: m::Thing = m::make();
+    let w = make;
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_var_no_ascription_for_closure() {
+        check_assist_with_config(
+            extract_variable,
+            AssistConfig { assist_extract_variable_ascribe_type: true, ..TEST_CONFIG },
+            r#"
+fn foo() {
+    let f = $0|x: u32| x$0;
+}"#,
+            r#"
+fn foo() {
+    let $0var_name = |x: u32| x;
+    let f = var_name;
 }"#,
         );
     }
@@ -0,0 +1,698 @@
+use ide_db::famous_defs::FamousDefs;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, AstNode, HasGenericParams, HasName, HasVisibility, StructKind},
+    TextRange,
+};
+
+use crate::{
+    utils::{
+        find_impl_block_start, find_struct_impl, generate_impl_text, generate_impl_text_with_name,
+    },
+    AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+// Assist: generate_builder
+//
+// Generates a builder for a struct with named fields. Fields whose type implements `Default` are
+// optional, `build` returns an error if any other field wasn't set. The typestate builder checks
+// this at compile time instead. Struct literals in the selection are rewritten to use the builder.
+//
+// ```
+// # //- minicore: default, derive
+// #[derive(Default)]
+// struct Port(u16);
+//
+// struct Server$0 {
+//     host: &'static str,
+//     port: Port,
+// }
+// ```
+// ->
+// ```
+// #[derive(Default)]
+// struct Port(u16);
+//
+// struct Server {
+//     host: &'static str,
+//     port: Port,
+// }
+//
+// impl Server {
+//     fn builder() -> ServerBuilder {
+//         ServerBuilder { host: None, port: None }
+//     }
+// }
+//
+// struct ServerBuilder {
+//     host: Option<&'static str>,
+//     port: Option<Port>,
+// }
+//
+// impl ServerBuilder {
+//     fn host(mut self, host: &'static str) -> Self {
+//         self.host = Some(host);
+//         self
+//     }
+//
+//     fn port(mut self, port: Port) -> Self {
+//         self.port = Some(port);
+//         self
+//     }
+//
+//     fn build(self) -> Result<Server, &'static str> {
+//         Ok(Server {
+//             host: self.host.ok_or("missing field `host`")?,
+//             port: self.port.unwrap_or_default(),
+//         })
+//     }
+// }
+// ```
+pub(crate) fn generate_builder(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(named) => named,
+        _ => return None,
+    };
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+    let module = strukt_def.module(ctx.db());
+    let builder_name = format!("{}Builder", strukt.name()?);
+    if module.scope(ctx.db(), None).iter().any(|(name, _)| name.to_smol_str() == builder_name) {
+        return None;
+    }
+    let adt = ast::Adt::Struct(strukt.clone());
+    let impl_def = find_struct_impl(ctx, &adt, &[String::from("builder")])?;
+
+    let default_trait = FamousDefs(&ctx.sema, module.krate()).core_default_Default();
+    let fields = field_list
+        .fields()
+        .map(|field| {
+            let optional = match (default_trait, ctx.sema.to_def(&field)) {
+                (Some(default_trait), Some(def)) => {
+                    def.ty(ctx.db()).impls_trait(ctx.db(), default_trait, &[])
+                }
+                _ => false,
+            };
+            Some(Field { name: field.name()?, ty: field.ty()?, optional })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        return None;
+    }
+    let has_required = fields.iter().any(|it| !it.optional);
+
+    let sites = construction_sites(ctx, &strukt, strukt_def);
+
+    let mut kinds = vec![BuilderKind::Checked];
+    // The states of required fields are tracked with generic parameters, which would have to be
+    // interleaved with the ones of the struct.
+    if has_required && strukt.generic_param_list().is_none() {
+        kinds.push(BuilderKind::Typestate);
+    }
+
+    let target = strukt.syntax().text_range();
+    for kind in kinds {
+        let label = match kind {
+            BuilderKind::Checked => "Generate builder",
+            BuilderKind::Typestate => "Generate typestate builder",
+        };
+        acc.add_group(
+            &GroupLabel("Generate builder…".to_owned()),
+            AssistId("generate_builder", AssistKind::Generate),
+            label,
+            target,
+            |edit| {
+                let vis = strukt.visibility().map_or(String::new(), |v| format!("{v} "));
+                let builder = Builder { strukt: &strukt, name: &builder_name, vis: &vis };
+                let (items, builder_ty, builder_expr) = match kind {
+                    BuilderKind::Checked => builder.checked(&fields),
+                    BuilderKind::Typestate => builder.typestate(&fields),
+                };
+
+                let mut buf = String::with_capacity(512);
+                if impl_def.is_some() {
+                    buf.push('\n');
+                }
+                format_to!(
+                    buf,
+                    "    {vis}fn builder() -> {builder_ty} {{\n        {builder_expr}\n    }}"
+                );
+                let strukt_end = strukt.syntax().text_range().end();
+                match impl_def.clone().and_then(|it| find_impl_block_start(it, &mut buf)) {
+                    Some(start_offset) => {
+                        edit.insert(start_offset, buf);
+                        edit.insert(strukt_end, items);
+                    }
+                    None => {
+                        edit.insert(strukt_end, generate_impl_text(&adt, &buf) + &items);
+                    }
+                }
+
+                let fallible = kind == BuilderKind::Checked && has_required;
+                for site in &sites {
+                    if let Some(call) = builder_call(site, fallible) {
+                        edit.replace(site.syntax().text_range(), call);
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BuilderKind {
+    /// Required fields are checked when building.
+    Checked,
+    /// Required fields are tracked in the type of the builder.
+    Typestate,
+}
+
+struct Field {
+    name: ast::Name,
+    ty: ast::Type,
+    optional: bool,
+}
+
+struct Builder<'a> {
+    strukt: &'a ast::Struct,
+    name: &'a str,
+    vis: &'a str,
+}
+
+impl Builder<'_> {
+    /// Returns the builder items, the type of the builder returned by `builder()` and the
+    /// expression creating it.
+    fn checked(&self, fields: &[Field]) -> (String, String, String) {
+        let Builder { strukt, name, vis } = *self;
+        let strukt_name = strukt.name().map_or(String::new(), |it| it.to_string());
+        let generic_args = strukt
+            .generic_param_list()
+            .map_or(String::new(), |it| it.to_generic_args().to_string());
+
+        let mut buf = String::new();
+        format_to!(buf, "\n\n{vis}struct {name}");
+        if let Some(generic_params) = strukt.generic_param_list() {
+            format_to!(buf, "{generic_params}");
+        }
+        match strukt.where_clause() {
+            Some(where_clause) => format_to!(buf, "\n{where_clause}\n{{\n"),
+            None => buf.push_str(" {\n"),
+        }
+        for Field { name, ty, .. } in fields {
+            format_to!(buf, "    {name}: Option<{ty}>,\n");
+        }
+        buf.push('}');
+
+        let has_required = fields.iter().any(|it| !it.optional);
+        let values = fields
+            .iter()
+            .map(|Field { name, optional, .. }| {
+                if *optional {
+                    format!("            {name}: self.{name}.unwrap_or_default(),")
+                } else {
+                    format!("            {name}: self.{name}.ok_or(\"missing field `{name}`\")?,")
+                }
+            })
+            .join("\n");
+        let build = if has_required {
+            format!(
+                "    {vis}fn build(self) -> Result<{strukt_name}{generic_args}, &'static str> {{
+        Ok({strukt_name} {{
+{values}
+        }})
+    }}"
+            )
+        } else {
+            format!(
+                "    {vis}fn build(self) -> {strukt_name}{generic_args} {{
+        {strukt_name} {{
+{values}
+        }}
+    }}"
+            )
+        };
+        let code = format!("{}\n\n{build}", setters(vis, fields.iter()));
+        buf.push_str(&generate_impl_text_with_name(&ast::Adt::Struct(strukt.clone()), name, &code));
+
+        let builder_ty = format!("{name}{generic_args}");
+        let nones = fields.iter().map(|it| format!("{}: None", it.name)).join(", ");
+        (buf, builder_ty, format!("{name} {{ {nones} }}"))
+    }
+
+    /// Like `checked`, but the builder has a generic parameter for every required field, which is
+    /// `()` until the field is set and `(T,)` afterwards. `build` is only available once all of
+    /// them are set.
+    fn typestate(&self, fields: &[Field]) -> (String, String, String) {
+        let Builder { strukt, name, vis } = *self;
+        let strukt_name = strukt.name().map_or(String::new(), |it| it.to_string());
+        let required: Vec<_> = fields.iter().filter(|it| !it.optional).collect();
+        let states: Vec<_> = required.iter().map(|it| state_param(&it.name)).collect();
+        let args = |f: &dyn Fn(usize, &Field) -> String| {
+            required.iter().enumerate().map(|(idx, field)| f(idx, field)).join(", ")
+        };
+
+        let mut buf = String::new();
+        format_to!(buf, "\n\n{vis}struct {name}<{}> {{\n", states.join(", "));
+        for field in fields {
+            match required.iter().position(|it| it.name.text() == field.name.text()) {
+                Some(idx) => format_to!(buf, "    {}: {},\n", field.name, states[idx]),
+                None => format_to!(buf, "    {}: Option<{}>,\n", field.name, field.ty),
+            }
+        }
+        buf.push('}');
+
+        if fields.iter().any(|it| it.optional) {
+            let states = states.join(", ");
+            format_to!(
+                buf,
+                "\n\nimpl<{states}> {name}<{states}> {{\n{}\n}}",
+                setters(vis, fields.iter().filter(|it| it.optional))
+            );
+        }
+
+        for (idx, field) in required.iter().enumerate() {
+            let other_states = states
+                .iter()
+                .enumerate()
+                .filter(|&(it, _)| it != idx)
+                .map(|(_, state)| state)
+                .join(", ");
+            let impl_params =
+                if other_states.is_empty() { String::new() } else { format!("<{other_states}>") };
+            let state_args = |set: &str| {
+                args(&|it, _| if it == idx { set.to_owned() } else { states[it].clone() })
+            };
+            let values = fields
+                .iter()
+                .map(|it| {
+                    if it.name.text() == field.name.text() {
+                        format!("            {0}: ({0},),", it.name)
+                    } else {
+                        format!("            {0}: self.{0},", it.name)
+                    }
+                })
+                .join("\n");
+            let Field { name: field_name, ty, .. } = field;
+            format_to!(
+                buf,
+                "\n\nimpl{impl_params} {name}<{}> {{
+    {vis}fn {field_name}(self, {field_name}: {ty}) -> {name}<{}> {{
+        {name} {{
+{values}
+        }}
+    }}
+}}",
+                state_args("()"),
+                state_args(&format!("({ty},)"))
+            );
+        }
+
+        let values = fields
+            .iter()
+            .map(|Field { name, optional, .. }| {
+                if *optional {
+                    format!("            {name}: self.{name}.unwrap_or_default(),")
+                } else {
+                    format!("            {name}: self.{name}.0,")
+                }
+            })
+            .join("\n");
+        format_to!(
+            buf,
+            "\n\nimpl {name}<{}> {{
+    {vis}fn build(self) -> {strukt_name} {{
+        {strukt_name} {{
+{values}
+        }}
+    }}
+}}",
+            args(&|_, it| format!("({},)", it.ty))
+        );
+
+        let builder_ty = format!("{name}<{}>", args(&|_, _| "()".to_owned()));
+        let values = fields
+            .iter()
+            .map(|it| format!("{}: {}", it.name, if it.optional { "None" } else { "()" }))
+            .join(", ");
+        (buf, builder_ty, format!("{name} {{ {values} }}"))
+    }
+}
+
+fn setters<'a>(vis: &str, fields: impl Iterator<Item = &'a Field>) -> String {
+    fields
+        .map(|Field { name, ty, .. }| {
+            format!(
+                "    {vis}fn {name}(mut self, {name}: {ty}) -> Self {{
+        self.{name} = Some({name});
+        self
+    }}"
+            )
+        })
+        .join("\n\n")
+}
+
+/// The name of the generic parameter tracking whether the field is set, e.g. `HostState`.
+fn state_param(field: &ast::Name) -> String {
+    let mut buf = String::new();
+    for part in field.text().split('_') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            buf.extend(first.to_uppercase());
+            buf.extend(chars);
+        }
+    }
+    buf.push_str("State");
+    buf
+}
+
+/// Struct literals of the struct in the selection, which are rewritten to use the builder. Literals
+/// with a `..base` aren't, as the builder can't take the remaining fields from another value.
+fn construction_sites(
+    ctx: &AssistContext<'_>,
+    strukt: &ast::Struct,
+    strukt_def: hir::Struct,
+) -> Vec<ast::RecordExpr> {
+    let selection = ctx.selection_trimmed();
+    let mut sites: Vec<ast::RecordExpr> = Vec::new();
+    let Some(root) = strukt.syntax().ancestors().last() else { return sites };
+    if ctx.has_empty_selection() {
+        return sites;
+    }
+    for expr in root.descendants().filter_map(ast::RecordExpr::cast) {
+        let range = expr.syntax().text_range();
+        if !selection.contains_range(range) || nested_in(&sites, range) {
+            continue;
+        }
+        let has_spread = expr.record_expr_field_list().map_or(true, |it| it.spread().is_some());
+        let is_strukt = ctx
+            .sema
+            .type_of_expr(&expr.clone().into())
+            .map_or(false, |it| it.original.as_adt() == Some(hir::Adt::Struct(strukt_def)));
+        if is_strukt && !has_spread {
+            sites.push(expr);
+        }
+    }
+    sites
+}
+
+fn nested_in(sites: &[ast::RecordExpr], range: TextRange) -> bool {
+    sites.iter().any(|it| it.syntax().text_range().contains_range(range))
+}
+
+fn builder_call(site: &ast::RecordExpr, fallible: bool) -> Option<String> {
+    let mut buf = format!("{}::builder()", site.path()?);
+    for field in site.record_expr_field_list()?.fields() {
+        format_to!(buf, ".{}({})", field.field_name()?, field.expr()?);
+    }
+    buf.push_str(".build()");
+    if fallible {
+        buf.push_str(".unwrap()");
+    }
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_to_tuple_structs() {
+        check_assist_not_applicable(generate_builder, r#"struct Foo$0(u32);"#);
+    }
+
+    #[test]
+    fn not_applicable_if_builder_exists() {
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct Foo$0 { x: u32 }
+struct FooBuilder;
+"#,
+        );
+    }
+
+    #[test]
+    fn all_fields_optional() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+struct Count(u32);
+
+struct Counter$0 {
+    count: Count,
+}
+"#,
+            r#"
+#[derive(Default)]
+struct Count(u32);
+
+struct Counter {
+    count: Count,
+}
+
+impl Counter {
+    fn builder() -> CounterBuilder {
+        CounterBuilder { count: None }
+    }
+}
+
+struct CounterBuilder {
+    count: Option<Count>,
+}
+
+impl CounterBuilder {
+    fn count(mut self, count: Count) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    fn build(self) -> Counter {
+        Counter {
+            count: self.count.unwrap_or_default(),
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_struct_with_existing_impl() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+struct Count(u32);
+
+struct Wrapper$0<T: Clone> {
+    value: T,
+    count: Count,
+}
+
+impl<T: Clone> Wrapper<T> {
+    fn get(&self) -> &T { &self.value }
+}
+"#,
+            r#"
+#[derive(Default)]
+struct Count(u32);
+
+struct Wrapper<T: Clone> {
+    value: T,
+    count: Count,
+}
+
+struct WrapperBuilder<T: Clone> {
+    value: Option<T>,
+    count: Option<Count>,
+}
+
+impl<T: Clone> WrapperBuilder<T> {
+    fn value(mut self, value: T) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    fn count(mut self, count: Count) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    fn build(self) -> Result<Wrapper<T>, &'static str> {
+        Ok(Wrapper {
+            value: self.value.ok_or("missing field `value`")?,
+            count: self.count.unwrap_or_default(),
+        })
+    }
+}
+
+impl<T: Clone> Wrapper<T> {
+    fn builder() -> WrapperBuilder<T> {
+        WrapperBuilder { value: None, count: None }
+    }
+
+    fn get(&self) -> &T { &self.value }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrite_selected_literals() {
+        check_assist(
+            generate_builder,
+            r#"
+struct $0Point {
+    x: &'static str,
+}
+
+fn f() -> Point {
+    Point { x: "a" }
+}
+
+fn g(x: &'static str) -> Point {
+    let base = Point { x };
+    Point { ..base }
+}$0
+
+fn h() -> Point {
+    Point { x: "b" }
+}
+"#,
+            r#"
+struct Point {
+    x: &'static str,
+}
+
+impl Point {
+    fn builder() -> PointBuilder {
+        PointBuilder { x: None }
+    }
+}
+
+struct PointBuilder {
+    x: Option<&'static str>,
+}
+
+impl PointBuilder {
+    fn x(mut self, x: &'static str) -> Self {
+        self.x = Some(x);
+        self
+    }
+
+    fn build(self) -> Result<Point, &'static str> {
+        Ok(Point {
+            x: self.x.ok_or("missing field `x`")?,
+        })
+    }
+}
+
+fn f() -> Point {
+    Point::builder().x("a").build().unwrap()
+}
+
+fn g(x: &'static str) -> Point {
+    let base = Point::builder().x(x).build().unwrap();
+    Point { ..base }
+}
+
+fn h() -> Point {
+    Point { x: "b" }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn typestate_builder() {
+        check_assist_by_label(
+            generate_builder,
+            r#"
+//- minicore: default, derive
+#[derive(Default)]
+struct Port(u16);
+
+pub struct $0Server {
+    host: &'static str,
+    port: Port,
+    name: &'static str,
+}
+
+fn main() {
+    let _ = Server { host: "localhost", port: Port(80), name: "web" };
+}$0
+"#,
+            r#"
+#[derive(Default)]
+struct Port(u16);
+
+pub struct Server {
+    host: &'static str,
+    port: Port,
+    name: &'static str,
+}
+
+impl Server {
+    pub fn builder() -> ServerBuilder<(), ()> {
+        ServerBuilder { host: (), port: None, name: () }
+    }
+}
+
+pub struct ServerBuilder<HostState, NameState> {
+    host: HostState,
+    port: Option<Port>,
+    name: NameState,
+}
+
+impl<HostState, NameState> ServerBuilder<HostState, NameState> {
+    pub fn port(mut self, port: Port) -> Self {
+        self.port = Some(port);
+        self
+    }
+}
+
+impl<NameState> ServerBuilder<(), NameState> {
+    pub fn host(self, host: &'static str) -> ServerBuilder<(&'static str,), NameState> {
+        ServerBuilder {
+            host: (host,),
+            port: self.port,
+            name: self.name,
+        }
+    }
+}
+
+impl<HostState> ServerBuilder<HostState, ()> {
+    pub fn name(self, name: &'static str) -> ServerBuilder<HostState, (&'static str,)> {
+        ServerBuilder {
+            host: self.host,
+            port: self.port,
+            name: (name,),
+        }
+    }
+}
+
+impl ServerBuilder<(&'static str,), (&'static str,)> {
+    pub fn build(self) -> Server {
+        Server {
+            host: self.host.0,
+            port: self.port.unwrap_or_default(),
+            name: self.name.0,
+        }
+    }
+}
+
+fn main() {
+    let _ = Server::builder().host("localhost").port(Port(80)).name("web").build();
+}
+"#,
+            "Generate typestate builder",
+        );
+    }
+}
@@ -0,0 +1,526 @@
+use hir::{HasSource, ModuleDef, ScopeDef};
+use ide_db::path_transform::PathTransform;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit_in_place::AttrsOwnerEdit, make, AstNode, HasName},
+    ted, SyntaxNode,
+};
+
+use crate::{
+    utils::generate_trait_impl_text_intransitive, AssistContext, AssistId, AssistKind, Assists,
+    GroupLabel,
+};
+
+// Assist: generate_delegate_trait
+//
+// Generates an impl of a trait implemented by a field, forwarding all items of the trait to the
+// field.
+//
+// ```
+// trait Counter {
+//     const START: u32;
+//     fn count(&self, step: u32) -> u32;
+// }
+//
+// struct Inner;
+// impl Counter for Inner {
+//     const START: u32 = 0;
+//     fn count(&self, step: u32) -> u32 {
+//         Self::START + step
+//     }
+// }
+//
+// struct Wrapper($0Inner);
+// ```
+// ->
+// ```
+// trait Counter {
+//     const START: u32;
+//     fn count(&self, step: u32) -> u32;
+// }
+//
+// struct Inner;
+// impl Counter for Inner {
+//     const START: u32 = 0;
+//     fn count(&self, step: u32) -> u32 {
+//         Self::START + step
+//     }
+// }
+//
+// struct Wrapper(Inner);
+//
+// impl Counter for Wrapper {
+//     $0const START: u32 = <Inner as Counter>::START;
+//
+//     fn count(&self, step: u32) -> u32 {
+//         <Inner as Counter>::count(&self.0, step)
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_trait(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+
+    let (field_name, field_ty, target) = match ctx.find_node_at_offset::<ast::RecordField>() {
+        Some(field) => {
+            let field_name = field.name()?;
+            let field_ty = field.ty()?;
+            (field_name.to_string(), field_ty, field.syntax().text_range())
+        }
+        None => {
+            let field = ctx.find_node_at_offset::<ast::TupleField>()?;
+            let field_list = ctx.find_node_at_offset::<ast::TupleFieldList>()?;
+            let field_list_index = field_list.fields().position(|it| it == field)?;
+            let field_ty = field.ty()?;
+            (field_list_index.to_string(), field_ty, field.syntax().text_range())
+        }
+    };
+    let sema_field_ty = ctx.sema.resolve_type(&field_ty)?;
+    let strukt_ty = strukt_def.ty(ctx.db());
+
+    let target_scope = ctx.sema.scope(strukt.syntax())?;
+    let mut traits = Vec::new();
+    target_scope.process_all_names(&mut |name, def| {
+        if let ScopeDef::ModuleDef(ModuleDef::Trait(trait_)) = def {
+            traits.push((name, trait_));
+        }
+    });
+    traits.sort_by_key(|(name, _)| name.to_smol_str());
+
+    for (trait_name, trait_) in traits {
+        let db = ctx.db();
+        if trait_.is_auto(db)
+            || trait_.is_unsafe(db)
+            || trait_.type_or_const_param_count(db, false) != 0
+            || !sema_field_ty.impls_trait(db, trait_, &[])
+            || strukt_ty.impls_trait(db, trait_, &[])
+        {
+            continue;
+        }
+        let delegate = Delegate {
+            inner: field_ty.to_string(),
+            trait_name: trait_name.to_string(),
+            field_name: &field_name,
+        };
+        let source_scope = ctx.sema.scope_for_def(trait_);
+        let transform = PathTransform::generic_transformation(&target_scope, &source_scope);
+        let Some(items) = trait_
+            .items(db)
+            .into_iter()
+            .filter_map(|item| delegate.item(ctx, &transform, item).transpose())
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        if items.is_empty() {
+            continue;
+        }
+
+        acc.add_group(
+            &GroupLabel("Generate delegate trait impls…".to_owned()),
+            AssistId("generate_delegate_trait", AssistKind::Generate),
+            format!("Generate `{trait_name}` impl delegating to `self.{field_name}`"),
+            target,
+            |builder| {
+                let adt = ast::Adt::Struct(strukt.clone());
+                let mut code = items.iter().map(|it| format!("    {it}")).join("\n\n");
+                let offset = strukt.syntax().text_range().end();
+                match ctx.config.snippet_cap {
+                    Some(cap) => {
+                        code.insert_str(4, "$0");
+                        let snippet = generate_trait_impl_text_intransitive(
+                            &adt,
+                            &delegate.trait_name,
+                            &code,
+                        );
+                        builder.insert_snippet(cap, offset, snippet);
+                    }
+                    None => {
+                        let text = generate_trait_impl_text_intransitive(
+                            &adt,
+                            &delegate.trait_name,
+                            &code,
+                        );
+                        builder.insert(offset, text);
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+struct Delegate<'a> {
+    inner: String,
+    trait_name: String,
+    field_name: &'a str,
+}
+
+impl Delegate<'_> {
+    /// Renders the impl item forwarding `item` to the field.
+    ///
+    /// Returns `Some(None)` for provided methods which can't be forwarded, they are left to their
+    /// default implementation. Returns `None` if a required item can't be forwarded.
+    fn item(
+        &self,
+        ctx: &AssistContext<'_>,
+        transform: &PathTransform<'_>,
+        item: hir::AssocItem,
+    ) -> Option<Option<String>> {
+        let Self { inner, trait_name, .. } = self;
+        match item {
+            hir::AssocItem::Function(func) => {
+                let source = ctx.sema.source(func)?.value.clone_for_update();
+                transform.apply(source.syntax());
+                source.remove_attrs_and_docs();
+                match self.function(&source) {
+                    Some(it) => Some(Some(it)),
+                    None if source.body().is_some() => Some(None),
+                    None => None,
+                }
+            }
+            hir::AssocItem::Const(const_) => {
+                let source = ctx.sema.source(const_)?.value.clone_for_update();
+                transform.apply(source.syntax());
+                let name = source.name()?;
+                let ty = source.ty()?;
+                Some(Some(format!("const {name}: {ty} = <{inner} as {trait_name}>::{name};")))
+            }
+            hir::AssocItem::TypeAlias(type_alias) => {
+                let source = ctx.sema.source(type_alias)?.value.clone_for_update();
+                transform.apply(source.syntax());
+                let name = source.name()?;
+                let mut text = format!("type {name}");
+                let mut args = String::new();
+                if let Some(generic_params) = source.generic_param_list() {
+                    format_to!(text, "{generic_params}");
+                    format_to!(args, "{}", generic_params.to_generic_args());
+                }
+                format_to!(text, " = <{inner} as {trait_name}>::{name}{args}");
+                if let Some(where_clause) = source.where_clause() {
+                    format_to!(text, " {where_clause}");
+                }
+                text.push(';');
+                Some(Some(text))
+            }
+        }
+    }
+
+    fn function(&self, func: &ast::Fn) -> Option<String> {
+        let Self { inner, trait_name, field_name } = self;
+        let name = func.name()?;
+        let param_list = func.param_list()?;
+
+        let mut args = Vec::new();
+        if let Some(self_param) = param_list.self_param() {
+            // `self: Box<Self>` and the like can't be forwarded to a field.
+            if self_param.ty().is_some() {
+                return None;
+            }
+            let field = make::ext::field_from_idents(["self", field_name])?;
+            args.push(match self_param.kind() {
+                ast::SelfParamKind::Owned => field,
+                ast::SelfParamKind::Ref => make::expr_ref(field, false),
+                ast::SelfParamKind::MutRef => make::expr_ref(field, true),
+            });
+        }
+        for (idx, param) in param_list.params().enumerate() {
+            // Without `Self` in the signature, the forwarded item doesn't need to be converted
+            // to or from the field's type.
+            if mentions_self_type(param.ty()?.syntax()) {
+                return None;
+            }
+            let name = match param.pat()? {
+                ast::Pat::IdentPat(pat) if pat.at_token().is_none() => pat.name()?,
+                pat => {
+                    let name = make::name(&format!("arg{idx}"));
+                    let new_pat = make::ident_pat(false, false, name.clone());
+                    ted::replace(pat.syntax(), new_pat.clone_for_update().syntax());
+                    name
+                }
+            };
+            args.push(make::expr_path(make::ext::ident_path(&name.text())));
+        }
+        if let Some(ret_type) = func.ret_type() {
+            if mentions_self_type(ret_type.syntax()) {
+                return None;
+            }
+        }
+
+        let path = make::path_from_text(&format!("<{inner} as {trait_name}>::{name}"));
+        let mut call = make::expr_call(make::expr_path(path), make::arg_list(args));
+        if func.async_token().is_some() {
+            call = make::expr_await(call);
+        }
+
+        let signature = match func.body() {
+            Some(body) => {
+                let len = body.syntax().text_range().start() - func.syntax().text_range().start();
+                func.syntax().text().slice(..len).to_string()
+            }
+            None => func.syntax().to_string().trim_end_matches(';').to_owned(),
+        };
+        Some(format!("{} {{\n        {call}\n    }}", signature.trim_end()))
+    }
+}
+
+/// Checks whether `node` refers to the `Self` type, not counting its associated items like
+/// `Self::Item`, which are forwarded as well.
+fn mentions_self_type(node: &SyntaxNode) -> bool {
+    node.descendants().filter_map(ast::Path::cast).any(|path| {
+        path.qualifier().is_none()
+            && path.segment().map_or(false, |it| it.self_type_token().is_some())
+            && path.syntax().parent().and_then(ast::Path::cast).is_none()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn forward_methods_to_tuple_field() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Trait {
+    fn by_ref(&self) -> u32;
+    fn by_mut(&mut self, x: u32);
+    fn by_value(self, _: bool) -> u32;
+}
+
+struct Inner;
+impl Trait for Inner {
+    fn by_ref(&self) -> u32 { 0 }
+    fn by_mut(&mut self, x: u32) {}
+    fn by_value(self, _: bool) -> u32 { 0 }
+}
+
+struct Wrapper(In$0ner);
+"#,
+            r#"
+trait Trait {
+    fn by_ref(&self) -> u32;
+    fn by_mut(&mut self, x: u32);
+    fn by_value(self, _: bool) -> u32;
+}
+
+struct Inner;
+impl Trait for Inner {
+    fn by_ref(&self) -> u32 { 0 }
+    fn by_mut(&mut self, x: u32) {}
+    fn by_value(self, _: bool) -> u32 { 0 }
+}
+
+struct Wrapper(Inner);
+
+impl Trait for Wrapper {
+    $0fn by_ref(&self) -> u32 {
+        <Inner as Trait>::by_ref(&self.0)
+    }
+
+    fn by_mut(&mut self, x: u32) {
+        <Inner as Trait>::by_mut(&mut self.0, x)
+    }
+
+    fn by_value(self, arg0: bool) -> u32 {
+        <Inner as Trait>::by_value(self.0, arg0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn forward_assoc_types_and_consts_to_record_field() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+//- minicore: option
+trait Container {
+    type Item;
+    const LEN: usize;
+    fn first(&self) -> Option<Self::Item>;
+}
+
+struct Inner<T>(T);
+impl<T: Copy> Container for Inner<T> {
+    type Item = T;
+    const LEN: usize = 1;
+    fn first(&self) -> Option<T> { None }
+}
+
+struct Wrapper<T: Copy> {
+    inner$0: Inner<T>,
+}
+"#,
+            r#"
+trait Container {
+    type Item;
+    const LEN: usize;
+    fn first(&self) -> Option<Self::Item>;
+}
+
+struct Inner<T>(T);
+impl<T: Copy> Container for Inner<T> {
+    type Item = T;
+    const LEN: usize = 1;
+    fn first(&self) -> Option<T> { None }
+}
+
+struct Wrapper<T: Copy> {
+    inner: Inner<T>,
+}
+
+impl<T: Copy> Container for Wrapper<T> {
+    $0type Item = <Inner<T> as Container>::Item;
+
+    const LEN: usize = <Inner<T> as Container>::LEN;
+
+    fn first(&self) -> Option<Self::Item> {
+        <Inner<T> as Container>::first(&self.inner)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn provided_methods_mentioning_self_are_skipped() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Trait {
+    fn value(&self) -> u32;
+    fn with_default(&self) -> u32 { 0 }
+    fn by_self(self) -> Self where Self: Sized { self }
+}
+
+struct Inner;
+impl Trait for Inner {
+    fn value(&self) -> u32 { 0 }
+}
+
+struct Wrapper(Inner$0);
+"#,
+            r#"
+trait Trait {
+    fn value(&self) -> u32;
+    fn with_default(&self) -> u32 { 0 }
+    fn by_self(self) -> Self where Self: Sized { self }
+}
+
+struct Inner;
+impl Trait for Inner {
+    fn value(&self) -> u32 { 0 }
+}
+
+struct Wrapper(Inner);
+
+impl Trait for Wrapper {
+    $0fn value(&self) -> u32 {
+        <Inner as Trait>::value(&self.0)
+    }
+
+    fn with_default(&self) -> u32 {
+        <Inner as Trait>::with_default(&self.0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn one_assist_per_trait() {
+        check_assist_by_label(
+            generate_delegate_trait,
+            r#"
+trait A {
+    fn a(&self);
+}
+trait B {
+    fn b(&self);
+}
+
+struct Inner;
+impl A for Inner {
+    fn a(&self) {}
+}
+impl B for Inner {
+    fn b(&self) {}
+}
+
+struct Wrapper(Inner$0);
+"#,
+            r#"
+trait A {
+    fn a(&self);
+}
+trait B {
+    fn b(&self);
+}
+
+struct Inner;
+impl A for Inner {
+    fn a(&self) {}
+}
+impl B for Inner {
+    fn b(&self) {}
+}
+
+struct Wrapper(Inner);
+
+impl B for Wrapper {
+    $0fn b(&self) {
+        <Inner as B>::b(&self.0)
+    }
+}
+"#,
+            "Generate `B` impl delegating to `self.0`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_required_method_returns_self() {
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Duplicate {
+    fn duplicate(&self) -> Self;
+}
+
+struct Inner;
+impl Duplicate for Inner {
+    fn duplicate(&self) -> Self { Inner }
+}
+
+struct Wrapper(Inner$0);
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_already_implemented() {
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Trait {
+    fn f(&self);
+}
+
+struct Inner;
+impl Trait for Inner {
+    fn f(&self) {}
+}
+
+struct Wrapper(Inner$0);
+impl Trait for Wrapper {
+    fn f(&self) {}
+}
+"#,
+        );
+    }
+}
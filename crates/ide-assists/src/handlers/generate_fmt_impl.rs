@@ -0,0 +1,541 @@
+use hir::{HirDisplay, ModuleDef, StructKind};
+use ide_db::famous_defs::FamousDefs;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::ast::{self, AstNode, HasName};
+
+use crate::{utils::generate_trait_impl_text, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_debug_impl
+//
+// Generates a manual `Debug` impl listing the fields of a struct or enum. Fields whose type doesn't
+// implement `Debug` are skipped with a comment, so the impl compiles where a derive wouldn't.
+//
+// ```
+// # //- minicore: fmt
+// struct Socket;
+//
+// struct Conn$0<T> {
+//     peer: T,
+//     socket: Socket,
+// }
+// ```
+// ->
+// ```
+// struct Socket;
+//
+// struct Conn<T> {
+//     peer: T,
+//     socket: Socket,
+// }
+//
+// impl<T: core::fmt::Debug> core::fmt::Debug for Conn<T> {
+//     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+//         f.debug_struct("Conn")
+//             .field("peer", &self.peer)
+//             // field `socket` is skipped, `Socket` doesn't implement `Debug`
+//             .finish_non_exhaustive()
+//     }
+// }
+// ```
+pub(crate) fn generate_debug_impl(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    generate_fmt_impl(acc, ctx, FmtTrait::Debug)
+}
+
+// Assist: generate_display_impl
+//
+// Generates a `Display` impl writing out the fields of a struct or enum. Fields are formatted with
+// `Display` if possible and with `Debug` otherwise, fields implementing neither are skipped.
+//
+// ```
+// # //- minicore: fmt
+// enum Shape$0<T> {
+//     Point,
+//     Circle { radius: T },
+// }
+// ```
+// ->
+// ```
+// enum Shape<T> {
+//     Point,
+//     Circle { radius: T },
+// }
+//
+// impl<T: core::fmt::Display> core::fmt::Display for Shape<T> {
+//     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+//         match self {
+//             Self::Point => f.write_str("Point"),
+//             Self::Circle { radius } => write!(f, "Circle {{ radius: {} }}", radius),
+//         }
+//     }
+// }
+// ```
+pub(crate) fn generate_display_impl(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    generate_fmt_impl(acc, ctx, FmtTrait::Display)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FmtTrait {
+    Debug,
+    Display,
+}
+
+/// How a field is written by the generated impl.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldFmt {
+    Display,
+    Debug,
+    Skip,
+}
+
+struct Field {
+    /// The name of the field, or its index for tuple fields.
+    name: String,
+    /// The expression referring to the field, `self.name` for structs and the binding of the match
+    /// arm for enums.
+    value: String,
+    ty: String,
+    fmt: FieldFmt,
+}
+
+impl Field {
+    /// The pattern binding the field in a match arm.
+    fn binding(&self) -> &str {
+        match self.fmt {
+            FieldFmt::Skip => "_",
+            _ => &self.value,
+        }
+    }
+}
+
+fn generate_fmt_impl(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+    fmt_trait: FmtTrait,
+) -> Option<()> {
+    let adt = ctx.find_node_at_offset::<ast::Adt>()?;
+    let adt_def = ctx.sema.to_def(&adt)?;
+    let module = adt_def.module(ctx.db());
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let debug = famous_defs.core_fmt_Debug()?;
+    let display = famous_defs.core_fmt_Display()?;
+    let (trait_, trait_name, id) = match fmt_trait {
+        FmtTrait::Debug => (debug, "Debug", "generate_debug_impl"),
+        FmtTrait::Display => (display, "Display", "generate_display_impl"),
+    };
+    if adt_def.ty(ctx.db()).impls_trait(ctx.db(), trait_, &[]) {
+        return None;
+    }
+    let fmt = module.find_use_path(
+        ctx.db(),
+        ModuleDef::Module(trait_.module(ctx.db())),
+        ctx.config.prefer_no_std,
+    )?;
+
+    // Type parameters get a bound on the implemented trait, so fields of their type implement it.
+    let field_fmt = |field: &hir::Field| {
+        let ty = field.ty(ctx.db());
+        let implements = |it: hir::Trait| {
+            ty.impls_trait(ctx.db(), it, &[])
+                || (it == trait_ && ty.as_type_param(ctx.db()).is_some())
+        };
+        match fmt_trait {
+            FmtTrait::Display if implements(display) => FieldFmt::Display,
+            _ if implements(debug) => FieldFmt::Debug,
+            _ => FieldFmt::Skip,
+        }
+    };
+    let collect_fields = |fields: Vec<hir::Field>, value: &dyn Fn(&str) -> String| {
+        fields
+            .iter()
+            .map(|field| {
+                let name = field.name(ctx.db()).to_string();
+                let ty = field.ty(ctx.db()).display(ctx.db()).to_string();
+                Field { value: value(&name), name, ty, fmt: field_fmt(field) }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let body = match adt_def {
+        hir::Adt::Struct(strukt) => {
+            let name = strukt.name(ctx.db()).to_string();
+            let kind = strukt.kind(ctx.db());
+            let fields = collect_fields(strukt.fields(ctx.db()), &|it| format!("self.{it}"));
+            match fmt_trait {
+                FmtTrait::Debug => debug_fields(&name, kind, &fields, "&", "        "),
+                FmtTrait::Display => display_fields(&name, kind, &fields, "        "),
+            }
+        }
+        hir::Adt::Enum(enum_) => {
+            let mut buf = String::from("        match self {\n");
+            for variant in enum_.variants(ctx.db()) {
+                let name = variant.name(ctx.db()).to_string();
+                let kind = variant.kind(ctx.db());
+                let fields = collect_fields(variant.fields(ctx.db()), &|it| match kind {
+                    StructKind::Tuple => format!("arg{it}"),
+                    _ => it.to_owned(),
+                });
+                let pat = match kind {
+                    StructKind::Record => {
+                        let mut bindings = fields
+                            .iter()
+                            .filter(|it| it.fmt != FieldFmt::Skip)
+                            .map(Field::binding)
+                            .collect::<Vec<_>>();
+                        if bindings.len() < fields.len() {
+                            bindings.push("..");
+                        }
+                        format!("Self::{name} {{ {} }}", bindings.join(", "))
+                    }
+                    StructKind::Tuple => {
+                        format!("Self::{name}({})", fields.iter().map(Field::binding).join(", "))
+                    }
+                    StructKind::Unit => format!("Self::{name}"),
+                };
+                match (fmt_trait, kind) {
+                    (FmtTrait::Debug, StructKind::Unit) | (FmtTrait::Display, _) => {
+                        let write = match fmt_trait {
+                            FmtTrait::Debug => debug_fields(&name, kind, &fields, "", ""),
+                            FmtTrait::Display => display_fields(&name, kind, &fields, ""),
+                        };
+                        // Comments on skipped fields go before the arm.
+                        let (comments, write) = match write.rsplit_once('\n') {
+                            Some((comments, write)) => (comments.to_owned(), write.to_owned()),
+                            None => (String::new(), write),
+                        };
+                        for comment in comments.lines() {
+                            format_to!(buf, "            {comment}\n");
+                        }
+                        format_to!(buf, "            {pat} => {write},\n");
+                    }
+                    (FmtTrait::Debug, _) => {
+                        let chain = debug_fields(&name, kind, &fields, "", "                ");
+                        format_to!(buf, "            {pat} => {{\n{chain}\n            }}\n");
+                    }
+                }
+            }
+            buf.push_str("        }");
+            buf
+        }
+        hir::Adt::Union(_) => return None,
+    };
+
+    let target = adt.syntax().text_range();
+    acc.add(
+        AssistId(id, AssistKind::Generate),
+        format!("Generate `{trait_name}` impl"),
+        target,
+        |edit| {
+            let code = format!(
+                "    fn fmt(&self, f: &mut {fmt}::Formatter<'_>) -> {fmt}::Result {{\n{body}\n    }}"
+            );
+            let impl_text = generate_trait_impl_text(&adt, &format!("{fmt}::{trait_name}"), &code);
+            edit.insert(target.end(), impl_text);
+        },
+    )
+}
+
+/// The `f.debug_struct(..)` or `f.debug_tuple(..)` chain for a `Debug` impl, with each line
+/// indented by `indent`. `borrow` is prepended to the values of the fields.
+fn debug_fields(
+    name: &str,
+    kind: StructKind,
+    fields: &[Field],
+    borrow: &str,
+    indent: &str,
+) -> String {
+    let mut buf = String::new();
+    match kind {
+        StructKind::Unit => format_to!(buf, "{indent}f.write_str(\"{name}\")"),
+        StructKind::Record => {
+            format_to!(buf, "{indent}f.debug_struct(\"{name}\")");
+            for Field { name: field, value, ty, fmt } in fields {
+                match fmt {
+                    FieldFmt::Skip => format_to!(
+                        buf,
+                        "\n{indent}    // field `{field}` is skipped, `{ty}` doesn't implement `Debug`"
+                    ),
+                    _ => format_to!(buf, "\n{indent}    .field(\"{field}\", {borrow}{value})"),
+                }
+            }
+            let finish = if fields.iter().any(|it| it.fmt == FieldFmt::Skip) {
+                "finish_non_exhaustive"
+            } else {
+                "finish"
+            };
+            format_to!(buf, "\n{indent}    .{finish}()");
+        }
+        StructKind::Tuple => {
+            format_to!(buf, "{indent}f.debug_tuple(\"{name}\")");
+            for Field { name: field, value, ty, fmt } in fields {
+                if *fmt == FieldFmt::Skip {
+                    format_to!(
+                        buf,
+                        "\n{indent}    // field `{field}` is abbreviated, `{ty}` doesn't implement `Debug`"
+                    );
+                    format_to!(buf, "\n{indent}    .field(&format_args!(\"_\"))");
+                } else {
+                    format_to!(buf, "\n{indent}    .field({borrow}{value})");
+                }
+            }
+            format_to!(buf, "\n{indent}    .finish()");
+        }
+    }
+    buf
+}
+
+/// The `write!` call for a `Display` impl, preceded by a comment for every skipped field. Each
+/// line is indented by `indent`.
+fn display_fields(name: &str, kind: StructKind, fields: &[Field], indent: &str) -> String {
+    let mut buf = String::new();
+    for Field { name: field, ty, .. } in fields.iter().filter(|it| it.fmt == FieldFmt::Skip) {
+        format_to!(
+            buf,
+            "{indent}// field `{field}` is skipped, `{ty}` implements neither `Display` nor `Debug`\n"
+        );
+    }
+    let placeholder = |fmt| match fmt {
+        FieldFmt::Display => "{}",
+        FieldFmt::Debug => "{:?}",
+        FieldFmt::Skip => "_",
+    };
+    let format = match kind {
+        StructKind::Record if !fields.is_empty() => {
+            let mut parts = fields
+                .iter()
+                .filter(|it| it.fmt != FieldFmt::Skip)
+                .map(|it| format!("{}: {}", it.name, placeholder(it.fmt)))
+                .collect::<Vec<_>>();
+            if fields.iter().any(|it| it.fmt == FieldFmt::Skip) {
+                parts.push("..".to_owned());
+            }
+            format!("{name} {{{{ {} }}}}", parts.join(", "))
+        }
+        StructKind::Tuple => {
+            format!("{name}({})", fields.iter().map(|it| placeholder(it.fmt)).join(", "))
+        }
+        _ => {
+            format_to!(buf, "{indent}f.write_str(\"{name}\")");
+            return buf;
+        }
+    };
+    let args =
+        fields.iter().filter(|it| it.fmt != FieldFmt::Skip).map(|it| format!(", {}", it.value));
+    format_to!(buf, "{indent}write!(f, \"{format}\"{})", args.format(""));
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_if_implemented() {
+        check_assist_not_applicable(
+            generate_debug_impl,
+            r#"
+//- minicore: fmt
+struct Foo$0;
+
+impl core::fmt::Debug for Foo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { loop {} }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unions() {
+        check_assist_not_applicable(
+            generate_display_impl,
+            r#"
+//- minicore: fmt
+union Foo$0 { a: u8 }
+"#,
+        );
+    }
+
+    #[test]
+    fn debug_tuple_struct() {
+        check_assist(
+            generate_debug_impl,
+            r#"
+//- minicore: fmt
+use core::fmt;
+
+struct Id;
+
+impl fmt::Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { loop {} }
+}
+
+struct Handle$0(Id, Fd);
+
+struct Fd;
+"#,
+            r#"
+use core::fmt;
+
+struct Id;
+
+impl fmt::Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { loop {} }
+}
+
+struct Handle(Id, Fd);
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Handle")
+            .field(&self.0)
+            // field `1` is abbreviated, `Fd` doesn't implement `Debug`
+            .field(&format_args!("_"))
+            .finish()
+    }
+}
+
+struct Fd;
+"#,
+        );
+    }
+
+    #[test]
+    fn debug_enum() {
+        check_assist(
+            generate_debug_impl,
+            r#"
+//- minicore: fmt
+struct Secret;
+
+enum $0Auth<T> {
+    None,
+    Token(T, Secret),
+    Password { user: T, password: Secret },
+}
+"#,
+            r#"
+struct Secret;
+
+enum Auth<T> {
+    None,
+    Token(T, Secret),
+    Password { user: T, password: Secret },
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Auth<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Token(arg0, _) => {
+                f.debug_tuple("Token")
+                    .field(arg0)
+                    // field `1` is abbreviated, `Secret` doesn't implement `Debug`
+                    .field(&format_args!("_"))
+                    .finish()
+            }
+            Self::Password { user, .. } => {
+                f.debug_struct("Password")
+                    .field("user", user)
+                    // field `password` is skipped, `Secret` doesn't implement `Debug`
+                    .finish_non_exhaustive()
+            }
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn display_struct_falls_back_to_debug() {
+        check_assist(
+            generate_display_impl,
+            r#"
+//- minicore: fmt
+use core::fmt::{self, Debug, Display};
+
+struct Name;
+impl Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { loop {} }
+}
+
+struct Id;
+impl Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { loop {} }
+}
+
+struct Socket;
+
+struct $0User {
+    name: Name,
+    id: Id,
+    socket: Socket,
+}
+"#,
+            r#"
+use core::fmt::{self, Debug, Display};
+
+struct Name;
+impl Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { loop {} }
+}
+
+struct Id;
+impl Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { loop {} }
+}
+
+struct Socket;
+
+struct User {
+    name: Name,
+    id: Id,
+    socket: Socket,
+}
+
+impl fmt::Display for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // field `socket` is skipped, `Socket` implements neither `Display` nor `Debug`
+        write!(f, "User {{ name: {}, id: {:?}, .. }}", self.name, self.id)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn display_enum_with_skipped_fields() {
+        check_assist(
+            generate_display_impl,
+            r#"
+//- minicore: fmt
+struct Socket;
+
+enum Conn$0 {
+    Closed,
+    Open(Socket),
+}
+"#,
+            r#"
+struct Socket;
+
+enum Conn {
+    Closed,
+    Open(Socket),
+}
+
+impl core::fmt::Display for Conn {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Closed => f.write_str("Closed"),
+            // field `0` is skipped, `Socket` implements neither `Display` nor `Debug`
+            Self::Open(_) => write!(f, "Open(_)"),
+        }
+    }
+}
+"#,
+        );
+    }
+}
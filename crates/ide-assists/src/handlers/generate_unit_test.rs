@@ -0,0 +1,863 @@
+use hir::{AsAssocItem, HasVisibility, HirDisplay, ModPath, ModuleDef, PathKind, StructKind};
+use ide_db::{base_db::FileId, famous_defs::FamousDefs, helpers::mod_path_to_ast};
+use itertools::Itertools;
+use stdx::{format_to, to_lower_snake_case};
+use syntax::{
+    ast::{self, edit::IndentLevel, HasAttrs, HasModuleItem, HasName},
+    AstNode, TextSize,
+};
+
+use crate::{utils::test_related_attribute, AssistContext, AssistId, AssistKind, Assists};
+
+/// How deep placeholder arguments nest constructors and literals before resorting to `todo!()`.
+const MAX_DEPTH: usize = 3;
+
+// Assist: generate_unit_test
+//
+// Generates a unit test calling the function, with placeholder values for its arguments and an
+// assertion on its result. The test is added to the test module next to the function, which is
+// created if there is none.
+//
+// ```
+// fn add$0(a: u32, b: u32) -> u32 {
+//     a + b
+// }
+// ```
+// ->
+// ```
+// fn add(a: u32, b: u32) -> u32 {
+//     a + b
+// }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn test_add() {
+//         let a = 0;
+//         let b = 0;
+//         let result = add(a, b);
+//         assert_eq!(result, ${0:todo!()});
+//     }
+// }
+// ```
+pub(crate) fn generate_unit_test(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let ast_func = name.syntax().parent().and_then(ast::Fn::cast)?;
+    if ast_func.body().is_none()
+        || ast_func.async_token().is_some()
+        || test_related_attribute(&ast_func).is_some()
+        || ast_func.syntax().ancestors().filter_map(ast::Module::cast).any(|it| is_cfg_test(&it))
+    {
+        return None;
+    }
+    let db = ctx.db();
+    let func = ctx.sema.to_def(&ast_func)?;
+    let assoc = func.as_assoc_item(db);
+    let impl_ = match assoc.map(|it| it.container(db)) {
+        Some(hir::AssocItemContainer::Impl(it)) => Some(it),
+        // Default methods would need a type implementing the trait to be called on.
+        Some(hir::AssocItemContainer::Trait(_)) => return None,
+        None => None,
+    };
+
+    // The test goes into the test module next to the function, or the impl containing it.
+    let item = match impl_ {
+        Some(_) => ast_func.syntax().ancestors().find_map(ast::Impl::cast)?.syntax().clone(),
+        None => ast_func.syntax().clone(),
+    };
+    let items = item.parent()?;
+    let item_list = match ast::SourceFile::cast(items.clone()) {
+        Some(file) => file.items().collect::<Vec<_>>(),
+        None => ast::ItemList::cast(items.clone())?.items().collect(),
+    };
+    let test_module = item_list.iter().find_map(|it| match it {
+        ast::Item::Module(it) if is_cfg_test(it) => Some(it.clone()),
+        _ => None,
+    });
+
+    let (scope, existing_fns) = match &test_module {
+        Some(it) => (ctx.sema.to_def(it)?, test_module_fns(ctx, it)?),
+        None => (func.module(db), Vec::new()),
+    };
+    let placeholders = Placeholders {
+        ctx,
+        scope,
+        in_new_module: test_module.is_none(),
+        krate: func.module(db).krate(),
+    };
+    let test_name = test_name(&ast_func.name()?.text(), &existing_fns);
+
+    let placement = match &test_module {
+        Some(module) => match module.item_list() {
+            Some(list) => {
+                let (offset, sep) = match list.items().last() {
+                    Some(last) => (last.syntax().text_range().end(), "\n\n"),
+                    None => (list.l_curly_token()?.text_range().end(), "\n"),
+                };
+                let indent = IndentLevel::from_node(module.syntax()) + 1;
+                Placement::Inline { offset, sep, indent }
+            }
+            None => {
+                let source = ctx.sema.to_def(module)?.definition_source(db);
+                let hir::ModuleSource::SourceFile(file) = source.value else { return None };
+                let sep = if file.items().next().is_some() { "\n" } else { "" };
+                let offset = file.syntax().text_range().end();
+                Placement::File { file_id: source.file_id.original_file(db), offset, sep }
+            }
+        },
+        None => Placement::NewModule {
+            offset: item_list.last()?.syntax().text_range().end(),
+            indent: IndentLevel::from_node(&item),
+        },
+    };
+
+    acc.add(
+        AssistId("generate_unit_test", AssistKind::Generate),
+        "Generate a unit test",
+        ast_func.syntax().text_range(),
+        |builder| {
+            let body = placeholders.test_body(func, impl_);
+            let mut test = format!("#[test]\nfn {test_name}() {{\n");
+            for line in body {
+                format_to!(test, "    {line}\n");
+            }
+            test.push('}');
+
+            let (offset, text) = match placement {
+                Placement::Inline { offset, sep, indent } => {
+                    (offset, format!("{sep}{indent}{}", indent_text(&test, indent)))
+                }
+                Placement::File { file_id, offset, sep } => {
+                    // Snippets are only supported in the current file.
+                    builder.edit_file(file_id);
+                    builder.insert(offset, format!("{sep}{}\n", without_snippet(&test)));
+                    return;
+                }
+                Placement::NewModule { offset, indent } => {
+                    let module = format!(
+                        "#[cfg(test)]\nmod tests {{\n    use super::*;\n\n    {}\n}}",
+                        indent_text(&test, IndentLevel(1))
+                    );
+                    (offset, format!("\n\n{indent}{}", indent_text(&module, indent)))
+                }
+            };
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, text),
+                None => builder.insert(offset, without_snippet(&text)),
+            }
+        },
+    )
+}
+
+enum Placement {
+    /// Appends to an inline test module.
+    Inline { offset: TextSize, sep: &'static str, indent: IndentLevel },
+    /// Appends to a test module in its own file.
+    File { file_id: FileId, offset: TextSize, sep: &'static str },
+    /// Creates a test module after the last item.
+    NewModule { offset: TextSize, indent: IndentLevel },
+}
+
+struct Placeholders<'a> {
+    ctx: &'a AssistContext<'a>,
+    /// The module paths in the test are resolved from.
+    scope: hir::Module,
+    /// Whether the test module is created as a child of `scope`.
+    in_new_module: bool,
+    krate: hir::Crate,
+}
+
+impl Placeholders<'_> {
+    /// Returns the lines of a test calling `func`.
+    fn test_body(&self, func: hir::Function, impl_: Option<hir::Impl>) -> Vec<String> {
+        let db = self.ctx.db();
+        let mut lines = Vec::new();
+        let mut args = Vec::new();
+        let self_ty = impl_.map(|it| it.self_ty(db));
+
+        let receiver = match (func.self_param(db), &self_ty) {
+            (Some(self_param), Some(self_ty)) => {
+                let name = match self_ty.as_adt() {
+                    Some(adt) => to_lower_snake_case(&adt.name(db).to_smol_str()),
+                    None => "value".to_owned(),
+                };
+                let mut_ =
+                    if self_param.access(db) == hir::Access::Exclusive { "mut " } else { "" };
+                lines.push(format!("let {mut_}{name} = {};", self.term(self_ty, MAX_DEPTH)));
+                Some(name)
+            }
+            _ => None,
+        };
+        for (idx, param) in func.params_without_self(db).into_iter().enumerate() {
+            let name = match param.name(db) {
+                Some(name) => name.to_smol_str().to_string(),
+                None => format!("arg{idx}"),
+            };
+            let ty = param.ty();
+            match ty.as_reference() {
+                Some((inner, mutability)) if !inner.is_slice() && !is_str(&inner) => {
+                    let (mut_, borrow) = match mutability {
+                        hir::Mutability::Mut => ("mut ", "&mut "),
+                        hir::Mutability::Shared => ("", "&"),
+                    };
+                    lines.push(format!("let {mut_}{name} = {};", self.term(&inner, MAX_DEPTH)));
+                    args.push(format!("{borrow}{name}"));
+                }
+                _ => {
+                    lines.push(format!("let {name} = {};", self.term(ty, MAX_DEPTH)));
+                    args.push(name);
+                }
+            }
+        }
+
+        let fn_name = func.name(db);
+        let args = args.join(", ");
+        let mut call = match (receiver, &self_ty) {
+            (Some(receiver), _) => format!("{receiver}.{fn_name}({args})"),
+            (None, Some(self_ty)) => {
+                let self_path = self_ty
+                    .as_adt()
+                    .and_then(|it| self.path(it))
+                    .unwrap_or_else(|| self_ty.display(db).to_string());
+                format!("{self_path}::{fn_name}({args})")
+            }
+            (None, None) => {
+                let path = self.path(func).unwrap_or_else(|| fn_name.to_string());
+                format!("{path}({args})")
+            }
+        };
+        if func.is_unsafe_to_call(db) {
+            call = format!("unsafe {{ {call} }}");
+        }
+
+        let ret_ty = func.ret_type(db);
+        let famous_defs = FamousDefs(&self.ctx.sema, self.krate);
+        let result_args = match (ret_ty.as_adt(), famous_defs.core_result_Result()) {
+            (Some(hir::Adt::Enum(it)), Some(result)) if it == result => {
+                ret_ty.type_arguments().collect_tuple()
+            }
+            _ => None,
+        };
+        if ret_ty.is_unit() || ret_ty.is_never() {
+            lines.push(format!("{call};"));
+        } else if let Some((ok_ty, err_ty)) = result_args {
+            let err_is_debug = famous_defs
+                .core_fmt_Debug()
+                .map_or(false, |debug| err_ty.impls_trait(db, debug, &[]));
+            match (ok_ty.is_unit(), err_is_debug) {
+                (true, true) => lines.push(format!("{call}.unwrap();")),
+                (true, false) => lines.push(format!("assert!({call}.is_ok());")),
+                (false, true) => {
+                    lines.push(format!("let result = {call}.unwrap();"));
+                    lines.push(assertion(&ok_ty));
+                }
+                (false, false) => {
+                    lines.push(format!("let Ok(result) = {call} else {{ panic!() }};"));
+                    lines.push(assertion(&ok_ty));
+                }
+            }
+        } else if ret_ty.is_bool() {
+            lines.push(format!("assert!({call});"));
+        } else {
+            lines.push(format!("let result = {call};"));
+            lines.push(assertion(&ret_ty));
+        }
+        lines
+    }
+
+    /// Builds an expression of type `ty`, falling back to `todo!()` where none is found.
+    fn term(&self, ty: &hir::Type, depth: usize) -> String {
+        self.try_term(ty, depth).unwrap_or_else(|| "todo!()".to_owned())
+    }
+
+    fn try_term(&self, ty: &hir::Type, depth: usize) -> Option<String> {
+        let db = self.ctx.db();
+        if ty.is_unit() {
+            return Some("()".to_owned());
+        } else if ty.is_bool() {
+            return Some("false".to_owned());
+        } else if ty.is_int_or_uint() {
+            return Some("0".to_owned());
+        } else if ty.is_float() {
+            return Some("0.0".to_owned());
+        } else if ty.is_char() {
+            return Some("'a'".to_owned());
+        }
+        if let Some((inner, mutability)) = ty.as_reference() {
+            let mut_ = if mutability == hir::Mutability::Mut { "mut " } else { "" };
+            if is_str(&inner) && mut_.is_empty() {
+                return Some("\"\"".to_owned());
+            } else if inner.is_slice() {
+                return Some(format!("&{mut_}[]"));
+            }
+            return Some(format!("&{mut_}{}", self.try_term(&inner, depth)?));
+        }
+        if depth == 0 {
+            return None;
+        }
+        let depth = depth - 1;
+
+        let fields = ty.tuple_fields(db);
+        if !fields.is_empty() {
+            let trailing_comma = if fields.len() == 1 { "," } else { "" };
+            let fields = fields.iter().map(|it| self.term(it, depth)).join(", ");
+            return Some(format!("({fields}{trailing_comma})"));
+        }
+        if let Some((elem, len)) = ty.as_array(db) {
+            return Some(format!("[{}; {len}]", self.term(&elem, depth)));
+        }
+
+        match ty.as_adt()? {
+            hir::Adt::Enum(enum_) => {
+                // Prefer variants like `None` over ones with fields.
+                let variant =
+                    enum_.variants(db).into_iter().min_by_key(|it| it.fields(db).len())?;
+                let fields = variant.fields(db).iter().map(|it| (it.name(db), it.ty(db))).collect();
+                Some(self.constructor_call(self.path(variant)?, variant.kind(db), fields, depth))
+            }
+            adt @ hir::Adt::Struct(strukt) => {
+                let path = self.path(adt)?;
+                if strukt.kind(db) == StructKind::Unit {
+                    return Some(path);
+                }
+                if let Some(ctor) = self.constructor(ty, adt, &path, depth) {
+                    return Some(ctor);
+                }
+                let default = FamousDefs(&self.ctx.sema, self.krate).core_default_Default();
+                if default.map_or(false, |it| ty.impls_trait(db, it, &[])) {
+                    return Some("Default::default()".to_owned());
+                }
+                let fields = strukt.fields(db);
+                if !fields.iter().all(|it| it.is_visible_from(db, self.scope)) {
+                    return None;
+                }
+                let fields = fields.iter().map(|it| (it.name(db), it.ty(db))).collect();
+                Some(self.constructor_call(path, strukt.kind(db), fields, depth))
+            }
+            hir::Adt::Union(_) => None,
+        }
+    }
+
+    /// Calls an associated function returning `adt`, preferring one called `new`.
+    fn constructor(
+        &self,
+        ty: &hir::Type,
+        adt: hir::Adt,
+        path: &str,
+        depth: usize,
+    ) -> Option<String> {
+        let db = self.ctx.db();
+        let mut ctors = Vec::new();
+        ty.iterate_assoc_items(db, self.krate, |item| {
+            if let hir::AssocItem::Function(func) = item {
+                if !func.has_self_param(db)
+                    && func.ret_type(db).as_adt() == Some(adt)
+                    && func.is_visible_from(db, self.scope)
+                {
+                    ctors.push(func);
+                }
+            }
+            None::<()>
+        });
+        let ctor = ctors
+            .into_iter()
+            .min_by_key(|it| (it.name(db).to_smol_str() != "new", it.num_params(db)))?;
+        let args = ctor.assoc_fn_params(db).iter().map(|it| self.term(it.ty(), depth)).join(", ");
+        Some(format!("{path}::{}({args})", ctor.name(db)))
+    }
+
+    fn constructor_call(
+        &self,
+        path: String,
+        kind: StructKind,
+        fields: Vec<(hir::Name, hir::Type)>,
+        depth: usize,
+    ) -> String {
+        match kind {
+            StructKind::Unit => path,
+            StructKind::Tuple => {
+                let args = fields.iter().map(|(_, ty)| self.term(ty, depth)).join(", ");
+                format!("{path}({args})")
+            }
+            StructKind::Record => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {}", self.term(ty, depth)))
+                    .join(", ");
+                format!("{path} {{ {fields} }}")
+            }
+        }
+    }
+
+    /// Renders a path to `def` that is valid inside the test module.
+    fn path(&self, def: impl Into<ModuleDef>) -> Option<String> {
+        let db = self.ctx.db();
+        let path = self.scope.find_use_path(db, def.into(), self.ctx.config.prefer_no_std)?;
+        let path = match path.kind {
+            PathKind::Super(n) if self.in_new_module => {
+                ModPath::from_segments(PathKind::Super(n + 1), path.segments().iter().cloned())
+            }
+            _ => path,
+        };
+        Some(mod_path_to_ast(&path).to_string())
+    }
+}
+
+fn assertion(ty: &hir::Type) -> String {
+    if ty.is_bool() {
+        "assert!(result);".to_owned()
+    } else {
+        "assert_eq!(result, ${0:todo!()});".to_owned()
+    }
+}
+
+fn is_str(ty: &hir::Type) -> bool {
+    ty.as_builtin().map_or(false, |it| it.is_str())
+}
+
+fn is_cfg_test(module: &ast::Module) -> bool {
+    module.attrs().any(|attr| match attr.as_simple_call() {
+        Some((name, tt)) => name == "cfg" && tt.syntax().text() == "(test)",
+        None => false,
+    })
+}
+
+/// Returns the functions of the test module, whether it is inline or in its own file.
+fn test_module_fns(ctx: &AssistContext<'_>, module: &ast::Module) -> Option<Vec<ast::Fn>> {
+    let items: Vec<_> = match module.item_list() {
+        Some(list) => list.items().collect(),
+        None => match ctx.sema.to_def(module)?.definition_source(ctx.db()).value {
+            hir::ModuleSource::SourceFile(file) => file.items().collect(),
+            _ => return None,
+        },
+    };
+    Some(
+        items
+            .into_iter()
+            .filter_map(|it| match it {
+                ast::Item::Fn(it) => Some(it),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+/// Names the test like the existing tests: with a `test_` prefix, unless they mostly don't have
+/// one, in which case the name needs a suffix to not shadow the function under test.
+fn test_name(fn_name: &str, existing_fns: &[ast::Fn]) -> String {
+    let tests: Vec<_> = existing_fns
+        .iter()
+        .filter(|it| test_related_attribute(it).is_some())
+        .filter_map(|it| it.name())
+        .collect();
+    let prefixed = tests.iter().filter(|it| it.text().starts_with("test_")).count();
+    let base = if prefixed * 2 >= tests.len() {
+        format!("test_{fn_name}")
+    } else {
+        format!("{fn_name}_works")
+    };
+    let is_taken =
+        |name: &str| existing_fns.iter().filter_map(|it| it.name()).any(|it| it.text() == name);
+    let mut name = base.clone();
+    let mut counter = 1;
+    while is_taken(&name) {
+        counter += 1;
+        name = format!("{base}_{counter}");
+    }
+    name
+}
+
+fn indent_text(text: &str, indent: IndentLevel) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| match idx {
+            0 => line.to_owned(),
+            _ if line.is_empty() => String::new(),
+            _ => format!("{indent}{line}"),
+        })
+        .join("\n")
+}
+
+fn without_snippet(text: &str) -> String {
+    text.replace("${0:todo!()}", "todo!()")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn appends_to_test_module() {
+        check_assist(
+            generate_unit_test,
+            r#"
+fn double$0(x: i32) -> i32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_other() {}
+}
+"#,
+            r#"
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_other() {}
+
+    #[test]
+    fn test_double() {
+        let x = 0;
+        let result = double(x);
+        assert_eq!(result, ${0:todo!()});
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn follows_test_naming() {
+        check_assist(
+            generate_unit_test,
+            r#"
+fn double$0(x: i32) -> i32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {}
+
+    #[test]
+    fn double_works() {}
+}
+"#,
+            r#"
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {}
+
+    #[test]
+    fn double_works() {}
+
+    #[test]
+    fn double_works_2() {
+        let x = 0;
+        let result = double(x);
+        assert_eq!(result, ${0:todo!()});
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn appends_to_test_module_file() {
+        check_assist(
+            generate_unit_test,
+            r#"
+//- /main.rs
+fn is_even$0(x: i32) -> bool {
+    x % 2 == 0
+}
+
+#[cfg(test)]
+mod tests;
+//- /tests.rs
+use super::*;
+"#,
+            r#"
+use super::*;
+
+#[test]
+fn test_is_even() {
+    let x = 0;
+    assert!(is_even(x));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_returning_result() {
+        check_assist(
+            generate_unit_test,
+            r#"
+//- minicore: result, fmt
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn new(count: u32) -> Counter {
+        Counter { count }
+    }
+
+    fn add$0(&mut self, n: u32) -> Result<u32, Error> {
+        self.count += n;
+        Ok(self.count)
+    }
+}
+
+struct Error;
+
+impl core::fmt::Debug for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Ok(())
+    }
+}
+"#,
+            r#"
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn new(count: u32) -> Counter {
+        Counter { count }
+    }
+
+    fn add(&mut self, n: u32) -> Result<u32, Error> {
+        self.count += n;
+        Ok(self.count)
+    }
+}
+
+struct Error;
+
+impl core::fmt::Debug for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut counter = Counter::new(0);
+        let n = 0;
+        let result = counter.add(n).unwrap();
+        assert_eq!(result, ${0:todo!()});
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn result_without_debug_error() {
+        check_assist(
+            generate_unit_test,
+            r#"
+//- minicore: result, fmt
+struct Error;
+
+fn parse$0(s: &str) -> Result<u8, Error> {
+    Err(Error)
+}
+
+fn check(s: &str) -> Result<(), Error> {
+    Ok(())
+}
+"#,
+            r#"
+struct Error;
+
+fn parse(s: &str) -> Result<u8, Error> {
+    Err(Error)
+}
+
+fn check(s: &str) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let s = "";
+        let Ok(result) = parse(s) else { panic!() };
+        assert_eq!(result, ${0:todo!()});
+    }
+}
+"#,
+        );
+        check_assist(
+            generate_unit_test,
+            r#"
+//- minicore: result, fmt
+struct Error;
+
+fn check$0(s: &str) -> Result<(), Error> {
+    Ok(())
+}
+"#,
+            r#"
+struct Error;
+
+fn check(s: &str) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let s = "";
+        assert!(check(s).is_ok());
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn argument_placeholders() {
+        check_assist(
+            generate_unit_test,
+            r#"
+//- minicore: option, default
+mod shapes {
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    pub enum Shape {
+        Circle(f32),
+        Square { side: f32 },
+    }
+
+    pub struct Canvas;
+
+    pub struct Settings {
+        scale: f32,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Settings { scale: 1.0 }
+        }
+    }
+
+    pub fn draw$0(
+        canvas: Canvas,
+        at: &Point,
+        shape: Shape,
+        settings: &mut Settings,
+        label: &str,
+        flags: (bool, char),
+        color: Option<u8>,
+    ) {
+    }
+}
+"#,
+            r#"
+mod shapes {
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    pub enum Shape {
+        Circle(f32),
+        Square { side: f32 },
+    }
+
+    pub struct Canvas;
+
+    pub struct Settings {
+        scale: f32,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Settings { scale: 1.0 }
+        }
+    }
+
+    pub fn draw(
+        canvas: Canvas,
+        at: &Point,
+        shape: Shape,
+        settings: &mut Settings,
+        label: &str,
+        flags: (bool, char),
+        color: Option<u8>,
+    ) {
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_draw() {
+            let canvas = Canvas;
+            let at = Point { x: 0, y: 0 };
+            let shape = Shape::Circle(0.0);
+            let mut settings = Default::default();
+            let label = "";
+            let flags = (false, 'a');
+            let color = None;
+            draw(canvas, &at, shape, &mut settings, label, flags, color);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_tests_or_traits() {
+        check_assist_not_applicable(
+            generate_unit_test,
+            r#"
+#[cfg(test)]
+mod tests {
+    fn helper$0() {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_unit_test,
+            r#"
+trait Foo {
+    fn foo$0(&self) {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,515 @@
+use hir::PathResolution;
+use ide_db::{
+    assists::{AssistId, AssistKind},
+    change_signature::{self, SignatureParam},
+};
+use stdx::never;
+use syntax::{
+    ast::{self, HasName},
+    AstNode, NodeOrToken, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use SyntaxKind::WHITESPACE;
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    utils::{suggest_name, unique_local_name},
+};
+
+// Assist: introduce_parameter
+//
+// Turns an expression, or a local variable, into a new parameter of the enclosing function. The
+// expression is passed as the new argument at every call site of the function.
+//
+// ```
+// struct Color(u8, u8, u8);
+// fn paint(width: u32, color: Color) {}
+//
+// fn fill(width: u32) {
+//     paint(width, $0Color(0, 0, 0)$0);
+// }
+//
+// fn main() {
+//     fill(10);
+// }
+// ```
+// ->
+// ```
+// struct Color(u8, u8, u8);
+// fn paint(width: u32, color: Color) {}
+//
+// fn fill(width: u32, $0color: Color) {
+//     paint(width, color);
+// }
+//
+// fn main() {
+//     fill(10, Color(0, 0, 0));
+// }
+// ```
+pub(crate) fn introduce_parameter(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let target = Target::find(ctx)?;
+    let func = target.syntax().ancestors().find_map(ast::Fn::cast)?;
+    let body = func.body()?;
+    if !body.syntax().text_range().contains_range(target.syntax().text_range()) {
+        return None;
+    }
+    let db = ctx.db();
+    let fn_def = ctx.sema.to_def(&func)?;
+    // The signatures of trait methods, and of functions defined by macros, can't be changed.
+    if change_signature::check_function(db, fn_def).is_err() {
+        cov_mark::hit!(introduce_parameter_unsupported_function);
+        return None;
+    }
+
+    let value = target.value()?;
+    // The value is evaluated at the call sites, so it can't refer to anything which is only
+    // visible inside of the function.
+    if !is_movable_to_call_site(ctx, &value) {
+        cov_mark::hit!(introduce_parameter_not_movable);
+        return None;
+    }
+
+    let ty = target.ty(ctx)?;
+    if ty.contains_unknown() || !ty.generic_params(db).is_empty() {
+        return None;
+    }
+    let module = ctx.sema.scope(func.syntax())?.module();
+    let ty = ty.display_source_code(db, module.into(), false).ok()?;
+    let param_list = func.param_list()?;
+
+    let (pattern, name) = match &target {
+        Target::Expr(expr) => {
+            let scope = ctx.sema.scope(expr.syntax())?;
+            let name = unique_local_name(&scope, suggest_name::for_variable(expr, &ctx.sema));
+            (name.clone(), name)
+        }
+        Target::Local(_, pat) => (pat.to_string(), pat.name()?.to_string()),
+    };
+
+    acc.add(
+        AssistId("introduce_parameter", AssistKind::RefactorExtract),
+        "Introduce parameter",
+        target.syntax().text_range(),
+        |builder| {
+            let tabstop = if ctx.config.snippet_cap.is_some() { "$0" } else { "" };
+            let mut params: Vec<_> =
+                (0..param_list.params().count()).map(SignatureParam::Existing).collect();
+            params.push(SignatureParam::FromBody {
+                pattern: format!("{tabstop}{pattern}"),
+                name: name.clone(),
+                ty,
+                value,
+            });
+            let edit = match &target {
+                Target::Expr(_) => name,
+                Target::Local(..) => String::new(),
+            };
+            let edits = [(target.range_to_edit(), edit)];
+            // Other references to the function, like `iter.map(f)`, are wrapped in closures.
+            let source_change =
+                match change_signature::change_signature(&ctx.sema, fn_def, &params, &edits) {
+                    Ok(it) => it,
+                    Err(e) => {
+                        never!("failed to introduce a parameter: {}", e);
+                        return;
+                    }
+                };
+            let signature = param_list.syntax().text_range();
+            for (file_id, edit) in source_change.source_file_edits {
+                builder.edit_file(file_id);
+                let is_fn_file = file_id == ctx.file_id();
+                for indel in edit {
+                    match ctx.config.snippet_cap {
+                        Some(cap) if is_fn_file && indel.delete.contains_range(signature) => {
+                            builder.replace_snippet(cap, indel.delete, indel.insert)
+                        }
+                        _ => builder.replace(indel.delete, indel.insert),
+                    }
+                }
+            }
+        },
+    )
+}
+
+enum Target {
+    /// The selected expression.
+    Expr(ast::Expr),
+    /// A `let` statement whose binding becomes the parameter.
+    Local(ast::LetStmt, ast::IdentPat),
+}
+
+impl Target {
+    fn find(ctx: &AssistContext<'_>) -> Option<Target> {
+        if ctx.has_empty_selection() {
+            let pat = ctx.find_node_at_offset::<ast::IdentPat>()?;
+            if pat.at_token().is_some() || pat.ref_token().is_some() {
+                return None;
+            }
+            let let_stmt = pat.syntax().parent().and_then(ast::LetStmt::cast)?;
+            if let_stmt.let_else().is_some() || let_stmt.initializer().is_none() {
+                return None;
+            }
+            return Some(Target::Local(let_stmt, pat));
+        }
+
+        let node = match ctx.covering_element() {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent()?,
+        };
+        let expr = node.ancestors().find_map(ast::Expr::cast)?;
+        if expr.syntax().text_range() != ctx.selection_trimmed() {
+            return None;
+        }
+        Some(Target::Expr(expr))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Target::Expr(expr) => expr.syntax(),
+            Target::Local(let_stmt, _) => let_stmt.syntax(),
+        }
+    }
+
+    fn value(&self) -> Option<ast::Expr> {
+        match self {
+            Target::Expr(expr) => Some(expr.clone()),
+            Target::Local(let_stmt, _) => let_stmt.initializer(),
+        }
+    }
+
+    fn ty(&self, ctx: &AssistContext<'_>) -> Option<hir::Type> {
+        match self {
+            Target::Expr(expr) => Some(ctx.sema.type_of_expr(expr)?.original),
+            Target::Local(_, pat) => Some(ctx.sema.type_of_pat(&pat.clone().into())?.original),
+        }
+    }
+
+    /// The range which is replaced by the parameter's name, or removed for locals.
+    fn range_to_edit(&self) -> TextRange {
+        let range = self.syntax().text_range();
+        match self {
+            Target::Expr(_) => range,
+            Target::Local(let_stmt, _) => {
+                match let_stmt.syntax().next_sibling_or_token().and_then(|it| it.into_token()) {
+                    Some(ws) if ws.kind() == WHITESPACE => range.cover(ws.text_range()),
+                    _ => range,
+                }
+            }
+        }
+    }
+}
+
+/// Whether `expr` only uses items, so that it means the same thing at the call sites.
+fn is_movable_to_call_site(ctx: &AssistContext<'_>, expr: &ast::Expr) -> bool {
+    expr.syntax().descendants().all(|node| {
+        if let Some(path) = ast::Path::cast(node.clone()) {
+            return !matches!(
+                ctx.sema.resolve_path(&path),
+                Some(
+                    PathResolution::Local(_)
+                        | PathResolution::TypeParam(_)
+                        | PathResolution::ConstParam(_)
+                        | PathResolution::SelfType(_)
+                )
+            );
+        }
+        // Control flow would now apply to the caller.
+        !matches!(
+            ast::Expr::cast(node),
+            Some(
+                ast::Expr::ReturnExpr(_)
+                    | ast::Expr::TryExpr(_)
+                    | ast::Expr::BreakExpr(_)
+                    | ast::Expr::ContinueExpr(_)
+                    | ast::Expr::AwaitExpr(_)
+                    | ast::Expr::YieldExpr(_)
+            )
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn introduce_expression() {
+        check_assist(
+            introduce_parameter,
+            r#"
+fn scale(x: u32) -> u32 {
+    x + $010 * 2$0
+}
+
+fn main() {
+    scale(1);
+    scale(2,);
+}
+"#,
+            r#"
+fn scale(x: u32, $0var_name: u32) -> u32 {
+    x + var_name
+}
+
+fn main() {
+    scale(1, 10 * 2);
+    scale(2, 10 * 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_local() {
+        check_assist(
+            introduce_parameter,
+            r#"
+struct Config { verbose: bool }
+fn run() {
+    let $0config = Config { verbose: true };
+    let _ = config.verbose;
+}
+
+fn main() {
+    run();
+}
+"#,
+            r#"
+struct Config { verbose: bool }
+fn run($0config: Config) {
+    let _ = config.verbose;
+}
+
+fn main() {
+    run(Config { verbose: true });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_mutable_local_into_method() {
+        check_assist(
+            introduce_parameter,
+            r#"
+struct S;
+impl S {
+    fn count(&self) -> usize {
+        let mut $0n = 0;
+        n += 1;
+        n
+    }
+}
+
+fn main() {
+    S.count();
+    S::count(&S);
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn count(&self, $0mut n: usize) -> usize {
+        n += 1;
+        n
+    }
+}
+
+fn main() {
+    S.count(0);
+    S::count(&S, 0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn recursive_calls_pass_the_parameter() {
+        check_assist(
+            introduce_parameter,
+            r#"
+fn countdown(n: u32) {
+    if n > 0 {
+        countdown(n - $01$0);
+    }
+}
+
+fn main() {
+    countdown(3);
+}
+"#,
+            r#"
+fn countdown(n: u32, $0var_name: u32) {
+    if n > 0 {
+        countdown(n - var_name, var_name);
+    }
+}
+
+fn main() {
+    countdown(3, 1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_in_other_files() {
+        check_assist(
+            introduce_parameter,
+            r#"
+//- /main.rs
+fn greet() -> &'static str {
+    $0"hi"$0
+}
+
+mod foo;
+
+//- /foo.rs
+use super::greet;
+
+fn bar() {
+    let _ = greet();
+}
+"#,
+            r#"
+//- /main.rs
+fn greet($0var_name: &'static str) -> &'static str {
+    var_name
+}
+
+mod foo;
+
+//- /foo.rs
+use super::greet;
+
+fn bar() {
+    let _ = greet("hi");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualify_paths_at_call_sites() {
+        check_assist(
+            introduce_parameter,
+            r#"
+mod paint {
+    pub struct Color(pub u8, pub u8, pub u8);
+    pub const BLACK: u8 = 0;
+
+    pub fn fill() -> Color {
+        $0Color(BLACK, 0, 0)$0
+    }
+
+    fn refill() -> Color {
+        fill()
+    }
+}
+
+fn main() {
+    paint::fill();
+}
+"#,
+            r#"
+mod paint {
+    pub struct Color(pub u8, pub u8, pub u8);
+    pub const BLACK: u8 = 0;
+
+    pub fn fill($0color: Color) -> Color {
+        color
+    }
+
+    fn refill() -> Color {
+        fill(Color(BLACK, 0, 0))
+    }
+}
+
+fn main() {
+    paint::fill(paint::Color(paint::BLACK, 0, 0));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_references_in_closures() {
+        check_assist(
+            introduce_parameter,
+            r#"
+//- minicore: option
+fn double(x: u32) -> u32 {
+    x * $02$0
+}
+
+fn main() {
+    Some(1).map(double);
+}
+"#,
+            r#"
+fn double(x: u32, $0var_name: u32) -> u32 {
+    x * var_name
+}
+
+fn main() {
+    Some(1).map(|x| double(x, 2));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_trait_impl() {
+        cov_mark::check!(introduce_parameter_unsupported_function);
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+trait Tr { fn f(&self) -> u32; }
+struct S;
+impl Tr for S {
+    fn f(&self) -> u32 { $092$0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_using_locals() {
+        cov_mark::check!(introduce_parameter_not_movable);
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+fn f(x: u32) -> u32 {
+    let y = $0x + 1$0;
+    y
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_control_flow() {
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+//- minicore: option
+fn f(x: Option<u32>) -> Option<u32> {
+    let y = $0Some(1)?$0;
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_of_fn_body() {
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+const C: u32 = $01 + 1$0;
+"#,
+        );
+    }
+}
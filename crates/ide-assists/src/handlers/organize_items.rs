@@ -0,0 +1,173 @@
+use syntax::{ast, AstNode, TextRange};
+
+use crate::{
+    handlers::{
+        reorder_impl_items::impl_items_in_trait_order,
+        reorder_match_arms::{match_arms_in_variant_order, reorder_match_arms_text},
+    },
+    utils::reorder_siblings,
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: organize_items
+//
+// Sorts all the trait impl items of the file in the order of the trait definition,
+// and all the match arms in the order of the enum definition. This is a source
+// action, so it can be run on save.
+//
+// ```
+// $0enum Dir { North, South }
+// trait Flip { fn flip(&self) -> Dir; fn name(&self) -> &str; }
+//
+// impl Flip for Dir {
+//     fn name(&self) -> &str { "dir" }
+//     fn flip(&self) -> Dir { opposite(self) }
+// }
+//
+// fn opposite(dir: &Dir) -> Dir {
+//     match dir {
+//         Dir::South => Dir::North,
+//         Dir::North => Dir::South,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Dir { North, South }
+// trait Flip { fn flip(&self) -> Dir; fn name(&self) -> &str; }
+//
+// impl Flip for Dir {
+//     fn flip(&self) -> Dir { opposite(self) }
+//     fn name(&self) -> &str { "dir" }
+// }
+//
+// fn opposite(dir: &Dir) -> Dir {
+//     match dir {
+//         Dir::North => Dir::South,
+//         Dir::South => Dir::North,
+//     }
+// }
+// ```
+pub(crate) fn organize_items(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let source_file = ctx.source_file();
+
+    // The nodes are visited in preorder, so when an unsorted node contains another one, the outer
+    // node is sorted first and the inner one is left for the next run.
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    for node in source_file.syntax().descendants() {
+        if edits.last().map_or(false, |(range, _)| range.contains_range(node.text_range())) {
+            continue;
+        }
+        let edit = if let Some(impl_) = ast::Impl::cast(node.clone()) {
+            let Some((items, sorted)) = impl_items_in_trait_order(&ctx.sema, &impl_) else {
+                continue;
+            };
+            if items == sorted {
+                continue;
+            }
+            reorder_siblings(&items, &sorted, |_| None)
+        } else if let Some(match_expr) = ast::MatchExpr::cast(node) {
+            let Some((arms, sorted)) = match_arms_in_variant_order(&ctx.sema, &match_expr) else {
+                continue;
+            };
+            if arms == sorted {
+                continue;
+            }
+            reorder_match_arms_text(&arms, &sorted)
+        } else {
+            continue;
+        };
+        edits.extend(edit);
+    }
+    if edits.is_empty() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("organize_items", AssistKind::Source),
+        "Sort impl items and match arms by definition",
+        source_file.syntax().text_range(),
+        |builder| {
+            for (range, text) in edits {
+                builder.replace(range, text);
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_if_everything_is_sorted() {
+        check_assist_not_applicable(
+            organize_items,
+            r#"
+trait T { fn a(); fn b(); }
+struct S;
+impl T for S {
+    fn a() {}
+    fn b() {}
+}
+impl S {
+    fn b() {}
+    fn a() {$0}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_the_whole_file() {
+        check_assist(
+            organize_items,
+            r#"
+$0enum E { A, B }
+trait T { const X: u8; fn f(e: E) -> u8; }
+struct S;
+impl T for S {
+    fn f(e: E) -> u8 {
+        match e {
+            E::B => 1,
+            E::A => Self::X,
+        }
+    }
+    /// The answer.
+    const X: u8 = 42;
+}
+fn g(e: E) -> u8 {
+    match e {
+        // b
+        E::B => 2,
+        E::A => 1,
+    }
+}
+"#,
+            r#"
+enum E { A, B }
+trait T { const X: u8; fn f(e: E) -> u8; }
+struct S;
+impl T for S {
+    /// The answer.
+    const X: u8 = 42;
+    fn f(e: E) -> u8 {
+        match e {
+            E::B => 1,
+            E::A => Self::X,
+        }
+    }
+}
+fn g(e: E) -> u8 {
+    match e {
+        E::A => 1,
+        // b
+        E::B => 2,
+    }
+}
+"#,
+        );
+    }
+}
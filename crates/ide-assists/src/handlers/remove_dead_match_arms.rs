@@ -0,0 +1,322 @@
+use hir::{PathResolution, Semantics};
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxKind, TextRange};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: remove_dead_match_arms
+//
+// Removes the arms of a `match` which can't match, because the value of the scrutinee is known
+// at compile time, or because their patterns bind an uninhabited type.
+//
+// ```
+// enum Never {}
+//
+// fn f(x: Result<u8, Never>) -> u8 {
+//     $0match x {
+//         Ok(x) => x,
+//         Err(_) => 0,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Never {}
+//
+// fn f(x: Result<u8, Never>) -> u8 {
+//     match x {
+//         Ok(x) => x,
+//     }
+// }
+// ```
+pub(crate) fn remove_dead_match_arms(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    let module = ctx.sema.scope(match_expr.syntax())?.module();
+
+    let mut known_value = match_expr.expr().and_then(|expr| scrutinee_value(&ctx.sema, &expr));
+    let mut dead_arms = Vec::new();
+    let mut reasons = Vec::new();
+    let mut value_reason = None;
+    for arm in &arms {
+        let Some(pat) = arm.pat() else { continue };
+        if let Some(ty) = uninhabited_pat_ty(&ctx.sema, module, &pat) {
+            dead_arms.push(arm.clone());
+            reasons.push(format!("`{}` is uninhabited", ty.display(ctx.db())));
+            continue;
+        }
+        let Some((scrutinee, value)) = &known_value else { continue };
+        match pat_matches(&ctx.sema, &pat, *value) {
+            Some(false) => {
+                dead_arms.push(arm.clone());
+                value_reason.get_or_insert_with(|| format!("`{scrutinee}` is known"));
+            }
+            Some(true) if arm.guard().is_none() => {
+                // All the following arms are unreachable.
+                dead_arms.extend(arms.iter().skip_while(|it| *it != arm).skip(1).cloned());
+                if let Some(text) = matching_literal_text(&ctx.sema, &pat, *value) {
+                    value_reason = Some(format!("`{scrutinee}` is `{text}`"));
+                } else {
+                    value_reason.get_or_insert_with(|| format!("`{scrutinee}` is known"));
+                }
+                known_value = None;
+            }
+            _ => {}
+        }
+    }
+    if dead_arms.is_empty() {
+        return None;
+    }
+    reasons.extend(value_reason);
+
+    acc.add(
+        AssistId("remove_dead_match_arms", AssistKind::RefactorRewrite),
+        format!("Remove dead match arms ({})", reasons.into_iter().unique().join(", ")),
+        match_expr.syntax().text_range(),
+        |builder| {
+            for arm in dead_arms {
+                let arm = arm.syntax();
+                let start = match arm.prev_sibling_or_token() {
+                    Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+                    _ => arm.text_range().start(),
+                };
+                builder.delete(TextRange::new(start, arm.text_range().end()));
+            }
+        },
+    )
+}
+
+/// Returns the type which makes `pat` unable to match anything, if any. Or-patterns are dead if
+/// all their alternatives are. References and boxes are never considered uninhabited.
+fn uninhabited_pat_ty(
+    sema: &Semantics<'_, RootDatabase>,
+    module: hir::Module,
+    pat: &ast::Pat,
+) -> Option<hir::Type> {
+    match pat {
+        ast::Pat::OrPat(pat) => {
+            let tys = pat.pats().map(|pat| uninhabited_pat_ty(sema, module, &pat));
+            return tys.collect::<Option<Vec<_>>>()?.into_iter().next();
+        }
+        ast::Pat::RefPat(_) | ast::Pat::BoxPat(_) | ast::Pat::SlicePat(_) => return None,
+        _ => {}
+    }
+    let ty = sema.type_of_pat(pat)?.original;
+    if ty.is_uninhabited_from(sema.db, module) {
+        return Some(ty);
+    }
+    let sub_pats = pat.syntax().descendants().skip(1).filter_map(ast::Pat::cast).filter(|it| {
+        it.syntax().ancestors().skip(1).find_map(ast::Pat::cast).as_ref() == Some(pat)
+    });
+    sub_pats.find_map(|it| uninhabited_pat_ty(sema, module, &it))
+}
+
+/// Returns the text of `expr` and its value, if it's a literal or a const whose value is a
+/// `bool`, a `char` or an integer.
+fn scrutinee_value(sema: &Semantics<'_, RootDatabase>, expr: &ast::Expr) -> Option<(String, i128)> {
+    let value = match expr {
+        ast::Expr::Literal(lit) => literal_value(lit)?,
+        ast::Expr::PathExpr(path) => match sema.resolve_path(&path.path()?)? {
+            PathResolution::Def(hir::ModuleDef::Const(it)) => it.eval_scalar(sema.db)?,
+            _ => return None,
+        },
+        ast::Expr::ParenExpr(expr) => return scrutinee_value(sema, &expr.expr()?),
+        _ => return None,
+    };
+    Some((expr.syntax().text().to_string(), value))
+}
+
+fn literal_value(lit: &ast::Literal) -> Option<i128> {
+    let value = match lit.kind() {
+        ast::LiteralKind::IntNumber(it) => it.value()? as i128,
+        ast::LiteralKind::Char(it) => it.value()? as i128,
+        ast::LiteralKind::Byte(it) => it.value()? as i128,
+        ast::LiteralKind::Bool(it) => it as i128,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Returns whether `pat` matches `value`, or `None` if that isn't known.
+fn pat_matches(sema: &Semantics<'_, RootDatabase>, pat: &ast::Pat, value: i128) -> Option<bool> {
+    match pat {
+        ast::Pat::LiteralPat(pat) => {
+            let lit = literal_value(&pat.literal()?)?;
+            let lit = if pat.minus_token().is_some() { -lit } else { lit };
+            Some(lit == value)
+        }
+        ast::Pat::OrPat(pat) => {
+            let matches = pat.pats().map(|pat| pat_matches(sema, &pat, value)).collect_vec();
+            if matches.contains(&Some(true)) {
+                Some(true)
+            } else if matches.iter().all(|it| *it == Some(false)) {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        ast::Pat::ParenPat(pat) => pat_matches(sema, &pat.pat()?, value),
+        ast::Pat::WildcardPat(_) => Some(true),
+        ast::Pat::IdentPat(pat)
+            if pat.pat().is_none() && sema.resolve_bind_pat_to_const(pat).is_none() =>
+        {
+            Some(true)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the text of the literal pattern of `pat` which matches `value`, if any.
+fn matching_literal_text(
+    sema: &Semantics<'_, RootDatabase>,
+    pat: &ast::Pat,
+    value: i128,
+) -> Option<String> {
+    match pat {
+        ast::Pat::LiteralPat(_) if pat_matches(sema, pat, value)? => {
+            Some(pat.syntax().text().to_string())
+        }
+        ast::Pat::OrPat(pat) => pat.pats().find_map(|it| matching_literal_text(sema, &it, value)),
+        ast::Pat::ParenPat(pat) => matching_literal_text(sema, &pat.pat()?, value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_without_dead_arms() {
+        check_assist_not_applicable(
+            remove_dead_match_arms,
+            r#"
+//- minicore: result
+fn f(x: Result<u8, u8>) -> u8 {
+    $0match x {
+        Ok(x) => x,
+        Err(_) => 0,
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            remove_dead_match_arms,
+            r#"
+fn f(x: u8) -> u8 {
+    $0match x {
+        1 => 2,
+        _ => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_uninhabited_arms() {
+        check_assist(
+            remove_dead_match_arms,
+            r#"
+//- minicore: result
+enum Never {}
+enum E { A(u8), B { never: Never, x: u8 }, C(Result<Never, Never>) }
+fn f(e: E) -> u8 {
+    $0match e {
+        E::A(x) => x,
+        E::B { x, never: _ } => x,
+        E::C(Ok(_) | Err(_)) => 0,
+    }
+}
+"#,
+            r#"
+enum Never {}
+enum E { A(u8), B { never: Never, x: u8 }, C(Result<Never, Never>) }
+fn f(e: E) -> u8 {
+    match e {
+        E::A(x) => x,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keep_arms_behind_references() {
+        check_assist_not_applicable(
+            remove_dead_match_arms,
+            r#"
+//- minicore: result
+enum Never {}
+fn f(x: Result<u8, &Never>) -> u8 {
+    $0match x {
+        Ok(x) => x,
+        Err(&_) => 0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_arms_not_matching_a_const() {
+        check_assist(
+            remove_dead_match_arms,
+            r#"
+const MODE: i32 = -2 + 1;
+fn f() -> u8 {
+    $0match MODE {
+        0 | 1 => 1,
+        -1 if false => 2,
+        -1 => 3,
+        x => 4,
+    }
+}
+"#,
+            r#"
+const MODE: i32 = -2 + 1;
+fn f() -> u8 {
+    match MODE {
+        -1 if false => 2,
+        -1 => 3,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn label_shows_the_reasons() {
+        check_assist_label(
+            remove_dead_match_arms,
+            r#"
+const DEBUG: bool = true;
+fn f() -> u8 {
+    $0match DEBUG {
+        true => 1,
+        false => 0,
+    }
+}
+"#,
+            "Remove dead match arms (`DEBUG` is `true`)",
+            None,
+        );
+        check_assist_label(
+            remove_dead_match_arms,
+            r#"
+const C: char = 'b';
+fn f() -> u8 {
+    $0match C {
+        'a' => 1,
+        _ => 0,
+    }
+}
+"#,
+            "Remove dead match arms (`C` is known)",
+            None,
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use syntax::{ast, AstNode};
+use syntax::{
+    ast::{self, make},
+    ted, AstNode, SyntaxKind, SyntaxNode, T,
+};
 
 use crate::{AssistContext, AssistId, AssistKind, Assists};
 
@@ -27,10 +30,7 @@ pub(crate) fn remove_parentheses(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         return None;
     }
 
-    let expr = parens.expr()?;
-
-    let parent = parens.syntax().parent()?;
-    if expr.needs_parens_in(parent) {
+    if !is_redundant(&parens) {
         return None;
     }
 
@@ -39,10 +39,133 @@ pub(crate) fn remove_parentheses(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         AssistId("remove_parentheses", AssistKind::Refactor),
         "Remove redundant parentheses",
         target,
-        |builder| builder.replace_ast(parens.into(), expr),
+        |builder| remove_parens(&builder.make_mut(parens)),
+    )
+}
+
+// Assist: remove_parentheses_in_expr
+//
+// Removes all the redundant parentheses of the expression under the cursor.
+//
+// ```
+// fn main() {
+//     let x = $0((1 + 2)) * (3 as u8).pow(2) - (4);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = (1 + 2) * (3 as u8).pow(2) - 4;
+// }
+// ```
+pub(crate) fn remove_parentheses_in_expr(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let expr = ctx.find_node_at_offset::<ast::Expr>()?;
+    let top_expr = expr
+        .syntax()
+        .ancestors()
+        .take_while(|it| {
+            !matches!(
+                it.kind(),
+                SyntaxKind::STMT_LIST | SyntaxKind::EXPR_STMT | SyntaxKind::LET_STMT
+            ) && !ast::Item::can_cast(it.kind())
+        })
+        .filter(|it| ast::Expr::can_cast(it.kind()))
+        .last()?;
+    if !top_expr.descendants().filter_map(ast::ParenExpr::cast).any(|it| is_redundant(&it)) {
+        return None;
+    }
+
+    acc.add(
+        AssistId("remove_parentheses_in_expr", AssistKind::Refactor),
+        "Remove all redundant parentheses in expression",
+        top_expr.text_range(),
+        |builder| remove_all_redundant_parens(&builder.make_syntax_mut(top_expr)),
     )
 }
 
+// Assist: remove_parentheses_in_file
+//
+// Removes all the redundant parentheses of the file. This is a source action, so it can be run
+// on save.
+//
+// ```
+// fn f() -> u8 { $0(1) + 2 }
+// fn g(x: u8) -> bool { (x as u16) < (2) }
+// ```
+// ->
+// ```
+// fn f() -> u8 { 1 + 2 }
+// fn g(x: u8) -> bool { (x as u16) < 2 }
+// ```
+pub(crate) fn remove_parentheses_in_file(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let source_file = ctx.source_file();
+    if !source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::ParenExpr::cast)
+        .any(|it| is_redundant(&it))
+    {
+        return None;
+    }
+
+    acc.add(
+        AssistId("remove_parentheses_in_file", AssistKind::Source),
+        "Remove all redundant parentheses in file",
+        source_file.syntax().text_range(),
+        |builder| {
+            remove_all_redundant_parens(&builder.make_syntax_mut(source_file.syntax().clone()))
+        },
+    )
+}
+
+fn is_redundant(parens: &ast::ParenExpr) -> bool {
+    match (parens.expr(), parens.syntax().parent()) {
+        (Some(expr), Some(parent)) => !expr.needs_parens_in(parent),
+        _ => false,
+    }
+}
+
+/// Removes the redundant parentheses of the mutable `node`, outermost first. Each pair is checked
+/// again after the outer ones are removed, so `((a + b)) * c` keeps one pair.
+fn remove_all_redundant_parens(node: &SyntaxNode) {
+    let parens = node.descendants().filter_map(ast::ParenExpr::cast).collect::<Vec<_>>();
+    for parens in parens {
+        if is_redundant(&parens) {
+            remove_parens(&parens);
+        }
+    }
+}
+
+/// Replaces the mutable `parens` with its inner expression, adding spaces where tokens would
+/// otherwise be glued together, as in `return(x)`.
+fn remove_parens(parens: &ast::ParenExpr) {
+    let Some(expr) = parens.expr() else { return };
+    let is_word = |kind: SyntaxKind| {
+        matches!(kind, SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT | T![_])
+            || kind.is_keyword()
+            || kind.is_literal()
+    };
+    let space_before =
+        parens.syntax().first_token().and_then(|it| it.prev_token()).map_or(false, |prev| {
+            is_word(prev.kind())
+                && expr.syntax().first_token().map_or(false, |it| is_word(it.kind()))
+        });
+    let space_after =
+        parens.syntax().last_token().and_then(|it| it.next_token()).map_or(false, |next| {
+            is_word(next.kind())
+                && expr.syntax().last_token().map_or(false, |it| is_word(it.kind()))
+        });
+
+    expr.syntax().detach();
+    ted::replace(parens.syntax(), expr.syntax());
+    if space_before {
+        ted::insert(ted::Position::before(expr.syntax()), make::tokens::single_space());
+    }
+    if space_after {
+        ted::insert(ted::Position::after(expr.syntax()), make::tokens::single_space());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -218,4 +341,77 @@ mod tests {
 
         check_assist_not_applicable(remove_parentheses, r#"fn f() { $0(return 2) + 2 }"#);
     }
+
+    #[test]
+    fn remove_parens_doesnt_apply_when_lexed_or_parsed_differently() {
+        // a field call isn't a method call
+        check_assist_not_applicable(remove_parentheses, r#"fn f() { $0(a.b)(); }"#);
+        // `<` would start generic arguments
+        check_assist_not_applicable(remove_parentheses, r#"fn f() { $0(x as u8) < y; }"#);
+        check_assist_not_applicable(remove_parentheses, r#"fn f() { y + $0(x as u8) << 2; }"#);
+        // `1.0` is a float
+        check_assist_not_applicable(remove_parentheses, r#"fn f() { $0(1).0; }"#);
+        check_assist_not_applicable(remove_parentheses, r#"fn f() { $0(1.).max(2.); }"#);
+        check_assist_not_applicable(remove_parentheses, r#"fn f() { $0(a..b).len(); }"#);
+    }
+
+    #[test]
+    fn remove_parens_where_lexed_the_same() {
+        check_assist(remove_parentheses, r#"fn f() { $0(a.b).c(); }"#, r#"fn f() { a.b.c(); }"#);
+        check_assist(
+            remove_parentheses,
+            r#"fn f() { $0(x as u8) > y; }"#,
+            r#"fn f() { x as u8 > y; }"#,
+        );
+        check_assist(
+            remove_parentheses,
+            r#"fn f() { $0(1.0).max(2.); }"#,
+            r#"fn f() { 1.0.max(2.); }"#,
+        );
+        check_assist(remove_parentheses, r#"fn f() { $0(x.await)?; }"#, r#"fn f() { x.await?; }"#);
+    }
+
+    #[test]
+    fn remove_parens_keeps_words_apart() {
+        check_assist(remove_parentheses, r#"fn f() { return$0(x); }"#, r#"fn f() { return x; }"#);
+    }
+
+    #[test]
+    fn remove_parens_in_expr() {
+        check_assist(
+            remove_parentheses_in_expr,
+            r#"fn f() { let _ = $0((a + b)) * c + (d.e)() - (f(g)); }"#,
+            r#"fn f() { let _ = (a + b) * c + (d.e)() - f(g); }"#,
+        );
+        check_assist(
+            remove_parentheses_in_expr,
+            r#"fn f() { foo((1), [(2)$0]); bar((3)); }"#,
+            r#"fn f() { foo(1, [2]); bar((3)); }"#,
+        );
+        check_assist_not_applicable(
+            remove_parentheses_in_expr,
+            r#"fn f() { let _ = (a + b)$0 * (c as u8) < (1).0; }"#,
+        );
+    }
+
+    #[test]
+    fn remove_parens_in_file() {
+        check_assist(
+            remove_parentheses_in_file,
+            r#"
+fn f() -> u8 { $0(1) }
+fn g() -> bool { if (true) { return(false) } true }
+const C: u8 = ((1 + 2)) * 3;
+"#,
+            r#"
+fn f() -> u8 { 1 }
+fn g() -> bool { if true { return false } true }
+const C: u8 = (1 + 2) * 3;
+"#,
+        );
+        check_assist_not_applicable(
+            remove_parentheses_in_file,
+            r#"fn f() -> u8 { $0(1 + 2) * 3 }"#,
+        );
+    }
 }
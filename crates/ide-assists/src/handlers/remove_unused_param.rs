@@ -1,15 +1,12 @@
 use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
-use syntax::{
-    algo::find_node_at_range,
-    ast::{self, HasArgList},
-    AstNode, SourceFile, SyntaxKind, SyntaxNode, TextRange, T,
-};
+use syntax::{ast, AstNode, SourceFile, SyntaxKind, SyntaxNode, TextRange, T};
 
 use SyntaxKind::WHITESPACE;
 
 use crate::{
-    assist_context::SourceChangeBuilder, utils::next_prev, AssistContext, AssistId, AssistKind,
-    Assists,
+    assist_context::SourceChangeBuilder,
+    utils::{call_arg_list, next_prev},
+    AssistContext, AssistId, AssistKind, Assists,
 };
 
 // Assist: remove_unused_param
@@ -119,34 +116,12 @@ fn process_usage(
     mut arg_to_remove: usize,
     is_self_present: bool,
 ) -> Option<TextRange> {
-    let call_expr_opt: Option<ast::CallExpr> = find_node_at_range(source_file.syntax(), range);
-    if let Some(call_expr) = call_expr_opt {
-        let call_expr_range = call_expr.expr()?.syntax().text_range();
-        if !call_expr_range.contains_range(range) {
-            return None;
-        }
-
-        let arg = call_expr.arg_list()?.args().nth(arg_to_remove)?;
-        return Some(range_to_remove(arg.syntax()));
-    }
-
-    let method_call_expr_opt: Option<ast::MethodCallExpr> =
-        find_node_at_range(source_file.syntax(), range);
-    if let Some(method_call_expr) = method_call_expr_opt {
-        let method_call_expr_range = method_call_expr.name_ref()?.syntax().text_range();
-        if !method_call_expr_range.contains_range(range) {
-            return None;
-        }
-
-        if is_self_present {
-            arg_to_remove -= 1;
-        }
-
-        let arg = method_call_expr.arg_list()?.args().nth(arg_to_remove)?;
-        return Some(range_to_remove(arg.syntax()));
+    let (arg_list, is_method_call) = call_arg_list(source_file, range)?;
+    if is_method_call && is_self_present {
+        arg_to_remove -= 1;
     }
-
-    None
+    let arg = arg_list.args().nth(arg_to_remove)?;
+    Some(range_to_remove(arg.syntax()))
 }
 
 pub(crate) fn range_to_remove(node: &SyntaxNode) -> TextRange {
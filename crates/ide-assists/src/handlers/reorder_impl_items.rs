@@ -55,9 +55,38 @@ pub(crate) fn reorder_impl_items(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         return None;
     }
 
-    let assoc_items = items.assoc_items().collect::<Vec<_>>();
+    let (assoc_items, sorted) = impl_items_in_trait_order(&ctx.sema, &impl_ast)?;
 
-    let path = impl_ast
+    // Don't edit already sorted methods:
+    if assoc_items == sorted {
+        cov_mark::hit!(not_applicable_if_sorted);
+        return None;
+    }
+
+    let target = items.syntax().text_range();
+    acc.add(
+        AssistId("reorder_impl_items", AssistKind::RefactorRewrite),
+        "Sort items by trait definition",
+        target,
+        |builder| {
+            let assoc_items =
+                assoc_items.into_iter().map(|item| builder.make_mut(item)).collect::<Vec<_>>();
+            assoc_items
+                .into_iter()
+                .zip(sorted)
+                .for_each(|(old, new)| ted::replace(old.syntax(), new.clone_for_update().syntax()));
+        },
+    )
+}
+
+/// Returns the items of `impl_`, and the same items sorted in the order of the trait definition.
+pub(crate) fn impl_items_in_trait_order(
+    sema: &Semantics<'_, RootDatabase>,
+    impl_: &ast::Impl,
+) -> Option<(Vec<ast::AssocItem>, Vec<ast::AssocItem>)> {
+    let assoc_items = impl_.assoc_item_list()?.assoc_items().collect::<Vec<_>>();
+
+    let path = impl_
         .trait_()
         .and_then(|t| match t {
             ast::Type::PathType(path) => Some(path),
@@ -65,7 +94,7 @@ pub(crate) fn reorder_impl_items(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         })?
         .path()?;
 
-    let ranks = compute_item_ranks(&path, ctx)?;
+    let ranks = compute_item_ranks(&path, sema)?;
     let sorted: Vec<_> = assoc_items
         .iter()
         .cloned()
@@ -80,39 +109,19 @@ pub(crate) fn reorder_impl_items(acc: &mut Assists, ctx: &AssistContext<'_>) ->
             name.and_then(|n| ranks.get(&n.to_string()).copied()).unwrap_or(usize::max_value())
         })
         .collect();
-
-    // Don't edit already sorted methods:
-    if assoc_items == sorted {
-        cov_mark::hit!(not_applicable_if_sorted);
-        return None;
-    }
-
-    let target = items.syntax().text_range();
-    acc.add(
-        AssistId("reorder_impl_items", AssistKind::RefactorRewrite),
-        "Sort items by trait definition",
-        target,
-        |builder| {
-            let assoc_items =
-                assoc_items.into_iter().map(|item| builder.make_mut(item)).collect::<Vec<_>>();
-            assoc_items
-                .into_iter()
-                .zip(sorted)
-                .for_each(|(old, new)| ted::replace(old.syntax(), new.clone_for_update().syntax()));
-        },
-    )
+    Some((assoc_items, sorted))
 }
 
 fn compute_item_ranks(
     path: &ast::Path,
-    ctx: &AssistContext<'_>,
+    sema: &Semantics<'_, RootDatabase>,
 ) -> Option<FxHashMap<String, usize>> {
-    let td = trait_definition(path, &ctx.sema)?;
+    let td = trait_definition(path, sema)?;
 
     Some(
-        td.items(ctx.db())
+        td.items(sema.db)
             .iter()
-            .flat_map(|i| i.name(ctx.db()))
+            .flat_map(|i| i.name(sema.db))
             .enumerate()
             .map(|(idx, name)| (name.to_string(), idx))
             .collect(),
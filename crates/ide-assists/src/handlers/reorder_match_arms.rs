@@ -0,0 +1,272 @@
+use hir::{PathResolution, Semantics};
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use syntax::{ast, AstNode, TextRange};
+
+use crate::{utils::reorder_siblings, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: reorder_match_arms
+//
+// Reorder the arms of a `match`. The arms will be ordered in the same order
+// as the variants in the enum definition.
+//
+// ```
+// enum Dir { North, East, South, West }
+//
+// fn f(dir: Dir) -> u8 {
+//     $0match dir {
+//         Dir::South => 2,
+//         // Going up.
+//         Dir::North => 0,
+//         Dir::West | Dir::East => 1,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Dir { North, East, South, West }
+//
+// fn f(dir: Dir) -> u8 {
+//     match dir {
+//         // Going up.
+//         Dir::North => 0,
+//         Dir::West | Dir::East => 1,
+//         Dir::South => 2,
+//     }
+// }
+// ```
+pub(crate) fn reorder_match_arms(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arm_list = match_expr.match_arm_list()?;
+
+    // Like `reorder_impl_items`, don't get in the way while editing the arms.
+    if arm_list.syntax().text_range().contains_inclusive(ctx.offset()) {
+        cov_mark::hit!(not_applicable_editing_match_arms);
+        return None;
+    }
+
+    let (arms, sorted) = match_arms_in_variant_order(&ctx.sema, &match_expr)?;
+    if arms == sorted {
+        cov_mark::hit!(not_applicable_if_arms_sorted);
+        return None;
+    }
+    let (range, text) = reorder_match_arms_text(&arms, &sorted)?;
+
+    acc.add(
+        AssistId("reorder_match_arms", AssistKind::RefactorRewrite),
+        "Sort match arms by enum definition",
+        arm_list.syntax().text_range(),
+        |builder| builder.replace(range, text),
+    )
+}
+
+/// Returns the arms of `match_expr`, and the same arms sorted in the order of the variants of the
+/// matched enum. Catch-all arms must be at the end, and stay there. Returns `None` if sorting would
+/// change which arm a value matches, i.e. if two arms for a common variant would be swapped.
+pub(crate) fn match_arms_in_variant_order(
+    sema: &Semantics<'_, RootDatabase>,
+    match_expr: &ast::MatchExpr,
+) -> Option<(Vec<ast::MatchArm>, Vec<ast::MatchArm>)> {
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    let variants =
+        arms.iter().map(|arm| pat_variants(sema, &arm.pat()?)).collect::<Vec<Option<_>>>();
+
+    let sortable = variants.iter().take_while(|it| it.is_some()).count();
+    if variants[sortable..].iter().any(Option::is_some) {
+        return None;
+    }
+    let variants = variants.into_iter().flatten().collect::<Vec<_>>();
+
+    let order = (0..sortable).sorted_by_key(|&i| variants[i].iter().min().copied()).collect_vec();
+    for (pos, &i) in order.iter().enumerate() {
+        let swapped_overlapping = order[pos + 1..]
+            .iter()
+            .any(|&j| j < i && variants[i].iter().any(|it| variants[j].contains(it)));
+        if swapped_overlapping {
+            return None;
+        }
+    }
+
+    let sorted = order.into_iter().map(|i| arms[i].clone()).chain(arms[sortable..].iter().cloned());
+    let sorted = sorted.collect();
+    Some((arms, sorted))
+}
+
+/// Returns the edit which rearranges `arms` into the order of `sorted`.
+pub(crate) fn reorder_match_arms_text(
+    arms: &[ast::MatchArm],
+    sorted: &[ast::MatchArm],
+) -> Option<(TextRange, String)> {
+    reorder_siblings(arms, sorted, |arm| {
+        let needs_comma =
+            arm.comma_token().is_none() && arm.expr().map_or(false, |expr| !expr.is_block_like());
+        needs_comma.then_some(",")
+    })
+}
+
+/// Returns the indices of the enum variants matched by `pat`, or `None` if it isn't made of
+/// variant patterns.
+fn pat_variants(sema: &Semantics<'_, RootDatabase>, pat: &ast::Pat) -> Option<Vec<usize>> {
+    let path = match pat {
+        ast::Pat::OrPat(pat) => {
+            let variants = pat.pats().map(|pat| pat_variants(sema, &pat));
+            return Some(variants.collect::<Option<Vec<_>>>()?.concat());
+        }
+        ast::Pat::ParenPat(pat) => return pat_variants(sema, &pat.pat()?),
+        ast::Pat::IdentPat(pat) => {
+            return match sema.resolve_bind_pat_to_const(pat)? {
+                hir::ModuleDef::Variant(variant) => variant_index(sema, variant).map(|it| vec![it]),
+                _ => None,
+            };
+        }
+        ast::Pat::PathPat(pat) => pat.path()?,
+        ast::Pat::TupleStructPat(pat) => pat.path()?,
+        ast::Pat::RecordPat(pat) => pat.path()?,
+        _ => return None,
+    };
+    match sema.resolve_path(&path)? {
+        PathResolution::Def(hir::ModuleDef::Variant(variant)) => {
+            variant_index(sema, variant).map(|it| vec![it])
+        }
+        _ => None,
+    }
+}
+
+fn variant_index(sema: &Semantics<'_, RootDatabase>, variant: hir::Variant) -> Option<usize> {
+    variant.parent_enum(sema.db).variants(sema.db).iter().position(|it| *it == variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn not_applicable_if_sorted() {
+        cov_mark::check!(not_applicable_if_arms_sorted);
+        check_assist_not_applicable(
+            reorder_match_arms,
+            r#"
+enum E { A, B(u8), C { x: u8 } }
+fn f(e: E) {
+    $0match e {
+        E::A => (),
+        E::B(_) => (),
+        _ => (),
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn not_applicable_editing_arms() {
+        cov_mark::check!(not_applicable_editing_match_arms);
+        check_assist_not_applicable(
+            reorder_match_arms,
+            r#"
+enum E { A, B }
+fn f(e: E) {
+    match e {
+        E::B => (),
+        E::A => $0(),
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn not_applicable_with_catch_all_in_the_middle() {
+        check_assist_not_applicable(
+            reorder_match_arms,
+            r#"
+enum E { A, B, C }
+fn f(e: E) {
+    $0match e {
+        E::B => (),
+        _ => (),
+        E::A => (),
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn not_applicable_if_overlapping_arms_would_swap() {
+        check_assist_not_applicable(
+            reorder_match_arms,
+            r#"
+enum E { A, B(u8), C }
+fn f(e: E) {
+    $0match e {
+        E::B(0) => (),
+        E::C | E::A => (),
+        E::B(_) | E::A => (),
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn reorder_keeps_guards_and_catch_all() {
+        check_assist(
+            reorder_match_arms,
+            r#"
+enum E { A, B(u8), C { x: u8 } }
+fn f(e: E) -> u8 {
+    $0match e {
+        E::C { x } => x,
+        E::B(x) if x > 2 => x,
+        E::B(_) => 1,
+        E::A => 0,
+        _ => 5,
+    }
+}
+"#,
+            r#"
+enum E { A, B(u8), C { x: u8 } }
+fn f(e: E) -> u8 {
+    match e {
+        E::A => 0,
+        E::B(x) if x > 2 => x,
+        E::B(_) => 1,
+        E::C { x } => x,
+        _ => 5,
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn reorder_keeps_comments_attached() {
+        check_assist(
+            reorder_match_arms,
+            r#"
+//- minicore: option
+fn f(x: Option<u8>) -> u8 {
+    $0match x {
+        // Has a value.
+        Some(x) => { x } // no comma needed
+        /* Nothing. */
+        None => 0
+    }
+}
+"#,
+            r#"
+fn f(x: Option<u8>) -> u8 {
+    match x {
+        /* Nothing. */
+        None => 0,
+        // Has a value.
+        Some(x) => { x } // no comma needed
+    }
+}
+"#,
+        )
+    }
+}
@@ -107,6 +107,7 @@ mod handlers {
     pub(crate) type Handler = fn(&mut Assists, &AssistContext<'_>) -> Option<()>;
 
     mod add_braces;
+    mod add_clarifying_parentheses;
     mod add_explicit_type;
     mod add_label_to_loop;
     mod add_lifetime_to_type;
@@ -116,6 +117,7 @@ mod handlers {
     mod auto_import;
     mod change_visibility;
     mod convert_bool_then;
+    mod convert_closure_to_fn;
     mod convert_comment_block;
     mod convert_integer_literal;
     mod convert_into_to_from;
@@ -125,11 +127,14 @@ mod handlers {
     mod convert_nested_function_to_closure;
     mod convert_tuple_struct_to_named_struct;
     mod convert_named_struct_to_tuple_struct;
+    mod convert_to_glob_import;
     mod convert_to_guarded_return;
+    mod convert_tuple_match;
     mod convert_two_arm_bool_match_to_matches_macro;
     mod convert_while_to_loop;
     mod desugar_doc_comment;
     mod destructure_tuple_binding;
+    mod document_const_value;
     mod expand_glob_import;
     mod extract_expressions_from_format_string;
     mod extract_function;
@@ -142,6 +147,7 @@ mod handlers {
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod generate_builder;
     mod generate_constant;
     mod generate_default_from_enum_variant;
     mod generate_default_from_new;
@@ -151,6 +157,7 @@ mod handlers {
     mod generate_enum_is_method;
     mod generate_enum_projection_method;
     mod generate_enum_variant;
+    mod generate_fmt_impl;
     mod generate_from_impl_for_enum;
     mod generate_function;
     mod generate_getter;
@@ -158,13 +165,16 @@ mod handlers {
     mod generate_is_empty_from_len;
     mod generate_new;
     mod generate_setter;
+    mod generate_unit_test;
     mod generate_delegate_methods;
+    mod generate_delegate_trait;
     mod add_return_type;
     mod inline_call;
     mod inline_local_variable;
     mod inline_macro;
     mod inline_type_alias;
     mod introduce_named_lifetime;
+    mod introduce_parameter;
     mod invert_if;
     mod merge_imports;
     mod merge_match_arms;
@@ -175,17 +185,20 @@ mod handlers {
     mod move_to_mod_rs;
     mod move_from_mod_rs;
     mod number_representation;
+    mod organize_items;
     mod promote_local_to_const;
     mod pull_assignment_up;
     mod qualify_path;
     mod qualify_method_call;
     mod raw_string;
     mod remove_dbg;
+    mod remove_dead_match_arms;
     mod remove_mut;
     mod remove_unused_param;
     mod remove_parentheses;
     mod reorder_fields;
     mod reorder_impl_items;
+    mod reorder_match_arms;
     mod replace_try_expr_with_match;
     mod replace_derive_with_manual_impl;
     mod replace_if_let_with_match;
@@ -212,6 +225,7 @@ mod handlers {
         &[
             // These are alphabetic for the foolish consistency
             add_braces::add_braces,
+            add_clarifying_parentheses::add_clarifying_parentheses,
             add_explicit_type::add_explicit_type,
             add_label_to_loop::add_label_to_loop,
             add_missing_match_arms::add_missing_match_arms,
@@ -223,6 +237,8 @@ mod handlers {
             change_visibility::change_visibility,
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
+            convert_closure_to_fn::convert_closure_to_fn,
+            convert_closure_to_fn::convert_fn_item_to_closure,
             convert_comment_block::convert_comment_block,
             convert_integer_literal::convert_integer_literal,
             convert_into_to_from::convert_into_to_from,
@@ -232,12 +248,16 @@ mod handlers {
             convert_match_to_let_else::convert_match_to_let_else,
             convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
             convert_nested_function_to_closure::convert_nested_function_to_closure,
+            convert_to_glob_import::convert_to_glob_import,
             convert_to_guarded_return::convert_to_guarded_return,
+            convert_tuple_match::convert_nested_match_to_tuple_match,
+            convert_tuple_match::convert_tuple_match_to_nested_match,
             convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
             convert_two_arm_bool_match_to_matches_macro::convert_two_arm_bool_match_to_matches_macro,
             convert_while_to_loop::convert_while_to_loop,
             desugar_doc_comment::desugar_doc_comment,
             destructure_tuple_binding::destructure_tuple_binding,
+            document_const_value::document_const_value,
             expand_glob_import::expand_glob_import,
             extract_expressions_from_format_string::extract_expressions_from_format_string,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
@@ -247,6 +267,7 @@ mod handlers {
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
             generate_constant::generate_constant,
+            generate_builder::generate_builder,
             generate_default_from_enum_variant::generate_default_from_enum_variant,
             generate_default_from_new::generate_default_from_new,
             generate_derive::generate_derive,
@@ -256,12 +277,15 @@ mod handlers {
             generate_enum_projection_method::generate_enum_as_method,
             generate_enum_projection_method::generate_enum_try_into_method,
             generate_enum_variant::generate_enum_variant,
+            generate_fmt_impl::generate_debug_impl,
+            generate_fmt_impl::generate_display_impl,
             generate_from_impl_for_enum::generate_from_impl_for_enum,
             generate_function::generate_function,
             generate_impl::generate_impl,
             generate_impl::generate_trait_impl,
             generate_is_empty_from_len::generate_is_empty_from_len,
             generate_new::generate_new,
+            generate_unit_test::generate_unit_test,
             inline_call::inline_call,
             inline_call::inline_into_callers,
             inline_local_variable::inline_local_variable,
@@ -280,6 +304,7 @@ mod handlers {
             move_to_mod_rs::move_to_mod_rs,
             move_from_mod_rs::move_from_mod_rs,
             number_representation::reformat_number_literal,
+            organize_items::organize_items,
             pull_assignment_up::pull_assignment_up,
             promote_local_to_const::promote_local_to_const,
             qualify_path::qualify_path,
@@ -287,11 +312,15 @@ mod handlers {
             raw_string::add_hash,
             raw_string::make_usual_string,
             raw_string::remove_hash,
+            remove_dead_match_arms::remove_dead_match_arms,
             remove_mut::remove_mut,
             remove_unused_param::remove_unused_param,
             remove_parentheses::remove_parentheses,
+            remove_parentheses::remove_parentheses_in_expr,
+            remove_parentheses::remove_parentheses_in_file,
             reorder_fields::reorder_fields,
             reorder_impl_items::reorder_impl_items,
+            reorder_match_arms::reorder_match_arms,
             replace_try_expr_with_match::replace_try_expr_with_match,
             replace_derive_with_manual_impl::replace_derive_with_manual_impl,
             replace_if_let_with_match::replace_if_let_with_match,
@@ -329,11 +358,13 @@ mod handlers {
             extract_variable::extract_variable,
             extract_function::extract_function,
             extract_module::extract_module,
+            introduce_parameter::introduce_parameter,
             //
             generate_getter::generate_getter,
             generate_getter::generate_getter_mut,
             generate_setter::generate_setter,
             generate_delegate_methods::generate_delegate_methods,
+            generate_delegate_trait::generate_delegate_trait,
             generate_deref::generate_deref,
             //
             remove_dbg::remove_dbg,
@@ -27,6 +27,23 @@ fn foo(n: i32) -> i32 {
     )
 }
 
+#[test]
+fn doctest_add_clarifying_parentheses() {
+    check_doc_test(
+        "add_clarifying_parentheses",
+        r#####"
+fn main() {
+    let _ = 1 + 2 $0* 3 << 4 as u32 == 5 && true;
+}
+"#####,
+        r#####"
+fn main() {
+    let _ = (((1 + (2 * 3)) << (4 as u32)) == 5) && true;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check_doc_test(
@@ -300,6 +317,48 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_closure_to_fn() {
+    check_doc_test(
+        "convert_closure_to_fn",
+        r#####"
+fn main() {
+    let add = $0|a: u32, b| a + b;
+    add(1, 2u32);
+}
+"#####,
+        r#####"
+fn main() {
+    fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+    add(1, 2u32);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_fn_item_to_closure() {
+    check_doc_test(
+        "convert_fn_item_to_closure",
+        r#####"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn apply(f: impl Fn(u32, u32) -> u32) {}
+fn main() {
+    apply(ad$0d);
+}
+"#####,
+        r#####"
+fn add(a: u32, b: u32) -> u32 { a + b }
+fn apply(f: impl Fn(u32, u32) -> u32) {}
+fn main() {
+    apply(|a, b| add(a, b));
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_for_loop_with_for_each() {
     check_doc_test(
@@ -519,6 +578,61 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_nested_match_to_tuple_match() {
+    check_doc_test(
+        "convert_nested_match_to_tuple_match",
+        r#####"
+//- minicore: copy
+fn f(a: bool, b: bool) -> u8 {
+    $0match a {
+        true => match b {
+            true => 3,
+            false => 2,
+        },
+        false => 0,
+    }
+}
+"#####,
+        r#####"
+fn f(a: bool, b: bool) -> u8 {
+    match (a, b) {
+        (true, true) => 3,
+        (true, false) => 2,
+        (false, _) => 0,
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_to_glob_import() {
+    check_doc_test(
+        "convert_to_glob_import",
+        r#####"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+use foo::{Bar, $0Baz};
+
+fn qux(bar: Bar, baz: Baz) {}
+"#####,
+        r#####"
+mod foo {
+    pub struct Bar;
+    pub struct Baz;
+}
+
+use foo::*;
+
+fn qux(bar: Bar, baz: Baz) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -543,6 +657,34 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_tuple_match_to_nested_match() {
+    check_doc_test(
+        "convert_tuple_match_to_nested_match",
+        r#####"
+//- minicore: copy
+fn f(a: bool, b: bool) -> u8 {
+    $0match (a, b) {
+        (true, true) => 3,
+        (true, false) => 2,
+        (false, _) => 0,
+    }
+}
+"#####,
+        r#####"
+fn f(a: bool, b: bool) -> u8 {
+    match a {
+        true => match b {
+            true => 3,
+            false => 2,
+        }
+        false => 0,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_tuple_struct_to_named_struct() {
     check_doc_test(
@@ -565,19 +707,19 @@ impl Point {
 }
 "#####,
         r#####"
-struct Point { field1: f32, field2: f32 }
+struct Point { x: f32, y: f32 }
 
 impl Point {
     pub fn new(x: f32, y: f32) -> Self {
-        Point { field1: x, field2: y }
+        Point { x, y }
     }
 
     pub fn x(&self) -> f32 {
-        self.field1
+        self.x
     }
 
     pub fn y(&self) -> f32 {
-        self.field2
+        self.y
     }
 }
 "#####,
@@ -662,6 +804,20 @@ comment"]
     )
 }
 
+#[test]
+fn doctest_document_const_value() {
+    check_doc_test(
+        "document_const_value",
+        r#####"
+const MASK$0: u32 = 1 << 13 | 1 << 12 | 0x40;
+"#####,
+        r#####"
+/// = 0x3040 (12352)
+const MASK: u32 = 1 << 13 | 1 << 12 | 0x40;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_expand_glob_import() {
     check_doc_test(
@@ -898,6 +1054,62 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_builder() {
+    check_doc_test(
+        "generate_builder",
+        r#####"
+//- minicore: default, derive
+#[derive(Default)]
+struct Port(u16);
+
+struct Server$0 {
+    host: &'static str,
+    port: Port,
+}
+"#####,
+        r#####"
+#[derive(Default)]
+struct Port(u16);
+
+struct Server {
+    host: &'static str,
+    port: Port,
+}
+
+impl Server {
+    fn builder() -> ServerBuilder {
+        ServerBuilder { host: None, port: None }
+    }
+}
+
+struct ServerBuilder {
+    host: Option<&'static str>,
+    port: Option<Port>,
+}
+
+impl ServerBuilder {
+    fn host(mut self, host: &'static str) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    fn port(mut self, port: Port) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    fn build(self) -> Result<Server, &'static str> {
+        Ok(Server {
+            host: self.host.ok_or("missing field `host`")?,
+            port: self.port.unwrap_or_default(),
+        })
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_constant() {
     check_doc_test(
@@ -920,6 +1132,39 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_generate_debug_impl() {
+    check_doc_test(
+        "generate_debug_impl",
+        r#####"
+//- minicore: fmt
+struct Socket;
+
+struct Conn$0<T> {
+    peer: T,
+    socket: Socket,
+}
+"#####,
+        r#####"
+struct Socket;
+
+struct Conn<T> {
+    peer: T,
+    socket: Socket,
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Conn<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Conn")
+            .field("peer", &self.peer)
+            // field `socket` is skipped, `Socket` doesn't implement `Debug`
+            .finish_non_exhaustive()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_default_from_enum_variant() {
     check_doc_test(
@@ -1015,6 +1260,53 @@ impl Person {
     )
 }
 
+#[test]
+fn doctest_generate_delegate_trait() {
+    check_doc_test(
+        "generate_delegate_trait",
+        r#####"
+trait Counter {
+    const START: u32;
+    fn count(&self, step: u32) -> u32;
+}
+
+struct Inner;
+impl Counter for Inner {
+    const START: u32 = 0;
+    fn count(&self, step: u32) -> u32 {
+        Self::START + step
+    }
+}
+
+struct Wrapper($0Inner);
+"#####,
+        r#####"
+trait Counter {
+    const START: u32;
+    fn count(&self, step: u32) -> u32;
+}
+
+struct Inner;
+impl Counter for Inner {
+    const START: u32 = 0;
+    fn count(&self, step: u32) -> u32 {
+        Self::START + step
+    }
+}
+
+struct Wrapper(Inner);
+
+impl Counter for Wrapper {
+    $0const START: u32 = <Inner as Counter>::START;
+
+    fn count(&self, step: u32) -> u32 {
+        <Inner as Counter>::count(&self.0, step)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_deref() {
     check_doc_test(
@@ -1063,6 +1355,35 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_generate_display_impl() {
+    check_doc_test(
+        "generate_display_impl",
+        r#####"
+//- minicore: fmt
+enum Shape$0<T> {
+    Point,
+    Circle { radius: T },
+}
+"#####,
+        r#####"
+enum Shape<T> {
+    Point,
+    Circle { radius: T },
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for Shape<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Point => f.write_str("Point"),
+            Self::Circle { radius } => write!(f, "Circle {{ radius: {} }}", radius),
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_doc_example() {
     check_doc_test(
@@ -1458,6 +1779,36 @@ impl<T: Clone> $0 for Ctx<T> {
     )
 }
 
+#[test]
+fn doctest_generate_unit_test() {
+    check_doc_test(
+        "generate_unit_test",
+        r#####"
+fn add$0(a: u32, b: u32) -> u32 {
+    a + b
+}
+"#####,
+        r#####"
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let a = 0;
+        let b = 0;
+        let result = add(a, b);
+        assert_eq!(result, ${0:todo!()});
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_call() {
     check_doc_test(
@@ -1651,6 +2002,37 @@ impl<'a> Cursor<'a> {
     )
 }
 
+#[test]
+fn doctest_introduce_parameter() {
+    check_doc_test(
+        "introduce_parameter",
+        r#####"
+struct Color(u8, u8, u8);
+fn paint(width: u32, color: Color) {}
+
+fn fill(width: u32) {
+    paint(width, $0Color(0, 0, 0)$0);
+}
+
+fn main() {
+    fill(10);
+}
+"#####,
+        r#####"
+struct Color(u8, u8, u8);
+fn paint(width: u32, color: Color) {}
+
+fn fill(width: u32, $0color: Color) {
+    paint(width, color);
+}
+
+fn main() {
+    fill(10, Color(0, 0, 0));
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_invert_if() {
     check_doc_test(
@@ -1908,6 +2290,45 @@ fn t() {}
     )
 }
 
+#[test]
+fn doctest_organize_items() {
+    check_doc_test(
+        "organize_items",
+        r#####"
+$0enum Dir { North, South }
+trait Flip { fn flip(&self) -> Dir; fn name(&self) -> &str; }
+
+impl Flip for Dir {
+    fn name(&self) -> &str { "dir" }
+    fn flip(&self) -> Dir { opposite(self) }
+}
+
+fn opposite(dir: &Dir) -> Dir {
+    match dir {
+        Dir::South => Dir::North,
+        Dir::North => Dir::South,
+    }
+}
+"#####,
+        r#####"
+enum Dir { North, South }
+trait Flip { fn flip(&self) -> Dir; fn name(&self) -> &str; }
+
+impl Flip for Dir {
+    fn flip(&self) -> Dir { opposite(self) }
+    fn name(&self) -> &str { "dir" }
+}
+
+fn opposite(dir: &Dir) -> Dir {
+    match dir {
+        Dir::North => Dir::South,
+        Dir::South => Dir::North,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_promote_local_to_const() {
     check_doc_test(
@@ -2042,6 +2463,32 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_remove_dead_match_arms() {
+    check_doc_test(
+        "remove_dead_match_arms",
+        r#####"
+enum Never {}
+
+fn f(x: Result<u8, Never>) -> u8 {
+    $0match x {
+        Ok(x) => x,
+        Err(_) => 0,
+    }
+}
+"#####,
+        r#####"
+enum Never {}
+
+fn f(x: Result<u8, Never>) -> u8 {
+    match x {
+        Ok(x) => x,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_hash() {
     check_doc_test(
@@ -2093,6 +2540,38 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_remove_parentheses_in_expr() {
+    check_doc_test(
+        "remove_parentheses_in_expr",
+        r#####"
+fn main() {
+    let x = $0((1 + 2)) * (3 as u8).pow(2) - (4);
+}
+"#####,
+        r#####"
+fn main() {
+    let x = (1 + 2) * (3 as u8).pow(2) - 4;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_parentheses_in_file() {
+    check_doc_test(
+        "remove_parentheses_in_file",
+        r#####"
+fn f() -> u8 { $0(1) + 2 }
+fn g(x: u8) -> bool { (x as u16) < (2) }
+"#####,
+        r#####"
+fn f() -> u8 { 1 + 2 }
+fn g(x: u8) -> bool { (x as u16) < 2 }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_unused_param() {
     check_doc_test(
@@ -2164,6 +2643,37 @@ impl Foo for Bar {
     )
 }
 
+#[test]
+fn doctest_reorder_match_arms() {
+    check_doc_test(
+        "reorder_match_arms",
+        r#####"
+enum Dir { North, East, South, West }
+
+fn f(dir: Dir) -> u8 {
+    $0match dir {
+        Dir::South => 2,
+        // Going up.
+        Dir::North => 0,
+        Dir::West | Dir::East => 1,
+    }
+}
+"#####,
+        r#####"
+enum Dir { North, East, South, West }
+
+fn f(dir: Dir) -> u8 {
+    match dir {
+        // Going up.
+        Dir::North => 0,
+        Dir::West | Dir::East => 1,
+        Dir::South => 2,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_arith_with_checked() {
     check_doc_test(
@@ -3,17 +3,20 @@
 use std::ops;
 
 pub(crate) use gen_trait_fn_body::gen_trait_fn_body;
-use hir::{db::HirDatabase, HirDisplay, Semantics};
-use ide_db::{famous_defs::FamousDefs, path_transform::PathTransform, RootDatabase, SnippetCap};
+use hir::{db::HirDatabase, HirDisplay, ScopeDef, Semantics, SemanticsScope};
+use ide_db::{
+    famous_defs::FamousDefs, path_transform::PathTransform, FxHashSet, RootDatabase, SnippetCap,
+};
 use stdx::format_to;
 use syntax::{
+    algo::find_node_at_range,
     ast::{
         self,
         edit::{self, AstNodeEdit},
         edit_in_place::{AttrsOwnerEdit, Removable},
         make, HasArgList, HasAttrs, HasGenericParams, HasName, HasTypeBounds, Whitespace,
     },
-    ted, AstNode, AstToken, Direction, SourceFile,
+    ted, AstNode, AstToken, Direction, NodeOrToken, SourceFile,
     SyntaxKind::*,
     SyntaxNode, TextRange, TextSize, T,
 };
@@ -420,7 +423,13 @@ pub(crate) fn find_impl_block_end(impl_def: ast::Impl, buf: &mut String) -> Opti
 /// Generates the surrounding `impl Type { <code> }` including type and lifetime
 /// parameters.
 pub(crate) fn generate_impl_text(adt: &ast::Adt, code: &str) -> String {
-    generate_impl_text_inner(adt, None, true, code)
+    generate_impl_text_inner(adt, None, None, true, code)
+}
+
+/// Generates the surrounding `impl Name { <code> }` for a type called `name` that has the same
+/// type and lifetime parameters as `adt`, like a builder generated for it.
+pub(crate) fn generate_impl_text_with_name(adt: &ast::Adt, name: &str, code: &str) -> String {
+    generate_impl_text_inner(adt, Some(name), None, true, code)
 }
 
 /// Generates the surrounding `impl <trait> for Type { <code> }` including type
@@ -428,7 +437,7 @@ pub(crate) fn generate_impl_text(adt: &ast::Adt, code: &str) -> String {
 ///
 /// This is useful for traits like `PartialEq`, since `impl<T> PartialEq for U<T>` often requires `T: PartialEq`.
 pub(crate) fn generate_trait_impl_text(adt: &ast::Adt, trait_text: &str, code: &str) -> String {
-    generate_impl_text_inner(adt, Some(trait_text), true, code)
+    generate_impl_text_inner(adt, None, Some(trait_text), true, code)
 }
 
 /// Generates the surrounding `impl <trait> for Type { <code> }` including type
@@ -440,11 +449,12 @@ pub(crate) fn generate_trait_impl_text_intransitive(
     trait_text: &str,
     code: &str,
 ) -> String {
-    generate_impl_text_inner(adt, Some(trait_text), false, code)
+    generate_impl_text_inner(adt, None, Some(trait_text), false, code)
 }
 
 fn generate_impl_text_inner(
     adt: &ast::Adt,
+    self_ty_name: Option<&str>,
     trait_text: Option<&str>,
     trait_is_transitive: bool,
     code: &str,
@@ -506,7 +516,10 @@ fn generate_impl_text_inner(
         buf.push_str(trait_text);
         buf.push_str(" for ");
     }
-    buf.push_str(&adt.name().unwrap().text());
+    match self_ty_name {
+        Some(name) => buf.push_str(name),
+        None => buf.push_str(&adt.name().unwrap().text()),
+    }
     if let Some(generic_params) = generic_params {
         format_to!(buf, "{}", generic_params.to_generic_args());
     }
@@ -700,6 +713,77 @@ pub(crate) fn get_methods(items: &ast::AssocItemList) -> Vec<ast::Fn> {
         .collect()
 }
 
+/// Returns the range covered by `nodes`, which are consecutive siblings, and its text with the nodes
+/// rearranged into the order of `sorted`. Comments on the lines right above a node, and a comment
+/// at the end of its last line, move together with the node. `missing_separator` returns the
+/// separator a node needs when it's moved away from the last position, like the comma of a match
+/// arm.
+pub(crate) fn reorder_siblings<N: AstNode + PartialEq>(
+    nodes: &[N],
+    sorted: &[N],
+    missing_separator: impl Fn(&N) -> Option<&'static str>,
+) -> Option<(TextRange, String)> {
+    let parent = nodes.first()?.syntax().parent()?;
+    let parent_text = parent.to_string();
+    let text = |range: TextRange| &parent_text[range - parent.text_range().start()];
+
+    let chunks = nodes
+        .iter()
+        .map(|node| {
+            let node = node.syntax();
+            let mut start = node.text_range().start();
+            let mut prev = node.prev_sibling_or_token();
+            while let Some(NodeOrToken::Token(token)) = prev {
+                match token.kind() {
+                    WHITESPACE if token.text().contains("\n\n") => break,
+                    WHITESPACE => (),
+                    COMMENT => {
+                        // A comment after some code on the same line belongs to that code.
+                        let on_own_line = token
+                            .prev_token()
+                            .map_or(true, |it| it.kind() == WHITESPACE && it.text().contains('\n'));
+                        if !on_own_line {
+                            break;
+                        }
+                        start = token.text_range().start();
+                    }
+                    _ => break,
+                }
+                prev = token.prev_sibling_or_token();
+            }
+            let mut end = node.text_range().end();
+            let mut next = node.next_sibling_or_token();
+            while let Some(NodeOrToken::Token(token)) = next {
+                match token.kind() {
+                    WHITESPACE if !token.text().contains('\n') => (),
+                    COMMENT => {
+                        end = token.text_range().end();
+                        break;
+                    }
+                    _ => break,
+                }
+                next = token.next_sibling_or_token();
+            }
+            (TextRange::new(start, end), node.text_range().end())
+        })
+        .collect::<Vec<_>>();
+
+    let mut buf = String::new();
+    let mut prev_end = chunks.first()?.0.start();
+    for (i, node) in sorted.iter().enumerate() {
+        let (slot, _) = chunks[i];
+        let (chunk, node_end) = chunks[nodes.iter().position(|it| it == node)?];
+        buf.push_str(text(TextRange::new(prev_end, slot.start())));
+        buf.push_str(text(TextRange::new(chunk.start(), node_end)));
+        if i + 1 != sorted.len() {
+            buf.push_str(missing_separator(node).unwrap_or_default());
+        }
+        buf.push_str(text(TextRange::new(node_end, chunk.end())));
+        prev_end = slot.end();
+    }
+    Some((TextRange::new(chunks.first()?.0.start(), prev_end), buf))
+}
+
 /// Trim(remove leading and trailing whitespace) `initial_range` in `source_file`, return the trimmed range.
 pub(crate) fn trimmed_text_range(source_file: &SourceFile, initial_range: TextRange) -> TextRange {
     let mut trimmed_range = initial_range;
@@ -742,6 +826,50 @@ pub(crate) fn convert_param_list_to_arg_list(list: ast::ParamList) -> ast::ArgLi
     make::arg_list(args)
 }
 
+/// Finds the argument list of the call made through the function reference at `range`, so that
+/// call sites can be updated along with the function's signature. The returned flag is `true` for
+/// method calls, whose receiver is not part of the argument list.
+pub(crate) fn call_arg_list(
+    source_file: &SourceFile,
+    range: TextRange,
+) -> Option<(ast::ArgList, bool)> {
+    if let Some(call_expr) = find_node_at_range::<ast::CallExpr>(source_file.syntax(), range) {
+        if !call_expr.expr()?.syntax().text_range().contains_range(range) {
+            return None;
+        }
+        return Some((call_expr.arg_list()?, false));
+    }
+
+    if let Some(method_call_expr) =
+        find_node_at_range::<ast::MethodCallExpr>(source_file.syntax(), range)
+    {
+        if !method_call_expr.name_ref()?.syntax().text_range().contains_range(range) {
+            return None;
+        }
+        return Some((method_call_expr.arg_list()?, true));
+    }
+
+    None
+}
+
+/// Returns `name`, with a numeric suffix appended if a local of that name is already in `scope`.
+pub(crate) fn unique_local_name(scope: &SemanticsScope<'_>, name: String) -> String {
+    let mut locals = FxHashSet::default();
+    scope.process_all_names(&mut |local, def| {
+        if let ScopeDef::Local(_) = def {
+            locals.insert(local.to_smol_str());
+        }
+    });
+
+    let mut unique_name = name.clone();
+    let mut counter = 0;
+    while locals.contains(unique_name.as_str()) {
+        counter += 1;
+        unique_name = format!("{name}{counter}");
+    }
+    unique_name
+}
+
 /// Calculate the number of hashes required for a raw string containing `s`
 pub(crate) fn required_hashes(s: &str) -> usize {
     let mut res = 0usize;
@@ -122,6 +122,16 @@ pub(crate) fn for_variable(expr: &ast::Expr, sema: &Semantics<'_, RootDatabase>)
     "var_name".to_string()
 }
 
+/// Suggest name of a value of the given type, based on the name of the type
+///
+/// # Examples
+/// `Option<Config>` -> `config`
+/// `&dyn Display` -> `display`
+pub(crate) fn for_type(ty: &hir::Type, db: &RootDatabase) -> Option<String> {
+    let ty = ty.remove_ref().unwrap_or_else(|| ty.clone());
+    name_of_type(&ty, db)
+}
+
 fn normalize(name: &str) -> Option<String> {
     let name = to_lower_snake_case(name);
 
@@ -35,7 +35,9 @@ use crate::{
     item::Builder,
     render::{
         const_::render_const,
-        function::{render_fn, render_method},
+        function::{
+            render_fn, render_fn_with_suffixes, render_method, render_method_with_suffixes,
+        },
         literal::{render_struct_literal, render_variant_lit},
         macro_::render_macro,
         pattern::{render_struct_pat, render_variant_pat},
@@ -189,6 +191,18 @@ impl Completions {
             Visible::Editable => true,
             Visible::No => return,
         };
+        if let hir::ScopeDef::ModuleDef(hir::ModuleDef::Function(func)) = resolution {
+            self.add_all(
+                render_fn_with_suffixes(
+                    RenderContext::new(ctx).private_editable(is_private_editable),
+                    path_ctx,
+                    Some(local_name.clone()),
+                    func,
+                )
+                .into_iter()
+                .map(Builder::build),
+            );
+        }
         self.add(
             render_path_resolution(
                 RenderContext::new(ctx)
@@ -303,6 +317,16 @@ impl Completions {
             Visible::Editable => true,
             Visible::No => return,
         };
+        self.add_all(
+            render_fn_with_suffixes(
+                RenderContext::new(ctx).private_editable(is_private_editable),
+                path_ctx,
+                local_name.clone(),
+                func,
+            )
+            .into_iter()
+            .map(Builder::build),
+        );
         self.add(
             render_fn(
                 RenderContext::new(ctx).private_editable(is_private_editable),
@@ -330,6 +354,17 @@ impl Completions {
             Visible::Editable => true,
             Visible::No => return,
         };
+        self.add_all(
+            render_method_with_suffixes(
+                RenderContext::new(ctx).private_editable(is_private_editable),
+                dot_access,
+                receiver.clone(),
+                local_name.clone(),
+                func,
+            )
+            .into_iter()
+            .map(Builder::build),
+        );
         self.add(
             render_method(
                 RenderContext::new(ctx).private_editable(is_private_editable),
@@ -357,6 +392,19 @@ impl Completions {
             Visible::Editable => true,
             Visible::No => return,
         };
+        self.add_all(
+            render_method_with_suffixes(
+                RenderContext::new(ctx)
+                    .private_editable(is_private_editable)
+                    .import_to_add(Some(import.clone())),
+                dot_access,
+                None,
+                None,
+                func,
+            )
+            .into_iter()
+            .map(Builder::build),
+        );
         self.add(
             render_method(
                 RenderContext::new(ctx)
@@ -537,6 +537,48 @@ fn foo(a: A<u32>) { a.$0 }
         )
     }
 
+    #[test]
+    fn test_arbitrary_self_type_method_completion() {
+        check(
+            r#"
+//- minicore: receiver
+mod ptr {
+    pub struct MyPtr<T>(*const T);
+    impl<T> core::ops::Receiver for MyPtr<T> {
+        type Target = T;
+    }
+}
+use ptr::MyPtr;
+struct A {}
+impl A {
+    fn by_ptr(self: MyPtr<Self>) {}
+    fn by_ref(&self) {}
+}
+fn foo(a: MyPtr<A>) { a.$0 }
+"#,
+            expect![[r#"
+                me by_ptr() fn(self)
+            "#]],
+        )
+    }
+
+    #[test]
+    fn test_type_alias_impl_trait_method_completion() {
+        check(
+            r#"
+struct A {}
+trait Trait { fn the_method(&self); }
+impl Trait for A {}
+type Alias = impl Trait;
+fn make() -> Alias { A {} }
+fn foo() { make().$0 }
+"#,
+            expect![[r#"
+                me the_method() (as Trait) fn(&self)
+            "#]],
+        );
+    }
+
     #[test]
     fn test_trait_method_completion() {
         check(
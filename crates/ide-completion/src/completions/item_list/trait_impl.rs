@@ -30,6 +30,9 @@
 //!     fn foo() {}$0
 //! }
 //! ```
+//!
+//! Functions with a default implementation in the trait are completed with a
+//! copy of that implementation as their body, to be adjusted as needed.
 
 use hir::{self, HasAttrs};
 use ide_db::{
@@ -205,13 +208,25 @@ fn add_function_impl(
                 _ => unreachable!(),
             };
 
-            let function_decl = function_declaration(&transformed_fn, source.file_id.is_macro());
-            match ctx.config.snippet_cap {
-                Some(cap) => {
+            let needs_whitespace = source.file_id.is_macro();
+            let function_decl = function_declaration(&transformed_fn, needs_whitespace);
+            let default_body = default_body(&transformed_fn, needs_whitespace);
+            match (ctx.config.snippet_cap, default_body) {
+                (Some(cap), Some(body)) => {
+                    // Escape the body so that it isn't treated as snippet-specific constructs.
+                    let body = body.replace('\\', "\\\\").replace('$', "\\$");
+                    let snippet = format!("{function_decl} {body}$0");
+                    item.snippet_edit(cap, TextEdit::replace(replacement_range, snippet));
+                }
+                (Some(cap), None) => {
                     let snippet = format!("{function_decl} {{\n    $0\n}}");
                     item.snippet_edit(cap, TextEdit::replace(replacement_range, snippet));
                 }
-                None => {
+                (None, Some(body)) => {
+                    let text = format!("{function_decl} {body}");
+                    item.text_edit(TextEdit::replace(replacement_range, text));
+                }
+                (None, None) => {
                     let header = format!("{function_decl} {{");
                     item.text_edit(TextEdit::replace(replacement_range, header));
                 }
@@ -390,6 +405,17 @@ fn function_declaration(node: &ast::Fn, needs_whitespace: bool) -> String {
     syntax.trim_end().to_owned()
 }
 
+/// Returns the body of the trait's default implementation of the function, if there is one.
+fn default_body(node: &ast::Fn, needs_whitespace: bool) -> Option<String> {
+    let body = node.body()?;
+    let body = if needs_whitespace {
+        insert_whitespace_into_node::insert_ws_into(body.syntax().clone())
+    } else {
+        body.syntax().clone()
+    };
+    Some(body.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
@@ -1289,6 +1315,72 @@ trait Tr {
 impl Tr for () {
     type Item = $0;
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn default_fn_body() {
+        check_edit(
+            "fn foo",
+            r#"
+trait Test {
+    fn foo(&self) -> u32 {
+        42
+    }
+}
+struct T;
+
+impl Test for T {
+    fn f$0
+}
+"#,
+            r#"
+trait Test {
+    fn foo(&self) -> u32 {
+        42
+    }
+}
+struct T;
+
+impl Test for T {
+    fn foo(&self) -> u32 {
+        42
+    }$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generics_are_inlined_in_default_fn_body() {
+        check_edit(
+            "fn foo",
+            r#"
+trait Test<T> {
+    fn foo(&self) -> Option<T> where T: Copy {
+        None::<T>
+    }
+}
+struct S;
+
+impl Test<u32> for S {
+    fn $0
+}
+"#,
+            r#"
+trait Test<T> {
+    fn foo(&self) -> Option<T> where T: Copy {
+        None::<T>
+    }
+}
+struct S;
+
+impl Test<u32> for S {
+    fn foo(&self) -> Option<u32> where u32: Copy {
+        None::<u32>
+    }$0
+}
 "#,
         );
     }
@@ -4,7 +4,7 @@ use hir::{db::HirDatabase, AsAssocItem, HirDisplay};
 use ide_db::{SnippetCap, SymbolKind};
 use itertools::Itertools;
 use stdx::{format_to, to_lower_snake_case};
-use syntax::{AstNode, SmolStr};
+use syntax::{ast, match_ast, AstNode, SmolStr};
 
 use crate::{
     context::{CompletionContext, DotAccess, DotAccessKind, PathCompletionCtx, PathKind},
@@ -13,12 +13,31 @@ use crate::{
     CallableSnippets,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum FuncKind<'ctx> {
     Function(&'ctx PathCompletionCtx),
     Method(&'ctx DotAccess, Option<hir::Name>),
 }
 
+/// A postfix which is appended to the inserted call of a function, because the type it returns
+/// has to be awaited and/or propagated with `?` at the completion site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallSuffix {
+    Await,
+    Try,
+    AwaitTry,
+}
+
+impl CallSuffix {
+    fn as_str(self) -> &'static str {
+        match self {
+            CallSuffix::Await => ".await",
+            CallSuffix::Try => "?",
+            CallSuffix::AwaitTry => ".await?",
+        }
+    }
+}
+
 pub(crate) fn render_fn(
     ctx: RenderContext<'_>,
     path_ctx: &PathCompletionCtx,
@@ -26,7 +45,19 @@ pub(crate) fn render_fn(
     func: hir::Function,
 ) -> Builder {
     let _p = profile::span("render_fn");
-    render(ctx, local_name, func, FuncKind::Function(path_ctx))
+    render(ctx, local_name, func, FuncKind::Function(path_ctx), None)
+}
+
+/// Renders the variants of a function completion which append `.await` and/or `?` to the
+/// inserted call, if the return type of the function requires them at the completion site.
+pub(crate) fn render_fn_with_suffixes(
+    ctx: RenderContext<'_>,
+    path_ctx: &PathCompletionCtx,
+    local_name: Option<hir::Name>,
+    func: hir::Function,
+) -> Vec<Builder> {
+    let _p = profile::span("render_fn_with_suffixes");
+    render_with_suffixes(ctx, local_name, func, FuncKind::Function(path_ctx))
 }
 
 pub(crate) fn render_method(
@@ -37,7 +68,40 @@ pub(crate) fn render_method(
     func: hir::Function,
 ) -> Builder {
     let _p = profile::span("render_method");
-    render(ctx, local_name, func, FuncKind::Method(dot_access, receiver))
+    render(ctx, local_name, func, FuncKind::Method(dot_access, receiver), None)
+}
+
+/// Like [`render_fn_with_suffixes`], but for methods.
+pub(crate) fn render_method_with_suffixes(
+    ctx: RenderContext<'_>,
+    dot_access: &DotAccess,
+    receiver: Option<hir::Name>,
+    local_name: Option<hir::Name>,
+    func: hir::Function,
+) -> Vec<Builder> {
+    let _p = profile::span("render_method_with_suffixes");
+    render_with_suffixes(ctx, local_name, func, FuncKind::Method(dot_access, receiver))
+}
+
+fn render_with_suffixes(
+    ctx: RenderContext<'_>,
+    local_name: Option<hir::Name>,
+    func: hir::Function,
+    func_kind: FuncKind<'_>,
+) -> Vec<Builder> {
+    let completion = ctx.completion;
+    // The suffix is appended to the call we insert, so there is nothing to do if we don't insert one.
+    if completion.config.snippet_cap.is_none()
+        || call_params(completion, func, &func_kind).is_none()
+    {
+        return Vec::new();
+    }
+    call_suffixes(completion, func)
+        .into_iter()
+        .map(|suffix| {
+            render(ctx.clone(), local_name.clone(), func, func_kind.clone(), Some(suffix))
+        })
+        .collect()
 }
 
 fn render(
@@ -45,6 +109,7 @@ fn render(
     local_name: Option<hir::Name>,
     func: hir::Function,
     func_kind: FuncKind<'_>,
+    suffix: Option<(CallSuffix, hir::Type)>,
 ) -> Builder {
     let db = completion.db;
 
@@ -67,7 +132,11 @@ fn render(
         call.clone(),
     );
 
-    let ret_type = func.ret_type(db);
+    // With a suffix, the completed expression has the type of the suffixed call.
+    let (suffix, ret_type) = match suffix {
+        Some((suffix, ty)) => (suffix.as_str(), ty),
+        None => ("", func.ret_type(db)),
+    };
     let is_op_method = func
         .as_assoc_item(ctx.db())
         .and_then(|trait_| trait_.containing_trait_or_trait_impl(ctx.db()))
@@ -96,44 +165,22 @@ fn render(
     item.set_documentation(ctx.docs(func))
         .set_deprecated(ctx.is_deprecated(func) || ctx.is_deprecated_assoc_item(func))
         .detail(detail(db, func))
-        .lookup_by(name.unescaped().to_smol_str());
-
-    match ctx.completion.config.snippet_cap {
-        Some(cap) => {
-            let complete_params = match func_kind {
-                FuncKind::Function(PathCompletionCtx {
-                    kind: PathKind::Expr { .. },
-                    has_call_parens: false,
-                    ..
-                }) => Some(false),
-                FuncKind::Method(
-                    DotAccess {
-                        kind:
-                            DotAccessKind::Method { has_parens: false } | DotAccessKind::Field { .. },
-                        ..
-                    },
-                    _,
-                ) => Some(true),
-                _ => None,
-            };
-            if let Some(has_dot_receiver) = complete_params {
-                if let Some((self_param, params)) =
-                    params(ctx.completion, func, &func_kind, has_dot_receiver)
-                {
-                    add_call_parens(
-                        &mut item,
-                        completion,
-                        cap,
-                        call,
-                        escaped_call,
-                        self_param,
-                        params,
-                    );
-                }
-            }
+        .lookup_by(SmolStr::from_iter([name.unescaped().to_smol_str().as_str(), suffix]));
+
+    if let Some(cap) = ctx.completion.config.snippet_cap {
+        if let Some((self_param, params)) = call_params(ctx.completion, func, &func_kind) {
+            add_call_parens(
+                &mut item,
+                completion,
+                cap,
+                call,
+                escaped_call,
+                self_param,
+                params,
+                suffix,
+            );
         }
-        _ => (),
-    };
+    }
 
     match ctx.import_to_add {
         Some(import_to_add) => {
@@ -150,6 +197,145 @@ fn render(
     item
 }
 
+/// Returns the parameters of the call we should insert for `func`, if any.
+fn call_params(
+    ctx: &CompletionContext<'_>,
+    func: hir::Function,
+    func_kind: &FuncKind<'_>,
+) -> Option<(Option<hir::SelfParam>, Vec<hir::Param>)> {
+    let has_dot_receiver = match func_kind {
+        FuncKind::Function(PathCompletionCtx {
+            kind: PathKind::Expr { .. },
+            has_call_parens: false,
+            ..
+        }) => false,
+        FuncKind::Method(
+            DotAccess {
+                kind: DotAccessKind::Method { has_parens: false } | DotAccessKind::Field { .. },
+                ..
+            },
+            _,
+        ) => true,
+        _ => return None,
+    };
+    params(ctx, func, func_kind, has_dot_receiver)
+}
+
+/// Computes the suffixes that are worth appending to a call of `func` at the completion site,
+/// together with the type of the resulting expression.
+fn call_suffixes(ctx: &CompletionContext<'_>, func: hir::Function) -> Vec<(CallSuffix, hir::Type)> {
+    let config = ctx.config;
+    if !config.enable_auto_await && !config.enable_auto_try {
+        return Vec::new();
+    }
+    let (is_async, try_target) = enclosing_body_info(ctx);
+    let ret_ty = func.ret_type(ctx.db);
+    let mut res = Vec::new();
+    let awaited = match ret_ty.into_future_output(ctx.db) {
+        Some(output) if config.enable_auto_await && is_async => {
+            res.push((CallSuffix::Await, output.clone()));
+            Some(output)
+        }
+        _ => None,
+    };
+    if let (true, Some(try_target)) = (config.enable_auto_try, try_target) {
+        match awaited {
+            Some(awaited) => {
+                if let Some(output) = try_output(ctx, &awaited, &try_target) {
+                    res.push((CallSuffix::AwaitTry, output));
+                }
+            }
+            None => {
+                if let Some(output) = try_output(ctx, &ret_ty, &try_target) {
+                    res.push((CallSuffix::Try, output));
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Finds out whether the completion site is in an async context, and the type that `?` returns
+/// to there, if known.
+fn enclosing_body_info(ctx: &CompletionContext<'_>) -> (bool, Option<hir::Type>) {
+    let sema = &ctx.sema;
+    let Some(parent) = ctx.original_token.parent() else { return (false, None) };
+    let mut in_try_block = false;
+    for node in sema.ancestors_with_macros(parent) {
+        match_ast! {
+            match node {
+                ast::Fn(it) => {
+                    let ret_ty = sema.to_def(&it).filter(|_| !in_try_block).and_then(|it| {
+                        if it.is_async(ctx.db) {
+                            it.async_ret_type(ctx.db)
+                        } else {
+                            Some(it.ret_type(ctx.db))
+                        }
+                    });
+                    return (it.async_token().is_some(), ret_ty);
+                },
+                ast::ClosureExpr(it) => {
+                    let is_async = it.async_token().is_some();
+                    let ret_ty = if in_try_block || is_async {
+                        None
+                    } else {
+                        sema.type_of_expr(&ast::Expr::ClosureExpr(it))
+                            .and_then(|ty| ty.original.as_callable(ctx.db))
+                            .map(|it| it.return_type())
+                    };
+                    return (is_async, ret_ty);
+                },
+                ast::BlockExpr(it) => {
+                    if it.async_token().is_some() {
+                        return (true, None);
+                    }
+                    if it.const_token().is_some() {
+                        return (false, None);
+                    }
+                    if it.try_token().is_some() {
+                        in_try_block = true;
+                    }
+                },
+                _ => {
+                    if ast::Item::can_cast(node.kind()) {
+                        break;
+                    }
+                },
+            }
+        }
+    }
+    (false, None)
+}
+
+/// Returns the type of `expr?` where `expr` has type `ty`, if `?` can propagate its residual to a
+/// body returning `target`.
+fn try_output(
+    ctx: &CompletionContext<'_>,
+    ty: &hir::Type,
+    target: &hir::Type,
+) -> Option<hir::Type> {
+    let famous_defs = ctx.famous_defs();
+    let adt = ty.as_adt()?;
+    if target.as_adt() != Some(adt) {
+        return None;
+    }
+    let mut args = ty.type_arguments();
+    let output = args.next()?;
+    if famous_defs.core_option_Option().map(hir::Adt::Enum) == Some(adt) {
+        return Some(output);
+    }
+    if famous_defs.core_result_Result().map(hir::Adt::Enum) == Some(adt) {
+        let error = args.next()?;
+        let target_error = target.type_arguments().nth(1)?;
+        let convertible = error.could_unify_with(ctx.db, &target_error)
+            || famous_defs
+                .core_convert_From()
+                .map_or(false, |from| target_error.impls_trait(ctx.db, from, &[error]));
+        return convertible.then_some(output);
+    }
+    None
+}
+
 pub(super) fn add_call_parens<'b>(
     builder: &'b mut Builder,
     ctx: &CompletionContext<'_>,
@@ -158,11 +344,12 @@ pub(super) fn add_call_parens<'b>(
     escaped_name: SmolStr,
     self_param: Option<hir::SelfParam>,
     params: Vec<hir::Param>,
+    suffix: &str,
 ) -> &'b mut Builder {
     cov_mark::hit!(inserts_parens_for_function_calls);
 
     let (snippet, label_suffix) = if self_param.is_none() && params.is_empty() {
-        (format!("{escaped_name}()$0"), "()")
+        (format!("{escaped_name}(){suffix}$0"), "()")
     } else {
         builder.trigger_call_info();
         let snippet = if let Some(CallableSnippets::FillArguments) = ctx.config.callable {
@@ -192,7 +379,7 @@ pub(super) fn add_call_parens<'b>(
             match self_param {
                 Some(self_param) => {
                     format!(
-                        "{}(${{1:{}}}{}{})$0",
+                        "{}(${{1:{}}}{}{}){suffix}$0",
                         escaped_name,
                         self_param.display(ctx.db),
                         if params.is_empty() { "" } else { ", " },
@@ -200,17 +387,21 @@ pub(super) fn add_call_parens<'b>(
                     )
                 }
                 None => {
-                    format!("{escaped_name}({function_params_snippet})$0")
+                    format!("{escaped_name}({function_params_snippet}){suffix}$0")
                 }
             }
         } else {
             cov_mark::hit!(suppress_arg_snippets);
-            format!("{escaped_name}($0)")
+            if suffix.is_empty() {
+                format!("{escaped_name}($0)")
+            } else {
+                format!("{escaped_name}($1){suffix}$0")
+            }
         };
 
         (snippet, "(…)")
     };
-    builder.label(SmolStr::from_iter([&name, label_suffix])).insert_snippet(cap, snippet)
+    builder.label(SmolStr::from_iter([&name, label_suffix, suffix])).insert_snippet(cap, snippet)
 }
 
 fn ref_of_param(ctx: &CompletionContext<'_>, arg: &str, ty: &hir::Type) -> &'static str {
@@ -307,7 +498,7 @@ fn params(
 #[cfg(test)]
 mod tests {
     use crate::{
-        tests::{check_edit, check_edit_with_config, TEST_CONFIG},
+        tests::{check_edit, check_edit_with_config, completion_list_no_kw, TEST_CONFIG},
         CallableSnippets, CompletionConfig,
     };
 
@@ -437,6 +628,118 @@ fn main() { with_args($0) }
         );
     }
 
+    #[test]
+    fn appends_await_to_calls_in_async_context() {
+        check_edit(
+            "foo.await",
+            r#"
+//- minicore: future
+async fn foo() -> u32 { 0 }
+async fn bar() { fo$0 }
+"#,
+            r#"
+async fn foo() -> u32 { 0 }
+async fn bar() { foo().await$0 }
+"#,
+        );
+        check_edit(
+            "foo.await",
+            r#"
+//- minicore: future
+struct S;
+impl S {
+    async fn foo(&self, x: i32) -> u32 { x as u32 }
+}
+fn bar(s: S) {
+    async move { s.fo$0 };
+}
+"#,
+            r#"
+struct S;
+impl S {
+    async fn foo(&self, x: i32) -> u32 { x as u32 }
+}
+fn bar(s: S) {
+    async move { s.foo(${1:x}).await$0 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn appends_try_to_calls_in_fallible_context() {
+        check_edit(
+            "foo?",
+            r#"
+//- minicore: option
+fn foo() -> Option<u32> { None }
+fn bar() -> Option<()> { fo$0 }
+"#,
+            r#"
+fn foo() -> Option<u32> { None }
+fn bar() -> Option<()> { foo()?$0 }
+"#,
+        );
+        check_edit(
+            "foo?",
+            r#"
+//- minicore: result, from
+struct E1;
+struct E2;
+impl From<E1> for E2 {
+    fn from(_: E1) -> E2 { E2 }
+}
+fn foo() -> Result<u32, E1> { Err(E1) }
+fn bar() -> Result<(), E2> { fo$0 }
+"#,
+            r#"
+struct E1;
+struct E2;
+impl From<E1> for E2 {
+    fn from(_: E1) -> E2 { E2 }
+}
+fn foo() -> Result<u32, E1> { Err(E1) }
+fn bar() -> Result<(), E2> { foo()?$0 }
+"#,
+        );
+        check_edit(
+            "foo.await?",
+            r#"
+//- minicore: future, result
+async fn foo() -> Result<u32, ()> { Ok(0) }
+async fn bar() -> Result<(), ()> { fo$0 }
+"#,
+            r#"
+async fn foo() -> Result<u32, ()> { Ok(0) }
+async fn bar() -> Result<(), ()> { foo().await?$0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn no_suffixes_outside_of_matching_context() {
+        check_no_suffixes(
+            r#"
+//- minicore: future, option
+async fn foo() -> Option<u32> { None }
+fn bar() -> Option<()> { fo$0 }
+"#,
+        );
+        check_no_suffixes(
+            r#"
+//- minicore: option, result
+fn foo() -> Option<u32> { None }
+fn bar() -> Result<(), ()> { fo$0 }
+"#,
+        );
+    }
+
+    fn check_no_suffixes(ra_fixture: &str) {
+        let items = completion_list_no_kw(ra_fixture);
+        assert!(!items.contains(".await"), "{items}");
+        assert!(!items.contains('?'), "{items}");
+    }
+
     #[test]
     fn strips_underscores_from_args() {
         check_edit(
@@ -4,8 +4,9 @@ use std::sync::Arc;
 
 use base_db::{
     salsa::{Database, Durability},
-    Change, SourceRootId,
+    Change, SourceDatabase, SourceDatabaseExt, SourceRootId,
 };
+use hir::db::DefDatabase;
 use profile::{memory_usage, Bytes};
 use rustc_hash::FxHashSet;
 
@@ -38,6 +39,37 @@ impl RootDatabase {
         change.apply(self);
     }
 
+    /// Returns a new database with the same inputs as this one. The two databases are independent
+    /// of each other, and nothing is analyzed in the new one yet.
+    pub fn fork(&self) -> RootDatabase {
+        let _p = profile::span("RootDatabase::fork");
+        let (local_roots, library_roots) = (self.local_roots(), self.library_roots());
+        let mut root_ids =
+            local_roots.iter().chain(library_roots.iter()).copied().collect::<Vec<_>>();
+        root_ids.sort_unstable();
+
+        let mut change = Change::new();
+        let mut roots = Vec::with_capacity(root_ids.len());
+        for root_id in root_ids {
+            let root = self.source_root(root_id);
+            for file_id in root.iter() {
+                change.change_file(file_id, Some(self.file_text(file_id)));
+            }
+            roots.push((*root).clone());
+        }
+        // The roots get new ids, but nothing besides the files refers to them.
+        change.set_roots(roots);
+        change.set_crate_graph((*self.crate_graph()).clone());
+        change.set_proc_macros((*self.proc_macros()).clone());
+
+        let mut db = RootDatabase::new(None);
+        if self.expand_proc_attr_macros() {
+            db.enable_proc_attr_macros();
+        }
+        db.apply_change(change);
+        db
+    }
+
     // Feature: Memory Usage
     //
     // Clears rust-analyzer's internal database and prints memory usage statistics.
@@ -125,6 +157,7 @@ impl RootDatabase {
             hir::db::InferQueryQuery
             hir::db::MirBodyQuery
             hir::db::BorrowckQuery
+            hir::db::RegionInferenceQuery
             hir::db::TyQuery
             hir::db::ValueTyQuery
             hir::db::ImplSelfTyQuery
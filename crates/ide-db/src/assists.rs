@@ -43,6 +43,8 @@ pub enum AssistKind {
     RefactorExtract,
     RefactorInline,
     RefactorRewrite,
+    /// Applies to the whole file, like organizing its items. Clients can run these on save.
+    Source,
 }
 
 impl AssistKind {
@@ -72,6 +74,7 @@ impl AssistKind {
             AssistKind::RefactorExtract => "RefactorExtract",
             AssistKind::RefactorInline => "RefactorInline",
             AssistKind::RefactorRewrite => "RefactorRewrite",
+            AssistKind::Source => "Source",
         }
     }
 }
@@ -88,6 +91,7 @@ impl FromStr for AssistKind {
             "RefactorExtract" => Ok(AssistKind::RefactorExtract),
             "RefactorInline" => Ok(AssistKind::RefactorInline),
             "RefactorRewrite" => Ok(AssistKind::RefactorRewrite),
+            "Source" => Ok(AssistKind::Source),
             unknown => Err(format!("Unknown AssistKind: '{unknown}'")),
         }
     }
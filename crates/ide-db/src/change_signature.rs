@@ -0,0 +1,328 @@
+//! Changes the parameters of a function along with all of its call sites. It's used by the
+//! "Change Signature" refactoring of the ide, and by assists which add parameters.
+
+use std::{cmp::Reverse, fmt, mem};
+
+use base_db::{FileId, SourceDatabaseExt};
+use hir::{known, AsAssocItem, HasSource, Semantics};
+use syntax::{ast, AstNode, SyntaxNode, TextRange};
+use text_edit::TextEdit;
+
+use crate::{
+    defs::Definition, path_transform::PathTransform, search::FileReference,
+    source_change::SourceChange, FxHashMap, RootDatabase,
+};
+
+/// A parameter of the signature a function is changed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureParam {
+    /// The parameter at this index of the current signature, not counting `self`.
+    Existing(usize),
+    /// A new parameter, for which call sites pass `value`.
+    New { pattern: String, ty: String, value: String },
+    /// A new parameter taking `value`, an expression of the body of the function, whose paths are
+    /// qualified for each call site. Calls in the body of the function pass `name` on instead.
+    FromBody { pattern: String, name: String, ty: String, value: ast::Expr },
+}
+
+#[derive(Debug)]
+pub struct ChangeSignatureError(pub String);
+
+impl fmt::Display for ChangeSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+macro_rules! bail {
+    ($($tokens:tt)*) => {return Err(ChangeSignatureError(format!($($tokens)*)))}
+}
+
+/// Returns why the signature of `func` can't be changed, if it can't.
+pub fn check_function(db: &RootDatabase, func: hir::Function) -> Result<(), ChangeSignatureError> {
+    // The other impls of the trait, and the trait itself, would have to change as well.
+    if func.as_assoc_item(db).and_then(|it| it.containing_trait_or_trait_impl(db)).is_some() {
+        bail!("Changing the signature of trait methods is not supported")
+    }
+    let Some(src) = func.source(db) else { bail!("No source found for the function") };
+    if src.file_id.is_macro() {
+        bail!("Cannot change the signature of a function defined by a macro")
+    }
+    if src.value.param_list().is_none() {
+        bail!("The function has no parameter list")
+    }
+    Ok(())
+}
+
+/// Changes the parameters of `func` to `params`, and updates its call sites.
+///
+/// `edits` are replacements in the file of the function which are applied along with the updates,
+/// e.g. in its body. Nested updates, like the ones of recursive calls, are applied in them.
+pub fn change_signature(
+    sema: &Semantics<'_, RootDatabase>,
+    func: hir::Function,
+    params: &[SignatureParam],
+    edits: &[(TextRange, String)],
+) -> Result<SourceChange, ChangeSignatureError> {
+    let db = sema.db;
+    check_function(db, func)?;
+    let Some(src) = func.source(db) else { bail!("No source found for the function") };
+    let Some(param_list) = src.value.param_list() else {
+        bail!("The function has no parameter list")
+    };
+    let old_params: Vec<ast::Param> = param_list.params().collect();
+    check_params(params, old_params.len())?;
+
+    let file_id = src.file_id.original_file(db);
+    let mut replacements: FxHashMap<FileId, Vec<Replacement>> = FxHashMap::default();
+    let in_file = replacements.entry(file_id).or_default();
+    in_file.push(signature_replacement(&param_list, &old_params, params));
+    in_file.extend(
+        edits.iter().map(|(range, text)| Replacement {
+            range: *range,
+            parts: vec![Part::Text(text.clone())],
+        }),
+    );
+
+    let has_self = func.self_param(db).is_some();
+    let closure_params: Vec<String> = func
+        .assoc_fn_params(db)
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| match param.name(db) {
+            Some(name) if name == known::SELF_PARAM => "this".to_owned(),
+            Some(name) => name.to_string(),
+            None => format!("arg{idx}"),
+        })
+        .collect();
+    let body = src.value.body().map(|it| (file_id, it.syntax().text_range()));
+    let call_sites = CallSites { sema, has_self, closure_params, params, body };
+    for (_, references) in Definition::Function(func).usages(sema).all() {
+        for reference in references {
+            // References whose call can't be mapped out of a macro expansion are left alone.
+            if let Some((file_id, replacement)) = call_sites.replacement(&reference) {
+                replacements.entry(file_id).or_default().push(replacement);
+            }
+        }
+    }
+
+    Ok(replacements
+        .into_iter()
+        .map(|(file_id, replacements)| (file_id, render_edit(&db.file_text(file_id), replacements)))
+        .collect())
+}
+
+fn check_params(params: &[SignatureParam], old_len: usize) -> Result<(), ChangeSignatureError> {
+    let mut used = vec![false; old_len];
+    for param in params {
+        match param {
+            SignatureParam::Existing(idx) => {
+                let Some(used) = used.get_mut(*idx) else {
+                    bail!("Parameter index {idx} is out of bounds")
+                };
+                if mem::replace(used, true) {
+                    bail!("Parameter {idx} is used more than once")
+                }
+            }
+            SignatureParam::New { pattern, ty, value } => {
+                if [pattern, ty, value].iter().any(|it| it.trim().is_empty()) {
+                    bail!("New parameters need a pattern, a type and a value")
+                }
+            }
+            SignatureParam::FromBody { .. } => (),
+        }
+    }
+    Ok(())
+}
+
+/// A replacement of `range` in the original file, whose text is built from `parts`.
+///
+/// As call sites nest, e.g. in `f(f(a, b), c)`, the parts which copy text from the original file
+/// pick up the replacements inside of them when rendered.
+struct Replacement {
+    range: TextRange,
+    parts: Vec<Part>,
+}
+
+enum Part {
+    Text(String),
+    Copy(TextRange),
+}
+
+impl Replacement {
+    /// Builds the replacement of a parenthesized, comma separated list.
+    fn list(range: TextRange, items: impl IntoIterator<Item = Part>) -> Replacement {
+        let mut parts = vec![Part::Text("(".to_owned())];
+        for (idx, item) in items.into_iter().enumerate() {
+            if idx != 0 {
+                parts.push(Part::Text(", ".to_owned()));
+            }
+            parts.push(item);
+        }
+        parts.push(Part::Text(")".to_owned()));
+        Replacement { range, parts }
+    }
+}
+
+fn signature_replacement(
+    param_list: &ast::ParamList,
+    old_params: &[ast::Param],
+    params: &[SignatureParam],
+) -> Replacement {
+    let self_param = param_list.self_param().map(|it| Part::Copy(it.syntax().text_range()));
+    let params = params.iter().map(|param| match param {
+        SignatureParam::Existing(idx) => Part::Copy(old_params[*idx].syntax().text_range()),
+        SignatureParam::New { pattern, ty, .. } | SignatureParam::FromBody { pattern, ty, .. } => {
+            Part::Text(format!("{pattern}: {ty}"))
+        }
+    });
+    Replacement::list(param_list.syntax().text_range(), self_param.into_iter().chain(params))
+}
+
+struct CallSites<'a> {
+    sema: &'a Semantics<'a, RootDatabase>,
+    has_self: bool,
+    /// Names of the parameters of closures which wrap references to the function.
+    closure_params: Vec<String>,
+    params: &'a [SignatureParam],
+    /// The body of the function, in its original file.
+    body: Option<(FileId, TextRange)>,
+}
+
+impl CallSites<'_> {
+    fn replacement(&self, reference: &FileReference) -> Option<(FileId, Replacement)> {
+        let ast::NameLike::NameRef(name_ref) = &reference.name else { return None };
+        let parent = name_ref.syntax().parent()?;
+        if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+            return self.call(&call.arg_list()?, 0);
+        }
+
+        let path = ast::PathSegment::cast(parent)?.parent_path();
+        let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+        match path_expr.syntax().parent().and_then(ast::CallExpr::cast) {
+            Some(call) if call.expr().map_or(false, |it| it.syntax() == path_expr.syntax()) => {
+                // Fully qualified method calls pass the receiver as their first argument.
+                self.call(&call.arg_list()?, self.has_self as usize)
+            }
+            _ => self.closure(&path_expr),
+        }
+    }
+
+    /// Rewrites the arguments of a call, keeping the first `receivers` ones in place.
+    fn call(&self, arg_list: &ast::ArgList, receivers: usize) -> Option<(FileId, Replacement)> {
+        let list = self.sema.original_range_opt(arg_list.syntax())?;
+        let args = arg_list
+            .args()
+            .map(|arg| {
+                let arg = self.sema.original_range_opt(arg.syntax())?;
+                (arg.file_id == list.file_id).then_some(arg.range)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if args.len() < receivers {
+            return None;
+        }
+        let in_body = self.in_body(list.file_id, list.range);
+        let (receivers, args) = args.split_at(receivers);
+        let args = self
+            .params
+            .iter()
+            .map(|param| match param {
+                SignatureParam::Existing(idx) => args.get(*idx).copied().map(Part::Copy),
+                _ => self.new_value(param, arg_list.syntax(), in_body).map(Part::Text),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let items = receivers.iter().copied().map(Part::Copy).chain(args);
+        Some((list.file_id, Replacement::list(list.range, items)))
+    }
+
+    /// Wraps a reference to the function which isn't called directly, like `iter.map(f)`, in a
+    /// closure taking the old parameters.
+    fn closure(&self, path_expr: &ast::PathExpr) -> Option<(FileId, Replacement)> {
+        let range = self.sema.original_range_opt(path_expr.syntax())?;
+        let in_body = self.in_body(range.file_id, range.range);
+        let receivers = self.has_self as usize;
+        let (self_param, old_params) = self.closure_params.split_at(receivers);
+        let args = self
+            .params
+            .iter()
+            .map(|param| match param {
+                SignatureParam::Existing(idx) => Some(old_params[*idx].clone()),
+                _ => self.new_value(param, path_expr.syntax(), in_body),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let args = self_param.iter().cloned().chain(args);
+        let mut replacement = Replacement::list(range.range, args.map(Part::Text));
+        replacement.parts.insert(0, Part::Copy(range.range));
+        replacement.parts.insert(0, Part::Text(format!("|{}| ", self.closure_params.join(", "))));
+        Some((range.file_id, replacement))
+    }
+
+    /// Returns the value passed for the new parameter `param` by a call at `call_site`.
+    fn new_value(
+        &self,
+        param: &SignatureParam,
+        call_site: &SyntaxNode,
+        in_body: bool,
+    ) -> Option<String> {
+        match param {
+            SignatureParam::Existing(_) => None,
+            SignatureParam::New { value, .. } => Some(value.clone()),
+            SignatureParam::FromBody { name, .. } if in_body => Some(name.clone()),
+            SignatureParam::FromBody { value, .. } => {
+                let source_scope = self.sema.scope(value.syntax())?;
+                let target_scope = self.sema.scope(call_site)?;
+                let value = value.clone_subtree().clone_for_update();
+                PathTransform::generic_transformation(&target_scope, &source_scope)
+                    .apply(value.syntax());
+                Some(value.to_string())
+            }
+        }
+    }
+
+    fn in_body(&self, file_id: FileId, range: TextRange) -> bool {
+        self.body
+            .map_or(false, |(body_file, body)| body_file == file_id && body.contains_range(range))
+    }
+}
+
+fn render_edit(text: &str, mut replacements: Vec<Replacement>) -> TextEdit {
+    // Outer replacements come before the ones nested in them.
+    replacements.sort_by_key(|it| (it.range.start(), Reverse(it.range.end())));
+    replacements.dedup_by_key(|it| it.range);
+
+    let mut builder = TextEdit::builder();
+    let mut last_end = None;
+    for (idx, replacement) in replacements.iter().enumerate() {
+        if last_end.map_or(false, |end| replacement.range.start() < end) {
+            // Nested in a previous replacement, or dropped along with an argument.
+            continue;
+        }
+        builder.replace(replacement.range, render(text, replacement, &replacements[idx + 1..]));
+        last_end = Some(replacement.range.end());
+    }
+    builder.finish()
+}
+
+/// Renders `replacement`, applying the ones in `rest` which are nested in its copied parts.
+fn render(text: &str, replacement: &Replacement, rest: &[Replacement]) -> String {
+    let mut buf = String::new();
+    for part in &replacement.parts {
+        let range = match part {
+            Part::Text(it) => {
+                buf.push_str(it);
+                continue;
+            }
+            Part::Copy(range) => *range,
+        };
+        let mut offset = range.start();
+        for (idx, inner) in rest.iter().enumerate() {
+            if range.contains_range(inner.range) && offset <= inner.range.start() {
+                buf.push_str(&text[TextRange::new(offset, inner.range.start())]);
+                buf.push_str(&render(text, inner, &rest[idx + 1..]));
+                offset = inner.range.end();
+            }
+        }
+        buf.push_str(&text[TextRange::new(offset, range.end())]);
+    }
+    buf
+}
@@ -8,6 +8,7 @@ mod apply_change;
 
 pub mod active_parameter;
 pub mod assists;
+pub mod change_signature;
 pub mod defs;
 pub mod famous_defs;
 pub mod helpers;
@@ -39,6 +40,7 @@ pub mod syntax_helpers {
     pub mod insert_whitespace_into_node;
     pub mod format_string;
     pub mod format_string_exprs;
+    pub mod asm_template;
 
     pub use parser::LexedStr;
 }
@@ -251,6 +253,7 @@ impl RootDatabase {
             hir_db::InferQueryQuery
             hir_db::MirBodyQuery
             hir_db::BorrowckQuery
+            hir_db::RegionInferenceQuery
             hir_db::TyQuery
             hir_db::ValueTyQuery
             hir_db::ImplSelfTyQuery
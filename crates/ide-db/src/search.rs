@@ -106,7 +106,7 @@ impl SearchScope {
     }
 
     /// Build a search scope spanning all the reverse dependencies of the given crate.
-    fn reverse_dependencies(db: &RootDatabase, of: hir::Crate) -> SearchScope {
+    pub fn reverse_dependencies(db: &RootDatabase, of: hir::Crate) -> SearchScope {
         let mut entries = NoHashHashMap::default();
         for rev_dep in of.transitive_reverse_dependencies(db) {
             let root_file = rev_dep.root_file(db);
@@ -118,7 +118,7 @@ impl SearchScope {
     }
 
     /// Build a search scope spanning the given crate.
-    fn krate(db: &RootDatabase, of: hir::Crate) -> SearchScope {
+    pub fn krate(db: &RootDatabase, of: hir::Crate) -> SearchScope {
         let root_file = of.root_file(db);
         let source_root_id = db.file_source_root(root_file);
         let source_root = db.source_root(source_root_id);
@@ -126,7 +126,7 @@ impl SearchScope {
     }
 
     /// Build a search scope spanning the given module and all its submodules.
-    fn module_and_children(db: &RootDatabase, module: hir::Module) -> SearchScope {
+    pub fn module_and_children(db: &RootDatabase, module: hir::Module) -> SearchScope {
         let mut entries = NoHashHashMap::default();
 
         let (file_id, range) = {
@@ -320,6 +320,7 @@ impl Definition {
             scope: None,
             include_self_kw_refs: None,
             search_self_mod: false,
+            rename: None,
         }
     }
 }
@@ -335,6 +336,8 @@ pub struct FindUsages<'a> {
     include_self_kw_refs: Option<hir::Type>,
     /// whether to search for the `self` module
     search_self_mod: bool,
+    /// the import alias to search for instead of the definition's name
+    rename: Option<&'a ast::Rename>,
 }
 
 impl<'a> FindUsages<'a> {
@@ -357,6 +360,12 @@ impl<'a> FindUsages<'a> {
         self
     }
 
+    /// Search for usages through the given import alias of the definition instead.
+    pub fn with_rename(mut self, rename: Option<&'a ast::Rename>) -> FindUsages<'a> {
+        self.rename = rename;
+        self
+    }
+
     pub fn at_least_one(&self) -> bool {
         let mut found = false;
         self.search(&mut |_, _| {
@@ -389,9 +398,10 @@ impl<'a> FindUsages<'a> {
             }
         };
 
-        let name = match self.def {
+        let name = match (self.rename, self.def) {
+            (Some(rename), _) => rename.name().map(|it| it.text().trim_start_matches("r#").into()),
             // special case crate modules as these do not have a proper name
-            Definition::Module(module) if module.is_crate_root(self.sema.db) => {
+            (_, Definition::Module(module)) if module.is_crate_root(self.sema.db) => {
                 // FIXME: This assumes the crate name is always equal to its display name when it really isn't
                 module
                     .krate()
@@ -0,0 +1,306 @@
+//! Tools to work with the template strings and operands of the `asm!` family of macros.
+use hir::Semantics;
+use syntax::{
+    ast::{self, IsString},
+    AstNode, AstToken, NodeOrToken, SmolStr, SyntaxElement, SyntaxKind, SyntaxToken, TextRange,
+    TextSize, T,
+};
+
+use crate::RootDatabase;
+
+/// How a placeholder of a template string refers to an operand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsmOperandRef {
+    /// `{0}`, or `{}` which refers to the operand after the one of the previous `{}`.
+    Index(usize),
+    /// `{name}`
+    Name(SmolStr),
+}
+
+/// A `{..}` placeholder in a template string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmPlaceholder {
+    /// The range of the whole placeholder, including the braces and the modifier.
+    pub range: TextRange,
+    /// The range of the operand index or name, which is empty for `{}` and `{:e}`.
+    pub operand_range: TextRange,
+    pub operand: AsmOperandRef,
+}
+
+/// An operand of an `asm!` invocation, like `x = in(reg) 5`.
+#[derive(Debug, Clone)]
+pub struct AsmOperand {
+    pub name: Option<SyntaxToken>,
+    /// The direction and the register class of the operand, like `in(reg)` or `const`.
+    pub kind: String,
+    /// Whether the operand names a register explicitly, like `in("eax")`. These operands can't
+    /// be referred to by the template.
+    pub explicit_register: bool,
+    /// The ranges of the expressions of the operand. `inout(reg) x => y` has two.
+    pub exprs: Vec<TextRange>,
+    pub range: TextRange,
+}
+
+/// The arguments of an `asm!` or `global_asm!` invocation.
+#[derive(Debug, Clone)]
+pub struct AsmArgs {
+    pub templates: Vec<ast::String>,
+    pub operands: Vec<AsmOperand>,
+}
+
+impl AsmArgs {
+    /// Parses the arguments of `macro_call`, if it is an `asm!` or `global_asm!` invocation.
+    pub fn parse(macro_call: &ast::MacroCall) -> Option<AsmArgs> {
+        let name = macro_call.path()?.segment()?.name_ref()?;
+        if !matches!(name.text().as_str(), "asm" | "global_asm") {
+            return None;
+        }
+        let elements = macro_call
+            .token_tree()?
+            .syntax()
+            .children_with_tokens()
+            .filter(|it| !it.kind().is_trivia())
+            .collect::<Vec<_>>();
+        // Skip the delimiters.
+        let elements = match &*elements {
+            [_, elements @ .., last] if matches!(last.kind(), T![')'] | T![']'] | T!['}']) => {
+                elements
+            }
+            [_, elements @ ..] => elements,
+            [] => return None,
+        };
+
+        let mut args = AsmArgs { templates: Vec::new(), operands: Vec::new() };
+        for arg in elements.split(|it| it.kind() == T![,]) {
+            match arg {
+                [] => {}
+                [NodeOrToken::Token(token)] if args.operands.is_empty() => {
+                    args.templates.extend(ast::String::cast(token.clone()))
+                }
+                _ => args.operands.extend(parse_operand(arg)),
+            }
+        }
+        Some(args)
+    }
+
+    /// Parses the arguments of the built-in `asm!` invocation `token` is an argument of.
+    pub fn for_token(sema: &Semantics<'_, RootDatabase>, token: &SyntaxToken) -> Option<AsmArgs> {
+        let tt = token.parent().and_then(ast::TokenTree::cast)?;
+        let macro_call = tt.syntax().parent().and_then(ast::MacroCall::cast)?;
+        let args = AsmArgs::parse(&macro_call)?;
+        // Ignore non-built-in macros to account for shadowing
+        if let Some(it) = sema.resolve_macro_call(&macro_call) {
+            if !matches!(it.kind(sema.db), hir::MacroKind::BuiltIn) {
+                return None;
+            }
+        }
+        Some(args)
+    }
+
+    /// Returns all the placeholders of the template strings, in order.
+    pub fn placeholders(&self) -> Vec<AsmPlaceholder> {
+        let mut acc = Vec::new();
+        let mut next_index = 0;
+        for template in &self.templates {
+            lex_placeholders(template, &mut next_index, &mut acc);
+        }
+        acc
+    }
+
+    /// Returns the placeholder at `offset`, if any.
+    pub fn placeholder_at(&self, offset: TextSize) -> Option<AsmPlaceholder> {
+        self.placeholders().into_iter().find(|it| it.range.contains_inclusive(offset))
+    }
+
+    /// Returns the operand `operand` refers to. Positional operands come before the named ones,
+    /// and operands with explicit registers can't be referred to.
+    pub fn resolve(&self, operand: &AsmOperandRef) -> Option<&AsmOperand> {
+        match operand {
+            AsmOperandRef::Index(idx) => self
+                .operands
+                .iter()
+                .filter(|it| it.name.is_none() && !it.explicit_register)
+                .nth(*idx),
+            AsmOperandRef::Name(name) => self
+                .operands
+                .iter()
+                .find(|it| it.name.as_ref().map_or(false, |it| it.text() == *name)),
+        }
+    }
+
+    /// Returns the operand named by `token`, like `x` in `x = in(reg) 5`.
+    pub fn operand_named_by(&self, token: &SyntaxToken) -> Option<&AsmOperand> {
+        self.operands.iter().find(|it| it.name.as_ref() == Some(token))
+    }
+
+    /// Returns the placeholders referring to `operand`.
+    pub fn placeholders_of(&self, operand: &AsmOperand) -> Vec<AsmPlaceholder> {
+        let mut placeholders = self.placeholders();
+        placeholders
+            .retain(|it| self.resolve(&it.operand).map_or(false, |it| it.range == operand.range));
+        placeholders
+    }
+}
+
+fn parse_operand(arg: &[SyntaxElement]) -> Option<AsmOperand> {
+    let range = arg.first()?.text_range().cover(arg.last()?.text_range());
+    let (name, rest) = match arg {
+        [NodeOrToken::Token(name), eq, rest @ ..]
+            if name.kind() == SyntaxKind::IDENT && eq.kind() == T![=] =>
+        {
+            (Some(name.clone()), rest)
+        }
+        _ => (None, arg),
+    };
+    let (kind, explicit_register, exprs) = match rest {
+        [NodeOrToken::Token(kw), NodeOrToken::Node(reg), exprs @ ..]
+            if matches!(kw.text(), "in" | "out" | "lateout" | "inout" | "inlateout") =>
+        {
+            let explicit_register =
+                reg.descendants_with_tokens().any(|it| it.kind() == SyntaxKind::STRING);
+            (format!("{}{}", kw.text(), reg.text()), explicit_register, exprs)
+        }
+        [NodeOrToken::Token(kw), exprs @ ..] if matches!(kw.text(), "const" | "sym") => {
+            (kw.text().to_string(), false, exprs)
+        }
+        // `options(..)` and `clobber_abi(..)`
+        _ => return None,
+    };
+    let exprs = exprs
+        .split(|it| it.kind() == T![=>])
+        .filter(|expr| !matches!(expr, [it] if it.kind() == T![_]))
+        .filter_map(|expr| Some(expr.first()?.text_range().cover(expr.last()?.text_range())))
+        .collect();
+    Some(AsmOperand { name, kind, explicit_register, exprs, range })
+}
+
+fn lex_placeholders(string: &ast::String, next_index: &mut usize, acc: &mut Vec<AsmPlaceholder>) {
+    let offset = string.syntax().text_range().start();
+    let mut chars = Vec::new();
+    string.escaped_char_ranges(&mut |range, res| {
+        if let Ok(c) = res {
+            chars.push((range + offset, c));
+        }
+    });
+
+    let mut chars = chars.into_iter().peekable();
+    while let Some((open, c)) = chars.next() {
+        match c {
+            '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                chars.next();
+            }
+            '{' => {
+                let mut operand = String::new();
+                let mut operand_range = TextRange::empty(open.end());
+                let mut in_modifier = false;
+                let close = loop {
+                    match chars.next() {
+                        Some((range, '}')) => break range,
+                        Some((_, ':')) => in_modifier = true,
+                        Some((range, c)) if !in_modifier => {
+                            operand.push(c);
+                            operand_range = operand_range.cover(range);
+                        }
+                        Some(_) => {}
+                        None => return,
+                    }
+                };
+                let operand = if operand.is_empty() {
+                    *next_index += 1;
+                    AsmOperandRef::Index(*next_index - 1)
+                } else if let Ok(idx) = operand.parse() {
+                    AsmOperandRef::Index(idx)
+                } else {
+                    AsmOperandRef::Name(operand.into())
+                };
+                acc.push(AsmPlaceholder { range: open.cover(close), operand_range, operand });
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::SourceFile;
+
+    use super::*;
+
+    fn parse(ra_fixture: &str) -> AsmArgs {
+        let file = SourceFile::parse(ra_fixture).tree();
+        let macro_call = file.syntax().descendants().find_map(ast::MacroCall::cast).unwrap();
+        AsmArgs::parse(&macro_call).unwrap()
+    }
+
+    fn text(ra_fixture: &str, range: TextRange) -> &str {
+        &ra_fixture[range]
+    }
+
+    #[test]
+    fn parse_operands() {
+        let fixture = r#"
+fn f() {
+    asm!(
+        "mov {0}, {x}",
+        "add {tmp:e}, {}",
+        in(reg) a,
+        x = inout(reg) b => c,
+        tmp = out(reg) _,
+        in("eax") d,
+        const 5,
+        sym g,
+        options(nostack),
+    );
+}
+"#;
+        let args = parse(fixture);
+        assert_eq!(args.templates.len(), 2);
+        let operands = args
+            .operands
+            .iter()
+            .map(|it| {
+                let exprs = it.exprs.iter().map(|&range| text(fixture, range)).collect::<Vec<_>>();
+                (it.name.as_ref().map(|it| it.text().to_string()), it.kind.as_str(), exprs)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operands,
+            [
+                (None, "in(reg)", vec!["a"]),
+                (Some("x".to_string()), "inout(reg)", vec!["b", "c"]),
+                (Some("tmp".to_string()), "out(reg)", vec![]),
+                (None, "in(\"eax\")", vec!["d"]),
+                (None, "const", vec!["5"]),
+                (None, "sym", vec!["g"]),
+            ]
+        );
+        assert!(args.operands[3].explicit_register);
+    }
+
+    #[test]
+    fn resolve_placeholders() {
+        let fixture = r#"
+fn f() {
+    asm!("{{}} {} {x:e} {1} {}", x = in(reg) a, in("eax") b, in(reg) c, in(reg) d);
+}
+"#;
+        let args = parse(fixture);
+        let placeholders = args
+            .placeholders()
+            .into_iter()
+            .map(|it| {
+                let expr = args.resolve(&it.operand).map(|op| text(fixture, op.exprs[0]));
+                (text(fixture, it.range), text(fixture, it.operand_range), expr)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            placeholders,
+            [
+                ("{}", "", Some("c")),
+                ("{x:e}", "x", Some("a")),
+                ("{1}", "1", Some("d")),
+                ("{}", "", Some("d")),
+            ]
+        );
+    }
+}
@@ -332,6 +332,7 @@ pub fn for_each_tail_expr(expr: &ast::Expr, cb: &mut dyn FnMut(&ast::Expr)) {
         | ast::Expr::TupleExpr(_)
         | ast::Expr::LetExpr(_)
         | ast::Expr::UnderscoreExpr(_)
+        | ast::Expr::OffsetOfExpr(_)
         | ast::Expr::YieldExpr(_)
         | ast::Expr::YeetExpr(_) => cb(expr),
     }
@@ -0,0 +1,99 @@
+use hir::HirDisplay;
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: conflicting-impls
+//
+// This diagnostic is triggered if two impls implement the same trait for the same types.
+pub(crate) fn conflicting_impls(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::ConflictingImpls,
+) -> Diagnostic {
+    let db = ctx.sema.db;
+    Diagnostic::new(
+        "conflicting-impls",
+        format!(
+            "conflicting implementations of trait `{}` for type `{}`",
+            d.trait_.name(db),
+            d.self_ty.display(db)
+        ),
+        ctx.sema.diagnostics_display_range(d.impl_.clone().map(|it| it.into())).range,
+    )
+    .severity(Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn same_type() {
+        check_diagnostics(
+            r#"
+trait Trait {}
+struct S;
+  impl Trait for S {}
+//^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Trait` for type `S`
+  impl Trait for S {}
+//^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Trait` for type `S`
+"#,
+        );
+    }
+
+    #[test]
+    fn blanket_impl() {
+        check_diagnostics(
+            r#"
+trait Trait {}
+struct S<T>(T);
+  impl<T> Trait for S<T> {}
+//^^^^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Trait` for type `S<T>`
+  impl Trait for S<u32> {}
+//^^^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Trait` for type `S<u32>`
+impl Trait for u32 {}
+"#,
+        );
+    }
+
+    #[test]
+    fn disjoint_where_clauses() {
+        check_diagnostics(
+            r#"
+trait Trait {}
+trait Bound {}
+struct A;
+struct B;
+impl Bound for A {}
+impl<T: Bound> Trait for T {}
+impl Trait for B {}
+"#,
+        );
+    }
+
+    #[test]
+    fn different_trait_args() {
+        check_diagnostics(
+            r#"
+trait Trait<T> {}
+struct S;
+impl Trait<u8> for S {}
+impl Trait<u16> for S {}
+"#,
+        );
+    }
+
+    #[test]
+    fn impl_in_dependency() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:foo
+pub trait Trait {}
+impl<T> Trait for T {}
+//- /main.rs crate:main deps:foo
+struct S;
+  impl foo::Trait for S {}
+//^^^^^^^^^^^^^^^^^^^^^^^^ error: conflicting implementations of trait `Trait` for type `S`
+"#,
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use hir::db::ExpandDatabase;
+use ide_db::{assists::Assist, source_change::SourceChange};
+use syntax::{ast, AstNode, SyntaxKind, TextRange};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: dead-store
+//
+// This diagnostic is triggered when the value assigned to a variable is always overwritten before
+// it is read, for example by an assignment in a loop or in all the arms of a `match`.
+pub(crate) fn dead_store(ctx: &DiagnosticsContext<'_>, d: &hir::DeadStore) -> Diagnostic {
+    Diagnostic::new(
+        "dead-store",
+        format!("value assigned to `{}` is never read", d.local.name(ctx.sema.db)),
+        ctx.sema.diagnostics_display_range(d.value.clone().map(|it| it.into())).range,
+    )
+    .severity(Severity::WeakWarning)
+    .experimental() // Not supporting `#[allow(unused_assignments)]` leads to false positive.
+    .with_fixes(fixes(ctx, d))
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::DeadStore) -> Option<Vec<Assist>> {
+    if d.value.file_id.is_macro() {
+        return None;
+    }
+    let root = ctx.sema.db.parse_or_expand(d.value.file_id);
+    let value = d.value.value.to_node(&root);
+    let file_id = d.value.file_id.original_file(ctx.sema.db);
+
+    let parent = value.syntax().parent()?;
+    let (id, label, range) = if let Some(let_stmt) = ast::LetStmt::cast(parent.clone()) {
+        let before_eq = match let_stmt.ty() {
+            Some(ty) => ty.syntax().text_range().end(),
+            None => let_stmt.pat()?.syntax().text_range().end(),
+        };
+        let range = TextRange::new(before_eq, value.syntax().text_range().end());
+        ("remove_initializer", "Remove the initializer", range)
+    } else {
+        let stmt = ast::ExprStmt::cast(ast::BinExpr::cast(parent)?.syntax().parent()?)?;
+        let stmt = stmt.syntax();
+        let start = match stmt.prev_sibling_or_token() {
+            Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().start(),
+            _ => stmt.text_range().start(),
+        };
+        let range = TextRange::new(start, stmt.text_range().end());
+        ("remove_assignment", "Remove the assignment", range)
+    };
+    let edit = TextEdit::delete(range);
+    Some(vec![fix(
+        id,
+        label,
+        SourceChange::from_text_edit(file_id, edit),
+        value.syntax().text_range(),
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn overwritten_in_all_branches() {
+        check_diagnostics(
+            r#"
+fn f(_: i32) {}
+fn main(b: u8) {
+    let mut x = 0;
+              //^ 💡 weak: value assigned to `x` is never read
+    match b {
+        0 => x = 1,
+        _ => x = 2,
+    }
+    f(x);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn overwritten_in_loop() {
+        check_diagnostics(
+            r#"
+fn f(_: i32) -> bool { true }
+fn main() {
+    let mut x = 0;
+              //^ 💡 weak: value assigned to `x` is never read
+    let mut y = 0;
+    loop {
+        x = 2 + 3;
+        y = y + x;
+        if f(y) {
+            break;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_false_positives() {
+        check_diagnostics(
+            r#"
+fn g() -> i32 { 0 }
+fn f(_: i32) {}
+fn main(b: bool) {
+    let mut read_sometimes = 0;
+    if b {
+        read_sometimes = 1;
+    }
+    f(read_sometimes);
+
+    let mut borrowed = 0;
+    let r = &borrowed;
+    borrowed = 1;
+
+    let mut side_effect = g();
+    side_effect = 2;
+    f(side_effect);
+
+    let mut _ignored = 0;
+    _ignored = 1;
+    f(_ignored);
+
+    let mut never_read = 0;
+    never_read = 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_initializer() {
+        check_fix(
+            r#"
+fn f(_: i32) {}
+fn main(b: bool) {
+    let mut x: i32 = $00;
+    if b {
+        x = 1;
+    } else {
+        x = 2;
+    }
+    f(x);
+}
+"#,
+            r#"
+fn f(_: i32) {}
+fn main(b: bool) {
+    let mut x: i32;
+    if b {
+        x = 1;
+    } else {
+        x = 2;
+    }
+    f(x);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_assignment() {
+        check_fix(
+            r#"
+fn f(_: i32) {}
+fn main() {
+    let mut x = 1;
+    f(x);
+    x = $02;
+    x = 3;
+    f(x);
+}
+"#,
+            r#"
+fn f(_: i32) {}
+fn main() {
+    let mut x = 1;
+    f(x);
+    x = 3;
+    f(x);
+}
+"#,
+        );
+    }
+}
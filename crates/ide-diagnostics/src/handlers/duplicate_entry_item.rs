@@ -0,0 +1,71 @@
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: duplicate-entry-item
+//
+// This diagnostic is triggered if a `#[panic_handler]` function, a `#[global_allocator]` static or
+// an `#[alloc_error_handler]` function is also defined by the crate itself or one of its
+// dependencies.
+pub(crate) fn duplicate_entry_item(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::DuplicateEntryItem,
+) -> Diagnostic {
+    let kind = if d.attr == "global_allocator" { "static" } else { "function" };
+    Diagnostic::new(
+        "duplicate-entry-item",
+        format!("duplicate `#[{}]` {kind}", d.attr),
+        ctx.sema.diagnostics_display_range(d.name.clone().map(|it| it.into())).range,
+    )
+    .severity(Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn duplicate_in_dependency() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:platform
+#![no_std]
+#[panic_handler]
+fn panic() -> ! { loop {} }
+//- /main.rs crate:main deps:platform
+#![no_std]
+#[panic_handler]
+fn my_panic() -> ! { loop {} }
+ //^^^^^^^^ error: duplicate `#[panic_handler]` function
+"#,
+        );
+    }
+
+    #[test]
+    fn duplicate_global_allocator() {
+        check_diagnostics(
+            r#"
+struct Alloc;
+#[global_allocator]
+static A: Alloc = Alloc;
+     //^ error: duplicate `#[global_allocator]` static
+#[global_allocator]
+static B: Alloc = Alloc;
+     //^ error: duplicate `#[global_allocator]` static
+"#,
+        );
+    }
+
+    #[test]
+    fn single_items() {
+        check_diagnostics(
+            r#"
+struct Alloc;
+#[global_allocator]
+static A: Alloc = Alloc;
+#[alloc_error_handler]
+fn oom() -> ! { loop {} }
+#[panic_handler]
+fn panic() -> ! { loop {} }
+"#,
+        );
+    }
+}
@@ -1,4 +1,11 @@
-use crate::{Diagnostic, DiagnosticsContext};
+use ide_db::source_change::SourceChange;
+use syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode, SyntaxKind, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Assist, Diagnostic, DiagnosticsContext};
 
 // Diagnostic: missing-match-arm
 //
@@ -12,11 +19,58 @@ pub(crate) fn missing_match_arms(
         format!("missing match arm: {}", d.uncovered_patterns),
         ctx.sema.diagnostics_display_range(d.scrutinee_expr.clone().map(Into::into)).range,
     )
+    .with_fixes(fixes(ctx, d))
+}
+
+/// Inserts an arm for each uncovered pattern at the end of the match.
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::MissingMatchArms) -> Option<Vec<Assist>> {
+    if d.witnesses.is_empty() {
+        return None;
+    }
+    let file_id = d.scrutinee_expr.file_id.file_id()?;
+    let root = ctx.sema.db.parse_or_expand(d.scrutinee_expr.file_id);
+    let scrutinee = d.scrutinee_expr.value.to_node(&root);
+    let match_expr = scrutinee.syntax().parent().and_then(ast::MatchExpr::cast)?;
+    let arm_list = match_expr.match_arm_list()?;
+    let r_curly = arm_list.r_curly_token()?;
+
+    // The arms replace the whitespace before the closing brace.
+    let range = match r_curly.prev_token() {
+        Some(it) if it.kind() == SyntaxKind::WHITESPACE => it.text_range(),
+        _ => TextRange::empty(r_curly.text_range().start()),
+    };
+    let mut builder = TextEdit::builder();
+    let mut arms = String::new();
+    // The last arm needs a comma, unless its expression is a block.
+    if let Some(last_arm) = arm_list.arms().last() {
+        let needs_comma = last_arm.comma_token().is_none()
+            && !matches!(last_arm.expr(), Some(ast::Expr::BlockExpr(_)));
+        let end = last_arm.syntax().text_range().end();
+        match needs_comma {
+            true if end == range.start() => arms.push(','),
+            true => builder.insert(end, ",".to_owned()),
+            false => (),
+        }
+    }
+    let indent = IndentLevel::from_node(match_expr.syntax());
+    for witness in &d.witnesses {
+        arms += &format!("\n{}{witness} => todo!(),", indent + 1);
+    }
+    arms += &format!("\n{indent}");
+    builder.replace(range, arms);
+
+    let source_change = SourceChange::from_text_edit(file_id, builder.finish());
+    Some(vec![fix(
+        "add_uncovered_match_arms",
+        "Add arms for the uncovered patterns",
+        source_change,
+        match_expr.syntax().text_range(),
+    )])
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::check_diagnostics;
+    use crate::tests::{check_diagnostics, check_fix};
 
     fn check_diagnostics_no_bails(ra_fixture: &str) {
         cov_mark::check_count!(validate_match_bailed_out, 0);
@@ -164,6 +218,76 @@ fn main() {
         );
     }
 
+    #[test]
+    fn fix_adds_uncovered_arms() {
+        check_fix(
+            r#"
+enum Either { A, B(bool) }
+
+fn main() {
+    match Either::A$0 {
+        Either::A => ()
+    }
+}
+"#,
+            r#"
+enum Either { A, B(bool) }
+
+fn main() {
+    match Either::A {
+        Either::A => (),
+        Either::B(_) => todo!(),
+    }
+}
+"#,
+        );
+        check_fix(
+            r#"
+enum Either { A, B(bool) }
+
+fn main() {
+    match Either::A$0 { }
+}
+"#,
+            r#"
+enum Either { A, B(bool) }
+
+fn main() {
+    match Either::A {
+        Either::A => todo!(),
+        Either::B(_) => todo!(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_adds_nested_witnesses() {
+        check_fix(
+            r#"
+//- minicore: option, result
+fn main() {
+    let x: Option<Result<u8, ()>> = None;
+    match x$0 {
+        Some(Ok(_)) => (),
+        None => {}
+    }
+}
+"#,
+            r#"
+fn main() {
+    let x: Option<Result<u8, ()>> = None;
+    match x {
+        Some(Ok(_)) => (),
+        None => {}
+        Some(Err(_)) => todo!(),
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn enum_containing_bool() {
         check_diagnostics_no_bails(
@@ -945,6 +1069,118 @@ fn f() {
         );
     }
 
+    #[test]
+    fn slices() {
+        check_diagnostics_no_bails(
+            r#"
+fn main(s: &[bool], a: [bool; 2]) {
+    match s {
+        [] => {}
+        [_, _rest @ ..] => {}
+    }
+    match s {
+        [] => {}
+        [.., true] => {}
+        [.., false] => {}
+    }
+    match s {
+        //^ error: missing match arm: `&[_, _, ..]` not covered
+        [] => {}
+        [_] => {}
+    }
+    match a {
+        [true, _] => {}
+        [false, _] => {}
+    }
+    match a {
+        //^ error: missing match arm: `[false, false]` not covered
+        [true, _] => {}
+        [false, true] => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn integers() {
+        check_diagnostics_no_bails(
+            r#"
+const LOW: i32 = -100;
+const HIGH: i32 = 1000;
+fn main(x: u8, y: i32, z: usize, w: isize) {
+    match 5 {
+        //^ error: missing match arm: `i32::MIN..=9` and `20..=i32::MAX` not covered
+        10 => (),
+        11..20 => (),
+    }
+    match x {
+        //^ error: missing match arm: `201..=249` not covered
+        0..10 => (),
+        10..=200 => (),
+        250.. => (),
+    }
+    match x {
+        ..=100 => (),
+        101.. => (),
+    }
+    match y {
+        LOW..=-1 => (),
+        0 => (),
+        1..=HIGH => (),
+        ..LOW | HIGH.. => (),
+    }
+    match y {
+        //^ error: missing match arm: `i32::MIN..=-101`, `0` and `1000..=i32::MAX` not covered
+        LOW..0 => (),
+        1..HIGH => (),
+    }
+    match z {
+        //^ error: missing match arm: `6..` not covered
+        0 => (),
+        1..=5 => (),
+    }
+    match z {
+        0 => (),
+        1.. => (),
+    }
+    match w {
+        //^ error: missing match arm: `..=0` not covered
+        1.. => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn chars() {
+        check_diagnostics_no_bails(
+            r#"
+fn main(c: char) {
+    match c {
+        '\0'..='\u{d7ff}' => (),
+        '\u{e000}'.. => (),
+    }
+    match c {
+        //^ error: missing match arm: `'{'..='\u{d7ff}'` not covered
+        ..='z' => (),
+        '\u{e000}'..='\u{10ffff}' => (),
+    }
+    match c {
+        //^ error: missing match arm: `'\0'..='`'`, `'{'..='\u{d7ff}'` and `'\u{e000}'..='\u{10ffff}'` not covered
+        'a'..='z' => (),
+    }
+    match (c, true) {
+        //^^^^^^^^^ error: missing match arm: `('a', false)` not covered
+        ('a', true) => (),
+        (_, true) | ('b'.., false) | (..'a', false) => (),
+    }
+}
+"#,
+        );
+    }
+
     mod rust_unstable {
         use super::*;
 
@@ -1001,23 +1237,6 @@ fn test(x: Option<lib::PrivatelyUninhabited>) {
         //!   2. It ensures the code doesn't panic when handling these cases.
         use super::*;
 
-        #[test]
-        fn integers() {
-            cov_mark::check_count!(validate_match_bailed_out, 1);
-
-            // We don't currently check integer exhaustiveness.
-            check_diagnostics(
-                r#"
-fn main() {
-    match 5 {
-        10 => (),
-        11..20 => (),
-    }
-}
-"#,
-            );
-        }
-
         #[test]
         fn reference_patterns_at_top_level() {
             cov_mark::check_count!(validate_match_bailed_out, 1);
@@ -0,0 +1,67 @@
+use syntax::{ast::HasAttrs, AstNode};
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: missing-panic-handler
+//
+// This diagnostic is triggered if a `#![no_std]` binary, marked with `#![no_main]`, doesn't have
+// a `#[panic_handler]` function in its own code or its dependencies.
+pub(crate) fn missing_panic_handler(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::MissingPanicHandler,
+) -> Diagnostic {
+    let source_file = ctx.sema.parse(d.file);
+    let range = source_file
+        .attrs()
+        .find(|attr| attr.simple_name().as_deref() == Some("no_main"))
+        .map_or_else(|| source_file.syntax().text_range(), |attr| attr.syntax().text_range());
+    Diagnostic::new(
+        "missing-panic-handler",
+        "`#[panic_handler]` function required, but not found",
+        range,
+    )
+    .severity(Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn missing() {
+        check_diagnostics(
+            r#"
+#![no_std]
+  #![no_main]
+//^^^^^^^^^^^ error: `#[panic_handler]` function required, but not found
+"#,
+        );
+    }
+
+    #[test]
+    fn defined_by_dependency() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:platform
+#![no_std]
+#[panic_handler]
+fn panic() -> ! { loop {} }
+//- /main.rs crate:main deps:platform
+#![no_std]
+#![no_main]
+"#,
+        );
+    }
+
+    #[test]
+    fn not_needed() {
+        check_diagnostics(
+            r#"
+//- /lib.rs crate:lib
+#![no_std]
+//- /main.rs crate:main deps:lib
+#![no_main]
+"#,
+        );
+    }
+}
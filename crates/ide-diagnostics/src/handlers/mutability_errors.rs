@@ -112,6 +112,7 @@ fn main() {
 fn f(_: i32) {}
 fn main() {
     let mut x = 2;
+              //^ 💡 weak: value assigned to `x` is never read
     x = 5;
     f(x);
 }
@@ -119,6 +120,30 @@ fn main() {
         );
     }
 
+    #[test]
+    fn let_chains() {
+        check_diagnostics(
+            r#"
+//- minicore: option
+fn f(_: i32) {}
+fn main() {
+    let x = Some(2);
+    let mut y = 0;
+    if let Some(mut z) = x && z > 1 {
+        z = z + y;
+        y = z;
+    }
+    f(y);
+    let w = 3;
+    while let Some(v) = x && v < w {
+        w = v;
+      //^^^^^ 💡 error: cannot mutate immutable variable `w`
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn multiple_errors_for_single_variable() {
         check_diagnostics(
@@ -463,6 +488,7 @@ fn main(b: bool) {
     let x;
     if b {
         x = 1;
+          //^ 💡 weak: value assigned to `x` is never read
     }
     x = 3;
   //^^^^^ 💡 error: cannot mutate immutable variable `x`
@@ -793,6 +819,7 @@ fn fn_once(mut x: impl FnOnce(u8) -> u8) -> u8 {
                               //^ 💡 error: cannot mutate immutable variable `x`
             let closure3 = || {
                 let x = 2;
+                      //^ 💡 weak: value assigned to `x` is never read
                 x = 5;
               //^^^^^ 💡 error: cannot mutate immutable variable `x`
                 x
@@ -813,6 +840,7 @@ fn fn_once(mut x: impl FnOnce(u8) -> u8) -> u8 {
             y = 7;
             let closure = || {
                 let mut z = 8;
+                          //^ 💡 weak: value assigned to `z` is never read
                 z = 3;
                 let mut k = z;
                   //^^^^^ 💡 weak: variable does not need to be mutable
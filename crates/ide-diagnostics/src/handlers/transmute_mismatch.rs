@@ -0,0 +1,93 @@
+use hir::HirDisplay;
+
+use crate::{Diagnostic, DiagnosticsContext, Severity};
+
+// Diagnostic: transmute-size-mismatch
+//
+// This diagnostic is triggered when `transmute` is called with types which don't have the same size
+// for the target, like rustc's E0512.
+pub(crate) fn transmute_size_mismatch(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::TransmuteSizeMismatch,
+) -> Diagnostic {
+    Diagnostic::new(
+        "transmute-size-mismatch",
+        format!(
+            "cannot transmute between types of different sizes: `{}` is {} bytes, `{}` is {} bytes",
+            d.from.display(ctx.sema.db),
+            d.from_size,
+            d.to.display(ctx.sema.db),
+            d.to_size,
+        ),
+        ctx.sema.diagnostics_display_range(d.call.clone().map(|it| it.into())).range,
+    )
+}
+
+// Diagnostic: unaligned-transmute
+//
+// This diagnostic is triggered when `transmute` turns a pointer into a pointer to a type needing a
+// greater alignment, like `&[u8; 4]` into `&u32`. Dereferencing the result is undefined behavior
+// unless the pointer happens to be aligned enough.
+pub(crate) fn unaligned_transmute(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnalignedTransmute,
+) -> Diagnostic {
+    Diagnostic::new(
+        "unaligned-transmute",
+        format!(
+            "transmuting `{}` into `{}` may create an unaligned pointer: the alignment of the \
+             pointee grows from {} to {} bytes",
+            d.from.display(ctx.sema.db),
+            d.to.display(ctx.sema.db),
+            d.from_align,
+            d.to_align,
+        ),
+        ctx.sema.diagnostics_display_range(d.call.clone().map(|it| it.into())).range,
+    )
+    .severity(Severity::WeakWarning)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn size_mismatch() {
+        check_diagnostics(
+            r#"
+extern "rust-intrinsic" {
+    fn transmute<T, U>(e: T) -> U;
+}
+struct Pair(u16, u16);
+fn f<T>(t: T) {
+    let _: u32 = unsafe { transmute(Pair(1, 2)) };
+    let _: [u8; 4] = unsafe { transmute(0u32) };
+    let _ = unsafe { transmute::<u32, u64>(0) };
+                   //^^^^^^^^^^^^^^^^^^^^^^^^ error: cannot transmute between types of different sizes: `u32` is 4 bytes, `u64` is 8 bytes
+    let _: (u8, u32) = unsafe { transmute(0u32) };
+                              //^^^^^^^^^^^^^^^ error: cannot transmute between types of different sizes: `u32` is 4 bytes, `(u8, u32)` is 8 bytes
+    let _: T = unsafe { transmute(t) };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unaligned_pointers() {
+        check_diagnostics(
+            r#"
+extern "rust-intrinsic" {
+    fn transmute<T, U>(e: T) -> U;
+}
+fn f(bytes: &[u8; 4], word: &u32) {
+    let _: &u32 = unsafe { transmute(bytes) };
+                         //^^^^^^^^^^^^^^^^ weak: transmuting `&[u8; 4]` into `&u32` may create an unaligned pointer: the alignment of the pointee grows from 1 to 4 bytes
+    let _: *const u16 = unsafe { transmute(bytes as *const [u8; 4]) };
+                               //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: transmuting `*const [u8; 4]` into `*const u16` may create an unaligned pointer: the alignment of the pointee grows from 1 to 2 bytes
+    let _: &[u8; 4] = unsafe { transmute(word) };
+    let _: &i32 = unsafe { transmute(word) };
+}
+"#,
+        );
+    }
+}
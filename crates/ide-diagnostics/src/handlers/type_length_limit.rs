@@ -0,0 +1,94 @@
+use hir::Semantics;
+use ide_db::RootDatabase;
+use syntax::{ast, AstNode, SyntaxNode};
+
+use crate::{Diagnostic, DiagnosticsConfig, Severity};
+
+// Diagnostic: type-length-limit
+//
+// Diagnostic for bindings whose type is made of more types than the configured limit, like deeply
+// nested iterator adapters or futures. Such types slow down the compiler as well as the IDE.
+pub(crate) fn type_length_limit(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    let limit = config.type_length_limit?;
+    let pat = ast::IdentPat::cast(node.clone())?;
+    let name = pat.name()?;
+    let ty = sema.type_of_pat(&pat.clone().into())?.original;
+    if ty.type_length(limit) <= limit {
+        return None;
+    }
+
+    acc.push(
+        Diagnostic::new(
+            "type-length-limit",
+            format!(
+                "the type of `{name}` is longer than the type length limit of {limit}, \
+                 consider boxing it or erasing it with `dyn Trait`"
+            ),
+            name.syntax().text_range(),
+        )
+        .severity(Severity::WeakWarning),
+    );
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config(limit: usize) -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.type_length_limit = Some(limit);
+        config
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics(
+            r#"
+struct W<T>(T);
+fn f() {
+    let w = W(W(W(W(W(W(W(W(0u8))))))));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn long_binding_types() {
+        check_diagnostics_with_config(
+            config(8),
+            r#"
+struct W<T>(T);
+fn f(short: W<u8>) {
+    let long = W(W(W(W(W(W(W(W(0u8))))))));
+      //^^^^ weak: the type of `long` is longer than the type length limit of 8, consider boxing it or erasing it with `dyn Trait`
+    let W(at_the_limit) = W(W(W(W(W(W(W(W(0u8))))))));
+    let shorter = W(W(W(0u8)));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn counts_generic_arguments_and_consts() {
+        check_diagnostics_with_config(
+            config(5),
+            r#"
+struct P<A, B>(A, B);
+fn f() {
+    let pair = P(P(0u8, 0u16), P(0u32, [0u8; 2]));
+      //^^^^ weak: the type of `pair` is longer than the type length limit of 5, consider boxing it or erasing it with `dyn Trait`
+}
+"#,
+        );
+    }
+}
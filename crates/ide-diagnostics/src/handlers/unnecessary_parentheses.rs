@@ -0,0 +1,126 @@
+use ide_db::{base_db::FileId, source_change::SourceChange};
+use syntax::{ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, T};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, Severity};
+
+// Diagnostic: unnecessary-parentheses
+//
+// Diagnostic for parentheses which don't change how an expression is parsed. This is disabled by
+// default, and reports more cases than rustc's `unused_parens` lint.
+pub(crate) fn unnecessary_parentheses(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let parens = ast::ParenExpr::cast(node.clone())?;
+    let expr = parens.expr()?;
+    if expr.needs_parens_in(node.parent()?) {
+        return None;
+    }
+
+    let (l_paren, r_paren) = (parens.l_paren_token()?, parens.r_paren_token()?);
+    let first = expr.syntax().first_token()?;
+    let last = expr.syntax().last_token()?;
+    let mut edit = TextEdit::builder();
+    match l_paren.prev_token() {
+        Some(prev) if is_word(&prev) && is_word(&first) => {
+            edit.replace(l_paren.text_range(), " ".to_string())
+        }
+        _ => edit.delete(l_paren.text_range()),
+    }
+    match r_paren.next_token() {
+        Some(next) if is_word(&next) && is_word(&last) => {
+            edit.replace(r_paren.text_range(), " ".to_string())
+        }
+        _ => edit.delete(r_paren.text_range()),
+    }
+
+    let range = parens.syntax().text_range();
+    acc.push(
+        Diagnostic::new("unnecessary-parentheses", "Unnecessary parentheses", range)
+            .severity(Severity::WeakWarning)
+            .with_fixes(Some(vec![fix(
+                "remove_parentheses",
+                "Remove unnecessary parentheses",
+                SourceChange::from_text_edit(file_id, edit.finish()),
+                range,
+            )])),
+    );
+
+    Some(())
+}
+
+/// Returns `true` if `token` would be glued to an adjacent word, like `return` to `x` in
+/// `return(x)`.
+fn is_word(token: &SyntaxToken) -> bool {
+    let kind = token.kind();
+    matches!(kind, SyntaxKind::IDENT | SyntaxKind::LIFETIME_IDENT | T![_])
+        || kind.is_keyword()
+        || kind.is_literal()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config, check_fix_with_config},
+        DiagnosticsConfig,
+    };
+
+    fn config() -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.unnecessary_parentheses = true;
+        config
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics(
+            r#"
+fn f() -> i32 { (1) + 2 }
+"#,
+        );
+    }
+
+    #[test]
+    fn unnecessary_parentheses() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+struct S { f: fn() -> i32 }
+fn f(s: S, x: i32, t: (i32,)) -> bool {
+    let _ = (1) + (2 + 3) * (x);
+          //^^^ 💡 weak: Unnecessary parentheses
+                          //^^^ 💡 weak: Unnecessary parentheses
+    let _ = (s.f)() + (t.0);
+                    //^^^^^ 💡 weak: Unnecessary parentheses
+    if (x as u8) < 2 { return(true) }
+                           //^^^^^^ 💡 weak: Unnecessary parentheses
+    x == 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_parentheses() {
+        check_fix_with_config(
+            config(),
+            r#"
+fn f(x: i32) -> i32 { 2 * $0(x) }
+"#,
+            r#"
+fn f(x: i32) -> i32 { 2 * x }
+"#,
+        );
+        check_fix_with_config(
+            config(),
+            r#"
+fn f(x: bool) -> bool { return$0(x) }
+"#,
+            r#"
+fn f(x: bool) -> bool { return x }
+"#,
+        );
+    }
+}
@@ -27,6 +27,9 @@
 
 mod handlers {
     pub(crate) mod break_outside_of_loop;
+    pub(crate) mod conflicting_impls;
+    pub(crate) mod dead_store;
+    pub(crate) mod duplicate_entry_item;
     pub(crate) mod expected_function;
     pub(crate) mod inactive_code;
     pub(crate) mod incoherent_impl;
@@ -37,12 +40,14 @@ mod handlers {
     pub(crate) mod mismatched_arg_count;
     pub(crate) mod missing_fields;
     pub(crate) mod missing_match_arms;
+    pub(crate) mod missing_panic_handler;
     pub(crate) mod missing_unsafe;
     pub(crate) mod mutability_errors;
     pub(crate) mod no_such_field;
     pub(crate) mod private_assoc_item;
     pub(crate) mod private_field;
     pub(crate) mod replace_filter_map_next_with_find_map;
+    pub(crate) mod transmute_mismatch;
     pub(crate) mod type_mismatch;
     pub(crate) mod unimplemented_builtin_macro;
     pub(crate) mod unresolved_extern_crate;
@@ -58,8 +63,10 @@ mod handlers {
     // The handlers below are unusual, the implement the diagnostics as well.
     pub(crate) mod field_shorthand;
     pub(crate) mod useless_braces;
+    pub(crate) mod unnecessary_parentheses;
     pub(crate) mod unlinked_file;
     pub(crate) mod json_is_not_rust;
+    pub(crate) mod type_length_limit;
 }
 
 #[cfg(test)]
@@ -161,6 +168,9 @@ pub struct DiagnosticsConfig {
     // FIXME: We may want to include a whole `AssistConfig` here
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
+    pub unnecessary_parentheses: bool,
+    /// Warn about bindings whose type is made of more types than this.
+    pub type_length_limit: Option<usize>,
 }
 
 impl DiagnosticsConfig {
@@ -182,6 +192,8 @@ impl DiagnosticsConfig {
                 skip_glob_imports: false,
             },
             prefer_no_std: false,
+            unnecessary_parentheses: false,
+            type_length_limit: None,
         }
     }
 }
@@ -238,6 +250,10 @@ pub fn diagnostics(
         handlers::useless_braces::useless_braces(&mut res, file_id, &node);
         handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
         handlers::json_is_not_rust::json_in_items(&sema, &mut res, file_id, &node, config);
+        if config.unnecessary_parentheses {
+            handlers::unnecessary_parentheses::unnecessary_parentheses(&mut res, file_id, &node);
+        }
+        handlers::type_length_limit::type_length_limit(&sema, &mut res, &node, config);
     }
 
     let module = sema.to_module_def(file_id);
@@ -282,13 +298,16 @@ pub fn diagnostics(
             AnyDiagnostic::MismatchedArgCount(d) => handlers::mismatched_arg_count::mismatched_arg_count(&ctx, &d),
             AnyDiagnostic::MissingFields(d) => handlers::missing_fields::missing_fields(&ctx, &d),
             AnyDiagnostic::MissingMatchArms(d) => handlers::missing_match_arms::missing_match_arms(&ctx, &d),
+            AnyDiagnostic::MissingPanicHandler(d) => handlers::missing_panic_handler::missing_panic_handler(&ctx, &d),
             AnyDiagnostic::MissingUnsafe(d) => handlers::missing_unsafe::missing_unsafe(&ctx, &d),
             AnyDiagnostic::NeedMut(d) => handlers::mutability_errors::need_mut(&ctx, &d),
             AnyDiagnostic::NoSuchField(d) => handlers::no_such_field::no_such_field(&ctx, &d),
             AnyDiagnostic::PrivateAssocItem(d) => handlers::private_assoc_item::private_assoc_item(&ctx, &d),
             AnyDiagnostic::PrivateField(d) => handlers::private_field::private_field(&ctx, &d),
             AnyDiagnostic::ReplaceFilterMapNextWithFindMap(d) => handlers::replace_filter_map_next_with_find_map::replace_filter_map_next_with_find_map(&ctx, &d),
+            AnyDiagnostic::TransmuteSizeMismatch(d) => handlers::transmute_mismatch::transmute_size_mismatch(&ctx, &d),
             AnyDiagnostic::TypeMismatch(d) => handlers::type_mismatch::type_mismatch(&ctx, &d),
+            AnyDiagnostic::UnalignedTransmute(d) => handlers::transmute_mismatch::unaligned_transmute(&ctx, &d),
             AnyDiagnostic::UndeclaredLabel(d) => handlers::undeclared_label::undeclared_label(&ctx, &d),
             AnyDiagnostic::UnimplementedBuiltinMacro(d) => handlers::unimplemented_builtin_macro::unimplemented_builtin_macro(&ctx, &d),
             AnyDiagnostic::UnreachableLabel(d) => handlers::unreachable_label:: unreachable_label(&ctx, &d),
@@ -301,6 +320,9 @@ pub fn diagnostics(
             AnyDiagnostic::UnresolvedProcMacro(d) => handlers::unresolved_proc_macro::unresolved_proc_macro(&ctx, &d, config.proc_macros_enabled, config.proc_attr_macros_enabled),
             AnyDiagnostic::UnusedMut(d) => handlers::mutability_errors::unused_mut(&ctx, &d),
             AnyDiagnostic::BreakOutsideOfLoop(d) => handlers::break_outside_of_loop::break_outside_of_loop(&ctx, &d),
+            AnyDiagnostic::ConflictingImpls(d) => handlers::conflicting_impls::conflicting_impls(&ctx, &d),
+            AnyDiagnostic::DeadStore(d) => handlers::dead_store::dead_store(&ctx, &d),
+            AnyDiagnostic::DuplicateEntryItem(d) => handlers::duplicate_entry_item::duplicate_entry_item(&ctx, &d),
         };
         res.push(d)
     }
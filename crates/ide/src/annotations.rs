@@ -33,6 +33,7 @@ pub struct Annotation {
 pub enum AnnotationKind {
     Runnable(Runnable),
     HasImpls { pos: FilePosition, data: Option<Vec<NavigationTarget>> },
+    HasOverrides { pos: FilePosition, data: Option<Vec<NavigationTarget>> },
     HasReferences { pos: FilePosition, data: Option<Vec<FileRange>> },
 }
 
@@ -40,9 +41,13 @@ pub struct AnnotationConfig {
     pub binary_target: bool,
     pub annotate_runnables: bool,
     pub annotate_impls: bool,
+    pub annotate_method_impls: bool,
     pub annotate_references: bool,
     pub annotate_method_references: bool,
     pub annotate_enum_variant_references: bool,
+    /// The maximum number of reference annotations per file. Resolving each of them searches the
+    /// whole workspace.
+    pub references_budget: Option<usize>,
     pub location: AnnotationLocation,
 }
 
@@ -81,6 +86,22 @@ pub(crate) fn annotations(
     };
 
     visit_file_defs(&Semantics::new(db), file_id, &mut |def| {
+        if let Definition::Trait(trait_) = def {
+            if config.annotate_method_impls {
+                let methods = trait_.items(db).into_iter().filter_map(|item| match item {
+                    hir::AssocItem::Function(it) => it.source(db),
+                    _ => None,
+                });
+                for range in methods.filter_map(|node| name_range(db, node, file_id)) {
+                    let (annotation_range, target_pos) = mk_ranges(range);
+                    annotations.push(Annotation {
+                        range: annotation_range,
+                        kind: AnnotationKind::HasOverrides { pos: target_pos, data: None },
+                    });
+                }
+            }
+        }
+
         let range = match def {
             Definition::Const(konst) if config.annotate_references => {
                 konst.source(db).and_then(|node| name_range(db, node, file_id))
@@ -172,12 +193,24 @@ pub(crate) fn annotations(
         }));
     }
 
+    if let Some(mut budget) = config.references_budget {
+        annotations.retain(|annotation| match annotation.kind {
+            AnnotationKind::HasReferences { .. } if budget == 0 => false,
+            AnnotationKind::HasReferences { .. } => {
+                budget -= 1;
+                true
+            }
+            _ => true,
+        });
+    }
+
     annotations
 }
 
 pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation) -> Annotation {
     match annotation.kind {
-        AnnotationKind::HasImpls { pos, ref mut data } => {
+        AnnotationKind::HasImpls { pos, ref mut data }
+        | AnnotationKind::HasOverrides { pos, ref mut data } => {
             *data = goto_implementation(db, pos).map(|range| range.info);
         }
         AnnotationKind::HasReferences { pos, ref mut data } => {
@@ -216,9 +249,11 @@ mod tests {
         binary_target: true,
         annotate_runnables: true,
         annotate_impls: true,
+        annotate_method_impls: false,
         annotate_references: true,
         annotate_method_references: true,
         annotate_enum_variant_references: true,
+        references_budget: None,
         location: AnnotationLocation::AboveName,
     };
 
@@ -909,4 +944,120 @@ struct Foo;
             &AnnotationConfig { location: AnnotationLocation::AboveWholeItem, ..DEFAULT_CONFIG },
         );
     }
+
+    #[test]
+    fn test_trait_method_overrides() {
+        check_with_config(
+            r#"
+trait T {
+    fn f(&self);
+    fn g();
+}
+
+struct S;
+
+impl T for S {
+    fn f(&self) {}
+    fn g() {}
+}
+"#,
+            expect![[r#"
+                [
+                    Annotation {
+                        range: 18..19,
+                        kind: HasOverrides {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 18,
+                            },
+                            data: Some(
+                                [
+                                    NavigationTarget {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        full_range: 73..87,
+                                        focus_range: 76..77,
+                                        name: "f",
+                                        kind: Function,
+                                        description: "fn f(&self)",
+                                    },
+                                ],
+                            ),
+                        },
+                    },
+                    Annotation {
+                        range: 35..36,
+                        kind: HasOverrides {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 35,
+                            },
+                            data: Some(
+                                [
+                                    NavigationTarget {
+                                        file_id: FileId(
+                                            0,
+                                        ),
+                                        full_range: 92..101,
+                                        focus_range: 95..96,
+                                        name: "g",
+                                        kind: Function,
+                                        description: "fn g()",
+                                    },
+                                ],
+                            ),
+                        },
+                    },
+                ]
+            "#]],
+            &AnnotationConfig {
+                annotate_runnables: false,
+                annotate_impls: false,
+                annotate_method_impls: true,
+                annotate_references: false,
+                annotate_method_references: false,
+                annotate_enum_variant_references: false,
+                ..DEFAULT_CONFIG
+            },
+        );
+    }
+
+    #[test]
+    fn test_references_budget() {
+        check_with_config(
+            r#"
+struct A;
+struct B;
+"#,
+            expect![[r#"
+                [
+                    Annotation {
+                        range: 8..9,
+                        kind: HasReferences {
+                            pos: FilePosition {
+                                file_id: FileId(
+                                    0,
+                                ),
+                                offset: 8,
+                            },
+                            data: Some(
+                                [],
+                            ),
+                        },
+                    },
+                ]
+            "#]],
+            &AnnotationConfig {
+                annotate_runnables: false,
+                annotate_impls: false,
+                references_budget: Some(1),
+                ..DEFAULT_CONFIG
+            },
+        );
+    }
 }
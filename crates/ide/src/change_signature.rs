@@ -0,0 +1,323 @@
+use hir::Semantics;
+use ide_db::{
+    base_db::FilePosition,
+    change_signature::{ChangeSignatureError, SignatureParam},
+    defs::{Definition, NameClass, NameRefClass},
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxNode};
+
+// Feature: Change Signature
+//
+// Reorders, adds and removes the parameters of the function below the cursor, updating all of
+// its call sites. Parameters omitted from the new signature are removed, new parameters are
+// passed a given value at every call site, and references to the function which aren't called
+// directly are wrapped in a closure with the old signature.
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    params: &[SignatureParam],
+) -> Result<SourceChange, ChangeSignatureError> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let Some(func) = find_function(&sema, source_file.syntax(), position) else {
+        return Err(ChangeSignatureError("No function found at position".to_owned()));
+    };
+    ide_db::change_signature::change_signature(&sema, func, params, &[])
+}
+
+fn find_function(
+    sema: &Semantics<'_, RootDatabase>,
+    syntax: &SyntaxNode,
+    position: FilePosition,
+) -> Option<hir::Function> {
+    sema.find_nodes_at_offset_with_descend::<ast::NameLike>(syntax, position.offset).find_map(
+        |name_like| {
+            let def = match name_like {
+                ast::NameLike::Name(name) => match NameClass::classify(sema, &name)? {
+                    NameClass::Definition(it) => it,
+                    _ => return None,
+                },
+                ast::NameLike::NameRef(name_ref) => {
+                    match NameRefClass::classify(sema, &name_ref)? {
+                        NameRefClass::Definition(it) => it,
+                        _ => return None,
+                    }
+                }
+                ast::NameLike::Lifetime(_) => return None,
+            };
+            match def {
+                Definition::Function(it) => Some(it),
+                _ => None,
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    use super::SignatureParam::{self, Existing};
+
+    fn new(pattern: &str, ty: &str, value: &str) -> SignatureParam {
+        SignatureParam::New {
+            pattern: pattern.to_owned(),
+            ty: ty.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[track_caller]
+    fn check(params: &[SignatureParam], ra_fixture_before: &str, ra_fixture_after: &str) {
+        let ra_fixture_after = &trim_indent(ra_fixture_after);
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let result = analysis.change_signature(position, params).unwrap();
+        match result {
+            Ok(source_change) => {
+                let mut result = analysis.file_text(position.file_id).unwrap().to_string();
+                if let Some(edit) = source_change.get_source_edit(position.file_id) {
+                    edit.apply(&mut result);
+                }
+                assert_eq_text!(ra_fixture_after, &*result);
+            }
+            Err(err) => {
+                let error_message = ra_fixture_after
+                    .strip_prefix("error:")
+                    .unwrap_or_else(|| panic!("Change signature failed unexpectedly: {err}"));
+                assert_eq!(error_message.trim(), err.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn swap_params() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+fn foo$0(a: u32, b: bool) {}
+fn main() {
+    foo(1, true);
+}
+"#,
+            r#"
+fn foo(b: bool, a: u32) {}
+fn main() {
+    foo(true, 1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_and_remove_params() {
+        check(
+            &[Existing(2), new("flag", "bool", "false")],
+            r#"
+fn foo(a: u32, b: u32, c: &str) {}
+fn main() {
+    foo$0(1, 2, "c");
+}
+"#,
+            r#"
+fn foo(c: &str, flag: bool) {}
+fn main() {
+    foo("c", false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_calls() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+struct S;
+impl S {
+    fn foo$0(&self, a: u32, b: u32) {}
+}
+fn main() {
+    let s = S;
+    s.foo(1, 2);
+    S::foo(&s, 1, 2);
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self, b: u32, a: u32) {}
+}
+fn main() {
+    let s = S;
+    s.foo(2, 1);
+    S::foo(&s, 2, 1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn qualified_and_nested_calls() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+mod m {
+    pub fn foo$0(a: u32, b: u32) -> u32 { a }
+}
+fn main() {
+    m::foo(m::foo(1, 2), crate::m::foo(3, 4));
+}
+"#,
+            r#"
+mod m {
+    pub fn foo(b: u32, a: u32) -> u32 { a }
+}
+fn main() {
+    m::foo(crate::m::foo(4, 3), m::foo(2, 1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn nested_call_in_removed_arg() {
+        check(
+            &[Existing(1)],
+            r#"
+fn foo$0(a: u32, b: u32) -> u32 { b }
+fn main() {
+    foo(foo(1, 2), 3);
+}
+"#,
+            r#"
+fn foo(b: u32) -> u32 { b }
+fn main() {
+    foo(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn recursive_call() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+fn foo$0(a: u32, b: bool) -> u32 {
+    if b { foo(a - 1, false) } else { a }
+}
+"#,
+            r#"
+fn foo(b: bool, a: u32) -> u32 {
+    if b { foo(false, a - 1) } else { a }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_passed_by_name() {
+        check(
+            &[new("offset", "u32", "0"), Existing(0)],
+            r#"
+//- minicore: option
+fn double$0(x: u32) -> u32 { x * 2 }
+fn main() {
+    Some(1).map(double);
+}
+"#,
+            r#"
+fn double(offset: u32, x: u32) -> u32 { x * 2 }
+fn main() {
+    Some(1).map(|x| double(0, x));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn method_passed_by_name() {
+        check(
+            &[],
+            r#"
+struct S;
+impl S {
+    fn foo$0(&self, (a, b): (u32, u32)) {}
+}
+fn main() {
+    let f = S::foo;
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self) {}
+}
+fn main() {
+    let f = |this, arg1| S::foo(this);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn calls_in_macro_calls() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+macro_rules! id {
+    ($($t:tt)*) => { $($t)* };
+}
+macro_rules! call_foo {
+    ($a:expr) => { foo($a, 0) };
+}
+fn foo$0(a: u32, b: u32) {}
+fn main() {
+    id!(foo(1, 2));
+    call_foo!(3);
+}
+"#,
+            r#"
+macro_rules! id {
+    ($($t:tt)*) => { $($t)* };
+}
+macro_rules! call_foo {
+    ($a:expr) => { foo($a, 0) };
+}
+fn foo(b: u32, a: u32) {}
+fn main() {
+    id!(foo(2, 1));
+    call_foo!(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_method() {
+        check(
+            &[],
+            r#"
+trait Tr {
+    fn foo$0(&self, a: u32);
+}
+"#,
+            "error: Changing the signature of trait methods is not supported",
+        );
+    }
+
+    #[test]
+    fn invalid_params() {
+        check(
+            &[Existing(0), Existing(0)],
+            "fn foo$0(a: u32) {}",
+            "error: Parameter 0 is used more than once",
+        );
+        check(&[Existing(1)], "fn foo$0(a: u32) {}", "error: Parameter index 1 is out of bounds");
+    }
+}
@@ -0,0 +1,205 @@
+//! Evaluates consts on background threads.
+//!
+//! Evaluating a const can take arbitrarily long, for example for big lookup tables, and features
+//! like hover shouldn't stall on it. [`ConstEvalPool`] hands the evaluation to a worker thread,
+//! waits for it for a short while, and otherwise tells the caller that the value is still being
+//! evaluated. The results are cached until the database changes, so the next request gets them.
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use ide_db::{
+    base_db::{
+        salsa::{ParallelDatabase, Snapshot},
+        Cancelled,
+    },
+    FxHashMap, RootDatabase,
+};
+
+/// How long a request waits for a worker before answering with [`ConstEvalState::Evaluating`].
+const WAIT_FOR_RESULT: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ConstEvalState {
+    /// The const is being evaluated on a worker thread.
+    Evaluating,
+    /// The rendered value of the const, or `None` if it couldn't be evaluated.
+    Done(Option<String>),
+}
+
+/// A handle to the worker threads evaluating consts. The default pool has no threads, and
+/// evaluates consts on the calling thread.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConstEvalPool {
+    inner: Option<Arc<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// A zero-capacity channel, so that jobs are only accepted by idle workers. A job waiting in a
+    /// queue would hold on to its database snapshot, and block writes to the database.
+    sender: Sender<Job>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Incremented whenever the cache is cleared, so that evaluations which started before that
+    /// don't store their outdated results.
+    generation: u64,
+    cache: FxHashMap<hir::Const, ConstEvalState>,
+}
+
+struct Job {
+    db: Snapshot<RootDatabase>,
+    konst: hir::Const,
+    generation: u64,
+    reply: Sender<Option<String>>,
+}
+
+impl ConstEvalPool {
+    pub(crate) fn new(num_threads: usize) -> ConstEvalPool {
+        if num_threads == 0 {
+            return ConstEvalPool::default();
+        }
+        const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+        let (sender, receiver) = crossbeam_channel::bounded(0);
+        let state = Arc::new(Mutex::new(State::default()));
+        for _ in 0..num_threads {
+            let (receiver, state) = (receiver.clone(), state.clone());
+            thread::Builder::new()
+                .name("ConstEval".into())
+                .stack_size(STACK_SIZE)
+                .spawn(move || worker(receiver, state))
+                .expect("failed to spawn const evaluation thread");
+        }
+        ConstEvalPool { inner: Some(Arc::new(Inner { sender, state })) }
+    }
+
+    /// Returns the rendered value of `konst`, or [`ConstEvalState::Evaluating`] if it doesn't
+    /// finish in time. Evaluations are cancelled together with the snapshot of `db`.
+    pub(crate) fn render(&self, db: &RootDatabase, konst: hir::Const) -> ConstEvalState {
+        let Some(inner) = &self.inner else {
+            return ConstEvalState::Done(konst.render_eval(db).ok());
+        };
+
+        let generation = {
+            let mut state = inner.state.lock().unwrap();
+            if let Some(it) = state.cache.get(&konst) {
+                return it.clone();
+            }
+            state.cache.insert(konst, ConstEvalState::Evaluating);
+            state.generation
+        };
+
+        let (reply, result) = crossbeam_channel::bounded(1);
+        let job = Job { db: db.snapshot(), konst, generation, reply };
+        if inner.sender.try_send(job).is_err() {
+            // All the workers are busy, the next request will try again.
+            let mut state = inner.state.lock().unwrap();
+            if state.generation == generation {
+                state.cache.remove(&konst);
+            }
+            return ConstEvalState::Evaluating;
+        }
+
+        match result.recv_timeout(WAIT_FOR_RESULT) {
+            Ok(value) => ConstEvalState::Done(value),
+            Err(_) => ConstEvalState::Evaluating,
+        }
+    }
+
+    /// Forgets all the results, because the database is about to change.
+    pub(crate) fn clear(&self) {
+        if let Some(inner) = &self.inner {
+            let mut state = inner.state.lock().unwrap();
+            state.generation += 1;
+            state.cache.clear();
+        }
+    }
+}
+
+fn worker(receiver: Receiver<Job>, state: Arc<Mutex<State>>) {
+    for Job { db, konst, generation, reply } in receiver {
+        // Panics are caught so that they don't kill the worker.
+        let value = panic::catch_unwind(AssertUnwindSafe(|| konst.render_eval(&db).ok()));
+        drop(db);
+
+        let mut state = state.lock().unwrap();
+        if state.generation != generation {
+            continue;
+        }
+        match value {
+            Ok(value) => {
+                state.cache.insert(konst, ConstEvalState::Done(value.clone()));
+                drop(state);
+                reply.send(value).ok();
+            }
+            // The next request evaluates the const again, with the new database.
+            Err(payload) if payload.is::<Cancelled>() => {
+                state.cache.remove(&konst);
+            }
+            // Evaluating the const panicked, so it's shown without a value like a const failing to
+            // evaluate, instead of being evaluated again on every request.
+            Err(_) => {
+                state.cache.insert(konst, ConstEvalState::Done(None));
+                drop(state);
+                reply.send(None).ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::base_db::{fixture::ChangeFixture, FileRange};
+    use syntax::TextRange;
+
+    use crate::{AnalysisHost, HoverConfig, HoverDocFormat};
+
+    #[test]
+    fn hover_shows_values_evaluated_off_thread() {
+        let mut host = AnalysisHost::default();
+        host.set_const_eval_threads(1);
+        let change_fixture = ChangeFixture::parse(
+            r#"
+const SUM: u64 = {
+    let mut i = 0;
+    let mut sum = 0;
+    while i < 100000 {
+        sum = sum + i;
+        i = i + 1;
+    }
+    sum
+};
+const X: u64 = SUM$0;
+"#,
+        );
+        host.apply_change(change_fixture.change);
+        let (file_id, offset) = change_fixture.file_position.unwrap();
+        let range = FileRange { file_id, range: TextRange::empty(offset.expect_offset()) };
+        let config = HoverConfig {
+            links_in_hover: false,
+            documentation: false,
+            keywords: false,
+            format: HoverDocFormat::Markdown,
+            interpret_tests: false,
+            interpret_profiles: Vec::new(),
+        };
+
+        let hover = loop {
+            let hover = host.analysis().hover(&config, range).unwrap().unwrap();
+            let markup = hover.info.markup.to_string();
+            if !markup.contains("evaluating…") {
+                break markup;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert!(hover.contains("const SUM: u64 = 4999950000 (0x12A052EB0)"), "{hover}");
+    }
+}
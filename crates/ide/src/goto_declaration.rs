@@ -1,9 +1,18 @@
-use hir::{AsAssocItem, Semantics};
+use hir::{
+    symbols::FileSymbolKind, AsAssocItem, Function, HasSource, ModuleDef, ModuleSource,
+    PathResolution, ScopeDef, Semantics,
+};
 use ide_db::{
     defs::{Definition, NameClass, NameRefClass},
+    symbol_index::{self, Query},
     RootDatabase,
 };
-use syntax::{ast, match_ast, AstNode, SyntaxKind::*, T};
+use syntax::{
+    ast::{self, HasName},
+    match_ast, AstNode,
+    SyntaxKind::*,
+    SyntaxNode, SyntaxNodePtr, T,
+};
 
 use crate::{
     goto_definition::goto_definition, navigation_target::TryToNav, FilePosition, NavigationTarget,
@@ -18,6 +27,9 @@ use crate::{
 // - outline modules will navigate to the `mod name;` item declaration
 // - trait assoc items will navigate to the assoc item of the trait declaration opposed to the trait impl
 // - fields in patterns will navigate to the field declaration of the struct, union or variant
+// - imported items will navigate to the `use` item or `extern crate` bringing them into the scope
+//   the path is resolved in, like the `pub use` of a re-export
+// - functions defined with an `extern` ABI will navigate to their declarations in `extern` blocks
 pub(crate) fn goto_declaration(
     db: &RootDatabase,
     position: FilePosition,
@@ -36,31 +48,44 @@ pub(crate) fn goto_declaration(
             let def = match_ast! {
                 match parent {
                     ast::NameRef(name_ref) => match NameRefClass::classify(&sema, &name_ref)? {
-                        NameRefClass::Definition(it) => Some(it),
-                        NameRefClass::FieldShorthand { field_ref, .. } => return field_ref.try_to_nav(db),
+                        NameRefClass::Definition(it) => {
+                            if let Some(nav) = import_declaration(&sema, &name_ref, it) {
+                                return Some(vec![nav]);
+                            }
+                            Some(it)
+                        }
+                        NameRefClass::FieldShorthand { field_ref, .. } => {
+                            return field_ref.try_to_nav(db).map(|it| vec![it])
+                        }
                     },
                     ast::Name(name) => match NameClass::classify(&sema, &name)? {
                         NameClass::Definition(it) | NameClass::ConstReference(it) => Some(it),
-                        NameClass::PatFieldShorthand { field_ref, .. } => return field_ref.try_to_nav(db),
+                        NameClass::PatFieldShorthand { field_ref, .. } => {
+                            return field_ref.try_to_nav(db).map(|it| vec![it])
+                        }
                     },
                     _ => None
                 }
             };
             let assoc = match def? {
                 Definition::Module(module) => {
-                    return Some(NavigationTarget::from_module_to_decl(db, module))
+                    return Some(vec![NavigationTarget::from_module_to_decl(db, module)])
                 }
                 Definition::Const(c) => c.as_assoc_item(db),
                 Definition::TypeAlias(ta) => ta.as_assoc_item(db),
-                Definition::Function(f) => f.as_assoc_item(db),
+                Definition::Function(f) => match f.as_assoc_item(db) {
+                    Some(assoc) => Some(assoc),
+                    None => return extern_declarations(&sema, f),
+                },
                 _ => None,
             }?;
 
             let trait_ = assoc.containing_trait_impl(db)?;
             let name = Some(assoc.name(db)?);
             let item = trait_.items(db).into_iter().find(|it| it.name(db) == name)?;
-            item.try_to_nav(db)
+            item.try_to_nav(db).map(|it| vec![it])
         })
+        .flatten()
         .collect();
 
     if info.is_empty() {
@@ -70,6 +95,210 @@ pub(crate) fn goto_declaration(
     }
 }
 
+/// Returns the `use` item or `extern crate` importing `def` into the module the path of
+/// `name_ref` resolves it in, if `def` isn't declared in that module. Explicit imports shadow glob
+/// imports, like they do in name resolution.
+fn import_declaration(
+    sema: &Semantics<'_, RootDatabase>,
+    name_ref: &ast::NameRef,
+    def: Definition,
+) -> Option<NavigationTarget> {
+    let db = sema.db;
+    // `self`, `super` and `crate` aren't imported.
+    name_ref.ident_token()?;
+    if !matches!(
+        def,
+        Definition::Macro(_)
+            | Definition::Module(_)
+            | Definition::Function(_)
+            | Definition::Adt(_)
+            | Definition::Variant(_)
+            | Definition::Const(_)
+            | Definition::Static(_)
+            | Definition::Trait(_)
+            | Definition::TraitAlias(_)
+            | Definition::TypeAlias(_)
+    ) {
+        return None;
+    }
+    let path = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?.parent_path();
+    let scope = match path.qualifier() {
+        Some(qualifier) => match sema.resolve_path(&qualifier)? {
+            PathResolution::Def(ModuleDef::Module(module)) => module,
+            _ => return None,
+        },
+        None => sema.scope(path.syntax())?.module(),
+    };
+    if def.module(db) == Some(scope) {
+        return None;
+    }
+
+    // The module is parsed through `sema`, so that the paths of its imports can be resolved.
+    let src = scope.definition_source(db);
+    let root = sema.parse_or_expand(src.file_id);
+    let module_node = match &src.value {
+        ModuleSource::SourceFile(file) => file.syntax(),
+        ModuleSource::Module(module) => module.syntax(),
+        ModuleSource::BlockExpr(block) => block.syntax(),
+    };
+    let module_node = SyntaxNodePtr::new(module_node).to_node(&root);
+    let items: Vec<ast::Item> = match_ast! {
+        match module_node {
+            ast::SourceFile(file) => file.items().collect(),
+            ast::Module(module) => module.item_list()?.items().collect(),
+            ast::BlockExpr(block) => block
+                .statements()
+                .filter_map(|stmt| match stmt {
+                    ast::Stmt::Item(item) => Some(item),
+                    _ => None,
+                })
+                .collect(),
+            _ => return None,
+        }
+    };
+    let mut imports = Vec::new();
+    collect_imports(sema, items, &mut imports);
+
+    let name = name_ref.text();
+    let explicit = imports.iter().find_map(|import| match import {
+        ast::Item::Use(use_) => use_
+            .syntax()
+            .descendants()
+            .filter_map(ast::UseTree::cast)
+            .filter(|tree| tree.use_tree_list().is_none() && tree.star_token().is_none())
+            .find_map(|tree| {
+                let path = tree.path()?;
+                let imported = imported_name(tree.rename(), path.segment()?.name_ref())?;
+                (imported.text() == name.as_str() && use_path_imports(sema, &path, def))
+                    .then(|| (import, Some(imported)))
+            }),
+        ast::Item::ExternCrate(extern_crate) => {
+            let imported = imported_name(extern_crate.rename(), extern_crate.name_ref())?;
+            let krate = sema.resolve_extern_crate(extern_crate)?;
+            (imported.text() == name.as_str() && def == Definition::Module(krate.root_module(db)))
+                .then(|| (import, Some(imported)))
+        }
+        _ => None,
+    });
+    let (import, imported) = explicit.or_else(|| {
+        imports.iter().find_map(|import| match import {
+            ast::Item::Use(use_) => use_
+                .syntax()
+                .descendants()
+                .filter_map(ast::UseTree::cast)
+                .filter(|tree| tree.star_token().is_some())
+                .find(|tree| {
+                    tree.path().map_or(false, |path| {
+                        module_scope_contains(sema, &path, name.as_str(), def)
+                    })
+                })
+                .map(|_| (import, None)),
+            _ => None,
+        })
+    })?;
+
+    let mut nav = def.try_to_nav(db)?;
+    let full_range = sema.original_range(import.syntax());
+    nav.file_id = full_range.file_id;
+    nav.full_range = full_range.range;
+    nav.focus_range = imported
+        .and_then(|it| sema.original_range_opt(&it))
+        .filter(|it| it.file_id == full_range.file_id && full_range.range.contains_range(it.range))
+        .map(|it| it.range);
+    Some(nav)
+}
+
+/// Collects the imports among `items`, including the ones produced by item macros.
+fn collect_imports(
+    sema: &Semantics<'_, RootDatabase>,
+    items: impl IntoIterator<Item = ast::Item>,
+    acc: &mut Vec<ast::Item>,
+) {
+    for item in items {
+        match &item {
+            ast::Item::Use(_) | ast::Item::ExternCrate(_) => acc.push(item),
+            ast::Item::MacroCall(call) => {
+                if let Some(expansion) = sema.expand(call).and_then(ast::MacroItems::cast) {
+                    collect_imports(sema, expansion.items(), acc);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Whether the path of a use tree imports `def`.
+fn use_path_imports(sema: &Semantics<'_, RootDatabase>, path: &ast::Path, def: Definition) -> bool {
+    if sema.resolve_path(path).map(Definition::from) == Some(def) {
+        return true;
+    }
+    // The path resolves to an item of one namespace only, `def` may be the item of the same name in
+    // another one.
+    let name = match path.segment().and_then(|it| it.name_ref()) {
+        Some(it) => it,
+        None => return false,
+    };
+    let prefix = path.qualifier().or_else(|| {
+        let tree = path.syntax().parent().and_then(ast::UseTree::cast)?;
+        tree.syntax().parent().and_then(ast::UseTreeList::cast)?.parent_use_tree().path()
+    });
+    prefix.map_or(false, |prefix| module_scope_contains(sema, &prefix, name.text().as_str(), def))
+}
+
+/// Whether `def` is visible as `name` in the module `module_path` resolves to.
+fn module_scope_contains(
+    sema: &Semantics<'_, RootDatabase>,
+    module_path: &ast::Path,
+    name: &str,
+    def: Definition,
+) -> bool {
+    let module = match sema.resolve_path(module_path) {
+        Some(PathResolution::Def(ModuleDef::Module(module))) => module,
+        _ => return false,
+    };
+    module.scope(sema.db, None).into_iter().any(|(scope_name, scope_def)| {
+        scope_name.to_smol_str() == name
+            && matches!(scope_def, ScopeDef::ModuleDef(it) if Definition::from(it) == def)
+    })
+}
+
+/// Returns the name an import binds, the rename if there is one.
+fn imported_name(
+    rename: Option<ast::Rename>,
+    path_name: Option<ast::NameRef>,
+) -> Option<SyntaxNode> {
+    match rename {
+        Some(rename) => rename.name().map(|it| it.syntax().clone()),
+        None => path_name.map(|it| it.syntax().clone()),
+    }
+}
+
+/// Returns the declarations in `extern` blocks of a function defined with an `extern` ABI, which
+/// are linked to it by name.
+fn extern_declarations(
+    sema: &Semantics<'_, RootDatabase>,
+    function: Function,
+) -> Option<Vec<NavigationTarget>> {
+    let db = sema.db;
+    if !function.has_body(db) {
+        return None;
+    }
+    function.source(db)?.value.abi()?;
+
+    let mut query = Query::new(function.name(db).to_smol_str().to_string());
+    query.exact();
+    query.case_sensitive();
+    let navs: Vec<_> = symbol_index::world_symbols(db, query)
+        .into_iter()
+        .filter(|symbol| symbol.kind == FileSymbolKind::Function)
+        .filter(|symbol| {
+            symbol.loc.syntax(sema).parent().map_or(false, |it| it.kind() == EXTERN_ITEM_LIST)
+        })
+        .filter_map(|symbol| symbol.try_to_nav(db))
+        .collect();
+    (!navs.is_empty()).then_some(navs)
+}
+
 #[cfg(test)]
 mod tests {
     use ide_db::base_db::FileRange;
@@ -208,6 +437,149 @@ fn main() {
     let field = 0;
     Foo { field$0 };
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_impl_method_call() {
+        check(
+            r#"
+trait Trait {
+    fn f(&self);
+     //^
+}
+struct S;
+impl Trait for S {
+    fn f(&self) {}
+}
+fn main() {
+    S.f$0();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_reexport() {
+        check(
+            r#"
+mod inner {
+    pub struct Foo;
+}
+mod reexports {
+    pub use crate::inner::Foo;
+                        //^^^
+}
+fn main() {
+    reexports::Foo$0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_import_rename() {
+        check(
+            r#"
+mod inner {
+    pub fn foo() {}
+}
+use inner::foo as bar;
+                //^^^
+fn main() {
+    bar$0();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_extern_crate() {
+        check(
+            r#"
+//- /main.rs crate:main deps:std
+extern crate std as core_std;
+                  //^^^^^^^^
+fn main() {
+    core_std$0::S;
+}
+//- /std.rs crate:std
+pub struct S;
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_import_of_other_namespace() {
+        check(
+            r#"
+mod m {
+    mod types {
+        pub mod foo {}
+    }
+    mod values {
+        pub fn foo() {}
+    }
+    use types::foo;
+    use values::*;
+ // ^^^^^^^^^^^^^^
+    fn f() {
+        foo$0();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_import_in_both_namespaces() {
+        check(
+            r#"
+mod inner {
+    pub mod foo {}
+    pub fn foo() {}
+}
+use inner::foo;
+         //^^^
+fn main() {
+    foo$0();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_macro_import() {
+        check(
+            r#"
+mod inner {
+    pub fn foo() {}
+}
+macro_rules! import {
+    ($($tt:tt)*) => { $($tt)* };
+}
+import!(use inner::foo;);
+                 //^^^
+fn main() {
+    foo$0();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_decl_extern_fn() {
+        check(
+            r#"
+//- /main.rs crate:main deps:lib
+extern "C" {
+    fn foo();
+     //^^^
+}
+//- /lib.rs crate:lib
+#[no_mangle]
+pub extern "C" fn foo$0() {}
 "#,
         );
     }
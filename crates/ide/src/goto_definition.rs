@@ -6,6 +6,7 @@ use ide_db::{
     base_db::{AnchoredPath, FileId, FileLoader},
     defs::{Definition, IdentClass},
     helpers::pick_best_token,
+    syntax_helpers::asm_template::AsmArgs,
     RootDatabase,
 };
 use itertools::Itertools;
@@ -57,6 +58,11 @@ pub(crate) fn goto_definition(
             },
         );
     }
+    if original_token.kind() == STRING {
+        if let Some(res) = try_lookup_asm_operand(sema, &original_token, position) {
+            return Some(res);
+        }
+    }
     let navs = sema
         .descend_into_macros(original_token.clone())
         .into_iter()
@@ -120,6 +126,35 @@ fn try_lookup_include_path(
         docs: None,
     })
 }
+
+fn try_lookup_asm_operand(
+    sema: &Semantics<'_, RootDatabase>,
+    token: &SyntaxToken,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let args = AsmArgs::for_token(sema, token)?;
+    let placeholder = args.placeholder_at(position.offset)?;
+    let operand = args.resolve(&placeholder.operand)?;
+    let nav = NavigationTarget {
+        file_id: position.file_id,
+        full_range: operand.range,
+        name: operand
+            .name
+            .as_ref()
+            .map_or_else(|| operand.kind.clone().into(), |it| it.text().into()),
+        focus_range: operand
+            .name
+            .as_ref()
+            .map(|it| it.text_range())
+            .or(operand.exprs.first().copied()),
+        kind: None,
+        container_name: None,
+        description: Some(operand.kind.clone()),
+        docs: None,
+    };
+    Some(RangeInfo::new(placeholder.range, vec![nav]))
+}
+
 /// finds the trait definition of an impl'd item, except function
 /// e.g.
 /// ```rust
@@ -1439,6 +1474,34 @@ struct Item;
         );
     }
 
+    #[test]
+    fn goto_asm_operand() {
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn main() {
+    let a = 0u64;
+    asm!("mov {x}, {$01}", x = out(reg) _, in(reg) a, in(reg) 5);
+                                                          //^
+}
+"#,
+        );
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn main() {
+    let a = 0u64;
+    asm!("mov {x$0}, {}", x = out(reg) _, in(reg) a);
+                      //^
+}
+"#,
+        );
+    }
+
     #[test]
     fn goto_shadow_include() {
         check(
@@ -1595,6 +1658,60 @@ fn f() {
             );
         }
 
+        #[test]
+        fn method_call_specialized() {
+            check(
+                r#"
+trait Twait {
+    fn a(&self);
+}
+
+struct Stwuct<T>(T);
+
+impl<T> Twait for Stwuct<T> {
+    default fn a(&self) {}
+}
+
+impl Twait for Stwuct<u8> {
+    fn a(&self) {}
+     //^
+}
+fn f() {
+    let s = Stwuct(0u8);
+    s.a$0();
+}
+        "#,
+            );
+        }
+
+        #[test]
+        fn method_call_specialized_defaulted() {
+            check(
+                r#"
+trait Twait {
+    fn a(&self);
+    fn b(&self);
+}
+
+struct Stwuct<T>(T);
+
+impl<T> Twait for Stwuct<T> {
+    default fn a(&self) {}
+    default fn b(&self) {}
+             //^
+}
+
+impl Twait for Stwuct<u8> {
+    fn a(&self) {}
+}
+fn f() {
+    let s = Stwuct(0u8);
+    s.b$0();
+}
+        "#,
+            );
+        }
+
         #[test]
         fn method_call_on_generic() {
             check(
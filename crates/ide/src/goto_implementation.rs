@@ -13,6 +13,9 @@ use crate::{FilePosition, NavigationTarget, RangeInfo, TryToNav};
 //
 // Navigates to the impl blocks of types.
 //
+// On a method call, navigates to the methods of the impls applying to the receiver, the one
+// which is called first, followed by the ones it specializes.
+//
 // |===
 // | Editor  | Shortcut
 //
@@ -34,8 +37,17 @@ pub(crate) fn goto_implementation(
             _ => 0,
         })?;
     let range = original_token.text_range();
-    let navs = sema
-        .descend_into_macros(original_token)
+    let tokens = sema.descend_into_macros(original_token);
+    let method_impls: Vec<_> = tokens
+        .iter()
+        .filter_map(|token| token.parent()?.parent().and_then(ast::MethodCallExpr::cast))
+        .flat_map(|call| impls_for_method_call(&sema, &call))
+        .unique()
+        .collect();
+    if !method_impls.is_empty() {
+        return Some(RangeInfo { range, info: method_impls });
+    }
+    let navs = tokens
         .into_iter()
         .filter_map(|token| token.parent().and_then(ast::NameLike::cast))
         .filter_map(|node| match &node {
@@ -86,6 +98,24 @@ pub(crate) fn goto_implementation(
     Some(RangeInfo { range, info: navs })
 }
 
+/// Returns the methods of the trait impls `call` may run, or nothing when the call is only resolved
+/// to the method of the trait, like for a generic receiver, or isn't a call of a trait method.
+fn impls_for_method_call(
+    sema: &Semantics<'_, RootDatabase>,
+    call: &ast::MethodCallExpr,
+) -> Vec<NavigationTarget> {
+    let candidates = sema.resolve_method_call_candidates(call);
+    let in_trait_impl = candidates
+        .first()
+        .and_then(|it| it.as_assoc_item(sema.db))
+        .and_then(|it| it.containing_trait_impl(sema.db))
+        .is_some();
+    if !in_trait_impl {
+        return Vec::new();
+    }
+    candidates.into_iter().filter_map(|it| it.try_to_nav(sema.db)).collect()
+}
+
 fn impls_for_ty(sema: &Semantics<'_, RootDatabase>, ty: hir::Type) -> Vec<NavigationTarget> {
     Impl::all_for_type(sema.db, ty).into_iter().filter_map(|imp| imp.try_to_nav(sema.db)).collect()
 }
@@ -141,6 +171,24 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    /// Like [`check`], but the annotations are labelled with the expected position of the target.
+    fn check_ordered(ra_fixture: &str) {
+        let (analysis, position, expected) = fixture::annotations(ra_fixture);
+
+        let navs = analysis.goto_implementation(position).unwrap().unwrap().info;
+
+        let actual = navs
+            .into_iter()
+            .map(|nav| FileRange { file_id: nav.file_id, range: nav.focus_or_full_range() })
+            .collect::<Vec<_>>();
+        let expected = expected
+            .into_iter()
+            .sorted_by_key(|(_, label)| label.clone())
+            .map(|(range, _)| range)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn goto_implementation_works() {
         check(
@@ -339,6 +387,93 @@ impl Tr for S {
     const C: usize = 4;
         //^
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_method_call_specialized() {
+        check_ordered(
+            r#"
+trait Tr {
+    fn f(&self);
+}
+
+struct S<T>(T);
+
+impl<T> Tr for S<T> {
+    default fn f(&self) {}
+             //^ 2
+}
+
+impl Tr for S<u8> {
+    fn f(&self) {}
+     //^ 1
+}
+
+impl Tr for S<u16> {
+    fn f(&self) {}
+}
+
+fn main() {
+    S(0u8).f$0();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_method_call_specialized_defaulted() {
+        check_ordered(
+            r#"
+trait Tr {
+    fn f(&self);
+    fn g(&self);
+}
+
+struct S<T>(T);
+
+impl<T> Tr for S<T> {
+    default fn f(&self) {}
+    default fn g(&self) {}
+             //^ 1
+}
+
+impl Tr for S<u8> {
+    fn f(&self) {}
+}
+
+fn main() {
+    S(0u8).g$0();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_implementation_method_call_on_generic() {
+        check(
+            r#"
+trait Tr {
+    fn f(&self);
+}
+
+struct S;
+struct U;
+
+impl Tr for S {
+    fn f(&self) {}
+     //^
+}
+
+impl Tr for U {
+    fn f(&self) {}
+     //^
+}
+
+fn main<T: Tr>(t: T) {
+    t.f$0();
+}
 "#,
         );
     }
@@ -1,20 +1,25 @@
+use std::iter;
+
 use hir::Semantics;
 use ide_db::{
     base_db::{FileId, FilePosition},
     defs::{Definition, IdentClass},
     helpers::pick_best_token,
     search::{FileReference, ReferenceCategory, SearchScope},
-    syntax_helpers::node_ext::{for_each_break_and_continue_expr, for_each_tail_expr, walk_expr},
+    syntax_helpers::{
+        asm_template::AsmArgs,
+        node_ext::{for_each_break_and_continue_expr, for_each_tail_expr, walk_expr},
+    },
     FxHashSet, RootDatabase,
 };
 use syntax::{
     ast::{self, HasLoopBody},
     match_ast, AstNode,
-    SyntaxKind::{self, IDENT, INT_NUMBER},
-    SyntaxNode, SyntaxToken, TextRange, T,
+    SyntaxKind::{self, IDENT, INT_NUMBER, STRING},
+    SyntaxNode, SyntaxToken, TextRange, TextSize, T,
 };
 
-use crate::{navigation_target::ToNav, references, NavigationTarget, TryToNav};
+use crate::{lifetime_reach, navigation_target::ToNav, references, NavigationTarget, TryToNav};
 
 #[derive(PartialEq, Eq, Hash)]
 pub struct HighlightedRange {
@@ -70,11 +75,34 @@ pub(crate) fn highlight_related(
         T![break] | T![loop] | T![while] | T![continue] if config.break_points => {
             highlight_break_points(token)
         }
-        _ if config.references => highlight_references(sema, &syntax, token, file_id),
+        _ if config.references => highlight_asm_operand(sema, &token, offset)
+            .or_else(|| highlight_references(sema, &syntax, token, file_id)),
         _ => None,
     }
 }
 
+/// Highlights a named or positional operand of an `asm!` invocation, together with the
+/// placeholders of the template strings referring to it.
+fn highlight_asm_operand(
+    sema: &Semantics<'_, RootDatabase>,
+    token: &SyntaxToken,
+    offset: TextSize,
+) -> Option<Vec<HighlightedRange>> {
+    let args = AsmArgs::for_token(sema, token)?;
+    let operand = match token.kind() {
+        STRING => args.resolve(&args.placeholder_at(offset)?.operand)?,
+        _ => args.operand_named_by(token)?,
+    };
+    let def_range = operand.name.as_ref().map_or(operand.range, |it| it.text_range());
+    let res = args
+        .placeholders_of(operand)
+        .into_iter()
+        .map(|it| HighlightedRange { range: it.range, category: Some(ReferenceCategory::Read) })
+        .chain(iter::once(HighlightedRange { range: def_range, category: None }))
+        .collect();
+    Some(res)
+}
+
 fn highlight_references(
     sema: &Semantics<'_, RootDatabase>,
     node: &SyntaxNode,
@@ -113,6 +141,18 @@ fn highlight_references(
                         res.insert(x);
                     });
             }
+            Definition::GenericParam(hir::GenericParam::LifetimeParam(param)) => {
+                let decl = param
+                    .try_to_nav(sema.db)
+                    .filter(|decl| decl.file_id == file_id)
+                    .and_then(|decl| decl.focus_range);
+                // The elided lifetimes of signatures resolving to the parameter are highlighted at
+                // their reference.
+                let elided = lifetime_reach::elided_references_to(sema, param, file_id);
+                for range in decl.into_iter().chain(elided) {
+                    res.insert(HighlightedRange { range, category: None });
+                }
+            }
             def => {
                 let hl_range = match def {
                     Definition::Module(module) => {
@@ -413,6 +453,34 @@ fn foo(t: Tuple) {
         );
     }
 
+    #[test]
+    fn test_hl_asm_operands() {
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(a: u32) {
+    asm!("mov {x}, {}", "add {x}, {0}", in(reg) a, x$0 = inout(reg) 0u32 => _);
+            //^^^ read       ^^^ read
+                                                 //^
+}
+"#,
+        );
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(a: u32) {
+    asm!("mov {x}, {$0}", "add {x}, {0}", in(reg) a, x = inout(reg) 0u32 => _);
+                 //^^ read        ^^^ read
+                                      //^^^^^^^^^
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_hl_module() {
         check(
@@ -493,6 +561,21 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn test_hl_lifetime_param_elided_uses() {
+        check(
+            r#"
+fn first<'a$0>(bytes: &'a [u8]) -> (&u8, &'_ u8) {
+//       ^^
+//                   ^^
+//                                ^^^
+//                                      ^^
+    (&bytes[0], &bytes[1])
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_multi_macro_usage() {
         check(
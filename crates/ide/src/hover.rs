@@ -6,7 +6,7 @@ mod tests;
 use std::iter;
 
 use either::Either;
-use hir::{HasSource, Semantics};
+use hir::{BuildProfile, HasSource, Semantics};
 use ide_db::{
     base_db::FileRange,
     defs::{Definition, IdentClass, OperatorClass},
@@ -18,6 +18,7 @@ use itertools::Itertools;
 use syntax::{ast, AstNode, SyntaxKind::*, SyntaxNode, T};
 
 use crate::{
+    const_eval_pool::ConstEvalPool,
     doc_links::token_as_doc_comment,
     markdown_remove::remove_markdown,
     markup::Markup,
@@ -31,6 +32,8 @@ pub struct HoverConfig {
     pub keywords: bool,
     pub format: HoverDocFormat,
     pub interpret_tests: bool,
+    /// The profiles the tests are interpreted under, with a result for each.
+    pub interpret_profiles: Vec<BuildProfile>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -89,11 +92,13 @@ pub(crate) fn hover(
     db: &RootDatabase,
     frange @ FileRange { file_id, range }: FileRange,
     config: &HoverConfig,
+    const_eval_pool: &ConstEvalPool,
 ) -> Option<RangeInfo<HoverResult>> {
     let sema = &hir::Semantics::new(db);
     let file = sema.parse(file_id).syntax().clone();
     let mut res = if range.is_empty() {
-        hover_simple(sema, FilePosition { file_id, offset: range.start() }, file, config)
+        let position = FilePosition { file_id, offset: range.start() };
+        hover_simple(sema, position, file, config, const_eval_pool)
     } else {
         hover_ranged(sema, frange, file, config)
     }?;
@@ -109,6 +114,7 @@ fn hover_simple(
     FilePosition { file_id, offset }: FilePosition,
     file: SyntaxNode,
     config: &HoverConfig,
+    const_eval_pool: &ConstEvalPool,
 ) -> Option<RangeInfo<HoverResult>> {
     let original_token = pick_best_token(file.token_at_offset(offset), |kind| match kind {
         IDENT
@@ -130,11 +136,21 @@ fn hover_simple(
     if let Some(doc_comment) = token_as_doc_comment(&original_token) {
         cov_mark::hit!(no_highlight_on_comment_hover);
         return doc_comment.get_definition_with_descend_at(sema, offset, |def, node, range| {
-            let res = hover_for_definition(sema, file_id, def, &node, config)?;
+            let res = hover_for_definition(sema, file_id, def, &node, config, const_eval_pool)?;
             Some(RangeInfo::new(range, res))
         });
     }
 
+    if original_token.kind() == STRING {
+        if let Some(res) = render::asm_placeholder(sema, &file, &original_token, offset) {
+            return Some(res);
+        }
+    }
+
+    if let Some(res) = render::cfg_macro(sema, &original_token) {
+        return Some(RangeInfo::new(original_token.text_range(), res));
+    }
+
     let in_attr = original_token
         .parent_ancestors()
         .filter_map(ast::Item::cast)
@@ -175,7 +191,9 @@ fn hover_simple(
                 })
                 .flatten()
                 .unique_by(|&(def, _)| def)
-                .filter_map(|(def, node)| hover_for_definition(sema, file_id, def, &node, config))
+                .filter_map(|(def, node)| {
+                    hover_for_definition(sema, file_id, def, &node, config, const_eval_pool)
+                })
                 .reduce(|mut acc: HoverResult, HoverResult { markup, actions }| {
                     acc.actions.extend(actions);
                     acc.markup = Markup::from(format!("{}\n---\n{markup}", acc.markup));
@@ -219,6 +237,20 @@ fn hover_simple(
                 };
                 render::type_info_of(sema, config, &Either::Left(call_expr))
             })
+        })
+        // try closure hovers
+        .or_else(|| {
+            descended().find_map(|token| {
+                if token.kind() != T![|] && token.kind() != T![||] {
+                    return None;
+                }
+                let parent = token.parent()?;
+                let closure = match ast::ParamList::cast(parent.clone()) {
+                    Some(param_list) => param_list.syntax().parent()?,
+                    None => parent,
+                };
+                render::closure_expr(sema, config, &ast::ClosureExpr::cast(closure)?)
+            })
         });
 
     result.map(|mut res: HoverResult| {
@@ -264,24 +296,25 @@ pub(crate) fn hover_for_definition(
     definition: Definition,
     node: &SyntaxNode,
     config: &HoverConfig,
+    const_eval_pool: &ConstEvalPool,
 ) -> Option<HoverResult> {
     let famous_defs = match &definition {
         Definition::BuiltinType(_) => Some(FamousDefs(sema, sema.scope(node)?.krate())),
         _ => None,
     };
-    render::definition(sema.db, definition, famous_defs.as_ref(), config).map(|markup| {
-        HoverResult {
-            markup: render::process_markup(sema.db, definition, &markup, config),
-            actions: [
-                show_implementations_action(sema.db, definition),
-                show_fn_references_action(sema.db, definition),
-                runnable_action(sema, definition, file_id),
-                goto_type_action_for_def(sema.db, definition),
-            ]
-            .into_iter()
-            .flatten()
-            .collect(),
-        }
+    let markup =
+        render::definition(sema, definition, famous_defs.as_ref(), config, const_eval_pool);
+    markup.map(|markup| HoverResult {
+        markup: render::process_markup(sema.db, definition, &markup, config),
+        actions: [
+            show_implementations_action(sema.db, definition),
+            show_fn_references_action(sema.db, definition),
+            runnable_action(sema, definition, file_id),
+            goto_type_action_for_def(sema.db, definition),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
     })
 }
 
@@ -1,17 +1,18 @@
 //! Logic for rendering the different hover messages
 use std::fmt::Display;
 
+use cfg::DnfExpr;
 use either::Either;
 use hir::{
-    db::DefDatabase, Adt, AsAssocItem, AttributeTemplate, HasAttrs, HasSource, HirDisplay,
-    MirEvalError, Semantics, TypeInfo,
+    Adt, AsAssocItem, AttributeTemplate, HasAttrs, HasSource, HirDisplay, ModuleDef,
+    PathResolution, Semantics, TypeInfo,
 };
 use ide_db::{
     base_db::SourceDatabase,
     defs::Definition,
     famous_defs::FamousDefs,
     generated::lints::{CLIPPY_LINTS, DEFAULT_LINTS, FEATURES},
-    syntax_helpers::insert_whitespace_into_node,
+    syntax_helpers::{asm_template::AsmArgs, insert_whitespace_into_node},
     RootDatabase,
 };
 use itertools::Itertools;
@@ -21,13 +22,15 @@ use syntax::{
     ast::{self, RecordPat},
     match_ast, AstNode, Direction,
     SyntaxKind::{LET_EXPR, LET_STMT},
-    SyntaxToken, T,
+    SyntaxNode, SyntaxToken, TextSize, T,
 };
 
 use crate::{
+    const_eval_pool::{ConstEvalPool, ConstEvalState},
     doc_links::{remove_links, rewrite_links},
     hover::walk_and_push_ty,
-    HoverAction, HoverConfig, HoverResult, Markup,
+    interpret_function::interpret_function,
+    HoverAction, HoverConfig, HoverResult, Markup, RangeInfo,
 };
 
 pub(super) fn type_info_of(
@@ -39,7 +42,13 @@ pub(super) fn type_info_of(
         Either::Left(expr) => sema.type_of_expr(expr)?,
         Either::Right(pat) => sema.type_of_pat(pat)?,
     };
-    type_info(sema, _config, original, adjusted)
+    let node = expr_or_pat.as_ref().either(AstNode::syntax, AstNode::syntax);
+    let not_send = future_not_send(sema, node, &original);
+    let mut res = type_info(sema, _config, original, adjusted)?;
+    if let Some(not_send) = not_send {
+        res.markup = format!("{}\n\n{not_send}", res.markup.as_str().trim_end()).into();
+    }
+    Some(res)
 }
 
 pub(super) fn try_expr(
@@ -191,6 +200,193 @@ pub(super) fn deref_expr(
     Some(res)
 }
 
+pub(super) fn closure_expr(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &HoverConfig,
+    closure: &ast::ClosureExpr,
+) -> Option<HoverResult> {
+    let ty = sema.type_of_expr(&ast::Expr::from(closure.clone()))?.original;
+    let hir_closure = ty.as_closure()?;
+    let captures = hir_closure.captured_items(sema.db);
+    let fn_trait = hir_closure.fn_trait(sema.db);
+    let mut res = type_info(sema, config, ty, None)?;
+    if captures.is_empty() {
+        return Some(res);
+    }
+
+    let mut markup = res.markup.to_string();
+    markup.push_str("\n\n## Captures\n");
+    for capture in &captures {
+        let kind = match capture.kind() {
+            hir::CaptureKind::SharedRef => "by immutable borrow",
+            hir::CaptureKind::UniqueSharedRef => "by unique borrow",
+            hir::CaptureKind::MutableRef => "by mutable borrow",
+            hir::CaptureKind::Move => "by value",
+        };
+        format_to!(markup, "* `{}` {kind}\n", capture.display_place(sema.db));
+    }
+    if let Some(reason) = closure_kind_reason(sema.db, closure, fn_trait, &captures) {
+        format_to!(markup, "\n{reason}\n");
+    }
+    if let Some(requirements) = capture_requirements(sema, closure, &captures) {
+        format_to!(markup, "\n{requirements}");
+    }
+    res.markup = markup.trim_end().to_owned().into();
+    Some(res)
+}
+
+/// Explains why the closure only implements `FnMut` or `FnOnce`, by the captures it mutates or
+/// moves.
+fn closure_kind_reason(
+    db: &RootDatabase,
+    closure: &ast::ClosureExpr,
+    fn_trait: hir::FnTrait,
+    captures: &[hir::ClosureCapture],
+) -> Option<String> {
+    if closure.move_token().is_some() {
+        // FIXME: All the captures of `move` closures are by value, the uses deciding the kind of
+        // the closure aren't recorded.
+        return None;
+    }
+    let verb = match fn_trait {
+        hir::FnTrait::Fn => return None,
+        hir::FnTrait::FnMut => "mutates",
+        hir::FnTrait::FnOnce => "moves",
+    };
+    let places = captures
+        .iter()
+        .filter(|it| match (fn_trait, it.kind()) {
+            (
+                hir::FnTrait::FnMut,
+                hir::CaptureKind::MutableRef | hir::CaptureKind::UniqueSharedRef,
+            )
+            | (hir::FnTrait::FnOnce, hir::CaptureKind::Move) => true,
+            _ => false,
+        })
+        .map(|it| format!("`{}`", it.display_place(db)))
+        .join(", ");
+    if places.is_empty() {
+        return None;
+    }
+    Some(format!("This closure is `{fn_trait:?}` because it {verb} {places}"))
+}
+
+/// Explains which captures keep the closure from being `Send` or `'static`, if it is passed to a
+/// function requiring that, like `std::thread::spawn`.
+fn capture_requirements(
+    sema: &Semantics<'_, RootDatabase>,
+    closure: &ast::ClosureExpr,
+    captures: &[hir::ClosureCapture],
+) -> Option<String> {
+    let db = sema.db;
+    let arg_list = closure.syntax().parent().and_then(ast::ArgList::cast)?;
+    let arg_idx = arg_list.args().position(|it| it.syntax() == closure.syntax())?;
+    let (func, params) = match_ast! {
+        match (arg_list.syntax().parent()?) {
+            ast::MethodCallExpr(call) => {
+                let func = sema.resolve_method_call(&call)?;
+                (func, func.params_without_self(db))
+            },
+            ast::CallExpr(call) => {
+                let ast::Expr::PathExpr(callee) = call.expr()? else { return None };
+                let hir::PathResolution::Def(hir::ModuleDef::Function(func)) =
+                    sema.resolve_path(&callee.path()?)?
+                else {
+                    return None;
+                };
+                (func, func.assoc_fn_params(db))
+            },
+            _ => return None,
+        }
+    };
+    let param = params.get(arg_idx)?.ty().as_type_param(db)?;
+
+    let send = FamousDefs(sema, func.module(db).krate()).core_marker_Send();
+    let needs_send = send.map_or(false, |send| param.trait_bounds(db).contains(&send));
+    let needs_static = param.lifetime_bounds(db).iter().any(|it| it.to_smol_str() == "'static");
+
+    let mut problems = Vec::new();
+    for capture in captures {
+        let name = capture.local().name(db);
+        let ty = capture.ty(db);
+        let blocker = send.filter(|_| needs_send).and_then(|send| ty.auto_trait_blocker(db, send));
+        if let Some(steps) = blocker {
+            problems.push(format!(
+                "* `{name}` is captured as `{}`, which is not `Send`{}",
+                ty.display(db),
+                explain_blocking_steps(db, &steps)
+            ));
+        }
+        if needs_static && capture.kind() != hir::CaptureKind::Move {
+            problems.push(format!(
+                "* `{name}` is captured by reference, which doesn't live for `'static`; \
+                 consider using a `move` closure"
+            ));
+        }
+    }
+    if problems.is_empty() {
+        return None;
+    }
+    let bounds = match (needs_send, needs_static) {
+        (true, true) => "`Send + 'static`",
+        (true, false) => "`Send`",
+        (false, _) => "`'static`",
+    };
+    Some(format!(
+        "`{}` requires the closure to be {bounds}:\n{}",
+        func.name(db),
+        problems.join("\n")
+    ))
+}
+
+/// Explains why a future isn't `Send`, by the local held across an `.await` which isn't `Send`.
+fn future_not_send(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+    ty: &hir::Type,
+) -> Option<String> {
+    let db = sema.db;
+    if !ty.impls_into_future(db) {
+        return None;
+    }
+    let send = FamousDefs(sema, sema.scope(node)?.krate()).core_marker_Send()?;
+    let steps = ty.auto_trait_blocker(db, send)?;
+    Some(format!("This future is not `Send`{}", explain_blocking_steps(db, &steps)))
+}
+
+/// Renders the types keeping a type from implementing an auto trait, as returned by
+/// [`hir::Type::auto_trait_blocker`], as a chain of "because" clauses.
+fn explain_blocking_steps(db: &RootDatabase, steps: &[hir::AutoTraitBlockingStep]) -> String {
+    let mut res = String::new();
+    for step in steps {
+        let ty = step.ty.display(db);
+        let trait_ = step.trait_.name(db);
+        let reason = match step.kind {
+            hir::AutoTraitBlockingStepKind::Field(field) => {
+                format!("its field `{}` has type `{ty}`", field.name(db))
+            }
+            hir::AutoTraitBlockingStepKind::TupleField(idx) => {
+                format!("its field `{idx}` has type `{ty}`")
+            }
+            hir::AutoTraitBlockingStepKind::Element => format!("it contains `{ty}`"),
+            hir::AutoTraitBlockingStepKind::Capture(local) => {
+                format!("it captures `{}` as `{ty}`", local.name(db))
+            }
+            hir::AutoTraitBlockingStepKind::HeldAcrossAwait(local) => {
+                format!("`{}` is held across an `.await` and has type `{ty}`", local.name(db))
+            }
+            // The hidden type of an `impl Future` is displayed the same way.
+            hir::AutoTraitBlockingStepKind::HiddenType => continue,
+            hir::AutoTraitBlockingStepKind::WhereClause => {
+                format_to!(res, " because `{ty}` is not `{trait_}`");
+                continue;
+            }
+        };
+        format_to!(res, " because {reason}, which is not `{trait_}`");
+    }
+    res
+}
+
 pub(super) fn underscore(
     sema: &Semantics<'_, RootDatabase>,
     config: &HoverConfig,
@@ -338,6 +534,63 @@ pub(super) fn try_for_lint(attr: &ast::Attr, token: &SyntaxToken) -> Option<Hove
     })
 }
 
+pub(super) fn asm_placeholder(
+    sema: &Semantics<'_, RootDatabase>,
+    file: &SyntaxNode,
+    token: &SyntaxToken,
+    offset: TextSize,
+) -> Option<RangeInfo<HoverResult>> {
+    let args = AsmArgs::for_token(sema, token)?;
+    let placeholder = args.placeholder_at(offset)?;
+    let operand = args.resolve(&placeholder.operand)?;
+
+    let mut desc = String::new();
+    if let Some(name) = &operand.name {
+        format_to!(desc, "{} = ", name.text());
+    }
+    desc.push_str(&operand.kind);
+
+    let mut res = HoverResult::default();
+    let ty = operand.exprs.first().and_then(|&range| {
+        let token = file.token_at_offset(range.start()).right_biased()?;
+        sema.descend_into_macros(token).into_iter().find_map(|token| {
+            let init = token.parent_ancestors().find_map(ast::LetStmt::cast)?.initializer()?;
+            Some(sema.type_of_expr(&init)?.original)
+        })
+    });
+    if let Some(ty) = ty {
+        format_to!(desc, ": {}", ty.display(sema.db));
+        let mut targets: Vec<hir::ModuleDef> = Vec::new();
+        walk_and_push_ty(sema.db, &ty, &mut |item| {
+            if !targets.contains(&item) {
+                targets.push(item);
+            }
+        });
+        res.actions.push(HoverAction::goto_type_from_targets(sema.db, targets));
+    }
+    res.markup = Markup::fenced_block(&desc);
+    Some(RangeInfo::new(placeholder.range, res))
+}
+
+pub(super) fn cfg_macro(
+    sema: &Semantics<'_, RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let path = token.parent_ancestors().take_while(|it| !ast::MacroCall::can_cast(it.kind()));
+    let path = path.filter_map(ast::Path::cast).last()?;
+    let macro_call = path.syntax().parent().and_then(ast::MacroCall::cast)?;
+    let (cfg, opts) = sema.cfg_macro_predicate(&macro_call)?;
+
+    let enabled = opts.check(&cfg)?;
+    let dnf = DnfExpr::new(cfg);
+    let reason = if enabled { dnf.why_active(&opts) } else { dnf.why_inactive(&opts) };
+    let mut markup = format!("```rust\n{enabled}\n```");
+    if let Some(reason) = reason.map(|it| it.to_string()).filter(|it| !it.is_empty()) {
+        format_to!(markup, "\n___\n\n{reason}");
+    }
+    Some(HoverResult { markup: markup.into(), ..Default::default() })
+}
+
 pub(super) fn process_markup(
     db: &RootDatabase,
     def: Definition,
@@ -376,11 +629,13 @@ pub(super) fn path(db: &RootDatabase, module: hir::Module, item_name: Option<Str
 }
 
 pub(super) fn definition(
-    db: &RootDatabase,
+    sema: &Semantics<'_, RootDatabase>,
     def: Definition,
     famous_defs: Option<&FamousDefs<'_, '_>>,
     config: &HoverConfig,
+    const_eval_pool: &ConstEvalPool,
 ) -> Option<Markup> {
+    let db = sema.db;
     let mod_path = definition_mod_path(db, &def);
     let (label, docs) = match def {
         Definition::Macro(it) => label_and_docs(db, it),
@@ -392,14 +647,16 @@ pub(super) fn definition(
                 hir::VariantDef::Struct(s) => Adt::from(s)
                     .layout(db)
                     .ok()
-                    .map(|layout| format!(", offset = {}", layout.fields.offset(id).bytes())),
+                    .and_then(|layout| layout.field_offset(id))
+                    .map(|offset| format!(", offset = {offset}")),
                 _ => None,
             };
             Some(format!(
-                "size = {}, align = {}{}",
-                layout.size.bytes(),
-                layout.align.abi.bytes(),
-                offset.as_deref().unwrap_or_default()
+                "size = {}, align = {}{}{}",
+                layout.size(),
+                layout.align(),
+                offset.as_deref().unwrap_or_default(),
+                render_niches(&layout)
             ))
         }),
         Definition::Module(it) => label_and_docs(db, it),
@@ -407,18 +664,31 @@ pub(super) fn definition(
             if !config.interpret_tests {
                 return None;
             }
-            match it.eval(db) {
-                Ok(()) => Some("pass".into()),
-                Err(MirEvalError::MirLowerError(f, e)) => {
-                    let name = &db.function_data(f).name;
-                    Some(format!("error: fail to lower {name} due {e:?}"))
-                }
-                Err(e) => Some(format!("error: {e:?}")),
+            let results = config
+                .interpret_profiles
+                .iter()
+                .map(|&profile| (profile, interpret_function(db, it, profile)))
+                .collect::<Vec<_>>();
+            match &*results {
+                [] => None,
+                [(_, result)] => Some(result.clone()),
+                // Under several profiles, the results are labeled with them, to be compared.
+                _ => Some(
+                    results
+                        .iter()
+                        .map(|(profile, result)| format!("{profile}: {result}"))
+                        .join(", "),
+                ),
             }
         }),
         Definition::Adt(it) => label_and_layout_info_and_docs(db, it, |&it| {
             let layout = it.layout(db).ok()?;
-            Some(format!("size = {}, align = {}", layout.size.bytes(), layout.align.abi.bytes()))
+            Some(format!(
+                "size = {}, align = {}{}",
+                layout.size(),
+                layout.align(),
+                render_niches(&layout)
+            ))
         }),
         Definition::Variant(it) => label_value_and_docs(db, it, |&it| {
             if !it.parent_enum(db).is_data_carrying(db) {
@@ -430,20 +700,25 @@ pub(super) fn definition(
                 None
             }
         }),
-        Definition::Const(it) => label_value_and_docs(db, it, |it| {
-            let body = it.render_eval(db);
-            match body {
-                Ok(x) => Some(x),
-                Err(_) => {
-                    let source = it.source(db)?;
-                    let mut body = source.value.body()?.syntax().clone();
-                    if source.file_id.is_macro() {
-                        body = insert_whitespace_into_node::insert_ws_into(body);
+        Definition::Const(it) => {
+            let (label, docs) =
+                label_value_and_docs(db, it, |&it| match const_eval_pool.render(db, it) {
+                    ConstEvalState::Done(Some(x)) => Some(x),
+                    ConstEvalState::Evaluating => Some("evaluating…".to_owned()),
+                    ConstEvalState::Done(None) => {
+                        let source = it.source(db)?;
+                        let mut body = source.value.body()?.syntax().clone();
+                        if source.file_id.is_macro() {
+                            body = insert_whitespace_into_node::insert_ws_into(body);
+                        }
+                        Some(body.to_string())
                     }
-                    Some(body.to_string())
-                }
+                });
+            match bool_const_terms(sema, it, const_eval_pool) {
+                Some(terms) => (format!("{label}\n\n{terms}"), docs),
+                None => (label, docs),
             }
-        }),
+        }
         Definition::Static(it) => label_value_and_docs(db, it, |it| {
             let source = it.source(db)?;
             let mut body = source.value.body()?.syntax().clone();
@@ -487,6 +762,105 @@ pub(super) fn definition(
     markup(docs, label, mod_path)
 }
 
+/// Renders the terms of a `bool` const combining other consts and cfgs with `&&` or `||`, like
+/// `FEATURE && cfg!(unix)`, one per line and with their values if they are known.
+fn bool_const_terms(
+    sema: &Semantics<'_, RootDatabase>,
+    konst: hir::Const,
+    const_eval_pool: &ConstEvalPool,
+) -> Option<String> {
+    if !konst.ty(sema.db).is_bool() {
+        return None;
+    }
+    let source = sema.source(konst)?;
+    if source.file_id.is_macro() {
+        return None;
+    }
+    let mut body = source.value.body()?;
+    while let ast::Expr::ParenExpr(paren) = body {
+        body = paren.expr()?;
+    }
+    let ast::Expr::BinExpr(bin_expr) = &body else { return None };
+    let Some(ast::BinaryOp::LogicOp(op)) = bin_expr.op_kind() else { return None };
+
+    let mut terms = Vec::new();
+    collect_bool_terms(body, op, &mut terms);
+    let mut buf = String::new();
+    for (i, term) in terms.iter().enumerate() {
+        if i != 0 {
+            format_to!(buf, "\n{op} ");
+        }
+        format_to!(buf, "{term}");
+        if let Some(value) = bool_value(sema, term, const_eval_pool) {
+            format_to!(buf, " // {value}");
+        }
+    }
+    Some(buf)
+}
+
+/// Flattens a chain of `op`s, like `a && b && c`, into its terms.
+fn collect_bool_terms(expr: ast::Expr, op: ast::LogicOp, terms: &mut Vec<ast::Expr>) {
+    match &expr {
+        ast::Expr::BinExpr(bin_expr) if bin_expr.op_kind() == Some(ast::BinaryOp::LogicOp(op)) => {
+            if let Some(lhs) = bin_expr.lhs() {
+                collect_bool_terms(lhs, op, terms);
+            }
+            if let Some(rhs) = bin_expr.rhs() {
+                collect_bool_terms(rhs, op, terms);
+            }
+        }
+        _ => terms.push(expr),
+    }
+}
+
+/// Computes the value of a boolean expression made of literals, consts and macros like `cfg!`.
+fn bool_value(
+    sema: &Semantics<'_, RootDatabase>,
+    expr: &ast::Expr,
+    const_eval_pool: &ConstEvalPool,
+) -> Option<bool> {
+    match expr {
+        ast::Expr::Literal(literal) => match literal.kind() {
+            ast::LiteralKind::Bool(value) => Some(value),
+            _ => None,
+        },
+        ast::Expr::ParenExpr(paren) => bool_value(sema, &paren.expr()?, const_eval_pool),
+        ast::Expr::PrefixExpr(prefix) if prefix.op_kind() == Some(ast::UnaryOp::Not) => {
+            bool_value(sema, &prefix.expr()?, const_eval_pool).map(|it| !it)
+        }
+        ast::Expr::BinExpr(bin_expr) => {
+            let ast::BinaryOp::LogicOp(op) = bin_expr.op_kind()? else { return None };
+            let lhs = bool_value(sema, &bin_expr.lhs()?, const_eval_pool);
+            let rhs = bool_value(sema, &bin_expr.rhs()?, const_eval_pool);
+            // One known term can be enough to know the value.
+            match (op, lhs, rhs) {
+                (ast::LogicOp::And, Some(false), _) | (ast::LogicOp::And, _, Some(false)) => {
+                    Some(false)
+                }
+                (ast::LogicOp::Or, Some(true), _) | (ast::LogicOp::Or, _, Some(true)) => Some(true),
+                (ast::LogicOp::And, Some(_), Some(_)) => Some(true),
+                (ast::LogicOp::Or, Some(_), Some(_)) => Some(false),
+                _ => None,
+            }
+        }
+        ast::Expr::PathExpr(path) => match sema.resolve_path(&path.path()?)? {
+            PathResolution::Def(ModuleDef::Const(konst)) => {
+                match const_eval_pool.render(sema.db, konst) {
+                    ConstEvalState::Done(Some(value)) => value.parse().ok(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        ast::Expr::MacroExpr(macro_expr) => {
+            let expansion = sema.expand(&macro_expr.macro_call()?)?;
+            let expr = expansion.descendants().find_map(ast::Expr::cast)?;
+            bool_value(sema, &expr, const_eval_pool)
+        }
+        _ => None,
+    }
+}
+
 fn type_info(
     sema: &Semantics<'_, RootDatabase>,
     _config: &HoverConfig,
@@ -571,6 +945,14 @@ where
     (label, docs)
 }
 
+/// Renders the niches of a type with a layout, if it has some.
+fn render_niches(layout: &hir::Layout) -> String {
+    match layout.niches() {
+        Some(niches) if niches > 0 => format!(", niches = {niches}"),
+        _ => String::new(),
+    }
+}
+
 fn label_value_and_docs<D, E, V>(
     db: &RootDatabase,
     def: D,
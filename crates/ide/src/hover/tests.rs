@@ -10,6 +10,7 @@ const HOVER_BASE_CONFIG: HoverConfig = HoverConfig {
     format: HoverDocFormat::Markdown,
     keywords: true,
     interpret_tests: false,
+    interpret_profiles: Vec::new(),
 };
 
 fn check_hover_no_result(ra_fixture: &str) {
@@ -472,6 +473,42 @@ struct Foo { fiel$0d_a: u8, field_b: i32, field_c: i16 }
     );
 }
 
+#[test]
+fn hover_niches() {
+    check(
+        r#"
+struct Foo { fiel$0d_a: bool, field_b: u32 }
+"#,
+        expect![[r#"
+            *field_a*
+
+            ```rust
+            test::Foo
+            ```
+
+            ```rust
+            field_a: bool // size = 1, align = 1, offset = 4, niches = 254
+            ```
+        "#]],
+    );
+    check(
+        r#"
+enum Foo$0 { A(char), B }
+"#,
+        expect![[r#"
+            *Foo*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            enum Foo // size = 4, align = 4, niches = 4293853183
+            ```
+        "#]],
+    );
+}
+
 #[test]
 fn hover_shows_struct_field_info() {
     // Hovering over the field when instantiating
@@ -2938,7 +2975,7 @@ struct S$0T<const C: usize = 1, T = Foo>(T);
             ```
 
             ```rust
-            struct ST<const C: usize, T = Foo>
+            struct ST<const C: usize = 1, T = Foo>
             ```
         "#]],
     );
@@ -4065,6 +4102,32 @@ fn test() {
     );
 }
 
+#[test]
+fn hover_const_eval_offset_of() {
+    check(
+        r#"
+//- minicore: offset_of
+#[repr(C)]
+struct S {
+    a: u8,
+    b: u32,
+}
+const OFFSET$0: usize = core::mem::offset_of!(S, b);
+"#,
+        expect![[r#"
+            *OFFSET*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            const OFFSET: usize = 4 (0x4)
+            ```
+        "#]],
+    );
+}
+
 #[test]
 fn hover_const_pat() {
     check(
@@ -5843,3 +5906,231 @@ fn test() {
         "#]],
     );
 }
+
+#[test]
+fn hover_asm_operand() {
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+fn f(a: u32) {
+    let mut b = 0u64;
+    asm!("add {0:e}, {$0b}", in(reg) a, b = inout(reg) b);
+}
+"#,
+        expect![[r#"
+            *{b}*
+            ```rust
+            b = inout(reg): u64
+            ```
+        "#]],
+    );
+    check(
+        r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+fn f(a: u32) {
+    asm!("mov {1:e}, {$0}", in(reg) &a, in(reg) a, options(nostack));
+}
+"#,
+        expect![[r#"
+            *{}*
+            ```rust
+            in(reg): &u32
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_cfg_macro() {
+    check(
+        r#"
+//- /main.rs cfg:feature=x
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn f() -> bool {
+    cfg$0!(any(feature = "y", all(feature = "x", not(test))))
+}
+"#,
+        expect![[r#"
+            *cfg*
+            ```rust
+            true
+            ```
+            ___
+
+            feature = "x" is enabled and test is disabled
+        "#]],
+    );
+    check(
+        r#"
+//- /main.rs cfg:feature=x
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn f() -> bool {
+    cfg$0!(all(feature = "x", feature = "y"))
+}
+"#,
+        expect![[r#"
+            *cfg*
+            ```rust
+            false
+            ```
+            ___
+
+            feature = "y" is disabled
+        "#]],
+    );
+}
+
+#[test]
+fn hover_bool_const_terms() {
+    check(
+        r#"
+//- /main.rs cfg:feature=x
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+const FEATURE_X: bool = cfg!(feature = "x");
+const DEBUG: bool = false;
+const ENABLED$0: bool = FEATURE_X && (cfg!(feature = "y") || !DEBUG) && true;
+"#,
+        expect![[r#"
+            *ENABLED*
+
+            ```rust
+            test
+            ```
+
+            ```rust
+            const ENABLED: bool = true
+
+            FEATURE_X // true
+            && (cfg!(feature = "y") || !DEBUG) // true
+            && true // true
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn hover_closure_captures() {
+    check(
+        r#"
+//- minicore: copy, fn
+fn main() {
+    let x = 5;
+    let mut y = 6;
+    let c = |$0| {
+        let a = x;
+        y = a;
+    };
+}
+"#,
+        expect![[r#"
+            *||*
+            ```rust
+            impl FnMut()
+            ```
+
+            ## Captures
+            * `x` by immutable borrow
+            * `y` by mutable borrow
+
+            This closure is `FnMut` because it mutates `y`
+        "#]],
+    );
+}
+
+#[test]
+fn hover_closure_disjoint_captures() {
+    check(
+        r#"
+//- minicore: copy, fn
+struct Point { x: i32, y: (i32, i32) }
+struct Line { start: Point, end: Point }
+fn main() {
+    let line = Line { start: Point { x: 0, y: (0, 0) }, end: Point { x: 1, y: (1, 1) } };
+    let mut other = Line { start: Point { x: 0, y: (0, 0) }, end: Point { x: 1, y: (1, 1) } };
+    let r = &mut other;
+    let c = |$0| {
+        let a = line.start.x;
+        r.end.y.1 = a;
+    };
+}
+"#,
+        expect![[r#"
+            *||*
+            ```rust
+            impl FnMut()
+            ```
+
+            ## Captures
+            * `line.start.x` by immutable borrow
+            * `(*r).end.y.1` by mutable borrow
+
+            This closure is `FnMut` because it mutates `(*r).end.y.1`
+        "#]],
+    );
+}
+
+#[test]
+fn hover_closure_captures_not_send_or_static() {
+    check(
+        r#"
+//- minicore: fn, send, sync
+struct Rc(*const u8);
+fn make_rc() -> Rc { loop {} }
+fn spawn<F: FnOnce() + Send + 'static>(f: F) {}
+fn main() {
+    let rc = make_rc();
+    let x = 0u8;
+    spawn(|$0| {
+        let r = rc;
+        let y = &x;
+    });
+}
+"#,
+        expect![[r#"
+            *||*
+            ```rust
+            impl FnOnce()
+            ```
+
+            ## Captures
+            * `rc` by value
+            * `x` by immutable borrow
+
+            This closure is `FnOnce` because it moves `rc`
+
+            `spawn` requires the closure to be `Send + 'static`:
+            * `rc` is captured as `Rc`, which is not `Send` because its field `0` has type `*const u8`, which is not `Send`
+            * `x` is captured by reference, which doesn't live for `'static`; consider using a `move` closure
+        "#]],
+    );
+}
+
+#[test]
+fn hover_future_not_send() {
+    check_hover_range(
+        r#"
+//- minicore: future, send, sync
+struct Rc(*const u8);
+fn make_rc() -> Rc { loop {} }
+async fn yield_now() {}
+async fn work() {
+    let rc = make_rc();
+    yield_now().await;
+}
+fn main() {
+    let fut = $0work()$0;
+}
+"#,
+        expect![[r#"
+            ```rust
+            impl Future<Output = ()>
+            ```
+
+            This future is not `Send` because `rc` is held across an `.await` and has type `Rc`, which is not `Send` because its field `0` has type `*const u8`, which is not `Send`"#]],
+    );
+}
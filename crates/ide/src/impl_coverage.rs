@@ -0,0 +1,256 @@
+use hir::{Semantics, Type};
+use ide_db::{
+    base_db::{CrateOrigin, FilePosition, FileRange},
+    defs::{Definition, NameClass, NameRefClass},
+    FxHashSet, RootDatabase,
+};
+use syntax::{ast, AstNode};
+
+use crate::{NavigationTarget, TryToNav};
+
+/// The workspace types which look like they should implement a trait, but don't.
+#[derive(Debug)]
+pub struct ImplCoverage {
+    pub trait_name: String,
+    pub missing: Vec<MissingImpl>,
+}
+
+#[derive(Debug)]
+pub struct MissingImpl {
+    pub ty: NavigationTarget,
+    pub reason: MissingImplReason,
+}
+
+/// Why a type looks like it should implement the trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingImplReason {
+    /// The name of the type contains the name of the trait, like `FooPlugin` for `Plugin`.
+    Name,
+    /// The type is passed, at this argument, to a function expecting implementors of the trait.
+    Registered(FileRange),
+}
+
+// Feature: Impl Coverage
+//
+// Lists the workspace types which look like they should implement the trait under the cursor, but
+// don't. These are the types whose name contains the name of the trait, and the types passed to
+// functions expecting implementors of the trait, like `fn register(plugin: Box<dyn Plugin>)` or
+// `fn register<P: Plugin>(plugin: P)`.
+pub(crate) fn impl_coverage(db: &RootDatabase, position: FilePosition) -> Option<ImplCoverage> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let trait_ = sema
+        .find_nodes_at_offset_with_descend::<ast::NameLike>(file.syntax(), position.offset)
+        .find_map(|name_like| match name_like {
+            ast::NameLike::Name(name) => match NameClass::classify(&sema, &name)? {
+                NameClass::Definition(Definition::Trait(it)) => Some(it),
+                _ => None,
+            },
+            ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(&sema, &name_ref)? {
+                NameRefClass::Definition(Definition::Trait(it)) => Some(it),
+                _ => None,
+            },
+            ast::NameLike::Lifetime(_) => None,
+        })?;
+    let trait_name = trait_.name(db).to_smol_str();
+
+    let implementors: FxHashSet<hir::Adt> = hir::Impl::all_for_trait(db, trait_)
+        .into_iter()
+        .filter_map(|it| it.self_ty(db).as_adt())
+        .collect();
+    // Blanket impls are only checked for types without generic parameters, and for traits without
+    // them either, as the other ones need the arguments to check.
+    let trait_has_params = trait_.type_or_const_param_count(db, false) > 0;
+    let is_missing = |adt: hir::Adt| {
+        is_local(db, adt.module(db).krate())
+            && !implementors.contains(&adt)
+            && (trait_has_params
+                || adt.has_non_default_type_params(db)
+                || !adt.ty(db).impls_trait(db, trait_, &[]))
+    };
+
+    let mut missing = Vec::new();
+    let mut reported = FxHashSet::default();
+    let mut report = |adt: hir::Adt, reason: MissingImplReason| {
+        if reported.insert(adt) {
+            if let Some(ty) = adt.try_to_nav(db) {
+                missing.push(MissingImpl { ty, reason });
+            }
+        }
+    };
+
+    for krate in hir::Crate::all(db).into_iter().filter(|&it| is_local(db, it)) {
+        for module in krate.modules(db) {
+            for decl in module.declarations(db) {
+                let hir::ModuleDef::Adt(adt) = decl else { continue };
+                let name = adt.name(db).to_smol_str();
+                if name != trait_name && name.contains(trait_name.as_str()) && is_missing(adt) {
+                    report(adt, MissingImplReason::Name);
+                }
+            }
+        }
+    }
+
+    for (function, params) in registration_functions(&sema, trait_) {
+        let self_param = function.self_param(db).is_some();
+        for (file_id, references) in Definition::Function(function).usages(&sema).all() {
+            for reference in references {
+                let Some(name_ref) = reference.name.as_name_ref() else { continue };
+                let Some((args, is_method)) = call_args(name_ref) else { continue };
+                for (idx, arg) in args.into_iter().enumerate() {
+                    let idx = if is_method && self_param { idx + 1 } else { idx };
+                    if !params.contains(&idx) {
+                        continue;
+                    }
+                    let Some(ty) = sema.type_of_expr(&arg) else { continue };
+                    let range = FileRange { file_id, range: arg.syntax().text_range() };
+                    ty.original.walk(db, |ty| {
+                        if let Some(adt) = ty.as_adt() {
+                            if is_missing(adt) {
+                                report(adt, MissingImplReason::Registered(range));
+                            }
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    Some(ImplCoverage { trait_name: trait_name.to_string(), missing })
+}
+
+fn is_local(db: &RootDatabase, krate: hir::Crate) -> bool {
+    matches!(krate.origin(db), CrateOrigin::Local { .. })
+}
+
+/// Returns the functions with parameters expecting implementors of `trait_` in their signature,
+/// with the indices of these parameters, counting `self`.
+fn registration_functions(
+    sema: &Semantics<'_, RootDatabase>,
+    trait_: hir::Trait,
+) -> Vec<(hir::Function, Vec<usize>)> {
+    let db = sema.db;
+    let mut seen = FxHashSet::default();
+    let mut result = Vec::new();
+    for (_, references) in Definition::Trait(trait_).usages(sema).all() {
+        for reference in references {
+            let Some(fn_) = reference.name.syntax().ancestors().find_map(ast::Fn::cast) else {
+                continue;
+            };
+            // Only references in the signature, not the ones in the body.
+            if fn_.body().map_or(false, |body| {
+                body.syntax().text_range().contains_range(reference.name.syntax().text_range())
+            }) {
+                continue;
+            }
+            let Some(function) = sema.to_def(&fn_) else { continue };
+            if !seen.insert(function) {
+                continue;
+            }
+            let params: Vec<_> = function
+                .assoc_fn_params(db)
+                .iter()
+                .enumerate()
+                .filter(|(_, param)| expects_implementors(db, param.ty(), trait_))
+                .map(|(idx, _)| idx)
+                .collect();
+            if !params.is_empty() {
+                result.push((function, params));
+            }
+        }
+    }
+    result
+}
+
+/// Returns whether `ty` is made of `dyn Trait`, `impl Trait` or a generic parameter bounded by
+/// `Trait`.
+fn expects_implementors(db: &RootDatabase, ty: &Type, trait_: hir::Trait) -> bool {
+    let mut found = false;
+    ty.walk(db, |ty| {
+        found |= ty.as_dyn_trait() == Some(trait_)
+            || ty.as_type_param(db).map_or(false, |it| it.trait_bounds(db).contains(&trait_));
+    });
+    found
+}
+
+/// Returns the arguments of the call of `name_ref`, and whether it is a method call.
+fn call_args(name_ref: &ast::NameRef) -> Option<(Vec<ast::Expr>, bool)> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        return Some((call.arg_list()?.args().collect(), true));
+    }
+    let path_expr = name_ref
+        .syntax()
+        .ancestors()
+        .take_while(|it| !ast::CallExpr::can_cast(it.kind()))
+        .last()?;
+    let call = path_expr.parent().and_then(ast::CallExpr::cast)?;
+    if call.expr()?.syntax() != &path_expr {
+        return None;
+    }
+    Some((call.arg_list()?.args().collect(), false))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    use super::MissingImplReason;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let coverage = analysis.impl_coverage(position).unwrap().unwrap();
+        let mut actual = format!("{}\n", coverage.trait_name);
+        for missing in coverage.missing {
+            let reason = match missing.reason {
+                MissingImplReason::Name => "name".to_owned(),
+                MissingImplReason::Registered(range) => format!("registered at {:?}", range.range),
+            };
+            actual += &format!("{}: {reason}\n", missing.ty.name);
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn missing_impls() {
+        check(
+            r#"
+trait Plugin$0 {}
+trait Other {}
+impl<T: Other> Plugin for T {}
+
+struct AudioPlugin;
+impl Plugin for AudioPlugin {}
+struct VideoPlugin;
+struct CachedPlugin;
+impl Other for CachedPlugin {}
+struct Wrapper<T>(T);
+struct Logger;
+struct Unrelated;
+
+fn register(plugin: &dyn Plugin) {}
+fn register_all<P: Plugin>(plugins: [P; 1]) {}
+struct Registry;
+impl Registry {
+    fn add(&mut self, plugin: impl Plugin) {}
+}
+
+fn main() {
+    register(&AudioPlugin);
+    register_all([Wrapper(Logger)]);
+    Registry.add(Unrelated);
+    let _ = Unrelated;
+}
+"#,
+            expect![[r#"
+                Plugin
+                VideoPlugin: name
+                Wrapper: registered at 464..481
+                Logger: registered at 464..481
+                Unrelated: registered at 501..510
+            "#]],
+        );
+    }
+}
@@ -43,15 +43,77 @@ pub struct InlayHintsConfig {
     pub adjustment_hints_hide_outside_unsafe: bool,
     pub closure_return_type_hints: ClosureReturnTypeHints,
     pub binding_mode_hints: bool,
+    /// Shows the `ref mut` match ergonomics give to bindings, without the other binding modes.
+    pub implicit_ref_mut_hints: bool,
     pub lifetime_elision_hints: LifetimeElisionHints,
     pub param_names_for_lifetime_elision_hints: bool,
     pub hide_named_constructor_hints: bool,
     pub hide_closure_initialization_hints: bool,
+    pub type_hint_filters: Vec<TypeHintFilter>,
     pub closure_style: ClosureStyle,
     pub max_length: Option<usize>,
     pub closing_brace_hints_min_lines: Option<usize>,
+    pub fields_to_resolve: InlayFieldsToResolve,
 }
 
+/// The parts of the hints which are expensive to compute, and which the client can ask for lazily.
+/// These are left out by [`crate::Analysis::inlay_hints`], and filled in by
+/// [`crate::Analysis::inlay_hints_resolve`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InlayFieldsToResolve {
+    /// The text edits inserting the rendered types of type hints.
+    pub resolve_text_edits: bool,
+    /// The locations the parts of the labels of type hints link to.
+    pub resolve_label_location: bool,
+}
+
+/// A rule hiding the type hints of the bindings it matches. All the conditions which are set have
+/// to match, and a rule without conditions matches nothing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeHintFilter {
+    /// Matches the rendered type, as shown by the hint.
+    pub ty: Option<HintPattern>,
+    /// Matches the text of the initializer of the binding.
+    pub initializer: Option<HintPattern>,
+    /// Matches the path of the module of the binding, like `crate::tests`.
+    pub module: Option<HintPattern>,
+    /// Matches the rendered types which are longer than this.
+    pub longer_than: Option<usize>,
+    /// Matches the types whose name appears in the initializer, like `Vec` in
+    /// `Vec::<u8>::with_capacity(8)`.
+    pub repeats_initializer: bool,
+}
+
+/// A regular expression which has to match the whole text.
+#[derive(Clone, Debug)]
+pub struct HintPattern {
+    source: String,
+    regex: regex::Regex,
+}
+
+impl HintPattern {
+    pub fn new(source: &str) -> Result<HintPattern, String> {
+        let regex = regex::Regex::new(&format!("^(?:{source})$")).map_err(|e| e.to_string())?;
+        Ok(HintPattern { source: source.to_owned(), regex })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for HintPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for HintPattern {}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ClosureReturnTypeHints {
     Always,
@@ -88,7 +150,7 @@ pub enum AdjustmentHintsMode {
     PreferPostfix,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum InlayKind {
     BindingMode,
     Chaining,
@@ -116,6 +178,9 @@ pub struct InlayHint {
     pub label: InlayHintLabel,
     /// Text edit to apply when "accepting" this inlay hint.
     pub text_edit: Option<TextEdit>,
+    /// Whether some of the fields of [`InlayHintsConfig::fields_to_resolve`] were left out, and
+    /// the hint has to be resolved with [`crate::Analysis::inlay_hints_resolve`] to get them.
+    pub needs_resolve: bool,
 }
 
 impl InlayHint {
@@ -125,6 +190,7 @@ impl InlayHint {
             kind: InlayKind::ClosingParenthesis,
             label: InlayHintLabel::from(")"),
             text_edit: None,
+            needs_resolve: false,
         }
     }
     fn opening_paren(range: TextRange) -> InlayHint {
@@ -133,6 +199,7 @@ impl InlayHint {
             kind: InlayKind::OpeningParenthesis,
             label: InlayHintLabel::from("("),
             text_edit: None,
+            needs_resolve: false,
         }
     }
 }
@@ -255,6 +322,8 @@ struct InlayHintLabelBuilder<'a> {
     result: InlayHintLabel,
     last_part: String,
     location: Option<FileRange>,
+    /// Whether the locations are resolved lazily, in which case the parts are split but not linked.
+    resolve_locations: bool,
 }
 
 impl fmt::Write for InlayHintLabelBuilder<'_> {
@@ -269,6 +338,9 @@ impl HirWrite for InlayHintLabelBuilder<'_> {
             never!("location link is already started");
         }
         self.make_new_part();
+        if self.resolve_locations {
+            return;
+        }
         let Some(location) = ModuleDef::from(def).try_to_nav(self.db) else { return };
         let location =
             FileRange { file_id: location.file_id, range: location.focus_or_full_range() };
@@ -353,13 +425,30 @@ fn label_of_ty(
         last_part: String::new(),
         location: None,
         result: InlayHintLabel::default(),
+        resolve_locations: config.fields_to_resolve.resolve_label_location,
     };
     let _ = rec(sema, famous_defs, config.max_length, ty, &mut label_builder, config);
     let r = label_builder.finish();
     Some(r)
 }
 
+/// Returns the text edit inserting `ty`, unless text edits are resolved lazily. Returns whether
+/// the hint has to be resolved as well.
 fn ty_to_text_edit(
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    node_for_hint: &SyntaxNode,
+    ty: &hir::Type,
+    offset_to_insert: TextSize,
+    prefix: String,
+) -> (Option<TextEdit>, bool) {
+    if config.fields_to_resolve.resolve_text_edits {
+        return (None, true);
+    }
+    (render_ty_edit(sema, node_for_hint, ty, offset_to_insert, prefix), false)
+}
+
+fn render_ty_edit(
     sema: &Semantics<'_, RootDatabase>,
     node_for_hint: &SyntaxNode,
     ty: &hir::Type,
@@ -426,6 +515,38 @@ pub(crate) fn inlay_hints(
     acc
 }
 
+/// Computes the hint at `resolve_range` whose hash is `hash` again, with all the fields the first
+/// computation left out. Only the nodes the hint can originate from are visited, which are the
+/// ones covering its range.
+pub(crate) fn inlay_hints_resolve(
+    db: &RootDatabase,
+    file_id: FileId,
+    resolve_range: TextRange,
+    hash: u64,
+    config: &InlayHintsConfig,
+    hasher: impl Fn(&InlayHint) -> u64,
+) -> Option<InlayHint> {
+    let _p = profile::span("inlay_hints_resolve");
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let file = file.syntax();
+
+    let scope = sema.scope(file)?;
+    let famous_defs = FamousDefs(&sema, scope.krate());
+    let config =
+        InlayHintsConfig { fields_to_resolve: InlayFieldsToResolve::default(), ..config.clone() };
+
+    let node = match file.covering_element(resolve_range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent()?,
+    };
+    let mut acc = Vec::new();
+    for node in node.ancestors() {
+        hints(&mut acc, &famous_defs, &config, file_id, node);
+    }
+    acc.into_iter().find(|hint| hint.range == resolve_range && hasher(hint) == hash)
+}
+
 fn hints(
     hints: &mut Vec<InlayHint>,
     famous_defs @ FamousDefs(sema, _): &FamousDefs<'_, '_>,
@@ -521,9 +642,9 @@ mod tests {
     use itertools::Itertools;
     use test_utils::extract_annotations;
 
-    use crate::inlay_hints::{AdjustmentHints, AdjustmentHintsMode};
+    use crate::inlay_hints::{AdjustmentHints, AdjustmentHintsMode, InlayFieldsToResolve};
     use crate::DiscriminantHints;
-    use crate::{fixture, inlay_hints::InlayHintsConfig, LifetimeElisionHints};
+    use crate::{fixture, inlay_hints::InlayHintsConfig, InlayHint, LifetimeElisionHints};
 
     use super::ClosureReturnTypeHints;
 
@@ -539,12 +660,18 @@ mod tests {
         adjustment_hints_mode: AdjustmentHintsMode::Prefix,
         adjustment_hints_hide_outside_unsafe: false,
         binding_mode_hints: false,
+        implicit_ref_mut_hints: false,
         hide_named_constructor_hints: false,
         hide_closure_initialization_hints: false,
+        type_hint_filters: Vec::new(),
         closure_style: ClosureStyle::ImplFn,
         param_names_for_lifetime_elision_hints: false,
         max_length: None,
         closing_brace_hints_min_lines: None,
+        fields_to_resolve: InlayFieldsToResolve {
+            resolve_text_edits: false,
+            resolve_label_location: false,
+        },
     };
     pub(super) const TEST_CONFIG: InlayHintsConfig = InlayHintsConfig {
         type_hints: true,
@@ -614,6 +741,40 @@ mod tests {
         assert!(edits.is_empty(), "unexpected edits: {edits:?}");
     }
 
+    #[test]
+    fn resolve_lazy_fields() {
+        let config = InlayHintsConfig {
+            fields_to_resolve: InlayFieldsToResolve {
+                resolve_text_edits: true,
+                resolve_label_location: true,
+            },
+            ..TEST_CONFIG
+        };
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct Struct;
+fn main() {
+    let t = (Struct, 0u32);
+}"#,
+        );
+        let hasher = |hint: &InlayHint| hint.label.to_string().len() as u64;
+
+        let hints = analysis.inlay_hints(&config, file_id, None).unwrap();
+        let [hint] = &*hints else { panic!("expected a single hint: {hints:?}") };
+        assert!(hint.needs_resolve);
+        assert!(hint.text_edit.is_none());
+        assert!(hint.label.parts.iter().all(|part| part.linked_location.is_none()));
+
+        let resolved = analysis
+            .inlay_hints_resolve(&config, file_id, hint.range, hasher(hint), hasher)
+            .unwrap()
+            .expect("hint wasn't resolved");
+        assert!(!resolved.needs_resolve);
+        assert_eq!(resolved.label.to_string(), "(Struct, u32)");
+        assert!(resolved.text_edit.is_some());
+        assert!(resolved.label.parts.iter().any(|part| part.linked_location.is_some()));
+    }
+
     #[test]
     fn hints_disabled() {
         check_with_config(
@@ -136,6 +136,7 @@ pub(super) fn hints(
                 None,
             ),
             text_edit: None,
+            needs_resolve: false,
         });
     }
     if !postfix && needs_inner_parens {
@@ -14,7 +14,7 @@ use syntax::{
 
 use crate::{
     inlay_hints::{closure_has_block_body, label_of_ty, ty_to_text_edit},
-    InlayHint, InlayHintsConfig, InlayKind,
+    InlayHint, InlayHintsConfig, InlayKind, TypeHintFilter,
 };
 
 pub(super) fn hints(
@@ -44,21 +44,29 @@ pub(super) fn hints(
         return None;
     }
 
+    if !config.type_hint_filters.is_empty() {
+        let label = label.to_string();
+        if config.type_hint_filters.iter().any(|filter| is_filtered(sema, filter, pat, &label)) {
+            return None;
+        }
+    }
+
     let type_annotation_is_valid = desc_pat
         .syntax()
         .parent()
         .map(|it| ast::LetStmt::can_cast(it.kind()) || ast::Param::can_cast(it.kind()))
         .unwrap_or(false);
-    let text_edit = if type_annotation_is_valid {
+    let (text_edit, text_edit_needs_resolve) = if type_annotation_is_valid {
         ty_to_text_edit(
             sema,
+            config,
             desc_pat.syntax(),
             &ty,
             pat.syntax().text_range().end(),
             String::from(": "),
         )
     } else {
-        None
+        (None, false)
     };
 
     acc.push(InlayHint {
@@ -69,6 +77,7 @@ pub(super) fn hints(
         kind: InlayKind::Type,
         label,
         text_edit,
+        needs_resolve: text_edit_needs_resolve || config.fields_to_resolve.resolve_label_location,
     });
 
     Some(())
@@ -178,6 +187,82 @@ fn is_named_constructor(
     (ctor_name == ty_name).then_some(())
 }
 
+/// Returns whether `filter` hides the hint `label` of `pat`.
+fn is_filtered(
+    sema: &Semantics<'_, RootDatabase>,
+    filter: &TypeHintFilter,
+    pat: &ast::IdentPat,
+    label: &str,
+) -> bool {
+    let TypeHintFilter { ty, initializer, module, longer_than, repeats_initializer } = filter;
+    if ty.is_none()
+        && initializer.is_none()
+        && module.is_none()
+        && longer_than.is_none()
+        && !repeats_initializer
+    {
+        return false;
+    }
+
+    if ty.as_ref().map_or(false, |it| !it.is_match(label)) {
+        return false;
+    }
+    if longer_than.map_or(false, |it| label.chars().count() <= it) {
+        return false;
+    }
+    if initializer.is_some() || *repeats_initializer {
+        let Some(init) = pat.syntax().parent().and_then(|it| {
+            match_ast! {
+                match it {
+                    ast::LetStmt(it) => it.initializer(),
+                    ast::LetExpr(it) => it.expr(),
+                    _ => None,
+                }
+            }
+        }) else {
+            return false;
+        };
+        let init = init.syntax().text().to_string();
+        if initializer.as_ref().map_or(false, |it| !it.is_match(&init)) {
+            return false;
+        }
+        if *repeats_initializer && !contains_word(&init, type_name(label)) {
+            return false;
+        }
+    }
+    if let Some(module) = module {
+        let Some(scope) = sema.scope(pat.syntax()) else { return false };
+        let path = scope
+            .module()
+            .path_to_root(sema.db)
+            .into_iter()
+            .rev()
+            .map(|it| it.name(sema.db).map_or("crate".to_owned(), |name| name.to_string()))
+            .join("::");
+        if !module.is_match(&path) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns the name of the rendered type `ty`, without its references and generic arguments.
+fn type_name(ty: &str) -> &str {
+    let ty = ty.trim_start_matches('&');
+    let ty = ty.strip_prefix("mut ").unwrap_or(ty);
+    ty.split('<').next().unwrap_or(ty)
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+    !word.is_empty()
+        && text.match_indices(word).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + word.len()..].chars().next();
+            !before.map_or(false, is_ident_char) && !after.map_or(false, is_ident_char)
+        })
+}
+
 fn pat_is_enum_variant(db: &RootDatabase, bind_pat: &ast::IdentPat, pat_ty: &hir::Type) -> bool {
     if let Some(hir::Adt::Enum(enum_data)) = pat_ty.as_adt() {
         let pat_text = bind_pat.to_string();
@@ -200,7 +285,9 @@ mod tests {
     use syntax::{TextRange, TextSize};
     use test_utils::extract_annotations;
 
-    use crate::{fixture, inlay_hints::InlayHintsConfig, ClosureReturnTypeHints};
+    use crate::{
+        fixture, inlay_hints::InlayHintsConfig, ClosureReturnTypeHints, HintPattern, TypeHintFilter,
+    };
 
     use crate::inlay_hints::tests::{
         check, check_edit, check_no_edit, check_with_config, DISABLED_CONFIG, TEST_CONFIG,
@@ -785,6 +872,63 @@ fn fallible() -> ControlFlow<()> {
         );
     }
 
+    #[test]
+    fn type_hint_filters() {
+        let pattern = |it| Some(HintPattern::new(it).unwrap());
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                type_hint_filters: vec![
+                    TypeHintFilter { ty: pattern("Wrapper<.*>"), ..Default::default() },
+                    TypeHintFilter { repeats_initializer: true, ..Default::default() },
+                    TypeHintFilter {
+                        initializer: pattern("make_.*"),
+                        longer_than: Some(5),
+                        ..Default::default()
+                    },
+                    TypeHintFilter { module: pattern("crate::tests"), ..Default::default() },
+                    TypeHintFilter::default(),
+                ],
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Wrapper<T>(T);
+struct Generic<T>(T);
+impl<T> Generic<T> {
+    fn new(it: T) -> Self { Generic(it) }
+}
+struct LongName;
+fn make_long() -> LongName { LongName }
+fn make_short() -> u8 { 0 }
+fn long() -> LongName { LongName }
+
+fn main() {
+    let wrapper = Wrapper(0u8);
+    let generic = Generic::<u8>::new(0);
+    let by_ref = &Generic::new(0u8);
+    let field = Wrapper(Generic(0u8)).0;
+    let long = make_long();
+    let short = make_short();
+    //  ^^^^^ u8
+    let other = long();
+    //  ^^^^^ LongName
+}
+
+mod tests {
+    fn f() {
+        let x = 0u8;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn invalid_hint_pattern() {
+        assert!(HintPattern::new("Vec<(").is_err());
+        assert_eq!(HintPattern::new("Vec<.*>").unwrap().as_str(), "Vec<.*>");
+    }
+
     #[test]
     fn shows_constructor_type_hints_when_enabled() {
         check_types(
@@ -16,6 +16,9 @@ pub(super) fn hints(
     pat: &ast::Pat,
 ) -> Option<()> {
     if !config.binding_mode_hints {
+        if config.implicit_ref_mut_hints {
+            implicit_ref_mut_hint(acc, sema, pat);
+        }
         return None;
     }
 
@@ -54,6 +57,7 @@ pub(super) fn hints(
             kind: InlayKind::BindingMode,
             label: r.to_string().into(),
             text_edit: None,
+            needs_resolve: false,
         });
     });
     match pat {
@@ -69,6 +73,7 @@ pub(super) fn hints(
                 kind: InlayKind::BindingMode,
                 label: bm.to_string().into(),
                 text_edit: None,
+                needs_resolve: false,
             });
         }
         ast::Pat::OrPat(pat) if !pattern_adjustments.is_empty() && outer_paren_pat.is_none() => {
@@ -81,6 +86,31 @@ pub(super) fn hints(
     Some(())
 }
 
+fn implicit_ref_mut_hint(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    pat: &ast::Pat,
+) -> Option<()> {
+    let ast::Pat::IdentPat(ident) = pat else {
+        return None;
+    };
+    if ident.ref_token().is_some() || ident.mut_token().is_some() {
+        return None;
+    }
+    let info = sema.type_of_pat_with_adjustments(pat)?;
+    if info.binding_mode != Some(hir::BindingMode::Ref(Mutability::Mut)) {
+        return None;
+    }
+    acc.push(InlayHint {
+        range: ident.syntax().text_range(),
+        kind: InlayKind::BindingMode,
+        label: "ref mut".into(),
+        text_edit: None,
+        needs_resolve: false,
+    });
+    Some(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -154,4 +184,24 @@ fn foo(s @ Struct { field, .. }: &Struct) {}
 "#,
         );
     }
+
+    #[test]
+    fn hints_implicit_ref_mut() {
+        check_with_config(
+            InlayHintsConfig { implicit_ref_mut_hints: true, ..DISABLED_CONFIG },
+            r#"
+fn __(v: &mut (u32, u32)) {
+    let (x, y) = v;
+       //^ ref mut
+          //^ ref mut
+    let (x,) = &(0,);
+    let (ref mut x,) = &mut (0,);
+    match &mut Some(0) {
+        Some(x) => (),
+           //^ ref mut
+        None => (),
+    }
+}"#,
+        );
+    }
 }
@@ -62,6 +62,7 @@ pub(super) fn hints(
                 kind: InlayKind::Chaining,
                 label: label_of_ty(famous_defs, config, ty)?,
                 text_edit: None,
+                needs_resolve: config.fields_to_resolve.resolve_label_location,
             });
         }
     }
@@ -122,6 +123,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 147..154,
@@ -143,6 +145,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                 ]
             "#]],
@@ -209,6 +212,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 143..179,
@@ -230,6 +234,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                 ]
             "#]],
@@ -280,6 +285,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 143..179,
@@ -301,6 +307,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                 ]
             "#]],
@@ -365,6 +372,7 @@ fn main() {
                             "<i32, bool>>",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 246..265,
@@ -399,6 +407,7 @@ fn main() {
                             "<i32, bool>>",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                 ]
             "#]],
@@ -465,6 +474,7 @@ fn main() {
                             " = ()>",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 174..224,
@@ -499,6 +509,7 @@ fn main() {
                             " = ()>",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 174..206,
@@ -533,6 +544,7 @@ fn main() {
                             " = ()>",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 174..189,
@@ -554,6 +566,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                 ]
             "#]],
@@ -613,6 +626,7 @@ fn main() {
                                 ],
                             },
                         ),
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 145..185,
@@ -634,6 +648,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 145..168,
@@ -655,6 +670,7 @@ fn main() {
                             "",
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                     InlayHint {
                         range: 222..228,
@@ -674,6 +690,7 @@ fn main() {
                             },
                         ],
                         text_edit: None,
+                        needs_resolve: false,
                     },
                 ]
             "#]],
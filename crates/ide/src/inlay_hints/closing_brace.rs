@@ -113,6 +113,7 @@ pub(super) fn hints(
         kind: InlayKind::ClosingBrace,
         label: InlayHintLabel::simple(label, None, linked_location),
         text_edit: None,
+        needs_resolve: false,
     });
 
     None
@@ -40,16 +40,17 @@ pub(super) fn hints(
     }
 
     // FIXME?: We could provide text edit to insert braces for closures with non-block body.
-    let text_edit = if has_block_body {
+    let (text_edit, text_edit_needs_resolve) = if has_block_body {
         ty_to_text_edit(
             sema,
+            config,
             closure.syntax(),
             &ty,
             param_list.syntax().text_range().end(),
             String::from(" -> "),
         )
     } else {
-        None
+        (None, false)
     };
 
     acc.push(InlayHint {
@@ -57,6 +58,7 @@ pub(super) fn hints(
         kind: InlayKind::ClosureReturnType,
         label: label_of_ty(famous_defs, config, ty)?,
         text_edit,
+        needs_resolve: text_edit_needs_resolve || config.fields_to_resolve.resolve_label_location,
     });
     Some(())
 }
@@ -76,6 +76,7 @@ fn variant_hints(
             None,
         ),
         text_edit: None,
+        needs_resolve: false,
     });
 
     Some(())
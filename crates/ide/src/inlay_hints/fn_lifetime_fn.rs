@@ -26,6 +26,7 @@ pub(super) fn hints(
         kind: InlayKind::Lifetime,
         label: label.into(),
         text_edit: None,
+        needs_resolve: false,
     };
 
     let param_list = func.param_list()?;
@@ -191,6 +192,7 @@ pub(super) fn hints(
                 )
                 .into(),
                 text_edit: None,
+                needs_resolve: false,
             });
         }
         (None, allocated_lifetimes) => acc.push(InlayHint {
@@ -198,6 +200,7 @@ pub(super) fn hints(
             kind: InlayKind::GenericParamList,
             label: format!("<{}>", allocated_lifetimes.iter().format(", "),).into(),
             text_edit: None,
+            needs_resolve: false,
         }),
     }
     Some(())
@@ -35,6 +35,7 @@ pub(super) fn hints(
                 kind: InlayKind::Lifetime,
                 label: "'static".to_owned().into(),
                 text_edit: None,
+                needs_resolve: false,
             });
         }
     }
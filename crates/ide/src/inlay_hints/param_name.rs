@@ -58,6 +58,7 @@ pub(super) fn hints(
                 kind: InlayKind::Parameter,
                 label: InlayHintLabel::simple(param_name, None, linked_location),
                 text_edit: None,
+                needs_resolve: false,
             }
         });
 
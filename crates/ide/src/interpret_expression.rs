@@ -0,0 +1,212 @@
+//! Evaluates an expression as if it was written at a position in a file, like the console of a
+//! debugger does.
+//!
+//! The expression is inserted into the file in an overlay of the database, see
+//! [`crate::AnalysisHost::overlay_analysis`], as a `let` statement before the statement at the
+//! position, or as a `const` item if the position isn't inside a body. Then the body is interpreted
+//! until the inserted binding gets its value. This way, the expression can use the locals and the
+//! items in scope at the position, and only what depends on the file is analyzed again.
+use std::{fmt, sync::Arc};
+
+use hir::Semantics;
+use ide_db::{
+    base_db::{FilePosition, SourceDatabase, SourceDatabaseExt},
+    RootDatabase,
+};
+use syntax::{algo::find_node_at_offset, ast, AstNode, SyntaxKind, TextRange, TextSize};
+
+/// The name of the binding the expression is evaluated into.
+const BINDING: &str = "__ra_interpreted";
+
+/// Why an expression couldn't be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpretExpressionError {
+    /// The expression has syntax errors.
+    Syntax(Vec<String>),
+    /// The body the expression was inserted into returned before reaching the expression.
+    NotReached,
+    /// Lowering or interpreting the body failed, for example because the expression doesn't
+    /// type check, or because the body has parameters whose values aren't known.
+    Eval(String),
+}
+
+impl fmt::Display for InterpretExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpretExpressionError::Syntax(errors) => {
+                write!(f, "syntax error: {}", errors.join(", "))
+            }
+            InterpretExpressionError::NotReached => {
+                f.write_str("the expression isn't reached when evaluating the body")
+            }
+            InterpretExpressionError::Eval(e) => write!(f, "evaluation failed: {e}"),
+        }
+    }
+}
+
+/// The text of a file with an expression to evaluate inserted into it.
+pub(crate) struct Insertion {
+    pub(crate) text: Arc<str>,
+    offset: TextSize,
+    inserted: String,
+}
+
+/// Inserts `expr` into the text of the file of `position`.
+pub(crate) fn insert_expression(
+    db: &RootDatabase,
+    position: FilePosition,
+    expr: &str,
+) -> Insertion {
+    let expr = expr.trim().trim_end_matches(';');
+    let (offset, inserted) = insertion(db, position, expr);
+    let mut text = db.file_text(position.file_id).to_string();
+    text.insert_str(offset.into(), &inserted);
+    Insertion { text: Arc::from(text), offset, inserted }
+}
+
+/// Evaluates the expression of `insertion` in `db`, where the file of `position` has the text of
+/// `insertion`.
+pub(crate) fn interpret_inserted_expression(
+    db: &RootDatabase,
+    position: FilePosition,
+    insertion: &Insertion,
+) -> Result<String, InterpretExpressionError> {
+    let (offset, inserted) = (insertion.offset, &insertion.inserted);
+    let inserted_range = TextRange::at(offset, TextSize::of(inserted));
+    let errors: Vec<_> = db
+        .parse(position.file_id)
+        .errors()
+        .iter()
+        .filter(|it| inserted_range.contains_range(it.range()))
+        .map(|it| it.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(InterpretExpressionError::Syntax(errors));
+    }
+
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let binding_offset = offset + TextSize::of(inserted.split(BINDING).next().unwrap_or_default());
+    let local = find_node_at_offset::<ast::IdentPat>(source_file.syntax(), binding_offset)
+        .and_then(|pat| sema.to_def(&pat))
+        .ok_or_else(|| InterpretExpressionError::Eval("the expression isn't in a body".into()))?;
+    match local.eval_first_value(db) {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(InterpretExpressionError::NotReached),
+        Err(e) => Err(InterpretExpressionError::Eval(format!("{e:?}"))),
+    }
+}
+
+/// Returns where to insert the expression and the text to insert.
+fn insertion(db: &RootDatabase, position: FilePosition, expr: &str) -> (TextSize, String) {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let token = source_file.syntax().token_at_offset(position.offset).left_biased();
+    let stmt_list =
+        token.as_ref().and_then(|it| it.parent_ancestors().find_map(ast::StmtList::cast));
+    if let Some(stmt_list) = stmt_list {
+        // Before the statement at the position, or at the end of the block.
+        let offset = stmt_list
+            .statements()
+            .map(|it| it.syntax().clone())
+            .chain(stmt_list.tail_expr().map(|it| it.syntax().clone()))
+            .find(|it| it.text_range().end() > position.offset)
+            .map(|it| it.text_range().start())
+            .or_else(|| stmt_list.r_curly_token().map(|it| it.text_range().start()))
+            .unwrap_or(position.offset);
+        return (offset, format!("let {BINDING} = ({expr});\n"));
+    }
+
+    // Before the item at the position, in the scope of the module.
+    let item = token.and_then(|it| {
+        it.parent_ancestors().filter_map(ast::Item::cast).find(|item| {
+            item.syntax().parent().map_or(false, |parent| {
+                parent.kind() == SyntaxKind::SOURCE_FILE
+                    || ast::ItemList::can_cast(parent.kind())
+                        && parent.parent().map_or(false, |it| ast::Module::can_cast(it.kind()))
+            })
+        })
+    });
+    let offset = item.map_or(position.offset, |it| it.syntax().text_range().start());
+    (offset, format!("const _: () = {{ let {BINDING} = ({expr}); }};\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::base_db::{fixture::ChangeFixture, FilePosition};
+
+    use crate::{AnalysisHost, InterpretExpressionError};
+
+    fn interpret(ra_fixture: &str, expr: &str) -> Result<String, InterpretExpressionError> {
+        let mut host = AnalysisHost::default();
+        let fixture = ChangeFixture::parse(ra_fixture);
+        host.apply_change(fixture.change);
+        let (file_id, offset) = fixture.file_position.expect("expected a marker ($0)");
+        let position = FilePosition { file_id, offset: offset.expect_offset() };
+        let value = host.interpret_expression_at(position, expr).unwrap();
+        // The live state doesn't see the expression.
+        assert!(!host.analysis().file_text(file_id).unwrap().contains(super::BINDING));
+        value
+    }
+
+    fn check(ra_fixture: &str, expr: &str, expected: Result<&str, InterpretExpressionError>) {
+        let value = interpret(ra_fixture, expr);
+        assert_eq!(value.as_deref().map_err(Clone::clone), expected);
+    }
+
+    #[test]
+    fn uses_locals() {
+        check(
+            r#"
+fn main() {
+    let x = 2;
+    let y = x * 3;
+    $0
+    let z = 0;
+}
+"#,
+            "x + y",
+            Ok("8"),
+        );
+    }
+
+    #[test]
+    fn evaluates_before_the_statement() {
+        check(
+            r#"
+fn main() {
+    let mut x = 2;
+    x = $0x * 5;
+    x = x + 1;
+}
+"#,
+            "x",
+            Ok("2"),
+        );
+    }
+
+    #[test]
+    fn uses_items_outside_of_bodies() {
+        check(
+            r#"
+const fn square(x: i32) -> i32 { x * x }
+const A: i32 = 5;
+$0
+"#,
+            "square(A) + 1",
+            Ok("26"),
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let value = interpret("fn main() { $0 }", "1 +");
+        assert!(matches!(value, Err(InterpretExpressionError::Syntax(_))), "{value:?}");
+    }
+
+    #[test]
+    fn reports_functions_with_parameters() {
+        let value = interpret("fn f(x: i32) { $0 }", "x");
+        assert!(matches!(value, Err(InterpretExpressionError::Eval(_))), "{value:?}");
+    }
+}
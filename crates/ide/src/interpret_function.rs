@@ -0,0 +1,129 @@
+//! Runs functions with the interpreter, like tests, under the semantics of the debug or the
+//! release profile: whether `cfg(debug_assertions)` holds, and whether arithmetic overflows panic.
+//!
+//! The interpreter checks the `cfg!(debug_assertions)` of the crates outside of the sysroot, like
+//! the ones of `debug_assert!`, under the profile. The `#[cfg(debug_assertions)]` items and
+//! statements are the ones of the crate graph, which [`crate::AnalysisHost::set_build_profile`]
+//! sets up.
+
+use cfg::{CfgAtom, CfgDiff, CfgExpr};
+use hir::{db::DefDatabase, BuildProfile, FunctionEval, MirEvalError};
+use ide_db::{
+    base_db::{CrateGraph, CrateOrigin, SourceDatabase},
+    RootDatabase,
+};
+use itertools::Itertools;
+
+/// Runs `func` like a test under `profile`, and renders the result: `pass`, or why it failed.
+pub(crate) fn interpret_function(
+    db: &RootDatabase,
+    func: hir::Function,
+    profile: BuildProfile,
+) -> String {
+    render_eval(db, func.eval_with_profile(db, None, profile))
+}
+
+/// Returns the crate graph of `db` with `cfg(debug_assertions)` set like `profile` sets it in the
+/// crates outside of the sysroot, or `None` if it already is.
+pub(crate) fn crate_graph_for_profile(
+    db: &RootDatabase,
+    profile: BuildProfile,
+) -> Option<CrateGraph> {
+    let atom = CfgAtom::Flag("debug_assertions".into());
+    let expr = CfgExpr::Atom(atom.clone());
+    let crate_graph = db.crate_graph();
+    let mismatched: Vec<_> = crate_graph
+        .iter()
+        .filter(|&it| {
+            let data = &crate_graph[it];
+            !matches!(data.origin, CrateOrigin::Lang(_))
+                && data.cfg_options.check(&expr) != Some(profile.debug_assertions())
+        })
+        .collect();
+    if mismatched.is_empty() {
+        return None;
+    }
+    let mut crate_graph = (*crate_graph).clone();
+    for krate in mismatched {
+        let diff = if profile.debug_assertions() {
+            CfgDiff::new(vec![atom.clone()], Vec::new())
+        } else {
+            CfgDiff::new(Vec::new(), vec![atom.clone()])
+        };
+        // A single atom can't conflict with itself.
+        crate_graph.apply_cfg_diff(krate, diff.unwrap());
+    }
+    Some(crate_graph)
+}
+
+fn render_eval(db: &RootDatabase, eval: FunctionEval) -> String {
+    match eval.result {
+        Ok(()) => "pass".into(),
+        Err(MirEvalError::MirLowerError(f, e)) => {
+            let name = &db.function_data(f).name;
+            format!("error: fail to lower {name} due {e:?}")
+        }
+        Err(MirEvalError::UnlowerableCallees(callees)) => {
+            let callees = callees
+                .iter()
+                .map(|(f, e)| format!("{} due {e:?}", db.function_data(*f).name))
+                .join(", ");
+            format!("error: fail to lower {callees}")
+        }
+        Err(e) => format!("error: {e:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::BuildProfile;
+    use ide_db::RootDatabase;
+    use syntax::{algo::find_node_at_offset, ast, AstNode};
+
+    use crate::fixture;
+
+    use super::interpret_function;
+
+    #[track_caller]
+    fn check(ra_fixture: &str, profile: BuildProfile, expected: &str) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let result = analysis
+            .with_db(|db: &RootDatabase| {
+                let sema = hir::Semantics::new(db);
+                let file = sema.parse(position.file_id);
+                let fn_ = find_node_at_offset::<ast::Fn>(file.syntax(), position.offset).unwrap();
+                interpret_function(db, sema.to_def(&fn_).unwrap(), profile)
+            })
+            .unwrap();
+        assert!(result.contains(expected), "{result}");
+    }
+
+    #[test]
+    fn overflow_checks() {
+        let fixture = r#"
+fn sum$0() {
+    let x: u8 = 200;
+    let _sum = x + 100;
+}
+"#;
+        check(fixture, BuildProfile::Debug, "Overflow in Add");
+        check(fixture, BuildProfile::Release, "pass");
+    }
+
+    #[test]
+    fn debug_assertions() {
+        let body = r#"
+#[rustc_builtin_macro]
+macro_rules! cfg {}
+fn sum$0() {
+    let x: u8 = if cfg!(debug_assertions) { 255 } else { 0 };
+    let _sum = x + 1;
+}
+"#;
+        check(body, BuildProfile::Debug, "Overflow in Add");
+        check(body, BuildProfile::Release, "pass");
+        let fixture = format!("//- /main.rs cfg:debug_assertions{body}");
+        check(&fixture, BuildProfile::Debug, "Overflow in Add");
+        check(&fixture, BuildProfile::Release, "pass");
+    }
+}
@@ -25,6 +25,8 @@ mod navigation_target;
 
 mod annotations;
 mod call_hierarchy;
+mod change_signature;
+mod const_eval_pool;
 mod signature_help;
 mod doc_links;
 mod highlight_related;
@@ -37,21 +39,28 @@ mod goto_definition;
 mod goto_implementation;
 mod goto_type_definition;
 mod hover;
+mod impl_coverage;
 mod inlay_hints;
+mod interpret_expression;
+mod interpret_function;
 mod join_lines;
+mod lifetime_reach;
 mod markdown_remove;
 mod matching_brace;
 mod moniker;
 mod move_item;
+mod overlay;
 mod parent_module;
 mod references;
 mod rename;
+mod safe_delete;
 mod runnables;
 mod ssr;
 mod static_index;
 mod status;
 mod syntax_highlighting;
 mod syntax_tree;
+mod trace_const_eval;
 mod typing;
 mod view_crate_graph;
 mod view_hir;
@@ -71,7 +80,11 @@ use ide_db::{
 };
 use syntax::SourceFile;
 
-use crate::navigation_target::{ToNav, TryToNav};
+use crate::{
+    const_eval_pool::ConstEvalPool,
+    navigation_target::{ToNav, TryToNav},
+    overlay::OverlayDatabase,
+};
 
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind, AnnotationLocation},
@@ -81,12 +94,15 @@ pub use crate::{
     folding_ranges::{Fold, FoldKind},
     highlight_related::{HighlightRelatedConfig, HighlightedRange},
     hover::{HoverAction, HoverConfig, HoverDocFormat, HoverGotoTypeData, HoverResult},
+    impl_coverage::{ImplCoverage, MissingImpl, MissingImplReason},
     inlay_hints::{
-        AdjustmentHints, AdjustmentHintsMode, ClosureReturnTypeHints, DiscriminantHints, InlayHint,
-        InlayHintLabel, InlayHintLabelPart, InlayHintsConfig, InlayKind, InlayTooltip,
-        LifetimeElisionHints,
+        AdjustmentHints, AdjustmentHintsMode, ClosureReturnTypeHints, DiscriminantHints,
+        HintPattern, InlayFieldsToResolve, InlayHint, InlayHintLabel, InlayHintLabelPart,
+        InlayHintsConfig, InlayKind, InlayTooltip, LifetimeElisionHints, TypeHintFilter,
     },
+    interpret_expression::InterpretExpressionError,
     join_lines::JoinLinesConfig,
+    lifetime_reach::{LifetimePosition, LifetimePositionKind, LifetimeReach},
     markup::Markup,
     moniker::{MonikerDescriptorKind, MonikerKind, MonikerResult, PackageInformation},
     move_item::Direction,
@@ -95,6 +111,7 @@ pub use crate::{
     references::ReferenceSearchResult,
     rename::RenameError,
     runnables::{Runnable, RunnableKind, TestId},
+    safe_delete::{SafeDelete, SafeDeleteBlocker, SafeDeleteError},
     signature_help::SignatureHelp,
     static_index::{StaticIndex, StaticIndexedFile, TokenId, TokenStaticData},
     syntax_highlighting::{
@@ -102,7 +119,7 @@ pub use crate::{
         HighlightConfig, HlRange,
     },
 };
-pub use hir::{Documentation, Semantics};
+pub use hir::{BuildProfile, Documentation, Semantics};
 pub use ide_assists::{
     Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, SingleResolve,
 };
@@ -115,6 +132,7 @@ pub use ide_db::{
         Cancelled, Change, CrateGraph, CrateId, Edition, FileId, FilePosition, FileRange,
         SourceRoot, SourceRootId,
     },
+    change_signature::{ChangeSignatureError, SignatureParam},
     label::Label,
     line_index::{LineCol, LineIndex},
     search::{ReferenceCategory, SearchScope},
@@ -146,11 +164,23 @@ impl<T> RangeInfo<T> {
 #[derive(Debug)]
 pub struct AnalysisHost {
     db: RootDatabase,
+    const_eval_pool: ConstEvalPool,
+    overlay: OverlayDatabase,
 }
 
 impl AnalysisHost {
     pub fn new(lru_capacity: Option<usize>) -> AnalysisHost {
-        AnalysisHost { db: RootDatabase::new(lru_capacity) }
+        AnalysisHost {
+            db: RootDatabase::new(lru_capacity),
+            const_eval_pool: ConstEvalPool::default(),
+            overlay: OverlayDatabase::default(),
+        }
+    }
+
+    /// Evaluates consts for hover on `num_threads` background threads, showing a placeholder until
+    /// the value is known. With zero threads, consts are evaluated when they're needed.
+    pub fn set_const_eval_threads(&mut self, num_threads: usize) {
+        self.const_eval_pool = ConstEvalPool::new(num_threads);
     }
 
     pub fn update_lru_capacity(&mut self, lru_capacity: Option<usize>) {
@@ -164,30 +194,75 @@ impl AnalysisHost {
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
-        Analysis { db: self.db.snapshot() }
+        Analysis { db: self.db.snapshot(), const_eval_pool: self.const_eval_pool.clone() }
+    }
+
+    /// Returns a snapshot of the current state where the files of `texts` have these texts, like
+    /// the unsaved documents of another editor. The current state doesn't change.
+    ///
+    /// All overlays share a database which follows the current state, so the analysis of the
+    /// files they don't replace is kept from one overlay to the next. Taking an overlay with other
+    /// texts cancels the snapshots of the previous one.
+    pub fn overlay_analysis(&mut self, texts: &[(FileId, Arc<str>)]) -> Analysis {
+        let db = self.overlay.with_texts(&self.db, texts);
+        Analysis { db: db.snapshot(), const_eval_pool: ConstEvalPool::default() }
+    }
+
+    /// Evaluates `expr` as if it was written at `position`, so that it can use the locals and the
+    /// items in scope there, and returns the rendered value. The expression is inserted into the
+    /// file in an overlay, see [`AnalysisHost::overlay_analysis`], the current state doesn't
+    /// change.
+    pub fn interpret_expression_at(
+        &mut self,
+        position: FilePosition,
+        expr: &str,
+    ) -> Cancellable<Result<String, InterpretExpressionError>> {
+        let insertion = interpret_expression::insert_expression(&self.db, position, expr);
+        let overlay = self.overlay_analysis(&[(position.file_id, insertion.text.clone())]);
+        overlay.with_db(|db| {
+            interpret_expression::interpret_inserted_expression(db, position, &insertion)
+        })
     }
 
     /// Applies changes to the current state of the world. If there are
     /// outstanding snapshots, they will be canceled.
     pub fn apply_change(&mut self, change: Change) {
+        self.const_eval_pool.clear();
+        self.overlay.apply_change(&change);
         self.db.apply_change(change)
     }
 
+    /// Sets `cfg(debug_assertions)` in the crates outside of the sysroot like `profile` does, so
+    /// that the code evaluated afterwards is the one built with `profile`.
+    pub fn set_build_profile(&mut self, profile: BuildProfile) {
+        if let Some(crate_graph) = interpret_function::crate_graph_for_profile(&self.db, profile) {
+            let mut change = Change::new();
+            change.set_crate_graph(crate_graph);
+            self.apply_change(change);
+        }
+    }
+
     /// NB: this clears the database
     pub fn per_query_memory_usage(&mut self) -> Vec<(String, profile::Bytes)> {
         self.db.per_query_memory_usage()
     }
     pub fn request_cancellation(&mut self) {
+        self.const_eval_pool.clear();
+        self.overlay.request_cancellation();
         self.db.request_cancellation();
     }
     pub fn raw_database(&self) -> &RootDatabase {
         &self.db
     }
     pub fn raw_database_mut(&mut self) -> &mut RootDatabase {
+        self.const_eval_pool.clear();
+        // The changes made through the raw database aren't known to the overlays.
+        self.overlay.clear();
         &mut self.db
     }
 
     pub fn shuffle_crate_graph(&mut self) {
+        self.overlay.clear();
         shuffle_crate_graph::shuffle_crate_graph(&mut self.db);
     }
 }
@@ -205,6 +280,7 @@ impl Default for AnalysisHost {
 #[derive(Debug)]
 pub struct Analysis {
     db: salsa::Snapshot<RootDatabase>,
+    const_eval_pool: ConstEvalPool,
 }
 
 // As a general design guideline, `Analysis` API are intended to be independent
@@ -317,6 +393,16 @@ impl Analysis {
         self.with_db(|db| view_mir::view_mir(db, position))
     }
 
+    /// Traces the evaluation of the body at `position`. `progress` is called periodically with
+    /// the number of executed blocks, and stops the evaluation when it returns `false`.
+    pub fn trace_const_eval(
+        &self,
+        position: FilePosition,
+        progress: impl Fn(usize) -> bool + std::panic::UnwindSafe,
+    ) -> Cancellable<String> {
+        self.with_db(|db| trace_const_eval::trace_const_eval(db, position, &progress))
+    }
+
     pub fn view_item_tree(&self, file_id: FileId) -> Cancellable<String> {
         self.with_db(|db| view_item_tree::view_item_tree(db, file_id))
     }
@@ -383,6 +469,21 @@ impl Analysis {
         self.with_db(|db| inlay_hints::inlay_hints(db, file_id, range, config))
     }
 
+    /// Computes the fields of the inlay hint at `resolve_range` which were left out by
+    /// [`Analysis::inlay_hints`]. `hash` identifies the hint among the ones at that range.
+    pub fn inlay_hints_resolve(
+        &self,
+        config: &InlayHintsConfig,
+        file_id: FileId,
+        resolve_range: TextRange,
+        hash: u64,
+        hasher: impl Fn(&InlayHint) -> u64 + std::panic::UnwindSafe,
+    ) -> Cancellable<Option<InlayHint>> {
+        self.with_db(|db| {
+            inlay_hints::inlay_hints_resolve(db, file_id, resolve_range, hash, config, hasher)
+        })
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancellable<Vec<Fold>> {
         self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
@@ -445,7 +546,7 @@ impl Analysis {
         config: &HoverConfig,
         range: FileRange,
     ) -> Cancellable<Option<RangeInfo<HoverResult>>> {
-        self.with_db(|db| hover::hover(db, range, config))
+        self.with_db(|db| hover::hover(db, range, config, &self.const_eval_pool))
     }
 
     /// Returns moniker of symbol at position.
@@ -531,6 +632,18 @@ impl Analysis {
         self.with_db(|db| runnables::related_tests(db, position, search_scope))
     }
 
+    /// Returns the workspace types which look like they should implement the trait at the given
+    /// position, but don't.
+    pub fn impl_coverage(&self, position: FilePosition) -> Cancellable<Option<ImplCoverage>> {
+        self.with_db(|db| impl_coverage::impl_coverage(db, position))
+    }
+
+    /// Returns the places of the item declaring the lifetime parameter at the given position where
+    /// the lifetime appears, including the elided ones.
+    pub fn lifetime_reach(&self, position: FilePosition) -> Cancellable<Option<LifetimeReach>> {
+        self.with_db(|db| lifetime_reach::lifetime_reach(db, position))
+    }
+
     /// Computes syntax highlighting for the given file
     pub fn highlight(
         &self,
@@ -567,6 +680,19 @@ impl Analysis {
         self.with_db(|db| syntax_highlighting::highlight_as_html(db, file_id, rainbow))
     }
 
+    /// Computes syntax highlighting for the given file, with the types of names as tooltips and
+    /// links to their definitions in the files of `pages`, which maps files to their URLs.
+    pub fn highlight_as_html_with_links(
+        &self,
+        file_id: FileId,
+        rainbow: bool,
+        pages: &FxHashMap<FileId, String>,
+    ) -> Cancellable<String> {
+        self.with_db(|db| {
+            syntax_highlighting::highlight_as_html_with_links(db, file_id, rainbow, pages)
+        })
+    }
+
     /// Computes completions at the given position.
     pub fn completions(
         &self,
@@ -660,6 +786,25 @@ impl Analysis {
         self.with_db(|db| rename::will_rename_file(db, file_id, new_name_stem))
     }
 
+    /// Returns the edit required to change the signature of the function at the position, and
+    /// to update its call sites accordingly.
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        params: &[SignatureParam],
+    ) -> Cancellable<Result<SourceChange, ChangeSignatureError>> {
+        self.with_db(|db| change_signature::change_signature(db, position, params))
+    }
+
+    /// Returns the edit deleting the item at the position along with its imports, or the usages
+    /// preventing the deletion.
+    pub fn safe_delete(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Result<SafeDelete, SafeDeleteError>> {
+        self.with_db(|db| safe_delete::safe_delete(db, position))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -0,0 +1,301 @@
+use hir::{GenericParam, Name, Semantics, SignatureLifetime};
+use ide_db::{
+    base_db::{FileId, FilePosition, FileRange},
+    defs::{Definition, IdentClass, NameRefClass},
+    RootDatabase,
+};
+use syntax::{ast, match_ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, TextRange};
+
+/// The places of an item where one of its lifetime parameters appears.
+#[derive(Debug)]
+pub struct LifetimeReach {
+    pub name: String,
+    pub positions: Vec<LifetimePosition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifetimePosition {
+    pub range: TextRange,
+    pub kind: LifetimePositionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifetimePositionKind {
+    Declaration,
+    /// A use in a type, like `&'a T` or `Foo<'a>`.
+    Type,
+    /// A use in a bound or a where clause, like `T: 'a` or `'b: 'a`.
+    Bound,
+    /// A reference of a function signature whose lifetime is elided, but is the parameter, like the
+    /// return type of `fn first<'a>(bytes: &'a [u8]) -> &u8`. The range is the one of the
+    /// reference, or of its `'_`.
+    Elided,
+}
+
+// Feature: Lifetime Reach
+//
+// Lists the places of the item declaring the lifetime parameter under the cursor where the
+// lifetime appears: its uses in types, bounds and where clauses, and the references of function
+// signatures whose elided lifetime resolves to it. The document highlights of a lifetime parameter
+// include the latter too.
+pub(crate) fn lifetime_reach(db: &RootDatabase, position: FilePosition) -> Option<LifetimeReach> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let lifetime =
+        sema.find_node_at_offset_with_descend::<ast::Lifetime>(file.syntax(), position.offset)?;
+    let param = IdentClass::classify_lifetime(&sema, &lifetime)?
+        .definitions()
+        .into_iter()
+        .find_map(|def| match def {
+            Definition::GenericParam(GenericParam::LifetimeParam(it)) => Some(it),
+            _ => None,
+        })?;
+
+    let source = sema.source(param)?;
+    let item = declaring_item(&source.value)?;
+    let mut positions = Vec::new();
+    let mut push = |node: &SyntaxNode, kind| {
+        let FileRange { file_id, range } = sema.original_range(node);
+        if file_id == position.file_id {
+            positions.push(LifetimePosition { range, kind });
+        }
+    };
+    if let Some(it) = source.value.lifetime() {
+        push(it.syntax(), LifetimePositionKind::Declaration);
+    }
+    for lifetime in item.descendants().filter_map(ast::Lifetime::cast) {
+        let is_use = matches!(
+            NameRefClass::classify_lifetime(&sema, &lifetime),
+            Some(NameRefClass::Definition(Definition::GenericParam(GenericParam::LifetimeParam(it))))
+                if it == param
+        );
+        if is_use {
+            push(lifetime.syntax(), use_kind(&lifetime, &item));
+        }
+    }
+    positions.extend(
+        elided_references_to(&sema, param, position.file_id)
+            .into_iter()
+            .map(|range| LifetimePosition { range, kind: LifetimePositionKind::Elided }),
+    );
+    positions.sort_by_key(|it| it.range.start());
+    positions.dedup();
+    Some(LifetimeReach { name: param.name(db).to_smol_str().to_string(), positions })
+}
+
+/// Returns the references of the signatures of the item declaring `param` whose elided lifetime
+/// is `param`, in `file_id`.
+pub(crate) fn elided_references_to(
+    sema: &Semantics<'_, RootDatabase>,
+    param: hir::LifetimeParam,
+    file_id: FileId,
+) -> Vec<TextRange> {
+    let Some(item) = sema.source(param).and_then(|it| declaring_item(&it.value)) else {
+        return Vec::new();
+    };
+    elided_references(sema, &item, &param.name(sema.db))
+        .into_iter()
+        .filter(|it| it.file_id == file_id)
+        .map(|it| it.range)
+        .collect()
+}
+
+/// Returns the item whose generic parameter list declares `param`.
+fn declaring_item(param: &ast::LifetimeParam) -> Option<SyntaxNode> {
+    param.syntax().ancestors().find(|it| it.kind() == SyntaxKind::GENERIC_PARAM_LIST)?.parent()
+}
+
+fn use_kind(lifetime: &ast::Lifetime, item: &SyntaxNode) -> LifetimePositionKind {
+    for node in lifetime.syntax().ancestors().take_while(|it| it != item) {
+        match node.kind() {
+            SyntaxKind::WHERE_PRED => return LifetimePositionKind::Bound,
+            // `dyn Trait + 'a` and `impl Trait + 'a` are types.
+            SyntaxKind::DYN_TRAIT_TYPE | SyntaxKind::IMPL_TRAIT_TYPE => {
+                return LifetimePositionKind::Type
+            }
+            SyntaxKind::TYPE_BOUND_LIST => return LifetimePositionKind::Bound,
+            _ => (),
+        }
+    }
+    LifetimePositionKind::Type
+}
+
+/// Returns the references of the signatures of `item`, or of its associated functions, whose
+/// elided lifetime is the lifetime named `name`.
+fn elided_references(
+    sema: &Semantics<'_, RootDatabase>,
+    item: &SyntaxNode,
+    name: &Name,
+) -> Vec<FileRange> {
+    let fns: Vec<ast::Fn> = match_ast! {
+        match item {
+            ast::Fn(it) => vec![it],
+            ast::Impl(it) => assoc_fns(it.assoc_item_list()),
+            ast::Trait(it) => assoc_fns(it.assoc_item_list()),
+            _ => Vec::new(),
+        }
+    };
+    let mut res = Vec::new();
+    for fn_ in fns {
+        let Some(func) = sema.to_def(&fn_) else { continue };
+        let lifetimes = func.signature_lifetimes(sema.db);
+        let (params, ret) = signature_references(&fn_);
+        if params.len() != lifetimes.params.len() {
+            continue;
+        }
+        let pairs = params.iter().zip(&lifetimes.params).chain([(&ret, &lifetimes.ret)]);
+        for (references, lifetimes) in pairs {
+            // The references of types which don't match their type references, like the ones in
+            // macro calls, can't be matched with their lifetimes.
+            if references.len() != lifetimes.len() {
+                continue;
+            }
+            for (reference, lifetime) in references.iter().zip(lifetimes) {
+                let is_param = matches!(lifetime, SignatureLifetime::Named(it) if it == name);
+                if is_param {
+                    if let Some(node) = reference.elided_node() {
+                        res.push(sema.original_range(&node));
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+fn assoc_fns(list: Option<ast::AssocItemList>) -> Vec<ast::Fn> {
+    list.into_iter()
+        .flat_map(|it| it.assoc_items())
+        .filter_map(|it| match it {
+            ast::AssocItem::Fn(it) => Some(it),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A reference in a signature: its `&`, and its lifetime if it is written.
+struct Reference {
+    amp: Option<SyntaxToken>,
+    lifetime: Option<ast::Lifetime>,
+}
+
+impl Reference {
+    /// Returns the node to highlight if the lifetime of the reference is elided: its `'_`, or
+    /// the whole reference.
+    fn elided_node(&self) -> Option<SyntaxNode> {
+        match &self.lifetime {
+            Some(it) if it.text() == "'_" => Some(it.syntax().clone()),
+            Some(_) => None,
+            None => self.amp.as_ref().and_then(|it| it.parent()),
+        }
+    }
+}
+
+/// Returns the references in the types of the parameters, including `self`, and in the return
+/// type of `fn_`, in the order they are written, like the order of `TypeRef::walk`.
+fn signature_references(fn_: &ast::Fn) -> (Vec<Vec<Reference>>, Vec<Reference>) {
+    let references = |ty: Option<ast::Type>| -> Vec<Reference> {
+        ty.into_iter()
+            .flat_map(|it| it.syntax().descendants().filter_map(ast::RefType::cast))
+            .map(|it| Reference { amp: it.amp_token(), lifetime: it.lifetime() })
+            .collect()
+    };
+    let mut params = Vec::new();
+    if let Some(param_list) = fn_.param_list() {
+        if let Some(self_param) = param_list.self_param() {
+            params.push(match self_param.ty() {
+                Some(ty) => references(Some(ty)),
+                None if self_param.amp_token().is_some() => {
+                    vec![Reference { amp: self_param.amp_token(), lifetime: self_param.lifetime() }]
+                }
+                None => Vec::new(),
+            });
+        }
+        params.extend(param_list.params().map(|it| references(it.ty())));
+    }
+    let ret = references(fn_.ret_type().and_then(|it| it.ty()));
+    (params, ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let text = analysis.file_text(position.file_id).unwrap();
+        let reach = analysis.lifetime_reach(position).unwrap().unwrap();
+        let mut actual = format!("{}\n", reach.name);
+        for it in reach.positions {
+            actual += &format!("{:?} {:?}: {}\n", it.kind, it.range, &text[it.range]);
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn function_lifetime() {
+        check(
+            r#"
+fn first<'a$0, 'b: 'a, T: 'a>(bytes: &'a [T], _: &'b u8) -> (&u8, &'static u8)
+where
+    &'a T: Copy,
+{
+    loop {}
+}
+"#,
+            expect![[r#"
+                'a
+                Declaration 9..11: 'a
+                Bound 17..19: 'a
+                Bound 24..26: 'a
+                Type 36..38: 'a
+                Bound 88..90: 'a
+            "#]],
+        );
+    }
+
+    #[test]
+    fn elided_return_type() {
+        check(
+            r#"
+fn first<'a>(bytes: &'a$0 [u8]) -> (&u8, &'_ u8) {
+    (&bytes[0], &bytes[1])
+}
+"#,
+            expect![[r#"
+                'a
+                Declaration 9..11: 'a
+                Type 21..23: 'a
+                Elided 34..37: &u8
+                Elided 40..42: '_
+            "#]],
+        );
+    }
+
+    #[test]
+    fn impl_lifetime() {
+        check(
+            r#"
+struct Parser<'a>(&'a str);
+impl<'a$0> Parser<'a> {
+    fn rest(text: &'a str) -> &str {
+        text
+    }
+    fn get(&self) -> &'a str {
+        self.0
+    }
+}
+"#,
+            expect![[r#"
+                'a
+                Declaration 33..35: 'a
+                Type 44..46: 'a
+                Type 69..71: 'a
+                Elided 80..84: &str
+                Type 128..130: 'a
+            "#]],
+        );
+    }
+}
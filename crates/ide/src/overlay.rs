@@ -0,0 +1,129 @@
+//! The database analyzing overlays. An overlay replaces the text of some files without touching the
+//! live state, like the unsaved documents of a query client, an old revision of a document, or a
+//! file with an expression to evaluate inserted into it.
+//!
+//! All overlays share a single database, which follows the changes of the live one and only differs
+//! from it in the texts of the files of the current overlay. Switching to another overlay changes
+//! the texts of the files of the two overlays, so what doesn't depend on them, like the analysis of
+//! the dependencies, is kept across overlays instead of being redone from scratch for each one.
+use std::{mem, sync::Arc};
+
+use ide_db::{
+    base_db::{Change, FileId, SourceDatabaseExt},
+    FxHashMap, RootDatabase,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct OverlayDatabase {
+    /// Forked from the live database for the first overlay, and dropped when the live database
+    /// changes in a way [`OverlayDatabase::apply_change`] doesn't follow.
+    db: Option<RootDatabase>,
+    /// The live texts of the files the current overlay replaces.
+    live_texts: FxHashMap<FileId, Arc<str>>,
+}
+
+impl OverlayDatabase {
+    /// Applies a change of the live database. The files of the current overlay keep their text
+    /// until the next overlay.
+    pub(crate) fn apply_change(&mut self, change: &Change) {
+        let Some(db) = &mut self.db else { return };
+        let mut change = change.clone();
+        change.files_changed.retain(|(file_id, text)| match self.live_texts.get_mut(file_id) {
+            Some(live_text) => {
+                *live_text = text.clone().unwrap_or_else(|| Arc::from(""));
+                false
+            }
+            None => true,
+        });
+        db.apply_change(change);
+    }
+
+    pub(crate) fn request_cancellation(&mut self) {
+        if let Some(db) = &mut self.db {
+            db.request_cancellation();
+        }
+    }
+
+    /// Drops the database, which is forked again for the next overlay.
+    pub(crate) fn clear(&mut self) {
+        self.request_cancellation();
+        *self = OverlayDatabase::default();
+    }
+
+    /// Returns the database with the texts of `texts`, and the live texts of the other files of
+    /// `live`. The database only changes if the texts are not the ones of the current overlay.
+    pub(crate) fn with_texts(
+        &mut self,
+        live: &RootDatabase,
+        texts: &[(FileId, Arc<str>)],
+    ) -> &RootDatabase {
+        let db = self.db.get_or_insert_with(|| live.fork());
+        let mut previous = mem::take(&mut self.live_texts);
+        let mut change = Change::new();
+        for (file_id, text) in texts {
+            let live_text = previous.remove(file_id).unwrap_or_else(|| db.file_text(*file_id));
+            if *db.file_text(*file_id) != **text {
+                change.change_file(*file_id, Some(text.clone()));
+            }
+            self.live_texts.insert(*file_id, live_text);
+        }
+        // The files of the previous overlay which aren't in this one.
+        for (file_id, live_text) in previous {
+            change.change_file(file_id, Some(live_text));
+        }
+        if !change.files_changed.is_empty() {
+            db.apply_change(change);
+        }
+        db
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ide_db::base_db::{fixture::ChangeFixture, Change, FilePosition};
+
+    use crate::AnalysisHost;
+
+    #[test]
+    fn overlays_share_a_database_following_the_live_one() {
+        let mut host = AnalysisHost::default();
+        let fixture = ChangeFixture::parse(
+            r#"
+//- /main.rs
+mod foo;
+fn main() { foo::bar(); }
+//- /foo.rs
+pub fn bar() {}
+"#,
+        );
+        host.apply_change(fixture.change);
+        let (main, foo) = (fixture.files[0], fixture.files[1]);
+        let text = "mod foo;\nfn main() {\n    foo::bar();\n}\n";
+
+        let overlay = host.overlay_analysis(&[(main, Arc::from(text))]);
+        assert_eq!(&*overlay.file_text(main).unwrap(), text);
+        assert_ne!(&*host.analysis().file_text(main).unwrap(), text);
+        let position = FilePosition { file_id: main, offset: 31.into() };
+        let navs = overlay.goto_definition(position).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        assert_eq!(navs[0].file_id, foo);
+        drop(overlay);
+
+        // Live changes show up in the overlays, except for the files they replace.
+        let mut change = Change::new();
+        change.change_file(main, Some(Arc::from("fn main() {}\n")));
+        change.change_file(foo, Some(Arc::from("pub fn bar() {}\npub fn baz() {}\n")));
+        host.apply_change(change);
+        let overlay = host.overlay_analysis(&[(main, Arc::from(text))]);
+        assert_eq!(&*overlay.file_text(main).unwrap(), text);
+        assert!(overlay.file_text(foo).unwrap().contains("baz"));
+        drop(overlay);
+
+        // The files of the previous overlay get their live text back.
+        let overlay = host.overlay_analysis(&[(foo, Arc::from(""))]);
+        assert_eq!(&*overlay.file_text(main).unwrap(), "fn main() {}\n");
+        assert_eq!(&*overlay.file_text(foo).unwrap(), "");
+    }
+}
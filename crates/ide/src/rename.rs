@@ -9,11 +9,14 @@ use ide_db::{
     base_db::FileId,
     defs::{Definition, NameClass, NameRefClass},
     rename::{bail, format_err, source_edit_from_references, IdentifierKind},
+    syntax_helpers::asm_template::{AsmArgs, AsmOperand, AsmOperandRef},
     RootDatabase,
 };
 use itertools::Itertools;
 use stdx::{always, never};
-use syntax::{ast, utils::is_raw_identifier, AstNode, SmolStr, SyntaxNode, TextRange, TextSize};
+use syntax::{
+    ast, utils::is_raw_identifier, AstNode, SmolStr, SyntaxKind, SyntaxNode, TextRange, TextSize,
+};
 
 use text_edit::TextEdit;
 
@@ -33,6 +36,10 @@ pub(crate) fn prepare_rename(
     let source_file = sema.parse(position.file_id);
     let syntax = source_file.syntax();
 
+    if let Some((_, _, range)) = find_asm_operand(&sema, syntax, position) {
+        return Ok(RangeInfo::new(range, ()));
+    }
+
     let res = find_definitions(&sema, syntax, position)?
         .map(|(name_like, def)| {
             // ensure all ranges are valid
@@ -90,6 +97,10 @@ pub(crate) fn rename(
     let source_file = sema.parse(position.file_id);
     let syntax = source_file.syntax();
 
+    if let Some((args, operand, _)) = find_asm_operand(&sema, syntax, position) {
+        return rename_asm_operand(&args, &operand, position.file_id, new_name);
+    }
+
     let defs = find_definitions(&sema, syntax, position)?;
 
     let ops: RenameResult<Vec<SourceChange>> = defs
@@ -131,6 +142,51 @@ pub(crate) fn will_rename_file(
     Some(change)
 }
 
+/// Finds the named `asm!` operand at `position`, either by its name or by a placeholder of a
+/// template string, and returns the range of the name under the cursor.
+fn find_asm_operand(
+    sema: &Semantics<'_, RootDatabase>,
+    syntax: &SyntaxNode,
+    position: FilePosition,
+) -> Option<(AsmArgs, AsmOperand, TextRange)> {
+    syntax.token_at_offset(position.offset).find_map(|token| {
+        let args = AsmArgs::for_token(sema, &token)?;
+        let (operand, range) = match token.kind() {
+            SyntaxKind::STRING => {
+                let placeholder = args.placeholder_at(position.offset)?;
+                if !matches!(placeholder.operand, AsmOperandRef::Name(_)) {
+                    return None;
+                }
+                (args.resolve(&placeholder.operand)?.clone(), placeholder.operand_range)
+            }
+            SyntaxKind::IDENT => (args.operand_named_by(&token)?.clone(), token.text_range()),
+            _ => return None,
+        };
+        Some((args, operand, range))
+    })
+}
+
+fn rename_asm_operand(
+    args: &AsmArgs,
+    operand: &AsmOperand,
+    file_id: FileId,
+    new_name: &str,
+) -> RenameResult<SourceChange> {
+    if IdentifierKind::classify(new_name)? != IdentifierKind::Ident {
+        bail!("Invalid name `{}`: not an identifier", new_name);
+    }
+    let Some(name) = &operand.name else {
+        bail!("No references found at position");
+    };
+
+    let mut edit = TextEdit::builder();
+    edit.replace(name.text_range(), new_name.to_string());
+    for placeholder in args.placeholders_of(operand) {
+        edit.replace(placeholder.operand_range, new_name.to_string());
+    }
+    Ok(SourceChange::from_text_edit(file_id, edit.finish()))
+}
+
 fn find_definitions(
     sema: &Semantics<'_, RootDatabase>,
     syntax: &SyntaxNode,
@@ -2487,4 +2543,70 @@ fn main() {
 ",
         )
     }
+
+    #[test]
+    fn test_rename_asm_operand() {
+        check(
+            "tmp",
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(a: u32) {
+    asm!("mov {x}, {0}", "add {x:e}, {x$0}", in(reg) a, x = out(reg) _);
+}
+"#,
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(a: u32) {
+    asm!("mov {tmp}, {0}", "add {tmp:e}, {tmp}", in(reg) a, tmp = out(reg) _);
+}
+"#,
+        );
+        check(
+            "tmp",
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(a: u32) {
+    asm!("mov {x}, {0}", in(reg) a, x$0 = out(reg) _);
+}
+"#,
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f(a: u32) {
+    asm!("mov {tmp}, {0}", in(reg) a, tmp = out(reg) _);
+}
+"#,
+        );
+        check(
+            "1",
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+
+fn f() {
+    asm!("mov {x$0}, 1", x = out(reg) _);
+}
+"#,
+            "error: Invalid name `1`: not an identifier",
+        );
+    }
+
+    #[test]
+    fn test_prepare_rename_asm_operand() {
+        check_prepare(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! asm {}
+fn f() { asm!("mov {x$0:e}, 1", x = out(reg) _); }
+"#,
+            expect![[r#"63..64: x"#]],
+        );
+    }
 }
@@ -310,9 +310,11 @@ pub(crate) fn runnable_fn(
     let func = def.source(sema.db)?;
     let name = def.name(sema.db).to_smol_str();
 
-    let root = def.module(sema.db).krate().root_module(sema.db);
+    let krate = def.module(sema.db).krate();
+    let root = krate.root_module(sema.db);
 
-    let kind = if name == "main" && def.module(sema.db) == root {
+    // The `main` function of a `#![no_main]` crate isn't its entry point.
+    let kind = if name == "main" && def.module(sema.db) == root && !krate.is_no_main(sema.db) {
         RunnableKind::Bin
     } else {
         let test_id = || {
@@ -692,6 +694,63 @@ mod not_a_root {
         );
     }
 
+    #[test]
+    fn test_runnables_no_main() {
+        check(
+            r#"
+//- /lib.rs
+$0
+#![no_main]
+
+fn main() {}
+
+#[test]
+fn test_foo() {}
+"#,
+            &[TestMod, Test],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..53,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 28..52,
+                            focus_range: 39..47,
+                            name: "test_foo",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "test_foo",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_runnables_doc_test() {
         check(
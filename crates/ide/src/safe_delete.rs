@@ -0,0 +1,377 @@
+use std::fmt;
+
+use hir::{AsAssocItem, HasSource, InFile, Semantics};
+use ide_db::{
+    base_db::{FileId, FilePosition, FileRange},
+    defs::{Definition, NameClass, NameRefClass},
+    search::{FileReference, ReferenceCategory},
+    source_change::{SourceChange, SourceChangeBuilder},
+    FxHashMap, FxHashSet, RootDatabase,
+};
+use syntax::{
+    ast::{self, edit_in_place::Removable, HasName},
+    ted, AstNode, SyntaxKind, SyntaxNode,
+};
+
+/// A usage of an item which prevents it from being deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeDeleteBlocker {
+    pub range: FileRange,
+    /// The name of the item the usage is in, if any.
+    pub container: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum SafeDelete {
+    /// The item wasn't used anywhere but in imports, this deletes it together with those.
+    Deleted(SourceChange),
+    /// The item is still used, sorted by file and offset.
+    Blocked(Vec<SafeDeleteBlocker>),
+}
+
+#[derive(Debug)]
+pub struct SafeDeleteError(pub String);
+
+impl fmt::Display for SafeDeleteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+macro_rules! bail {
+    ($($tokens:tt)*) => {return Err(SafeDeleteError(format!($($tokens)*)))}
+}
+
+// Feature: Safe Delete
+//
+// Deletes the item below the cursor, but only if nothing refers to it anymore. Imports of the
+// item, including ones renaming it, don't count as usages and are removed along with it. If the
+// item is still used, nothing is deleted and the usages are reported instead.
+pub(crate) fn safe_delete(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Result<SafeDelete, SafeDeleteError> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let Some(def) = find_definition(&sema, source_file.syntax(), position) else {
+        bail!("No item found at position")
+    };
+    let Some(item) = item_syntax(db, def) else {
+        bail!("Only functions, types, traits, consts and statics can be deleted")
+    };
+    if item.file_id.is_macro() {
+        bail!("Cannot delete an item defined by a macro")
+    }
+    // The implementation would stop compiling without the item.
+    if def.as_assoc_item(db).and_then(|it| it.containing_trait_impl(db)).is_some() {
+        bail!("Cannot delete an item of a trait implementation")
+    }
+    let item_file = item.file_id.original_file(db);
+    let item_range = item.value.text_range();
+
+    let mut blockers = Vec::new();
+    let mut imports: FxHashMap<FileId, Vec<ast::UseTree>> = FxHashMap::default();
+    // Usages through an alias aren't found by searching for the item's name, so every
+    // `use item as alias` is searched for separately.
+    let mut renames = vec![None];
+    let mut seen_renames = FxHashSet::default();
+    while let Some(rename) = renames.pop() {
+        for (file_id, references) in def.usages(&sema).with_rename(rename.as_ref()).all() {
+            for reference in references {
+                if file_id == item_file && item_range.contains_range(reference.range) {
+                    continue;
+                }
+                match import_use_tree(&sema, &reference) {
+                    Some(use_tree) => {
+                        if let Some(rename) = use_tree.rename() {
+                            if rename.name().is_some() && seen_renames.insert(rename.clone()) {
+                                renames.push(Some(rename));
+                            }
+                        }
+                        imports.entry(file_id).or_default().push(use_tree);
+                    }
+                    None => blockers.push(SafeDeleteBlocker {
+                        range: FileRange { file_id, range: reference.range },
+                        container: container_name(&sema, reference.name.syntax()),
+                    }),
+                }
+            }
+        }
+    }
+
+    if !blockers.is_empty() {
+        blockers.sort_by_key(|it| (it.range.file_id, it.range.range.start()));
+        blockers.dedup();
+        return Ok(SafeDelete::Blocked(blockers));
+    }
+
+    imports.entry(item_file).or_default();
+    let mut builder = SourceChangeBuilder::new(item_file);
+    for (file_id, use_trees) in imports {
+        builder.edit_file(file_id);
+        // All nodes have to be made mutable before the first edit, as that shifts the ranges
+        // the mutable nodes are looked up by.
+        let use_trees: Vec<_> = use_trees.into_iter().map(|it| builder.make_mut(it)).collect();
+        let item = (file_id == item_file).then(|| builder.make_syntax_mut(item.value.clone()));
+        use_trees.into_iter().for_each(remove_import);
+        if let Some(item) = item {
+            remove_item(&item);
+        }
+    }
+    Ok(SafeDelete::Deleted(builder.finish()))
+}
+
+fn find_definition(
+    sema: &Semantics<'_, RootDatabase>,
+    syntax: &SyntaxNode,
+    position: FilePosition,
+) -> Option<Definition> {
+    sema.find_nodes_at_offset_with_descend::<ast::NameLike>(syntax, position.offset).find_map(
+        |name_like| match name_like {
+            ast::NameLike::Name(name) => match NameClass::classify(sema, &name)? {
+                NameClass::Definition(it) => Some(it),
+                _ => None,
+            },
+            ast::NameLike::NameRef(name_ref) => match NameRefClass::classify(sema, &name_ref)? {
+                NameRefClass::Definition(it) => Some(it),
+                _ => None,
+            },
+            ast::NameLike::Lifetime(_) => None,
+        },
+    )
+}
+
+fn item_syntax(db: &RootDatabase, def: Definition) -> Option<InFile<SyntaxNode>> {
+    let src = match def {
+        Definition::Function(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        Definition::Adt(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        Definition::Const(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        Definition::Static(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        Definition::Trait(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        Definition::TraitAlias(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        Definition::TypeAlias(it) => it.source(db)?.map(|it| it.syntax().clone()),
+        _ => return None,
+    };
+    Some(src)
+}
+
+/// Returns the use tree importing the item, if `reference` is part of a `use` item that can be
+/// edited.
+fn import_use_tree(
+    sema: &Semantics<'_, RootDatabase>,
+    reference: &FileReference,
+) -> Option<ast::UseTree> {
+    if reference.category != Some(ReferenceCategory::Import) {
+        return None;
+    }
+    let ast::NameLike::NameRef(name_ref) = &reference.name else { return None };
+    if sema.hir_file_for(name_ref.syntax()).is_macro() {
+        return None;
+    }
+    name_ref.syntax().ancestors().find_map(ast::UseTree::cast)
+}
+
+fn container_name(sema: &Semantics<'_, RootDatabase>, node: &SyntaxNode) -> Option<String> {
+    sema.ancestors_with_macros(node.clone()).find_map(|node| {
+        let name = match ast::Item::cast(node)? {
+            ast::Item::Fn(it) => it.name(),
+            ast::Item::Const(it) => it.name(),
+            ast::Item::Static(it) => it.name(),
+            ast::Item::Struct(it) => it.name(),
+            ast::Item::Enum(it) => it.name(),
+            ast::Item::Union(it) => it.name(),
+            ast::Item::Trait(it) => it.name(),
+            ast::Item::TypeAlias(it) => it.name(),
+            ast::Item::Module(it) => it.name(),
+            ast::Item::Impl(it) => return Some(format!("impl {}", it.self_ty()?)),
+            _ => None,
+        };
+        name.map(|it| it.to_string())
+    })
+}
+
+/// Removes the use tree, and any use tree list or `use` item that would be left empty by that.
+fn remove_import(mut use_tree: ast::UseTree) {
+    loop {
+        let Some(parent) = use_tree.syntax().parent() else { return };
+        if let Some(use_) = ast::Use::cast(parent.clone()) {
+            use_.remove();
+            return;
+        }
+        let Some(list) = ast::UseTreeList::cast(parent) else { return };
+        if list.use_trees().nth(1).is_some() {
+            use_tree.remove();
+            return;
+        }
+        match list.syntax().parent().and_then(ast::UseTree::cast) {
+            Some(parent) => use_tree = parent,
+            None => return,
+        }
+    }
+}
+
+fn remove_item(item: &SyntaxNode) {
+    let whitespace = item
+        .prev_sibling_or_token()
+        .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+        .or_else(|| item.next_sibling_or_token().filter(|it| it.kind() == SyntaxKind::WHITESPACE));
+    if let Some(whitespace) = whitespace {
+        ted::remove(whitespace);
+    }
+    ted::remove(item);
+}
+
+#[cfg(test)]
+mod tests {
+    use stdx::{format_to, trim_indent};
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    use super::SafeDelete;
+
+    #[track_caller]
+    fn check(ra_fixture_before: &str, ra_fixture_after: &str) {
+        let ra_fixture_after = &trim_indent(ra_fixture_after);
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        match analysis.safe_delete(position).unwrap() {
+            Ok(SafeDelete::Deleted(source_change)) => {
+                let mut edits: Vec<_> = source_change.source_file_edits.into_iter().collect();
+                edits.sort_by_key(|(file_id, _)| *file_id);
+                let mut result = String::new();
+                for (file_id, edit) in edits {
+                    let mut text = analysis.file_text(file_id).unwrap().to_string();
+                    edit.apply(&mut text);
+                    format_to!(result, "//- {}\n{}", file_id.0, text);
+                }
+                assert_eq_text!(ra_fixture_after, &*result);
+            }
+            Ok(SafeDelete::Blocked(blockers)) => {
+                let mut result = String::from("blocked:\n");
+                for blocker in blockers {
+                    format_to!(
+                        result,
+                        "{} {:?} {}\n",
+                        blocker.range.file_id.0,
+                        blocker.range.range,
+                        blocker.container.as_deref().unwrap_or("-")
+                    );
+                }
+                assert_eq_text!(ra_fixture_after, &*result);
+            }
+            Err(err) => {
+                let error_message = ra_fixture_after
+                    .strip_prefix("error:")
+                    .unwrap_or_else(|| panic!("Safe delete failed unexpectedly: {err}"));
+                assert_eq!(error_message.trim(), err.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn delete_unused_function() {
+        check(
+            r#"
+fn unused$0() {}
+
+fn main() {}
+"#,
+            r#"
+//- 0
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn recursive_calls_are_not_usages() {
+        check(
+            r#"
+fn main() {}
+
+fn fact$0(n: u32) -> u32 {
+    if n == 0 { 1 } else { n * fact(n - 1) }
+}
+"#,
+            r#"
+//- 0
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_imports_and_aliases() {
+        check(
+            r#"
+//- /main.rs
+mod foo;
+use foo::{Unused, Used};
+use foo::Unused as Alias;
+
+fn main() { let _ = Used; }
+//- /foo.rs
+pub struct Used;
+
+pub struct Unused$0;
+"#,
+            r#"
+//- 0
+mod foo;
+use foo::{Used};
+
+fn main() { let _ = Used; }
+//- 1
+pub struct Used;
+"#,
+        );
+    }
+
+    #[test]
+    fn blocked_by_usages() {
+        check(
+            r#"
+//- /main.rs
+mod foo;
+use foo::helper;
+fn main() { helper(); }
+struct S;
+impl S { fn m() { foo::helper(); } }
+//- /foo.rs
+pub fn helper$0() {}
+"#,
+            r#"
+blocked:
+0 38..44 main
+0 83..89 m
+"#,
+        );
+    }
+
+    #[test]
+    fn blocked_by_usage_through_alias() {
+        check(
+            r#"
+mod foo { pub fn func$0() {} }
+use foo::func as renamed;
+fn main() { renamed(); }
+"#,
+            r#"
+blocked:
+0 67..74 main
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_impl_item() {
+        check(
+            r#"
+trait T { fn f(); }
+struct S;
+impl T for S { fn f$0() {} }
+"#,
+            "error: Cannot delete an item of a trait implementation",
+        );
+    }
+}
@@ -12,6 +12,7 @@ use ide_db::{
 use syntax::{AstNode, SyntaxKind::*, SyntaxToken, TextRange, T};
 
 use crate::{
+    const_eval_pool::ConstEvalPool,
     hover::hover_for_definition,
     inlay_hints::AdjustmentHintsMode,
     moniker::{def_to_moniker, MonikerResult},
@@ -118,11 +119,14 @@ impl StaticIndex<'_> {
                     adjustment_hints_hide_outside_unsafe: false,
                     hide_named_constructor_hints: false,
                     hide_closure_initialization_hints: false,
+                    type_hint_filters: Vec::new(),
                     closure_style: hir::ClosureStyle::ImplFn,
                     param_names_for_lifetime_elision_hints: false,
                     binding_mode_hints: false,
+                    implicit_ref_mut_hints: false,
                     max_length: Some(25),
                     closing_brace_hints_min_lines: Some(25),
+                    fields_to_resolve: crate::InlayFieldsToResolve::default(),
                 },
                 file_id,
                 None,
@@ -141,7 +145,10 @@ impl StaticIndex<'_> {
             keywords: true,
             format: crate::HoverDocFormat::Markdown,
             interpret_tests: false,
+            interpret_profiles: Vec::new(),
         };
+        // The index is computed once, so consts are evaluated right away.
+        let const_eval_pool = ConstEvalPool::default();
         let tokens = tokens.filter(|token| {
             matches!(
                 token.kind(),
@@ -160,7 +167,14 @@ impl StaticIndex<'_> {
                 *x
             } else {
                 let x = self.tokens.insert(TokenStaticData {
-                    hover: hover_for_definition(&sema, file_id, def, &node, &hover_config),
+                    hover: hover_for_definition(
+                        &sema,
+                        file_id,
+                        def,
+                        &node,
+                        &hover_config,
+                        &const_eval_pool,
+                    ),
                     definition: def
                         .try_to_nav(self.db)
                         .map(|x| FileRange { file_id: x.file_id, range: x.focus_or_full_range() }),
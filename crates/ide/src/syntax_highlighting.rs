@@ -30,7 +30,7 @@ use crate::{
     FileId, HlMod, HlOperator, HlPunct, HlTag,
 };
 
-pub(crate) use html::highlight_as_html;
+pub(crate) use html::{highlight_as_html, highlight_as_html_with_links};
 
 #[derive(Debug, Clone, Copy)]
 pub struct HlRange {
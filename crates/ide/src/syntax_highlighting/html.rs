@@ -1,17 +1,43 @@
 //! Renders a bit of code as HTML.
 
-use ide_db::base_db::SourceDatabase;
+use hir::{HirDisplay, Semantics};
+use ide_db::{
+    defs::{Definition, IdentClass},
+    FxHashMap,
+};
 use oorandom::Rand32;
 use stdx::format_to;
-use syntax::AstNode;
+use syntax::{AstNode, SyntaxKind, SyntaxNode, TextRange};
 
 use crate::{
+    navigation_target::TryToNav,
     syntax_highlighting::{highlight, HighlightConfig},
     FileId, RootDatabase,
 };
 
 pub(crate) fn highlight_as_html(db: &RootDatabase, file_id: FileId, rainbow: bool) -> String {
-    let parse = db.parse(file_id);
+    render(db, file_id, rainbow, None)
+}
+
+/// Like [`highlight_as_html`], but also shows the types of names as tooltips and links them to
+/// their definitions. `pages` maps files to the URLs of their pages, other files aren't linked.
+pub(crate) fn highlight_as_html_with_links(
+    db: &RootDatabase,
+    file_id: FileId,
+    rainbow: bool,
+    pages: &FxHashMap<FileId, String>,
+) -> String {
+    render(db, file_id, rainbow, Some(pages))
+}
+
+fn render(
+    db: &RootDatabase,
+    file_id: FileId,
+    rainbow: bool,
+    pages: Option<&FxHashMap<FileId, String>>,
+) -> String {
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
 
     fn rainbowify(seed: u64) -> String {
         let mut rng = Rand32::new(seed);
@@ -38,9 +64,12 @@ pub(crate) fn highlight_as_html(db: &RootDatabase, file_id: FileId, rainbow: boo
         file_id,
         None,
     );
-    let text = parse.tree().syntax().to_string();
+    let text = file.syntax().to_string();
     let mut buf = String::new();
     buf.push_str(STYLE);
+    if pages.is_some() {
+        buf.push_str(LINK_STYLE);
+    }
     buf.push_str("<pre><code>");
     for r in &hl_ranges {
         let chunk = html_escape(&text[r.range]);
@@ -56,7 +85,11 @@ pub(crate) fn highlight_as_html(db: &RootDatabase, file_id: FileId, rainbow: boo
             }
             _ => "".into(),
         };
-        format_to!(buf, "<span class=\"{}\"{}>{}</span>", class, color, chunk);
+        let (attrs, is_link) = pages
+            .and_then(|pages| name_attrs(&sema, file.syntax(), r.range, pages))
+            .unwrap_or_default();
+        let tag = if is_link { "a" } else { "span" };
+        format_to!(buf, "<{tag} class=\"{}\"{}{}>{}</{tag}>", class, color, attrs, chunk);
     }
     buf.push_str("</code></pre>");
     buf
@@ -67,6 +100,75 @@ fn html_escape(text: &str) -> String {
     text.replace('<', "&lt;").replace('>', "&gt;")
 }
 
+fn attr_escape(text: &str) -> String {
+    html_escape(&text.replace('&', "&amp;").replace('"', "&quot;"))
+}
+
+/// Returns the attributes of the name at `range`: an anchor for declarations, the type or the
+/// signature as a tooltip, and a link to the definition for references. The `bool` is whether
+/// there is a link.
+fn name_attrs(
+    sema: &Semantics<'_, RootDatabase>,
+    file: &SyntaxNode,
+    range: TextRange,
+    pages: &FxHashMap<FileId, String>,
+) -> Option<(String, bool)> {
+    let token = file.covering_element(range).into_token().filter(|it| it.text_range() == range)?;
+    let is_decl = token.parent().map_or(false, |it| it.kind() == SyntaxKind::NAME);
+    let def = sema.descend_into_macros(token).into_iter().find_map(|token| {
+        match IdentClass::classify_token(sema, &token)?.definitions_no_ops().as_slice() {
+            &[def] => Some(def),
+            _ => None,
+        }
+    })?;
+
+    let mut attrs = String::new();
+    if is_decl {
+        format_to!(attrs, " id=\"{}\"", u32::from(range.start()));
+    }
+    if let Some(tooltip) = tooltip(sema.db, def) {
+        format_to!(attrs, " title=\"{}\"", attr_escape(&tooltip));
+    }
+    let href = if is_decl {
+        None
+    } else {
+        def.try_to_nav(sema.db).and_then(|nav| {
+            let page = pages.get(&nav.file_id)?;
+            Some(format!("{page}#{}", u32::from(nav.focus_or_full_range().start())))
+        })
+    };
+    let is_link = href.is_some();
+    if let Some(href) = href {
+        format_to!(attrs, " href=\"{}\"", attr_escape(&href));
+    }
+    Some((attrs, is_link))
+}
+
+fn tooltip(db: &RootDatabase, def: Definition) -> Option<String> {
+    let tooltip = match def {
+        Definition::Local(it) => format!("{}: {}", it.name(db), it.ty(db).display(db)),
+        Definition::Macro(it) => it.display(db).to_string(),
+        Definition::Field(it) => it.display(db).to_string(),
+        Definition::Module(it) => it.display(db).to_string(),
+        Definition::Function(it) => it.display(db).to_string(),
+        Definition::Adt(it) => it.display(db).to_string(),
+        Definition::Variant(it) => it.display(db).to_string(),
+        Definition::Const(it) => it.display(db).to_string(),
+        Definition::Static(it) => it.display(db).to_string(),
+        Definition::Trait(it) => it.display(db).to_string(),
+        Definition::TypeAlias(it) => it.display(db).to_string(),
+        _ => return None,
+    };
+    Some(tooltip)
+}
+
+const LINK_STYLE: &str = "
+<style>
+a                   { color: inherit; text-decoration: none; }
+a:hover             { text-decoration: underline; }
+</style>
+";
+
 const STYLE: &str = "
 <style>
 body                { margin: 0; }
@@ -1058,6 +1058,29 @@ fn foo(x: &fn(&dyn Trait)) {}
     let _ = analysis.highlight(HL_CONFIG, file_id).unwrap();
 }
 
+#[test]
+fn highlight_as_html_with_links() {
+    let (analysis, file_id) = fixture::file(
+        r#"
+struct Foo { x: u32 }
+fn f(foo: Foo) -> u32 {
+    foo.x
+}
+"#,
+    );
+    let pages = [(file_id, "lib.rs.html".to_owned())].into_iter().collect();
+    let html = analysis.highlight_as_html_with_links(file_id, false, &pages).unwrap();
+    for expected in [
+        r#" id="8" title="struct Foo">Foo</span>"#,
+        r#" title="struct Foo" href="lib.rs.html#8">Foo</a>"#,
+        r#" id="28" title="foo: Foo">foo</span>"#,
+        r#" title="foo: Foo" href="lib.rs.html#28">foo</a>"#,
+        r#" title="x: u32" href="lib.rs.html#14">x</a>"#,
+    ] {
+        assert!(html.contains(expected), "{expected} not found in:\n{html}");
+    }
+}
+
 /// Highlights the code given by the `ra_fixture` argument, renders the
 /// result as HTML, and compares it with the HTML file given as `snapshot`.
 /// Note that the `snapshot` file is overwritten by the rendered HTML.
@@ -0,0 +1,149 @@
+use hir::{DefWithBody, Semantics};
+use ide_db::base_db::FilePosition;
+use ide_db::RootDatabase;
+use syntax::{algo::find_node_at_offset, ast, AstNode};
+
+// Feature: Trace Const Eval
+//
+// Shows the statements executed while evaluating the const, static or function without parameters
+// at the cursor, with the values they stored, and the result or the error. Useful to find out why
+// a const has an unexpected value, or where it panicked.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **rust-analyzer: Trace Const Eval**
+// |===
+//
+// Long evaluations report their progress, and can be cancelled.
+pub(crate) fn trace_const_eval(
+    db: &RootDatabase,
+    position: FilePosition,
+    progress: &dyn Fn(usize) -> bool,
+) -> String {
+    body_trace(db, position, progress)
+        .unwrap_or_else(|| "Not inside a const, static or function".to_string())
+}
+
+fn body_trace(
+    db: &RootDatabase,
+    position: FilePosition,
+    progress: &dyn Fn(usize) -> bool,
+) -> Option<String> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+
+    let item = find_node_at_offset::<ast::Item>(source_file.syntax(), position.offset)?;
+    let def: DefWithBody = match item {
+        ast::Item::Fn(it) => sema.to_def(&it)?.into(),
+        ast::Item::Const(it) => sema.to_def(&it)?.into(),
+        ast::Item::Static(it) => sema.to_def(&it)?.into(),
+        _ => return None,
+    };
+    Some(def.debug_eval_trace(db, progress))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{panic::AssertUnwindSafe, sync::mpsc, thread, time::Duration};
+
+    use ide_db::base_db::{fixture::ChangeFixture, FilePosition};
+
+    use crate::{fixture, AnalysisHost};
+
+    fn check(ra_fixture: &str, expected_lines: &[&str]) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let trace = analysis.trace_const_eval(position, |_| true).unwrap();
+        for line in expected_lines {
+            assert!(trace.lines().any(|it| it.starts_with(line)), "no {line:?} in:\n{trace}");
+        }
+    }
+
+    #[test]
+    fn trace_with_nested_calls() {
+        check(
+            r#"
+const fn double(x: i32) -> i32 {
+    x * 2
+}
+const X$0: i32 = {
+    let y = double(3);
+    y + 1
+};
+"#,
+            &["// const X", "    // fn double", "// result: 7"],
+        );
+    }
+
+    #[test]
+    fn trace_until_the_panic() {
+        check(
+            r#"
+//- minicore: panic
+const X$0: i32 = {
+    let y = 2;
+    if y > 1 {
+        panic!("too big");
+    }
+    y
+};
+"#,
+            &["// const X", "// error: Panic("],
+        );
+    }
+
+    #[test]
+    fn interrupted_by_progress() {
+        let (analysis, position) = fixture::position(
+            r#"
+const X$0: i32 = {
+    let mut i = 0;
+    while i < 10000 {
+        i = i + 1;
+    }
+    i
+};
+"#,
+        );
+        let steps = std::sync::Mutex::new(Vec::new());
+        let trace = analysis
+            .trace_const_eval(position, |step| {
+                steps.lock().unwrap().push(step);
+                false
+            })
+            .unwrap();
+        assert!(trace.ends_with("// error: interrupted"), "{trace}");
+        assert_eq!(*steps.lock().unwrap(), [1000]);
+    }
+
+    #[test]
+    fn not_applicable_outside_of_bodies() {
+        check("struct S$0;", &["Not inside a const, static or function"]);
+        check("fn f$0(x: i32) {}", &["error: functions with parameters can't be evaluated"]);
+    }
+
+    #[test]
+    fn cancelled_by_changes() {
+        let mut host = AnalysisHost::default();
+        let fixture = ChangeFixture::parse("const X$0: i32 = loop {};");
+        host.db.apply_change(fixture.change);
+        let (file_id, offset) = fixture.file_position.unwrap();
+        let position = FilePosition { file_id, offset: offset.expect_offset() };
+
+        let (sender, started) = mpsc::channel();
+        let sender = AssertUnwindSafe(sender);
+        let analysis = host.analysis();
+        let evaluation = thread::spawn(move || {
+            analysis.trace_const_eval(position, move |_| {
+                let _ = sender.send(());
+                // Leave the time to cancel the evaluation before it hits the execution limit.
+                thread::sleep(Duration::from_millis(10));
+                true
+            })
+        });
+        started.recv().unwrap();
+        // Waits for the evaluation to unwind.
+        host.request_cancellation();
+        assert!(evaluation.join().unwrap().is_err(), "the evaluation wasn't cancelled");
+    }
+}
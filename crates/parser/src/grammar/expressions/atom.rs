@@ -70,6 +70,9 @@ pub(super) fn atom_expr(
     if let Some(m) = literal(p) {
         return Some((m, BlockLike::NotBlock));
     }
+    if p.at_contextual_kw(T![builtin]) && p.nth_at(1, T![#]) {
+        return Some((builtin_expr(p), BlockLike::NotBlock));
+    }
     if paths::is_path_start(p) {
         return Some(path_expr(p, r));
     }
@@ -570,6 +573,38 @@ fn yeet_expr(p: &mut Parser<'_>) -> CompletedMarker {
     m.complete(p, YEET_EXPR)
 }
 
+// test offset_of_expr
+// fn foo() {
+//     builtin # offset_of(Foo, bar.baz.0);
+// }
+fn builtin_expr(p: &mut Parser<'_>) -> CompletedMarker {
+    assert!(p.at_contextual_kw(T![builtin]));
+    let m = p.start();
+    p.bump_remap(T![builtin]);
+    p.bump(T![#]);
+    if p.at_contextual_kw(T![offset_of]) {
+        p.bump_remap(T![offset_of]);
+        p.expect(T!['(']);
+        type_(p);
+        p.expect(T![,]);
+        while !p.at(EOF) && !p.at(T![')']) {
+            if p.at(IDENT) || p.at(INT_NUMBER) {
+                name_ref_or_index(p);
+            } else {
+                p.err_and_bump("expected field name or number");
+            }
+            if !p.at(T![')']) {
+                p.expect(T![.]);
+            }
+        }
+        p.expect(T![')']);
+        m.complete(p, OFFSET_OF_EXPR)
+    } else {
+        p.err_recover("expected a builtin", TokenSet::EMPTY);
+        m.complete(p, ERROR)
+    }
+}
+
 // test continue_expr
 // fn foo() {
 //     loop {
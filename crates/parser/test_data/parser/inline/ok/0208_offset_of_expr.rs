@@ -0,0 +1,3 @@
+fn foo() {
+    builtin # offset_of(Foo, bar.baz.0);
+}
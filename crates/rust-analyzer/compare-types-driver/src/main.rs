@@ -0,0 +1,122 @@
+//! A rustc driver dumping the types of the expressions of a crate for
+//! `rust-analyzer compare-types`, see `crates/rust-analyzer/src/cli/compare_types.rs`.
+//!
+//! It's used as the workspace wrapper of cargo, and appends the types of the crates of the
+//! workspace to the file of `RA_RUSTC_TYPES`:
+//!
+//! ```text
+//! $ cargo build --release --manifest-path crates/rust-analyzer/compare-types-driver/Cargo.toml
+//! $ cd my-project
+//! $ RUSTC_WORKSPACE_WRAPPER=$DRIVER RA_RUSTC_TYPES=$PWD/types.txt \
+//!     CARGO_TARGET_DIR=target/compare-types cargo +nightly-2023-03-01 check
+//! $ rust-analyzer compare-types . --rustc-types types.txt
+//! ```
+//!
+//! The driver only works with the toolchain it was built with. The crates which are already checked
+//! aren't dumped, hence the separate target directory.
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use std::{env, fs, io::Write, process};
+
+use rustc_driver::{Callbacks, Compilation, RunCompiler};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_interface::{interface::Compiler, Queries};
+use rustc_middle::ty::{TyCtxt, TypeckResults};
+use rustc_span::{FileName, RealFileName, Span};
+
+const OUTPUT_VAR: &str = "RA_RUSTC_TYPES";
+
+fn main() {
+    // Cargo passes the path of rustc first, which takes the place of the name of the program.
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(output) = env::var_os(OUTPUT_VAR) else {
+        eprintln!("`{OUTPUT_VAR}` should be the file to dump the types to");
+        process::exit(1);
+    };
+    let mut dump = DumpTypes { lines: Vec::new() };
+    let exit_code = rustc_driver::catch_with_exit_code(|| RunCompiler::new(&args, &mut dump).run());
+    if !dump.lines.is_empty() {
+        // Crates are checked in parallel, so each one is appended at once.
+        let res = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&output)
+            .and_then(|mut file| file.write_all(dump.lines.concat().as_bytes()));
+        if let Err(e) = res {
+            eprintln!("failed to write the types to {}: {e}", output.to_string_lossy());
+            process::exit(1);
+        }
+    }
+    process::exit(exit_code);
+}
+
+struct DumpTypes {
+    lines: Vec<String>,
+}
+
+impl Callbacks for DumpTypes {
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().enter(|tcx| {
+            // The body of a closure is visited with its own body owner.
+            for def_id in tcx.hir().body_owners() {
+                let body = tcx.hir().body(tcx.hir().body_owned_by(def_id));
+                let mut visitor =
+                    ExprVisitor { tcx, results: tcx.typeck(def_id), lines: &mut self.lines };
+                intravisit::walk_body(&mut visitor, body);
+            }
+        });
+        Compilation::Continue
+    }
+}
+
+struct ExprVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    results: &'tcx TypeckResults<'tcx>,
+    lines: &'a mut Vec<String>,
+}
+
+impl<'tcx> Visitor<'tcx> for ExprVisitor<'_, 'tcx> {
+    fn visit_expr(&mut self, expr: &'tcx rustc_hir::Expr<'tcx>) {
+        if let Some(ty) = self.results.node_type_opt(expr.hir_id) {
+            if let Some(location) = self.location(expr.span) {
+                self.lines.push(format!("{location}: {ty}\n"));
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
+impl ExprVisitor<'_, '_> {
+    /// Returns `file:line:col: line:col` for `span`, with one-based lines and columns counted in
+    /// characters, if it's in a local file and not in a macro expansion.
+    fn location(&self, span: Span) -> Option<String> {
+        if span.from_expansion() {
+            return None;
+        }
+        let source_map = self.tcx.sess.source_map();
+        let start = source_map.lookup_char_pos(span.lo());
+        let end = source_map.lookup_char_pos(span.hi());
+        let FileName::Real(RealFileName::LocalPath(path)) = &start.file.name else {
+            return None;
+        };
+        Some(format!(
+            "{}:{}:{}: {}:{}",
+            path.display(),
+            start.line,
+            start.col.0 + 1,
+            end.line,
+            end.col.0 + 1
+        ))
+    }
+}
@@ -19,7 +19,7 @@ use tracing_subscriber::{
     layer::SubscriberExt,
     registry::LookupSpan,
     util::SubscriberInitExt,
-    EnvFilter, Registry,
+    EnvFilter, Layer, Registry,
 };
 use tracing_tree::HierarchicalLayer;
 
@@ -76,23 +76,22 @@ impl Logger {
         let ra_fmt_layer =
             tracing_subscriber::fmt::layer().event_format(LoggerFormatter).with_writer(writer);
 
-        match chalk_level_dir {
+        // The filter only applies to the logs, the trace capture layer has a filter of its own.
+        let (filter, chalk_layer) = match chalk_level_dir {
             Some(val) => {
-                Registry::default()
-                    .with(
-                        self.filter
-                            .add_directive(format!("chalk_solve={val}").parse()?)
-                            .add_directive(format!("chalk_ir={val}").parse()?)
-                            .add_directive(format!("chalk_recursive={val}").parse()?),
-                    )
-                    .with(ra_fmt_layer)
-                    .with(chalk_layer)
-                    .init();
-            }
-            None => {
-                Registry::default().with(self.filter).with(ra_fmt_layer).init();
+                let filter = self
+                    .filter
+                    .add_directive(format!("chalk_solve={val}").parse()?)
+                    .add_directive(format!("chalk_ir={val}").parse()?)
+                    .add_directive(format!("chalk_recursive={val}").parse()?);
+                (filter, Some(chalk_layer))
             }
+            None => (self.filter, None),
         };
+        Registry::default()
+            .with(ra_fmt_layer.and_then(chalk_layer).with_filter(filter))
+            .with(rust_analyzer::trace_capture_layer())
+            .init();
 
         Ok(())
     }
@@ -7,7 +7,11 @@
 mod logger;
 mod rustc_wrapper;
 
-use std::{env, fs, path::Path, process};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
 
 use lsp_server::Connection;
 use rust_analyzer::{cli::flags, config::Config, from_json, Result};
@@ -74,7 +78,7 @@ fn try_main(flags: flags::RustAnalyzer) -> Result<()> {
                 println!("rust-analyzer {}", rust_analyzer::version());
                 return Ok(());
             }
-            with_extra_thread("LspServer", run_server)?;
+            with_extra_thread("LspServer", move || run_server(cmd.query_socket))?;
         }
         flags::RustAnalyzerCmd::ProcMacro(flags::ProcMacro) => {
             with_extra_thread("MacroExpander", || proc_macro_srv_cli::run().map_err(Into::into))?;
@@ -88,6 +92,9 @@ fn try_main(flags: flags::RustAnalyzer) -> Result<()> {
         flags::RustAnalyzerCmd::Search(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Lsif(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Scip(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::UsageReport(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::CompareTypes(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::EvalConsts(cmd) => cmd.run()?,
     }
     Ok(())
 }
@@ -145,10 +152,11 @@ fn with_extra_thread(
     }
 }
 
-fn run_server() -> Result<()> {
+fn run_server(query_socket: Option<PathBuf>) -> Result<()> {
     tracing::info!("server version {} will start", rust_analyzer::version());
 
     let (connection, io_threads) = Connection::stdio();
+    let query_clients = query_socket.map(rust_analyzer::QueryClients::listen).transpose()?;
 
     let (initialize_id, initialize_params) = connection.initialize_start()?;
     tracing::info!("InitializeParams: {}", initialize_params);
@@ -216,7 +224,7 @@ fn run_server() -> Result<()> {
         config.rediscover_workspaces();
     }
 
-    rust_analyzer::main_loop(config, connection)?;
+    rust_analyzer::main_loop_with_query_clients(config, connection, query_clients)?;
 
     io_threads.join()?;
     tracing::info!("server did shut down");
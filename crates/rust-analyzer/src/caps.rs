@@ -140,6 +140,7 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
         ))),
         inline_value_provider: None,
         experimental: Some(json!({
+            "changeSignature": true,
             "externalDocs": true,
             "hoverRange": true,
             "joinLines": true,
@@ -151,6 +152,7 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
             "runnables": {
                 "kinds": [ "cargo" ],
             },
+            "safeDelete": true,
             "ssr": true,
             "workspaceSymbolScopeKindFiltering": true,
         })),
@@ -209,6 +211,7 @@ fn code_action_capabilities(client_caps: &ClientCapabilities) -> CodeActionProvi
                     CodeActionKind::REFACTOR_EXTRACT,
                     CodeActionKind::REFACTOR_INLINE,
                     CodeActionKind::REFACTOR_REWRITE,
+                    CodeActionKind::SOURCE,
                 ]),
                 resolve_provider: Some(true),
                 work_done_progress_options: Default::default(),
@@ -10,6 +10,9 @@ mod diagnostics;
 mod ssr;
 mod lsif;
 mod scip;
+mod usage_report;
+mod compare_types;
+mod eval_consts;
 
 mod progress_report;
 
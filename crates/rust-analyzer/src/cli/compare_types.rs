@@ -0,0 +1,222 @@
+//! Compare the types inferred by rust-analyzer with the ones of rustc, to turn "some types are
+//! wrong somewhere" reports into lists of the most frequent differences.
+//!
+//! The types of rustc are read from a dump with one expression per line, like
+//! `src/main.rs:3:13: 3:20: Vec<i32>`, with the start and end of the span of the expression,
+//! relative to the workspace root, followed by its type. Lines and columns are one-based, and
+//! columns are counted in characters like rustc does. The dump is produced by the rustc driver of
+//! `crates/rust-analyzer/compare-types-driver`, see its documentation for how to run it.
+//!
+//! The types are compared as displayed, without the paths of the types and without lifetimes,
+//! since rust-analyzer and rustc don't display those the same way. Closures and opaque types are
+//! skipped, they don't have a common display.
+
+use std::{env, fs};
+
+use hir::{HirDisplay, Semantics};
+use ide_db::line_index::{WideEncoding, WideLineCol};
+use itertools::Itertools;
+use project_model::{CargoConfig, RustLibSource};
+use rustc_hash::FxHashMap;
+use syntax::{ast, AstNode, TextRange};
+use vfs::{AbsPathBuf, VfsPath};
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
+};
+
+const DEFAULT_LIMIT: usize = 20;
+/// The number of locations printed for each difference.
+const LOCATIONS: usize = 5;
+
+impl flags::CompareTypes {
+    pub fn run(self) -> anyhow::Result<()> {
+        let root = AbsPathBuf::assert(env::current_dir()?.join(&self.path)).normalize();
+        let dump = fs::read_to_string(&self.rustc_types)?;
+
+        let mut cargo_config = CargoConfig::default();
+        cargo_config.sysroot = Some(RustLibSource::Discover);
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+        let sema = Semantics::new(db);
+
+        let (mut matching, mut skipped, mut not_found) = (0, 0, 0);
+        let mut differences: FxHashMap<(String, String), Vec<String>> = FxHashMap::default();
+        for line in dump.lines().filter(|it| !it.trim().is_empty()) {
+            let Some(entry) = Entry::parse(line) else {
+                anyhow::bail!("invalid line in the types of rustc: `{line}`");
+            };
+            let path = VfsPath::from(root.join(entry.file));
+            let Some(file_id) = vfs.file_id(&path) else {
+                not_found += 1;
+                continue;
+            };
+            let line_index = analysis.file_line_index(file_id)?;
+            let offset = |it| line_index.offset(line_index.to_utf8(WideEncoding::Utf32, it));
+            let range = offset(entry.start)
+                .zip(offset(entry.end))
+                .map(|(start, end)| TextRange::new(start, end));
+            let file = sema.parse(file_id);
+            let expr = range.and_then(|range| {
+                let element = file.syntax().covering_element(range);
+                element
+                    .ancestors()
+                    .take_while(|it| it.text_range() == range)
+                    .find_map(ast::Expr::cast)
+            });
+            let Some(ty) = expr.and_then(|expr| sema.type_of_expr(&expr)) else {
+                not_found += 1;
+                continue;
+            };
+            let inferred = ty.original.display(db).to_string();
+            if is_skipped(&inferred) || is_skipped(entry.ty) {
+                skipped += 1;
+                continue;
+            }
+            if normalize(&inferred) == normalize(entry.ty) {
+                matching += 1;
+                continue;
+            }
+            let location = line.split(": ").next().unwrap_or(line).to_owned();
+            differences.entry((entry.ty.to_owned(), inferred)).or_default().push(location);
+        }
+
+        let different: usize = differences.values().map(Vec::len).sum();
+        println!(
+            "compared {} expressions: {matching} matching, {different} different, \
+             {skipped} skipped, {not_found} not found",
+            matching + different + skipped + not_found
+        );
+        let mut differences = differences.into_iter().collect_vec();
+        differences.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+        for ((expected, inferred), locations) in
+            differences.iter().take(self.limit.unwrap_or(DEFAULT_LIMIT))
+        {
+            println!();
+            println!("{}x rustc: `{expected}`, rust-analyzer: `{inferred}`", locations.len());
+            for location in locations.iter().take(LOCATIONS) {
+                println!("    {location}");
+            }
+            if locations.len() > LOCATIONS {
+                println!("    ... and {} more", locations.len() - LOCATIONS);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A line of the dump of the types of rustc.
+#[derive(Debug, PartialEq, Eq)]
+struct Entry<'a> {
+    file: &'a str,
+    /// The zero-based start of the expression, with the column in characters.
+    start: WideLineCol,
+    /// The zero-based end of the expression, with the column in characters.
+    end: WideLineCol,
+    ty: &'a str,
+}
+
+impl<'a> Entry<'a> {
+    /// Parses `file:line:col: line:col: type`.
+    fn parse(line: &'a str) -> Option<Entry<'a>> {
+        let (start, rest) = line.split_once(": ")?;
+        let (end, ty) = rest.split_once(": ")?;
+        // The file may contain colons, like the drive of a Windows path.
+        let (file, start) = start
+            .rsplitn(3, ':')
+            .collect_tuple()
+            .map(|(col, line, file)| (file, format!("{line}:{col}")))?;
+        Some(Entry { file, start: line_col(&start)?, end: line_col(end)?, ty: ty.trim() })
+    }
+}
+
+/// Parses a one-based `line:col`, with the column in characters.
+fn line_col(text: &str) -> Option<WideLineCol> {
+    let (line, col) = text.split_once(':')?;
+    let line = line.trim().parse::<u32>().ok()?.checked_sub(1)?;
+    let col = col.trim().parse::<u32>().ok()?.checked_sub(1)?;
+    Some(WideLineCol { line, col })
+}
+
+fn is_skipped(ty: &str) -> bool {
+    ty.contains("closure") || ty.contains("impl ") || ty.contains('|') || ty.contains("{async")
+}
+
+/// Removes the paths of the types, the lifetimes and the whitespace of `ty`, like
+/// `std::vec::Vec<&'a str>` to `Vec<&str>`.
+fn normalize(ty: &str) -> String {
+    let mut res = String::with_capacity(ty.len());
+    let mut chars = ty.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            // Skip the lifetime, and the comma following it in generic arguments.
+            while chars.next_if(|it| it.is_alphanumeric() || *it == '_').is_some() {}
+            while chars.next_if(|it| it.is_whitespace()).is_some() {}
+            chars.next_if_eq(&',');
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut ident = c.to_string();
+            while let Some(c) = chars.next_if(|it| it.is_alphanumeric() || *it == '_') {
+                ident.push(c);
+            }
+            if chars.peek() == Some(&':') {
+                // A path qualifier, `a::`.
+                chars.next();
+                chars.next_if_eq(&':');
+            } else {
+                res.push_str(&ident);
+            }
+        } else if !c.is_whitespace() {
+            res.push(c);
+        }
+    }
+    res.replace("<>", "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_entry() {
+        let entry = Entry::parse("src/main.rs:3:13: 3:20: Vec<i32>").unwrap();
+        assert_eq!(
+            entry,
+            Entry {
+                file: "src/main.rs",
+                start: WideLineCol { line: 2, col: 12 },
+                end: WideLineCol { line: 2, col: 19 },
+                ty: "Vec<i32>",
+            }
+        );
+
+        let entry = Entry::parse(r"C:\project\src\lib.rs:1:1: 2:3: &'static str").unwrap();
+        assert_eq!(entry.file, r"C:\project\src\lib.rs");
+        assert_eq!(entry.end, WideLineCol { line: 1, col: 2 });
+        assert_eq!(entry.ty, "&'static str");
+
+        assert_eq!(Entry::parse("src/main.rs:0:1: 1:1: u8"), None);
+        assert_eq!(Entry::parse("src/main.rs:1: 1:1: u8"), None);
+        assert_eq!(Entry::parse("src/main.rs: u8"), None);
+    }
+
+    #[test]
+    fn normalize_types() {
+        assert_eq!(normalize("std::vec::Vec<&'a str>"), "Vec<&str>");
+        assert_eq!(normalize("Foo<'a, i32>"), "Foo<i32>");
+        assert_eq!(normalize("Foo<'_>"), "Foo");
+        assert_eq!(normalize("&'static mut [u8]"), normalize("&mut [u8]"));
+        assert_eq!(
+            normalize("core::option::Option<alloc::string::String>"),
+            normalize("Option<String>")
+        );
+        assert_eq!(normalize("(i32, u8)"), "(i32,u8)");
+    }
+}
@@ -0,0 +1,110 @@
+//! Evaluates the consts and statics of the crates of a workspace, and prints their values, under
+//! the debug or the release profile, or under both to compare them.
+//!
+//! The profile decides whether `cfg(debug_assertions)` holds in the crates outside of the sysroot.
+//! Overflowing arithmetic panics under both, since it's a compile error in consts.
+
+use std::collections::BTreeMap;
+
+use hir::{AssocItem, Crate, HirDisplay, ModuleDef};
+use ide::{BuildProfile, RootDatabase};
+use ide_db::base_db::SourceDatabaseExt;
+use project_model::{CargoConfig, RustLibSource};
+
+use crate::cli::{
+    flags::{self, EvalProfile},
+    load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
+};
+
+impl flags::EvalConsts {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut cargo_config = CargoConfig::default();
+        cargo_config.sysroot = Some(RustLibSource::Discover);
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (mut host, _vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+
+        let profiles = match self.profile.unwrap_or(EvalProfile::Debug) {
+            EvalProfile::Debug => vec![BuildProfile::Debug],
+            EvalProfile::Release => vec![BuildProfile::Release],
+            EvalProfile::Both => vec![BuildProfile::Debug, BuildProfile::Release],
+        };
+        // The items are matched by path across profiles, since changing the crate graph changes
+        // the items themselves.
+        let mut values: BTreeMap<String, Vec<Option<String>>> = BTreeMap::new();
+        for (idx, &profile) in profiles.iter().enumerate() {
+            host.set_build_profile(profile);
+            let db = host.raw_database();
+            for (path, value) in self.eval_all(db) {
+                let entry = values.entry(path).or_insert_with(|| vec![None; profiles.len()]);
+                entry[idx] = Some(value);
+            }
+        }
+
+        for (path, values) in values {
+            let first = &values[0];
+            if values.iter().all(|it| it == first) {
+                println!("{path} = {}", render(first.as_deref()));
+                continue;
+            }
+            let values = values
+                .iter()
+                .zip(&profiles)
+                .map(|(value, profile)| format!("{} ({profile})", render(value.as_deref())))
+                .collect::<Vec<_>>();
+            println!("{path} = {}", values.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Evaluates the consts and statics of the local crates, and returns them by path.
+    fn eval_all(&self, db: &RootDatabase) -> Vec<(String, String)> {
+        let mut res = Vec::new();
+        for krate in Crate::all(db) {
+            let root = krate.root_module(db);
+            let file_id = root.definition_source(db).file_id.original_file(db);
+            if db.source_root(db.file_source_root(file_id)).is_library {
+                continue;
+            }
+            let crate_name =
+                krate.display_name(db).map_or_else(|| "?".to_owned(), |it| it.to_string());
+            let mut push = |path: String, value: String| {
+                let path = format!("{crate_name}::{path}");
+                if self.only.as_ref().map_or(true, |only| path.contains(only)) {
+                    res.push((path, value));
+                }
+            };
+            for module in krate.modules(db) {
+                for decl in module.declarations(db) {
+                    let value = match decl {
+                        ModuleDef::Const(it) if it.name(db).is_some() => it.render_eval(db),
+                        ModuleDef::Static(it) => it.render_eval(db),
+                        _ => continue,
+                    };
+                    if let Some(path) = decl.canonical_path(db) {
+                        push(path, value.unwrap_or_else(|e| format!("error: {e:?}")));
+                    }
+                }
+                for impl_def in module.impl_defs(db) {
+                    for item in impl_def.items(db) {
+                        let AssocItem::Const(it) = item else { continue };
+                        let Some(name) = it.name(db) else { continue };
+                        let self_ty = impl_def.self_ty(db).display(db).to_string();
+                        let value = it.render_eval(db).unwrap_or_else(|e| format!("error: {e:?}"));
+                        push(format!("<{self_ty}>::{name}"), value);
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Renders the value of an item under a profile, where it may not be defined.
+fn render(value: Option<&str>) -> &str {
+    value.unwrap_or("<not defined>")
+}
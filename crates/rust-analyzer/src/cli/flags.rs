@@ -34,6 +34,11 @@ xflags::xflags! {
 
             /// Dump a LSP config JSON schema.
             optional --print-config-schema
+
+            /// [Unstable] Also listen on a Unix socket at this path for query clients, secondary
+            /// clients like another editor which share the analysis of the main client. Only the
+            /// user running the server can connect to it.
+            optional --query-socket path: PathBuf
         }
 
         /// Parse stdin.
@@ -45,10 +50,18 @@ xflags::xflags! {
         /// Parse stdin and print the list of symbols.
         cmd symbols {}
 
-        /// Highlight stdin as html.
+        /// Highlight stdin as html, or render the files of a project to html pages.
         cmd highlight {
+            /// A directory with Cargo.toml to render all the files of, or a single file of a
+            /// project to render.
+            optional path: PathBuf
+
             /// Enable rainbow highlighting of identifiers.
             optional --rainbow
+            /// Output format, only `html` is supported.
+            optional --format format: HighlightFormat
+            /// Directory to write the pages to when rendering a project.
+            optional -o, --output-dir dir: PathBuf
         }
 
         /// Batch typecheck project and print summary statistics
@@ -115,6 +128,47 @@ xflags::xflags! {
         cmd scip {
             required path: PathBuf
         }
+
+        /// Print a JSON report of the most referenced items, the largest modules, the deepest
+        /// dependency chains and the most instantiated generic functions of a project.
+        cmd usage-report {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+
+            /// Number of entries to report in each category, 20 by default.
+            optional --limit count: usize
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+        }
+
+        /// Compare the types inferred for the expressions of a project with the ones of rustc, and
+        /// print the most frequent differences.
+        cmd compare-types {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+            /// The types of rustc, with one `file:line:col: line:col: type` line per expression,
+            /// as dumped by `crates/rust-analyzer/compare-types-driver`.
+            required --rustc-types file: PathBuf
+
+            /// Number of differences to print, 20 by default.
+            optional --limit count: usize
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+        }
+
+        /// Evaluate the consts and statics of a project, and print their values.
+        cmd eval-consts {
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+
+            /// The profile deciding whether `cfg(debug_assertions)` holds: `debug`, `release`, or
+            /// `both` to print the values which differ between them. `debug` by default.
+            optional --profile profile: EvalProfile
+            /// Only evaluate the items whose path contains this text.
+            optional -o, --only path: String
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+        }
     }
 }
 
@@ -144,12 +198,16 @@ pub enum RustAnalyzerCmd {
     ProcMacro(ProcMacro),
     Lsif(Lsif),
     Scip(Scip),
+    UsageReport(UsageReport),
+    CompareTypes(CompareTypes),
+    EvalConsts(EvalConsts),
 }
 
 #[derive(Debug)]
 pub struct LspServer {
     pub version: bool,
     pub print_config_schema: bool,
+    pub query_socket: Option<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -162,7 +220,11 @@ pub struct Symbols;
 
 #[derive(Debug)]
 pub struct Highlight {
+    pub path: Option<PathBuf>,
+
     pub rainbow: bool,
+    pub format: Option<HighlightFormat>,
+    pub output_dir: Option<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -216,6 +278,32 @@ pub struct Scip {
     pub path: PathBuf,
 }
 
+#[derive(Debug)]
+pub struct UsageReport {
+    pub path: PathBuf,
+
+    pub limit: Option<usize>,
+    pub disable_build_scripts: bool,
+}
+
+#[derive(Debug)]
+pub struct CompareTypes {
+    pub path: PathBuf,
+
+    pub rustc_types: PathBuf,
+    pub limit: Option<usize>,
+    pub disable_build_scripts: bool,
+}
+
+#[derive(Debug)]
+pub struct EvalConsts {
+    pub path: PathBuf,
+
+    pub profile: Option<EvalProfile>,
+    pub only: Option<String>,
+    pub disable_build_scripts: bool,
+}
+
 impl RustAnalyzer {
     #[allow(dead_code)]
     pub fn from_env_or_exit() -> Self {
@@ -239,6 +327,18 @@ pub enum OutputFormat {
     Csv,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum HighlightFormat {
+    Html,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalProfile {
+    Debug,
+    Release,
+    Both,
+}
+
 impl RustAnalyzer {
     pub fn verbosity(&self) -> Verbosity {
         if self.quiet {
@@ -262,3 +362,27 @@ impl FromStr for OutputFormat {
         }
     }
 }
+
+impl FromStr for HighlightFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(Self::Html),
+            _ => Err(format!("unknown highlight format `{s}`")),
+        }
+    }
+}
+
+impl FromStr for EvalProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debug" => Ok(Self::Debug),
+            "release" => Ok(Self::Release),
+            "both" => Ok(Self::Both),
+            _ => Err(format!("unknown profile `{s}`")),
+        }
+    }
+}
@@ -1,14 +1,116 @@
-//! Read Rust code on stdin, print HTML highlighted version to stdout.
+//! Read Rust code on stdin, print HTML highlighted version to stdout. Alternatively, render the
+//! files of a project to a directory of HTML pages linking to each other.
+
+use std::{env, fs, path::Path};
 
 use ide::Analysis;
+use ide_db::base_db::SourceDatabaseExt;
+use project_model::{CargoConfig, RustLibSource};
+use rustc_hash::FxHashMap;
+use vfs::AbsPathBuf;
 
-use crate::cli::{flags, read_stdin};
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
+    read_stdin,
+};
 
 impl flags::Highlight {
     pub fn run(self) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            return self.render_project(path);
+        }
         let (analysis, file_id) = Analysis::from_single_file(read_stdin()?);
         let html = analysis.highlight_as_html(file_id, self.rainbow).unwrap();
         println!("{html}");
         Ok(())
     }
+
+    fn render_project(&self, path: &Path) -> anyhow::Result<()> {
+        let Some(output_dir) = &self.output_dir else {
+            anyhow::bail!("`--output-dir` is required when rendering a project");
+        };
+        let path = env::current_dir()?.join(path);
+        let (root, only) = if path.is_file() {
+            let root = path.parent().map_or_else(|| path.clone(), Path::to_path_buf);
+            (root, Some(AbsPathBuf::assert(path).normalize()))
+        } else {
+            (path, None)
+        };
+
+        let mut cargo_config = CargoConfig::default();
+        cargo_config.sysroot = Some(RustLibSource::Discover);
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: true,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&root, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        // Every file of the project gets a page, so that the rendered files can link to all of
+        // them, even when only one file is rendered.
+        let root = AbsPathBuf::assert(root).normalize();
+        let mut pages = FxHashMap::default();
+        for (file_id, vfs_path) in vfs.iter() {
+            let Some(file_path) = vfs_path.as_path() else { continue };
+            let Some(relative) = file_path.strip_prefix(&root) else { continue };
+            let is_rust = file_path.extension().map_or(false, |it| it == "rs");
+            if !is_rust || db.source_root(db.file_source_root(file_id)).is_library {
+                continue;
+            }
+            let relative: &Path = relative.as_ref();
+            let page = relative.to_string_lossy().replace('\\', "/");
+            pages.insert(file_id, format!("{page}.html"));
+        }
+
+        let mut rendered = Vec::new();
+        for (&file_id, page) in &pages {
+            if let Some(only) = &only {
+                if vfs.file_path(file_id).as_path() != Some(only.as_path()) {
+                    continue;
+                }
+            }
+            let html =
+                analysis.highlight_as_html_with_links(file_id, self.rainbow, &pages).unwrap();
+            // Links are relative to the output directory.
+            let base = match page.matches('/').count() {
+                0 => "./".to_owned(),
+                depth => "../".repeat(depth),
+            };
+            let out = output_dir.join(page);
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&out, page_html(&base, page, &html))?;
+            rendered.push(page.as_str());
+        }
+
+        match only {
+            Some(only) if rendered.is_empty() => {
+                anyhow::bail!("`{}` is not a file of the project", only.display())
+            }
+            Some(_) => {}
+            None => {
+                rendered.sort_unstable();
+                let mut index = String::from("<ul>\n");
+                for page in &rendered {
+                    let name = page.trim_end_matches(".html");
+                    index.push_str(&format!("<li><a href=\"{page}\">{name}</a></li>\n"));
+                }
+                index.push_str("</ul>");
+                fs::write(output_dir.join("index.html"), page_html("./", "index", &index))?;
+            }
+        }
+        eprintln!("rendered {} files to {}", rendered.len(), output_dir.display());
+        Ok(())
+    }
+}
+
+fn page_html(base: &str, title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<meta charset=\"utf-8\">\n<base href=\"{base}\">\n<title>{title}</title>\n{body}\n"
+    )
 }
@@ -0,0 +1,241 @@
+//! Report the most referenced items, the largest modules, the deepest dependency chains and the
+//! most instantiated generic functions of a project, to find targets for refactorings.
+//!
+//! The report is printed as a JSON object with the following fields, each sorted in descending
+//! order and truncated to `--limit` entries:
+//!
+//! * `items`: `{ path, kind, references }` for the functions, types, traits, consts and statics.
+//! * `modules`: `{ path, items }` with the number of items declared in the module.
+//! * `dependencyChains`: `{ crate, depth, chain }` with the longest chain of dependencies of the
+//!   crate, starting with the crate itself.
+//! * `generics`: `{ path, instantiations, references }` for generic functions, where
+//!   `instantiations` is the number of distinct signatures the function is called with.
+
+use hir::{AssocItem, Crate, HirDisplay, Module, ModuleDef, Semantics};
+use ide::RootDatabase;
+use ide_db::{base_db::SourceDatabaseExt, defs::Definition};
+use itertools::Itertools;
+use project_model::{CargoConfig, RustLibSource};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use syntax::{ast, AstNode};
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice},
+};
+
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    items: Vec<ItemUsage>,
+    modules: Vec<ModuleSize>,
+    dependency_chains: Vec<DependencyChain>,
+    generics: Vec<GenericUsage>,
+}
+
+#[derive(Serialize)]
+struct ItemUsage {
+    path: String,
+    kind: &'static str,
+    references: usize,
+}
+
+#[derive(Serialize)]
+struct ModuleSize {
+    path: String,
+    items: usize,
+}
+
+#[derive(Serialize)]
+struct DependencyChain {
+    #[serde(rename = "crate")]
+    krate: String,
+    depth: usize,
+    chain: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct GenericUsage {
+    path: String,
+    instantiations: usize,
+    references: usize,
+}
+
+impl flags::UsageReport {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut cargo_config = CargoConfig::default();
+        cargo_config.sysroot = Some(RustLibSource::Discover);
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+            prefill_caches: false,
+        };
+        let (host, _vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let sema = Semantics::new(db);
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+
+        let krates = Crate::all(db).into_iter().filter(|&krate| is_local(db, krate)).collect_vec();
+
+        let mut modules = Vec::new();
+        let mut items = Vec::new();
+        let mut worklist = krates.iter().map(|krate| krate.root_module(db)).collect_vec();
+        while let Some(module) = worklist.pop() {
+            worklist.extend(module.children(db));
+            let mut num_items = 0;
+            for decl in module.declarations(db) {
+                num_items += 1;
+                items.extend(item_kind(decl).map(|kind| (decl, kind, item_path(db, decl))));
+            }
+            for impl_def in module.impl_defs(db) {
+                let self_ty = impl_def.self_ty(db).display(db).to_string();
+                for item in impl_def.items(db) {
+                    num_items += 1;
+                    if let AssocItem::Function(f) = item {
+                        let path =
+                            format!("{}::{self_ty}::{}", module_path(db, module), f.name(db));
+                        items.push((f.into(), "function", path));
+                    }
+                }
+            }
+            modules.push(ModuleSize { path: module_path(db, module), items: num_items });
+        }
+
+        eprintln!("searching the references of {} items", items.len());
+        let mut item_usages = Vec::new();
+        let mut generics = Vec::new();
+        for (def, kind, path) in items {
+            let usages = Definition::from(def).usages(&sema).all();
+            let references = usages.iter().map(|(_, refs)| refs.len()).sum();
+            if let ModuleDef::Function(f) = def {
+                if !hir::GenericDef::from(f).type_params(db).is_empty() {
+                    let instantiations = usages
+                        .iter()
+                        .flat_map(|(_, refs)| refs)
+                        .filter_map(|reference| match &reference.name {
+                            ast::NameLike::NameRef(name_ref) => instantiation(&sema, name_ref),
+                            _ => None,
+                        })
+                        .collect::<FxHashSet<_>>()
+                        .len();
+                    generics.push(GenericUsage { path: path.clone(), instantiations, references });
+                }
+            }
+            item_usages.push(ItemUsage { path, kind, references });
+        }
+
+        let mut chains = FxHashMap::default();
+        let mut dependency_chains = krates
+            .iter()
+            .map(|&krate| {
+                let chain = longest_chain(db, krate, &mut chains);
+                DependencyChain {
+                    krate: crate_name(db, krate),
+                    depth: chain.len() - 1,
+                    chain: chain.into_iter().map(|it| crate_name(db, it)).collect(),
+                }
+            })
+            .collect_vec();
+
+        item_usages
+            .sort_by(|a, b| b.references.cmp(&a.references).then_with(|| a.path.cmp(&b.path)));
+        modules.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.path.cmp(&b.path)));
+        dependency_chains.sort_by(|a, b| b.depth.cmp(&a.depth).then_with(|| a.krate.cmp(&b.krate)));
+        generics.sort_by(|a, b| {
+            (b.instantiations, b.references)
+                .cmp(&(a.instantiations, a.references))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        item_usages.truncate(limit);
+        modules.truncate(limit);
+        dependency_chains.truncate(limit);
+        generics.truncate(limit);
+
+        let report = Report { items: item_usages, modules, dependency_chains, generics };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+}
+
+fn is_local(db: &RootDatabase, krate: Crate) -> bool {
+    let file_id = krate.root_module(db).definition_source(db).file_id.original_file(db);
+    !db.source_root(db.file_source_root(file_id)).is_library
+}
+
+fn item_kind(def: ModuleDef) -> Option<&'static str> {
+    let kind = match def {
+        ModuleDef::Function(_) => "function",
+        ModuleDef::Adt(hir::Adt::Struct(_)) => "struct",
+        ModuleDef::Adt(hir::Adt::Enum(_)) => "enum",
+        ModuleDef::Adt(hir::Adt::Union(_)) => "union",
+        ModuleDef::Trait(_) => "trait",
+        ModuleDef::TypeAlias(_) => "typeAlias",
+        ModuleDef::Const(_) => "const",
+        ModuleDef::Static(_) => "static",
+        ModuleDef::Module(_)
+        | ModuleDef::Variant(_)
+        | ModuleDef::TraitAlias(_)
+        | ModuleDef::BuiltinType(_)
+        | ModuleDef::Macro(_) => return None,
+    };
+    Some(kind)
+}
+
+fn crate_name(db: &RootDatabase, krate: Crate) -> String {
+    krate.display_name(db).map_or_else(|| "unknown".to_owned(), |it| it.to_string())
+}
+
+fn module_path(db: &RootDatabase, module: Module) -> String {
+    let mut segments = module.path_to_root(db).iter().filter_map(|it| it.name(db)).collect_vec();
+    segments.reverse();
+    let krate = crate_name(db, module.krate());
+    segments.iter().fold(krate, |acc, it| format!("{acc}::{it}"))
+}
+
+fn item_path(db: &RootDatabase, def: ModuleDef) -> String {
+    match (def.module(db), def.name(db)) {
+        (Some(module), Some(name)) => format!("{}::{name}", module_path(db, module)),
+        _ => "unknown".to_owned(),
+    }
+}
+
+/// Returns the signature the function referred to by `name_ref` is called with, like
+/// `fn(i32) -> Option<i32>`, if `name_ref` is the callee of a call.
+fn instantiation(sema: &Semantics<'_, RootDatabase>, name_ref: &ast::NameRef) -> Option<String> {
+    let callable = match name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        Some(call) => sema.resolve_method_call_as_callable(&call)?,
+        None => {
+            // The function is the last segment of the path of a path expression.
+            let path = name_ref.syntax().parent()?.parent().and_then(ast::Path::cast)?;
+            let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+            sema.type_of_expr(&ast::Expr::PathExpr(path_expr))?.original.as_callable(sema.db)?
+        }
+    };
+    let params =
+        callable.params(sema.db).into_iter().map(|(_, ty)| ty.display(sema.db).to_string());
+    Some(format!("fn({}) -> {}", params.format(", "), callable.return_type().display(sema.db)))
+}
+
+/// Returns the longest chain of dependencies starting at `krate`.
+fn longest_chain(
+    db: &RootDatabase,
+    krate: Crate,
+    chains: &mut FxHashMap<Crate, Vec<Crate>>,
+) -> Vec<Crate> {
+    if let Some(chain) = chains.get(&krate) {
+        return chain.clone();
+    }
+    let mut chain = krate
+        .dependencies(db)
+        .into_iter()
+        .map(|dep| longest_chain(db, dep.krate, chains))
+        .max_by_key(Vec::len)
+        .unwrap_or_default();
+    chain.insert(0, krate);
+    chains.insert(krate, chain.clone());
+    chain
+}
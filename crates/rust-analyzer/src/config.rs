@@ -11,9 +11,10 @@ use std::{fmt, iter, ops::Not, path::PathBuf};
 
 use flycheck::FlycheckConfig;
 use ide::{
-    AssistConfig, CallableSnippets, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode,
-    HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat, InlayHintsConfig,
-    JoinLinesConfig, Snippet, SnippetScope,
+    AssistConfig, BuildProfile, CallableSnippets, CompletionConfig, DiagnosticsConfig,
+    ExprFillDefaultMode, HighlightConfig, HighlightRelatedConfig, HintPattern, HoverConfig,
+    HoverDocFormat, InlayFieldsToResolve, InlayHintsConfig, JoinLinesConfig, Snippet, SnippetScope,
+    TypeHintFilter,
 };
 use ide_db::{
     imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
@@ -61,6 +62,9 @@ config_data! {
         assist_emitMustUse: bool               = "false",
         /// Placeholder expression to use for missing expressions in assists.
         assist_expressionFillDefault: ExprFillDefaultDef              = "\"todo\"",
+        /// Whether the extract variable assist should annotate the new binding with its type,
+        /// importing the types which are not in scope yet.
+        assist_extractVariable_ascribeType: bool = "false",
 
         /// Warm up caches on project load.
         cachePriming_enable: bool = "true",
@@ -194,12 +198,18 @@ config_data! {
         /// Aliased as `"checkOnSave.targets"`.
         check_targets | checkOnSave_targets | checkOnSave_target: Option<CheckOnSaveTargets> = "null",
 
+        /// Toggles the additional completions that automatically append `.await` to calls of functions
+        /// returning a future, when inside an async function or block.
+        completion_autoAwait_enable: bool        = "true",
         /// Toggles the additional completions that automatically add imports when completed.
         /// Note that your client must specify the `additionalTextEdits` LSP client capability to truly have this feature enabled.
         completion_autoimport_enable: bool       = "true",
         /// Toggles the additional completions that automatically show method calls and field accesses
         /// with `self` prefixed to them when inside a method.
         completion_autoself_enable: bool        = "true",
+        /// Toggles the additional completions that automatically append `?` to calls of functions
+        /// returning a `Result` or `Option` which can be propagated to the enclosing function.
+        completion_autoTry_enable: bool        = "true",
         /// Whether to add parenthesis and argument snippets when completing function.
         completion_callable_snippets: CallableCompletionDef  = "\"fill_arguments\"",
         /// Maximum number of completions to return. If `None`, the limit is infinite.
@@ -259,9 +269,18 @@ config_data! {
         /// Whether to show experimental rust-analyzer diagnostics that might
         /// have more false positives than usual.
         diagnostics_experimental_enable: bool    = "false",
+        /// Whether to merge the diagnostics of `cargo check` with native rust-analyzer diagnostics
+        /// reporting the same problem at the same place, keeping the one with more details.
+        diagnostics_mergeDuplicates: bool = "true",
         /// Map of prefixes to be substituted when parsing diagnostic file paths.
         /// This should be the reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = "{}",
+        /// Warn about bindings whose type is made of more types than this, like deeply nested
+        /// iterator adapters. Set to null to disable.
+        diagnostics_typeLengthLimit: Option<usize> = "500",
+        /// Whether to show diagnostics for redundant parentheses in expressions.
+        /// This reports more cases than rustc's `unused_parens` lint.
+        diagnostics_unnecessaryParentheses_enable: bool = "false",
         /// List of warnings that should be displayed with hint severity.
         ///
         /// The warnings will be indicated by faded text or three dots in code
@@ -348,6 +367,9 @@ config_data! {
         inlayHints_expressionAdjustmentHints_hideOutsideUnsafe: bool = "false",
         /// Whether to show inlay hints as postfix ops (`.*` instead of `*`, etc).
         inlayHints_expressionAdjustmentHints_mode: AdjustmentHintsModeDef = "\"prefix\"",
+        /// Whether to show inlay hints for the `ref mut` binding mode that match ergonomics give
+        /// to bindings. These are also shown by `#rust-analyzer.inlayHints.bindingModeHints.enable#`.
+        inlayHints_implicitRefMutHints_enable: bool                = "false",
         /// Whether to show inlay type hints for elided lifetimes in function signatures.
         inlayHints_lifetimeElisionHints_enable: LifetimeElisionDef = "\"never\"",
         /// Whether to prefer using parameter names as the name for elided lifetime hints if possible.
@@ -369,6 +391,15 @@ config_data! {
         inlayHints_typeHints_hideClosureInitialization: bool       = "false",
         /// Whether to hide inlay type hints for constructors.
         inlayHints_typeHints_hideNamedConstructor: bool            = "false",
+        /// Rules hiding inlay type hints. A hint is hidden if it matches all the conditions of one
+        /// of the rules: `type`, `initializer` and `module` are regular expressions which have to match
+        /// the whole rendered type, the initializer of the binding, and the path of its module, like
+        /// `crate::tests`. `longerThan` is the length the rendered type has to exceed, and
+        /// `repeatsInitializer` requires the name of the type to appear in the initializer.
+        inlayHints_typeHints_hideRules: Vec<TypeHintHideRuleDef>   = "[]",
+        /// The profile interpreted tests run under, which decides whether `cfg(debug_assertions)` holds
+        /// and whether arithmetic overflows panic.
+        interpret_profile: InterpretProfileDef                     = "\"debug\"",
         /// Enables the experimental support for interpreting tests.
         interpret_tests: bool                                      = "false",
 
@@ -393,11 +424,17 @@ config_data! {
         /// Whether to show `Implementations` lens. Only applies when
         /// `#rust-analyzer.lens.enable#` is set.
         lens_implementations_enable: bool  = "true",
+        /// Whether to show `Overrides` lens above trait methods, listing their implementations.
+        /// Only applies when `#rust-analyzer.lens.enable#` is set.
+        lens_implementations_traitMethods_enable: bool = "false",
         /// Where to render annotations.
         lens_location: AnnotationLocation = "\"above_name\"",
         /// Whether to show `References` lens for Struct, Enum, and Union.
         /// Only applies when `#rust-analyzer.lens.enable#` is set.
         lens_references_adt_enable: bool = "false",
+        /// Maximum number of `References` lenses shown in a file. Resolving each of them
+        /// searches the whole workspace, so this bounds the work done when opening a file.
+        lens_references_budget: Option<usize> = "null",
         /// Whether to show `References` lens for Enum Variants.
         /// Only applies when `#rust-analyzer.lens.enable#` is set.
         lens_references_enumVariant_enable: bool = "false",
@@ -543,6 +580,7 @@ pub struct Config {
     data: ConfigData,
     detached_files: Vec<AbsPathBuf>,
     snippets: Vec<Snippet>,
+    type_hint_filters: Vec<TypeHintFilter>,
 }
 
 type ParallelCachePrimingNumThreads = u8;
@@ -578,12 +616,14 @@ pub struct LensConfig {
 
     // implementations
     pub implementations: bool,
+    pub trait_method_impls: bool,
 
     // references
     pub method_refs: bool,
     pub refs_adt: bool,   // for Struct, Enum, Union and Trait
     pub refs_trait: bool, // for Struct, Enum, Union and Trait
     pub enum_variant_refs: bool,
+    pub refs_budget: Option<usize>,
 
     // annotations
     pub location: AnnotationLocation,
@@ -610,6 +650,7 @@ impl LensConfig {
         self.run
             || self.debug
             || self.implementations
+            || self.trait_method_impls
             || self.method_refs
             || self.refs_adt
             || self.refs_trait
@@ -746,6 +787,7 @@ impl Config {
             discovered_projects: Vec::new(),
             root_path,
             snippets: Default::default(),
+            type_hint_filters: Default::default(),
             workspace_roots,
         }
     }
@@ -811,6 +853,27 @@ impl Config {
             }
         }
 
+        self.type_hint_filters.clear();
+        for (idx, def) in self.data.inlayHints_typeHints_hideRules.iter().enumerate() {
+            let pattern = |it: &Option<String>| it.as_deref().map(HintPattern::new).transpose();
+            let filter = (|| -> Result<_, String> {
+                Ok(TypeHintFilter {
+                    ty: pattern(&def.ty)?,
+                    initializer: pattern(&def.initializer)?,
+                    module: pattern(&def.module)?,
+                    longer_than: def.longer_than,
+                    repeats_initializer: def.repeats_initializer,
+                })
+            })();
+            match filter {
+                Ok(filter) => self.type_hint_filters.push(filter),
+                Err(e) => errors.push((
+                    format!("inlay hint hide rule {idx} is invalid"),
+                    <serde_json::Error as serde::de::Error>::custom(e),
+                )),
+            }
+        }
+
         self.validate(&mut errors);
 
         if errors.is_empty() {
@@ -1051,6 +1114,10 @@ impl Config {
         self.data.diagnostics_enable
     }
 
+    pub fn merge_duplicate_diagnostics(&self) -> bool {
+        self.data.diagnostics_mergeDuplicates
+    }
+
     pub fn diagnostics(&self) -> DiagnosticsConfig {
         DiagnosticsConfig {
             proc_attr_macros_enabled: self.expand_proc_attr_macros(),
@@ -1063,6 +1130,8 @@ impl Config {
             },
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
+            unnecessary_parentheses: self.data.diagnostics_unnecessaryParentheses_enable,
+            type_length_limit: self.data.diagnostics_typeLengthLimit,
         }
     }
 
@@ -1305,6 +1374,7 @@ impl Config {
                 LifetimeElisionDef::SkipTrivial => ide::LifetimeElisionHints::SkipTrivial,
             },
             hide_named_constructor_hints: self.data.inlayHints_typeHints_hideNamedConstructor,
+            type_hint_filters: self.type_hint_filters.clone(),
             hide_closure_initialization_hints: self
                 .data
                 .inlayHints_typeHints_hideClosureInitialization,
@@ -1334,6 +1404,7 @@ impl Config {
                 .data
                 .inlayHints_expressionAdjustmentHints_hideOutsideUnsafe,
             binding_mode_hints: self.data.inlayHints_bindingModeHints_enable,
+            implicit_ref_mut_hints: self.data.inlayHints_implicitRefMutHints_enable,
             param_names_for_lifetime_elision_hints: self
                 .data
                 .inlayHints_lifetimeElisionHints_useParameterNames,
@@ -1343,9 +1414,28 @@ impl Config {
             } else {
                 None
             },
+            fields_to_resolve: InlayFieldsToResolve {
+                resolve_text_edits: self.inlay_hint_resolve_support("textEdits"),
+                resolve_label_location: self.inlay_hint_resolve_support("label.location"),
+            },
         }
     }
 
+    fn inlay_hint_resolve_support(&self, property: &str) -> bool {
+        try_or_def!(self
+            .caps
+            .text_document
+            .as_ref()?
+            .inlay_hint
+            .as_ref()?
+            .resolve_support
+            .as_ref()?
+            .properties
+            .as_slice())
+        .iter()
+        .any(|it| it == property)
+    }
+
     fn insert_use_config(&self) -> InsertUseConfig {
         InsertUseConfig {
             granularity: match self.data.imports_granularity_group {
@@ -1372,6 +1462,8 @@ impl Config {
                 && completion_item_edit_resolve(&self.caps),
             enable_self_on_the_fly: self.data.completion_autoself_enable,
             enable_private_editable: self.data.completion_privateEditable_enable,
+            enable_auto_await: self.data.completion_autoAwait_enable,
+            enable_auto_try: self.data.completion_autoTry_enable,
             callable: match self.data.completion_callable_snippets {
                 CallableCompletionDef::FillArguments => Some(CallableSnippets::FillArguments),
                 CallableCompletionDef::AddParentheses => Some(CallableSnippets::AddParentheses),
@@ -1409,6 +1501,7 @@ impl Config {
             insert_use: self.insert_use_config(),
             prefer_no_std: self.data.imports_prefer_no_std,
             assist_emit_must_use: self.data.assist_emitMustUse,
+            assist_extract_variable_ascribe_type: self.data.assist_extractVariable_ascribeType,
         }
     }
 
@@ -1433,11 +1526,14 @@ impl Config {
             run: self.data.lens_enable && self.data.lens_run_enable,
             debug: self.data.lens_enable && self.data.lens_debug_enable,
             implementations: self.data.lens_enable && self.data.lens_implementations_enable,
+            trait_method_impls: self.data.lens_enable
+                && self.data.lens_implementations_traitMethods_enable,
             method_refs: self.data.lens_enable && self.data.lens_references_method_enable,
             refs_adt: self.data.lens_enable && self.data.lens_references_adt_enable,
             refs_trait: self.data.lens_enable && self.data.lens_references_trait_enable,
             enum_variant_refs: self.data.lens_enable
                 && self.data.lens_references_enumVariant_enable,
+            refs_budget: self.data.lens_references_budget,
             location: self.data.lens_location,
         }
     }
@@ -1491,6 +1587,11 @@ impl Config {
             },
             keywords: self.data.hover_documentation_keywords_enable,
             interpret_tests: self.data.interpret_tests,
+            interpret_profiles: match self.data.interpret_profile {
+                InterpretProfileDef::Debug => vec![BuildProfile::Debug],
+                InterpretProfileDef::Release => vec![BuildProfile::Release],
+                InterpretProfileDef::Both => vec![BuildProfile::Debug, BuildProfile::Release],
+            },
         }
     }
 
@@ -1711,6 +1812,17 @@ struct SnippetDef {
     scope: SnippetScopeDef,
 }
 
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct TypeHintHideRuleDef {
+    #[serde(rename = "type")]
+    ty: Option<String>,
+    initializer: Option<String>,
+    module: Option<String>,
+    longer_than: Option<usize>,
+    repeats_initializer: bool,
+}
+
 fn single_or_array<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -1871,6 +1983,14 @@ enum AdjustmentHintsModeDef {
     PreferPostfix,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum InterpretProfileDef {
+    Debug,
+    Release,
+    Both,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum FilesWatcherDef {
@@ -2103,6 +2223,19 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Force import paths to be absolute by always starting them with `crate` or the extern crate name they come from."
             ],
         },
+        "Vec<TypeHintHideRuleDef>" => set! {
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "type": { "type": "string" },
+                    "initializer": { "type": "string" },
+                    "module": { "type": "string" },
+                    "longerThan": { "type": "integer", "minimum": 0 },
+                    "repeatsInitializer": { "type": "boolean" },
+                },
+            },
+        },
         "Vec<ManifestOrProjectJson>" => set! {
             "type": "array",
             "items": { "type": ["string", "object"] },
@@ -2264,6 +2397,15 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Show only the parameters."
             ],
         },
+        "InterpretProfileDef" => set! {
+            "type": "string",
+            "enum": ["debug", "release", "both"],
+            "enumDescriptions": [
+                "Enable `cfg(debug_assertions)` and overflow checks, like `cargo test` does.",
+                "Disable `cfg(debug_assertions)` and overflow checks, like `cargo test --release` does.",
+                "Run the tests under both profiles, and show both results.",
+            ],
+        },
         "FilesWatcherDef" => set! {
             "type": "string",
             "enum": ["client", "server"],
@@ -5,6 +5,7 @@ use std::{mem, sync::Arc};
 
 use ide::FileId;
 use ide_db::FxHashMap;
+use lsp_types::NumberOrString;
 use stdx::hash::{NoHashHashMap, NoHashHashSet};
 
 use crate::lsp_ext;
@@ -96,13 +97,34 @@ impl DiagnosticCollection {
         self.changes.insert(file_id);
     }
 
+    /// Returns the diagnostics of `file_id`. With `merge_duplicates`, only one of a native and a
+    /// check diagnostic reporting the same problem is returned, the one with more details.
     pub(crate) fn diagnostics_for(
         &self,
         file_id: FileId,
+        merge_duplicates: bool,
     ) -> impl Iterator<Item = &lsp_types::Diagnostic> {
-        let native = self.native.get(&file_id).into_iter().flatten();
-        let check = self.check.values().filter_map(move |it| it.get(&file_id)).flatten();
-        native.chain(check)
+        let native = self.native.get(&file_id).map_or(&[][..], |it| &**it);
+        let native_kept = native.iter().filter(move |native| {
+            !merge_duplicates
+                || !self
+                    .check_diagnostics_for(file_id)
+                    .any(|check| are_duplicates(check, native) && details(check) >= details(native))
+        });
+        let check_kept = self.check_diagnostics_for(file_id).filter(move |check| {
+            !merge_duplicates
+                || !native
+                    .iter()
+                    .any(|native| are_duplicates(check, native) && details(native) > details(check))
+        });
+        native_kept.chain(check_kept)
+    }
+
+    fn check_diagnostics_for(
+        &self,
+        file_id: FileId,
+    ) -> impl Iterator<Item = &lsp_types::Diagnostic> {
+        self.check.values().filter_map(move |it| it.get(&file_id)).flatten()
     }
 
     pub(crate) fn take_changes(&mut self) -> Option<NoHashHashSet<FileId>> {
@@ -113,9 +135,138 @@ impl DiagnosticCollection {
     }
 }
 
+/// Whether the `cargo check` diagnostic `check` reports the same problem as the native diagnostic
+/// `native`, that is their ranges overlap and their codes correspond to each other.
+fn are_duplicates(check: &lsp_types::Diagnostic, native: &lsp_types::Diagnostic) -> bool {
+    let (Some(NumberOrString::String(check_code)), Some(NumberOrString::String(native_code))) =
+        (&check.code, &native.code)
+    else {
+        return false;
+    };
+    let overlap = check.range.start <= native.range.end && native.range.start <= check.range.end;
+    overlap && check_codes(native_code).contains(&check_code.as_str())
+}
+
+/// The codes of the rustc errors and of the rustc and Clippy lints reporting the same problem as
+/// the native diagnostic with the code `native_code`.
+fn check_codes(native_code: &str) -> &'static [&'static str] {
+    match native_code {
+        "break-outside-of-loop" => &["E0268"],
+        "dead-store" => &["unused_assignments"],
+        "duplicate-entry-item" => &["E0152"],
+        "expected-function" => &["E0618"],
+        "incoherent-impl" => &["E0116", "E0390"],
+        "incorrect-ident-case" => {
+            &["non_snake_case", "non_camel_case_types", "non_upper_case_globals"]
+        }
+        "mismatched-arg-count" => &["E0057", "E0060", "E0061"],
+        "missing-fields" => &["E0063"],
+        "missing-match-arm" => &["E0004"],
+        "missing-unsafe" => &["E0133"],
+        "need-mut" => &["E0384", "E0596"],
+        "no-such-field" => &["E0560"],
+        "private-assoc-item" => &["E0624"],
+        "private-field" => &["E0616"],
+        "replace-filter-map-next-with-find-map" => &["filter_map_next"],
+        "type-mismatch" => &["E0308"],
+        "undeclared-label" => &["E0426"],
+        "unnecessary-braces" => &["unused_braces"],
+        "unnecessary-parentheses" => &["unused_parens"],
+        "unreachable-label" => &["E0767"],
+        "unresolved-extern-crate" => &["E0463"],
+        "unresolved-field" => &["E0609"],
+        "unresolved-import" => &["E0432", "E0433"],
+        "unresolved-method" => &["E0599"],
+        "unresolved-module" => &["E0583"],
+        "unused-mut" => &["unused_mut"],
+        "use-field-shorthand" => &["redundant_field_names"],
+        _ => &[],
+    }
+}
+
+/// How many details `diagnostic` has besides its message, to pick one of two duplicates.
+fn details(diagnostic: &lsp_types::Diagnostic) -> usize {
+    let related = diagnostic.related_information.as_ref().map_or(0, Vec::len);
+    related + diagnostic.message.lines().count() + usize::from(diagnostic.data.is_some())
+}
+
 fn are_diagnostics_equal(left: &lsp_types::Diagnostic, right: &lsp_types::Diagnostic) -> bool {
     left.source == right.source
         && left.severity == right.severity
         && left.range == right.range
         && left.message == right.message
 }
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{DiagnosticRelatedInformation, Location, Position, Range, Url};
+
+    use super::*;
+
+    fn diagnostic(source: &str, code: &str, start: u32, end: u32) -> lsp_types::Diagnostic {
+        lsp_types::Diagnostic {
+            range: Range::new(Position::new(0, start), Position::new(0, end)),
+            code: Some(NumberOrString::String(code.to_owned())),
+            source: Some(source.to_owned()),
+            message: "mismatched types".to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn sources(collection: &DiagnosticCollection, merge_duplicates: bool) -> Vec<&str> {
+        collection
+            .diagnostics_for(FileId(0), merge_duplicates)
+            .map(|it| it.source.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn merges_duplicates() {
+        let mut collection = DiagnosticCollection::default();
+        collection.set_native_diagnostics(
+            FileId(0),
+            vec![
+                diagnostic("rust-analyzer", "type-mismatch", 4, 8),
+                diagnostic("rust-analyzer", "unresolved-method", 20, 24),
+            ],
+        );
+        let mut check = diagnostic("rustc", "E0308", 6, 8);
+        check.related_information = Some(vec![DiagnosticRelatedInformation {
+            location: Location::new(Url::parse("file:///main.rs").unwrap(), check.range),
+            message: "expected due to this".to_owned(),
+        }]);
+        collection.add_check_diagnostic(0, FileId(0), check, None);
+        collection.add_check_diagnostic(0, FileId(0), diagnostic("rustc", "E0599", 30, 34), None);
+
+        assert_eq!(
+            sources(&collection, false),
+            ["rust-analyzer", "rust-analyzer", "rustc", "rustc"]
+        );
+        let merged = collection.diagnostics_for(FileId(0), true).collect::<Vec<_>>();
+        let codes = merged.iter().map(|it| it.code.clone().unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                NumberOrString::String("unresolved-method".to_owned()),
+                NumberOrString::String("E0308".to_owned()),
+                NumberOrString::String("E0599".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_native_diagnostic_with_more_details() {
+        let mut collection = DiagnosticCollection::default();
+        let mut native = diagnostic("rust-analyzer", "unused-mut", 0, 4);
+        native.message = "variable does not need to be mutable\nremove the `mut`".to_owned();
+        collection.set_native_diagnostics(FileId(0), vec![native]);
+        collection.add_check_diagnostic(
+            0,
+            FileId(0),
+            diagnostic("rustc", "unused_mut", 0, 4),
+            None,
+        );
+
+        assert_eq!(sources(&collection, true), ["rust-analyzer"]);
+    }
+}
@@ -1,13 +1,21 @@
 //! See [RequestDispatcher].
-use std::{fmt, panic, thread};
+use std::{
+    fmt, panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
 
+use crossbeam_channel::Sender;
 use ide::Cancelled;
 use lsp_server::ExtractError;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     global_state::{GlobalState, GlobalStateSnapshot},
-    main_loop::Task,
+    main_loop::{RequestProgress, Task},
     version::version,
     LspError, Result,
 };
@@ -24,6 +32,9 @@ use crate::{
 /// Some requests modify the state, and are run on the main thread to get
 /// `&mut` (`on_sync_mut`).
 ///
+/// Some read-only requests can run for a long time, and report their progress
+/// with a cancel button on the client side (`on_with_progress`).
+///
 /// Read-only requests are wrapped into `catch_unwind` -- they don't modify the
 /// state, so it's OK to recover from their failures.
 pub(crate) struct RequestDispatcher<'a> {
@@ -73,7 +84,7 @@ impl<'a> RequestDispatcher<'a> {
             Some(it) => it,
             None => return self,
         };
-        let global_state_snapshot = self.global_state.snapshot();
+        let global_state_snapshot = self.global_state.request_snapshot(&req);
 
         let result = panic::catch_unwind(move || {
             let _pctx = stdx::panic_context::enter(panic_context);
@@ -102,21 +113,19 @@ impl<'a> RequestDispatcher<'a> {
             None => return self,
         };
 
-        self.global_state.task_pool.handle.spawn({
-            let world = self.global_state.snapshot();
-            move || {
-                let result = panic::catch_unwind(move || {
-                    let _pctx = stdx::panic_context::enter(panic_context);
-                    f(world, params)
-                });
-                match thread_result_to_response::<R>(req.id.clone(), result) {
-                    Ok(response) => Task::Response(response),
-                    Err(_) => Task::Response(lsp_server::Response::new_err(
-                        req.id,
-                        lsp_server::ErrorCode::ContentModified as i32,
-                        "content modified".to_string(),
-                    )),
-                }
+        let world = self.global_state.request_snapshot(&req);
+        self.global_state.task_pool.handle.spawn(move || {
+            let result = panic::catch_unwind(move || {
+                let _pctx = stdx::panic_context::enter(panic_context);
+                f(world, params)
+            });
+            match thread_result_to_response::<R>(req.id.clone(), result) {
+                Ok(response) => Task::Response(response),
+                Err(_) => Task::Response(lsp_server::Response::new_err(
+                    req.id,
+                    lsp_server::ErrorCode::ContentModified as i32,
+                    "content modified".to_string(),
+                )),
             }
         });
 
@@ -138,23 +147,66 @@ impl<'a> RequestDispatcher<'a> {
             None => return self,
         };
 
-        self.global_state.task_pool.handle.spawn({
-            let world = self.global_state.snapshot();
-            move || {
-                let result = panic::catch_unwind(move || {
-                    let _pctx = stdx::panic_context::enter(panic_context);
-                    f(world, params)
-                });
-                match thread_result_to_response::<R>(req.id.clone(), result) {
-                    Ok(response) => Task::Response(response),
-                    Err(_) => Task::Retry(req),
-                }
+        let world = self.global_state.request_snapshot(&req);
+        self.global_state.task_pool.handle.spawn(move || {
+            let result = panic::catch_unwind(move || {
+                let _pctx = stdx::panic_context::enter(panic_context);
+                f(world, params)
+            });
+            match thread_result_to_response::<R>(req.id.clone(), result) {
+                Ok(response) => Task::Response(response),
+                Err(_) => Task::Retry(req),
             }
         });
 
         self
     }
 
+    /// Dispatches a long-running request onto thread pool. The handler reports
+    /// its progress through the [`ProgressReporter`], which also tells it when
+    /// the client cancelled the request.
+    pub(crate) fn on_with_progress<R>(
+        &mut self,
+        title: &'static str,
+        f: fn(GlobalStateSnapshot, R::Params, ProgressReporter) -> Result<R::Result>,
+    ) -> &mut Self
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + panic::UnwindSafe + Send + fmt::Debug,
+        R::Result: Serialize,
+    {
+        let (req, params, panic_context) = match self.parse::<R>() {
+            Some(it) => it,
+            None => return self,
+        };
+        let token = request_progress_token(&req.id);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.global_state.request_progress.insert(token.clone(), (cancelled.clone(), false));
+
+        let world = self.global_state.request_snapshot(&req);
+        self.global_state.task_pool.handle.spawn_with_sender(move |sender| {
+            let reporter = ProgressReporter {
+                token: token.clone(),
+                title,
+                sender: sender.clone(),
+                cancelled,
+            };
+            let result = panic::catch_unwind(move || {
+                let _pctx = stdx::panic_context::enter(panic_context);
+                f(world, params, reporter)
+            });
+            let end = RequestProgress::End { token, title };
+            sender.send(Task::RequestProgress(end)).unwrap();
+            let task = match thread_result_to_response::<R>(req.id.clone(), result) {
+                Ok(response) => Task::Response(response),
+                Err(_) => Task::Retry(req),
+            };
+            sender.send(task).unwrap();
+        });
+
+        self
+    }
+
     pub(crate) fn finish(&mut self) {
         if let Some(req) = self.req.take() {
             tracing::error!("unknown request: {:?}", req);
@@ -300,3 +352,34 @@ impl<'a> NotificationDispatcher<'a> {
         }
     }
 }
+
+/// The token of the progress reported by the request `id`, see
+/// [`RequestDispatcher::on_with_progress`].
+pub(crate) fn request_progress_token(id: &lsp_server::RequestId) -> String {
+    format!("rust-analyzer/request/{id}")
+}
+
+/// Reports the progress of a request dispatched with
+/// [`RequestDispatcher::on_with_progress`].
+pub(crate) struct ProgressReporter {
+    token: String,
+    title: &'static str,
+    sender: Sender<Task>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl panic::UnwindSafe for ProgressReporter {}
+
+impl ProgressReporter {
+    /// Reports `message`, and returns `false` if the request was cancelled.
+    pub(crate) fn report(&self, message: String) -> bool {
+        let progress =
+            RequestProgress::Report { token: self.token.clone(), title: self.title, message };
+        self.sender.send(Task::RequestProgress(progress)).unwrap();
+        !self.is_cancelled()
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
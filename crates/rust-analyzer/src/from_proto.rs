@@ -1,6 +1,6 @@
 //! Conversion lsp_types types to rust-analyzer specific ones.
 use anyhow::format_err;
-use ide::{Annotation, AnnotationKind, AssistKind, LineCol};
+use ide::{Annotation, AnnotationKind, AssistKind, LineCol, SignatureParam};
 use ide_db::{
     base_db::{FileId, FilePosition, FileRange},
     line_index::WideLineCol,
@@ -89,12 +89,22 @@ pub(crate) fn assist_kind(kind: lsp_types::CodeActionKind) -> Option<AssistKind>
         k if k == &lsp_types::CodeActionKind::REFACTOR_EXTRACT => AssistKind::RefactorExtract,
         k if k == &lsp_types::CodeActionKind::REFACTOR_INLINE => AssistKind::RefactorInline,
         k if k == &lsp_types::CodeActionKind::REFACTOR_REWRITE => AssistKind::RefactorRewrite,
+        k if k == &lsp_types::CodeActionKind::SOURCE => AssistKind::Source,
         _ => return None,
     };
 
     Some(assist_kind)
 }
 
+pub(crate) fn signature_param(param: lsp_ext::SignatureParam) -> SignatureParam {
+    match param {
+        lsp_ext::SignatureParam::Existing { index } => SignatureParam::Existing(index),
+        lsp_ext::SignatureParam::New { pattern, ty, value } => {
+            SignatureParam::New { pattern, ty, value }
+        }
+    }
+}
+
 pub(crate) fn annotation(
     snap: &GlobalStateSnapshot,
     code_lens: lsp_types::CodeLens,
@@ -103,8 +113,10 @@ pub(crate) fn annotation(
         code_lens.data.ok_or_else(|| invalid_params_error("code lens without data".to_string()))?;
     let resolve = from_json::<lsp_ext::CodeLensResolveData>("CodeLensResolveData", &data)?;
 
+    let overrides = matches!(resolve.kind, lsp_ext::CodeLensResolveDataKind::Overrides(_));
     match resolve.kind {
-        lsp_ext::CodeLensResolveDataKind::Impls(params) => {
+        lsp_ext::CodeLensResolveDataKind::Impls(params)
+        | lsp_ext::CodeLensResolveDataKind::Overrides(params) => {
             if snap.url_file_version(&params.text_document_position_params.text_document.uri)
                 != Some(resolve.version)
             {
@@ -116,7 +128,11 @@ pub(crate) fn annotation(
 
             Ok(Annotation {
                 range: text_range(&line_index, code_lens.range)?,
-                kind: AnnotationKind::HasImpls { pos, data: None },
+                kind: if overrides {
+                    AnnotationKind::HasOverrides { pos, data: None }
+                } else {
+                    AnnotationKind::HasImpls { pos, data: None }
+                },
             })
         }
         lsp_ext::CodeLensResolveDataKind::References(params) => {
@@ -3,7 +3,10 @@
 //!
 //! Each tick provides an immutable snapshot of the state as `WorldSnapshot`.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Instant,
+};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use flycheck::FlycheckHandle;
@@ -26,6 +29,7 @@ use crate::{
     main_loop::Task,
     mem_docs::MemDocs,
     op_queue::OpQueue,
+    query_clients::QueryClients,
     reload::{self, SourceRootConfig},
     task_pool::TaskPool,
     to_proto::url_from_abs_path,
@@ -107,6 +111,31 @@ pub(crate) struct GlobalState {
     pub(crate) fetch_proc_macros_queue: OpQueue<Vec<ProcMacroPaths>, bool>,
 
     pub(crate) prime_caches_queue: OpQueue,
+
+    /// Old revisions of files opened by the client, by their uri on the client
+    /// side.
+    pub(crate) historical_documents: FxHashMap<Url, HistoricalDocument>,
+
+    /// The cancellation flags of the requests reporting their progress, by
+    /// progress token, and whether any progress has been reported yet.
+    pub(crate) request_progress: FxHashMap<String, (Arc<AtomicBool>, bool)>,
+
+    /// Secondary clients sharing the analysis, see [`QueryClients`].
+    pub(crate) query_clients: QueryClients,
+}
+
+/// An old revision of a file, with content provided by the client, see
+/// [`lsp_ext::OpenHistoricalDocument`].
+///
+/// The document is analyzed as an overlay of the live state, see
+/// [`AnalysisHost::overlay_analysis`], so that it doesn't affect it. The other
+/// files of the workspace are the live ones.
+pub(crate) struct HistoricalDocument {
+    /// The file of the workspace the document is a revision of. The overlay
+    /// uses its id for the document.
+    pub(crate) file_id: FileId,
+    pub(crate) text: Arc<str>,
+    pub(crate) endings: LineEndings,
 }
 
 /// An immutable snapshot of the world's state at a point in time.
@@ -121,6 +150,12 @@ pub(crate) struct GlobalStateSnapshot {
     // used to signal semantic highlighting to fall back to syntax based highlighting until proc-macros have been loaded
     pub(crate) proc_macros_loaded: bool,
     pub(crate) flycheck: Arc<[FlycheckHandle]>,
+    /// The historical document analyzed by `analysis` instead of the live
+    /// file, see [`GlobalState::request_snapshot`].
+    historical_document: Option<(Url, FileId, LineEndings)>,
+    /// The line endings of the documents of the query client `analysis` is the overlay of, see
+    /// [`GlobalState::request_snapshot`].
+    query_client_endings: FxHashMap<FileId, LineEndings>,
 }
 
 impl std::panic::UnwindSafe for GlobalStateSnapshot {}
@@ -145,6 +180,8 @@ impl GlobalState {
         if let Some(capacities) = config.lru_query_capacities() {
             analysis_host.update_lru_capacities(capacities);
         }
+        // Big const tables shouldn't stall hovers, so evaluate consts on a separate thread.
+        analysis_host.set_const_eval_threads(1);
         let (flycheck_sender, flycheck_receiver) = unbounded();
         let mut this = GlobalState {
             sender,
@@ -179,6 +216,11 @@ impl GlobalState {
             fetch_proc_macros_queue: OpQueue::default(),
 
             prime_caches_queue: OpQueue::default(),
+
+            historical_documents: FxHashMap::default(),
+
+            request_progress: FxHashMap::default(),
+            query_clients: QueryClients::none(),
         };
         // Apply any required database inputs from the config.
         this.update_configuration(config);
@@ -318,7 +360,26 @@ impl GlobalState {
             proc_macros_loaded: !self.config.expand_proc_macros()
                 || *self.fetch_proc_macros_queue.last_op_result(),
             flycheck: self.flycheck.clone(),
+            historical_document: None,
+            query_client_endings: FxHashMap::default(),
+        }
+    }
+
+    /// Returns a snapshot for `req`, which sees the documents of its client if it comes from a
+    /// query client, or the historical document it is about.
+    pub(crate) fn request_snapshot(&mut self, req: &lsp_server::Request) -> GlobalStateSnapshot {
+        let mut snap = self.snapshot();
+        if let Some((url, doc)) = historical_document_url(req)
+            .and_then(|url| self.historical_documents.get_key_value(&url))
+        {
+            let texts = [(doc.file_id, doc.text.clone())];
+            snap.analysis = self.analysis_host.overlay_analysis(&texts);
+            snap.historical_document = Some((url.clone(), doc.file_id, doc.endings));
+        } else if let Some((texts, endings)) = self.query_clients.overlay_for_request(&req.id) {
+            snap.analysis = self.analysis_host.overlay_analysis(&texts);
+            snap.query_client_endings = endings;
         }
+        snap
     }
 
     pub(crate) fn send_request<R: lsp_types::request::Request>(
@@ -367,12 +428,19 @@ impl GlobalState {
 
             let duration = start.elapsed();
             tracing::debug!("handled {} - ({}) in {:0.2?}", method, response.id, duration);
-            self.send(response.into());
+            self.send_response(response);
         }
     }
 
     pub(crate) fn cancel(&mut self, request_id: lsp_server::RequestId) {
         if let Some(response) = self.req_queue.incoming.cancel(request_id) {
+            self.send_response(response);
+        }
+    }
+
+    /// Sends `response` to the client which sent the request, which may be a query client.
+    fn send_response(&mut self, response: lsp_server::Response) {
+        if let Some(response) = self.query_clients.respond(response) {
             self.send(response.into());
         }
     }
@@ -392,17 +460,40 @@ impl Drop for GlobalState {
     }
 }
 
+/// Returns the document `req` is about if it's one of the requests answered for historical
+/// documents, see [`lsp_ext::OpenHistoricalDocument`].
+fn historical_document_url(req: &lsp_server::Request) -> Option<Url> {
+    use lsp_types::request::{GotoDefinition, Request};
+
+    if ![lsp_ext::HoverRequest::METHOD, GotoDefinition::METHOD].contains(&req.method.as_str()) {
+        return None;
+    }
+    Url::parse(req.params.get("textDocument")?.get("uri")?.as_str()?).ok()
+}
+
 impl GlobalStateSnapshot {
     pub(crate) fn url_to_file_id(&self, url: &Url) -> Result<FileId> {
-        url_to_file_id(&self.vfs.read().0, url)
+        match &self.historical_document {
+            Some((doc_url, file_id, _)) if doc_url == url => Ok(*file_id),
+            _ => url_to_file_id(&self.vfs.read().0, url),
+        }
     }
 
     pub(crate) fn file_id_to_url(&self, id: FileId) -> Url {
-        file_id_to_url(&self.vfs.read().0, id)
+        match &self.historical_document {
+            Some((url, file_id, _)) if *file_id == id => url.clone(),
+            _ => file_id_to_url(&self.vfs.read().0, id),
+        }
     }
 
     pub(crate) fn file_line_index(&self, file_id: FileId) -> Cancellable<LineIndex> {
-        let endings = self.vfs.read().1[&file_id];
+        let endings = match &self.historical_document {
+            Some((_, id, endings)) if *id == file_id => *endings,
+            _ => match self.query_client_endings.get(&file_id) {
+                Some(endings) => *endings,
+                None => self.vfs.read().1[&file_id],
+            },
+        };
         let index = self.analysis.file_line_index(file_id)?;
         let res = LineIndex { index, endings, encoding: self.config.position_encoding() };
         Ok(res)
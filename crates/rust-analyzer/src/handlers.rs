@@ -3,16 +3,19 @@
 //! `ide` crate.
 
 use std::{
+    hash::{Hash, Hasher},
     io::Write as _,
     process::{self, Stdio},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
 use ide::{
     AnnotationConfig, AssistKind, AssistResolveStrategy, Cancellable, FileId, FilePosition,
-    FileRange, HoverAction, HoverGotoTypeData, Query, RangeInfo, ReferenceCategory, Runnable,
-    RunnableKind, SingleResolve, SourceChange, TextEdit,
+    FileRange, FileSystemEdit, HoverAction, HoverGotoTypeData, LifetimePositionKind,
+    MissingImplReason, Query, RangeInfo, ReferenceCategory, Runnable, RunnableKind, SafeDelete,
+    SingleResolve, SourceChange, TextEdit,
 };
 use ide_db::SymbolKind;
 use lsp_server::ErrorCode;
@@ -27,6 +30,7 @@ use lsp_types::{
     SymbolTag, TextDocumentIdentifier, Url, WorkspaceEdit,
 };
 use project_model::{ManifestPath, ProjectWorkspace, TargetKind};
+use rustc_hash::{FxHashSet, FxHasher};
 use serde_json::json;
 use stdx::{format_to, never};
 use syntax::{algo, ast, AstNode, TextRange, TextSize};
@@ -36,12 +40,13 @@ use crate::{
     cargo_target_spec::CargoTargetSpec,
     config::{RustfmtConfig, WorkspaceSymbolConfig},
     diff::diff,
+    dispatch::ProgressReporter,
     from_proto,
     global_state::{GlobalState, GlobalStateSnapshot},
     line_index::LineEndings,
     lsp_ext::{self, PositionOrRange, ViewCrateGraphParams, WorkspaceSymbolParams},
     lsp_utils::{all_edits_are_disjoint, invalid_params_error},
-    to_proto, LspError, Result,
+    to_proto, trace_capture, LspError, Result,
 };
 
 pub(crate) fn handle_workspace_reload(state: &mut GlobalState, _: ()) -> Result<()> {
@@ -163,6 +168,44 @@ pub(crate) fn handle_view_mir(
     Ok(res)
 }
 
+pub(crate) fn handle_trace_const_eval(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+    progress: ProgressReporter,
+) -> Result<String> {
+    let _p = profile::span("handle_trace_const_eval");
+    let position = from_proto::file_position(&snap, params)?;
+    let res = snap
+        .analysis
+        .trace_const_eval(position, move |steps| progress.report(format!("{steps} steps")))?;
+    Ok(res)
+}
+
+pub(crate) fn handle_capture_trace(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::CaptureTraceParams,
+) -> Result<String> {
+    let _p = profile::span("handle_capture_trace");
+    let (res, trace) = trace_capture::capture(|| match params.request {
+        lsp_ext::CapturedRequest::Hover => {
+            let params = lsp_ext::HoverParams {
+                text_document: params.text_document.clone(),
+                position: PositionOrRange::Position(params.position),
+                work_done_progress_params: Default::default(),
+            };
+            handle_hover(snap.clone(), params).map(drop)
+        }
+        lsp_ext::CapturedRequest::Diagnostics => {
+            let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+            publish_diagnostics(&snap, file_id).map(drop)
+        }
+    });
+    // Write the trace of failed requests as well, they might be what the trace is about.
+    std::fs::write(&params.output, trace)
+        .with_context(|| format!("failed to write the trace to {}", params.output))?;
+    res.map(|()| params.output)
+}
+
 pub(crate) fn handle_view_file_text(
     snap: GlobalStateSnapshot,
     params: lsp_types::TextDocumentIdentifier,
@@ -824,6 +867,52 @@ pub(crate) fn handle_related_tests(
     Ok(res)
 }
 
+pub(crate) fn handle_impl_coverage(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<lsp_ext::ImplCoverageResult>> {
+    let _p = profile::span("handle_impl_coverage");
+    let position = from_proto::file_position(&snap, params)?;
+
+    let Some(coverage) = snap.analysis.impl_coverage(position)? else { return Ok(None) };
+    let mut missing = Vec::new();
+    for it in coverage.missing {
+        let registered_at = match it.reason {
+            MissingImplReason::Name => None,
+            MissingImplReason::Registered(frange) => Some(to_proto::location(&snap, frange)?),
+        };
+        let name = it.ty.name.to_string();
+        let location = to_proto::location_from_nav(&snap, it.ty)?;
+        missing.push(lsp_ext::MissingImpl { name, location, registered_at });
+    }
+    Ok(Some(lsp_ext::ImplCoverageResult { trait_name: coverage.trait_name, missing }))
+}
+
+pub(crate) fn handle_lifetime_reach(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<lsp_ext::LifetimeReachResult>> {
+    let _p = profile::span("handle_lifetime_reach");
+    let position = from_proto::file_position(&snap, params)?;
+    let line_index = snap.file_line_index(position.file_id)?;
+
+    let Some(reach) = snap.analysis.lifetime_reach(position)? else { return Ok(None) };
+    let positions = reach
+        .positions
+        .into_iter()
+        .map(|it| lsp_ext::LifetimePosition {
+            range: to_proto::range(&line_index, it.range),
+            kind: match it.kind {
+                LifetimePositionKind::Declaration => lsp_ext::LifetimePositionKind::Declaration,
+                LifetimePositionKind::Type => lsp_ext::LifetimePositionKind::Type,
+                LifetimePositionKind::Bound => lsp_ext::LifetimePositionKind::Bound,
+                LifetimePositionKind::Elided => lsp_ext::LifetimePositionKind::Elided,
+            },
+        })
+        .collect();
+    Ok(Some(lsp_ext::LifetimeReachResult { name: reach.name, positions }))
+}
+
 pub(crate) fn handle_completion(
     snap: GlobalStateSnapshot,
     params: lsp_types::CompletionParams,
@@ -994,6 +1083,7 @@ pub(crate) fn handle_prepare_rename(
 pub(crate) fn handle_rename(
     snap: GlobalStateSnapshot,
     params: RenameParams,
+    progress: ProgressReporter,
 ) -> Result<Option<WorkspaceEdit>> {
     let _p = profile::span("handle_rename");
     let position = from_proto::file_position(&snap, params.text_document_position)?;
@@ -1010,8 +1100,8 @@ pub(crate) fn handle_rename(
     if !change.file_system_edits.is_empty() && snap.config.will_rename() {
         change.source_file_edits.clear();
     }
-    let workspace_edit = to_proto::workspace_edit(&snap, change)?;
-    Ok(Some(workspace_edit))
+    let workspace_edit = RefactoringTransaction::new(&snap, progress).commit(change)?;
+    Ok(Some(workspace_edit.into()))
 }
 
 pub(crate) fn handle_references(
@@ -1244,9 +1334,11 @@ pub(crate) fn handle_code_lens(
                 .unwrap_or(false),
             annotate_runnables: lens_config.runnable(),
             annotate_impls: lens_config.implementations,
+            annotate_method_impls: lens_config.trait_method_impls,
             annotate_references: lens_config.refs_adt,
             annotate_method_references: lens_config.method_refs,
             annotate_enum_variant_references: lens_config.enum_variant_refs,
+            references_budget: lens_config.refs_budget,
             location: lens_config.location.into(),
         },
         file_id,
@@ -1306,6 +1398,7 @@ pub(crate) fn handle_document_highlight(
 pub(crate) fn handle_ssr(
     snap: GlobalStateSnapshot,
     params: lsp_ext::SsrParams,
+    progress: ProgressReporter,
 ) -> Result<lsp_types::WorkspaceEdit> {
     let _p = profile::span("handle_ssr");
     let selections = params
@@ -1320,7 +1413,51 @@ pub(crate) fn handle_ssr(
         position,
         selections,
     )??;
-    to_proto::workspace_edit(&snap, source_change).map_err(Into::into)
+    Ok(RefactoringTransaction::new(&snap, progress).commit(source_change)?.into())
+}
+
+pub(crate) fn handle_change_signature(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ChangeSignatureParams,
+    progress: ProgressReporter,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile::span("handle_change_signature");
+    let position = from_proto::file_position(&snap, params.position)?;
+    let params = params.params.into_iter().map(from_proto::signature_param).collect::<Vec<_>>();
+    let source_change = snap
+        .analysis
+        .change_signature(position, &params)?
+        .map_err(to_proto::change_signature_error)?;
+    Ok(RefactoringTransaction::new(&snap, progress).commit(source_change)?.into())
+}
+
+pub(crate) fn handle_safe_delete(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+    progress: ProgressReporter,
+) -> Result<lsp_ext::SafeDeleteResult> {
+    let _p = profile::span("handle_safe_delete");
+    let position = from_proto::file_position(&snap, params)?;
+    let safe_delete = snap.analysis.safe_delete(position)?.map_err(to_proto::safe_delete_error)?;
+    let result = match safe_delete {
+        SafeDelete::Deleted(source_change) => lsp_ext::SafeDeleteResult {
+            edit: Some(RefactoringTransaction::new(&snap, progress).commit(source_change)?.into()),
+            blockers: Vec::new(),
+        },
+        SafeDelete::Blocked(blockers) => lsp_ext::SafeDeleteResult {
+            edit: None,
+            blockers: blockers
+                .into_iter()
+                .map(|blocker| {
+                    Ok(lsp_ext::SafeDeleteBlocker {
+                        location: to_proto::location(&snap, blocker.range)?,
+                        container: blocker.container,
+                    })
+                })
+                .collect::<Cancellable<_>>()?,
+        },
+    };
+    Ok(result)
 }
 
 pub(crate) fn publish_diagnostics(
@@ -1367,24 +1504,77 @@ pub(crate) fn handle_inlay_hints(
         params.range,
     )?;
     let line_index = snap.file_line_index(file_id)?;
+    let version = snap.url_file_version(document_uri);
     let inlay_hints_config = snap.config.inlay_hints();
     Ok(Some(
         snap.analysis
             .inlay_hints(&inlay_hints_config, file_id, Some(range))?
             .into_iter()
             .map(|it| {
-                to_proto::inlay_hint(&snap, &line_index, inlay_hints_config.render_colons, it)
+                let resolve_data = it.needs_resolve.then(|| lsp_ext::InlayHintResolveData {
+                    file_id: file_id.0,
+                    resolve_range: to_proto::range(&line_index, it.range),
+                    hash: inlay_hint_hash(&it),
+                    version,
+                });
+                to_proto::inlay_hint(
+                    &snap,
+                    &line_index,
+                    inlay_hints_config.render_colons,
+                    it,
+                    resolve_data,
+                )
             })
             .collect::<Cancellable<Vec<_>>>()?,
     ))
 }
 
 pub(crate) fn handle_inlay_hints_resolve(
-    _snap: GlobalStateSnapshot,
-    hint: InlayHint,
+    snap: GlobalStateSnapshot,
+    mut original_hint: InlayHint,
 ) -> Result<InlayHint> {
     let _p = profile::span("handle_inlay_hints_resolve");
-    Ok(hint)
+    let data = match original_hint.data.take() {
+        Some(it) => it,
+        None => return Ok(original_hint),
+    };
+
+    let resolve_data: lsp_ext::InlayHintResolveData = serde_json::from_value(data)?;
+    let file_id = FileId(resolve_data.file_id);
+    // The hint is outdated, the client is going to ask for the hints again.
+    if snap.url_file_version(&snap.file_id_to_url(file_id)) != resolve_data.version {
+        return Ok(original_hint);
+    }
+    let line_index = snap.file_line_index(file_id)?;
+    let resolve_range = from_proto::text_range(&line_index, resolve_data.resolve_range)?;
+
+    let inlay_hints_config = snap.config.inlay_hints();
+    let resolved_hint = snap.analysis.inlay_hints_resolve(
+        &inlay_hints_config,
+        file_id,
+        resolve_range,
+        resolve_data.hash,
+        inlay_hint_hash,
+    )?;
+    match resolved_hint {
+        Some(hint) => Ok(to_proto::inlay_hint(
+            &snap,
+            &line_index,
+            inlay_hints_config.render_colons,
+            hint,
+            None,
+        )?),
+        None => Ok(original_hint),
+    }
+}
+
+/// Identifies a hint among the ones with the same range, without the fields which are resolved
+/// lazily.
+fn inlay_hint_hash(hint: &ide::InlayHint) -> u64 {
+    let mut hasher = FxHasher::default();
+    hint.kind.hash(&mut hasher);
+    hint.label.to_string().hash(&mut hasher);
+    hasher.finish()
 }
 
 pub(crate) fn handle_call_hierarchy_prepare(
@@ -1609,6 +1799,86 @@ pub(crate) fn handle_move_item(
     }
 }
 
+/// How long a refactoring runs before its progress is shown.
+const REFACTORING_PROGRESS_DELAY: Duration = Duration::from_millis(200);
+
+/// Turns the edits of a refactoring, which can touch many files, into a
+/// workspace edit.
+///
+/// The files are converted one by one, reporting the progress and checking for
+/// cancellation in between. The client only gets the edit once all of it is
+/// converted, and applies it as a whole, so a cancelled refactoring never
+/// leaves partial edits behind.
+struct RefactoringTransaction<'a> {
+    snap: &'a GlobalStateSnapshot,
+    progress: ProgressReporter,
+    started: Instant,
+}
+
+impl<'a> RefactoringTransaction<'a> {
+    fn new(snap: &'a GlobalStateSnapshot, progress: ProgressReporter) -> Self {
+        RefactoringTransaction { snap, progress, started: Instant::now() }
+    }
+
+    fn commit(self, source_change: SourceChange) -> Result<lsp_ext::SnippetWorkspaceEdit> {
+        self.check_conflicts(&source_change)?;
+
+        let mut document_changes = Vec::new();
+        for op in source_change.file_system_edits {
+            document_changes.extend(to_proto::snippet_text_document_ops(self.snap, op)?);
+        }
+        let total = source_change.source_file_edits.len();
+        for (done, (file_id, edit)) in source_change.source_file_edits.into_iter().enumerate() {
+            self.report(|| format!("{done}/{total} files"))?;
+            let edit = to_proto::snippet_text_document_edit(
+                self.snap,
+                source_change.is_snippet,
+                file_id,
+                edit,
+            )?;
+            document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Edit(edit));
+        }
+        Ok(to_proto::snippet_workspace_edit_from_changes(self.snap, document_changes))
+    }
+
+    /// Reports the progress, once the refactoring has run long enough for it
+    /// to be shown, and fails if the refactoring was cancelled.
+    fn report(&self, message: impl FnOnce() -> String) -> Result<()> {
+        let cancelled = if self.started.elapsed() < REFACTORING_PROGRESS_DELAY {
+            self.progress.is_cancelled()
+        } else {
+            !self.progress.report(message())
+        };
+        if cancelled {
+            let message = "refactoring cancelled".to_owned();
+            return Err(LspError::new(ErrorCode::RequestCanceled as i32, message).into());
+        }
+        Ok(())
+    }
+
+    /// Fails if the file system edits of the refactoring would overwrite
+    /// existing files, or each other.
+    fn check_conflicts(&self, source_change: &SourceChange) -> Result<()> {
+        let mut seen = FxHashSet::default();
+        let mut conflicts = Vec::new();
+        for edit in &source_change.file_system_edits {
+            let (FileSystemEdit::CreateFile { dst, .. }
+            | FileSystemEdit::MoveFile { dst, .. }
+            | FileSystemEdit::MoveDir { dst, .. }) = edit;
+            let dst = self.snap.anchored_path(dst);
+            let exists = dst.to_file_path().map_or(false, |path| path.exists());
+            if !seen.insert(dst.clone()) || exists {
+                conflicts.push(dst.to_string());
+            }
+        }
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+        let message = format!("the refactoring would overwrite {}", conflicts.join(", "));
+        Err(LspError::new(ErrorCode::RequestFailed as i32, message).into())
+    }
+}
+
 fn to_command_link(command: lsp_types::Command, tooltip: String) -> lsp_ext::CommandLink {
     lsp_ext::CommandLink { tooltip: Some(tooltip), command }
 }
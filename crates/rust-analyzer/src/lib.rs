@@ -32,10 +32,12 @@ mod main_loop;
 mod markdown;
 mod mem_docs;
 mod op_queue;
+mod query_clients;
 mod reload;
 mod semantic_tokens;
 mod task_pool;
 mod to_proto;
+mod trace_capture;
 mod version;
 
 pub mod config;
@@ -48,7 +50,13 @@ use std::fmt;
 
 use serde::de::DeserializeOwned;
 
-pub use crate::{caps::server_capabilities, main_loop::main_loop, version::version};
+pub use crate::{
+    caps::server_capabilities,
+    main_loop::{main_loop, main_loop_with_query_clients},
+    query_clients::QueryClients,
+    trace_capture::trace_capture_layer,
+    version::version,
+};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Result<T, E = Error> = std::result::Result<T, E>;
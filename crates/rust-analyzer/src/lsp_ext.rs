@@ -7,7 +7,7 @@ use lsp_types::request::Request;
 use lsp_types::PositionEncodingKind;
 use lsp_types::{
     notification::Notification, CodeActionKind, DocumentOnTypeFormattingParams,
-    PartialResultParams, Position, Range, TextDocumentIdentifier, WorkDoneProgressParams,
+    PartialResultParams, Position, Range, TextDocumentIdentifier, Url, WorkDoneProgressParams,
 };
 use serde::{Deserialize, Serialize};
 
@@ -90,6 +90,40 @@ impl Request for ViewMir {
     const METHOD: &'static str = "rust-analyzer/viewMir";
 }
 
+pub enum TraceConstEval {}
+
+impl Request for TraceConstEval {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/traceConstEval";
+}
+
+pub enum CaptureTrace {}
+
+impl Request for CaptureTrace {
+    type Params = CaptureTraceParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/captureTrace";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureTraceParams {
+    pub request: CapturedRequest,
+    pub text_document: TextDocumentIdentifier,
+    /// The position to hover, ignored for the other requests.
+    pub position: Position,
+    /// The file the trace is written to.
+    pub output: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum CapturedRequest {
+    Hover,
+    Diagnostics,
+}
+
 pub enum ViewFileText {}
 
 impl Request for ViewFileText {
@@ -170,6 +204,36 @@ impl Notification for ClearFlycheck {
     const METHOD: &'static str = "rust-analyzer/clearFlycheck";
 }
 
+pub enum OpenHistoricalDocument {}
+
+impl Notification for OpenHistoricalDocument {
+    type Params = OpenHistoricalDocumentParams;
+    const METHOD: &'static str = "rust-analyzer/openHistoricalDocument";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenHistoricalDocumentParams {
+    /// The uri of the document on the client side, like the one of a diff view.
+    pub uri: Url,
+    /// The file of the workspace the document is a revision of.
+    pub text_document: TextDocumentIdentifier,
+    pub text: String,
+}
+
+pub enum CloseHistoricalDocument {}
+
+impl Notification for CloseHistoricalDocument {
+    type Params = CloseHistoricalDocumentParams;
+    const METHOD: &'static str = "rust-analyzer/closeHistoricalDocument";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseHistoricalDocumentParams {
+    pub uri: Url,
+}
+
 pub enum OpenServerLogs {}
 
 impl Notification for OpenServerLogs {
@@ -290,6 +354,62 @@ pub struct TestInfo {
     pub runnable: Runnable,
 }
 
+pub enum ImplCoverage {}
+
+impl Request for ImplCoverage {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<ImplCoverageResult>;
+    const METHOD: &'static str = "rust-analyzer/implCoverage";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImplCoverageResult {
+    pub trait_name: String,
+    pub missing: Vec<MissingImpl>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingImpl {
+    pub name: String,
+    pub location: lsp_types::Location,
+    /// The argument passing the type to a function expecting implementors of the trait, if this
+    /// is why the type looks like it should implement it, instead of its name.
+    pub registered_at: Option<lsp_types::Location>,
+}
+
+pub enum LifetimeReach {}
+
+impl Request for LifetimeReach {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<LifetimeReachResult>;
+    const METHOD: &'static str = "rust-analyzer/lifetimeReach";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeReachResult {
+    pub name: String,
+    pub positions: Vec<LifetimePosition>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimePosition {
+    pub range: Range,
+    pub kind: LifetimePositionKind,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LifetimePositionKind {
+    Declaration,
+    Type,
+    Bound,
+    Elided,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintsParams {
@@ -320,6 +440,64 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum ChangeSignature {}
+
+impl Request for ChangeSignature {
+    type Params = ChangeSignatureParams;
+    type Result = lsp_types::WorkspaceEdit;
+    const METHOD: &'static str = "experimental/changeSignature";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureParams {
+    /// Position of the function's name, or of a reference to the function.
+    #[serde(flatten)]
+    pub position: lsp_types::TextDocumentPositionParams,
+
+    /// The parameters of the new signature, in order. `self` is always kept.
+    pub params: Vec<SignatureParam>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SignatureParam {
+    /// The parameter at `index` in the current signature, not counting `self`.
+    Existing { index: usize },
+    /// A new parameter, for which call sites pass `value`.
+    New {
+        pattern: String,
+        #[serde(rename = "type")]
+        ty: String,
+        value: String,
+    },
+}
+
+pub enum SafeDelete {}
+
+impl Request for SafeDelete {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = SafeDeleteResult;
+    const METHOD: &'static str = "experimental/safeDelete";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeDeleteResult {
+    /// The edit deleting the item and its imports, if nothing else uses the item.
+    pub edit: Option<lsp_types::WorkspaceEdit>,
+    /// The usages preventing the deletion, empty if the item can be deleted.
+    pub blockers: Vec<SafeDeleteBlocker>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeDeleteBlocker {
+    pub location: lsp_types::Location,
+    /// The name of the item the usage is in, if any.
+    pub container: Option<String>,
+}
+
 pub enum ServerStatusNotification {}
 
 impl Notification for ServerStatusNotification {
@@ -504,6 +682,7 @@ pub struct CodeLensResolveData {
 #[serde(rename_all = "camelCase")]
 pub enum CodeLensResolveDataKind {
     Impls(lsp_types::request::GotoImplementationParams),
+    Overrides(lsp_types::request::GotoImplementationParams),
     References(lsp_types::TextDocumentPositionParams),
 }
 
@@ -607,7 +786,15 @@ pub struct CompletionResolveData {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct InlayHintResolveData {}
+pub struct InlayHintResolveData {
+    pub file_id: u32,
+    /// The range of the hint, which is where it is computed again.
+    pub resolve_range: lsp_types::Range,
+    /// Identifies the hint among the ones with the same range.
+    pub hash: u64,
+    /// The version of the document the hint was computed for.
+    pub version: Option<i32>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CompletionImport {
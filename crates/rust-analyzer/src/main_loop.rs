@@ -3,7 +3,7 @@
 use std::{
     fmt,
     ops::Deref,
-    sync::Arc,
+    sync::{atomic::Ordering, Arc},
     time::{Duration, Instant},
 };
 
@@ -18,17 +18,29 @@ use vfs::{AbsPathBuf, ChangeKind, FileId};
 
 use crate::{
     config::Config,
-    dispatch::{NotificationDispatcher, RequestDispatcher},
+    dispatch::{self, NotificationDispatcher, RequestDispatcher},
     from_proto,
-    global_state::{file_id_to_url, url_to_file_id, GlobalState},
-    handlers, lsp_ext,
+    global_state::{file_id_to_url, url_to_file_id, GlobalState, HistoricalDocument},
+    handlers,
+    line_index::LineEndings,
+    lsp_ext,
     lsp_utils::{apply_document_changes, notification_is, Progress},
     mem_docs::DocumentData,
+    query_clients::{ClientId, QueryClients, QueryRequest},
     reload::{self, BuildDataProgress, ProcMacroProgress, ProjectWorkspaceProgress},
     Result,
 };
 
 pub fn main_loop(config: Config, connection: Connection) -> Result<()> {
+    main_loop_with_query_clients(config, connection, None)
+}
+
+/// Like [`main_loop`], but also answers the requests of the secondary clients of `query_clients`.
+pub fn main_loop_with_query_clients(
+    config: Config,
+    connection: Connection,
+    query_clients: Option<QueryClients>,
+) -> Result<()> {
     tracing::info!("initial config: {:#?}", config);
 
     // Windows scheduler implements priority boosts: if thread waits for an
@@ -50,7 +62,11 @@ pub fn main_loop(config: Config, connection: Connection) -> Result<()> {
         SetThreadPriority(thread, thread_priority_above_normal);
     }
 
-    GlobalState::new(connection.sender, config).run(connection.receiver)
+    let mut state = GlobalState::new(connection.sender, config);
+    if let Some(query_clients) = query_clients {
+        state.query_clients = query_clients;
+    }
+    state.run(connection.receiver)
 }
 
 enum Event {
@@ -58,6 +74,8 @@ enum Event {
     Task(Task),
     Vfs(vfs::loader::Message),
     Flycheck(flycheck::Message),
+    /// A message of a query client, `None` when the client disconnected.
+    QueryClient(ClientId, Option<lsp_server::Message>),
 }
 
 #[derive(Debug)]
@@ -69,8 +87,10 @@ pub(crate) enum Task {
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
     LoadProcMacros(ProcMacroProgress),
+    RequestProgress(RequestProgress),
 }
 
+
 #[derive(Debug)]
 pub(crate) enum PrimeCachesProgress {
     Begin,
@@ -78,6 +98,13 @@ pub(crate) enum PrimeCachesProgress {
     End { cancelled: bool },
 }
 
+/// The progress of a request dispatched with [`RequestDispatcher::on_with_progress`].
+#[derive(Debug)]
+pub(crate) enum RequestProgress {
+    Report { token: String, title: &'static str, message: String },
+    End { token: String, title: &'static str },
+}
+
 impl fmt::Debug for Event {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let debug_verbose_not = |not: &Notification, f: &mut fmt::Formatter<'_>| {
@@ -106,6 +133,9 @@ impl fmt::Debug for Event {
             Event::Task(it) => fmt::Debug::fmt(it, f),
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Flycheck(it) => fmt::Debug::fmt(it, f),
+            Event::QueryClient(client, it) => {
+                f.debug_tuple("QueryClient").field(client).field(it).finish()
+            }
         }
     }
 }
@@ -179,6 +209,9 @@ impl GlobalState {
 
             recv(self.flycheck_receiver) -> task =>
                 Some(Event::Flycheck(task.unwrap())),
+
+            recv(self.query_clients.receiver) -> msg =>
+                msg.ok().map(|(client, msg)| Event::QueryClient(client, msg)),
         }
     }
 
@@ -272,6 +305,9 @@ impl GlobalState {
                     self.handle_flycheck_msg(message);
                 }
             }
+            Event::QueryClient(client, message) => {
+                self.on_query_client_message(loop_start, client, message)
+            }
         }
 
         let state_changed = self.process_changes();
@@ -321,8 +357,11 @@ impl GlobalState {
         if let Some(diagnostic_changes) = self.diagnostics.take_changes() {
             for file_id in diagnostic_changes {
                 let uri = file_id_to_url(&self.vfs.read().0, file_id);
-                let mut diagnostics =
-                    self.diagnostics.diagnostics_for(file_id).cloned().collect::<Vec<_>>();
+                let mut diagnostics = self
+                    .diagnostics
+                    .diagnostics_for(file_id, self.config.merge_duplicate_diagnostics())
+                    .cloned()
+                    .collect::<Vec<_>>();
 
                 // VSCode assumes diagnostic messages to be non-empty strings, so we need to patch
                 // empty diagnostics. Neither the docs of VSCode nor the LSP spec say whether
@@ -512,6 +551,30 @@ impl GlobalState {
                     self.report_progress("Loading", state, msg, None, None);
                 }
             }
+            Task::RequestProgress(progress) => match progress {
+                RequestProgress::Report { token, title, message } => {
+                    if self.query_clients.is_request_progress(&token) {
+                        // The main client doesn't know about the requests of query clients.
+                        return;
+                    }
+                    // Requests only show up once they report some progress, so that quick ones
+                    // don't flicker.
+                    let state = match self.request_progress.get_mut(&token) {
+                        Some((_, begun @ false)) => {
+                            *begun = true;
+                            Progress::Begin
+                        }
+                        Some(_) => Progress::Report,
+                        None => return,
+                    };
+                    self.report_progress(title, state, Some(message), None, Some(token));
+                }
+                RequestProgress::End { token, title } => {
+                    if let Some((_, true)) = self.request_progress.remove(&token) {
+                        self.report_progress(title, Progress::End, None, None, Some(token));
+                    }
+                }
+            },
         }
     }
 
@@ -632,6 +695,117 @@ impl GlobalState {
         self.on_request(req);
     }
 
+    /// Handles a message of a query client, see [`QueryClients`].
+    fn on_query_client_message(
+        &mut self,
+        request_received: Instant,
+        client: ClientId,
+        message: Option<lsp_server::Message>,
+    ) {
+        use lsp_types::notification::{
+            DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        };
+
+        let req = match message {
+            Some(lsp_server::Message::Request(req)) => req,
+            Some(lsp_server::Message::Notification(not)) => {
+                let vfs_file_id =
+                    |url: &lsp_types::Url| url_to_file_id(&self.vfs.read().0, url).ok();
+                match not.method.as_str() {
+                    lsp_types::notification::Cancel::METHOD => {
+                        if let Ok(params) =
+                            serde_json::from_value::<lsp_types::CancelParams>(not.params)
+                        {
+                            let id: lsp_server::RequestId = match params.id {
+                                lsp_types::NumberOrString::Number(id) => id.into(),
+                                lsp_types::NumberOrString::String(id) => id.into(),
+                            };
+                            self.cancel(QueryClients::request_id(client, &id));
+                        }
+                    }
+                    lsp_types::notification::Exit::METHOD => self.disconnect_query_client(client),
+                    // The documents of query clients only change what their own requests see, the
+                    // files outside of the workspace are ignored like for the main client.
+                    DidOpenTextDocument::METHOD => {
+                        if let Ok(params) = serde_json::from_value::<
+                            lsp_types::DidOpenTextDocumentParams,
+                        >(not.params)
+                        {
+                            if let Some(file_id) = vfs_file_id(&params.text_document.uri) {
+                                let text = params.text_document.text;
+                                self.query_clients.open_document(client, file_id, text);
+                            }
+                        }
+                    }
+                    DidChangeTextDocument::METHOD => {
+                        if let Ok(params) = serde_json::from_value::<
+                            lsp_types::DidChangeTextDocumentParams,
+                        >(not.params)
+                        {
+                            let encoding = self.config.position_encoding();
+                            if let Some(file_id) = vfs_file_id(&params.text_document.uri) {
+                                let changes = params.content_changes;
+                                self.query_clients
+                                    .change_document(client, file_id, encoding, changes);
+                            }
+                        }
+                    }
+                    DidCloseTextDocument::METHOD => {
+                        if let Ok(params) = serde_json::from_value::<
+                            lsp_types::DidCloseTextDocumentParams,
+                        >(not.params)
+                        {
+                            if let Some(file_id) = vfs_file_id(&params.text_document.uri) {
+                                self.query_clients.close_document(client, file_id);
+                            }
+                        }
+                    }
+                    // Query clients don't get diagnostics.
+                    _ => tracing::debug!("ignoring {} of query client {client}", not.method),
+                }
+                return;
+            }
+            // The server doesn't send requests to query clients.
+            Some(lsp_server::Message::Response(_)) => return,
+            None => return self.disconnect_query_client(client),
+        };
+
+        let response = match QueryClients::classify_request(&req.method) {
+            QueryRequest::Initialize => {
+                let result = lsp_types::InitializeResult {
+                    capabilities: crate::server_capabilities(&self.config),
+                    server_info: Some(lsp_types::ServerInfo {
+                        name: String::from("rust-analyzer"),
+                        version: Some(crate::version().to_string()),
+                    }),
+                    offset_encoding: None,
+                };
+                lsp_server::Response::new_ok(req.id, result)
+            }
+            // Shutting down a query client only closes its connection.
+            QueryRequest::Shutdown => lsp_server::Response::new_ok(req.id, ()),
+            QueryRequest::Unsupported => lsp_server::Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::InvalidRequest as i32,
+                format!("`{}` is not supported for query clients", req.method),
+            ),
+            QueryRequest::Forward => {
+                let req = self.query_clients.register_request(client, req);
+                return self.on_new_request(request_received, req);
+            }
+        };
+        self.query_clients.send(client, response.into());
+    }
+
+    /// Forgets the query client `client`, the requests it didn't get a response for yet are
+    /// cancelled.
+    fn disconnect_query_client(&mut self, client: ClientId) {
+        for id in self.query_clients.disconnect(client) {
+            // Nobody is waiting for the response anymore.
+            let _ = self.req_queue.incoming.cancel(id);
+        }
+    }
+
     /// Handles a request.
     fn on_request(&mut self, req: Request) {
         let mut dispatcher = RequestDispatcher { req: Some(req), global_state: self };
@@ -665,6 +839,11 @@ impl GlobalState {
             .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)
             .on::<lsp_ext::ViewHir>(handlers::handle_view_hir)
             .on::<lsp_ext::ViewMir>(handlers::handle_view_mir)
+            .on_with_progress::<lsp_ext::TraceConstEval>(
+                "Evaluating",
+                handlers::handle_trace_const_eval,
+            )
+            .on::<lsp_ext::CaptureTrace>(handlers::handle_capture_trace)
             .on::<lsp_ext::ViewFileText>(handlers::handle_view_file_text)
             .on::<lsp_ext::ViewCrateGraph>(handlers::handle_view_crate_graph)
             .on::<lsp_ext::ViewItemTree>(handlers::handle_view_item_tree)
@@ -672,6 +851,8 @@ impl GlobalState {
             .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)
             .on::<lsp_ext::Runnables>(handlers::handle_runnables)
             .on::<lsp_ext::RelatedTests>(handlers::handle_related_tests)
+            .on::<lsp_ext::ImplCoverage>(handlers::handle_impl_coverage)
+            .on::<lsp_ext::LifetimeReach>(handlers::handle_lifetime_reach)
             .on::<lsp_ext::CodeActionRequest>(handlers::handle_code_action)
             .on::<lsp_ext::CodeActionResolveRequest>(handlers::handle_code_action_resolve)
             .on::<lsp_ext::HoverRequest>(handlers::handle_hover)
@@ -694,7 +875,7 @@ impl GlobalState {
             .on::<lsp_types::request::FoldingRangeRequest>(handlers::handle_folding_range)
             .on::<lsp_types::request::SignatureHelpRequest>(handlers::handle_signature_help)
             .on::<lsp_types::request::PrepareRenameRequest>(handlers::handle_prepare_rename)
-            .on::<lsp_types::request::Rename>(handlers::handle_rename)
+            .on_with_progress::<lsp_types::request::Rename>("Renaming", handlers::handle_rename)
             .on::<lsp_types::request::References>(handlers::handle_references)
             .on::<lsp_types::request::Formatting>(handlers::handle_formatting)
             .on::<lsp_types::request::RangeFormatting>(handlers::handle_range_formatting)
@@ -716,7 +897,12 @@ impl GlobalState {
                 handlers::handle_semantic_tokens_range,
             )
             .on::<lsp_types::request::WillRenameFiles>(handlers::handle_will_rename_files)
-            .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on_with_progress::<lsp_ext::Ssr>("Replacing", handlers::handle_ssr)
+            .on_with_progress::<lsp_ext::ChangeSignature>(
+                "Changing signature",
+                handlers::handle_change_signature,
+            )
+            .on_with_progress::<lsp_ext::SafeDelete>("Deleting", handlers::handle_safe_delete)
             .finish();
     }
 
@@ -810,6 +996,11 @@ impl GlobalState {
                     lsp_types::NumberOrString::Number(id) => id.into(),
                     lsp_types::NumberOrString::String(id) => id.into(),
                 };
+                if let Some((cancelled, _)) =
+                    this.request_progress.get(&dispatch::request_progress_token(&id))
+                {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
                 this.cancel(id);
                 Ok(())
             })?
@@ -822,6 +1013,9 @@ impl GlobalState {
                             }
                         }
                     }
+                    if let Some((cancelled, _)) = this.request_progress.get(s) {
+                        cancelled.store(true, Ordering::Relaxed);
+                    }
                 }
                 // Just ignore this. It is OK to continue sending progress
                 // notifications for this token, as the client can't know when
@@ -885,6 +1079,17 @@ impl GlobalState {
                 }
                 Ok(())
             })?
+            .on::<lsp_ext::OpenHistoricalDocument>(|this, params| {
+                let file_id = url_to_file_id(&this.vfs.read().0, &params.text_document.uri)?;
+                let (text, endings) = LineEndings::normalize(params.text);
+                let doc = HistoricalDocument { file_id, text: Arc::from(text), endings };
+                this.historical_documents.insert(params.uri, doc);
+                Ok(())
+            })?
+            .on::<lsp_ext::CloseHistoricalDocument>(|this, params| {
+                this.historical_documents.remove(&params.uri);
+                Ok(())
+            })?
             .on::<lsp_ext::ClearFlycheck>(|this, ()| {
                 this.diagnostics.clear_check_all();
                 Ok(())
@@ -0,0 +1,461 @@
+//! Query clients are secondary LSP clients sharing the analysis of the server with its main client,
+//! like a second editor opened on the same workspace, so that the workspace doesn't have to be
+//! loaded by two servers. See the `--query-socket` flag.
+//!
+//! Query clients connect to a Unix socket of the server, which only its owner can connect to, and
+//! can send the requests which don't change the state of the server. The documents they open are
+//! isolated from the main client and the other query clients: their requests are handled in an
+//! overlay of the database with the text of their documents, see [`Overlay`]. The overlays share
+//! the analysis of the rest of the workspace with each other, see
+//! [`ide::AnalysisHost::overlay_analysis`]. They don't get diagnostics and progress notifications,
+//! those only go to the main client.
+//!
+//! The requests of query clients are handled like the ones of the main client, with their ids
+//! prefixed with the id of their client so that they don't clash with the ids of the main client.
+
+use std::{
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+};
+
+use crossbeam_channel::{never, unbounded, Receiver, Sender};
+use ide::FileId;
+use lsp_server::{Message, RequestId, Response};
+use lsp_types::request::{Initialize, Request as _, Shutdown};
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    dispatch::request_progress_token,
+    line_index::{LineEndings, PositionEncoding},
+    lsp_ext,
+    lsp_utils::apply_document_changes,
+};
+
+pub(crate) type ClientId = u32;
+
+/// The connections of the query clients.
+pub struct QueryClients {
+    /// Messages of the query clients, `None` when a client disconnected.
+    pub(crate) receiver: Receiver<(ClientId, Option<Message>)>,
+    senders: Arc<Mutex<FxHashMap<ClientId, Sender<Message>>>>,
+    /// The client and the original id of the requests of query clients being handled, by their id
+    /// in the request queue of the server.
+    requests: FxHashMap<RequestId, (ClientId, RequestId)>,
+    /// The documents opened by the query clients.
+    overlays: FxHashMap<ClientId, Overlay>,
+    /// The socket the server listens on, removed when the server exits.
+    socket: Option<PathBuf>,
+}
+
+/// How a request of a query client is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QueryRequest {
+    /// Answered with the capabilities of the server.
+    Initialize,
+    /// Only closes the connection of the client.
+    Shutdown,
+    /// Changes the state shared with the main client.
+    Unsupported,
+    /// Handled like the requests of the main client.
+    Forward,
+}
+
+/// The documents opened by a query client, which may have changes the client didn't save.
+#[derive(Debug, Default)]
+pub(crate) struct Overlay {
+    /// The text of the documents, as sent by the client.
+    documents: FxHashMap<FileId, String>,
+    /// The text of the documents as analyzed, with normalized line endings, and their line endings.
+    texts: FxHashMap<FileId, (Arc<str>, LineEndings)>,
+}
+
+impl Overlay {
+    fn set_document(&mut self, file_id: FileId, text: String) {
+        let (normalized, endings) = LineEndings::normalize(text.clone());
+        self.texts.insert(file_id, (Arc::from(normalized), endings));
+        self.documents.insert(file_id, text);
+    }
+}
+
+impl QueryClients {
+    /// Listens on the Unix socket `path` for query clients.
+    #[cfg(unix)]
+    pub fn listen(path: impl AsRef<Path>) -> io::Result<QueryClients> {
+        use std::{
+            fs,
+            os::unix::{
+                fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+                net::{UnixListener, UnixStream},
+            },
+        };
+
+        let path = path.as_ref();
+        // The socket of a server which didn't exit cleanly is left behind, and nothing listens on
+        // it anymore.
+        if fs::symlink_metadata(path).map_or(false, |it| it.file_type().is_socket()) {
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("a server already listens on {}", path.display()),
+                ));
+            }
+            fs::remove_file(path)?;
+        }
+        // Query clients get the same access to the workspace as the server, so only its owner
+        // may connect. The socket is bound in a private directory, and only moved to `path` once
+        // its permissions are restricted, so that nobody can connect in the meantime.
+        let mut private_dir = path.as_os_str().to_owned();
+        private_dir.push(format!(".{}.tmp", std::process::id()));
+        let private_dir = PathBuf::from(private_dir);
+        fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+        let bound = private_dir.join("socket");
+        let listener = UnixListener::bind(&bound).and_then(|listener| {
+            fs::set_permissions(&bound, fs::Permissions::from_mode(0o600))?;
+            fs::rename(&bound, path)?;
+            Ok(listener)
+        });
+        let _ = fs::remove_file(&bound);
+        fs::remove_dir(&private_dir)?;
+        let listener = listener?;
+        tracing::info!("listening for query clients on {}", path.display());
+        let (inbox, receiver) = unbounded();
+        let senders = Arc::new(Mutex::new(FxHashMap::default()));
+        thread::Builder::new().name("QueryClientListener".to_owned()).spawn({
+            let senders = senders.clone();
+            move || {
+                for (client, stream) in (0..).zip(listener.incoming()) {
+                    let res = stream.and_then(|stream| {
+                        let reader = BufReader::new(stream.try_clone()?);
+                        spawn_client(client, reader, stream, inbox.clone(), &mut senders.lock())
+                    });
+                    if let Err(e) = res {
+                        tracing::warn!("failed to accept a query client: {e}");
+                    }
+                }
+            }
+        })?;
+        Ok(QueryClients {
+            receiver,
+            senders,
+            requests: FxHashMap::default(),
+            overlays: FxHashMap::default(),
+            socket: Some(path.to_owned()),
+        })
+    }
+
+    /// Query clients connect through Unix sockets, which don't exist on this platform.
+    #[cfg(not(unix))]
+    pub fn listen(_path: impl AsRef<Path>) -> io::Result<QueryClients> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "query clients require Unix sockets"))
+    }
+
+    /// Returns a value without query clients, for servers which don't listen for them.
+    pub(crate) fn none() -> QueryClients {
+        QueryClients {
+            receiver: never(),
+            senders: Default::default(),
+            requests: FxHashMap::default(),
+            overlays: FxHashMap::default(),
+            socket: None,
+        }
+    }
+
+    /// Returns how a request with `method` of a query client is handled.
+    pub(crate) fn classify_request(method: &str) -> QueryRequest {
+        match method {
+            Initialize::METHOD => QueryRequest::Initialize,
+            Shutdown::METHOD => QueryRequest::Shutdown,
+            lsp_ext::ReloadWorkspace::METHOD
+            | lsp_ext::RebuildProcMacros::METHOD
+            | lsp_ext::MemoryUsage::METHOD
+            | lsp_ext::ShuffleCrateGraph::METHOD => QueryRequest::Unsupported,
+            _ => QueryRequest::Forward,
+        }
+    }
+
+    /// Returns the id of the request `id` of `client` in the request queue of the server.
+    pub(crate) fn request_id(client: ClientId, id: &RequestId) -> RequestId {
+        format!("query-client/{client}/{id}").into()
+    }
+
+    /// Records a request of `client`, and returns it with the id it has in the request queue of
+    /// the server.
+    pub(crate) fn register_request(
+        &mut self,
+        client: ClientId,
+        mut request: lsp_server::Request,
+    ) -> lsp_server::Request {
+        let id = QueryClients::request_id(client, &request.id);
+        self.requests.insert(id.clone(), (client, request.id));
+        request.id = id;
+        request
+    }
+
+    /// Sends `response` to the query client which sent the request, and returns it back if the
+    /// request comes from the main client.
+    pub(crate) fn respond(&mut self, mut response: Response) -> Option<Response> {
+        let Some((client, id)) = self.requests.remove(&response.id) else {
+            return Some(response);
+        };
+        response.id = id;
+        self.send(client, response.into());
+        None
+    }
+
+    /// Returns whether the progress `token` is the one of a request of a query client.
+    pub(crate) fn is_request_progress(&self, token: &str) -> bool {
+        self.requests.keys().any(|id| request_progress_token(id) == token)
+    }
+
+    pub(crate) fn send(&self, client: ClientId, message: Message) {
+        // The client may have disconnected in the meantime.
+        if let Some(sender) = self.senders.lock().get(&client) {
+            let _ = sender.send(message);
+        }
+    }
+
+    /// Forgets `client`, and returns the ids its pending requests have in the request queue of the
+    /// server, so that they can be cancelled.
+    pub(crate) fn disconnect(&mut self, client: ClientId) -> Vec<RequestId> {
+        tracing::info!("query client {client} disconnected");
+        self.senders.lock().remove(&client);
+        self.overlays.remove(&client);
+        let pending: Vec<_> = self
+            .requests
+            .iter()
+            .filter(|(_, (it, _))| *it == client)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &pending {
+            self.requests.remove(id);
+        }
+        pending
+    }
+
+    /// Records that `client` opened a document of `file_id` with `text`.
+    pub(crate) fn open_document(&mut self, client: ClientId, file_id: FileId, text: String) {
+        self.overlays.entry(client).or_default().set_document(file_id, text);
+    }
+
+    /// Applies the changes `client` made to its document of `file_id`, if the client opened it.
+    pub(crate) fn change_document(
+        &mut self,
+        client: ClientId,
+        file_id: FileId,
+        encoding: PositionEncoding,
+        changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
+    ) {
+        let Some(overlay) = self.overlays.get_mut(&client) else { return };
+        let Some(text) = overlay.documents.remove(&file_id) else { return };
+        let text = apply_document_changes(encoding, || text, changes);
+        overlay.set_document(file_id, text);
+    }
+
+    /// Records that `client` closed its document of `file_id`.
+    pub(crate) fn close_document(&mut self, client: ClientId, file_id: FileId) {
+        if let Some(overlay) = self.overlays.get_mut(&client) {
+            overlay.documents.remove(&file_id);
+            overlay.texts.remove(&file_id);
+            if overlay.documents.is_empty() {
+                // The client sees the files of the main client again.
+                self.overlays.remove(&client);
+            }
+        }
+    }
+
+    /// Returns the texts of the documents the request `id` of the request queue of the server is
+    /// handled with, and their line endings, if it comes from a query client with documents.
+    pub(crate) fn overlay_for_request(
+        &self,
+        id: &RequestId,
+    ) -> Option<(Vec<(FileId, Arc<str>)>, FxHashMap<FileId, LineEndings>)> {
+        let (client, _) = self.requests.get(id)?;
+        let overlay = self.overlays.get(client)?;
+        let texts = overlay.texts.iter().map(|(id, (text, _))| (*id, text.clone())).collect();
+        let endings = overlay.texts.iter().map(|(id, (_, endings))| (*id, *endings)).collect();
+        Some((texts, endings))
+    }
+}
+
+impl Drop for QueryClients {
+    fn drop(&mut self) {
+        if let Some(socket) = &self.socket {
+            if let Err(e) = std::fs::remove_file(socket) {
+                tracing::warn!("failed to remove {}: {e}", socket.display());
+            }
+        }
+    }
+}
+
+/// Spawns the threads reading and writing the messages of a new client.
+fn spawn_client(
+    client: ClientId,
+    mut reader: impl io::BufRead + Send + 'static,
+    mut writer: impl io::Write + Send + 'static,
+    inbox: Sender<(ClientId, Option<Message>)>,
+    senders: &mut FxHashMap<ClientId, Sender<Message>>,
+) -> io::Result<()> {
+    tracing::info!("query client {client} connected");
+    let (sender, receiver) = unbounded::<Message>();
+    // Register the client before reading its messages, so that the responses to its first
+    // requests can't get lost.
+    senders.insert(client, sender);
+    thread::Builder::new().name(format!("QueryClientWriter{client}")).spawn(move || {
+        for message in receiver {
+            if message.write(&mut writer).is_err() {
+                break;
+            }
+        }
+    })?;
+    thread::Builder::new().name(format!("QueryClientReader{client}")).spawn(move || {
+        while let Ok(Some(message)) = Message::read(&mut reader) {
+            if inbox.send((client, Some(message))).is_err() {
+                return;
+            }
+        }
+        let _ = inbox.send((client, None));
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{notification::Notification as _, Position, Range};
+
+    use super::*;
+
+    fn request(id: i32, method: &str) -> lsp_server::Request {
+        lsp_server::Request::new(id.into(), method.to_owned(), serde_json::Value::Null)
+    }
+
+    fn connect(clients: &mut QueryClients, client: ClientId) -> Receiver<Message> {
+        let (sender, receiver) = unbounded();
+        clients.senders.lock().insert(client, sender);
+        receiver
+    }
+
+    #[test]
+    fn classifies_requests() {
+        assert_eq!(QueryClients::classify_request(Initialize::METHOD), QueryRequest::Initialize);
+        assert_eq!(QueryClients::classify_request(Shutdown::METHOD), QueryRequest::Shutdown);
+        assert_eq!(
+            QueryClients::classify_request(lsp_ext::ReloadWorkspace::METHOD),
+            QueryRequest::Unsupported
+        );
+        assert_eq!(
+            QueryClients::classify_request(lsp_types::request::HoverRequest::METHOD),
+            QueryRequest::Forward
+        );
+    }
+
+    #[test]
+    fn routes_responses_to_their_client() {
+        let mut clients = QueryClients::none();
+        let first = connect(&mut clients, 0);
+        let second = connect(&mut clients, 1);
+
+        let a = clients.register_request(0, request(1, "textDocument/hover"));
+        let b = clients.register_request(1, request(1, "textDocument/hover"));
+        assert_ne!(a.id, b.id);
+
+        assert!(clients.respond(Response::new_ok(b.id, 2)).is_none());
+        assert!(clients.respond(Response::new_ok(a.id, 1)).is_none());
+        let main = clients.respond(Response::new_ok(RequestId::from(1), 0));
+        assert_eq!(main.map(|it| it.id), Some(RequestId::from(1)));
+
+        let received = |receiver: &Receiver<Message>| match receiver.try_recv() {
+            Ok(Message::Response(response)) => (response.id, response.result),
+            message => panic!("unexpected message {message:?}"),
+        };
+        assert_eq!(received(&first), (RequestId::from(1), Some(1.into())));
+        assert_eq!(received(&second), (RequestId::from(1), Some(2.into())));
+    }
+
+    #[test]
+    fn disconnect_forgets_the_client() {
+        let mut clients = QueryClients::none();
+        let _receiver = connect(&mut clients, 0);
+        let _other = connect(&mut clients, 1);
+        let pending = clients.register_request(0, request(1, "textDocument/hover")).id;
+        let other = clients.register_request(1, request(1, "textDocument/hover")).id;
+        clients.open_document(0, FileId(0), "fn main() {}".to_owned());
+
+        assert_eq!(clients.disconnect(0), vec![pending.clone()]);
+        assert!(!clients.senders.lock().contains_key(&0));
+        assert!(!clients.overlays.contains_key(&0));
+        // A late response of the client's request doesn't go to the main client.
+        assert!(!clients.requests.contains_key(&pending));
+        assert!(clients.requests.contains_key(&other));
+    }
+
+    #[test]
+    fn overlays_are_per_client() {
+        let mut clients = QueryClients::none();
+        let file_id = FileId(0);
+        clients.open_document(0, file_id, "fn main() {}\r\n".to_owned());
+        let change = lsp_types::TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(0, 3), Position::new(0, 7))),
+            range_length: None,
+            text: "test".to_owned(),
+        };
+        clients.change_document(0, file_id, PositionEncoding::Utf8, vec![change.clone()]);
+        // Client 1 didn't open the document.
+        clients.change_document(1, file_id, PositionEncoding::Utf8, vec![change]);
+        assert!(!clients.overlays.contains_key(&1));
+
+        let ours = clients.register_request(0, request(1, "textDocument/hover")).id;
+        let theirs = clients.register_request(1, request(1, "textDocument/hover")).id;
+        let (texts, endings) = clients.overlay_for_request(&ours).unwrap();
+        assert_eq!(texts, vec![(file_id, Arc::from("fn test() {}\n"))]);
+        assert_eq!(endings[&file_id], LineEndings::Dos);
+        assert!(clients.overlay_for_request(&theirs).is_none());
+        assert!(clients.overlay_for_request(&RequestId::from(1)).is_none());
+
+        clients.close_document(0, file_id);
+        assert!(clients.overlay_for_request(&ours).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn listens_on_a_private_unix_socket() {
+        use std::{os::unix::fs::PermissionsExt, os::unix::net::UnixStream};
+
+        let path = std::env::temp_dir().join(format!("ra-query-{}.sock", std::process::id()));
+        let clients = QueryClients::listen(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        let exit = lsp_server::Notification::new(
+            lsp_types::notification::Exit::METHOD.to_owned(),
+            serde_json::Value::Null,
+        );
+        Message::from(exit).write(&mut stream).unwrap();
+        drop(stream);
+        match clients.receiver.recv().unwrap() {
+            (0, Some(Message::Notification(not))) => assert_eq!(not.method, "exit"),
+            message => panic!("unexpected message {message:?}"),
+        }
+        assert!(matches!(clients.receiver.recv().unwrap(), (0, None)));
+        drop(clients);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replaces_stale_unix_sockets() {
+        use std::os::unix::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("ra-query-stale-{}.sock", std::process::id()));
+        // Dropping the listener leaves its socket behind, like a server which crashed.
+        drop(UnixListener::bind(&path).unwrap());
+        let clients = QueryClients::listen(&path).unwrap();
+        let err = QueryClients::listen(&path).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+        drop(clients);
+        assert!(!path.exists());
+    }
+}
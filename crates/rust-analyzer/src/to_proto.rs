@@ -6,12 +6,12 @@ use std::{
 };
 
 use ide::{
-    Annotation, AnnotationKind, Assist, AssistKind, Cancellable, CompletionItem,
-    CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
-    Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayKind, Markup, NavigationTarget, ReferenceCategory,
-    RenameError, Runnable, Severity, SignatureHelp, SourceChange, StructureNodeKind, SymbolKind,
-    TextEdit, TextRange, TextSize,
+    Annotation, AnnotationKind, Assist, AssistKind, Cancellable, ChangeSignatureError,
+    CompletionItem, CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange,
+    FileSystemEdit, Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel,
+    InlayHint, InlayHintLabel, InlayHintLabelPart, InlayKind, Markup, NavigationTarget,
+    ReferenceCategory, RenameError, Runnable, SafeDeleteError, Severity, SignatureHelp,
+    SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
 };
 use itertools::Itertools;
 use serde_json::to_value;
@@ -436,6 +436,7 @@ pub(crate) fn inlay_hint(
     line_index: &LineIndex,
     render_colons: bool,
     mut inlay_hint: InlayHint,
+    resolve_data: Option<lsp_ext::InlayHintResolveData>,
 ) -> Cancellable<lsp_types::InlayHint> {
     match inlay_hint.kind {
         InlayKind::Parameter if render_colons => inlay_hint.label.append_str(":"),
@@ -511,7 +512,7 @@ pub(crate) fn inlay_hint(
             | InlayKind::ClosingBrace => None,
         },
         text_edits: inlay_hint.text_edit.map(|it| text_edit_vec(line_index, it)),
-        data: None,
+        data: resolve_data.map(|it| to_value(it).unwrap()),
         tooltip,
         label,
     })
@@ -1005,6 +1006,13 @@ pub(crate) fn snippet_workspace_edit(
         let edit = snippet_text_document_edit(snap, source_change.is_snippet, file_id, edit)?;
         document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Edit(edit));
     }
+    Ok(snippet_workspace_edit_from_changes(snap, document_changes))
+}
+
+pub(crate) fn snippet_workspace_edit_from_changes(
+    snap: &GlobalStateSnapshot,
+    document_changes: Vec<lsp_ext::SnippetDocumentChangeOperation>,
+) -> lsp_ext::SnippetWorkspaceEdit {
     let mut workspace_edit = lsp_ext::SnippetWorkspaceEdit {
         changes: None,
         document_changes: Some(document_changes),
@@ -1025,7 +1033,7 @@ pub(crate) fn snippet_workspace_edit(
             .collect(),
         )
     }
-    Ok(workspace_edit)
+    workspace_edit
 }
 
 pub(crate) fn workspace_edit(
@@ -1109,6 +1117,7 @@ pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
         AssistKind::RefactorExtract => lsp_types::CodeActionKind::REFACTOR_EXTRACT,
         AssistKind::RefactorInline => lsp_types::CodeActionKind::REFACTOR_INLINE,
         AssistKind::RefactorRewrite => lsp_types::CodeActionKind::REFACTOR_REWRITE,
+        AssistKind::Source => lsp_types::CodeActionKind::SOURCE,
     }
 }
 
@@ -1180,6 +1189,7 @@ pub(crate) fn code_lens(
     annotation: Annotation,
 ) -> Cancellable<()> {
     let client_commands_config = snap.config.client_commands();
+    let overrides = matches!(annotation.kind, AnnotationKind::HasOverrides { .. });
     match annotation.kind {
         AnnotationKind::Runnable(run) => {
             let line_index = snap.file_line_index(run.nav.file_id)?;
@@ -1216,7 +1226,8 @@ pub(crate) fn code_lens(
                 })
             }
         }
-        AnnotationKind::HasImpls { pos: file_range, data } => {
+        AnnotationKind::HasImpls { pos: file_range, data }
+        | AnnotationKind::HasOverrides { pos: file_range, data } => {
             if !client_commands_config.show_reference {
                 return Ok(());
             }
@@ -1246,12 +1257,12 @@ pub(crate) fn code_lens(
                     })
                     .collect();
 
-                command::show_references(
-                    implementation_title(locations.len()),
-                    &url,
-                    annotation_range.start,
-                    locations,
-                )
+                let title = if overrides {
+                    override_title(locations.len())
+                } else {
+                    implementation_title(locations.len())
+                };
+                command::show_references(title, &url, annotation_range.start, locations)
             });
 
             acc.push(lsp_types::CodeLens {
@@ -1262,7 +1273,11 @@ pub(crate) fn code_lens(
                     Some(
                         to_value(lsp_ext::CodeLensResolveData {
                             version,
-                            kind: lsp_ext::CodeLensResolveDataKind::Impls(goto_params),
+                            kind: if overrides {
+                                lsp_ext::CodeLensResolveDataKind::Overrides(goto_params)
+                            } else {
+                                lsp_ext::CodeLensResolveDataKind::Impls(goto_params)
+                            },
                         })
                         .unwrap(),
                     )
@@ -1396,6 +1411,14 @@ pub(crate) fn implementation_title(count: usize) -> String {
     }
 }
 
+pub(crate) fn override_title(count: usize) -> String {
+    if count == 1 {
+        "1 override".into()
+    } else {
+        format!("{count} overrides")
+    }
+}
+
 pub(crate) fn reference_title(count: usize) -> String {
     if count == 1 {
         "1 reference".into()
@@ -1416,6 +1439,14 @@ pub(crate) fn markup_content(
     lsp_types::MarkupContent { kind, value }
 }
 
+pub(crate) fn change_signature_error(err: ChangeSignatureError) -> crate::LspError {
+    invalid_params_error(err.to_string())
+}
+
+pub(crate) fn safe_delete_error(err: SafeDeleteError) -> crate::LspError {
+    invalid_params_error(err.to_string())
+}
+
 pub(crate) fn rename_error(err: RenameError) -> crate::LspError {
     // This is wrong, but we don't have a better alternative I suppose?
     // https://github.com/microsoft/language-server-protocol/issues/1341
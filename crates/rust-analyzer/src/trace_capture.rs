@@ -0,0 +1,150 @@
+//! Captures the spans and events of a single request into a trace, regardless of the `RA_LOG`
+//! filter, for the `rust-analyzer/captureTrace` request.
+//!
+//! Only the thread handling the request is captured. Work done on other threads, like consts
+//! evaluated by the const evaluation pool, doesn't show up in the trace.
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Write},
+    time::Instant,
+};
+
+use tracing::{
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+    span, Event, Subscriber,
+};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::{filter::filter_fn, layer::Context, registry::LookupSpan, Layer};
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Capture>> = RefCell::new(None);
+}
+
+#[derive(Default)]
+struct Capture {
+    out: String,
+    /// When the entered spans were entered, innermost last.
+    entered: Vec<Instant>,
+}
+
+impl Capture {
+    fn push_line(&mut self, line: &str) {
+        for _ in 0..self.entered.len() {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+}
+
+/// Runs `f` and returns its result, together with the spans entered and the events emitted on the
+/// current thread while it ran.
+pub(crate) fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            // Also stop capturing when `f` unwinds, for example because it was cancelled.
+            CAPTURE.with(|it| it.borrow_mut().take());
+        }
+    }
+
+    CAPTURE.with(|it| *it.borrow_mut() = Some(Capture::default()));
+    let _reset = Reset;
+    let res = f();
+    let trace = CAPTURE.with(|it| it.borrow_mut().take()).unwrap_or_default().out;
+    (res, trace)
+}
+
+/// Returns the layer writing the trace of the threads running [`capture`]. It sees the spans and
+/// events up to the `DEBUG` level of those threads, and nothing of the other threads.
+pub fn trace_capture_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    CaptureLayer.with_filter(filter_fn(|_| is_capturing()).with_max_level_hint(LevelFilter::DEBUG))
+}
+
+fn is_capturing() -> bool {
+    // The capture is borrowed while a line is written, which happens only while capturing.
+    CAPTURE.with(|it| it.try_borrow().map_or(true, |it| it.is_some()))
+}
+
+fn with_capture(f: impl FnOnce(&mut Capture)) {
+    CAPTURE.with(|it| {
+        if let Some(capture) = &mut *it.borrow_mut() {
+            f(capture);
+        }
+    });
+}
+
+struct CaptureLayer;
+
+/// The formatted fields of a span, recorded when the span is created.
+struct SpanFields(String);
+
+impl<S> Layer<S> for CaptureLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = FieldsVisitor::default();
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(SpanFields(fields.0));
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let line = match span.extensions().get::<SpanFields>() {
+            Some(SpanFields(fields)) if !fields.is_empty() => {
+                format!("{}{{{fields}}}", span.name())
+            }
+            _ => span.name().to_owned(),
+        };
+        with_capture(|capture| {
+            capture.push_line(&line);
+            capture.entered.push(Instant::now());
+        });
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        with_capture(|capture| {
+            // The span might have been entered before the capture started.
+            let Some(entered) = capture.entered.pop() else { return };
+            capture.push_line(&format!("{}: {:?}", span.name(), entered.elapsed()));
+        });
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        // Events of the `log` crate have `log` as their target, the actual one is a field.
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+        let mut fields = FieldsVisitor::default();
+        event.record(&mut fields);
+        // Format the line before borrowing the capture, formatting the fields can emit events too.
+        let line = format!("[{} {}] {}", metadata.level(), metadata.target(), fields.0);
+        with_capture(|capture| capture.push_line(&line));
+    }
+}
+
+#[derive(Default)]
+struct FieldsVisitor(String);
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let name = field.name();
+        if name.starts_with("log.") {
+            return;
+        }
+        if !self.0.is_empty() {
+            self.0.push_str(", ");
+        }
+        let _ = match name {
+            "message" => write!(self.0, "{value:?}"),
+            _ => write!(self.0, "{name}={value:?}"),
+        };
+    }
+}
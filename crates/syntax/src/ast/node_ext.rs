@@ -10,7 +10,9 @@ use parser::SyntaxKind;
 use rowan::{GreenNodeData, GreenTokenData};
 
 use crate::{
-    ast::{self, support, AstNode, AstToken, HasAttrs, HasGenericParams, HasName, SyntaxNode},
+    ast::{
+        self, support, AstNode, AstToken, HasAttrs, HasGenericParams, HasName, RangeOp, SyntaxNode,
+    },
     NodeOrToken, SmolStr, SyntaxElement, SyntaxToken, TokenText, T,
 };
 
@@ -819,6 +821,20 @@ impl ast::RangePat {
             .filter_map(|it| it.into_node())
             .find_map(ast::Pat::cast)
     }
+
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| matches!(it.kind(), T![..] | T![..=] | T![...]))
+    }
+
+    pub fn op_kind(&self) -> Option<RangeOp> {
+        match self.op_token()?.kind() {
+            T![..] => Some(RangeOp::Exclusive),
+            _ => Some(RangeOp::Inclusive),
+        }
+    }
 }
 
 impl ast::TokenTree {
@@ -1,8 +1,9 @@
 //! Precedence representation.
 
 use crate::{
+    algo::skip_trivia_token,
     ast::{self, BinaryOp, Expr, HasArgList},
-    match_ast, AstNode, SyntaxNode,
+    match_ast, AstNode, Direction, SyntaxKind, SyntaxNode, T,
 };
 
 impl Expr {
@@ -50,6 +51,10 @@ impl Expr {
             return false;
         }
 
+        if self.is_lexed_or_parsed_differently_in(parent) {
+            return true;
+        }
+
         if self.is_paren_like()
             || parent.is_paren_like()
             || self.is_prefix() && (parent.is_prefix() || !self.is_ordered_before(parent))
@@ -69,6 +74,47 @@ impl Expr {
         (left_right_bp < right_left_bp) ^ inv
     }
 
+    /// Returns `true` if `self` would be lexed or parsed differently without parentheses, whatever
+    /// its binding power is.
+    fn is_lexed_or_parsed_differently_in(&self, parent: &Expr) -> bool {
+        use Expr::*;
+
+        let is_lt = |kind: SyntaxKind| matches!(kind, T![<] | T![<<]);
+        match (self, parent) {
+            // `(s.f)()` calls the field `f`, but `s.f()` is a method call
+            (FieldExpr(_), CallExpr(_)) => self.is_ordered_before(parent),
+
+            // `x as u8 < y` is an error, as `<` is parsed as the start of generic arguments
+            (CastExpr(_), BinExpr(e)) if self.is_ordered_before(parent) => {
+                e.op_token().map_or(false, |op| is_lt(op.kind()))
+            }
+            (CastExpr(_), _)
+                if parent.is_prefix()
+                    || matches!(parent, BinExpr(_) | RangeExpr(_))
+                        && !self.is_ordered_before(parent) =>
+            {
+                // The cast is at the end of `parent`, so it's followed by whatever follows `parent`
+                let next = parent.syntax().last_token().and_then(|it| it.next_token());
+                next.and_then(|it| skip_trivia_token(it, Direction::Next))
+                    .map_or(false, |it| is_lt(it.kind()))
+            }
+
+            // `(1.).f()` would be a range, and `(1).0` a float literal
+            (Literal(lit), FieldExpr(_) | MethodCallExpr(_) | AwaitExpr(_)) => {
+                let text = lit.syntax().text().to_string();
+                let is_tuple_index = match parent {
+                    FieldExpr(e) => {
+                        e.name_ref().map_or(false, |it| it.text().starts_with(char::is_numeric))
+                    }
+                    _ => false,
+                };
+                text.ends_with('.')
+                    || is_tuple_index && text.chars().all(|c| c.is_ascii_digit() || c == '_')
+            }
+            _ => false,
+        }
+    }
+
     fn needs_parens_in_stmt(&self, stmt: Option<&ast::Stmt>) -> bool {
         use Expr::*;
 
@@ -116,7 +162,7 @@ impl Expr {
     }
 
     /// Returns left and right so-called "binding powers" of this expression.
-    fn binding_power(&self) -> (u8, u8) {
+    pub fn binding_power(&self) -> (u8, u8) {
         use ast::{ArithOp::*, BinaryOp::*, Expr::*, LogicOp::*};
 
         match self {
@@ -169,7 +215,7 @@ impl Expr {
 
             ArrayExpr(_) | TupleExpr(_) | Literal(_) | PathExpr(_) | ParenExpr(_) | IfExpr(_)
             | WhileExpr(_) | ForExpr(_) | LoopExpr(_) | MatchExpr(_) | BlockExpr(_)
-            | RecordExpr(_) | UnderscoreExpr(_) => (0, 0),
+            | RecordExpr(_) | UnderscoreExpr(_) | OffsetOfExpr(_) => (0, 0),
         }
     }
 
@@ -296,7 +342,8 @@ impl Expr {
 
                 ArrayExpr(_) | TupleExpr(_) | Literal(_) | PathExpr(_) | ParenExpr(_)
                 | IfExpr(_) | WhileExpr(_) | ForExpr(_) | LoopExpr(_) | MatchExpr(_)
-                | BlockExpr(_) | RecordExpr(_) | UnderscoreExpr(_) | MacroExpr(_) => None,
+                | BlockExpr(_) | RecordExpr(_) | UnderscoreExpr(_) | MacroExpr(_)
+                | OffsetOfExpr(_) => None,
             };
 
             token.map(|t| t.text_range()).unwrap_or_else(|| this.syntax().text_range()).start()
@@ -310,7 +357,7 @@ impl Expr {
             ArrayExpr(_) | AwaitExpr(_) | BlockExpr(_) | CallExpr(_) | CastExpr(_)
             | ClosureExpr(_) | FieldExpr(_) | IndexExpr(_) | Literal(_) | LoopExpr(_)
             | MacroExpr(_) | MethodCallExpr(_) | ParenExpr(_) | PathExpr(_) | RecordExpr(_)
-            | TryExpr(_) | TupleExpr(_) | UnderscoreExpr(_) => false,
+            | TryExpr(_) | TupleExpr(_) | UnderscoreExpr(_) | OffsetOfExpr(_) => false,
 
             // For BinExpr and RangeExpr this is technically wrong -- the child can be on the left...
             BinExpr(_) | RangeExpr(_) | BoxExpr(_) | BreakExpr(_) | ContinueExpr(_)
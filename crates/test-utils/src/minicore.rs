@@ -32,11 +32,14 @@
 //!     iterator: option
 //!     iterators: iterator, fn
 //!     non_zero:
+//!     offset_of:
 //!     option: panic
 //!     ord: eq, option
 //!     panic:
 //!     pin:
+//!     ptr:
 //!     range:
+//!     receiver: deref
 //!     result:
 //!     send: sized
 //!     sized:
@@ -192,11 +195,18 @@ pub mod convert {
     // endregion:infallible
 }
 
-// region:drop
 pub mod mem {
+    // region:drop
     pub fn drop<T>(_x: T) {}
+    // endregion:drop
+
+    // region:offset_of
+    #[rustc_builtin_macro]
+    pub macro offset_of($Container:ty, $($fields:tt).+ $(,)?) {
+        /* compiler built-in */
+    }
+    // endregion:offset_of
 }
-// endregion:drop
 
 pub mod ops {
     // region:coerce_unsized
@@ -248,10 +258,18 @@ pub mod ops {
             fn deref_mut(&mut self) -> &mut Self::Target;
         }
         // endregion:deref_mut
+
+        // region:receiver
+        #[lang = "receiver"]
+        pub trait Receiver {
+            type Target: ?Sized;
+        }
+        // endregion:receiver
     }
     pub use self::deref::{
         Deref,
         DerefMut, // :deref_mut
+        Receiver, // :receiver
     };
     // endregion:deref
 
@@ -644,9 +662,160 @@ pub mod fmt {
     pub trait Display {
         fn fmt(&self, f: &mut Formatter<'_>) -> Result;
     }
+
+    pub struct Arguments<'a> {
+        pieces: &'a [&'static str],
+        args: &'a [ArgumentV1<'a>],
+    }
+
+    impl<'a> Arguments<'a> {
+        pub const fn new_v1(
+            pieces: &'a [&'static str],
+            args: &'a [ArgumentV1<'a>],
+        ) -> Arguments<'a> {
+            Arguments { pieces, args }
+        }
+    }
+
+    pub struct ArgumentV1<'a> {
+        value: &'a (),
+    }
+
+    impl<'a> ArgumentV1<'a> {
+        pub fn new<T>(_value: &'a T, _f: fn(&T, &mut Formatter<'_>) -> Result) -> ArgumentV1<'a> {
+            ArgumentV1 { value: &() }
+        }
+    }
 }
 // endregion:fmt
 
+// region:ptr
+pub mod ptr {
+    extern "rust-intrinsic" {
+        fn transmute<T, U>(e: T) -> U;
+        fn arith_offset<T>(dst: *const T, offset: isize) -> *const T;
+        fn offset<T>(dst: *const T, offset: isize) -> *const T;
+        fn ptr_offset_from<T>(ptr: *const T, base: *const T) -> isize;
+    }
+
+    pub const fn invalid<T>(addr: usize) -> *const T {
+        unsafe { transmute(addr) }
+    }
+
+    pub const fn invalid_mut<T>(addr: usize) -> *mut T {
+        unsafe { transmute(addr) }
+    }
+
+    #[lang = "const_ptr"]
+    impl<T> *const T {
+        pub const fn addr(self) -> usize {
+            unsafe { transmute(self) }
+        }
+
+        pub const fn with_addr(self, addr: usize) -> Self {
+            let offset = addr as isize - self.addr() as isize;
+            unsafe { arith_offset(self as *const u8, offset) as *const T }
+        }
+
+        pub const fn cast<U>(self) -> *const U {
+            self as *const U
+        }
+
+        pub const unsafe fn offset(self, count: isize) -> Self {
+            unsafe { offset(self as *const T, count) }
+        }
+
+        pub const unsafe fn add(self, count: usize) -> Self {
+            unsafe { self.offset(count as isize) }
+        }
+
+        pub const unsafe fn sub(self, count: usize) -> Self {
+            unsafe { self.offset(-(count as isize)) }
+        }
+
+        pub const unsafe fn byte_add(self, count: usize) -> Self {
+            unsafe { self.cast::<u8>().add(count).cast::<T>() }
+        }
+
+        pub const fn wrapping_offset(self, count: isize) -> Self {
+            unsafe { arith_offset(self as *const T, count) }
+        }
+
+        pub const fn wrapping_add(self, count: usize) -> Self {
+            self.wrapping_offset(count as isize)
+        }
+
+        pub const fn wrapping_sub(self, count: usize) -> Self {
+            self.wrapping_offset(-(count as isize))
+        }
+
+        pub const unsafe fn offset_from(self, origin: *const T) -> isize {
+            unsafe { ptr_offset_from(self as *const T, origin) }
+        }
+
+        // region:fn
+        pub fn map_addr(self, f: impl FnOnce(usize) -> usize) -> Self {
+            self.with_addr(f(self.addr()))
+        }
+        // endregion:fn
+    }
+
+    #[lang = "mut_ptr"]
+    impl<T> *mut T {
+        pub const fn addr(self) -> usize {
+            unsafe { transmute(self) }
+        }
+
+        pub const fn with_addr(self, addr: usize) -> Self {
+            let offset = addr as isize - self.addr() as isize;
+            unsafe { arith_offset(self as *const u8, offset) as *mut T }
+        }
+
+        pub const fn cast<U>(self) -> *mut U {
+            self as *mut U
+        }
+
+        pub const unsafe fn offset(self, count: isize) -> Self {
+            unsafe { offset(self as *const T, count) as *mut T }
+        }
+
+        pub const unsafe fn add(self, count: usize) -> Self {
+            unsafe { self.offset(count as isize) }
+        }
+
+        pub const unsafe fn sub(self, count: usize) -> Self {
+            unsafe { self.offset(-(count as isize)) }
+        }
+
+        pub const unsafe fn byte_add(self, count: usize) -> Self {
+            unsafe { self.cast::<u8>().add(count).cast::<T>() }
+        }
+
+        pub const fn wrapping_offset(self, count: isize) -> Self {
+            unsafe { arith_offset(self as *const T, count) as *mut T }
+        }
+
+        pub const fn wrapping_add(self, count: usize) -> Self {
+            self.wrapping_offset(count as isize)
+        }
+
+        pub const fn wrapping_sub(self, count: usize) -> Self {
+            self.wrapping_offset(-(count as isize))
+        }
+
+        pub const unsafe fn offset_from(self, origin: *const T) -> isize {
+            unsafe { ptr_offset_from(self as *const T, origin) }
+        }
+
+        // region:fn
+        pub fn map_addr(self, f: impl FnOnce(usize) -> usize) -> Self {
+            self.with_addr(f(self.addr()))
+        }
+        // endregion:fn
+    }
+}
+// endregion:ptr
+
 // region:slice
 pub mod slice {
     #[lang = "slice"]
@@ -724,6 +893,17 @@ pub mod result {
         #[lang = "Err"]
         Err(E),
     }
+
+    // region:panic
+    impl<T, E> Result<T, E> {
+        pub fn unwrap(self) -> T {
+            match self {
+                Ok(val) => val,
+                Err(_) => panic!("called `Result::unwrap()` on an `Err` value"),
+            }
+        }
+    }
+    // endregion:panic
 }
 // endregion:result
 
@@ -935,11 +1115,28 @@ pub mod iter {
 // region:panic
 mod panic {
     pub macro panic_2021 {
+        // region:fmt
+        () => (
+            $crate::panicking::panic_fmt($crate::const_format_args!("explicit panic"))
+        ),
+        ($($t:tt)+) => (
+            $crate::panicking::panic_fmt($crate::const_format_args!($($t)+))
+        ),
+        // endregion:fmt
         ($($t:tt)+) => (
             /* Nothing yet */
         ),
     }
 }
+
+// region:fmt
+mod panicking {
+    #[lang = "panic_fmt"]
+    pub const fn panic_fmt(_fmt: crate::fmt::Arguments<'_>) -> ! {
+        loop {}
+    }
+}
+// endregion:fmt
 // endregion:panic
 
 mod macros {
@@ -953,6 +1150,15 @@ mod macros {
     }
 
     pub(crate) use panic;
+
+    #[macro_export]
+    #[rustc_builtin_macro]
+    macro_rules! const_format_args {
+        ($fmt:expr) => {{ /* compiler built-in */ }};
+        ($fmt:expr, $($args:tt)*) => {{ /* compiler built-in */ }};
+    }
+
+    pub(crate) use const_format_args;
     // endregion:panic
 
     // region:derive
@@ -971,6 +1177,27 @@ pub mod num {
     #[rustc_layout_scalar_valid_range_start(1)]
     #[rustc_nonnull_optimization_guaranteed]
     pub struct NonZeroU8(u8);
+
+    #[repr(transparent)]
+    #[rustc_layout_scalar_valid_range_start(1)]
+    #[rustc_nonnull_optimization_guaranteed]
+    pub struct NonZeroU32(u32);
+
+    impl NonZeroU8 {
+        // region:option
+        pub const fn new(n: u8) -> Option<Self> {
+            if n != 0 {
+                Some(NonZeroU8(n))
+            } else {
+                None
+            }
+        }
+        // endregion:option
+
+        pub const fn get(self) -> u8 {
+            self.0
+        }
+    }
 }
 // endregion:non_zero
 
@@ -419,9 +419,9 @@ export function syntaxTree(ctx: CtxInit): Cmd {
     };
 }
 
-function viewHirOrMir(ctx: CtxInit, xir: "hir" | "mir"): Cmd {
-    const viewXir = xir === "hir" ? "viewHir" : "viewMir";
-    const requestType = xir === "hir" ? ra.viewHir : ra.viewMir;
+function viewHirOrMir(ctx: CtxInit, xir: "hir" | "mir" | "trace"): Cmd {
+    const viewXir = { hir: "viewHir", mir: "viewMir", trace: "traceConstEval" }[xir];
+    const requestType = { hir: ra.viewHir, mir: ra.viewMir, trace: ra.traceConstEval }[xir];
     const tdcp = new (class implements vscode.TextDocumentContentProvider {
         readonly uri = vscode.Uri.parse(`rust-analyzer-${xir}://${viewXir}/${xir}.rs`);
         readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
@@ -501,6 +501,41 @@ export function viewMir(ctx: CtxInit): Cmd {
     return viewHirOrMir(ctx, "mir");
 }
 
+// Opens the virtual file that will show the statements executed when evaluating the const,
+// static or function containing the cursor position
+//
+// The contents of the file come from the `TextDocumentContentProvider`
+export function traceConstEval(ctx: CtxInit): Cmd {
+    return viewHirOrMir(ctx, "trace");
+}
+
+// Writes the trace of the hover at the cursor position or of the diagnostics of the current file
+// to a file picked by the user, to attach it to bug reports about slow requests
+export function captureTrace(ctx: CtxInit): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        if (!editor) return;
+
+        const request = await vscode.window.showQuickPick(["hover", "diagnostics"], {
+            placeHolder: "Request to capture the trace of",
+        });
+        if (request !== "hover" && request !== "diagnostics") return;
+        const output = await vscode.window.showSaveDialog({
+            defaultUri: vscode.Uri.file(`rust-analyzer-${request}-trace.txt`),
+        });
+        if (!output) return;
+
+        const client = ctx.client;
+        const path = await client.sendRequest(ra.captureTrace, {
+            request,
+            textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
+            position: client.code2ProtocolConverter.asPosition(editor.selection.active),
+            output: output.fsPath,
+        });
+        await vscode.window.showTextDocument(vscode.Uri.file(path));
+    };
+}
+
 export function viewFileText(ctx: CtxInit): Cmd {
     const tdcp = new (class implements vscode.TextDocumentContentProvider {
         readonly uri = vscode.Uri.parse("rust-analyzer-file-text://viewFileText/file.rs");
@@ -64,9 +64,15 @@ export const viewHir = new lc.RequestType<lc.TextDocumentPositionParams, string,
 export const viewMir = new lc.RequestType<lc.TextDocumentPositionParams, string, void>(
     "rust-analyzer/viewMir"
 );
+export const traceConstEval = new lc.RequestType<lc.TextDocumentPositionParams, string, void>(
+    "rust-analyzer/traceConstEval"
+);
 export const viewItemTree = new lc.RequestType<ViewItemTreeParams, string, void>(
     "rust-analyzer/viewItemTree"
 );
+export const captureTrace = new lc.RequestType<CaptureTraceParams, string, void>(
+    "rust-analyzer/captureTrace"
+);
 
 export type AnalyzerStatusParams = { textDocument?: lc.TextDocumentIdentifier };
 
@@ -85,9 +91,18 @@ export type SyntaxTreeParams = {
 };
 export type ViewCrateGraphParams = { full: boolean };
 export type ViewItemTreeParams = { textDocument: lc.TextDocumentIdentifier };
+export type CaptureTraceParams = {
+    request: "hover" | "diagnostics";
+    textDocument: lc.TextDocumentIdentifier;
+    position: lc.Position;
+    output: string;
+};
 
 // experimental extensions
 
+export const changeSignature = new lc.RequestType<ChangeSignatureParams, lc.WorkspaceEdit, void>(
+    "experimental/changeSignature"
+);
 export const joinLines = new lc.RequestType<JoinLinesParams, lc.TextEdit[], void>(
     "experimental/joinLines"
 );
@@ -117,8 +132,29 @@ export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>(
 export const serverStatus = new lc.NotificationType<ServerStatusParams>(
     "experimental/serverStatus"
 );
+export const safeDelete = new lc.RequestType<
+    lc.TextDocumentPositionParams,
+    SafeDeleteResult,
+    void
+>("experimental/safeDelete");
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>("experimental/ssr");
 
+export type ChangeSignatureParams = {
+    textDocument: lc.TextDocumentIdentifier;
+    position: lc.Position;
+    params: SignatureParam[];
+};
+export type SignatureParam =
+    | { kind: "existing"; index: number }
+    | { kind: "new"; pattern: string; type: string; value: string };
+export type SafeDeleteResult = {
+    edit: lc.WorkspaceEdit | null;
+    blockers: SafeDeleteBlocker[];
+};
+export type SafeDeleteBlocker = {
+    location: lc.Location;
+    container: string | null;
+};
 export type JoinLinesParams = {
     textDocument: lc.TextDocumentIdentifier;
     ranges: lc.Range[];